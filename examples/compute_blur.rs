@@ -0,0 +1,310 @@
+//! Separable box blur of the storage image without window, surface and swapchain
+//!
+//! Horizontal pass reads `source` image and writes `target`,
+//! vertical pass reads `target` and writes result back into `source`
+use libvktypes::*;
+
+const BLUR_SHADER: &str = "
+#version 460
+
+layout(local_size_x = 8, local_size_y = 8) in;
+
+layout(set = 0, binding = 0, r32f) uniform image2D source;
+layout(set = 0, binding = 1, r32f) uniform image2D target;
+
+layout(push_constant) uniform Blur {
+    int radius;
+    int vertical;
+} blur;
+
+float load(ivec2 pos) {
+    return blur.vertical == 0 ? imageLoad(source, pos).r : imageLoad(target, pos).r;
+}
+
+void main() {
+    ivec2 pos = ivec2(gl_GlobalInvocationID.xy);
+    ivec2 size = imageSize(source);
+
+    if (pos.x >= size.x || pos.y >= size.y) {
+        return;
+    }
+
+    ivec2 dir = blur.vertical == 0 ? ivec2(1, 0) : ivec2(0, 1);
+
+    float sum = 0.0;
+
+    for (int i = -blur.radius; i <= blur.radius; ++i) {
+        sum += load(clamp(pos + i*dir, ivec2(0), size - 1));
+    }
+
+    vec4 value = vec4(sum/float(2*blur.radius + 1), 0.0, 0.0, 0.0);
+
+    if (blur.vertical == 0) {
+        imageStore(target, pos, value);
+    } else {
+        imageStore(source, pos, value);
+    }
+}
+";
+
+const SIZE: u32 = 8;
+
+const RADIUS: i32 = 1;
+
+fn main() {
+    let lib_type = libvk::InstanceType {
+        debug_layer: Some(layers::DebugLayer::default()),
+        extensions: &[extensions::DEBUG_EXT_NAME],
+        ..libvk::InstanceType::default()
+    };
+
+    let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+    let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+    let (hw_dev, queue, _) = hw_list
+        .find_first(
+            hw::HWDevice::is_dedicated_gpu,
+            hw::QueueFamilyDescription::is_compute,
+            |_| true
+        )
+        .expect("Failed to find suitable hardware device");
+
+    let dev_type = dev::DeviceCfg {
+        lib: &lib,
+        hw: hw_dev,
+        extensions: &[],
+        queues: &[],
+        features: hw::Features::default(),
+        dynamic_rendering: false,
+        allocator: None,
+        track_objects: false,
+    };
+
+    let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+    let format = memory::ImageFormat::R32_SFLOAT;
+
+    let extent = memory::Extent3D { width: SIZE, height: SIZE, depth: 1 };
+
+    let image_cfg = [
+        memory::ImageCfg {
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            format,
+            extent,
+            usage: memory::ImageUsageFlags::STORAGE
+                | memory::ImageUsageFlags::TRANSFER_SRC
+                | memory::ImageUsageFlags::TRANSFER_DST,
+            layout: memory::ImageLayout::UNDEFINED,
+            aspect: memory::ImageAspect::COLOR,
+            tiling: memory::Tiling::OPTIMAL,
+            samples: memory::SampleCount::TYPE_1,
+            mip_levels: 1,
+            kind: memory::ImageKind::D2,
+            array_layers: 1,
+            count: 2
+        }
+    ];
+
+    let alloc_info = memory::ImagesAllocationInfo {
+        properties: hw::MemoryProperty::DEVICE_LOCAL,
+        filter: &hw::any,
+        image_cfgs: &image_cfg
+    };
+
+    let images = memory::ImageMemory::allocate(&device, &alloc_info).expect("Failed to allocate images");
+
+    let source = images.view(0);
+    let target = images.view(1);
+
+    let staging_cfg = memory::BufferCfg {
+        size: formats::required_staging_size(format, extent),
+        usage: memory::BufferUsageFlags::TRANSFER_SRC | memory::BufferUsageFlags::TRANSFER_DST,
+        queue_families: &[queue.index()],
+        simultaneous_access: false,
+        count: 2
+    };
+
+    let mem_cfg = memory::MemoryCfg {
+        properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+        filter: &hw::any,
+        buffers: &[&staging_cfg]
+    };
+
+    let buffers = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+    // Single bright texel in the center
+    buffers.access(&mut |texels: &mut [f32]| {
+        texels.fill(0.0);
+        texels[(SIZE*SIZE/2 + SIZE/2) as usize] = 9.0;
+    }, 0).expect("Failed to fill staging buffer");
+
+    let descs = graphics::PipelineDescriptor::allocate(&device, &[&[
+        graphics::BindingCfg {
+            resource_type: graphics::DescriptorType::STORAGE_IMAGE,
+            stage: graphics::ShaderStage::COMPUTE,
+            count: 1,
+        },
+        graphics::BindingCfg {
+            resource_type: graphics::DescriptorType::STORAGE_IMAGE,
+            stage: graphics::ShaderStage::COMPUTE,
+            count: 1,
+        }
+    ]]).expect("Failed to allocate resources");
+
+    descs.update(&[
+        graphics::UpdateInfo {
+            set: 0,
+            binding: 0,
+            starting_array_element: 0,
+            resources: graphics::ShaderBinding::Images(&[graphics::ImageBinding::storage(source)]),
+        },
+        graphics::UpdateInfo {
+            set: 0,
+            binding: 1,
+            starting_array_element: 0,
+            resources: graphics::ShaderBinding::Images(&[graphics::ImageBinding::storage(target)]),
+        }
+    ]).expect("Failed to update descriptor");
+
+    let shader_type = shader::ShaderCfg {
+        path: "blur.comp",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let shader = shader::Shader::from_glsl(&device, &shader_type, BLUR_SHADER, shader::Kind::Compute)
+        .expect("Failed to create compute shader module");
+
+    let pipe_type = compute::PipelineCfg {
+        buffers: &[],
+        descriptor: Some(&descs),
+        shader: &shader,
+        specialization: None,
+        push_constant_size: 2*std::mem::size_of::<i32>() as u32,
+        cache: None,
+    };
+
+    let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+    let cmd_pool_type = cmd::PoolCfg {
+        queue_index: queue.index(),
+    };
+
+    let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+    let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+    cmd_buffer.set_image_barrier(
+        source,
+        cmd::AccessType::empty(),
+        cmd::AccessType::TRANSFER_WRITE,
+        memory::ImageLayout::UNDEFINED,
+        memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+        cmd::PipelineStage::TOP_OF_PIPE,
+        cmd::PipelineStage::TRANSFER,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED);
+
+    cmd_buffer.copy_buffer_to_image(buffers.view(0), source);
+
+    cmd_buffer.set_image_barrier(
+        source,
+        cmd::AccessType::TRANSFER_WRITE,
+        cmd::AccessType::SHADER_READ,
+        memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+        memory::ImageLayout::GENERAL,
+        cmd::PipelineStage::TRANSFER,
+        cmd::PipelineStage::COMPUTE_SHADER,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED);
+
+    cmd_buffer.set_image_barrier(
+        target,
+        cmd::AccessType::empty(),
+        cmd::AccessType::SHADER_WRITE,
+        memory::ImageLayout::UNDEFINED,
+        memory::ImageLayout::GENERAL,
+        cmd::PipelineStage::TOP_OF_PIPE,
+        cmd::PipelineStage::COMPUTE_SHADER,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED);
+
+    cmd_buffer.bind_compute_pipeline(&pipeline);
+
+    cmd_buffer.bind_compute_resources(&pipeline, &descs, &[]);
+
+    // Horizontal pass
+    cmd_buffer.push(&pipeline, graphics::ShaderStage::COMPUTE, 0, &[RADIUS, 0]).expect("Failed to push constants");
+
+    cmd_buffer.dispatch_for([SIZE, SIZE, 1], [8, 8, 1]).expect("Failed to dispatch");
+
+    // Vertical pass reads result of the horizontal one and overwrites source
+    cmd_buffer.set_image_barrier(
+        target,
+        cmd::AccessType::SHADER_WRITE,
+        cmd::AccessType::SHADER_READ,
+        memory::ImageLayout::GENERAL,
+        memory::ImageLayout::GENERAL,
+        cmd::PipelineStage::COMPUTE_SHADER,
+        cmd::PipelineStage::COMPUTE_SHADER,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED);
+
+    cmd_buffer.set_image_barrier(
+        source,
+        cmd::AccessType::SHADER_READ,
+        cmd::AccessType::SHADER_WRITE,
+        memory::ImageLayout::GENERAL,
+        memory::ImageLayout::GENERAL,
+        cmd::PipelineStage::COMPUTE_SHADER,
+        cmd::PipelineStage::COMPUTE_SHADER,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED);
+
+    cmd_buffer.push(&pipeline, graphics::ShaderStage::COMPUTE, 0, &[RADIUS, 1]).expect("Failed to push constants");
+
+    cmd_buffer.dispatch_for([SIZE, SIZE, 1], [8, 8, 1]).expect("Failed to dispatch");
+
+    cmd_buffer.set_image_barrier(
+        source,
+        cmd::AccessType::SHADER_WRITE,
+        cmd::AccessType::TRANSFER_READ,
+        memory::ImageLayout::GENERAL,
+        memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+        cmd::PipelineStage::COMPUTE_SHADER,
+        cmd::PipelineStage::TRANSFER,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED);
+
+    cmd_buffer.copy_image_to_buffer(source, buffers.view(1));
+
+    let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+    let queue_cfg = queue::QueueCfg {
+        family_index: queue.index(),
+        queue_index: 0
+    };
+
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+    let exec_info = queue::ExecInfo {
+        buffers: &[&exec_buffer],
+        wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+        timeout: u64::MAX,
+        wait: &[],
+        signal: &[],
+        fence: None,
+    };
+
+    cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+    let blurred: Vec<f32> = buffers.read_as(1).expect("Failed to read result");
+
+    for row in blurred.chunks_exact(SIZE as usize) {
+        let row: Vec<String> = row.iter().map(|x| format!("{:.2}", x)).collect();
+
+        println!("{}", row.join(" "));
+    }
+}
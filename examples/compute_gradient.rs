@@ -0,0 +1,224 @@
+use libvktypes::*;
+
+const GRADIENT_SHADER: &str = "
+#version 460
+
+layout(local_size_x = 16, local_size_y = 16) in;
+
+layout(set = 0, binding = 0) uniform writeonly image2D target;
+
+void main() {
+    ivec2 pos = ivec2(gl_GlobalInvocationID.xy);
+    ivec2 size = imageSize(target);
+
+    if (pos.x >= size.x || pos.y >= size.y) {
+        return;
+    }
+
+    vec2 uv = vec2(pos)/vec2(size);
+
+    imageStore(target, pos, vec4(uv.x, uv.y, 1.0 - uv.x, 1.0));
+}
+";
+
+fn main() {
+    let event_loop = window::eventloop().expect("Failed to create eventloop");
+
+    let wnd = window::create_window(&event_loop).expect("Failed to create window");
+
+    let mut extensions = extensions::required_extensions(&wnd);
+    extensions.push(extensions::DEBUG_EXT_NAME);
+    extensions.push(extensions::SURFACE_EXT_NAME);
+
+    let lib_type = libvk::InstanceType {
+        debug_layer: Some(layers::DebugLayer::default()),
+        extensions: &extensions,
+        ..libvk::InstanceType::default()
+    };
+
+    let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+    let surface = surface::Surface::new(&lib, &wnd).expect("Failed to create surface");
+
+    let hw_list = hw::Description::poll(&lib, Some(&surface)).expect("Failed to list hardware");
+
+    let (hw_dev, queue, _) = hw_list
+        .find_first(
+            |hw| hw.is_dedicated_gpu() && hw.features().shader_storage_image_write_without_format != 0,
+            |q| q.is_compute() && q.is_surface_supported(),
+            |_| true
+        )
+        .expect("Failed to find suitable hardware device");
+
+    let dev_type = dev::DeviceCfg {
+        lib: &lib,
+        hw: hw_dev,
+        extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features {
+            shader_storage_image_write_without_format: 1,
+            ..hw::Features::default()
+        },
+        dynamic_rendering: false,
+        allocator: None,
+        track_objects: false,
+    };
+
+    let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+    let capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
+
+    assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
+    assert!(
+        capabilities.supported_usage_flags().contains(memory::UsageFlags::STORAGE),
+        "Surface does not support storage swapchain images"
+    );
+
+    // sRGB formats usually can not be used as storage images
+    let surf_format = capabilities
+        .formats()
+        .find(|f| f.format == memory::ImageFormat::B8G8R8A8_UNORM || f.format == memory::ImageFormat::R8G8B8A8_UNORM)
+        .expect("No available UNORM formats")
+        .format;
+
+    let swp_type = swapchain::SwapchainCfg {
+        num_of_images: capabilities.min_img_count(),
+        format: surf_format,
+        color: memory::ColorSpace::SRGB_NONLINEAR,
+        present_mode: swapchain::PresentMode::FIFO,
+        flags: memory::UsageFlags::STORAGE,
+        extent: capabilities.extent2d(),
+        transform: capabilities.pre_transformation(),
+        alpha: capabilities.first_alpha_composition().expect("No alpha composition")
+    };
+
+    let swapchain = swapchain::Swapchain::new(&lib, &device, &surface, &swp_type).expect("Failed to create swapchain");
+
+    let shader_type = shader::ShaderCfg {
+        path: "gradient.comp",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let shader = shader::Shader::from_glsl(&device, &shader_type, GRADIENT_SHADER, shader::Kind::Compute)
+        .expect("Failed to create compute shader module");
+
+    let descs = graphics::PipelineDescriptor::allocate(&device, &[&[
+        graphics::BindingCfg {
+            resource_type: graphics::DescriptorType::STORAGE_IMAGE,
+            stage: graphics::ShaderStage::COMPUTE,
+            count: 1,
+        }
+    ]]).expect("Failed to allocate resources");
+
+    let pipe_type = compute::PipelineCfg {
+        buffers: &[],
+        descriptor: Some(&descs),
+        shader: &shader,
+        specialization: None,
+        push_constant_size: 0,
+        cache: None,
+    };
+
+    let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+    let img_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+    let render_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+
+    let cmd_pool_type = cmd::PoolCfg {
+        queue_index: queue.index(),
+    };
+
+    let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+    let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command pool");
+
+    let images = swapchain.images();
+
+    let img_index = swapchain
+        .next_image(std::time::Duration::MAX, swapchain::AcquireSync::Semaphore(&img_sem))
+        .expect("Failed to get image index")
+        .index;
+
+    let target = images[img_index as usize].view(0);
+
+    assert!(target.usage().contains(memory::UsageFlags::STORAGE));
+
+    descs.update(&[graphics::UpdateInfo {
+        set: 0,
+        binding: 0,
+        starting_array_element: 0,
+        resources: graphics::ShaderBinding::Images(&[graphics::ImageBinding::storage(target)]),
+    }]).expect("Failed to update descriptor");
+
+    cmd_buffer.set_image_barrier(
+        target,
+        cmd::AccessType::empty(),
+        cmd::AccessType::SHADER_WRITE,
+        memory::ImageLayout::UNDEFINED,
+        memory::ImageLayout::GENERAL,
+        cmd::PipelineStage::TOP_OF_PIPE,
+        cmd::PipelineStage::COMPUTE_SHADER,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED);
+
+    cmd_buffer.bind_compute_pipeline(&pipeline);
+
+    cmd_buffer.bind_compute_resources(&pipeline, &descs, &[]);
+
+    let extent = capabilities.extent2d();
+
+    cmd_buffer.dispatch(extent.width.div_ceil(16), extent.height.div_ceil(16), 1);
+
+    cmd_buffer.set_image_barrier(
+        target,
+        cmd::AccessType::SHADER_WRITE,
+        cmd::AccessType::empty(),
+        memory::ImageLayout::GENERAL,
+        memory::ImageLayout::PRESENT_SRC_KHR,
+        cmd::PipelineStage::COMPUTE_SHADER,
+        cmd::PipelineStage::BOTTOM_OF_PIPE,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED);
+
+    let exec_buffer = cmd_buffer.commit().expect("Failed to commit buffer");
+
+    let queue_cfg = queue::QueueCfg {
+        family_index: queue.index(),
+        queue_index: 0
+    };
+
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+    let exec_info = queue::ExecInfo {
+        buffers: &[&exec_buffer],
+        wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+        timeout: u64::MAX,
+        wait: &[&img_sem],
+        signal: &[&render_sem],
+        fence: None,
+    };
+
+    cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+    let present_info = queue::PresentInfo {
+        swapchain: &swapchain,
+        image_index: img_index,
+        wait: &[&render_sem]
+    };
+
+    cmd_queue.present(&present_info).expect("Failed to present frame");
+
+    event_loop.run(move |event, control_flow| {
+        match event {
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => {
+                control_flow.exit();
+            },
+            _ => ()
+        }
+
+    }).expect("Failed to run example");
+}
@@ -0,0 +1,265 @@
+use libvktypes::*;
+
+// Quad is generated on GPU as 4 vertices of triangle strip
+const COMP_SHADER: &str = "
+#version 460
+
+layout(local_size_x = 4) in;
+
+layout(set = 0, binding = 0) buffer Vertices {
+    vec4 positions[];
+};
+
+void main() {
+    uint i = gl_GlobalInvocationID.x;
+
+    positions[i] = vec4((i & 2) == 0 ? 0.5 : -0.5, (i & 1) == 0 ? 0.5 : -0.5, 0.0, 1.0);
+}
+";
+
+const VERT_SHADER: &str = "
+#version 460
+
+layout(location = 0) in vec4 position;
+
+void main() {
+    gl_Position = position;
+}
+";
+
+const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(0.0, 1.0, 0.0, 1.0);
+}
+";
+
+const VERTEX_COUNT: u32 = 4;
+
+fn main() {
+    let event_loop = window::eventloop().expect("Failed to create eventloop");
+
+    let wnd = window::create_window(&event_loop).expect("Failed to create window");
+
+    let mut extensions = extensions::required_extensions(&wnd);
+    extensions.push(extensions::DEBUG_EXT_NAME);
+    extensions.push(extensions::SURFACE_EXT_NAME);
+
+    let lib_type = libvk::InstanceType {
+        debug_layer: Some(layers::DebugLayer::default()),
+        extensions: &extensions,
+        ..libvk::InstanceType::default()
+    };
+
+    let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+    let surface = surface::Surface::new(&lib, &wnd).expect("Failed to create surface");
+
+    let hw_list = hw::Description::poll(&lib, Some(&surface)).expect("Failed to list hardware");
+
+    let (hw_dev, queue, _) = hw_list
+        .find_first(
+            hw::HWDevice::is_discrete_gpu,
+            |q| q.is_graphics() && q.is_compute() && q.is_surface_supported(),
+            |_| true
+        )
+        .expect("Failed to find suitable hardware device");
+
+    let dev_type = dev::DeviceCfg {
+        lib: &lib,
+        hw: hw_dev,
+        extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features::default(),
+        dynamic_rendering: false,
+        allocator: None,
+        track_objects: false,
+    };
+
+    let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+    let capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
+
+    assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
+    assert!(capabilities.is_flags_supported(memory::UsageFlags::COLOR_ATTACHMENT));
+
+    let surface_format = capabilities.choose_format(&[
+        (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+        (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+    ]);
+
+    let surf_format = surface_format.format;
+
+    let swp_type = swapchain::SwapchainCfg {
+        num_of_images: capabilities.min_img_count(),
+        format: surf_format,
+        color: surface_format.color_space,
+        present_mode: swapchain::PresentMode::FIFO,
+        flags: memory::UsageFlags::COLOR_ATTACHMENT,
+        extent: capabilities.extent2d(),
+        transform: capabilities.pre_transformation(),
+        alpha: capabilities.first_alpha_composition().expect("No alpha composition")
+    };
+
+    let swapchain = swapchain::Swapchain::new(&lib, &device, &surface, &swp_type).expect("Failed to create swapchain");
+
+    let shader_type = shader::ShaderCfg {
+        path: "COMPUTE_VERTICES",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let comp_shader = shader::Shader::from_glsl(&device, &shader_type, COMP_SHADER, shader::Kind::Compute)
+        .expect("Failed to create compute shader module");
+
+    let vert_shader = shader::Shader::from_glsl(&device, &shader_type, VERT_SHADER, shader::Kind::Vertex)
+        .expect("Failed to create vertex shader module");
+
+    let frag_shader = shader::Shader::from_glsl(&device, &shader_type, FRAG_SHADER, shader::Kind::Fragment)
+        .expect("Failed to create fragment shader module");
+
+    // Buffer is never touched by host so it may live in device local memory
+    let mem_cfg = memory::MemoryCfg {
+        properties: hw::MemoryProperty::DEVICE_LOCAL,
+        filter: &hw::any,
+        buffers: &[
+            &memory::BufferCfg {
+                size: (VERTEX_COUNT as usize*std::mem::size_of::<[f32; 4]>()) as u64,
+                usage: memory::STORAGE_VERTEX,
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                count: 1
+            }
+        ]
+    };
+
+    let vertex_data = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+    let compute_cfg = compute::PipelineCfg {
+        buffers: &[vertex_data.view(0)],
+        descriptor: None,
+        shader: &comp_shader,
+        specialization: None,
+        push_constant_size: 0,
+        cache: None,
+    };
+
+    let compute_pipeline = compute::Pipeline::new(&device, &compute_cfg).expect("Failed to create compute pipeline");
+
+    let render_pass = graphics::RenderPass::single_subpass(&device, surf_format)
+        .expect("Failed to create render pass");
+
+    let descriptor = graphics::PipelineDescriptor::empty(&device);
+
+    let pipe_type = graphics::PipelineCfg {
+        vertex_size: std::mem::size_of::<[f32; 4]>() as u32,
+        vert_input: &[graphics::VertexInputCfg {
+            location: 0,
+            binding: 0,
+            format: memory::ImageFormat::R32G32B32A32_SFLOAT,
+            offset: 0,
+        }],
+        topology: graphics::Topology::TRIANGLE_STRIP,
+        extent: capabilities.extent2d(),
+        render_pass: Some(&render_pass),
+        cull_mode: graphics::CullMode::BACK,
+        ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+    };
+
+    let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+    let img_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+    let render_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+
+    let cmd_pool_type = cmd::PoolCfg {
+        queue_index: queue.index(),
+    };
+
+    let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+    let mut cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command pool");
+
+    let images = swapchain.images();
+
+    let img_index = swapchain
+        .next_image(std::time::Duration::MAX, swapchain::AcquireSync::Semaphore(&img_sem))
+        .expect("Failed to get image index")
+        .index;
+
+    let frames_cfg = memory::FramebufferCfg {
+        render_pass: &render_pass,
+        images: &[images[img_index as usize].view(0)],
+        extent: capabilities.extent2d(),
+    };
+
+    let frame = memory::Framebuffer::new(&device, &frames_cfg).expect("Failed to create framebuffers");
+
+    cmd_buffer.bind_compute_pipeline(&compute_pipeline);
+
+    cmd_buffer.dispatch(1, 1, 1);
+
+    // Vertices written by compute shader must be visible to vertex input stage
+    cmd_buffer.set_barrier(
+        &vertex_data.view(0),
+        cmd::AccessType::SHADER_WRITE,
+        cmd::AccessType::VERTEX_ATTRIBUTE_READ,
+        cmd::PipelineStage::COMPUTE_SHADER,
+        cmd::PipelineStage::VERTEX_INPUT,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED
+    );
+
+    cmd_buffer.begin_render_pass(&render_pass, &frame);
+
+    cmd_buffer.bind_graphics_pipeline(&pipeline);
+
+    cmd_buffer.bind_vertex_buffers(&[vertex_data.vertex_view(0, 0).expect("Failed to create vertex view")]);
+
+    cmd_buffer.draw(VERTEX_COUNT, 1, 0, 0);
+
+    cmd_buffer.end_render_pass();
+
+    let exec_buffer = cmd_buffer.commit().expect("Failed to commit buffer");
+
+    let queue_cfg = queue::QueueCfg {
+        family_index: queue.index(),
+        queue_index: 0
+    };
+
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+    let exec_info = queue::ExecInfo {
+        buffers: &[&exec_buffer],
+        wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        timeout: u64::MAX,
+        wait: &[&img_sem],
+        signal: &[&render_sem],
+        fence: None,
+    };
+
+    cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+    let present_info = queue::PresentInfo {
+        swapchain: &swapchain,
+        image_index: img_index,
+        wait: &[&render_sem]
+    };
+
+    cmd_queue.present(&present_info).expect("Failed to present frame");
+
+    event_loop.run(move |event, control_flow| {
+        match event {
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => {
+                control_flow.exit();
+            },
+            _ => ()
+        }
+
+    }).expect("Failed to run example");
+}
\ No newline at end of file
@@ -12,11 +12,10 @@ use libvktypes::{
     graphics,
     sync,
     cmd,
-    queue
+    queue,
+    formats
 };
 
-use libvktypes::winit;
-
 const VERT_SHADER: &str = "
 #version 460
 
@@ -27,17 +26,20 @@ layout(set = 0, binding = 0) uniform Transformations {
     mat4 view;
     mat4 projection;
     mat4 scale;
-    mat4 z_rotation;
     mat4 y_rotation;
 } transformations;
 
+layout(push_constant) uniform Rotation {
+    mat4 z_rotation;
+} rotation;
+
 void main() {
     vec4 projection =
         transformations.projection*
         transformations.view*
         transformations.world*
         transformations.y_rotation*
-        transformations.z_rotation*
+        rotation.z_rotation*
         transformations.scale*
         position;
 
@@ -112,6 +114,8 @@ const CAMERA_NEAR_PLANE: f32 = 2.0;
 
 const CAMERA_FAR_PLANE: f32 = 5.0;
 
+const FRAMES_IN_FLIGHT: usize = 2;
+
 
 const COEF_1: f32 = 2.0*CAMERA_NEAR_PLANE/CAMERA_WIDTH;
 const COEF_2: f32 = 2.0*CAMERA_NEAR_PLANE/CAMERA_HEIGTH;
@@ -119,6 +123,9 @@ const COEF_3: f32 = CAMERA_FAR_PLANE/(CAMERA_FAR_PLANE - CAMERA_NEAR_PLANE);
 const COEF_4: f32 = (-CAMERA_NEAR_PLANE*CAMERA_FAR_PLANE)/(CAMERA_FAR_PLANE - CAMERA_NEAR_PLANE);
 
 fn main() {
+    // Run with `--wireframe` to draw only edges of the cube
+    let wireframe = std::env::args().any(|x| x == "--wireframe");
+
     let mut z_angle: f32 = 0.0;
 
     let mut transformations = [
@@ -154,12 +161,6 @@ fn main() {
         0.0,  0.0,  0.25, 0.0,
         0.0,  0.0,  0.0,  1.0,
 
-        // z rotation
-        z_angle.cos(),  z_angle.sin(), 0.0, 0.0,
-        -z_angle.sin(), z_angle.cos(), 0.0, 0.0,
-        0.0,            0.0,           1.0, 0.0,
-        0.0,            0.0,           0.0, 1.0,
-
         // y rotation
         Y_ANGLE.cos(), 0.0, -Y_ANGLE.sin(), 0.0,
         0.0,             1.0, 0.0,              0.0,
@@ -199,22 +200,34 @@ fn main() {
         lib: &lib,
         hw: hw_dev,
         extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features {
+            fill_mode_non_solid: wireframe.into(),
+            ..hw::Features::default()
+        },
+        dynamic_rendering: false,
         allocator: None,
+        track_objects: false,
     };
 
     let device = dev::Device::new(&dev_type).expect("Failed to create device");
 
-    let capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
+    let mut capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
 
     assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
     assert!(capabilities.is_flags_supported(memory::UsageFlags::COLOR_ATTACHMENT));
 
-    let surf_format = capabilities.formats().next().expect("No available formats").format;
+    let surface_format = capabilities.choose_format(&[
+        (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+        (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+    ]);
+
+    let surf_format = surface_format.format;
 
     let swp_type = swapchain::SwapchainCfg {
         num_of_images: capabilities.min_img_count(),
         format: surf_format,
-        color: memory::ColorSpace::SRGB_NONLINEAR,
+        color: surface_format.color_space,
         present_mode: swapchain::PresentMode::FIFO,
         flags: memory::UsageFlags::COLOR_ATTACHMENT,
         extent: capabilities.extent2d(),
@@ -227,6 +240,7 @@ fn main() {
     let vert_shader_type = shader::ShaderCfg {
         path: "VERT_DATA",
         entry: "main",
+        include_dirs: &[],
     };
 
     let vert_shader =
@@ -236,6 +250,7 @@ fn main() {
     let frag_shader_type = shader::ShaderCfg {
         path: "FRAG_DATA",
         entry: "main",
+        include_dirs: &[],
     };
 
     let frag_shader =
@@ -259,7 +274,14 @@ fn main() {
                 queue_families: &[queue.index()],
                 simultaneous_access: false,
                 count: 1
-            },
+            }
+        ]
+    };
+
+    let uniform_cfg = memory::MemoryCfg {
+        properties: hw::MemoryProperty::HOST_VISIBLE,
+        filter: &hw::any,
+        buffers: &[
             &memory::BufferCfg {
                 size: std::mem::size_of_val(&transformations) as u64,
                 usage: memory::UNIFORM,
@@ -289,54 +311,60 @@ fn main() {
     }, 1)
     .expect("Failed to fill indices");
 
-    data.access(&mut |bytes: &mut [f32]| {
-        bytes.clone_from_slice(&transformations);
-    }, 2)
-    .expect("Failed to fill coordinate transformations");
+    let mut uniforms = memory::Memory::allocate(&device, &uniform_cfg).expect("Failed to allocate uniform memory");
 
-    data.access(&mut |bytes: &mut [f32]| {
-        bytes.clone_from_slice(COLOR_DATA);
-    }, 3)
-    .expect("Failed to fill color data");
-
-    let descs = graphics::PipelineDescriptor::allocate(&device, &[&[
-        graphics::BindingCfg {
-            resource_type: graphics::DescriptorType::UNIFORM_BUFFER,
-            stage: graphics::ShaderStage::VERTEX,
-            count: 1,
-        },
-        graphics::BindingCfg {
-            resource_type: graphics::DescriptorType::UNIFORM_BUFFER,
-            stage: graphics::ShaderStage::FRAGMENT,
-            count: 1,
-        }
-    ]]).expect("Failed to allocate resources");
+    let descs = graphics::PipelineDescriptor::from_shaders(&device, &[&vert_shader, &frag_shader])
+        .expect("Failed to allocate resources");
 
     descs.update(&[
         graphics::UpdateInfo {
             set: 0,
             binding: 0,
             starting_array_element: 0,
-            resources: graphics::ShaderBinding::Buffers(&[graphics::BufferBinding::new(data.view(2))]),
+            resources: graphics::ShaderBinding::Buffers(&[graphics::BufferBinding::new(uniforms.view(0))]),
         },
         graphics::UpdateInfo {
             set: 0,
             binding: 1,
             starting_array_element: 0,
-            resources: graphics::ShaderBinding::Buffers(&[graphics::BufferBinding::new(data.view(3))]),
+            resources: graphics::ShaderBinding::Buffers(&[graphics::BufferBinding::new(uniforms.view(1))]),
         },
-    ]);
+    ]).expect("Failed to update descriptor");
+
+    // Transformations are updated every frame so keep them mapped
+    let mut mapped_data = uniforms.map_persistent().expect("Failed to map memory");
+
+    mapped_data.slice_of::<f32>(0).expect("Failed to get transformations").clone_from_slice(&transformations);
+
+    mapped_data.flush(0).expect("Failed to flush coordinate transformations");
+
+    mapped_data.slice_of::<f32>(1).expect("Failed to get color data").clone_from_slice(COLOR_DATA);
+
+    mapped_data.flush(1).expect("Failed to flush color data");
+
+    let depth_format = hw_dev
+        .find_supported_format(
+            &lib,
+            &[memory::ImageFormat::D32_SFLOAT, memory::ImageFormat::D24_UNORM_S8_UINT],
+            memory::Tiling::OPTIMAL,
+            hw::FormatFeature::DEPTH_STENCIL_ATTACHMENT
+        )
+        .expect("Failed to find depth buffer format");
 
     let depth_buffer_cfg = [
         memory::ImageCfg {
             queue_families: &[queue.index()],
             simultaneous_access: false,
-            format: memory::ImageFormat::D32_SFLOAT,
+            format: depth_format,
             extent: capabilities.extent3d(1),
             usage: memory::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
             layout: memory::ImageLayout::UNDEFINED,
-            aspect: memory::ImageAspect::DEPTH,
+            aspect: formats::aspect(depth_format),
             tiling: memory::Tiling::OPTIMAL,
+            samples: memory::SampleCount::TYPE_1,
+            mip_levels: 1,
+            kind: memory::ImageKind::D2,
+            array_layers: 1,
             count: 1
         }
     ];
@@ -349,7 +377,7 @@ fn main() {
 
     let depth_buffer = memory::ImageMemory::allocate(&device, &alloc_info).expect("Failed to allocate depth buffer");
 
-    let render_pass = graphics::RenderPass::with_depth_buffers(&device, surf_format, memory::ImageFormat::D32_SFLOAT, 1)
+    let render_pass = graphics::RenderPass::with_depth_buffers(&device, surf_format, depth_format, 1)
         .expect("Failed to create render pass");
 
     let vertex_cfg = [
@@ -362,26 +390,25 @@ fn main() {
     ];
 
     let pipe_type = graphics::PipelineCfg {
-        vertex_shader: &vert_shader,
         vertex_size: std::mem::size_of::<[f32; 4]>() as u32,
         vert_input: &vertex_cfg,
-        frag_shader: &frag_shader,
-        geom_shader: None,
-        topology: graphics::Topology::TRIANGLE_LIST,
         extent: capabilities.extent2d(),
-        push_constant_size: 0,
-        render_pass: &render_pass,
-        subpass_index: 0,
-        enable_depth_test: true,
-        enable_primitive_restart: false,
+        push_constants: &[
+            graphics::PushConstantCfg {
+                stage: graphics::ShaderStage::VERTEX,
+                offset: 0,
+                size: std::mem::size_of::<[f32; 16]>() as u32,
+            }
+        ],
+        render_pass: Some(&render_pass),
+        depth_stencil: Some(graphics::DepthStencilCfg::simple()),
         cull_mode: graphics::CullMode::BACK,
-        descriptor: &descs,
+        polygon_mode: if wireframe { graphics::PolygonMode::LINE } else { graphics::PolygonMode::FILL },
+        ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descs)
     };
 
     let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
 
-    let img_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
-    let render_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
 
     let cmd_pool_type = cmd::PoolCfg {
         queue_index: queue.index(),
@@ -389,94 +416,108 @@ fn main() {
 
     let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
 
-    let images = swapchain.images().expect("Failed to get images");
-
-    let frames: Vec<memory::Framebuffer> = images.iter()
-        .map(|image| {
-            let frames_cfg = memory::FramebufferCfg {
-                render_pass: &render_pass,
-                images: &[image.view(0), depth_buffer.view(0)],
-                extent: capabilities.extent2d(),
-            };
-
-            memory::Framebuffer::new(&device, &frames_cfg).expect("Failed to create framebuffers")
-        })
-        .collect();
+    let frames = memory::Framebuffer::for_swapchain(
+        &device,
+        &render_pass,
+        swapchain.images(),
+        &[depth_buffer.view(0)],
+        swapchain.extent()
+    ).expect("Failed to create framebuffers");
 
-    let cmd_buffers: Vec<cmd::ExecutableBuffer> = frames.iter()
-        .map(|frame| {
-            let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command pool");
+    // Driver may create more images than requested so use the actual number
+    let frames_in_flight = FRAMES_IN_FLIGHT.min(swapchain.image_count() as usize);
 
-            cmd_buffer.begin_render_pass(&render_pass, &frame);
-            cmd_buffer.bind_graphics_pipeline(&pipeline);
-            cmd_buffer.bind_vertex_buffers(&[data.vertex_view(0, vertex_cfg[0].offset)]);
-            cmd_buffer.bind_index_buffer(data.view(1), 0, memory::IndexBufferType::UINT32);
-            cmd_buffer.bind_resources(&pipeline, &descs, &[]);
-            cmd_buffer.draw_indexed(INDICES.len() as u32, 1, 0, 0, 0);
-            cmd_buffer.end_render_pass();
+    let mut frame_sync = sync::FrameSync::new(&device, frames_in_flight).expect("Failed to create frame synchronization");
 
-            cmd_buffer.commit().expect("Failed to commit buffer")
-        })
-        .collect();
+    let mut cmd_buffers: Vec<Option<cmd::ExecutableBuffer>> = (0..frames_in_flight).map(|_| None).collect();
 
     let queue_cfg = queue::QueueCfg {
         family_index: queue.index(),
         queue_index: 0
     };
 
-    let cmd_queue = queue::Queue::new(&device, &queue_cfg);
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
 
-    event_loop.run(move |event, control_flow| {
-        match event {
-            winit::event::Event::WindowEvent {
-                event: winit::event::WindowEvent::CloseRequested,
-                ..
-            } => {
-                control_flow.exit();
-            },
-            winit::event::Event::AboutToWait => {
-                wnd.request_redraw();
-            },
-            winit::event::Event::WindowEvent {
-                event: winit::event::WindowEvent::RedrawRequested,
-                ..
-            } => {
-                z_angle += 0.01;
-
-                transformations[64] = z_angle.cos();
-                transformations[65] = z_angle.sin();
-                transformations[68] = -z_angle.sin();
-                transformations[69] = z_angle.cos();
-
-                data.access(&mut |bytes: &mut [f32]| {
-                    bytes.clone_from_slice(&transformations);
-                }, 2)
-                .expect("Failed to fill coordinate transformations");
-
-                let img_index = swapchain.next_image(u64::MAX, Some(&img_sem), None).expect("Failed to get image index");
-
-                let exec_info = queue::ExecInfo {
-                    buffer: &cmd_buffers[img_index as usize],
-                    wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
-                    timeout: u64::MAX,
-                    wait: &[&img_sem],
-                    signal: &[&render_sem],
-                };
-
-                cmd_queue.exec(&exec_info).expect("Failed to execute queue");
-
-                let present_info = queue::PresentInfo {
-                    swapchain: &swapchain,
-                    image_index: img_index,
-                    wait: &[&render_sem]
-                };
-
-                cmd_queue.present(&present_info).expect("Failed to present frame");
-
-                std::thread::sleep(std::time::Duration::from_millis(10));
-            },
-            _ => ()
+    // Uniform memory stays borrowed by mapping so closure takes only references
+    let data = &data;
+
+    let surface = &surface;
+
+    window::run_render_loop(event_loop, &wnd, move |_| {
+        // Nothing can be presented while window is minimized
+        capabilities.refresh(hw_dev, surface).expect("Failed to refresh capabilities");
+
+        if capabilities.is_zero_extent() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+
+            return window::ControlFlow::Continue;
         }
 
+        z_angle += 0.01;
+
+        let z_rotation: [f32; 16] = [
+            z_angle.cos(),  z_angle.sin(), 0.0, 0.0,
+            -z_angle.sin(), z_angle.cos(), 0.0, 0.0,
+            0.0,            0.0,           1.0, 0.0,
+            0.0,            0.0,           0.0, 1.0,
+        ];
+
+        let z_rotation_bytes: Vec<u8> = z_rotation.iter().flat_map(|x| x.to_ne_bytes()).collect();
+
+        let (image, frame) = frame_sync.acquire(&swapchain).expect("Failed to acquire frame");
+
+        let y_angle = Y_ANGLE + 0.25*z_angle.sin();
+
+        transformations[64] = y_angle.cos();
+        transformations[66] = -y_angle.sin();
+        transformations[72] = y_angle.sin();
+        transformations[74] = y_angle.cos();
+
+        mapped_data.slice_of::<f32>(0).expect("Failed to get transformations").clone_from_slice(&transformations);
+
+        mapped_data.flush(0).expect("Failed to flush coordinate transformations");
+
+        // Fence of the current frame is signaled so its previous buffer may be re-recorded
+        let cmd_buffer = match cmd_buffers[frame_sync.current()].take() {
+            Some(buffer) => buffer.reset().expect("Failed to reset command buffer"),
+            None => cmd_pool.allocate().expect("Failed to allocate command buffer")
+        };
+
+        cmd_buffer.begin_render_pass(&render_pass, &frames[image.index as usize]);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.bind_vertex_buffers(&[data.vertex_view(0, vertex_cfg[0].offset).expect("Failed to create vertex view")]);
+        cmd_buffer.bind_index_buffer(data.view(1), 0, memory::IndexBufferType::UINT32);
+        cmd_buffer.bind_resources(&pipeline, &descs, &[]);
+        cmd_buffer.update_graphics_push_constants(&pipeline, graphics::ShaderStage::VERTEX, 0, &z_rotation_bytes);
+        cmd_buffer.draw_indexed(INDICES.len() as u32, 1, 0, 0, 0);
+        cmd_buffer.end_render_pass();
+
+        let cmd_buffer = &*cmd_buffers[frame_sync.current()]
+            .insert(cmd_buffer.commit().expect("Failed to commit buffer"));
+
+        let exec_info = queue::ExecInfo {
+            buffers: &[cmd_buffer],
+            wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+            timeout: u64::MAX,
+            wait: &[frame.image_available()],
+            signal: &[frame.render_finished()],
+            fence: None,
+        };
+
+        cmd_queue.submit_with_fence(&exec_info, frame.in_flight()).expect("Failed to execute queue");
+
+        let present_info = queue::PresentInfo {
+            swapchain: &swapchain,
+            image_index: image.index,
+            wait: &[frame.render_finished()]
+        };
+
+        cmd_queue.present(&present_info).expect("Failed to present frame");
+
+        frame_sync.advance();
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+
+        window::ControlFlow::Continue
     }).expect("Failed to run example");
 }
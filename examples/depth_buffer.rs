@@ -33,7 +33,11 @@ fn main() {
         lib: &lib,
         hw: hw_dev,
         extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features::default(),
+        dynamic_rendering: false,
         allocator: None,
+        track_objects: false,
     };
 
     let device = dev::Device::new(&dev_type).expect("Failed to create device");
@@ -41,17 +45,20 @@ fn main() {
     let capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
 
     //assert!(capabilities.is_img_count_supported(2));
-    assert!(capabilities.is_format_supported(surface::SurfaceFormat {
-        format: memory::ImageFormat::B8G8R8A8_UNORM,
-        color_space: memory::ColorSpace::SRGB_NONLINEAR,
-    }));
     assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
     assert!(capabilities.is_flags_supported(memory::UsageFlags::COLOR_ATTACHMENT));
 
+    let surface_format = capabilities.choose_format(&[
+        (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+        (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+    ]);
+
+    let surf_format = surface_format.format;
+
     let swp_type = swapchain::SwapchainCfg {
         num_of_images: 2,
-        format: memory::ImageFormat::B8G8R8A8_UNORM,
-        color: memory::ColorSpace::SRGB_NONLINEAR,
+        format: surf_format,
+        color: surface_format.color_space,
         present_mode: swapchain::PresentMode::FIFO,
         flags: memory::UsageFlags::COLOR_ATTACHMENT,
         extent: capabilities.extent2d(),
@@ -64,6 +71,7 @@ fn main() {
     let vert_shader_type = shader::ShaderCfg {
         path: "examples/compiled_shaders/depth_buffer.vert.spv",
         entry: "main",
+        include_dirs: &[],
     };
 
     let vert_shader = shader::Shader::from_file(&device, &vert_shader_type).expect("Failed to create vertex shader module");
@@ -71,12 +79,11 @@ fn main() {
     let frag_shader_type = shader::ShaderCfg {
         path: "examples/compiled_shaders/depth_buffer.frag.spv",
         entry: "main",
+        include_dirs: &[],
     };
 
     let frag_shader = shader::Shader::from_file(&device, &frag_shader_type).expect("Failed to create fragment shader module");
 
-    let surf_format = capabilities.formats().next().expect("No available formats").format;
-
     let mem_cfg = memory::MemoryCfg {
         properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
         filter: &hw::any,
@@ -118,6 +125,10 @@ fn main() {
         layout: memory::ImageLayout::UNDEFINED,
         aspect: memory::ImageAspect::DEPTH,
         tiling: memory::Tiling::OPTIMAL,
+        samples: memory::SampleCount::TYPE_1,
+        mip_levels: 1,
+        kind: memory::ImageKind::D2,
+        array_layers: 1,
         count: 1
     };
 
@@ -132,8 +143,9 @@ fn main() {
     let render_pass = graphics::RenderPass::with_depth_buffers(&device, surf_format, memory::ImageFormat::D32_SFLOAT, 1)
         .expect("Failed to create render pass");
 
+    let descriptor = graphics::PipelineDescriptor::empty(&device);
+
     let pipe_type = graphics::PipelineCfg {
-        vertex_shader: &vert_shader,
         vertex_size: std::mem::size_of::<[f32; 4]>() as u32,
         vert_input: &[graphics::VertexInputCfg {
             location: 0,
@@ -141,17 +153,11 @@ fn main() {
             format: memory::ImageFormat::R32G32B32A32_SFLOAT,
             offset: 0,
         }],
-        frag_shader: &frag_shader,
-        geom_shader: None,
-        topology: graphics::Topology::TRIANGLE_LIST,
         extent: capabilities.extent2d(),
-        push_constant_size: 0,
-        render_pass: &render_pass,
-        subpass_index: 0,
-        enable_depth_test: true,
-        enable_primitive_restart: false,
+        render_pass: Some(&render_pass),
+        depth_stencil: Some(graphics::DepthStencilCfg::simple()),
         cull_mode: graphics::CullMode::BACK,
-        descriptor: &graphics::PipelineDescriptor::empty(&device)
+        ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
     };
 
     let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
@@ -167,9 +173,12 @@ fn main() {
 
     let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command pool");
 
-    let images = swapchain.images().expect("Failed to get images");
+    let images = swapchain.images();
 
-    let img_index = swapchain.next_image(u64::MAX, Some(&img_sem), None).expect("Failed to get image index");
+    let img_index = swapchain
+        .next_image(std::time::Duration::MAX, swapchain::AcquireSync::Semaphore(&img_sem))
+        .expect("Failed to get image index")
+        .index;
 
     let framebuffer_cfg = memory::FramebufferCfg {
         images: &[images[img_index as usize].view(0), depth_buffer.view(0)],
@@ -183,7 +192,7 @@ fn main() {
 
     cmd_buffer.bind_graphics_pipeline(&pipeline);
 
-    cmd_buffer.bind_vertex_buffers(&[vertex_data.vertex_view(0, 0)]);
+    cmd_buffer.bind_vertex_buffers(&[vertex_data.vertex_view(0, 0).expect("Failed to create vertex view")]);
 
     cmd_buffer.draw(9, 1, 0, 0);
 
@@ -196,14 +205,15 @@ fn main() {
         queue_index: 0
     };
 
-    let cmd_queue = queue::Queue::new(&device, &queue_cfg);
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
 
     let exec_info = queue::ExecInfo {
-        buffer: &exec_buffer,
+        buffers: &[&exec_buffer],
         wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
         timeout: u64::MAX,
         wait: &[&img_sem],
         signal: &[&render_sem],
+        fence: None,
     };
 
     cmd_queue.exec(&exec_info).expect("Failed to execute queue");
@@ -0,0 +1,391 @@
+use libvktypes::{
+    window,
+    libvk,
+    layers,
+    extensions,
+    surface,
+    hw,
+    dev,
+    swapchain,
+    memory,
+    shader,
+    graphics,
+    sync,
+    cmd,
+    queue
+};
+
+use std::mem::{size_of, size_of_val};
+
+const VERT_SHADER: &str = "
+#version 460
+
+layout (location = 0) in vec4 pos;
+layout (location = 1) in vec2 in_uv;
+
+layout (location = 0) out vec2 out_uv;
+
+void main() {
+    out_uv = in_uv;
+    gl_Position = pos;
+}
+";
+
+const FRAG_SHADER: &str = "
+#version 460
+
+layout (location = 0) in vec2 uv;
+layout (location = 0) out vec4 out_color;
+
+layout (set = 0, binding = 0) uniform sampler2D samplerColor;
+
+void main() {
+    out_color = texture(samplerColor, uv);
+}
+";
+
+const TEXTURE_WIDTH: u32  = 3;
+const TEXTURE_HEIGHT: u32 = 2;
+
+const TEXTURE_SIZE: usize = (TEXTURE_WIDTH*TEXTURE_HEIGHT) as usize;
+
+const TEXTURE_DATA: [u32; TEXTURE_SIZE] = [
+    0x000000FF, 0x00000000, 0x0000FF00,
+    0x00FF0000, 0x00FFFFFF, 0x00FFFF00
+];
+
+const VERTEX_DATA: &[f32] = &[
+    -0.8, -0.8, 0.0, 1.0, 0.0, 0.0, // top left corner
+    -0.8,  0.8, 0.0, 1.0, 0.0, 1.0, // bottom left
+     0.8,  0.8, 0.0, 1.0, 1.0, 1.0, // bottom right
+     0.8, -0.8, 0.0, 1.0, 1.0, 0.0, // top right
+];
+
+const INDICES: &[u32] = &[
+    0, 1, 2,
+    0, 2, 3
+];
+
+fn main() {
+    let event_loop = window::eventloop().expect("Failed to create eventloop");
+
+    let wnd = window::create_window(&event_loop).expect("Failed to create window");
+
+    let mut extensions = extensions::required_extensions(&wnd);
+    extensions.push(extensions::DEBUG_EXT_NAME);
+    extensions.push(extensions::SURFACE_EXT_NAME);
+
+    let lib_type = libvk::InstanceType {
+        debug_layer: Some(layers::DebugLayer::default()),
+        extensions: &extensions,
+        // Dynamic rendering is the core feature since Vulkan 1.3
+        version_minor: 3,
+        ..libvk::InstanceType::default()
+    };
+
+    let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+    let surface = surface::Surface::new(&lib, &wnd).expect("Failed to create surface");
+
+    let hw_list = hw::Description::poll(&lib, Some(&surface)).expect("Failed to list hardware");
+
+    let (hw_dev, queue, _) = hw_list
+        .find_first(
+            hw::HWDevice::is_discrete_gpu,
+            |q| q.is_graphics() && q.is_surface_supported(),
+            |_| true
+        )
+        .expect("Failed to find suitable hardware device");
+
+    let dev_type = dev::DeviceCfg {
+        lib: &lib,
+        hw: hw_dev,
+        extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features::default(),
+        dynamic_rendering: true,
+        allocator: None,
+        track_objects: false,
+    };
+
+    let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+    let cmd_pool_type = cmd::PoolCfg {
+        queue_index: queue.index(),
+    };
+
+    let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+    let queue_cfg = queue::QueueCfg {
+        family_index: queue.index(),
+        queue_index: 0
+    };
+
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+    let capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
+
+    assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
+    assert!(capabilities.is_flags_supported(memory::UsageFlags::COLOR_ATTACHMENT));
+
+    let surface_format = capabilities.choose_format(&[
+        (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+        (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+    ]);
+
+    let surf_format = surface_format.format;
+
+    let swp_type = swapchain::SwapchainCfg {
+        num_of_images: capabilities.min_img_count(),
+        format: surf_format,
+        color: surface_format.color_space,
+        present_mode: swapchain::PresentMode::FIFO,
+        flags: memory::UsageFlags::COLOR_ATTACHMENT,
+        extent: capabilities.extent2d(),
+        transform: capabilities.pre_transformation(),
+        alpha: capabilities.first_alpha_composition().expect("No alpha composition")
+    };
+
+    let swapchain = swapchain::Swapchain::new(&lib, &device, &surface, &swp_type).expect("Failed to create swapchain");
+
+    let vert_shader_type = shader::ShaderCfg {
+        path: "VERT_DATA",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let vert_shader =
+        shader::Shader::from_glsl(&device, &vert_shader_type, VERT_SHADER, shader::Kind::Vertex)
+        .expect("Failed to create vertex shader module");
+
+    let frag_shader_type = shader::ShaderCfg {
+        path: "FRAG_DATA",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let frag_shader =
+        shader::Shader::from_glsl(&device, &frag_shader_type, FRAG_SHADER, shader::Kind::Fragment)
+        .expect("Failed to create fragment shader module");
+
+    let mem_cfg = memory::MemoryCfg {
+        properties: hw::MemoryProperty::HOST_VISIBLE,
+        filter: &hw::any,
+        buffers: &[
+            &memory::BufferCfg {
+                size: size_of_val(VERTEX_DATA) as u64,
+                usage: memory::VERTEX,
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                count: 1
+            },
+            &memory::BufferCfg {
+                size: size_of_val(INDICES) as u64,
+                usage: memory::INDEX,
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                count: 1
+            }
+        ]
+    };
+
+    let host_data = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+    host_data.view(0).access(&mut |bytes: &mut [f32]| {
+        bytes.clone_from_slice(VERTEX_DATA);
+    }).expect("Failed to fill vertex buffer");
+
+    host_data.view(1).access(&mut |bytes: &mut [u32]| {
+        bytes.clone_from_slice(INDICES);
+    }).expect("Failed to fill index buffer");
+
+    let texture_extent = memory::Extent3D {width: TEXTURE_WIDTH, height: TEXTURE_HEIGHT, depth: 1};
+
+    let texture_mem_cfg = memory::ImagesAllocationInfo {
+        properties: hw::MemoryProperty::DEVICE_LOCAL,
+        filter: &hw::any,
+        image_cfgs: &[
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: memory::ImageFormat::R8G8B8A8_SRGB,
+                extent: texture_extent,
+                usage:  memory::ImageUsageFlags::SAMPLED
+                    | memory::ImageUsageFlags::TRANSFER_SRC
+                    | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: memory::max_mip_levels(texture_extent),
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ]
+    };
+
+    let texture_memory =
+        memory::ImageMemory::allocate(&device, &texture_mem_cfg).expect("Failed to allocate texture memory");
+
+    let texture = texture_memory.view(0);
+
+    let texels: Vec<u8> = TEXTURE_DATA.iter().flat_map(|texel| texel.to_ne_bytes()).collect();
+
+    // Mip chain is generated with the draw commands
+    memory::StagingBuffer::new(&device, &cmd_queue, &cmd_pool)
+        .upload_image(texture, &texels, memory::ImageLayout::TRANSFER_DST_OPTIMAL)
+        .expect("Failed to upload texture");
+
+    let descs = graphics::PipelineDescriptor::allocate(&device, &[&[
+        graphics::BindingCfg {
+            resource_type: graphics::DescriptorType::COMBINED_IMAGE_SAMPLER,
+            stage: graphics::ShaderStage::FRAGMENT,
+            count: 1,
+        }
+    ]]).expect("Failed to allocate resources");
+
+    let vert_input = [
+        graphics::VertexInputCfg {
+            location: 0,
+            binding: 0,
+            format: memory::ImageFormat::R32G32B32A32_SFLOAT,
+            offset: 0,
+        },
+        graphics::VertexInputCfg {
+            location: 1,
+            binding: 0,
+            format: memory::ImageFormat::R32G32_SFLOAT,
+            offset: size_of::<[f32; 4]>() as u32,
+        }
+    ];
+
+    let pipe_type = graphics::PipelineCfg {
+        vertex_size: size_of::<[f32; 6]>() as u32,
+        vert_input: &vert_input,
+        extent: capabilities.extent2d(),
+        rendering_formats: Some(graphics::RenderingFormats {
+            color: &[surf_format],
+            depth: None,
+        }),
+        cull_mode: graphics::CullMode::BACK,
+        ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descs)
+    };
+
+    let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+    let sampler_cfg = graphics::SamplerCfg {
+        address_mode_u: graphics::SamplerAddressMode::MIRRORED_REPEAT,
+        address_mode_v: graphics::SamplerAddressMode::MIRRORED_REPEAT,
+        mipmap_mode: graphics::SamplerMipmapMode::LINEAR,
+        max_lod: texture.mip_levels() as f32,
+        ..Default::default()
+    };
+
+    let sampler = graphics::Sampler::new(&device, &sampler_cfg).expect("Failed to create sampler");
+
+    descs.update(&[graphics::UpdateInfo {
+        set: 0,
+        binding: 0,
+        starting_array_element: 0,
+        resources: graphics::ShaderBinding::Samplers(&[(&sampler, texture, memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL)]),
+    }]).expect("Failed to update descriptor");
+
+    let img_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+    let render_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+
+    let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command pool");
+
+    let images = swapchain.images();
+
+    let img_index = swapchain
+        .next_image(std::time::Duration::MAX, swapchain::AcquireSync::Semaphore(&img_sem))
+        .expect("Failed to get image index")
+        .index;
+
+    let target = images[img_index as usize].view(0);
+
+    cmd_buffer.blit_mip_chain(texture);
+
+    // Without render pass layout transitions are up to us
+    cmd_buffer.set_image_barrier(
+        target,
+        cmd::AccessType::NONE,
+        cmd::AccessType::COLOR_ATTACHMENT_WRITE,
+        memory::ImageLayout::UNDEFINED,
+        memory::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED
+    );
+
+    cmd_buffer.begin_rendering(&cmd::RenderingInfo {
+        extent: capabilities.extent2d(),
+        colors: &[cmd::RenderingAttachment {
+            view: target,
+            load_op: graphics::AttachmentLoadOp::CLEAR,
+            store_op: graphics::AttachmentStoreOp::STORE,
+            clear: cmd::ClearValue::Color([0.0, 0.0, 0.0, 1.0]),
+        }],
+        depth: None,
+    });
+
+    cmd_buffer.bind_graphics_pipeline(&pipeline);
+
+    cmd_buffer.bind_vertex_buffers(&[host_data.vertex_view(0, 0).expect("Failed to create vertex view")]);
+
+    cmd_buffer.bind_index_buffer(host_data.view(1), 0, memory::IndexBufferType::UINT32);
+
+    cmd_buffer.bind_resources(&pipeline, &descs, &[]);
+
+    cmd_buffer.draw_indexed(INDICES.len() as u32, 1, 0, 0, 0);
+
+    cmd_buffer.end_rendering();
+
+    cmd_buffer.set_image_barrier(
+        target,
+        cmd::AccessType::COLOR_ATTACHMENT_WRITE,
+        cmd::AccessType::NONE,
+        memory::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        memory::ImageLayout::PRESENT_SRC_KHR,
+        cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        cmd::PipelineStage::BOTTOM_OF_PIPE,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED
+    );
+
+    let exec_buffer = cmd_buffer.commit().expect("Failed to commit buffer");
+
+    let exec_info = queue::ExecInfo {
+        buffers: &[&exec_buffer],
+        wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        timeout: u64::MAX,
+        wait: &[&img_sem],
+        signal: &[&render_sem],
+        fence: None,
+    };
+
+    cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+    let present_info = queue::PresentInfo {
+        swapchain: &swapchain,
+        image_index: img_index,
+        wait: &[&render_sem]
+    };
+
+    cmd_queue.present(&present_info).expect("Failed to present frame");
+
+    event_loop.run(move |event, control_flow| {
+        match event {
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => {
+                control_flow.exit();
+            },
+            _ => ()
+        }
+
+    }).expect("Failed to run example");
+}
\ No newline at end of file
@@ -0,0 +1,262 @@
+//! Expand points into camera facing quads (billboards) in geometry shader
+//!
+//! Requires `geometryShader` feature, example exits if no device supports it
+use libvktypes::*;
+
+const VERT_SHADER: &str = "
+#version 460
+
+// Centers of the billboards
+vec2 positions[3] = vec2[](
+    vec2(-0.5, 0.5),
+    vec2(0.0, -0.5),
+    vec2(0.5, 0.5)
+);
+
+vec4 colors[3] = vec4[](
+    vec4(1.0, 0.0, 0.0, 1.0),
+    vec4(0.0, 1.0, 0.0, 1.0),
+    vec4(0.0, 0.0, 1.0, 1.0)
+);
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+    color = colors[gl_VertexIndex];
+}
+";
+
+const GEOM_SHADER: &str = "
+#version 460
+
+layout(points) in;
+layout(triangle_strip, max_vertices = 4) out;
+
+layout(location = 0) in vec4 in_color[];
+
+layout(location = 0) out vec4 color;
+
+const float SIZE = 0.2;
+
+vec2 corners[4] = vec2[](
+    vec2(-1.0, -1.0),
+    vec2(-1.0, 1.0),
+    vec2(1.0, -1.0),
+    vec2(1.0, 1.0)
+);
+
+void main() {
+    for (int i = 0; i < 4; i++) {
+        gl_Position = gl_in[0].gl_Position + vec4(corners[i]*SIZE, 0.0, 0.0);
+        color = in_color[0];
+        EmitVertex();
+    }
+
+    EndPrimitive();
+}
+";
+
+const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) in vec4 in_color;
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = in_color;
+}
+";
+
+fn main() {
+    let event_loop = window::eventloop().expect("Failed to create eventloop");
+
+    let wnd = window::create_window(&event_loop).expect("Failed to create window");
+
+    let mut extensions = extensions::required_extensions(&wnd);
+    extensions.push(extensions::DEBUG_EXT_NAME);
+    extensions.push(extensions::SURFACE_EXT_NAME);
+
+    let lib_type = libvk::InstanceType {
+        debug_layer: Some(layers::DebugLayer::default()),
+        extensions: &extensions,
+        ..libvk::InstanceType::default()
+    };
+
+    let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+    let surface = surface::Surface::new(&lib, &wnd).expect("Failed to create surface");
+
+    let hw_list = hw::Description::poll(&lib, Some(&surface)).expect("Failed to list hardware");
+
+    let Some((hw_dev, queue, _)) = hw_list.find_first(
+        |hw| hw.features().geometry_shader != 0,
+        |q| q.is_graphics() && q.is_surface_supported(),
+        |_| true
+    ) else {
+        println!("No device supports geometryShader feature, skipping");
+        return;
+    };
+
+    let dev_type = dev::DeviceCfg {
+        lib: &lib,
+        hw: hw_dev,
+        extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features {
+            geometry_shader: 1,
+            ..hw::Features::default()
+        },
+        dynamic_rendering: false,
+        allocator: None,
+        track_objects: false,
+    };
+
+    let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+    let capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
+
+    assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
+    assert!(capabilities.is_flags_supported(memory::UsageFlags::COLOR_ATTACHMENT));
+
+    let surface_format = capabilities.choose_format(&[
+        (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+        (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+    ]);
+
+    let surf_format = surface_format.format;
+
+    let swp_type = swapchain::SwapchainCfg {
+        num_of_images: capabilities.min_img_count(),
+        format: surf_format,
+        color: surface_format.color_space,
+        present_mode: swapchain::PresentMode::FIFO,
+        flags: memory::UsageFlags::COLOR_ATTACHMENT,
+        extent: capabilities.extent2d(),
+        transform: capabilities.pre_transformation(),
+        alpha: capabilities.first_alpha_composition().expect("No alpha composition")
+    };
+
+    let swapchain = swapchain::Swapchain::new(&lib, &device, &surface, &swp_type).expect("Failed to create swapchain");
+
+    let vert_shader_type = shader::ShaderCfg {
+        path: "VERT_DATA",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let vert_shader =
+        shader::Shader::from_glsl(&device, &vert_shader_type, VERT_SHADER, shader::Kind::Vertex)
+        .expect("Failed to create vertex shader module");
+
+    let geom_shader_type = shader::ShaderCfg {
+        path: "GEOM_DATA",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let geom_shader =
+        shader::Shader::from_glsl(&device, &geom_shader_type, GEOM_SHADER, shader::Kind::Geometry)
+        .expect("Failed to create geometry shader module");
+
+    let frag_shader_type = shader::ShaderCfg {
+        path: "FRAG_DATA",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let frag_shader =
+        shader::Shader::from_glsl(&device, &frag_shader_type, FRAG_SHADER, shader::Kind::Fragment)
+        .expect("Failed to create fragment shader module");
+
+    let render_pass = graphics::RenderPass::single_subpass(&device, surf_format)
+        .expect("Failed to create render pass");
+
+    let descriptor = graphics::PipelineDescriptor::empty(&device);
+
+    let pipe_type = graphics::PipelineCfg {
+        geom_shader: Some(&geom_shader),
+        topology: graphics::Topology::POINT_LIST,
+        extent: capabilities.extent2d(),
+        render_pass: Some(&render_pass),
+        ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+    };
+
+    let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+    let img_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+    let render_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+
+    let cmd_pool_type = cmd::PoolCfg {
+        queue_index: queue.index(),
+    };
+
+    let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+    let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command pool");
+
+    let images = swapchain.images();
+
+    let img_index = swapchain
+        .next_image(std::time::Duration::MAX, swapchain::AcquireSync::Semaphore(&img_sem))
+        .expect("Failed to get image index")
+        .index;
+
+    let frames_cfg = memory::FramebufferCfg {
+        render_pass: &render_pass,
+        images: &[images[img_index as usize].view(0)],
+        extent: capabilities.extent2d(),
+    };
+
+    let frame = memory::Framebuffer::new(&device, &frames_cfg).expect("Failed to create framebuffers");
+
+    cmd_buffer.begin_render_pass(&render_pass, &frame);
+
+    cmd_buffer.bind_graphics_pipeline(&pipeline);
+
+    cmd_buffer.draw(3, 1, 0, 0);
+
+    cmd_buffer.end_render_pass();
+
+    let exec_buffer = cmd_buffer.commit().expect("Failed to commit buffer");
+
+    let queue_cfg = queue::QueueCfg {
+        family_index: queue.index(),
+        queue_index: 0
+    };
+
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+    let exec_info = queue::ExecInfo {
+        buffers: &[&exec_buffer],
+        wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        timeout: u64::MAX,
+        wait: &[&img_sem],
+        signal: &[&render_sem],
+        fence: None,
+    };
+
+    cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+    let present_info = queue::PresentInfo {
+        swapchain: &swapchain,
+        image_index: img_index,
+        wait: &[&render_sem]
+    };
+
+    cmd_queue.present(&present_info).expect("Failed to present frame");
+
+    event_loop.run(move |event, control_flow| {
+        match event {
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => {
+                control_flow.exit();
+            },
+            _ => ()
+        }
+
+    }).expect("Failed to run example");
+}
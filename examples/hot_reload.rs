@@ -0,0 +1,216 @@
+//! Edit `examples/shaders/single_color.frag` while example is running
+//!
+//! Pipeline is rebuilt every time the file is saved
+use libvktypes::*;
+
+use std::fs;
+use std::time::SystemTime;
+
+const FRAG_SHADER_PATH: &str = "examples/shaders/single_color.frag";
+
+const FRAMES_IN_FLIGHT: usize = 2;
+
+fn modified() -> Option<SystemTime> {
+    fs::metadata(FRAG_SHADER_PATH).and_then(|x| x.modified()).ok()
+}
+
+fn pipeline_cfg<'a>(
+    vert_shader: &'a shader::Shader,
+    frag_shader: &'a shader::Shader,
+    render_pass: &'a graphics::RenderPass,
+    descriptor: &'a graphics::PipelineDescriptor,
+    extent: memory::Extent2D
+) -> graphics::PipelineCfg<'a> {
+    graphics::PipelineCfg {
+        vertex_size: std::mem::size_of::<[f32; 4]>() as u32,
+        extent,
+        render_pass: Some(render_pass),
+        cull_mode: graphics::CullMode::BACK,
+        ..graphics::PipelineCfg::new(vert_shader, frag_shader, descriptor)
+    }
+}
+
+fn main() {
+    let event_loop = window::eventloop().expect("Failed to create eventloop");
+
+    let wnd = window::create_window(&event_loop).expect("Failed to create window");
+
+    let mut extensions = extensions::required_extensions(&wnd);
+    extensions.push(extensions::DEBUG_EXT_NAME);
+    extensions.push(extensions::SURFACE_EXT_NAME);
+
+    let lib_type = libvk::InstanceType {
+        debug_layer: Some(layers::DebugLayer::default()),
+        extensions: &extensions,
+        ..libvk::InstanceType::default()
+    };
+
+    let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+    let surface = surface::Surface::new(&lib, &wnd).expect("Failed to create surface");
+
+    let hw_list = hw::Description::poll(&lib, Some(&surface)).expect("Failed to list hardware");
+
+    let (hw_dev, queue, _) = hw_list
+        .find_first(
+            hw::HWDevice::is_dedicated_gpu,
+            |q| q.is_graphics() && q.is_surface_supported(),
+            |_| true
+        )
+        .expect("Failed to find suitable hardware device");
+
+    let dev_type = dev::DeviceCfg {
+        lib: &lib,
+        hw: hw_dev,
+        extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features::default(),
+        dynamic_rendering: false,
+        allocator: None,
+        track_objects: false,
+    };
+
+    let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+    let capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
+
+    assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
+    assert!(capabilities.is_flags_supported(memory::UsageFlags::COLOR_ATTACHMENT));
+
+    let surface_format = capabilities.choose_format(&[
+        (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+        (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+    ]);
+
+    let surf_format = surface_format.format;
+
+    let swp_type = swapchain::SwapchainCfg {
+        num_of_images: capabilities.min_img_count(),
+        format: surf_format,
+        color: surface_format.color_space,
+        present_mode: swapchain::PresentMode::FIFO,
+        flags: memory::UsageFlags::COLOR_ATTACHMENT,
+        extent: capabilities.extent2d(),
+        transform: capabilities.pre_transformation(),
+        alpha: capabilities.first_alpha_composition().expect("No alpha composition")
+    };
+
+    let swapchain = swapchain::Swapchain::new(&lib, &device, &surface, &swp_type).expect("Failed to create swapchain");
+
+    let vert_shader_type = shader::ShaderCfg {
+        path: "examples/compiled_shaders/single_triangle.spv",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let vert_shader = shader::Shader::from_file(&device, &vert_shader_type).expect("Failed to create vertex shader module");
+
+    let frag_shader_type = shader::ShaderCfg {
+        path: FRAG_SHADER_PATH,
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let mut frag_shader = shader::Shader::from_glsl_file(&device, &frag_shader_type, shader::Kind::Fragment)
+        .expect("Failed to create fragment shader module");
+
+    let render_pass = graphics::RenderPass::single_subpass(&device, surf_format)
+        .expect("Failed to create render pass");
+
+    let descriptor = graphics::PipelineDescriptor::empty(&device);
+
+    let mut pipeline = graphics::Pipeline::new(
+        &device,
+        &pipeline_cfg(&vert_shader, &frag_shader, &render_pass, &descriptor, capabilities.extent2d())
+    ).expect("Failed to create pipeline");
+
+    let mut last_modified = modified();
+
+    let mut frame_sync = sync::FrameSync::new(&device, FRAMES_IN_FLIGHT).expect("Failed to create frame synchronization");
+
+    let cmd_pool_type = cmd::PoolCfg {
+        queue_index: queue.index(),
+    };
+
+    let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+    let images = swapchain.images();
+
+    let frames = memory::Framebuffer::for_swapchain(&device, &render_pass, &images, &[], capabilities.extent2d())
+        .expect("Failed to create framebuffers");
+
+    let mut cmd_buffers: Vec<Option<cmd::ExecutableBuffer>> = (0..FRAMES_IN_FLIGHT).map(|_| None).collect();
+
+    let queue_cfg = queue::QueueCfg {
+        family_index: queue.index(),
+        queue_index: 0
+    };
+
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+    window::run_render_loop(event_loop, &wnd, move |_| {
+        let current_modified = modified();
+
+        if current_modified != last_modified {
+            last_modified = current_modified;
+
+            // Keep the previous pipeline if the new code does not compile
+            match frag_shader.reload_from_file() {
+                Ok(()) => {
+                    // Command buffers in flight keep the previous pipeline alive
+                    match graphics::Pipeline::new(
+                        &device,
+                        &pipeline_cfg(&vert_shader, &frag_shader, &render_pass, &descriptor, capabilities.extent2d())
+                    ) {
+                        Ok(val) => pipeline = val,
+                        Err(err) => println!("{}", err)
+                    }
+
+                    frag_shader.release_retired();
+                },
+                Err(err) => println!("{}", err)
+            }
+        }
+
+        let (image, frame) = frame_sync.acquire(&swapchain).expect("Failed to acquire frame");
+
+        // Fence of the current frame is signaled so its previous buffer may be re-recorded
+        let cmd_buffer = match cmd_buffers[frame_sync.current()].take() {
+            Some(buffer) => buffer.reset().expect("Failed to reset command buffer"),
+            None => cmd_pool.allocate().expect("Failed to allocate command buffer")
+        };
+
+        cmd_buffer.begin_render_pass(&render_pass, &frames[image.index as usize]);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.draw(3, 1, 0, 0);
+        cmd_buffer.end_render_pass();
+
+        let cmd_buffer = &*cmd_buffers[frame_sync.current()]
+            .insert(cmd_buffer.commit().expect("Failed to commit buffer"));
+
+        let exec_info = queue::ExecInfo {
+            buffers: &[cmd_buffer],
+            wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+            timeout: u64::MAX,
+            wait: &[frame.image_available()],
+            signal: &[frame.render_finished()],
+            fence: None,
+        };
+
+        cmd_queue.submit_with_fence(&exec_info, frame.in_flight()).expect("Failed to execute queue");
+
+        let present_info = queue::PresentInfo {
+            swapchain: &swapchain,
+            image_index: image.index,
+            wait: &[frame.render_finished()]
+        };
+
+        cmd_queue.present(&present_info).expect("Failed to present frame");
+
+        frame_sync.advance();
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+
+        window::ControlFlow::Continue
+    }).expect("Failed to run example");
+}
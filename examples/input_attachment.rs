@@ -0,0 +1,329 @@
+use libvktypes::*;
+
+// First subpass: draw triangle into intermediate attachment
+const SCENE_VERT_SHADER: &str = "
+#version 460
+
+vec2 positions[3] = vec2[](
+    vec2(0.0, -0.5),
+    vec2(0.5, 0.5),
+    vec2(-0.5, 0.5)
+);
+
+vec3 colors[3] = vec3[](
+    vec3(1.0, 0.0, 0.0),
+    vec3(0.0, 1.0, 0.0),
+    vec3(0.0, 0.0, 1.0)
+);
+
+layout(location = 0) out vec3 color;
+
+void main() {
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+    color = colors[gl_VertexIndex];
+}
+";
+
+const SCENE_FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) in vec3 in_color;
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(in_color, 1.0);
+}
+";
+
+// Second subpass: read intermediate attachment and invert colors
+const POST_VERT_SHADER: &str = "
+#version 460
+
+void main() {
+    vec2 uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+";
+
+const POST_FRAG_SHADER: &str = "
+#version 460
+
+layout(input_attachment_index = 0, set = 0, binding = 0) uniform subpassInput scene;
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(1.0 - subpassLoad(scene).rgb, 1.0);
+}
+";
+
+fn main() {
+    let event_loop = window::eventloop().expect("Failed to create eventloop");
+
+    let wnd = window::create_window(&event_loop).expect("Failed to create window");
+
+    let mut extensions = extensions::required_extensions(&wnd);
+    extensions.push(extensions::DEBUG_EXT_NAME);
+    extensions.push(extensions::SURFACE_EXT_NAME);
+
+    let lib_type = libvk::InstanceType {
+        debug_layer: Some(layers::DebugLayer::default()),
+        extensions: &extensions,
+        ..libvk::InstanceType::default()
+    };
+
+    let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+    let surface = surface::Surface::new(&lib, &wnd).expect("Failed to create surface");
+
+    let hw_list = hw::Description::poll(&lib, Some(&surface)).expect("Failed to list hardware");
+
+    let (hw_dev, queue, _) = hw_list
+        .find_first(
+            hw::HWDevice::is_dedicated_gpu,
+            |q| q.is_graphics() && q.is_surface_supported(),
+            |_| true
+        )
+        .expect("Failed to find suitable hardware device");
+
+    let dev_type = dev::DeviceCfg {
+        lib: &lib,
+        hw: hw_dev,
+        extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features::default(),
+        dynamic_rendering: false,
+        allocator: None,
+        track_objects: false,
+    };
+
+    let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+    let capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
+
+    assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
+    assert!(capabilities.is_flags_supported(memory::UsageFlags::COLOR_ATTACHMENT));
+
+    let surface_format = capabilities.choose_format(&[
+        (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+        (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+    ]);
+
+    let surf_format = surface_format.format;
+
+    let swp_type = swapchain::SwapchainCfg {
+        num_of_images: capabilities.min_img_count(),
+        format: surf_format,
+        color: surface_format.color_space,
+        present_mode: swapchain::PresentMode::FIFO,
+        flags: memory::UsageFlags::COLOR_ATTACHMENT,
+        extent: capabilities.extent2d(),
+        transform: capabilities.pre_transformation(),
+        alpha: capabilities.first_alpha_composition().expect("No alpha composition")
+    };
+
+    let swapchain = swapchain::Swapchain::new(&lib, &device, &surface, &swp_type).expect("Failed to create swapchain");
+
+    let vert_shader_type = shader::ShaderCfg {
+        path: "SCENE_VERT_DATA",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let vert_shader =
+        shader::Shader::from_glsl(&device, &vert_shader_type, SCENE_VERT_SHADER, shader::Kind::Vertex)
+        .expect("Failed to create vertex shader module");
+
+    let frag_shader_type = shader::ShaderCfg {
+        path: "SCENE_FRAG_DATA",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let frag_shader =
+        shader::Shader::from_glsl(&device, &frag_shader_type, SCENE_FRAG_SHADER, shader::Kind::Fragment)
+        .expect("Failed to create fragment shader module");
+
+    let post_vert_shader_type = shader::ShaderCfg {
+        path: "POST_VERT_DATA",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let post_vert_shader =
+        shader::Shader::from_glsl(&device, &post_vert_shader_type, POST_VERT_SHADER, shader::Kind::Vertex)
+        .expect("Failed to create vertex shader module");
+
+    let post_frag_shader_type = shader::ShaderCfg {
+        path: "POST_FRAG_DATA",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let post_frag_shader =
+        shader::Shader::from_glsl(&device, &post_frag_shader_type, POST_FRAG_SHADER, shader::Kind::Fragment)
+        .expect("Failed to create fragment shader module");
+
+    let scene_format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+    let scene_image_cfg = memory::ImageCfg {
+        queue_families: &[queue.index()],
+        simultaneous_access: false,
+        format: scene_format,
+        extent: capabilities.extent3d(1),
+        usage: memory::ImageUsageFlags::COLOR_ATTACHMENT
+            | memory::ImageUsageFlags::INPUT_ATTACHMENT
+            | memory::ImageUsageFlags::TRANSIENT_ATTACHMENT,
+        layout: memory::ImageLayout::UNDEFINED,
+        aspect: memory::ImageAspect::COLOR,
+        tiling: memory::Tiling::OPTIMAL,
+        samples: memory::SampleCount::TYPE_1,
+        mip_levels: 1,
+        kind: memory::ImageKind::D2,
+        array_layers: 1,
+        count: 1
+    };
+
+    let alloc_info = memory::ImagesAllocationInfo {
+        properties: hw::MemoryProperty::DEVICE_LOCAL,
+        filter: &hw::any,
+        image_cfgs: &[scene_image_cfg]
+    };
+
+    let scene_image = memory::ImageMemory::allocate(&device, &alloc_info).expect("Failed to allocate scene image");
+
+    // Attachment 0 is swapchain image, attachment 1 is intermediate scene image
+    let render_pass = graphics::RenderPassBuilder::new()
+        .color_attachment(surf_format, graphics::AttachmentLoadOp::CLEAR,
+            graphics::AttachmentStoreOp::STORE, memory::ImageLayout::PRESENT_SRC_KHR)
+        .color_attachment(scene_format, graphics::AttachmentLoadOp::CLEAR,
+            graphics::AttachmentStoreOp::DONT_CARE, memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+        .subpass(&[1], None, &[])
+        .subpass(&[0], None, &[1])
+        .build(&device)
+        .expect("Failed to create render pass");
+
+    let descriptor = graphics::PipelineDescriptor::empty(&device);
+
+    let scene_pipe_type = graphics::PipelineCfg {
+        extent: capabilities.extent2d(),
+        render_pass: Some(&render_pass),
+        ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+    };
+
+    let scene_pipeline = graphics::Pipeline::new(&device, &scene_pipe_type).expect("Failed to create pipeline");
+
+    let post_descriptor = graphics::PipelineDescriptor::allocate(&device, &[&[
+        graphics::BindingCfg {
+            resource_type: graphics::DescriptorType::INPUT_ATTACHMENT,
+            stage: graphics::ShaderStage::FRAGMENT,
+            count: 1,
+        }
+    ]]).expect("Failed to allocate resources");
+
+    post_descriptor.update(&[graphics::UpdateInfo {
+        set: 0,
+        binding: 0,
+        starting_array_element: 0,
+        resources: graphics::ShaderBinding::InputAttachments(&[
+            graphics::InputAttachmentBinding::new(scene_image.view(0))
+        ]),
+    }]).expect("Failed to update descriptor");
+
+    let post_pipe_type = graphics::PipelineCfg {
+        vertex_shader: &post_vert_shader,
+        frag_shader: &post_frag_shader,
+        subpass_index: 1,
+        descriptor: &post_descriptor,
+        ..scene_pipe_type
+    };
+
+    let post_pipeline = graphics::Pipeline::new(&device, &post_pipe_type).expect("Failed to create pipeline");
+
+    let img_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+    let render_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+
+    let cmd_pool_type = cmd::PoolCfg {
+        queue_index: queue.index(),
+    };
+
+    let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+    let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command pool");
+
+    let images = swapchain.images();
+
+    let img_index = swapchain
+        .next_image(std::time::Duration::MAX, swapchain::AcquireSync::Semaphore(&img_sem))
+        .expect("Failed to get image index")
+        .index;
+
+    let frames_cfg = memory::FramebufferCfg {
+        render_pass: &render_pass,
+        images: &[images[img_index as usize].view(0), scene_image.view(0)],
+        extent: capabilities.extent2d(),
+    };
+
+    let frame = memory::Framebuffer::new(&device, &frames_cfg).expect("Failed to create framebuffers");
+
+    cmd_buffer.begin_render_pass_with_clear(&render_pass, &frame, &[
+        cmd::ClearValue::Color([0.0, 0.0, 0.0, 1.0]),
+        cmd::ClearValue::Color([0.0, 0.0, 0.0, 1.0])
+    ]);
+
+    cmd_buffer.bind_graphics_pipeline(&scene_pipeline);
+
+    cmd_buffer.draw(3, 1, 0, 0);
+
+    cmd_buffer.next_subpass();
+
+    cmd_buffer.bind_graphics_pipeline(&post_pipeline);
+
+    cmd_buffer.bind_resources(&post_pipeline, &post_descriptor, &[]);
+
+    cmd_buffer.draw(3, 1, 0, 0);
+
+    cmd_buffer.end_render_pass();
+
+    let exec_buffer = cmd_buffer.commit().expect("Failed to commit buffer");
+
+    let queue_cfg = queue::QueueCfg {
+        family_index: queue.index(),
+        queue_index: 0
+    };
+
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+    let exec_info = queue::ExecInfo {
+        buffers: &[&exec_buffer],
+        wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        timeout: u64::MAX,
+        wait: &[&img_sem],
+        signal: &[&render_sem],
+        fence: None,
+    };
+
+    cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+    let present_info = queue::PresentInfo {
+        swapchain: &swapchain,
+        image_index: img_index,
+        wait: &[&render_sem]
+    };
+
+    cmd_queue.present(&present_info).expect("Failed to present frame");
+
+    event_loop.run(move |event, control_flow| {
+        match event {
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => {
+                control_flow.exit();
+            },
+            _ => ()
+        }
+
+    }).expect("Failed to run example");
+}
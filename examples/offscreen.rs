@@ -0,0 +1,147 @@
+//! Render triangle without window, surface and swapchain and save result into `offscreen.ppm`
+use libvktypes::*;
+
+use std::fs;
+use std::io::Write;
+
+fn main() {
+    let lib_type = libvk::InstanceType {
+        debug_layer: Some(layers::DebugLayer::default()),
+        extensions: &[extensions::DEBUG_EXT_NAME],
+        ..libvk::InstanceType::default()
+    };
+
+    let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+    let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+    let (hw_dev, queue, _) = hw_list
+        .find_first(
+            hw::HWDevice::is_dedicated_gpu,
+            hw::QueueFamilyDescription::is_graphics,
+            |_| true
+        )
+        .expect("Failed to find suitable hardware device");
+
+    // Swapchain extension is not required
+    let dev_type = dev::DeviceCfg {
+        lib: &lib,
+        hw: hw_dev,
+        extensions: &[],
+        queues: &[],
+        features: hw::Features::default(),
+        dynamic_rendering: false,
+        allocator: None,
+        track_objects: false,
+    };
+
+    let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+    let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+    let extent = memory::Extent2D { width: 640, height: 480 };
+
+    let image = memory::ImageMemory::offscreen(&device, format, extent).expect("Failed to allocate image");
+
+    let readback_cfg = memory::BufferCfg {
+        size: (extent.width*extent.height) as u64*formats::block_size(format),
+        usage: memory::BufferUsageFlags::TRANSFER_DST,
+        queue_families: &[queue.index()],
+        simultaneous_access: false,
+        count: 1
+    };
+
+    let mem_cfg = memory::MemoryCfg {
+        properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+        filter: &hw::any,
+        buffers: &[&readback_cfg]
+    };
+
+    let readback = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+    let vert_shader_type = shader::ShaderCfg {
+        path: "examples/compiled_shaders/single_triangle.spv",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let vert_shader = shader::Shader::from_file(&device, &vert_shader_type).expect("Failed to create vertex shader module");
+
+    let frag_shader_type = shader::ShaderCfg {
+        path: "examples/compiled_shaders/single_color.spv",
+        entry: "main",
+        include_dirs: &[],
+    };
+
+    let frag_shader = shader::Shader::from_file(&device, &frag_shader_type).expect("Failed to create fragment shader module");
+
+    let render_pass = graphics::RenderPass::offscreen(&device, format).expect("Failed to create render pass");
+
+    let frame_cfg = memory::FramebufferCfg {
+        render_pass: &render_pass,
+        images: &[image.view(0)],
+        extent,
+    };
+
+    let frame = memory::Framebuffer::new(&device, &frame_cfg).expect("Failed to create framebuffer");
+
+    let descriptor = graphics::PipelineDescriptor::empty(&device);
+
+    let pipe_type = graphics::PipelineCfg {
+        extent,
+        render_pass: Some(&render_pass),
+        ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+    };
+
+    let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+    let cmd_pool_type = cmd::PoolCfg {
+        queue_index: queue.index(),
+    };
+
+    let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+    let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+    cmd_buffer.begin_render_pass_with_clear(&render_pass, &frame, &[cmd::ClearValue::Color([0.0, 0.0, 0.2, 1.0])]);
+    cmd_buffer.bind_graphics_pipeline(&pipeline);
+    cmd_buffer.draw(3, 1, 0, 0);
+    cmd_buffer.end_render_pass();
+
+    // Render pass moves image into TRANSFER_SRC_OPTIMAL layout but does not update tracked layout
+    image.view(0).set_layout(memory::ImageLayout::TRANSFER_SRC_OPTIMAL);
+
+    cmd_buffer.copy_image_to_buffer(image.view(0), readback.view(0));
+
+    let exec_buffer = cmd_buffer.commit().expect("Failed to commit buffer");
+
+    let queue_cfg = queue::QueueCfg {
+        family_index: queue.index(),
+        queue_index: 0
+    };
+
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+    let exec_info = queue::ExecInfo {
+        buffers: &[&exec_buffer],
+        wait_stage: cmd::PipelineStage::TRANSFER,
+        timeout: u64::MAX,
+        wait: &[],
+        signal: &[],
+        fence: None,
+    };
+
+    cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+    let mut file = fs::File::create("offscreen.ppm").expect("Failed to create file");
+
+    readback.access(&mut |pixels: &mut [[u8; 4]]| {
+        write!(file, "P6\n{} {}\n255\n", extent.width, extent.height).expect("Failed to write header");
+
+        let rgb: Vec<u8> = pixels.iter().flat_map(|x| [x[0], x[1], x[2]]).collect();
+
+        file.write_all(&rgb).expect("Failed to write pixels");
+    }, 0).expect("Failed to read rendered image");
+
+    println!("Image is saved into offscreen.ppm");
+}
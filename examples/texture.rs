@@ -99,7 +99,11 @@ fn main() {
         lib: &lib,
         hw: hw_dev,
         extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features::default(),
+        dynamic_rendering: false,
         allocator: None,
+        track_objects: false,
     };
 
     let device = dev::Device::new(&dev_type).expect("Failed to create device");
@@ -110,19 +114,29 @@ fn main() {
 
     let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
 
-    let copy_cmd_queue = cmd_pool.allocate().expect("Failed to allocate command pool");
+    let queue_cfg = queue::QueueCfg {
+        family_index: queue.index(),
+        queue_index: 0
+    };
+
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
 
     let capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
 
     assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
     assert!(capabilities.is_flags_supported(memory::UsageFlags::COLOR_ATTACHMENT));
 
-    let surf_format = capabilities.formats().next().expect("No available formats").format;
+    let surface_format = capabilities.choose_format(&[
+        (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+        (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+    ]);
+
+    let surf_format = surface_format.format;
 
     let swp_type = swapchain::SwapchainCfg {
         num_of_images: capabilities.min_img_count(),
         format: surf_format,
-        color: memory::ColorSpace::SRGB_NONLINEAR,
+        color: surface_format.color_space,
         present_mode: swapchain::PresentMode::FIFO,
         flags: memory::UsageFlags::COLOR_ATTACHMENT,
         extent: capabilities.extent2d(),
@@ -135,6 +149,7 @@ fn main() {
     let vert_shader_type = shader::ShaderCfg {
         path: "VERT_DATA",
         entry: "main",
+        include_dirs: &[],
     };
 
     let vert_shader =
@@ -144,6 +159,7 @@ fn main() {
     let frag_shader_type = shader::ShaderCfg {
         path: "FRAG_DATA",
         entry: "main",
+        include_dirs: &[],
     };
 
     let frag_shader =
@@ -167,13 +183,6 @@ fn main() {
                 queue_families: &[queue.index()],
                 simultaneous_access: false,
                 count: 1
-            },
-            &memory::BufferCfg {
-                size: (TEXTURE_SIZE*size_of::<u32>()) as u64,
-                usage: memory::BufferUsageFlags::TRANSFER_SRC,
-                queue_families: &[queue.index()],
-                simultaneous_access: false,
-                count: 1
             }
         ]
     };
@@ -184,15 +193,9 @@ fn main() {
         bytes.clone_from_slice(VERTEX_DATA);
     }).expect("Failed to fill vertex buffer");
 
-    host_data.view(1).access(&mut |bytes: &mut [u32]| {
-        bytes.clone_from_slice(INDICES);
-    }).expect("Failed to fill index buffer");
-
-    let image_stage_buffer = host_data.view(2);
+    host_data.write_indices_u32(1, INDICES).expect("Failed to fill index buffer");
 
-    image_stage_buffer.access(&mut |bytes: &mut [u32]| {
-        bytes.clone_from_slice(&TEXTURE_DATA);
-    }).expect("Failed to fill index buffer");
+    let texture_extent = memory::Extent3D {width: TEXTURE_WIDTH, height: TEXTURE_HEIGHT, depth: 1};
 
     let texture_mem_cfg = memory::ImagesAllocationInfo {
         properties: hw::MemoryProperty::DEVICE_LOCAL,
@@ -202,11 +205,17 @@ fn main() {
                 queue_families: &[queue.index()],
                 simultaneous_access: false,
                 format: memory::ImageFormat::R8G8B8A8_SRGB,
-                extent: memory::Extent3D {width: TEXTURE_WIDTH, height: TEXTURE_HEIGHT, depth: 1},
-                usage:  memory::ImageUsageFlags::SAMPLED | memory::ImageUsageFlags::TRANSFER_DST,
+                extent: texture_extent,
+                usage:  memory::ImageUsageFlags::SAMPLED
+                    | memory::ImageUsageFlags::TRANSFER_SRC
+                    | memory::ImageUsageFlags::TRANSFER_DST,
                 layout: memory::ImageLayout::UNDEFINED,
                 aspect: memory::ImageAspect::COLOR,
                 tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: memory::max_mip_levels(texture_extent),
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
                 count: 1
             }
         ]
@@ -217,48 +226,12 @@ fn main() {
 
     let texture = texture_memory.view(0);
 
-    copy_cmd_queue.set_image_barrier(
-        texture,
-        cmd::AccessType::NONE,
-        cmd::AccessType::TRANSFER_WRITE,
-        memory::ImageLayout::UNDEFINED,
-        memory::ImageLayout::TRANSFER_DST_OPTIMAL,
-        graphics::PipelineStage::BOTTOM_OF_PIPE,
-        graphics::PipelineStage::TRANSFER,
-        cmd::QUEUE_FAMILY_IGNORED,
-        cmd::QUEUE_FAMILY_IGNORED
-    );
-
-    copy_cmd_queue.copy_buffer_to_image(image_stage_buffer, texture);
-
-    copy_cmd_queue.set_image_barrier(
-        texture,
-        cmd::AccessType::TRANSFER_WRITE,
-        cmd::AccessType::SHADER_READ,
-        memory::ImageLayout::TRANSFER_DST_OPTIMAL,
-        memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
-        graphics::PipelineStage::TRANSFER,
-        graphics::PipelineStage::FRAGMENT_SHADER,
-        cmd::QUEUE_FAMILY_IGNORED,
-        cmd::QUEUE_FAMILY_IGNORED
-    );
+    let texels: Vec<u8> = TEXTURE_DATA.iter().flat_map(|texel| texel.to_ne_bytes()).collect();
 
-    let queue_cfg = queue::QueueCfg {
-        family_index: queue.index(),
-        queue_index: 0
-    };
-
-    let cmd_queue = queue::Queue::new(&device, &queue_cfg);
-
-    let copy_exec_info = queue::ExecInfo {
-        buffer: &copy_cmd_queue.commit().expect("Failed to commit buffer"),
-        wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
-        timeout: u64::MAX,
-        wait: &[],
-        signal: &[],
-    };
-
-    cmd_queue.exec(&copy_exec_info).expect("Failed to copy texture");
+    // Mip chain is generated with the draw commands
+    memory::StagingBuffer::new(&device, &cmd_queue, &cmd_pool)
+        .upload_image(texture, &texels, memory::ImageLayout::TRANSFER_DST_OPTIMAL)
+        .expect("Failed to upload texture");
 
     let render_pass = graphics::RenderPass::single_subpass(&device, surf_format)
         .expect("Failed to create render pass");
@@ -287,20 +260,12 @@ fn main() {
     ];
 
     let pipe_type = graphics::PipelineCfg {
-        vertex_shader: &vert_shader,
         vertex_size: size_of::<[f32; 6]>() as u32,
         vert_input: &vert_input,
-        frag_shader: &frag_shader,
-        geom_shader: None,
-        topology: graphics::Topology::TRIANGLE_LIST,
         extent: capabilities.extent2d(),
-        push_constant_size: 0,
-        render_pass: &render_pass,
-        subpass_index: 0,
-        enable_depth_test: false,
-        enable_primitive_restart: false,
+        render_pass: Some(&render_pass),
         cull_mode: graphics::CullMode::BACK,
-        descriptor: &descs
+        ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descs)
     };
 
     let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
@@ -308,6 +273,8 @@ fn main() {
     let sampler_cfg = graphics::SamplerCfg {
         address_mode_u: graphics::SamplerAddressMode::MIRRORED_REPEAT,
         address_mode_v: graphics::SamplerAddressMode::MIRRORED_REPEAT,
+        mipmap_mode: graphics::SamplerMipmapMode::LINEAR,
+        max_lod: texture.mip_levels() as f32,
         ..Default::default()
     };
 
@@ -318,16 +285,19 @@ fn main() {
         binding: 0,
         starting_array_element: 0,
         resources: graphics::ShaderBinding::Samplers(&[(&sampler, texture, memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL)]),
-    }]);
+    }]).expect("Failed to update descriptor");
 
     let img_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
     let render_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
 
     let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command pool");
 
-    let images = swapchain.images().expect("Failed to get images");
+    let images = swapchain.images();
 
-    let img_index = swapchain.next_image(u64::MAX, Some(&img_sem), None).expect("Failed to get image index");
+    let img_index = swapchain
+        .next_image(std::time::Duration::MAX, swapchain::AcquireSync::Semaphore(&img_sem))
+        .expect("Failed to get image index")
+        .index;
 
     let frames_cfg = memory::FramebufferCfg {
         render_pass: &render_pass,
@@ -337,13 +307,15 @@ fn main() {
 
     let frame = memory::Framebuffer::new(&device, &frames_cfg).expect("Failed to create framebuffers");
 
+    cmd_buffer.blit_mip_chain(texture);
+
     cmd_buffer.begin_render_pass(&render_pass, &frame);
 
     cmd_buffer.bind_graphics_pipeline(&pipeline);
 
-    cmd_buffer.bind_vertex_buffers(&[host_data.vertex_view(0, 0), host_data.vertex_view(0, size_of::<[f32; 4]>() as u32)]);
+    cmd_buffer.bind_vertex_buffers(&[host_data.vertex_view(0, 0).expect("Failed to create vertex view")]);
 
-    cmd_buffer.bind_index_buffer(host_data.view(1), 0, memory::IndexBufferType::UINT32);
+    cmd_buffer.bind_indices(host_data.index_view(1, memory::IndexBufferType::UINT32).expect("Failed to create index view"));
 
     cmd_buffer.bind_resources(&pipeline, &descs, &[]);
 
@@ -354,11 +326,12 @@ fn main() {
     let exec_buffer = cmd_buffer.commit().expect("Failed to commit buffer");
 
     let exec_info = queue::ExecInfo {
-        buffer: &exec_buffer,
+        buffers: &[&exec_buffer],
         wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
         timeout: u64::MAX,
         wait: &[&img_sem],
         signal: &[&render_sem],
+        fence: None,
     };
 
     cmd_queue.exec(&exec_info).expect("Failed to execute queue");
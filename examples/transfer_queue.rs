@@ -0,0 +1,280 @@
+use libvktypes::*;
+
+const TEXTURE_WIDTH: u32  = 4;
+const TEXTURE_HEIGHT: u32 = 4;
+
+const TEXTURE_SIZE: usize = (TEXTURE_WIDTH*TEXTURE_HEIGHT) as usize;
+
+fn main() {
+    let event_loop = window::eventloop().expect("Failed to create eventloop");
+
+    let wnd = window::create_window(&event_loop).expect("Failed to create window");
+
+    let mut extensions = extensions::required_extensions(&wnd);
+    extensions.push(extensions::DEBUG_EXT_NAME);
+    extensions.push(extensions::SURFACE_EXT_NAME);
+
+    let lib_type = libvk::InstanceType {
+        debug_layer: Some(layers::DebugLayer::default()),
+        extensions: &extensions,
+        ..libvk::InstanceType::default()
+    };
+
+    let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+    let surface = surface::Surface::new(&lib, &wnd).expect("Failed to create surface");
+
+    let hw_list = hw::Description::poll(&lib, Some(&surface)).expect("Failed to list hardware");
+
+    let is_transfer_only = |q: &hw::QueueFamilyDescription| q.is_transfer() && !q.is_graphics() && !q.is_compute();
+
+    let (hw_dev, graphics_family, _) = hw_list
+        .find_first(
+            |hw| hw.is_dedicated_gpu() && hw.queues().any(is_transfer_only),
+            |q| q.is_graphics() && q.is_surface_supported(),
+            |_| true
+        )
+        .expect("Failed to find hardware device with dedicated transfer queue");
+
+    let transfer_family = hw_dev.queues().find(|q| is_transfer_only(q)).expect("Failed to find transfer queue");
+
+    let dev_type = dev::DeviceCfg {
+        lib: &lib,
+        hw: hw_dev,
+        extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[
+            dev::QueueRequest::single(graphics_family.index()),
+            dev::QueueRequest::single(transfer_family.index())
+        ],
+        features: hw::Features::default(),
+        dynamic_rendering: false,
+        allocator: None,
+        track_objects: false,
+    };
+
+    let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+    let graphics_queue = device.get_queue(&queue::QueueCfg {
+        family_index: graphics_family.index(),
+        queue_index: 0
+    }).expect("Failed to get graphics queue");
+
+    let transfer_queue = device.get_queue(&queue::QueueCfg {
+        family_index: transfer_family.index(),
+        queue_index: 0
+    }).expect("Failed to get transfer queue");
+
+    let capabilities = surface::Capabilities::get(&hw_dev, &surface).expect("Failed to get capabilities");
+
+    assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
+    assert!(capabilities.is_flags_supported(memory::UsageFlags::TRANSFER_DST));
+
+    let surface_format = capabilities.choose_format(&[
+        (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+        (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+    ]);
+
+    let surf_format = surface_format.format;
+
+    let swp_type = swapchain::SwapchainCfg {
+        num_of_images: capabilities.min_img_count(),
+        format: surf_format,
+        color: surface_format.color_space,
+        present_mode: swapchain::PresentMode::FIFO,
+        flags: memory::UsageFlags::TRANSFER_DST,
+        extent: capabilities.extent2d(),
+        transform: capabilities.pre_transformation(),
+        alpha: capabilities.first_alpha_composition().expect("No alpha composition")
+    };
+
+    let swapchain = swapchain::Swapchain::new(&lib, &device, &surface, &swp_type).expect("Failed to create swapchain");
+
+    let mem_cfg = memory::MemoryCfg {
+        properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+        filter: &hw::any,
+        buffers: &[
+            &memory::BufferCfg {
+                size: (TEXTURE_SIZE*std::mem::size_of::<u32>()) as u64,
+                usage: memory::BufferUsageFlags::TRANSFER_SRC,
+                queue_families: &[transfer_family.index()],
+                simultaneous_access: false,
+                count: 1
+            }
+        ]
+    };
+
+    let staging = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+    // Checkerboard
+    staging.view(0).access(&mut |texels: &mut [u32]| {
+        for (i, texel) in texels.iter_mut().enumerate() {
+            let (x, y) = (i as u32 % TEXTURE_WIDTH, i as u32 / TEXTURE_WIDTH);
+
+            *texel = if (x + y) % 2 == 0 { 0xFFFFFFFF } else { 0xFF000000 };
+        }
+    }).expect("Failed to fill staging buffer");
+
+    let texture_mem_cfg = memory::ImagesAllocationInfo {
+        properties: hw::MemoryProperty::DEVICE_LOCAL,
+        filter: &hw::any,
+        image_cfgs: &[
+            memory::ImageCfg {
+                queue_families: &[transfer_family.index()],
+                simultaneous_access: false,
+                format: memory::ImageFormat::R8G8B8A8_UNORM,
+                extent: memory::Extent3D { width: TEXTURE_WIDTH, height: TEXTURE_HEIGHT, depth: 1 },
+                usage: memory::ImageUsageFlags::TRANSFER_SRC | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ]
+    };
+
+    let texture_memory =
+        memory::ImageMemory::allocate(&device, &texture_mem_cfg).expect("Failed to allocate texture memory");
+
+    let texture = texture_memory.view(0);
+
+    // Upload on transfer family
+    let transfer_pool = cmd::Pool::new(&device, &cmd::PoolCfg { queue_index: transfer_family.index() })
+        .expect("Failed to allocate transfer command pool");
+
+    let upload_cmd = transfer_pool.allocate().expect("Failed to allocate command buffer");
+
+    upload_cmd.set_image_barrier(
+        texture,
+        cmd::AccessType::NONE,
+        cmd::AccessType::TRANSFER_WRITE,
+        memory::ImageLayout::UNDEFINED,
+        memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+        cmd::PipelineStage::TOP_OF_PIPE,
+        cmd::PipelineStage::TRANSFER,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED
+    );
+
+    upload_cmd.copy_buffer_to_image(staging.view(0), texture);
+
+    upload_cmd.set_image_barrier(
+        texture,
+        cmd::AccessType::TRANSFER_WRITE,
+        cmd::AccessType::TRANSFER_READ,
+        memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+        memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+        cmd::PipelineStage::TRANSFER,
+        cmd::PipelineStage::TRANSFER,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED
+    );
+
+    upload_cmd.release_ownership(
+        texture,
+        transfer_family.index(),
+        graphics_family.index(),
+        cmd::PipelineStage::TRANSFER,
+        cmd::AccessType::TRANSFER_WRITE
+    );
+
+    let upload_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+
+    let upload_exec = upload_cmd.commit().expect("Failed to commit buffer");
+
+    transfer_queue.exec(&queue::ExecInfo {
+        buffers: &[&upload_exec],
+        wait_stage: cmd::PipelineStage::TRANSFER,
+        timeout: u64::MAX,
+        wait: &[],
+        signal: &[&upload_sem],
+        fence: None,
+    }).expect("Failed to execute transfer queue");
+
+    // Draw on graphics family
+    let images = swapchain.images();
+
+    let img_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+    let render_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+
+    let img_index = swapchain
+        .next_image(std::time::Duration::MAX, swapchain::AcquireSync::Semaphore(&img_sem))
+        .expect("Failed to get image index")
+        .index;
+
+    let target = images[img_index as usize].view(0);
+
+    let graphics_pool = cmd::Pool::new(&device, &cmd::PoolCfg { queue_index: graphics_family.index() })
+        .expect("Failed to allocate graphics command pool");
+
+    let draw_cmd = graphics_pool.allocate().expect("Failed to allocate command buffer");
+
+    draw_cmd.acquire_ownership(
+        texture,
+        transfer_family.index(),
+        graphics_family.index(),
+        cmd::PipelineStage::TRANSFER,
+        cmd::AccessType::TRANSFER_READ
+    );
+
+    draw_cmd.set_image_barrier(
+        target,
+        cmd::AccessType::NONE,
+        cmd::AccessType::TRANSFER_WRITE,
+        memory::ImageLayout::UNDEFINED,
+        memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+        cmd::PipelineStage::TRANSFER,
+        cmd::PipelineStage::TRANSFER,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED
+    );
+
+    draw_cmd.blit_image(texture, target, cmd::Filter::NEAREST);
+
+    draw_cmd.set_image_barrier(
+        target,
+        cmd::AccessType::TRANSFER_WRITE,
+        cmd::AccessType::NONE,
+        memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+        memory::ImageLayout::PRESENT_SRC_KHR,
+        cmd::PipelineStage::TRANSFER,
+        cmd::PipelineStage::BOTTOM_OF_PIPE,
+        cmd::QUEUE_FAMILY_IGNORED,
+        cmd::QUEUE_FAMILY_IGNORED
+    );
+
+    let draw_exec = draw_cmd.commit().expect("Failed to commit buffer");
+
+    graphics_queue.exec(&queue::ExecInfo {
+        buffers: &[&draw_exec],
+        wait_stage: cmd::PipelineStage::TRANSFER,
+        timeout: u64::MAX,
+        wait: &[&upload_sem, &img_sem],
+        signal: &[&render_sem],
+        fence: None,
+    }).expect("Failed to execute graphics queue");
+
+    let present_info = queue::PresentInfo {
+        swapchain: &swapchain,
+        image_index: img_index,
+        wait: &[&render_sem]
+    };
+
+    graphics_queue.present(&present_info).expect("Failed to present frame");
+
+    event_loop.run(move |event, control_flow| {
+        match event {
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => {
+                control_flow.exit();
+            },
+            _ => ()
+        }
+
+    }).expect("Failed to run example");
+}
@@ -0,0 +1,174 @@
+//! Two windows are rendered by the same device and presented with single call
+use libvktypes::*;
+
+fn main() {
+    let event_loop = window::eventloop().expect("Failed to create eventloop");
+
+    let main_wnd = window::create_window(&event_loop).expect("Failed to create window");
+    let palette_wnd = window::create_window(&event_loop).expect("Failed to create window");
+
+    let mut extensions = extensions::required_extensions(&main_wnd);
+    extensions.push(extensions::DEBUG_EXT_NAME);
+    extensions.push(extensions::SURFACE_EXT_NAME);
+
+    let lib_type = libvk::InstanceType {
+        debug_layer: Some(layers::DebugLayer::default()),
+        extensions: &extensions,
+        ..libvk::InstanceType::default()
+    };
+
+    let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+    let surfaces = [
+        surface::Surface::new(&lib, &main_wnd).expect("Failed to create surface"),
+        surface::Surface::new(&lib, &palette_wnd).expect("Failed to create surface")
+    ];
+
+    let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+    // Queue must be able to present into both windows
+    let (hw_dev, queue, _) = hw_list
+        .find_first_with_surfaces(
+            hw::HWDevice::is_dedicated_gpu,
+            hw::QueueFamilyDescription::is_graphics,
+            hw::any,
+            &[&surfaces[0], &surfaces[1]]
+        )
+        .expect("Failed to find suitable hardware device");
+
+    let dev_type = dev::DeviceCfg {
+        lib: &lib,
+        hw: hw_dev,
+        extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features::default(),
+        dynamic_rendering: false,
+        allocator: None,
+        track_objects: false,
+    };
+
+    let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+    let capabilities: Vec<surface::Capabilities> = surfaces
+        .iter()
+        .map(|surface| surface::Capabilities::get(hw_dev, surface).expect("Failed to get capabilities"))
+        .collect();
+
+    let formats: Vec<surface::SurfaceFormat> = capabilities
+        .iter()
+        .map(|cap| cap.choose_format(&[
+            (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+            (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+        ]))
+        .collect();
+
+    // Both swapchains are created from the same device
+    let swapchains: Vec<swapchain::Swapchain> = (0..surfaces.len())
+        .map(|i| {
+            let swp_type = swapchain::SwapchainCfg {
+                num_of_images: capabilities[i].min_img_count(),
+                format: formats[i].format,
+                color: formats[i].color_space,
+                present_mode: swapchain::PresentMode::FIFO,
+                flags: memory::UsageFlags::COLOR_ATTACHMENT,
+                extent: capabilities[i].extent2d(),
+                transform: capabilities[i].pre_transformation(),
+                alpha: capabilities[i].first_alpha_composition().expect("No alpha composition")
+            };
+
+            swapchain::Swapchain::new(&lib, &device, &surfaces[i], &swp_type).expect("Failed to create swapchain")
+        })
+        .collect();
+
+    let render_passes: Vec<graphics::RenderPass> = formats
+        .iter()
+        .map(|format| graphics::RenderPass::single_subpass(&device, format.format).expect("Failed to create render pass"))
+        .collect();
+
+    let img_sems = [
+        sync::Semaphore::new(&device).expect("Failed to create semaphore"),
+        sync::Semaphore::new(&device).expect("Failed to create semaphore")
+    ];
+
+    let render_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+
+    let cmd_pool_type = cmd::PoolCfg {
+        queue_index: queue.index(),
+    };
+
+    let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+    let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+    let colors = [[0.8, 0.2, 0.2, 1.0], [0.2, 0.2, 0.8, 1.0]];
+
+    let mut image_indices = Vec::new();
+
+    // Keep framebuffers alive until execution is finished
+    let mut framebuffers = Vec::new();
+
+    for i in 0..surfaces.len() {
+        let img_index = swapchains[i]
+            .next_image(std::time::Duration::MAX, swapchain::AcquireSync::Semaphore(&img_sems[i]))
+            .expect("Failed to get image index")
+            .index;
+
+        let images = swapchains[i].images();
+
+        let frames_cfg = memory::FramebufferCfg {
+            render_pass: &render_passes[i],
+            images: &[images[img_index as usize].view(0)],
+            extent: capabilities[i].extent2d(),
+        };
+
+        let frame = memory::Framebuffer::new(&device, &frames_cfg).expect("Failed to create framebuffer");
+
+        cmd_buffer.begin_render_pass_with_clear(&render_passes[i], &frame, &[cmd::ClearValue::Color(colors[i])]);
+        cmd_buffer.end_render_pass();
+
+        image_indices.push(img_index);
+        framebuffers.push(frame);
+    }
+
+    let exec_buffer = cmd_buffer.commit().expect("Failed to commit buffer");
+
+    let queue_cfg = queue::QueueCfg {
+        family_index: queue.index(),
+        queue_index: 0
+    };
+
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+    let exec_info = queue::ExecInfo {
+        buffers: &[&exec_buffer],
+        wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        timeout: u64::MAX,
+        wait: &[&img_sems[0], &img_sems[1]],
+        signal: &[&render_sem],
+        fence: None,
+    };
+
+    cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+    let present_info = queue::MultiPresentInfo {
+        images: &[(&swapchains[0], image_indices[0]), (&swapchains[1], image_indices[1])],
+        wait: &[&render_sem]
+    };
+
+    for result in cmd_queue.present_multiple(&present_info) {
+        result.expect("Failed to present frame");
+    }
+
+    event_loop.run(move |event, control_flow| {
+        match event {
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => {
+                control_flow.exit();
+            },
+            _ => ()
+        }
+
+    }).expect("Failed to run example");
+}
@@ -72,7 +72,11 @@ fn main() {
         lib: &lib,
         hw: hw_dev,
         extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+        queues: &[],
+        features: hw::Features::default(),
+        dynamic_rendering: false,
         allocator: None,
+        track_objects: false,
     };
 
     let device = dev::Device::new(&dev_type).expect("Failed to create device");
@@ -82,12 +86,17 @@ fn main() {
     assert!(capabilities.is_mode_supported(swapchain::PresentMode::FIFO));
     assert!(capabilities.is_flags_supported(memory::UsageFlags::COLOR_ATTACHMENT));
 
-    let surf_format = capabilities.formats().next().expect("No available formats").format;
+    let surface_format = capabilities.choose_format(&[
+        (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+        (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+    ]);
+
+    let surf_format = surface_format.format;
 
     let swp_type = swapchain::SwapchainCfg {
         num_of_images: capabilities.min_img_count(),
         format: surf_format,
-        color: memory::ColorSpace::SRGB_NONLINEAR,
+        color: surface_format.color_space,
         present_mode: swapchain::PresentMode::FIFO,
         flags: memory::UsageFlags::COLOR_ATTACHMENT,
         extent: capabilities.extent2d(),
@@ -100,6 +109,7 @@ fn main() {
     let vert_shader_type = shader::ShaderCfg {
         path: "VERT_DATA",
         entry: "main",
+        include_dirs: &[],
     };
 
     let vert_shader =
@@ -109,6 +119,7 @@ fn main() {
     let frag_shader_type = shader::ShaderCfg {
         path: "FRAG_DATA",
         entry: "main",
+        include_dirs: &[],
     };
 
     let frag_shader =
@@ -178,7 +189,6 @@ fn main() {
     ]]).expect("Failed to allocate resources");
 
     let pipe_type = graphics::PipelineCfg {
-        vertex_shader: &vert_shader,
         vertex_size: std::mem::size_of::<[f32; 4]>() as u32,
         vert_input: &[graphics::VertexInputCfg {
             location: 0,
@@ -186,17 +196,11 @@ fn main() {
             format: memory::ImageFormat::R32G32B32A32_SFLOAT,
             offset: 0,
         }],
-        frag_shader: &frag_shader,
-        geom_shader: None,
         topology: graphics::Topology::TRIANGLE_STRIP,
         extent: capabilities.extent2d(),
-        push_constant_size: 0,
-        render_pass: &render_pass,
-        subpass_index: 0,
-        enable_depth_test: false,
-        enable_primitive_restart: false,
+        render_pass: Some(&render_pass),
         cull_mode: graphics::CullMode::BACK,
-        descriptor: &descs
+        ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descs)
     };
 
     let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
@@ -206,7 +210,7 @@ fn main() {
         binding: 0,
         starting_array_element: 0,
         resources: graphics::ShaderBinding::Buffers(&[graphics::BufferBinding::new(data.view(1)), graphics::BufferBinding::new(data.view(2))]),
-    }]);
+    }]).expect("Failed to update descriptor");
 
     let img_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
     let render_sem = sync::Semaphore::new(&device).expect("Failed to create semaphore");
@@ -219,9 +223,12 @@ fn main() {
 
     let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command pool");
 
-    let images = swapchain.images().expect("Failed to get images");
+    let images = swapchain.images();
 
-    let img_index = swapchain.next_image(u64::MAX, Some(&img_sem), None).expect("Failed to get image index");
+    let img_index = swapchain
+        .next_image(std::time::Duration::MAX, swapchain::AcquireSync::Semaphore(&img_sem))
+        .expect("Failed to get image index")
+        .index;
 
     let frames_cfg = memory::FramebufferCfg {
         render_pass: &render_pass,
@@ -235,7 +242,7 @@ fn main() {
 
     cmd_buffer.bind_graphics_pipeline(&pipeline);
 
-    cmd_buffer.bind_vertex_buffers(&[data.vertex_view(0, 0)]);
+    cmd_buffer.bind_vertex_buffers(&[data.vertex_view(0, 0).expect("Failed to create vertex view")]);
 
     cmd_buffer.bind_resources(&pipeline, &descs, &[]);
 
@@ -250,14 +257,15 @@ fn main() {
         queue_index: 0
     };
 
-    let cmd_queue = queue::Queue::new(&device, &queue_cfg);
+    let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
 
     let exec_info = queue::ExecInfo {
-        buffer: &exec_buffer,
+        buffers: &[&exec_buffer],
         wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
         timeout: u64::MAX,
         wait: &[&img_sem],
         signal: &[&render_sem],
+        fence: None,
     };
 
     cmd_queue.exec(&exec_info).expect("Failed to execute queue");
@@ -3,5 +3,8 @@
 /// Callback configuration
 ///
 /// For now `Callback` must have static lifetime
+///
+/// Set it with [`InstanceType::allocator`](crate::libvk::InstanceType::allocator)
+/// or [`DeviceCfg::allocator`](crate::dev::DeviceCfg::allocator)
 #[doc = "See more: <https://docs.rs/ash/latest/ash/vk/struct.AllocationCallbacks.html>"]
 pub type Callback = ash::vk::AllocationCallbacks<'static>;
\ No newline at end of file
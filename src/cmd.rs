@@ -2,15 +2,19 @@
 
 use ash::vk;
 
-use crate::{dev, memory, compute, graphics};
+use crate::{dev, memory, compute, graphics, formats, sync};
 
-use crate::on_error_ret;
+use crate::{on_error, data_ptr};
 
 use std::{ptr, cmp};
 use std::iter::Iterator;
-use std::sync::Arc;
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::ops::Deref;
 use std::fmt;
 use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
 
 /// AccessType specifies memory access
 ///
@@ -26,9 +30,316 @@ pub type AccessType = vk::AccessFlags;
 #[doc = "Vulkan documentation <https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VkPipelineStageFlagBits.html>"]
 pub type PipelineStage = vk::PipelineStageFlags;
 
+/// Usage of the command buffer, see [`Pool::allocate_with`]
+///
+/// Use `ONE_TIME_SUBMIT` for buffers which are recorded for single submission
+/// and `SIMULTANEOUS_USE` for buffers which may be pending on several queues at once
+///
+#[doc = "Ash documentation about possible values <https://docs.rs/ash/latest/ash/vk/struct.CommandBufferUsageFlags.html>"]
+///
+#[doc = "Vulkan documentation <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkCommandBufferUsageFlagBits.html>"]
+pub type BufferUsage = vk::CommandBufferUsageFlags;
+
+/// Filter applied by [`blit_image`](Buffer::blit_image)
+///
+#[doc = "Ash documentation about possible values <https://docs.rs/ash/latest/ash/vk/struct.Filter.html>"]
+///
+#[doc = "Vulkan documentation <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkFilter.html>"]
+pub type Filter = vk::Filter;
+
+/// Value used to clear attachment or image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClearValue {
+    /// Color in `[r, g, b, a]` order
+    Color([f32; 4]),
+    DepthStencil {
+        depth: f32,
+        stencil: u32
+    }
+}
+
+impl From<ClearValue> for vk::ClearValue {
+    fn from(value: ClearValue) -> Self {
+        match value {
+            ClearValue::Color(color) => vk::ClearValue {
+                color: vk::ClearColorValue {
+                    float32: color,
+                }
+            },
+            ClearValue::DepthStencil { depth, stencil } => vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue {
+                    depth,
+                    stencil,
+                }
+            }
+        }
+    }
+}
+
+/// Single attachment of [`RenderingInfo`]
+///
+/// Image is used in its [tracked layout](memory::ImageView::layout)
+#[derive(Debug, Clone, Copy)]
+pub struct RenderingAttachment<'a> {
+    pub view: memory::ImageView<'a>,
+    pub load_op: graphics::AttachmentLoadOp,
+    pub store_op: graphics::AttachmentStoreOp,
+    /// Used only if `load_op` is `CLEAR`
+    pub clear: ClearValue,
+}
+
+impl RenderingAttachment<'_> {
+    fn raw(&self) -> vk::RenderingAttachmentInfo<'static> {
+        vk::RenderingAttachmentInfo {
+            s_type: vk::StructureType::RENDERING_ATTACHMENT_INFO,
+            p_next: ptr::null(),
+            image_view: self.view.image_view(),
+            image_layout: self.view.layout(),
+            resolve_mode: vk::ResolveModeFlags::NONE,
+            resolve_image_view: vk::ImageView::null(),
+            resolve_image_layout: vk::ImageLayout::UNDEFINED,
+            load_op: self.load_op,
+            store_op: self.store_op,
+            clear_value: self.clear.into(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Parameters of [`begin_rendering`](Buffer::begin_rendering)
+///
+/// Attachment formats must match [`RenderingFormats`](graphics::RenderingFormats)
+/// of the bound pipeline
+#[derive(Debug, Clone, Copy)]
+pub struct RenderingInfo<'a> {
+    /// Render area
+    pub extent: memory::Extent2D,
+    pub colors: &'a [RenderingAttachment<'a>],
+    pub depth: Option<RenderingAttachment<'a>>,
+}
+
+/// Parameters of single draw for [`draw_indirect`](Buffer::draw_indirect)
+///
+#[doc = "Vulkan documentation <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkDrawIndirectCommand.html>"]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawIndirectCommand {
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub first_vertex: u32,
+    pub first_instance: u32,
+}
+
+/// Parameters of single draw for [`draw_indexed_indirect`](Buffer::draw_indexed_indirect)
+///
+#[doc = "Vulkan documentation <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkDrawIndexedIndirectCommand.html>"]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawIndexedIndirectCommand {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub vertex_offset: i32,
+    pub first_instance: u32,
+}
+
+/// Parameters of dispatch for [`dispatch_indirect`](Buffer::dispatch_indirect)
+///
+#[doc = "Vulkan documentation <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkDispatchIndirectCommand.html>"]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DispatchIndirectCommand {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
 /// Special value for barriers to ignore specific queue family
 pub const QUEUE_FAMILY_IGNORED: u32 = vk::QUEUE_FAMILY_IGNORED;
 
+/// Region of the buffer to image copy
+///
+/// See [`Buffer::copy_buffer_to_image_region`]
+#[doc = "Vulkan documentation <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkBufferImageCopy.html>"]
+#[derive(Debug, Clone, Copy)]
+pub struct BufferImageRegion {
+    /// Offset in bytes from the start of the buffer
+    pub buffer_offset: u64,
+    /// Row length of the data in the buffer in texels
+    ///
+    /// Zero means data is tightly packed according to `image_extent`
+    pub buffer_row_length: u32,
+    /// Offset in texels of the image region
+    pub image_offset: memory::Offset3D,
+    /// Size in texels of the image region
+    pub image_extent: memory::Extent3D,
+    /// First array layer to copy into
+    pub base_layer: u32,
+    /// Number of array layers to copy into
+    pub layer_count: u32,
+}
+
+impl BufferImageRegion {
+    /// Region which covers the whole image from the start of the buffer
+    pub fn full(view: memory::ImageView) -> BufferImageRegion {
+        BufferImageRegion {
+            buffer_offset: 0,
+            buffer_row_length: 0,
+            image_offset: memory::Offset3D { x: 0, y: 0, z: 0 },
+            image_extent: view.extent(),
+            base_layer: 0,
+            layer_count: view.array_layers(),
+        }
+    }
+
+    /// Size in bytes of the buffer which is read by region for the image of `format`
+    /// including [`buffer_offset`](Self::buffer_offset)
+    ///
+    /// Rows and layers are rounded up to the whole [blocks](formats::FormatInfo::block_extent)
+    /// of compressed formats
+    ///
+    /// If format is unknown to the library or is combined depth/stencil format returns `None`
+    pub fn buffer_size(&self, format: memory::ImageFormat) -> Option<u64> {
+        let info = formats::info(format);
+
+        if info.block_size_bytes == 0 || info.aspect.contains(memory::ImageAspect::DEPTH | memory::ImageAspect::STENCIL) {
+            return None;
+        }
+
+        let block = info.block_extent;
+
+        let row_length = if self.buffer_row_length == 0 {
+            self.image_extent.width
+        } else {
+            self.buffer_row_length
+        };
+
+        let row_pitch = (row_length.div_ceil(block.width) as u64)*info.block_size_bytes;
+        let rows = self.image_extent.height.div_ceil(block.height) as u64;
+        let slices = (self.image_extent.depth.div_ceil(block.depth) as u64)*(self.layer_count as u64);
+
+        if rows == 0 || slices == 0 || self.image_extent.width == 0 {
+            return Some(self.buffer_offset);
+        }
+
+        // Last row is read only up to the region width
+        let last_row = (self.image_extent.width.div_ceil(block.width) as u64)*info.block_size_bytes;
+
+        Some(self.buffer_offset + ((slices - 1)*rows + rows - 1)*row_pitch + last_row)
+    }
+
+    /// Does region satisfy queue family
+    /// [`granularity`](crate::hw::QueueFamilyDescription::min_image_transfer_granularity)
+    /// for the image with `image_extent`
+    ///
+    /// If granularity is `(0, 0, 0)` region must cover the whole image
+    ///
+    /// Otherwise offset must be a multiple of the granularity and
+    /// extent must be a multiple of the granularity or reach the image border
+    pub fn fits_granularity(&self, granularity: memory::Extent3D, image_extent: memory::Extent3D) -> bool {
+        let dims = [
+            (self.image_offset.x, self.image_extent.width, image_extent.width, granularity.width),
+            (self.image_offset.y, self.image_extent.height, image_extent.height, granularity.height),
+            (self.image_offset.z, self.image_extent.depth, image_extent.depth, granularity.depth),
+        ];
+
+        if granularity.width == 0 && granularity.height == 0 && granularity.depth == 0 {
+            return dims.iter().all(|&(offset, extent, image, _)| offset == 0 && extent == image);
+        }
+
+        dims.iter().all(|&(offset, extent, image, step)| {
+            if offset < 0 || step == 0 {
+                return false;
+            }
+
+            (offset as u32).is_multiple_of(step)
+                && (extent.is_multiple_of(step) || (offset as u32) + extent == image)
+        })
+    }
+}
+
+/// Global memory barrier for [`Buffer::set_barriers`]
+///
+/// Fields have the same meaning as arguments of [`Buffer::set_global_barrier`]
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryBarrier {
+    pub src_type: AccessType,
+    pub dst_type: AccessType,
+}
+
+impl MemoryBarrier {
+    fn raw(&self) -> vk::MemoryBarrier<'static> {
+        vk::MemoryBarrier {
+            s_type: vk::StructureType::MEMORY_BARRIER,
+            p_next: ptr::null(),
+            src_access_mask: self.src_type,
+            dst_access_mask: self.dst_type,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Buffer memory barrier for [`Buffer::set_barriers`] and [`Buffer::wait_events`]
+///
+/// Fields have the same meaning as arguments of [`Buffer::set_barrier`]
+#[derive(Debug, Clone, Copy)]
+pub struct BufferBarrier<'a> {
+    pub view: memory::View<'a>,
+    pub src_type: AccessType,
+    pub dst_type: AccessType,
+    pub src_queue_family: u32,
+    pub dst_queue_family: u32,
+}
+
+impl BufferBarrier<'_> {
+    fn raw(&self) -> vk::BufferMemoryBarrier<'static> {
+        vk::BufferMemoryBarrier {
+            s_type: vk::StructureType::BUFFER_MEMORY_BARRIER,
+            p_next: ptr::null(),
+            src_access_mask: self.src_type,
+            dst_access_mask: self.dst_type,
+            src_queue_family_index: self.src_queue_family,
+            dst_queue_family_index: self.dst_queue_family,
+            buffer: self.view.buffer(),
+            offset: self.view.buffer_offset(),
+            size: self.view.size(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Image memory barrier for [`Buffer::set_barriers`] and [`Buffer::wait_events`]
+///
+/// Fields have the same meaning as arguments of [`Buffer::set_image_barrier`]
+#[derive(Debug, Clone, Copy)]
+pub struct ImageBarrier<'a> {
+    pub view: memory::ImageView<'a>,
+    pub src_type: AccessType,
+    pub dst_type: AccessType,
+    pub src_layout: memory::ImageLayout,
+    pub dst_layout: memory::ImageLayout,
+    pub src_queue_family: u32,
+    pub dst_queue_family: u32,
+}
+
+impl ImageBarrier<'_> {
+    fn raw(&self) -> vk::ImageMemoryBarrier<'static> {
+        vk::ImageMemoryBarrier {
+            s_type: vk::StructureType::IMAGE_MEMORY_BARRIER,
+            p_next: ptr::null(),
+            src_access_mask: self.src_type,
+            dst_access_mask: self.dst_type,
+            old_layout: self.src_layout,
+            new_layout: self.dst_layout,
+            src_queue_family_index: self.src_queue_family,
+            dst_queue_family_index: self.dst_queue_family,
+            image: self.view.image(),
+            subresource_range: self.view.subresource_range(),
+            _marker: PhantomData,
+        }
+    }
+}
+
 pub struct PoolCfg {
     pub queue_index: u32,
 }
@@ -38,12 +349,87 @@ pub enum PoolError {
     /// Failed to
     /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateCommandPool.html)
     /// command pool
-    Creating
+    Creating(vk::Result),
+    /// Pool has buffers (or clones) which are still alive
+    ///
+    /// See [`Pool::reset`]
+    OutstandingBuffers,
+    /// Failed to
+    /// [reset](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkResetCommandPool.html)
+    /// command pool
+    Reset(vk::Result)
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoolError::Creating(_) => write!(f, "vkCreateCommandPool call failed"),
+            PoolError::OutstandingBuffers => write!(f, "Pool has buffers which are still alive"),
+            PoolError::Reset(_) => write!(f, "vkResetCommandPool call failed"),
+        }
+    }
+}
+
+impl Error for PoolError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PoolError::Creating(result) | PoolError::Reset(result) => Some(result),
+            PoolError::OutstandingBuffers => None
+        }
+    }
 }
 
 struct CorePool {
     i_core: Arc<dev::Core>,
-    i_pool: vk::CommandPool
+    i_pool: vk::CommandPool,
+    i_granularity: memory::Extent3D,
+    i_dynamic_rendering: bool,
+    /// Every call which uses the pool or its buffers is serialized
+    i_lock: Mutex<()>
+}
+
+impl CorePool {
+    fn lock(&self) -> PoolGuard<'_> {
+        PoolGuard {
+            i_device: self.i_core.device(),
+            _lock: self.i_lock.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    fn begin(&self, buffer: vk::CommandBuffer, usage: BufferUsage) -> Result<(), BufferError> {
+        let cmd_begin_info = vk::CommandBufferBeginInfo {
+            s_type: vk::StructureType::COMMAND_BUFFER_BEGIN_INFO,
+            p_next: ptr::null(),
+            flags:  usage,
+            p_inheritance_info: ptr::null(),
+            _marker: PhantomData,
+        };
+
+        on_error!(
+            unsafe { self.lock().begin_command_buffer(buffer, &cmd_begin_info) },
+            |err| return Err(BufferError::Begin(err))
+        );
+
+        Ok(())
+    }
+
+    fn free(&self, buffer: vk::CommandBuffer) {
+        unsafe { self.lock().free_command_buffers(self.i_pool, &[buffer]) };
+    }
+}
+
+/// Device which may be used while the pool is locked
+struct PoolGuard<'a> {
+    i_device: &'a ash::Device,
+    _lock: MutexGuard<'a, ()>
+}
+
+impl Deref for PoolGuard<'_> {
+    type Target = ash::Device;
+
+    fn deref(&self) -> &ash::Device {
+        self.i_device
+    }
 }
 
 impl fmt::Debug for CorePool {
@@ -51,6 +437,8 @@ impl fmt::Debug for CorePool {
         f.debug_struct("Pool")
         .field("i_core", &self.i_core)
         .field("i_pool", &(&self.i_pool as *const vk::CommandPool))
+        .field("i_granularity", &self.i_granularity)
+        .field("i_dynamic_rendering", &self.i_dynamic_rendering)
         .finish()
     }
 }
@@ -63,12 +451,33 @@ impl Drop for CorePool {
                     self.i_pool, self.i_core.allocator()
                 );
         }
+
+        self.i_core.destroyed(dev::ObjectType::CommandPool, 1);
     }
 }
 
 /// All command buffers are allocated from `Pool`
-#[derive(Debug, Clone)]
-pub struct Pool(Arc<CorePool>);
+///
+/// # Threading
+///
+/// Vulkan requires external synchronization of the pool
+/// and of every buffer allocated from it (including recording)
+///
+/// So pool is `Send` but not `Sync`: it may be moved into another thread but not shared between threads.
+/// Use separate pool for each thread, see [`PoolSet::per_thread`]
+///
+/// [`ExecutableBuffer`] may still be dropped on another thread,
+/// so freeing of buffers is serialized with other calls of the pool by the pool lock
+///
+/// ```compile_fail
+/// use libvktypes::cmd;
+///
+/// fn is_sync<T: Sync>() {}
+///
+/// is_sync::<cmd::Pool>();
+/// ```
+#[derive(Debug)]
+pub struct Pool(Arc<CorePool>, PhantomData<Cell<()>>);
 
 impl Pool {
     pub fn new(dev: &dev::Device, pool_type: &PoolCfg) -> Result<Pool, PoolError> {
@@ -80,21 +489,51 @@ impl Pool {
             _marker: PhantomData,
         };
 
-        let cmd_pool = on_error_ret!(
-            unsafe { dev.device().create_command_pool(&pool_info, None) },
-            PoolError::Creating
+        let cmd_pool = on_error!(
+            unsafe { dev.device().create_command_pool(&pool_info, dev.allocator()) },
+            |err| return Err(PoolError::Creating(err))
         );
 
+        dev.core().created(dev::ObjectType::CommandPool, 1);
+
+        let granularity = dev
+            .hw()
+            .queues()
+            .find(|q| q.index() == pool_type.queue_index)
+            .map_or(
+                memory::Extent3D { width: 1, height: 1, depth: 1 },
+                |q| q.min_image_transfer_granularity()
+            );
+
         Ok(Pool(
             Arc::new(CorePool {
             i_core: dev.core().clone(),
-            i_pool: cmd_pool
-            }
-        )))
+            i_pool: cmd_pool,
+            i_granularity: granularity,
+            i_dynamic_rendering: dev.is_dynamic_rendering_enabled(),
+            i_lock: Mutex::new(())
+        }), PhantomData))
+    }
+
+    /// Return image transfer granularity of the pool's queue family
+    ///
+    /// See [`QueueFamilyDescription::min_image_transfer_granularity`](crate::hw::QueueFamilyDescription::min_image_transfer_granularity)
+    pub fn granularity(&self) -> memory::Extent3D {
+        self.0.i_granularity
     }
 
     /// Allocate new command buffer
+    ///
+    /// Same as [`allocate_with`](Self::allocate_with) with empty usage
     pub fn allocate(&self) -> Result<Buffer, BufferError> {
+        self.allocate_with(BufferUsage::empty())
+    }
+
+    /// Allocate new command buffer with selected `usage`
+    ///
+    /// Buffer with `ONE_TIME_SUBMIT` usage must be completed via
+    /// [`commit_once`](Buffer::commit_once)
+    pub fn allocate_with(&self, usage: BufferUsage) -> Result<Buffer, BufferError> {
         let cmd_buff_info = vk::CommandBufferAllocateInfo {
             s_type: vk::StructureType::COMMAND_BUFFER_ALLOCATE_INFO,
             p_next: ptr::null(),
@@ -104,35 +543,100 @@ impl Pool {
             _marker: PhantomData,
         };
 
-        let cmd_buffers = on_error_ret!(
-            unsafe { self.0.i_core.device().allocate_command_buffers(&cmd_buff_info) },
-            BufferError::Creating
-        );
+        let result = unsafe { self.0.lock().allocate_command_buffers(&cmd_buff_info) };
 
-        let cmd_begin_info = vk::CommandBufferBeginInfo {
-            s_type: vk::StructureType::COMMAND_BUFFER_BEGIN_INFO,
-            p_next: ptr::null(),
-            flags:  vk::CommandBufferUsageFlags::empty(),
-            p_inheritance_info: ptr::null(),
-            _marker: PhantomData,
-        };
+        let cmd_buffers = on_error!(result, |err| return Err(BufferError::Creating(err)));
+
+        if let Err(err) = self.0.begin(cmd_buffers[0], usage) {
+            self.0.free(cmd_buffers[0]);
+
+            return Err(err);
+        }
 
-        on_error_ret!(
-            unsafe { self.0.i_core.device().begin_command_buffer(cmd_buffers[0], &cmd_begin_info) },
-            BufferError::Begin
+        Ok(Buffer::new(self.0.clone(), cmd_buffers[0], usage))
+    }
+
+    /// Return all memory of the pool to the system
+    /// ([see more](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkResetCommandPool.html))
+    ///
+    /// Reset makes every buffer of the pool invalid.
+    /// So every [`Buffer`] and [`ExecutableBuffer`] of the pool **must be** dropped before the call.
+    /// Otherwise [`PoolError::OutstandingBuffers`] is returned and nothing happens
+    pub fn reset(&self) -> Result<(), PoolError> {
+        // Count is checked under the lock so no buffer is freed into the pool during reset
+        let pool = self.0.lock();
+
+        if Arc::strong_count(&self.0) > 1 {
+            return Err(PoolError::OutstandingBuffers);
+        }
+
+        on_error!(
+            unsafe {
+                pool.reset_command_pool(
+                    self.0.i_pool,
+                    vk::CommandPoolResetFlags::RELEASE_RESOURCES
+                )
+            },
+            |err| return Err(PoolError::Reset(err))
         );
 
-        Ok(
-            Buffer {
-                i_buffer: cmd_buffers[0],
-                i_pool: self.clone(),
-            }
-        )
+        Ok(())
     }
+}
 
-    #[doc(hidden)]
-    fn device(&self) -> &ash::Device {
-        self.0.i_core.device()
+/// Independent pools for worker threads
+///
+/// Every pool may be moved into its own thread to record buffers in parallel,
+/// recorded [`ExecutableBuffer`]s then may be sent back and submitted to a single queue
+///
+/// ```ignore
+/// let pools = cmd::PoolSet::per_thread(&device, queue.family(), 4)?;
+///
+/// let buffers: Vec<cmd::ExecutableBuffer> = std::thread::scope(|s| {
+///     let workers: Vec<_> = pools
+///         .into_iter()
+///         .map(|pool| s.spawn(move || record(&pool)))
+///         .collect();
+///
+///     workers.into_iter().map(|w| w.join().unwrap()).collect()
+/// });
+/// ```
+#[derive(Debug)]
+pub struct PoolSet(Vec<Pool>);
+
+impl PoolSet {
+    /// Create `count` pools for the queue family `queue_index`
+    pub fn per_thread(dev: &dev::Device, queue_index: u32, count: usize) -> Result<PoolSet, PoolError> {
+        let cfg = PoolCfg { queue_index };
+
+        let pools = (0..count)
+            .map(|_| Pool::new(dev, &cfg))
+            .collect::<Result<Vec<Pool>, PoolError>>()?;
+
+        Ok(PoolSet(pools))
+    }
+
+    /// Return pool with the given index
+    pub fn pool(&self, index: usize) -> &Pool {
+        &self.0[index]
+    }
+
+    /// Return number of pools
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl IntoIterator for PoolSet {
+    type Item = Pool;
+    type IntoIter = std::vec::IntoIter<Pool>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
     }
 }
 
@@ -141,82 +645,587 @@ pub enum BufferError {
     /// Failed to
     /// [allocate](https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/vkAllocateCommandBuffers.html)
     /// buffer
-    Creating,
+    Creating(vk::Result),
     /// Failed to
     /// [initialize](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkBeginCommandBuffer.html)
     /// buffer
-    Begin,
+    Begin(vk::Result),
     /// Failed to
     /// [complete](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkBeginCommandBuffer.html)
     /// buffer
-    Commit
+    Commit(vk::Result),
+    /// Failed to
+    /// [reset](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkResetCommandBuffer.html)
+    /// buffer
+    Reset(vk::Result),
+    /// Push constant data does not fit into any
+    /// [range](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPushConstantRange.html)
+    /// of the pipeline for selected stage
+    PushConstantRange,
+    /// Copy region does not satisfy
+    /// [transfer granularity](crate::hw::QueueFamilyDescription::min_image_transfer_granularity)
+    /// of the queue family
+    TransferGranularity,
+    /// Image has [layout](memory::ImageView::layout) which is not allowed for the command
+    ImageLayout {
+        layout: memory::ImageLayout
+    },
+    /// Buffer has `size` bytes though copy region
+    /// [requires](BufferImageRegion::buffer_size) `required` bytes
+    StagingSize {
+        required: u64,
+        size: u64
+    },
+    /// Recording validation failed, see [`RecordError`]
+    Record(RecordError),
+    /// Buffer with `ONE_TIME_SUBMIT` usage was completed by [`commit`](Buffer::commit)
+    ///
+    /// Use [`commit_once`](Buffer::commit_once) instead
+    OneTimeSubmit,
+    /// Buffer is still used by [unfinished](crate::queue::QueueError::Unfinished) submission
+    Pending
 }
 
-/// Buffer in which you can write commands
-///
-/// Note: this buffer is not ready for execution "as is"
-///
-/// For that you have to complete buffer via (`commit`)[crate::cmd::Buffer::commit]
-pub struct Buffer {
-    i_pool: Pool,
-    i_buffer: vk::CommandBuffer
+impl fmt::Display for BufferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BufferError::Creating(_) => write!(f, "vkAllocateCommandBuffers call failed"),
+            BufferError::Begin(_) => write!(f, "vkBeginCommandBuffer call failed"),
+            BufferError::Commit(_) => write!(f, "vkEndCommandBuffer call failed"),
+            BufferError::Reset(_) => write!(f, "vkResetCommandBuffer call failed"),
+            BufferError::PushConstantRange => write!(f, "Push constant data does not fit into any range of the pipeline"),
+            BufferError::TransferGranularity => write!(f, "Copy region does not satisfy transfer granularity"),
+            BufferError::ImageLayout { layout } => write!(f, "Image layout {:?} is not allowed for the command", layout),
+            BufferError::StagingSize { required, size } => {
+                write!(f, "Copy region requires {} bytes of buffer with size {}", required, size)
+            },
+            BufferError::Record(err) => write!(f, "{}", err),
+            BufferError::OneTimeSubmit => write!(f, "ONE_TIME_SUBMIT buffer must be completed with commit_once"),
+            BufferError::Pending => write!(f, "Buffer is still used by unfinished submission"),
+        }
+    }
 }
 
-impl Buffer {
-    /// Modify buffer into executable
-    ///
-    /// Original buffer will not be available
-    pub fn commit(self) -> Result<ExecutableBuffer, BufferError> {
-        let dev = self.i_pool.device();
+impl Error for BufferError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BufferError::Creating(result)
+            | BufferError::Begin(result)
+            | BufferError::Commit(result)
+            | BufferError::Reset(result) => Some(result),
+            BufferError::Record(err) => Some(err),
+            _ => None
+        }
+    }
+}
 
-        on_error_ret!(
-            unsafe { dev.end_command_buffer(self.i_buffer) },
-            BufferError::Commit
-        );
+/// Command which was recorded with image in unexpected layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutViolation {
+    /// Index of the command within buffer (starting from 0)
+    pub command: usize,
+    /// Name of the [`Buffer`] method
+    pub name: &'static str,
+    /// [Tracked layout](memory::ImageView::layout) of the image at the moment of recording
+    pub layout: memory::ImageLayout
+}
 
-        Ok(
-            ExecutableBuffer {
-                i_buffer: self.i_buffer,
-                i_pool: self.i_pool,
-            }
-        )
-    }
+/// Command argument which exceeds device limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitViolation {
+    /// Index of the command within buffer (starting from 0)
+    pub command: usize,
+    /// Name of the [`Buffer`] method
+    pub name: &'static str,
+    /// Name of the limit, usually from
+    /// [`VkPhysicalDeviceLimits`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceLimits.html)
+    pub limit: &'static str,
+    /// Dimension of the limit (`0` for scalar limits)
+    pub dimension: usize,
+    /// Requested value
+    pub value: u32,
+    /// Device limit
+    pub max: u32
+}
 
-    /// Bind specifically *compute* pipeline
-    ///
-    /// For graphics see [`bind_graphics_pipeline`](Buffer::bind_graphics_pipeline)
-    pub fn bind_compute_pipeline(&self, pipe: &compute::Pipeline) {
-        let dev = self.i_pool.device();
+/// Draw command which reads outside of the bound buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeViolation {
+    /// Index of the command within buffer (starting from 0)
+    pub command: usize,
+    /// Name of the [`Buffer`] method
+    pub name: &'static str,
+    /// Vertex binding or `None` for index buffer
+    pub binding: Option<u32>,
+    /// Bytes required by the command
+    pub required: u64,
+    /// Bytes of the bound buffer after its offset
+    pub available: u64
+}
 
-        unsafe {
-            dev.cmd_bind_pipeline(
-                self.i_buffer,
-                vk::PipelineBindPoint::COMPUTE,
-                pipe.pipeline()
-            );
+/// Preconditions which were violated during recording
+///
+/// Layout and range checks are performed only in debug builds and returned by [`commit`](Buffer::commit)
+/// as [`BufferError::Record`]
+///
+/// Range checks compare vertices and indices read by draw commands
+/// with [sizes](graphics::VertexView::size) of the bound buffers
+/// and [vertex size](graphics::Pipeline::vertex_size) of the bound pipeline
+///
+/// Layout checks are based on [tracked layout](memory::ImageView::layout),
+/// so layout changed by render pass must be [updated](memory::ImageView::set_layout) manually
+///
+/// Limit checks are performed by checked commands (e.g. [`dispatch_checked`](Buffer::dispatch_checked))
+/// which return error immediately and do not record anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordError {
+    pub violations: Vec<LayoutViolation>,
+    pub limits: Vec<LimitViolation>,
+    pub ranges: Vec<RangeViolation>
+}
 
-            dev.cmd_bind_descriptor_sets(
-                self.i_buffer,
-                vk::PipelineBindPoint::COMPUTE,
-                pipe.pipeline_layout(),
-                0,
-                &[pipe.descriptor_set()],
-                &[]
-            );
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.limits.is_empty() {
+            let limits: Vec<String> = self
+                .limits
+                .iter()
+                .map(|v| format!("{} ({}) {}[{}] = {} > {}", v.command, v.name, v.limit, v.dimension, v.value, v.max))
+                .collect();
+
+            return write!(f, "{:?}", format!("Device limits exceeded in commands: {}", limits.join(", ")));
         }
-    }
 
-    /// Copy `src` buffer into `dst`
-    ///
-    /// If `dst` has less capacity then copy only first (`dst.size()`)[crate::memory::View::size()] bytes
-    ///
-    /// If `src` has less capacity then rest of the `dst` memory will be left intact
-    pub fn copy_memory(&self, src: &memory::View, dst: &memory::View) {
-        let dev = self.i_pool.device();
+        if !self.ranges.is_empty() {
+            let ranges: Vec<String> = self
+                .ranges
+                .iter()
+                .map(|v| format!("{} ({}) binding {:?} requires {} bytes of {}", v.command, v.name, v.binding, v.required, v.available))
+                .collect();
 
-        let copy_info = vk::BufferCopy {
-            src_offset: 0,
-            dst_offset: 0,
+            return write!(f, "{:?}", format!("Buffer range exceeded in commands: {}", ranges.join(", ")));
+        }
+
+        let commands: Vec<String> = self
+            .violations
+            .iter()
+            .map(|v| format!("{} ({}) with layout {:?}", v.command, v.name, v.layout))
+            .collect();
+
+        write!(f, "{:?}", format!("Invalid image layout in commands: {}", commands.join(", ")))
+    }
+}
+
+/// Return number of work groups required to cover `total` invocations
+/// with work groups of `local` size
+///
+/// Each dimension is divided with rounding up, zero `local` dimension is treated as `1`
+pub fn group_count(total: [u32; 3], local: [u32; 3]) -> [u32; 3] {
+    [
+        total[0].div_ceil(local[0].max(1)),
+        total[1].div_ceil(local[1].max(1)),
+        total[2].div_ceil(local[2].max(1)),
+    ]
+}
+
+impl Error for RecordError {}
+
+/// Data which can be passed via push constants
+///
+/// Implemented for every `Copy` type and for slices of `f32`, `u32` and `i32`
+///
+/// Note: value is copied as is so prefer `#[repr(C)]` types without padding
+pub trait AsPushConstant {
+    /// Raw representation of the value
+    fn as_push_constant(&self) -> &[u8];
+}
+
+impl<T: Copy + 'static> AsPushConstant for T {
+    fn as_push_constant(&self) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(self as *const T as *const u8, std::mem::size_of::<T>())
+        }
+    }
+}
+
+macro_rules! impl_push_constant_slice {
+    ( $($t:ty),* ) => {
+        $(
+            impl AsPushConstant for [$t] {
+                fn as_push_constant(&self) -> &[u8] {
+                    unsafe {
+                        std::slice::from_raw_parts(self.as_ptr() as *const u8, std::mem::size_of_val(self))
+                    }
+                }
+            }
+        )*
+    }
+}
+
+impl_push_constant_slice!(f32, u32, i32);
+
+/// Pipeline which may accept push constants
+pub trait PushConstantLayout {
+    #[doc(hidden)]
+    fn push_constant_layout(&self) -> vk::PipelineLayout;
+
+    #[doc(hidden)]
+    fn push_constant_ranges(&self) -> &[vk::PushConstantRange];
+
+    #[doc(hidden)]
+    fn push_constant_keepalive(&self) -> dev::Keepalive;
+}
+
+impl PushConstantLayout for compute::Pipeline {
+    fn push_constant_layout(&self) -> vk::PipelineLayout {
+        self.pipeline_layout()
+    }
+
+    fn push_constant_ranges(&self) -> &[vk::PushConstantRange] {
+        compute::Pipeline::push_constant_ranges(self)
+    }
+
+    fn push_constant_keepalive(&self) -> dev::Keepalive {
+        self.keepalive()
+    }
+}
+
+impl PushConstantLayout for graphics::Pipeline {
+    fn push_constant_layout(&self) -> vk::PipelineLayout {
+        self.layout()
+    }
+
+    fn push_constant_ranges(&self) -> &[vk::PushConstantRange] {
+        graphics::Pipeline::push_constant_ranges(self)
+    }
+
+    fn push_constant_keepalive(&self) -> dev::Keepalive {
+        self.keepalive()
+    }
+}
+
+/// Resource which ownership can be transferred between queue families
+///
+/// Implemented for [`View`](memory::View) and [`ImageView`](memory::ImageView)
+///
+/// See [`release_ownership`](Buffer::release_ownership) and [`acquire_ownership`](Buffer::acquire_ownership)
+pub trait OwnershipTransfer {
+    #[doc(hidden)]
+    fn ownership_barrier(&self,
+        cmd: &Buffer,
+        access: (AccessType, AccessType),
+        stages: (PipelineStage, PipelineStage),
+        families: (u32, u32));
+}
+
+impl OwnershipTransfer for memory::View<'_> {
+    fn ownership_barrier(&self,
+        cmd: &Buffer,
+        access: (AccessType, AccessType),
+        stages: (PipelineStage, PipelineStage),
+        families: (u32, u32))
+    {
+        let barrier = BufferBarrier {
+            view: *self,
+            src_type: access.0,
+            dst_type: access.1,
+            src_queue_family: families.0,
+            dst_queue_family: families.1,
+        };
+
+        cmd.set_barriers(stages.0, stages.1, &[], &[barrier], &[]);
+    }
+}
+
+impl OwnershipTransfer for memory::ImageView<'_> {
+    fn ownership_barrier(&self,
+        cmd: &Buffer,
+        access: (AccessType, AccessType),
+        stages: (PipelineStage, PipelineStage),
+        families: (u32, u32))
+    {
+        let barrier = ImageBarrier {
+            view: *self,
+            src_type: access.0,
+            dst_type: access.1,
+            src_layout: self.layout(),
+            dst_layout: self.layout(),
+            src_queue_family: families.0,
+            dst_queue_family: families.1,
+        };
+
+        cmd.set_barriers(stages.0, stages.1, &[], &[], &[barrier]);
+    }
+}
+
+/// Layouts in which image may be read by transfer commands
+const TRANSFER_SRC_LAYOUTS: [memory::ImageLayout; 2] = [
+    memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+    memory::ImageLayout::GENERAL
+];
+
+/// Layouts in which image may be written by transfer commands
+const TRANSFER_DST_LAYOUTS: [memory::ImageLayout; 2] = [
+    memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+    memory::ImageLayout::GENERAL
+];
+
+/// Buffer in which you can write commands
+///
+/// Note: this buffer is not ready for execution "as is"
+///
+/// For that you have to complete buffer via (`commit`)[crate::cmd::Buffer::commit]
+///
+/// Every pipeline, descriptor, render pass, framebuffer and memory used by recorded commands
+/// is kept alive by the buffer (and then by [`ExecutableBuffer`]),
+/// so they may be dropped by user right after recording
+pub struct Buffer {
+    i_pool: Arc<CorePool>,
+    i_buffer: vk::CommandBuffer,
+    i_usage: BufferUsage,
+    i_commands: Cell<usize>,
+    i_violations: RefCell<Vec<LayoutViolation>>,
+    i_ranges: RefCell<Vec<RangeViolation>>,
+    i_draw: RefCell<DrawState>,
+    i_keepalive: RefCell<Vec<dev::Keepalive>>
+}
+
+/// Bound vertex input, tracked for range checks in debug builds
+#[derive(Default)]
+struct DrawState {
+    i_vertex_bindings: Vec<vk::VertexInputBindingDescription>,
+    /// Available bytes of the vertex buffer bound to every binding
+    i_vertex_buffers: Vec<u64>,
+    /// Available bytes and size of single index
+    i_index_buffer: Option<(u64, u64)>,
+}
+
+impl Buffer {
+    fn new(pool: Arc<CorePool>, buffer: vk::CommandBuffer, usage: BufferUsage) -> Buffer {
+        Buffer {
+            i_pool: pool,
+            i_buffer: buffer,
+            i_usage: usage,
+            i_commands: Cell::new(0),
+            i_violations: RefCell::new(Vec::new()),
+            i_ranges: RefCell::new(Vec::new()),
+            i_draw: RefCell::new(DrawState::default()),
+            i_keepalive: RefCell::new(Vec::new())
+        }
+    }
+
+    /// Modify buffer into executable
+    ///
+    /// Original buffer will not be available
+    ///
+    /// In debug builds returns [`BufferError::Record`] if any command was recorded
+    /// with image in unexpected layout or draw command reads outside of the bound buffers (see [`RecordError`]).
+    /// Buffer is freed in that case
+    ///
+    /// Buffer with `ONE_TIME_SUBMIT` usage is freed and rejected with [`BufferError::OneTimeSubmit`],
+    /// use [`commit_once`](Self::commit_once) for it
+    pub fn commit(self) -> Result<ExecutableBuffer, BufferError> {
+        if self.i_usage.contains(BufferUsage::ONE_TIME_SUBMIT) {
+            self.i_pool.free(self.i_buffer);
+
+            return Err(BufferError::OneTimeSubmit);
+        }
+
+        self.complete()
+    }
+
+    /// Modify buffer into executable which may be submitted only once
+    ///
+    /// See [`OneTimeBuffer`]
+    ///
+    /// Validation is the same as for [`commit`](Self::commit)
+    pub fn commit_once(self) -> Result<OneTimeBuffer, BufferError> {
+        Ok(OneTimeBuffer(self.complete()?))
+    }
+
+    fn complete(self) -> Result<ExecutableBuffer, BufferError> {
+        let result = unsafe { self.i_pool.lock().end_command_buffer(self.i_buffer) };
+
+        on_error!(result, |err| return Err(BufferError::Commit(err)));
+
+        let violations = self.i_violations.take();
+        let ranges = self.i_ranges.take();
+
+        if !violations.is_empty() || !ranges.is_empty() {
+            self.i_pool.free(self.i_buffer);
+
+            return Err(BufferError::Record(RecordError { violations, limits: Vec::new(), ranges }));
+        }
+
+        Ok(
+            ExecutableBuffer {
+                i_core: Arc::new(CoreBuffer {
+                    i_buffer: self.i_buffer,
+                    i_pool: self.i_pool,
+                    i_keepalive: self.i_keepalive.take(),
+                }),
+                i_usage: self.i_usage,
+            }
+        )
+    }
+
+    /// Return number of commands recorded so far
+    ///
+    /// For example [`set_barriers`](Self::set_barriers) is counted as one command
+    /// regardless of number of barriers
+    pub fn command_count(&self) -> usize {
+        self.i_commands.get()
+    }
+
+    /// Return device to record the next command
+    ///
+    /// Every call is counted as one command (see [`LayoutViolation::command`])
+    ///
+    /// Pool is locked until returned device is dropped
+    fn record(&self) -> PoolGuard<'_> {
+        self.i_commands.set(self.i_commands.get() + 1);
+
+        self.i_pool.lock()
+    }
+
+    /// Hold `resource` until the buffer is freed
+    fn keep(&self, resource: dev::Keepalive) {
+        let mut keepalive = self.i_keepalive.borrow_mut();
+
+        if !keepalive.iter().any(|x| Arc::ptr_eq(x, &resource)) {
+            keepalive.push(resource);
+        }
+    }
+
+    /// Remember violation if tracked layout of `view` is not in `allowed` (debug builds only)
+    ///
+    /// Must be called before [`record`](Self::record) of the command
+    fn expect_layout(&self, name: &'static str, view: memory::ImageView, allowed: &[memory::ImageLayout]) {
+        if cfg!(debug_assertions) && !allowed.contains(&view.layout()) {
+            self.i_violations.borrow_mut().push(LayoutViolation {
+                command: self.i_commands.get(),
+                name,
+                layout: view.layout()
+            });
+        }
+    }
+
+    /// Remember violation if draw command requires more than `available` bytes (debug builds only)
+    ///
+    /// Must be called before [`record`](Self::record) of the command
+    fn expect_range(&self, name: &'static str, binding: Option<u32>, required: u64, available: u64) {
+        if cfg!(debug_assertions) && required > available {
+            self.i_ranges.borrow_mut().push(RangeViolation {
+                command: self.i_commands.get(),
+                name,
+                binding,
+                required,
+                available
+            });
+        }
+    }
+
+    /// Check that every vertex binding of the bound pipeline has `vertex_count` vertices
+    fn expect_vertices(&self, name: &'static str, vertex_count: u64) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        let draw = self.i_draw.borrow();
+
+        for binding in &draw.i_vertex_bindings {
+            let available = draw.i_vertex_buffers.get(binding.binding as usize).copied().unwrap_or(0);
+
+            self.expect_range(name, Some(binding.binding), vertex_count*(binding.stride as u64), available);
+        }
+    }
+
+    /// Check that every image written into `res` with `SHADER_READ_ONLY_OPTIMAL` layout
+    /// is still in that layout
+    fn expect_sampled_layouts(&self, name: &'static str, res: &graphics::PipelineDescriptor) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        for layout in res.sampled_layouts() {
+            if layout != memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL {
+                self.i_violations.borrow_mut().push(LayoutViolation {
+                    command: self.i_commands.get(),
+                    name,
+                    layout
+                });
+            }
+        }
+    }
+
+    /// Bind specifically *compute* pipeline
+    ///
+    /// For graphics see [`bind_graphics_pipeline`](Buffer::bind_graphics_pipeline)
+    ///
+    /// If pipeline was created with external descriptor
+    /// then it must be bound via [`bind_compute_resources`](Buffer::bind_compute_resources)
+    pub fn bind_compute_pipeline(&self, pipe: &compute::Pipeline) {
+        self.keep(pipe.keepalive());
+
+        let dev = self.record();
+
+        unsafe {
+            dev.cmd_bind_pipeline(
+                self.i_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                pipe.pipeline()
+            );
+
+            if pipe.descriptor_set() != vk::DescriptorSet::null() {
+                dev.cmd_bind_descriptor_sets(
+                    self.i_buffer,
+                    vk::PipelineBindPoint::COMPUTE,
+                    pipe.pipeline_layout(),
+                    0,
+                    &[pipe.descriptor_set()],
+                    &[]
+                );
+            }
+        }
+    }
+
+    /// Enable resource usage for the compute `pipeline`
+    ///
+    /// Same as [`bind_resources`](Buffer::bind_resources) but for compute pipeline
+    pub fn bind_compute_resources(&self, pipe: &compute::Pipeline, res: &graphics::PipelineDescriptor, offsets: &[u32]) {
+        self.expect_sampled_layouts("bind_compute_resources", res);
+
+        self.keep(pipe.keepalive());
+        self.keep(res.keepalive());
+
+        unsafe {
+            self
+            .record()
+            .cmd_bind_descriptor_sets(
+                self.i_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                pipe.pipeline_layout(),
+                0,
+                res.descriptor_sets(),
+                offsets
+            );
+        }
+    }
+
+    /// Copy `src` buffer into `dst`
+    ///
+    /// If `dst` has less capacity then copy only first (`dst.size()`)[crate::memory::View::size()] bytes
+    ///
+    /// If `src` has less capacity then rest of the `dst` memory will be left intact
+    pub fn copy_memory(&self, src: &memory::View, dst: &memory::View) {
+        self.keep(src.keepalive());
+        self.keep(dst.keepalive());
+
+        let dev = self.record();
+
+        let copy_info = vk::BufferCopy {
+            src_offset: src.buffer_offset(),
+            dst_offset: dst.buffer_offset(),
             size: cmp::min(src.size(), dst.size()),
         };
 
@@ -225,148 +1234,880 @@ impl Buffer {
         }
     }
 
-    /// Copy `src` buffer into `dst`
+    /// Copy `size` bytes from `src` buffer starting at `src_offset`
+    /// into `dst` buffer starting at `dst_offset`
+    ///
+    /// Offsets are relative to the views
     ///
     /// Function does not check size of the buffers
+    pub fn copy_memory_range(&self, src: &memory::View, src_offset: u64, dst: &memory::View, dst_offset: u64, size: u64) {
+        self.keep(src.keepalive());
+        self.keep(dst.keepalive());
+
+        let dev = self.record();
+
+        let copy_info = vk::BufferCopy {
+            src_offset: src.buffer_offset() + src_offset,
+            dst_offset: dst.buffer_offset() + dst_offset,
+            size,
+        };
+
+        unsafe {
+            dev.cmd_copy_buffer(self.i_buffer, src.buffer(), dst.buffer(), &[copy_info]);
+        }
+    }
+
+    /// Copy `src` buffer into `dst`
+    ///
+    /// `src` must contain tightly packed texels of all layers
+    /// (see [`formats::required_staging_size`])
     ///
     /// `dst` image must has layout [`TRANSFER_DST_OPTIMAL`](memory::ImageLayout::TRANSFER_DST_OPTIMAL)
     /// or [`GENERAL`](memory::ImageLayout::GENERAL) on creation or via [barrier](Buffer::set_image_barrier)
+    ///
+    /// # Panics
+    ///
+    /// If copy is not valid, see [`try_copy_buffer_to_image`](Self::try_copy_buffer_to_image)
     pub fn copy_buffer_to_image(&self, src: memory::View, dst: memory::ImageView) {
-        let dev = self.i_pool.device();
+        self.try_copy_buffer_to_image(src, dst).unwrap_or_else(|err| panic!("copy_buffer_to_image: {}", err));
+    }
+
+    /// Same as [`copy_buffer_to_image`](Self::copy_buffer_to_image) but returns error instead of panic
+    ///
+    /// Returns [`BufferError::StagingSize`] if `src` is too small,
+    /// [`BufferError::ImageLayout`] if `dst` has wrong layout and
+    /// [`BufferError::TransferGranularity`] if pool's queue family
+    /// can not copy the whole image (see [`copy_buffer_to_image_region`](Self::copy_buffer_to_image_region)).
+    /// Nothing is recorded on error
+    pub fn try_copy_buffer_to_image(&self, src: memory::View, dst: memory::ImageView) -> Result<(), BufferError> {
+        self.try_copy_buffer_to_image_layers(src, dst, 0, dst.array_layers())
+    }
+
+    /// Copy data from buffer to the selected layers of the image
+    ///
+    /// Buffer must contain `layer_count` tightly packed layers one after another
+    ///
+    /// For example all six faces of [cubemap](crate::memory::ImageKind::Cube)
+    /// may be uploaded from single staging buffer
+    ///
+    /// # Panics
+    ///
+    /// If copy is not valid, see [`try_copy_buffer_to_image_layers`](Self::try_copy_buffer_to_image_layers)
+    pub fn copy_buffer_to_image_layers(&self,
+        src: memory::View,
+        dst: memory::ImageView,
+        base_layer: u32,
+        layer_count: u32)
+    {
+        self.try_copy_buffer_to_image_layers(src, dst, base_layer, layer_count)
+            .unwrap_or_else(|err| panic!("copy_buffer_to_image_layers: {}", err));
+    }
+
+    /// Same as [`copy_buffer_to_image_layers`](Self::copy_buffer_to_image_layers) but returns error instead of panic
+    ///
+    /// Errors are the same as for [`try_copy_buffer_to_image`](Self::try_copy_buffer_to_image)
+    pub fn try_copy_buffer_to_image_layers(&self,
+        src: memory::View,
+        dst: memory::ImageView,
+        base_layer: u32,
+        layer_count: u32) -> Result<(), BufferError>
+    {
+        let region = BufferImageRegion {
+            base_layer,
+            layer_count,
+            ..BufferImageRegion::full(dst)
+        };
+
+        self.try_copy_buffer_to_image_region(src, dst, &[region])
+    }
+
+    /// Copy selected regions of the buffer into the image
+    ///
+    /// Useful for uploading texture atlases or updating part of the image
+    ///
+    /// Layout requirements are the same as for [`copy_buffer_to_image`](Self::copy_buffer_to_image)
+    ///
+    /// # Panics
+    ///
+    /// If copy is not valid, see [`try_copy_buffer_to_image_region`](Self::try_copy_buffer_to_image_region)
+    pub fn copy_buffer_to_image_region(&self,
+        src: memory::View,
+        dst: memory::ImageView,
+        regions: &[BufferImageRegion])
+    {
+        self.try_copy_buffer_to_image_region(src, dst, regions)
+            .unwrap_or_else(|err| panic!("copy_buffer_to_image_region: {}", err));
+    }
+
+    /// Same as [`copy_buffer_to_image_region`](Self::copy_buffer_to_image_region) but returns error instead of panic
+    ///
+    /// Every region must [fit](BufferImageRegion::fits_granularity) transfer granularity
+    /// of the pool's queue family.
+    /// Otherwise nothing is recorded and [`BufferError::TransferGranularity`] is returned
+    ///
+    /// Every region must [read](BufferImageRegion::buffer_size) only bytes of the `src`.
+    /// Otherwise nothing is recorded and [`BufferError::StagingSize`] is returned
+    ///
+    /// If `dst` has wrong layout nothing is recorded and [`BufferError::ImageLayout`] is returned
+    pub fn try_copy_buffer_to_image_region(&self,
+        src: memory::View,
+        dst: memory::ImageView,
+        regions: &[BufferImageRegion]) -> Result<(), BufferError>
+    {
+        let granularity = self.i_pool.i_granularity;
+
+        if !regions.iter().all(|region| region.fits_granularity(granularity, dst.extent())) {
+            return Err(BufferError::TransferGranularity);
+        }
+
+        let required = regions.iter().filter_map(|region| region.buffer_size(dst.format())).max();
+
+        if let Some(required) = required.filter(|&required| required > src.size()) {
+            return Err(BufferError::StagingSize { required, size: src.size() });
+        }
+
+        if !TRANSFER_DST_LAYOUTS.contains(&dst.layout()) {
+            return Err(BufferError::ImageLayout { layout: dst.layout() });
+        }
+
+        self.keep(src.keepalive());
+        self.keep(dst.keepalive());
+
+        let dev = self.record();
+
+        let copy_info: Vec<vk::BufferImageCopy> = regions
+            .iter()
+            .map(|region| {
+                let mut subresource = dst.subresource_layer();
+                subresource.base_array_layer = region.base_layer;
+                subresource.layer_count = region.layer_count;
+
+                vk::BufferImageCopy {
+                    buffer_offset: src.buffer_offset() + region.buffer_offset,
+                    buffer_row_length: region.buffer_row_length,
+                    buffer_image_height: 0,
+                    image_subresource: subresource,
+                    image_offset: region.image_offset,
+                    image_extent: region.image_extent,
+                }
+            })
+            .collect();
+
+        unsafe {
+            dev.cmd_copy_buffer_to_image(
+                self.i_buffer,
+                src.buffer(),
+                dst.image(),
+                dst.layout(),
+                &copy_info);
+        }
+
+        Ok(())
+    }
+
+    /// Copy base mip level of `src` image into `dst` buffer
+    ///
+    /// `src` image must has [layout](memory::ImageView::layout)
+    /// [`TRANSFER_SRC_OPTIMAL`](memory::ImageLayout::TRANSFER_SRC_OPTIMAL)
+    /// or [`GENERAL`](memory::ImageLayout::GENERAL) (e.g. via [barrier](Buffer::set_image_barrier))
+    ///
+    /// Texels are tightly packed in `dst`
+    ///
+    /// If `dst` has less capacity than the whole image then only first layer is copied
+    /// and only rows which fit into `dst`
+    pub fn copy_image_to_buffer(&self, src: memory::ImageView, dst: memory::View) {
+        self.expect_layout("copy_image_to_buffer", src, &TRANSFER_SRC_LAYOUTS);
+
+        self.keep(src.keepalive());
+        self.keep(dst.keepalive());
+
+        let dev = self.record();
+
+        let mut subresource = src.subresource_layer();
+        let mut extent = src.extent();
+
+        let row_size = (extent.width as u64)*formats::block_size(src.format());
+        let layer_size = row_size*(extent.height as u64)*(extent.depth as u64);
+
+        if row_size != 0 && dst.size() < layer_size*(subresource.layer_count as u64) {
+            subresource.layer_count = 1;
+
+            if dst.size() < layer_size {
+                extent.depth = 1;
+                extent.height = cmp::min(extent.height, (dst.size()/row_size) as u32);
+            }
+        }
+
+        if extent.height == 0 {
+            return;
+        }
 
         let copy_info = vk::BufferImageCopy {
-            buffer_offset: 0,
+            buffer_offset: dst.buffer_offset(),
             buffer_row_length: 0,
             buffer_image_height: 0,
-            image_subresource: dst.subresource_layer(),
-            image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
-            image_extent: dst.extent(),
+            image_subresource: subresource,
+            image_offset: memory::Offset3D { x: 0, y: 0, z: 0 },
+            image_extent: extent,
         };
 
-        let transfer_layout = memory::ImageLayout::from_raw(
-            (memory::ImageLayout::TRANSFER_DST_OPTIMAL).as_raw() | (memory::ImageLayout::GENERAL).as_raw()
-        );
+        unsafe {
+            dev.cmd_copy_image_to_buffer(
+                self.i_buffer,
+                src.image(),
+                src.layout(),
+                dst.buffer(),
+                &[copy_info]);
+        }
+    }
+
+    /// Copy base mip level of `src` image into `dst` image
+    ///
+    /// `src` image must has [layout](memory::ImageView::layout)
+    /// [`TRANSFER_SRC_OPTIMAL`](memory::ImageLayout::TRANSFER_SRC_OPTIMAL)
+    /// and `dst` image must has layout [`TRANSFER_DST_OPTIMAL`](memory::ImageLayout::TRANSFER_DST_OPTIMAL)
+    /// (or [`GENERAL`](memory::ImageLayout::GENERAL) for both)
+    ///
+    /// Formats of the images must be compatible
+    ///
+    /// If images have different extent or number of layers then only common part is copied
+    pub fn copy_image(&self, src: memory::ImageView, dst: memory::ImageView) {
+        self.expect_layout("copy_image", src, &TRANSFER_SRC_LAYOUTS);
+        self.expect_layout("copy_image", dst, &TRANSFER_DST_LAYOUTS);
+
+        self.keep(src.keepalive());
+        self.keep(dst.keepalive());
+
+        let dev = self.record();
+
+        let src_extent = src.extent();
+        let dst_extent = dst.extent();
+
+        let layer_count = cmp::min(src.array_layers(), dst.array_layers());
+
+        let mut src_subresource = src.subresource_layer();
+        src_subresource.layer_count = layer_count;
+
+        let mut dst_subresource = dst.subresource_layer();
+        dst_subresource.layer_count = layer_count;
+
+        let copy_info = vk::ImageCopy {
+            src_subresource,
+            src_offset: memory::Offset3D { x: 0, y: 0, z: 0 },
+            dst_subresource,
+            dst_offset: memory::Offset3D { x: 0, y: 0, z: 0 },
+            extent: memory::Extent3D {
+                width: cmp::min(src_extent.width, dst_extent.width),
+                height: cmp::min(src_extent.height, dst_extent.height),
+                depth: cmp::min(src_extent.depth, dst_extent.depth),
+            },
+        };
 
         unsafe {
-            dev.cmd_copy_buffer_to_image(
+            dev.cmd_copy_image(
                 self.i_buffer,
-                src.buffer(),
+                src.image(),
+                src.layout(),
                 dst.image(),
-                transfer_layout,
+                dst.layout(),
                 &[copy_info]);
         }
     }
 
-    /// Dispatch work groups
-    pub fn dispatch(&self, x: u32, y: u32, z: u32) {
-        let dev = self.i_pool.device();
+    /// Fill the whole color image with `color`
+    ///
+    /// Image must has [layout](memory::ImageView::layout)
+    /// [`TRANSFER_DST_OPTIMAL`](memory::ImageLayout::TRANSFER_DST_OPTIMAL)
+    /// or [`GENERAL`](memory::ImageLayout::GENERAL)
+    pub fn clear_color_image(&self, view: memory::ImageView, color: [f32; 4]) {
+        self.expect_layout("clear_color_image", view, &TRANSFER_DST_LAYOUTS);
+
+        self.keep(view.keepalive());
+
+        let dev = self.record();
+
+        let clear_value = vk::ClearColorValue {
+            float32: color,
+        };
+
+        unsafe {
+            dev.cmd_clear_color_image(
+                self.i_buffer,
+                view.image(),
+                view.layout(),
+                &clear_value,
+                &[view.subresource_range()]);
+        }
+    }
+
+    /// Fill the whole depth (and stencil if present) image with `depth` and `stencil`
+    ///
+    /// Layout requirements are the same as for [`clear_color_image`](Buffer::clear_color_image)
+    pub fn clear_depth_image(&self, view: memory::ImageView, depth: f32, stencil: u32) {
+        self.expect_layout("clear_depth_image", view, &TRANSFER_DST_LAYOUTS);
+
+        self.keep(view.keepalive());
+
+        let dev = self.record();
+
+        let clear_value = vk::ClearDepthStencilValue {
+            depth,
+            stencil,
+        };
+
+        unsafe {
+            dev.cmd_clear_depth_stencil_image(
+                self.i_buffer,
+                view.image(),
+                view.layout(),
+                &clear_value,
+                &[view.subresource_range()]);
+        }
+    }
+
+    /// Copy base mip level of `src` image into `dst` image with scaling and format conversion
+    ///
+    /// Whole `src` image is stretched to the whole `dst` image
+    ///
+    /// Layout requirements are the same as for [`copy_image`](Buffer::copy_image)
+    ///
+    /// [`LINEAR`](Filter::LINEAR) filter requires format of `src` to support linear filtering
+    pub fn blit_image(&self, src: memory::ImageView, dst: memory::ImageView, filter: Filter) {
+        self.expect_layout("blit_image", src, &TRANSFER_SRC_LAYOUTS);
+        self.expect_layout("blit_image", dst, &TRANSFER_DST_LAYOUTS);
+
+        self.keep(src.keepalive());
+        self.keep(dst.keepalive());
+
+        let dev = self.record();
+
+        let corner = |extent: memory::Extent3D| memory::Offset3D {
+            x: extent.width as i32,
+            y: extent.height as i32,
+            z: extent.depth as i32
+        };
+
+        let layer_count = cmp::min(src.array_layers(), dst.array_layers());
+
+        let mut src_subresource = src.subresource_layer();
+        src_subresource.layer_count = layer_count;
+
+        let mut dst_subresource = dst.subresource_layer();
+        dst_subresource.layer_count = layer_count;
+
+        let blit_info = vk::ImageBlit {
+            src_subresource,
+            src_offsets: [memory::Offset3D { x: 0, y: 0, z: 0 }, corner(src.extent())],
+            dst_subresource,
+            dst_offsets: [memory::Offset3D { x: 0, y: 0, z: 0 }, corner(dst.extent())],
+        };
+
+        unsafe {
+            dev.cmd_blit_image(
+                self.i_buffer,
+                src.image(),
+                src.layout(),
+                dst.image(),
+                dst.layout(),
+                &[blit_info],
+                filter);
+        }
+    }
+
+    /// Generate mip chain for the image from its base level
+    ///
+    /// All mip levels of the image **must be** in `TRANSFER_DST_OPTIMAL` layout
+    /// (e.g. after [`copy_buffer_to_image`](Self::copy_buffer_to_image))
+    ///
+    /// Image **must be** created with `TRANSFER_SRC` and `TRANSFER_DST` usage
+    /// and its format **must** support linear filtering for blit
+    ///
+    /// After this command all mip levels are in `SHADER_READ_ONLY_OPTIMAL` layout
+    /// and available for the fragment shader
+    pub fn blit_mip_chain(&self, view: memory::ImageView) {
+        self.expect_layout("blit_mip_chain", view, &[memory::ImageLayout::TRANSFER_DST_OPTIMAL]);
+
+        self.keep(view.keepalive());
+
+        let dev = self.record();
+
+        let mut subresource = view.subresource_range();
+        subresource.level_count = 1;
+
+        let mut barrier = vk::ImageMemoryBarrier {
+            s_type: vk::StructureType::IMAGE_MEMORY_BARRIER,
+            p_next: ptr::null(),
+            src_access_mask: AccessType::TRANSFER_WRITE,
+            dst_access_mask: AccessType::TRANSFER_READ,
+            old_layout: memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            new_layout: memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            src_queue_family_index: QUEUE_FAMILY_IGNORED,
+            dst_queue_family_index: QUEUE_FAMILY_IGNORED,
+            image: view.image(),
+            subresource_range: subresource,
+            _marker: PhantomData,
+        };
+
+        let extent = view.extent();
+
+        let mut width = extent.width as i32;
+        let mut height = extent.height as i32;
+
+        for level in 1..view.mip_levels() {
+            barrier.subresource_range.base_mip_level = level - 1;
+            barrier.src_access_mask = AccessType::TRANSFER_WRITE;
+            barrier.dst_access_mask = AccessType::TRANSFER_READ;
+            barrier.old_layout = memory::ImageLayout::TRANSFER_DST_OPTIMAL;
+            barrier.new_layout = memory::ImageLayout::TRANSFER_SRC_OPTIMAL;
+
+            unsafe {
+                dev.cmd_pipeline_barrier(
+                    self.i_buffer,
+                    PipelineStage::TRANSFER,
+                    PipelineStage::TRANSFER,
+                    vk::DependencyFlags::empty(),
+                    &[],
+                    &[],
+                    &[barrier]
+                )
+            };
+
+            let next_width = cmp::max(width/2, 1);
+            let next_height = cmp::max(height/2, 1);
+
+            let blit = vk::ImageBlit {
+                src_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask: subresource.aspect_mask,
+                    mip_level: level - 1,
+                    base_array_layer: subresource.base_array_layer,
+                    layer_count: subresource.layer_count,
+                },
+                src_offsets: [
+                    vk::Offset3D { x: 0, y: 0, z: 0 },
+                    vk::Offset3D { x: width, y: height, z: 1 }
+                ],
+                dst_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask: subresource.aspect_mask,
+                    mip_level: level,
+                    base_array_layer: subresource.base_array_layer,
+                    layer_count: subresource.layer_count,
+                },
+                dst_offsets: [
+                    vk::Offset3D { x: 0, y: 0, z: 0 },
+                    vk::Offset3D { x: next_width, y: next_height, z: 1 }
+                ],
+            };
+
+            unsafe {
+                dev.cmd_blit_image(
+                    self.i_buffer,
+                    view.image(),
+                    memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    view.image(),
+                    memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[blit],
+                    vk::Filter::LINEAR
+                )
+            };
+
+            barrier.src_access_mask = AccessType::TRANSFER_READ;
+            barrier.dst_access_mask = AccessType::SHADER_READ;
+            barrier.old_layout = memory::ImageLayout::TRANSFER_SRC_OPTIMAL;
+            barrier.new_layout = memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+
+            unsafe {
+                dev.cmd_pipeline_barrier(
+                    self.i_buffer,
+                    PipelineStage::TRANSFER,
+                    PipelineStage::FRAGMENT_SHADER,
+                    vk::DependencyFlags::empty(),
+                    &[],
+                    &[],
+                    &[barrier]
+                )
+            };
+
+            width = next_width;
+            height = next_height;
+        }
+
+        barrier.subresource_range.base_mip_level = view.mip_levels() - 1;
+        barrier.src_access_mask = AccessType::TRANSFER_WRITE;
+        barrier.dst_access_mask = AccessType::SHADER_READ;
+        barrier.old_layout = memory::ImageLayout::TRANSFER_DST_OPTIMAL;
+        barrier.new_layout = memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+
+        unsafe {
+            dev.cmd_pipeline_barrier(
+                self.i_buffer,
+                PipelineStage::TRANSFER,
+                PipelineStage::FRAGMENT_SHADER,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[barrier]
+            )
+        };
+
+        view.set_layout(memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+    }
+
+    /// Dispatch work groups
+    pub fn dispatch(&self, x: u32, y: u32, z: u32) {
+        let dev = self.record();
+
+        unsafe {
+            dev.cmd_dispatch(self.i_buffer, x, y, z)
+        }
+    }
+
+    /// Same as [`dispatch`](Self::dispatch) but checks group count against
+    /// [device limits](crate::hw::HWDevice::max_work_group_count)
+    ///
+    /// Nothing is recorded if limits are exceeded
+    pub fn dispatch_checked(&self, x: u32, y: u32, z: u32) -> Result<(), RecordError> {
+        let max = self.i_pool.i_core.limits().max_compute_work_group_count;
+
+        self.check_limits("dispatch_checked", "maxComputeWorkGroupCount", [x, y, z], max)?;
+
+        self.dispatch(x, y, z);
+
+        Ok(())
+    }
+
+    /// Dispatch enough work groups of `local` size to cover `total` invocations
+    ///
+    /// `local` must match local size declared in the shader
+    ///
+    /// Group count is calculated by [`group_count`] and checked as in [`dispatch_checked`](Self::dispatch_checked),
+    /// `local` is checked against [max size](crate::hw::HWDevice::max_work_group_size)
+    /// and [max invocations](crate::hw::HWDevice::max_work_group_invocations) of the work group
+    pub fn dispatch_for(&self, total: [u32; 3], local: [u32; 3]) -> Result<(), RecordError> {
+        let limits = self.i_pool.i_core.limits();
+
+        let max_size = limits.max_compute_work_group_size;
+        let max_invocations = limits.max_compute_work_group_invocations;
+
+        self.check_limits("dispatch_for", "maxComputeWorkGroupSize", local, max_size)?;
+
+        let invocations = local.iter().fold(1u64, |acc, &x| acc*(x as u64));
+
+        if invocations > max_invocations as u64 {
+            return Err(RecordError {
+                violations: Vec::new(),
+                ranges: Vec::new(),
+                limits: vec![LimitViolation {
+                    command: self.i_commands.get(),
+                    name: "dispatch_for",
+                    limit: "maxComputeWorkGroupInvocations",
+                    dimension: 0,
+                    value: u32::try_from(invocations).unwrap_or(u32::MAX),
+                    max: max_invocations
+                }]
+            });
+        }
+
+        let [x, y, z] = group_count(total, local);
+
+        self.check_limits("dispatch_for", "maxComputeWorkGroupCount", [x, y, z], limits.max_compute_work_group_count)?;
+
+        self.dispatch(x, y, z);
+
+        Ok(())
+    }
+
+    fn check_limits(&self, name: &'static str, limit: &'static str, value: [u32; 3], max: [u32; 3]) -> Result<(), RecordError> {
+        let limits: Vec<LimitViolation> = (0..3)
+            .filter(|&i| value[i] > max[i])
+            .map(|i| LimitViolation {
+                command: self.i_commands.get(),
+                name,
+                limit,
+                dimension: i,
+                value: value[i],
+                max: max[i]
+            })
+            .collect();
+
+        if limits.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(RecordError { violations: Vec::new(), limits, ranges: Vec::new() })
+        }
+    }
+
+    /// Dispatch work groups with parameters from [`DispatchIndirectCommand`]
+    /// located in `view` at `offset` bytes
+    ///
+    /// Buffer must be created with [`INDIRECT`](crate::memory::INDIRECT) usage
+    pub fn dispatch_indirect(&self, view: memory::View, offset: u64) {
+        self.keep(view.keepalive());
+
+        let dev = self.record();
+
+        unsafe {
+            dev.cmd_dispatch_indirect(self.i_buffer, view.buffer(), view.buffer_offset() + offset)
+        }
+    }
+
+    // TODO can we infer AccessType and PipelineStage from buffer type?
+    // I think not
+    // Add usage type to Memory?
+
+    /// Set *buffer* memory barrier
+    /// ([see more](https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VkBufferMemoryBarrier.html))
+    ///
+    /// `src` is what should be before barrier (e.g. write to memory)
+    ///
+    /// `dst` is what should be after barrier (e.g. read)
+    ///
+    /// For more types see [AccessType]
+    ///
+    /// See [`set_barriers`](Self::set_barriers) to pass barrier as [`BufferBarrier`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_barrier(&mut self,
+        mem: &memory::View,
+        src_type: AccessType,
+        dst_type: AccessType,
+        src_stage: PipelineStage,
+        dst_stage: PipelineStage,
+        src_queue_family: u32,
+        dst_queue_family: u32)
+    {
+        let barrier = BufferBarrier {
+            view: *mem,
+            src_type,
+            dst_type,
+            src_queue_family,
+            dst_queue_family,
+        };
+
+        self.set_barriers(src_stage, dst_stage, &[], &[barrier], &[]);
+    }
+
+    /// Set image memory barrier
+    /// ([see more](https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VkImageMemoryBarrier.html))
+    ///
+    /// `src` is what should be before barrier (e.g. write to memory)
+    ///
+    /// `dst` is what should be after barrier (e.g. read)
+    ///
+    /// For more types see [AccessType]
+    ///
+    /// If you don't care for specific queue family use [`cmd::QUEUE_FAMILY_IGNORED`](QUEUE_FAMILY_IGNORED)
+    ///
+    /// [Tracked layout](memory::ImageView::layout) of the image becomes `dst_layout`
+    ///
+    /// See [`set_barriers`](Self::set_barriers) to pass barrier as [`ImageBarrier`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_image_barrier(&self,
+        view: memory::ImageView,
+        src_type: AccessType,
+        dst_type: AccessType,
+        src_layout: memory::ImageLayout,
+        dst_layout: memory::ImageLayout,
+        src_stage: PipelineStage,
+        dst_stage: PipelineStage,
+        src_queue_family: u32,
+        dst_queue_family: u32)
+    {
+        let barrier = ImageBarrier {
+            view,
+            src_type,
+            dst_type,
+            src_layout,
+            dst_layout,
+            src_queue_family,
+            dst_queue_family,
+        };
+
+        self.set_barriers(src_stage, dst_stage, &[], &[], &[barrier]);
+    }
+
+    /// Set global memory barrier
+    /// ([see more](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkMemoryBarrier.html))
+    ///
+    /// Barrier affects all memory accessed in `src_stage` and `dst_stage`
+    pub fn set_global_barrier(&self,
+        src_type: AccessType,
+        dst_type: AccessType,
+        src_stage: PipelineStage,
+        dst_stage: PipelineStage)
+    {
+        self.set_barriers(src_stage, dst_stage, &[MemoryBarrier { src_type, dst_type }], &[], &[]);
+    }
+
+    /// Record all barriers with a single
+    /// [`vkCmdPipelineBarrier`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdPipelineBarrier.html)
+    /// call
+    ///
+    /// Prefer it to several [`set_barrier`](Self::set_barrier) or [`set_image_barrier`](Self::set_image_barrier)
+    /// calls with the same stages (e.g. layout transitions at the frame start)
+    ///
+    /// [Tracked layout](memory::ImageView::layout) of every image becomes its `dst_layout`
+    pub fn set_barriers(&self,
+        src_stage: PipelineStage,
+        dst_stage: PipelineStage,
+        memory: &[MemoryBarrier],
+        buffers: &[BufferBarrier],
+        images: &[ImageBarrier])
+    {
+        let memory_barriers: Vec<vk::MemoryBarrier> = memory.iter().map(MemoryBarrier::raw).collect();
+        let buffer_barriers = self.raw_buffer_barriers(buffers);
+        let image_barriers = self.raw_image_barriers(images);
+
+        unsafe {
+            self.record()
+            .cmd_pipeline_barrier(
+                self.i_buffer,
+                src_stage,
+                dst_stage,
+                vk::DependencyFlags::empty(),
+                &memory_barriers,
+                &buffer_barriers,
+                &image_barriers
+            )
+        };
+
+        for barrier in images {
+            barrier.view.set_layout(barrier.dst_layout);
+        }
+    }
+
+    fn raw_buffer_barriers(&self, barriers: &[BufferBarrier]) -> Vec<vk::BufferMemoryBarrier<'static>> {
+        barriers
+            .iter()
+            .map(|b| {
+                self.keep(b.view.keepalive());
+
+                b.raw()
+            })
+            .collect()
+    }
+
+    fn raw_image_barriers(&self, barriers: &[ImageBarrier]) -> Vec<vk::ImageMemoryBarrier<'static>> {
+        barriers
+            .iter()
+            .map(|b| {
+                self.keep(b.view.keepalive());
+
+                b.raw()
+            })
+            .collect()
+    }
 
+    /// Signal `event` when all previous commands complete `stage`
+    pub fn set_event(&self, event: &sync::Event, stage: PipelineStage) {
         unsafe {
-            dev.cmd_dispatch(self.i_buffer, x, y, z)
+            self.record().cmd_set_event(self.i_buffer, event.event(), stage)
         }
     }
 
-    // TODO can we infer AccessType and PipelineStage from buffer type?
-    // I think not
-    // Add usage type to Memory?
+    /// Unsignal `event` when all previous commands complete `stage`
+    pub fn reset_event(&self, event: &sync::Event, stage: PipelineStage) {
+        unsafe {
+            self.record().cmd_reset_event(self.i_buffer, event.event(), stage)
+        }
+    }
 
-    /// Set *buffer* memory barrier
-    /// ([see more](https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VkBufferMemoryBarrier.html))
+    /// Wait until all `events` are signaled
+    /// ([see more](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdWaitEvents.html))
     ///
-    /// `src` is what should be before barrier (e.g. write to memory)
+    /// `src_stage` must include stages used in [`set_event`](Self::set_event)
+    /// (or `HOST` if event is [set](sync::Event::set) from host)
     ///
-    /// `dst` is what should be after barrier (e.g. read)
+    /// Commands recorded after the call wait for events only in `dst_stage`,
+    /// so work between [`set_event`](Self::set_event) and `wait_events` is not blocked
     ///
-    /// For more types see [AccessType]
-    pub fn set_barrier(&mut self,
-        mem: &memory::View,
-        src_type: AccessType,
-        dst_type: AccessType,
+    /// Barriers are applied as in [`set_barriers`](Self::set_barriers)
+    pub fn wait_events(&self,
+        events: &[&sync::Event],
         src_stage: PipelineStage,
         dst_stage: PipelineStage,
-        src_queue_family: u32,
-        dst_queue_family: u32)
+        buffer_barriers: &[BufferBarrier],
+        image_barriers: &[ImageBarrier])
     {
-        let dev = self.i_pool.device();
-
-        let mem_barrier = vk::BufferMemoryBarrier {
-            s_type: vk::StructureType::BUFFER_MEMORY_BARRIER,
-            p_next: ptr::null(),
-            src_access_mask: src_type,
-            dst_access_mask: dst_type,
-            src_queue_family_index: src_queue_family,
-            dst_queue_family_index: dst_queue_family,
-            buffer: mem.buffer(),
-            offset: mem.offset(),
-            size: mem.size(),
-            _marker: PhantomData,
-        };
+        let events: Vec<vk::Event> = events.iter().map(|e| e.event()).collect();
+        let vk_buffer_barriers = self.raw_buffer_barriers(buffer_barriers);
+        let vk_image_barriers = self.raw_image_barriers(image_barriers);
 
         unsafe {
-            dev.cmd_pipeline_barrier(
+            self.record()
+            .cmd_wait_events(
                 self.i_buffer,
+                &events,
                 src_stage,
                 dst_stage,
-                vk::DependencyFlags::empty(),
                 &[],
-                &[mem_barrier],
-                &[]
+                &vk_buffer_barriers,
+                &vk_image_barriers
             )
+        };
+
+        for barrier in image_barriers {
+            barrier.view.set_layout(barrier.dst_layout);
         }
     }
 
-    /// Set image memory barrier
-    /// ([see more](https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VkBufferMemoryBarrier.html))
+    /// Release `res` from `src_family` to `dst_family`
+    /// ([see more](https://registry.khronos.org/vulkan/specs/1.3-extensions/html/vkspec.html#synchronization-queue-transfers))
     ///
-    /// `src` is what should be before barrier (e.g. write to memory)
+    /// Must be recorded into buffer executed on `src_family` queue
     ///
-    /// `dst` is what should be after barrier (e.g. read)
+    /// `src_stage` and `src_access` describe the last use of the resource on `src_family`
     ///
-    /// For more types see [AccessType]
+    /// Transfer is complete only after matching [`acquire_ownership`](Buffer::acquire_ownership)
+    /// with the same families is executed on `dst_family` queue.
+    /// Both halves are required, use semaphore to order them
     ///
-    /// If you don't care for specific queue family use [`cmd::QUEUE_FAMILY_IGNORED`](QUEUE_FAMILY_IGNORED)
-    pub fn set_image_barrier(&self,
-        view: memory::ImageView,
-        src_type: AccessType,
-        dst_type: AccessType,
-        src_layout: memory::ImageLayout,
-        dst_layout: memory::ImageLayout,
+    /// Image layout is preserved.
+    /// If you need layout transition do it with [`set_image_barrier`](Buffer::set_image_barrier)
+    /// before release or after acquire
+    pub fn release_ownership<T: OwnershipTransfer>(&self,
+        res: T,
+        src_family: u32,
+        dst_family: u32,
         src_stage: PipelineStage,
-        dst_stage: PipelineStage,
-        src_queue_family: u32,
-        dst_queue_family: u32)
+        src_access: AccessType)
     {
-        let img_barrier = vk::ImageMemoryBarrier {
-            s_type: vk::StructureType::IMAGE_MEMORY_BARRIER,
-            p_next: ptr::null(),
-            src_access_mask: src_type,
-            dst_access_mask: dst_type,
-            old_layout: src_layout,
-            new_layout: dst_layout,
-            src_queue_family_index: src_queue_family,
-            dst_queue_family_index: dst_queue_family,
-            image: view.image(),
-            subresource_range: view.subresource_range(),
-            _marker: PhantomData,
-        };
+        res.ownership_barrier(
+            self,
+            (src_access, AccessType::empty()),
+            (src_stage, PipelineStage::BOTTOM_OF_PIPE),
+            (src_family, dst_family)
+        );
+    }
 
-        unsafe {
-            self.i_pool.device()
-            .cmd_pipeline_barrier(
-                self.i_buffer,
-                src_stage,
-                dst_stage,
-                vk::DependencyFlags::empty(),
-                &[],
-                &[],
-                &[img_barrier]
-            )
-        };
+    /// Acquire `res` released by [`release_ownership`](Buffer::release_ownership)
+    ///
+    /// Must be recorded into buffer executed on `dst_family` queue
+    ///
+    /// `src_family` and `dst_family` must be the same as in release
+    ///
+    /// `dst_stage` and `dst_access` describe the first use of the resource on `dst_family`
+    ///
+    /// For images old and new layouts of the release are repeated
+    pub fn acquire_ownership<T: OwnershipTransfer>(&self,
+        res: T,
+        src_family: u32,
+        dst_family: u32,
+        dst_stage: PipelineStage,
+        dst_access: AccessType)
+    {
+        res.ownership_barrier(
+            self,
+            (AccessType::empty(), dst_access),
+            (PipelineStage::TOP_OF_PIPE, dst_stage),
+            (src_family, dst_family)
+        );
     }
 
     /// Update push constatnts with raw data
     pub fn update_push_constants(&self, pipe: &compute::Pipeline, data: &[u8]) {
-        let dev = self.i_pool.device();
+        self.keep(pipe.keepalive());
+
+        let dev = self.record();
 
         unsafe {
             dev.cmd_push_constants(
@@ -375,40 +2116,148 @@ impl Buffer {
         }
     }
 
+    /// Update push constants of graphics pipeline with raw data
+    ///
+    /// `stage` and `offset` must match push constant range defined by
+    /// [`PipelineCfg::push_constants`](graphics::PipelineCfg::push_constants)
+    pub fn update_graphics_push_constants(&self,
+        pipe: &graphics::Pipeline,
+        stage: graphics::ShaderStage,
+        offset: u32,
+        data: &[u8])
+    {
+        self.keep(pipe.keepalive());
+
+        let dev = self.record();
+
+        unsafe {
+            dev.cmd_push_constants(self.i_buffer, pipe.layout(), stage, offset, data)
+        }
+    }
+
+    /// Update push constants with typed value
+    ///
+    /// Unlike [`update_push_constants`](Buffer::update_push_constants) checks that
+    /// `value` fits into push constant range of the `pipe` declared for `stage`
+    ///
+    /// Otherwise returns [`BufferError::PushConstantRange`] and command is not recorded
+    ///
+    /// For compute pipeline `stage` must be [`ShaderStage::COMPUTE`](graphics::ShaderStage::COMPUTE)
+    pub fn push<P, T>(&self, pipe: &P, stage: graphics::ShaderStage, offset: u32, value: &T) -> Result<(), BufferError>
+    where
+        P: PushConstantLayout,
+        T: AsPushConstant + ?Sized
+    {
+        let data = value.as_push_constant();
+
+        let end = offset as u64 + data.len() as u64;
+
+        let fits = pipe.push_constant_ranges().iter().any(|range| {
+            range.stage_flags.contains(stage)
+            && range.offset <= offset
+            && end <= range.offset as u64 + range.size as u64
+        });
+
+        if data.is_empty() || stage.is_empty() || !fits {
+            return Err(BufferError::PushConstantRange);
+        }
+
+        self.keep(pipe.push_constant_keepalive());
+
+        unsafe {
+            self.record().cmd_push_constants(
+                self.i_buffer, pipe.push_constant_layout(), stage, offset, data
+            )
+        }
+
+        Ok(())
+    }
+
     /// Begin render pass with selected framebuffer
     ///
     /// Must be ended with [`end_render_pass`](crate::cmd::Buffer::end_render_pass)
+    ///
+    /// Color attachments are cleared with `[0.0, 0.0, 0.0, 0.0]`, depth with `1.0`
+    ///
+    /// For custom values see [`begin_render_pass_with_clear`](Buffer::begin_render_pass_with_clear)
     pub fn begin_render_pass(&self, rp: &graphics::RenderPass, fb: &memory::Framebuffer) {
-        let dev = self.i_pool.device();
+        self.begin_render_pass_with_clear(rp, fb, &[
+            ClearValue::Color([0.0, 0.0, 0.0, 0.0]),
+            ClearValue::DepthStencil { depth: 1.0, stencil: 0 }
+        ]);
+    }
 
-        let clear_value = [
-            vk::ClearValue {
-                color: vk::ClearColorValue {
-                    float32: [0.0, 0.0, 0.0, 0.0],
-                }
+    /// Begin render pass with selected framebuffer and clear values
+    ///
+    /// `clear` is indexed by attachment number
+    /// so it must contain value for every attachment which is cleared on load
+    ///
+    /// Must be ended with [`end_render_pass`](crate::cmd::Buffer::end_render_pass)
+    pub fn begin_render_pass_with_clear(&self,
+        rp: &graphics::RenderPass,
+        fb: &memory::Framebuffer,
+        clear: &[ClearValue])
+    {
+        let area = graphics::Rect2D {
+            offset: vk::Offset2D {
+                x: 0,
+                y: 0,
             },
-            vk::ClearValue {
-                depth_stencil: vk::ClearDepthStencilValue {
-                    depth: 1.0,
-                    stencil: 0,
-                }
-            }
-        ];
+            extent: fb.extent(),
+        };
+
+        self.record_render_pass(rp, fb, area, clear);
+    }
+
+    /// Same as [`begin_render_pass_with_clear`](Buffer::begin_render_pass_with_clear)
+    /// but rendering (including clears on load) is restricted to `area`
+    ///
+    /// `area` must be contained within the framebuffer,
+    /// otherwise [`LimitViolation`] with `"framebuffer extent"` limit is returned
+    /// (negative offset is reported as `u32::MAX`)
+    ///
+    /// Nothing is recorded if `area` is invalid
+    pub fn begin_render_pass_area(&self,
+        rp: &graphics::RenderPass,
+        fb: &memory::Framebuffer,
+        area: graphics::Rect2D,
+        clear: &[ClearValue]) -> Result<(), RecordError>
+    {
+        let end = |offset: i32, size: u32| u32::try_from(offset).map_or(u32::MAX, |x| x.saturating_add(size));
+
+        self.check_limits(
+            "begin_render_pass_area",
+            "framebuffer extent",
+            [end(area.offset.x, area.extent.width), end(area.offset.y, area.extent.height), 0],
+            [fb.extent().width, fb.extent().height, 0]
+        )?;
+
+        self.record_render_pass(rp, fb, area, clear);
+
+        Ok(())
+    }
+
+    fn record_render_pass(&self,
+        rp: &graphics::RenderPass,
+        fb: &memory::Framebuffer,
+        area: graphics::Rect2D,
+        clear: &[ClearValue])
+    {
+        self.keep(rp.keepalive());
+        self.keep(fb.keepalive());
+
+        let dev = self.record();
+
+        let clear_value: Vec<vk::ClearValue> = clear.iter().map(|&x| x.into()).collect();
 
         let render_pass_begin_info = vk::RenderPassBeginInfo {
             s_type: vk::StructureType::RENDER_PASS_BEGIN_INFO,
             p_next: ptr::null(),
             render_pass: rp.render_pass(),
             framebuffer: fb.framebuffer(),
-            render_area: vk::Rect2D {
-                offset: vk::Offset2D {
-                    x: 0,
-                    y: 0,
-                },
-                extent: fb.extent(),
-            },
+            render_area: area,
             clear_value_count: clear_value.len() as u32,
-            p_clear_values: clear_value.as_ptr(),
+            p_clear_values: data_ptr!(clear_value),
             _marker: PhantomData,
         };
 
@@ -420,11 +2269,26 @@ impl Buffer {
     /// Update vertex bindings
     ///
     /// Updating starts from **first** binding
+    ///
+    /// Every buffer **must be** created with `VERTEX_BUFFER` usage (checked in debug builds)
     pub fn bind_vertex_buffers(&self, buffers: &[graphics::VertexView]) {
-        let dev = self.i_pool.device();
+        debug_assert!(
+            buffers.iter().all(|x| x.view().usage().contains(memory::BufferUsageFlags::VERTEX_BUFFER)),
+            "Vertex buffer must be created with VERTEX_BUFFER usage"
+        );
+
+        if cfg!(debug_assertions) {
+            self.i_draw.borrow_mut().i_vertex_buffers = buffers.iter().map(|x| x.size()).collect();
+        }
+
+        for buffer in buffers {
+            self.keep(buffer.view().keepalive());
+        }
+
+        let dev = self.record();
 
         let vertex_buffers: Vec<vk::Buffer> = buffers.iter().map(|x| x.buffer()).collect();
-        let offsets: Vec<vk::DeviceSize> = buffers.iter().map(|x| x.offset() as u64).collect();
+        let offsets: Vec<vk::DeviceSize> = buffers.iter().map(|x| x.view().buffer_offset() + x.offset() as u64).collect();
 
         unsafe {
             dev.cmd_bind_vertex_buffers(self.i_buffer, 0, vertex_buffers.as_slice(), offsets.as_slice())
@@ -435,7 +2299,13 @@ impl Buffer {
     ///
     /// For graphics see [`bind_compute_pipeline`](Buffer::bind_compute_pipeline)
     pub fn bind_graphics_pipeline(&self, pipe: &graphics::Pipeline) {
-        let dev = self.i_pool.device();
+        self.keep(pipe.keepalive());
+
+        if cfg!(debug_assertions) {
+            self.i_draw.borrow_mut().i_vertex_bindings = pipe.vertex_bindings().to_vec();
+        }
+
+        let dev = self.record();
 
         unsafe {
             dev.cmd_bind_pipeline(self.i_buffer, vk::PipelineBindPoint::GRAPHICS, pipe.pipeline())
@@ -446,14 +2316,20 @@ impl Buffer {
     ///
     /// Each element of `offsets` must be multiple of [`hw::ubo_offset`](crate::hw::HWDevice::ubo_offset)
     ///
+    /// Use [`DynamicOffsets`](graphics::DynamicOffsets) to check `offsets` beforehand
+    ///
     /// See [more](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdBindDescriptorSets.html)
     ///
     /// If you do not care about `offsets` leave it as `&[]`
     pub fn bind_resources(&self, pipe: &graphics::Pipeline, res: &graphics::PipelineDescriptor, offsets: &[u32]) {
+        self.expect_sampled_layouts("bind_resources", res);
+
+        self.keep(pipe.keepalive());
+        self.keep(res.keepalive());
+
         unsafe {
             self
-            .i_pool
-            .device()
+            .record()
             .cmd_bind_descriptor_sets(
                 self.i_buffer,
                 vk::PipelineBindPoint::GRAPHICS,
@@ -466,19 +2342,53 @@ impl Buffer {
     }
 
     /// Bind index buffer
+    ///
+    /// Buffer **must be** created with `INDEX_BUFFER` usage (checked in debug builds)
+    ///
+    /// `offset` must be multiple of index size (checked in debug builds)
+    /// and leave at least one index in the buffer (see [`RangeViolation`])
     pub fn bind_index_buffer(&self, view: memory::View, offset: u64, it: memory::IndexBufferType) {
-        let dev = self.i_pool.device();
+        debug_assert!(
+            view.usage().contains(memory::BufferUsageFlags::INDEX_BUFFER),
+            "Index buffer must be created with INDEX_BUFFER usage"
+        );
+
+        let index_size = memory::index_size(it);
+
+        debug_assert!(offset.is_multiple_of(index_size), "Index buffer offset must be multiple of index size");
+
+        self.expect_range("bind_index_buffer", None, offset.saturating_add(index_size), view.size());
+
+        if cfg!(debug_assertions) {
+            self.i_draw.borrow_mut().i_index_buffer = Some((view.size().saturating_sub(offset), index_size));
+        }
+
+        self.keep(view.keepalive());
+
+        let dev = self.record();
 
         unsafe {
-            dev.cmd_bind_index_buffer(self.i_buffer, view.buffer(), offset, it)
+            dev.cmd_bind_index_buffer(self.i_buffer, view.buffer(), view.buffer_offset() + offset, it)
         }
     }
 
+    /// Bind the whole index buffer with its own [type](memory::IndexBuffer::index_type)
+    ///
+    /// Same as [`bind_index_buffer`](Buffer::bind_index_buffer) with zero offset
+    pub fn bind_indices(&self, indices: memory::IndexBuffer) {
+        self.bind_index_buffer(indices.view(), 0, indices.index_type());
+    }
+
     /// Add `vkCmdDraw` call to the buffer
     ///
     /// About args see [more](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdDraw.html)
+    ///
+    /// In debug builds vertices `[fv, fv + vc)` must fit into every bound vertex buffer
+    /// (see [`RangeViolation`])
     pub fn draw(&self, vc: u32, ic: u32, fv: u32, fi: u32) {
-        let dev = self.i_pool.device();
+        self.expect_vertices("draw", fv as u64 + vc as u64);
+
+        let dev = self.record();
 
         unsafe {
             dev.cmd_draw(self.i_buffer, vc, ic, fv, fi);
@@ -497,6 +2407,9 @@ impl Buffer {
     ///
     /// `first_instance` is the instance ID of the first instance to draw
     ///
+    /// In debug builds indices `[first_index, first_index + index_count)` must fit
+    /// into the bound index buffer (see [`RangeViolation`])
+    ///
     /// See [more](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdDrawIndexed.html)
     pub fn draw_indexed(
         &self,
@@ -506,7 +2419,13 @@ impl Buffer {
         vertex_offset: i32,
         first_instance: u32,
     ) {
-        let dev = self.i_pool.device();
+        if cfg!(debug_assertions) {
+            let (available, index_size) = self.i_draw.borrow().i_index_buffer.unwrap_or((0, 1));
+
+            self.expect_range("draw_indexed", None, (first_index as u64 + index_count as u64)*index_size, available);
+        }
+
+        let dev = self.record();
 
         unsafe {
             dev.cmd_draw_indexed(
@@ -520,16 +2439,121 @@ impl Buffer {
         }
     }
 
+    /// Draw `draw_count` times with parameters from [`DrawIndirectCommand`]s
+    /// located in `view` starting from `offset` bytes
+    ///
+    /// `stride` is distance in bytes between commands
+    /// (use `size_of::<DrawIndirectCommand>()` for tightly packed array)
+    ///
+    /// `draw_count` greater than 1 requires `multiDrawIndirect` device feature
+    ///
+    /// Buffer must be created with [`INDIRECT`](crate::memory::INDIRECT) usage
+    pub fn draw_indirect(&self, view: memory::View, offset: u64, draw_count: u32, stride: u32) {
+        self.keep(view.keepalive());
+
+        let dev = self.record();
+
+        unsafe {
+            dev.cmd_draw_indirect(self.i_buffer, view.buffer(), view.buffer_offset() + offset, draw_count, stride)
+        }
+    }
+
+    /// Indexed version of [`draw_indirect`](Buffer::draw_indirect)
+    /// with parameters from [`DrawIndexedIndirectCommand`]s
+    pub fn draw_indexed_indirect(&self, view: memory::View, offset: u64, draw_count: u32, stride: u32) {
+        self.keep(view.keepalive());
+
+        let dev = self.record();
+
+        unsafe {
+            dev.cmd_draw_indexed_indirect(self.i_buffer, view.buffer(), view.buffer_offset() + offset, draw_count, stride)
+        }
+    }
+
+    /// Transition to the next subpass of the current render pass
+    ///
+    /// Graphics pipeline for the next subpass must be bound after this call
+    pub fn next_subpass(&self) {
+        let dev = self.record();
+
+        unsafe {
+            dev.cmd_next_subpass(self.i_buffer, vk::SubpassContents::INLINE);
+        }
+    }
+
     /// End render pass
     ///
     /// Must be after [`begin_render_pass`](crate::cmd::Buffer::begin_render_pass)
     pub fn end_render_pass(&self) {
-        let dev = self.i_pool.device();
+        let dev = self.record();
 
         unsafe {
             dev.cmd_end_render_pass(self.i_buffer);
         }
     }
+
+    /// Begin dynamic rendering
+    /// ([see more](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCmdBeginRendering.html))
+    ///
+    /// Unlike [`begin_render_pass`](Buffer::begin_render_pass) does not require
+    /// [`RenderPass`](graphics::RenderPass) and [`Framebuffer`](memory::Framebuffer)
+    ///
+    /// Layouts are not changed so every attachment must be already transitioned
+    /// (e.g. by [`set_image_barrier`](Buffer::set_image_barrier))
+    ///
+    /// # Panics
+    ///
+    /// If device was not created with [`dynamic_rendering`](dev::DeviceCfg::dynamic_rendering)
+    pub fn begin_rendering(&self, info: &RenderingInfo) {
+        assert!(
+            self.i_pool.i_dynamic_rendering,
+            "begin_rendering requires device created with dynamic_rendering enabled"
+        );
+
+        for attachment in info.colors.iter().chain(info.depth.iter()) {
+            self.keep(attachment.view.keepalive());
+        }
+
+        let dev = self.record();
+
+        let colors: Vec<vk::RenderingAttachmentInfo> = info.colors.iter().map(|x| x.raw()).collect();
+        let depth: Option<vk::RenderingAttachmentInfo> = info.depth.map(|x| x.raw());
+
+        let rendering_info = vk::RenderingInfo {
+            s_type: vk::StructureType::RENDERING_INFO,
+            p_next: ptr::null(),
+            flags: vk::RenderingFlags::empty(),
+            render_area: vk::Rect2D {
+                offset: vk::Offset2D {
+                    x: 0,
+                    y: 0,
+                },
+                extent: info.extent,
+            },
+            layer_count: 1,
+            view_mask: 0,
+            color_attachment_count: colors.len() as u32,
+            p_color_attachments: data_ptr!(colors),
+            p_depth_attachment: depth.as_ref().map_or(ptr::null(), |x| x as *const vk::RenderingAttachmentInfo),
+            p_stencil_attachment: ptr::null(),
+            _marker: PhantomData,
+        };
+
+        unsafe {
+            dev.cmd_begin_rendering(self.i_buffer, &rendering_info)
+        };
+    }
+
+    /// End dynamic rendering
+    ///
+    /// Must be after [`begin_rendering`](crate::cmd::Buffer::begin_rendering)
+    pub fn end_rendering(&self) {
+        let dev = self.record();
+
+        unsafe {
+            dev.cmd_end_rendering(self.i_buffer);
+        }
+    }
 }
 
 impl fmt::Debug for Buffer {
@@ -542,23 +2566,127 @@ impl fmt::Debug for Buffer {
 }
 
 /// Buffer which is ready for execution
+///
+/// On drop buffer is freed and returned to the pool
+///
+/// Buffer **must not** be dropped while it is pending execution
+///
+/// Resources used by the recorded commands are released after the buffer is freed
+///
+/// Buffer is `Send` so it may be submitted and dropped on another thread,
+/// freeing is serialized with other calls of its [`Pool`]
+///
+/// If execution [is unfinished](crate::queue::QueueError::Unfinished) buffer is freed
+/// when the retire point of the submission is dropped
 pub struct ExecutableBuffer {
+    i_core: Arc<CoreBuffer>,
+    i_usage: BufferUsage,
+}
+
+/// Recorded buffer, freed with the last reference
+///
+/// Shared with [retire points](crate::queue::RetirePoint) of unfinished submissions
+struct CoreBuffer {
     i_buffer: vk::CommandBuffer,
-    i_pool: Pool,
+    i_pool: Arc<CorePool>,
+    /// Released after the buffer is freed
+    i_keepalive: Vec<dev::Keepalive>,
+}
+
+impl Drop for CoreBuffer {
+    fn drop(&mut self) {
+        self.i_pool.free(self.i_buffer);
+    }
 }
 
-#[doc(hidden)]
 impl ExecutableBuffer {
+    /// Clear recorded commands and return buffer for the new recording
+    ///
+    /// Unlike allocation of the new buffer memory of the buffer is reused,
+    /// so buffer may be re-recorded every frame
+    ///
+    /// Usage of the buffer is preserved, resources used by the previous recording are released
+    ///
+    /// Buffer **must not** be pending execution
+    ///
+    /// Returns [`BufferError::Pending`] if buffer is kept by the retire point of
+    /// [unfinished](crate::queue::QueueError::Unfinished) submission
+    ///
+    /// On error buffer is freed
+    pub fn reset(self) -> Result<Buffer, BufferError> {
+        let usage = self.i_usage;
+
+        let this = match Arc::try_unwrap(self.i_core) {
+            Ok(core) => ManuallyDrop::new(core),
+            Err(_) => return Err(BufferError::Pending)
+        };
+
+        // Drop is skipped so pool must be moved out manually
+        let pool = unsafe { ptr::read(&this.i_pool) };
+        let keepalive = unsafe { ptr::read(&this.i_keepalive) };
+        let buffer = this.i_buffer;
+
+        let reset = unsafe { pool.lock().reset_command_buffer(buffer, vk::CommandBufferResetFlags::empty()) };
+
+        let result = reset
+            .map_err(BufferError::Reset)
+            .and_then(|_| pool.begin(buffer, usage));
+
+        drop(keepalive);
+
+        if let Err(err) = result {
+            pool.free(buffer);
+
+            return Err(err);
+        }
+
+        Ok(Buffer::new(pool, buffer, usage))
+    }
+
+    #[doc(hidden)]
     pub fn buffer(&self) -> &vk::CommandBuffer {
-        &self.i_buffer
+        &self.i_core.i_buffer
+    }
+
+    pub(crate) fn keepalive(&self) -> dev::Keepalive {
+        self.i_core.clone()
+    }
+}
+
+/// Buffer which may be executed only once
+///
+/// Created by [`Buffer::commit_once`] and consumed by [`Queue::exec_once`](crate::queue::Queue::exec_once),
+/// so second submission is rejected at compile time
+///
+/// ```compile_fail
+/// use libvktypes::{cmd, queue};
+///
+/// fn submit_twice(queue: &queue::Queue, buffer: cmd::OneTimeBuffer, info: &queue::ExecInfo) {
+///     queue.exec_once(buffer, info).unwrap();
+///     queue.exec_once(buffer, info).unwrap();
+/// }
+/// ```
+pub struct OneTimeBuffer(ExecutableBuffer);
+
+impl OneTimeBuffer {
+    pub(crate) fn executable(&self) -> &ExecutableBuffer {
+        &self.0
+    }
+}
+
+impl fmt::Debug for OneTimeBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OneTimeBuffer")
+        .field(&self.0)
+        .finish()
     }
 }
 
 impl fmt::Debug for ExecutableBuffer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Buffer")
-        .field("i_buffer", &self.i_buffer)
-        .field("i_pool", &self.i_pool)
+        .field("i_buffer", &self.i_core.i_buffer)
+        .field("i_pool", &self.i_core.i_pool)
         .finish()
     }
 }
\ No newline at end of file
@@ -5,8 +5,9 @@ use ash::vk;
 use crate::dev;
 use crate::memory;
 use crate::shader;
+use crate::graphics;
 
-use crate::{on_error, on_error_ret};
+use crate::{on_error, on_error_ret, data_ptr};
 
 use std::sync::Arc;
 use std::{fmt, ptr};
@@ -15,9 +16,20 @@ use std::marker::PhantomData;
 
 /// Note: only [memory](crate::memory::Memory) with memory::UsageFlags::STORAGE_BUFFER is allowed
 pub struct PipelineCfg<'a, 'b : 'a> {
+    /// Storage buffers which are bound to `set = 0` in order of `binding`
+    ///
+    /// Ignored if [`descriptor`](PipelineCfg::descriptor) is set
     pub buffers: &'a [memory::View<'b>],
+    /// Descriptor with arbitrary number of sets
+    ///
+    /// Set it to `None` to use [`buffers`](PipelineCfg::buffers) instead
+    pub descriptor: Option<&'a graphics::PipelineDescriptor>,
     pub shader: &'a shader::Shader,
+    /// Values of specialization constants
+    pub specialization: Option<&'a shader::SpecializationInfo<'a>>,
     pub push_constant_size : u32,
+    /// Optional cache which is used during pipeline creation
+    pub cache: Option<&'a dev::PipelineCache>,
 }
 
 #[derive(Debug)]
@@ -27,6 +39,9 @@ pub enum PipelineError {
     DescriptorSet,
     PipelineLayout,
     PipelineCache,
+    /// Entry of [`specialization`](PipelineCfg::specialization) is out of its data,
+    /// see [`SpecializationInfo::is_valid`](shader::SpecializationInfo::is_valid)
+    Specialization,
     Pipeline
 }
 
@@ -48,6 +63,9 @@ impl fmt::Display for PipelineError {
             PipelineError::PipelineCache => {
                 "Failed to create pipeline cache (vkCreatePipelineCache call failed)"
             },
+            PipelineError::Specialization => {
+                "Specialization constant is out of data range"
+            },
             PipelineError::Pipeline => {
                 "Failed to create pipeline (vkCreatePipeline call failed)"
             }
@@ -59,91 +77,99 @@ impl fmt::Display for PipelineError {
 
 impl Error for PipelineError {}
 
-/// Represents compute pipeline
-pub struct Pipeline {
+struct CorePipeline {
     i_core:            Arc<dev::Core>,
     i_pipeline_layout: vk::PipelineLayout,
     i_desc_set_layout: vk::DescriptorSetLayout,
     i_desc_set:        vk::DescriptorSet,
     i_desc_pool:       vk::DescriptorPool,
     i_pipeline:        vk::Pipeline,
-    i_pipeline_cache:  vk::PipelineCache,
+    i_push_constants:  Vec<vk::PushConstantRange>,
+    /// Buffers written into internal descriptor set
+    #[allow(dead_code)]
+    i_buffers:         Vec<dev::Keepalive>,
+}
+
+impl Drop for CorePipeline {
+    fn drop(&mut self) {
+        let device = self.i_core.device();
+        let alloc = self.i_core.allocator();
+
+        unsafe {
+            device.destroy_pipeline(self.i_pipeline, alloc);
+            device.destroy_pipeline_layout(self.i_pipeline_layout, alloc);
+            device.destroy_descriptor_set_layout(self.i_desc_set_layout, alloc);
+        }
+
+        self.i_core.destroyed(dev::ObjectType::Pipeline, 1);
+
+        free_descriptor_pool(&self.i_core, self.i_desc_pool);
+    }
 }
 
+/// Destroy internal descriptor pool (if any)
+fn free_descriptor_pool(core: &dev::Core, desc_pool: vk::DescriptorPool) {
+    if desc_pool == vk::DescriptorPool::null() {
+        return;
+    }
+
+    unsafe { core.device().destroy_descriptor_pool(desc_pool, core.allocator()) };
+
+    core.destroyed(dev::ObjectType::DescriptorPool, 1);
+}
+
+/// Represents compute pipeline
+pub struct Pipeline(Arc<CorePipeline>);
+
 // TODO provide dynamic buffer binding
 // TODO shader module must outlive pipeline?
 impl Pipeline {
+    /// Create compute pipeline
+    ///
+    /// If [`descriptor`](PipelineCfg::descriptor) is set then its sets are used as pipeline layout
+    /// and must be bound via [`bind_compute_resources`](crate::cmd::Buffer::bind_compute_resources)
+    ///
+    /// Otherwise single set with [`buffers`](PipelineCfg::buffers) is created
+    /// and bound by [`bind_compute_pipeline`](crate::cmd::Buffer::bind_compute_pipeline)
     pub fn new(device: &dev::Device, pipe_type: &PipelineCfg) -> Result<Pipeline, PipelineError> {
-        let desc_size:[vk::DescriptorPoolSize; 1] =
-        [
-            vk::DescriptorPoolSize {
-                ty: vk::DescriptorType::STORAGE_BUFFER,
-                descriptor_count: pipe_type.buffers.len() as u32,
-            }
-        ];
-
-        let pool_size: u32 = 1;
-
-        // So max_sets is how many *sets* we can possibly allocate
-        // While PoolSize defines how many *descriptors* we can allocate
-        // within single set ?
-        let desc_info = vk::DescriptorPoolCreateInfo {
-            s_type: vk::StructureType::DESCRIPTOR_POOL_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::DescriptorPoolCreateFlags::empty(),
-            max_sets: pool_size,
-            pool_size_count: desc_size.len() as u32,
-            p_pool_sizes: desc_size.as_ptr(),
-            _marker: PhantomData,
-        };
-
-        let desc_pool = on_error_ret!(
-            unsafe { device.device().create_descriptor_pool(&desc_info, device.allocator()) },
-            PipelineError::DescriptorPool
+        debug_assert!(
+            pipe_type.descriptor.is_some()
+                || pipe_type.buffers.iter().all(|x| x.usage().contains(memory::BufferUsageFlags::STORAGE_BUFFER)),
+            "Buffers must be created with STORAGE_BUFFER usage"
         );
 
-        let bindings: Vec<vk::DescriptorSetLayoutBinding> = pipe_type.buffers.iter().enumerate().map(
-            |(i, _)| vk::DescriptorSetLayoutBinding {
-                binding: i as u32,
-                descriptor_type: vk::DescriptorType::STORAGE_BUFFER,
-                descriptor_count: 1,
-                stage_flags: vk::ShaderStageFlags::COMPUTE,
-                p_immutable_samplers: ptr::null(),
-                _marker: PhantomData,
-            }
-        ).collect();
+        if pipe_type.specialization.is_some_and(|spec| !spec.is_valid()) {
+            return Err(PipelineError::Specialization);
+        }
 
-        let desc_layout_info = vk::DescriptorSetLayoutCreateInfo {
-            s_type: vk::StructureType::DESCRIPTOR_SET_LAYOUT_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::DescriptorSetLayoutCreateFlags::empty(),
-            binding_count: bindings.len() as u32,
-            p_bindings: bindings.as_ptr(),
-            _marker: PhantomData,
+        let (desc_pool, desc_set_layout, desc_set) = match pipe_type.descriptor {
+            Some(_) => (vk::DescriptorPool::null(), vk::DescriptorSetLayout::null(), vk::DescriptorSet::null()),
+            None => create_buffers_set(device, pipe_type.buffers)?
         };
 
-        let desc_set_layout = unsafe { on_error!(
-            device.device().create_descriptor_set_layout(&desc_layout_info, device.allocator()),
-            {
-                device.device().destroy_descriptor_pool(desc_pool, device.allocator());
-                return Err(PipelineError::DescriptorSetLayout);
-            }
-        )};
+        let set_layouts: Vec<vk::DescriptorSetLayout> = match pipe_type.descriptor {
+            Some(desc) => desc.descriptor_layouts().to_vec(),
+            None => vec![desc_set_layout]
+        };
 
-        let push_const_range = vk::PushConstantRange {
-            stage_flags: vk::ShaderStageFlags::COMPUTE,
-            offset: 0,
-            size: pipe_type.push_constant_size,
+        let push_const_ranges: Vec<vk::PushConstantRange> = if pipe_type.push_constant_size != 0 {
+            vec![vk::PushConstantRange {
+                stage_flags: vk::ShaderStageFlags::COMPUTE,
+                offset: 0,
+                size: pipe_type.push_constant_size,
+            }]
+        } else {
+            Vec::new()
         };
 
         let pipeline_layout_info = vk::PipelineLayoutCreateInfo {
             s_type: vk::StructureType::PIPELINE_LAYOUT_CREATE_INFO,
             p_next: ptr::null(),
             flags: vk::PipelineLayoutCreateFlags::empty(),
-            set_layout_count: 1,
-            p_set_layouts: &desc_set_layout,
-            push_constant_range_count: if pipe_type.push_constant_size != 0 { 1 } else { 0 },
-            p_push_constant_ranges: if pipe_type.push_constant_size != 0 { &push_const_range } else { ptr::null() },
+            set_layout_count: set_layouts.len() as u32,
+            p_set_layouts: data_ptr!(set_layouts),
+            push_constant_range_count: push_const_ranges.len() as u32,
+            p_push_constant_ranges: data_ptr!(push_const_ranges),
             _marker: PhantomData,
         };
 
@@ -151,85 +177,16 @@ impl Pipeline {
             device.device().create_pipeline_layout(&pipeline_layout_info, device.allocator()),
             {
                 device.device().destroy_descriptor_set_layout(desc_set_layout, device.allocator());
-                device.device().destroy_descriptor_pool(desc_pool, device.allocator());
+                free_descriptor_pool(device.core(), desc_pool);
                 return Err(PipelineError::PipelineLayout);
             }
         )};
 
-        let alloc_info = vk::DescriptorSetAllocateInfo {
-            s_type: vk::StructureType::DESCRIPTOR_SET_ALLOCATE_INFO,
-            p_next: ptr::null(),
-            descriptor_pool: desc_pool,
-            descriptor_set_count: 1,
-            p_set_layouts: &desc_set_layout,
-            _marker: PhantomData,
-        };
-
-        let desc_set = unsafe { on_error!(
-            device.device().allocate_descriptor_sets(&alloc_info),
-            {
-                device.device().destroy_pipeline_layout(pipeline_layout, device.allocator());
-                device.device().destroy_descriptor_set_layout(desc_set_layout, device.allocator());
-                device.device().destroy_descriptor_pool(desc_pool, device.allocator());
-                return Err(PipelineError::DescriptorSet);
-            }
-        )};
-
-        let mut offset_counter = 0u64;
-        let mut buffer_descs: Vec<vk::DescriptorBufferInfo> = Vec::new();
+        let spec_entries = pipe_type.specialization.map(|spec| spec.map_entries());
 
-        for buffer in pipe_type.buffers {
-            buffer_descs.push(
-                    vk::DescriptorBufferInfo {
-                    buffer: buffer.buffer(),
-                    offset: offset_counter,
-                    range: vk::WHOLE_SIZE
-                }
-            );
-
-            offset_counter += buffer.size();
-        }
-
-        // TODO big question can we update set with single vk::WriteDescriptorSet?
-        // by setting descriptor_count
-        // what will be with dst_binding?
-        // how we access in shader?
-        let write_desc: Vec<vk::WriteDescriptorSet> = pipe_type.buffers.iter().enumerate().map(
-            |(i, _)| vk::WriteDescriptorSet {
-                s_type: vk::StructureType::WRITE_DESCRIPTOR_SET,
-                p_next: ptr::null(),
-                dst_set: desc_set[0],
-                dst_binding: i as u32,
-                dst_array_element: 0,
-                descriptor_count: 1,
-                descriptor_type: vk::DescriptorType::STORAGE_BUFFER,
-                p_image_info: ptr::null(),
-                p_buffer_info: &buffer_descs[i],
-                p_texel_buffer_view: ptr::null(),
-                _marker: PhantomData,
-            }
-        ).collect();
-
-        unsafe { device.device().update_descriptor_sets(&write_desc, &[]) };
-
-        let pipeline_cache_info = vk::PipelineCacheCreateInfo {
-            s_type: vk::StructureType::PIPELINE_CACHE_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::PipelineCacheCreateFlags::empty(),
-            initial_data_size: 0,
-            p_initial_data: ptr::null(),
-            _marker: PhantomData,
-        };
-
-        let pipeline_cache = unsafe { on_error!(
-            device.device().create_pipeline_cache(&pipeline_cache_info, device.allocator()),
-            {
-                device.device().destroy_pipeline_layout(pipeline_layout, device.allocator());
-                device.device().destroy_descriptor_set_layout(desc_set_layout, device.allocator());
-                device.device().destroy_descriptor_pool(desc_pool, device.allocator());
-                return Err(PipelineError::PipelineCache);
-            }
-        )};
+        let spec_info = pipe_type.specialization
+            .zip(spec_entries.as_ref())
+            .map(|(spec, entries)| spec.raw(entries));
 
         let pipeline_shader = vk::PipelineShaderStageCreateInfo {
             s_type: vk::StructureType::PIPELINE_SHADER_STAGE_CREATE_INFO,
@@ -238,7 +195,10 @@ impl Pipeline {
             stage: vk::ShaderStageFlags::COMPUTE,
             module: pipe_type.shader.module(),
             p_name: pipe_type.shader.entry().as_ptr(),
-            p_specialization_info: ptr::null(),
+            p_specialization_info: match &spec_info {
+                Some(info) => info,
+                None => ptr::null()
+            },
             _marker: PhantomData,
         };
 
@@ -254,56 +214,186 @@ impl Pipeline {
         };
 
         let pipelines = unsafe { on_error!(
-            device.device().create_compute_pipelines(pipeline_cache, &[pipeline_info], device.allocator()),
+            device.device().create_compute_pipelines(
+                pipe_type.cache.map_or(vk::PipelineCache::null(), |x| x.cache()),
+                &[pipeline_info],
+                device.allocator()
+            ),
             {
-                device.device().destroy_pipeline_cache(pipeline_cache, device.allocator());
                 device.device().destroy_pipeline_layout(pipeline_layout, device.allocator());
                 device.device().destroy_descriptor_set_layout(desc_set_layout, device.allocator());
-                device.device().destroy_descriptor_pool(desc_pool, device.allocator());
+                free_descriptor_pool(device.core(), desc_pool);
                 return Err(PipelineError::Pipeline);
             }
         )};
 
+        device.core().created(dev::ObjectType::Pipeline, 1);
+
+        let buffers = match pipe_type.descriptor {
+            Some(_) => Vec::new(),
+            None => pipe_type.buffers.iter().map(|x| x.keepalive()).collect()
+        };
+
         Ok(
-            Pipeline {
-                i_core: device.core().clone(),
-                i_pipeline_layout: pipeline_layout,
-                i_desc_set_layout: desc_set_layout,
-                i_desc_set: desc_set[0],
-                i_desc_pool: desc_pool,
-                i_pipeline: pipelines[0],
-                i_pipeline_cache: pipeline_cache,
-            }
+            Pipeline(
+                Arc::new(CorePipeline {
+                    i_core: device.core().clone(),
+                    i_pipeline_layout: pipeline_layout,
+                    i_desc_set_layout: desc_set_layout,
+                    i_desc_set: desc_set,
+                    i_desc_pool: desc_pool,
+                    i_pipeline: pipelines[0],
+                    i_push_constants: push_const_ranges,
+                    i_buffers: buffers,
+                })
+            )
         )
     }
 
+    /// Return internal descriptor set
+    ///
+    /// Null if pipeline was created with [`descriptor`](PipelineCfg::descriptor)
     #[doc(hidden)]
     pub fn descriptor_set(&self) -> vk::DescriptorSet {
-        self.i_desc_set
+        self.0.i_desc_set
     }
 
     #[doc(hidden)]
     pub fn pipeline_layout(&self) -> vk::PipelineLayout {
-        self.i_pipeline_layout
+        self.0.i_pipeline_layout
     }
 
     #[doc(hidden)]
     pub fn pipeline(&self) -> vk::Pipeline {
-        self.i_pipeline
+        self.0.i_pipeline
     }
-}
 
-impl Drop for Pipeline {
-    fn drop(&mut self) {
-        let device = self.i_core.device();
-        let alloc = self.i_core.allocator();
+    #[doc(hidden)]
+    pub fn push_constant_ranges(&self) -> &[vk::PushConstantRange] {
+        &self.0.i_push_constants
+    }
 
-        unsafe {
-            device.destroy_pipeline(self.i_pipeline, alloc);
-            device.destroy_pipeline_cache(self.i_pipeline_cache, alloc);
-            device.destroy_pipeline_layout(self.i_pipeline_layout, alloc);
-            device.destroy_descriptor_set_layout(self.i_desc_set_layout, alloc);
-            device.destroy_descriptor_pool(self.i_desc_pool, alloc);
+    pub(crate) fn keepalive(&self) -> dev::Keepalive {
+        self.0.clone()
+    }
+}
+
+/// Create descriptor set with storage buffers
+fn create_buffers_set(
+    device: &dev::Device,
+    buffers: &[memory::View]
+) -> Result<(vk::DescriptorPool, vk::DescriptorSetLayout, vk::DescriptorSet), PipelineError> {
+    let desc_size:[vk::DescriptorPoolSize; 1] =
+    [
+        vk::DescriptorPoolSize {
+            ty: vk::DescriptorType::STORAGE_BUFFER,
+            descriptor_count: buffers.len() as u32,
+        }
+    ];
+
+    let pool_size: u32 = 1;
+
+    // So max_sets is how many *sets* we can possibly allocate
+    // While PoolSize defines how many *descriptors* we can allocate
+    // within single set ?
+    let desc_info = vk::DescriptorPoolCreateInfo {
+        s_type: vk::StructureType::DESCRIPTOR_POOL_CREATE_INFO,
+        p_next: ptr::null(),
+        flags: vk::DescriptorPoolCreateFlags::empty(),
+        max_sets: pool_size,
+        pool_size_count: desc_size.len() as u32,
+        p_pool_sizes: desc_size.as_ptr(),
+        _marker: PhantomData,
+    };
+
+    let desc_pool = on_error_ret!(
+        unsafe { device.device().create_descriptor_pool(&desc_info, device.allocator()) },
+        PipelineError::DescriptorPool
+    );
+
+    device.core().created(dev::ObjectType::DescriptorPool, 1);
+
+    let bindings: Vec<vk::DescriptorSetLayoutBinding> = buffers.iter().enumerate().map(
+        |(i, _)| vk::DescriptorSetLayoutBinding {
+            binding: i as u32,
+            descriptor_type: vk::DescriptorType::STORAGE_BUFFER,
+            descriptor_count: 1,
+            stage_flags: vk::ShaderStageFlags::COMPUTE,
+            p_immutable_samplers: ptr::null(),
+            _marker: PhantomData,
+        }
+    ).collect();
+
+    let desc_layout_info = vk::DescriptorSetLayoutCreateInfo {
+        s_type: vk::StructureType::DESCRIPTOR_SET_LAYOUT_CREATE_INFO,
+        p_next: ptr::null(),
+        flags: vk::DescriptorSetLayoutCreateFlags::empty(),
+        binding_count: bindings.len() as u32,
+        p_bindings: bindings.as_ptr(),
+        _marker: PhantomData,
+    };
+
+    let desc_set_layout = unsafe { on_error!(
+        device.device().create_descriptor_set_layout(&desc_layout_info, device.allocator()),
+        {
+            free_descriptor_pool(device.core(), desc_pool);
+            return Err(PipelineError::DescriptorSetLayout);
+        }
+    )};
+
+    let alloc_info = vk::DescriptorSetAllocateInfo {
+        s_type: vk::StructureType::DESCRIPTOR_SET_ALLOCATE_INFO,
+        p_next: ptr::null(),
+        descriptor_pool: desc_pool,
+        descriptor_set_count: 1,
+        p_set_layouts: &desc_set_layout,
+        _marker: PhantomData,
+    };
+
+    let desc_set = unsafe { on_error!(
+        device.device().allocate_descriptor_sets(&alloc_info),
+        {
+            device.device().destroy_descriptor_set_layout(desc_set_layout, device.allocator());
+            free_descriptor_pool(device.core(), desc_pool);
+            return Err(PipelineError::DescriptorSet);
         }
+    )};
+
+    let mut buffer_descs: Vec<vk::DescriptorBufferInfo> = Vec::new();
+
+    for buffer in buffers {
+        let (offset, range) = buffer.buffer_range(0, vk::WHOLE_SIZE);
+
+        buffer_descs.push(
+                vk::DescriptorBufferInfo {
+                buffer: buffer.buffer(),
+                offset,
+                range
+            }
+        );
     }
-}
\ No newline at end of file
+
+    // TODO big question can we update set with single vk::WriteDescriptorSet?
+    // by setting descriptor_count
+    // what will be with dst_binding?
+    // how we access in shader?
+    let write_desc: Vec<vk::WriteDescriptorSet> = buffers.iter().enumerate().map(
+        |(i, _)| vk::WriteDescriptorSet {
+            s_type: vk::StructureType::WRITE_DESCRIPTOR_SET,
+            p_next: ptr::null(),
+            dst_set: desc_set[0],
+            dst_binding: i as u32,
+            dst_array_element: 0,
+            descriptor_count: 1,
+            descriptor_type: vk::DescriptorType::STORAGE_BUFFER,
+            p_image_info: ptr::null(),
+            p_buffer_info: &buffer_descs[i],
+            p_texel_buffer_view: ptr::null(),
+            _marker: PhantomData,
+        }
+    ).collect();
+
+    unsafe { device.device().update_descriptor_sets(&write_desc, &[]) };
+
+    Ok((desc_pool, desc_set_layout, desc_set[0]))
+}
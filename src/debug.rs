@@ -1,5 +1,8 @@
 use ash::vk;
 
+use crate::layers::DebugState;
+
+use std::sync::atomic::Ordering;
 use std::ffi::{
     c_void,
     CStr
@@ -7,12 +10,32 @@ use std::ffi::{
 
 /// The callback function used in Debug Utils
 /// # Safety
+///
+/// `p_user_data` must be either null or point to the alive `DebugState`
 pub unsafe extern "system" fn vulkan_debug_utils_callback(
     message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
     message_type: vk::DebugUtilsMessageTypeFlagsEXT,
     p_callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
-    _p_user_data: *mut c_void,
+    p_user_data: *mut c_void,
 ) -> vk::Bool32 {
+    let message = CStr::from_ptr((*p_callback_data).p_message);
+
+    let state = (p_user_data as *const DebugState).as_ref();
+
+    if let Some(state) = state {
+        match message_severity {
+            vk::DebugUtilsMessageSeverityFlagsEXT::ERROR => state.errors.fetch_add(1, Ordering::Relaxed),
+            vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => state.warnings.fetch_add(1, Ordering::Relaxed),
+            _ => 0
+        };
+
+        if let Some(callback) = &state.callback {
+            callback(message_severity, message_type, &message.to_string_lossy());
+
+            return vk::FALSE;
+        }
+    }
+
     let severity = match message_severity {
         vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE => "[Verbose]",
         vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => "[Warning]",
@@ -28,9 +51,7 @@ pub unsafe extern "system" fn vulkan_debug_utils_callback(
         _ => "[Unknown]",
     };
 
-    let message = CStr::from_ptr((*p_callback_data).p_message);
-
     println!("[Debug]{}{}{:?}", severity, types, message);
 
     vk::FALSE
-}
\ No newline at end of file
+}
@@ -1,20 +1,160 @@
+use ash::vk;
+
 use crate::{libvk, alloc};
 
+use std::any::Any;
 use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::fmt;
 
+/// Shared owner of the Vulkan object
+///
+/// Object is destroyed when the last handle is dropped
+#[doc(hidden)]
+pub type Keepalive = Arc<dyn Any + Send + Sync>;
+
+/// Type of the Vulkan object counted by [object tracking](crate::dev::DeviceCfg::track_objects)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectType {
+    Buffer,
+    Image,
+    ImageView,
+    Pipeline,
+    Framebuffer,
+    CommandPool,
+    Semaphore,
+    Fence,
+    DescriptorPool,
+}
+
+const OBJECT_TYPES: usize = 9;
+
+/// Number of live objects of each type
+///
+/// See [`Device::live_objects`](crate::dev::Device::live_objects)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ObjectReport {
+    pub buffers: usize,
+    pub images: usize,
+    pub image_views: usize,
+    pub pipelines: usize,
+    pub framebuffers: usize,
+    pub command_pools: usize,
+    pub semaphores: usize,
+    pub fences: usize,
+    pub descriptor_pools: usize,
+}
+
+impl ObjectReport {
+    /// Number of live objects of the given type
+    pub fn get(&self, object: ObjectType) -> usize {
+        match object {
+            ObjectType::Buffer => self.buffers,
+            ObjectType::Image => self.images,
+            ObjectType::ImageView => self.image_views,
+            ObjectType::Pipeline => self.pipelines,
+            ObjectType::Framebuffer => self.framebuffers,
+            ObjectType::CommandPool => self.command_pools,
+            ObjectType::Semaphore => self.semaphores,
+            ObjectType::Fence => self.fences,
+            ObjectType::DescriptorPool => self.descriptor_pools,
+        }
+    }
+
+    /// Total number of live objects
+    pub fn total(&self) -> usize {
+        self.buffers
+        + self.images
+        + self.image_views
+        + self.pipelines
+        + self.framebuffers
+        + self.command_pools
+        + self.semaphores
+        + self.fences
+        + self.descriptor_pools
+    }
+
+    /// Are there no live objects
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+}
+
+/// Counters of created and destroyed objects
+struct ObjectCounters {
+    i_created: [AtomicUsize; OBJECT_TYPES],
+    i_destroyed: [AtomicUsize; OBJECT_TYPES],
+}
+
+impl ObjectCounters {
+    fn new() -> ObjectCounters {
+        ObjectCounters {
+            i_created: Default::default(),
+            i_destroyed: Default::default(),
+        }
+    }
+
+    fn live(&self, object: ObjectType) -> usize {
+        let created = self.i_created[object as usize].load(Ordering::Acquire);
+        let destroyed = self.i_destroyed[object as usize].load(Ordering::Acquire);
+
+        created.saturating_sub(destroyed)
+    }
+
+    fn report(&self) -> ObjectReport {
+        ObjectReport {
+            buffers: self.live(ObjectType::Buffer),
+            images: self.live(ObjectType::Image),
+            image_views: self.live(ObjectType::ImageView),
+            pipelines: self.live(ObjectType::Pipeline),
+            framebuffers: self.live(ObjectType::Framebuffer),
+            command_pools: self.live(ObjectType::CommandPool),
+            semaphores: self.live(ObjectType::Semaphore),
+            fences: self.live(ObjectType::Fence),
+            descriptor_pools: self.live(ObjectType::DescriptorPool),
+        }
+    }
+}
+
 #[doc(hidden)]
 pub struct Core {
     i_device: ash::Device,
     i_callback: Option<alloc::Callback>,
+    i_limits: vk::PhysicalDeviceLimits,
+    i_lost: AtomicBool,
+    i_objects: Option<ObjectCounters>,
+    /// Lock of every created queue by family index
+    i_queues: Vec<(u32, Vec<Mutex<()>>)>,
     _marker: PhantomData<*const libvk::Instance>
 }
 
+// Device level functions are thread-safe by Vulkan spec
+// Objects which require external synchronization (pools, queues) are guarded by their own locks
+// Allocation callbacks must be thread-safe, see
+// https://registry.khronos.org/vulkan/specs/1.3-extensions/html/vkspec.html#memory-host
+unsafe impl Send for Core {}
+
+unsafe impl Sync for Core {}
+
 impl Core {
-    pub fn new(device: ash::Device, callback: Option<alloc::Callback>) -> Core {
+    pub fn new(
+        device: ash::Device,
+        callback: Option<alloc::Callback>,
+        limits: vk::PhysicalDeviceLimits,
+        track_objects: bool,
+        queues: &[(u32, u32)]
+    ) -> Core {
         Core {
             i_device: device,
             i_callback: callback,
+            i_limits: limits,
+            i_lost: AtomicBool::new(false),
+            i_objects: if track_objects { Some(ObjectCounters::new()) } else { None },
+            i_queues: queues
+                .iter()
+                .map(|&(family, count)| (family, (0..count).map(|_| Mutex::new(())).collect()))
+                .collect(),
             _marker: PhantomData
         }
     }
@@ -26,6 +166,64 @@ impl Core {
     pub fn allocator(&self) -> Option<&alloc::Callback> {
         self.i_callback.as_ref()
     }
+
+    /// Limits of the physical device which device was created from
+    pub fn limits(&self) -> &vk::PhysicalDeviceLimits {
+        &self.i_limits
+    }
+
+    /// Was `VK_ERROR_DEVICE_LOST` observed by any call
+    pub fn is_lost(&self) -> bool {
+        self.i_lost.load(Ordering::Acquire)
+    }
+
+    /// Record result of the Vulkan call
+    ///
+    /// Returns `true` if `result` is `VK_ERROR_DEVICE_LOST`
+    pub fn observe(&self, result: vk::Result) -> bool {
+        if result == vk::Result::ERROR_DEVICE_LOST {
+            self.i_lost.store(true, Ordering::Release);
+            return true;
+        }
+
+        false
+    }
+
+    /// Record creation of `count` objects
+    ///
+    /// Does nothing if object tracking is disabled
+    pub fn created(&self, object: ObjectType, count: usize) {
+        if let Some(objects) = &self.i_objects {
+            objects.i_created[object as usize].fetch_add(count, Ordering::AcqRel);
+        }
+    }
+
+    /// Record destruction of `count` objects
+    ///
+    /// Does nothing if object tracking is disabled
+    pub fn destroyed(&self, object: ObjectType, count: usize) {
+        if let Some(objects) = &self.i_objects {
+            objects.i_destroyed[object as usize].fetch_add(count, Ordering::AcqRel);
+        }
+    }
+
+    /// Lock queue `index` of the family `family` for submission or presentation
+    ///
+    /// Every handle of the same `vkQueue` uses the same lock
+    ///
+    /// Returns `None` if queue was not created with the device
+    pub fn lock_queue(&self, family: u32, index: u32) -> Option<MutexGuard<'_, ()>> {
+        self.i_queues
+        .iter()
+        .find(|(f, _)| *f == family)
+        .and_then(|(_, locks)| locks.get(index as usize))
+        .map(|lock| lock.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Snapshot of live objects, `None` if object tracking is disabled
+    pub fn live_objects(&self) -> Option<ObjectReport> {
+        self.i_objects.as_ref().map(ObjectCounters::report)
+    }
 }
 
 impl fmt::Debug for Core {
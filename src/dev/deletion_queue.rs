@@ -0,0 +1,95 @@
+//! Deferred destruction of resources which are still used by the GPU
+//!
+//! Dropping [memory](crate::memory::Memory), [images](crate::memory::ImageMemory),
+//! [pipelines](crate::graphics::Pipeline) or [executable buffers](crate::cmd::ExecutableBuffer)
+//! while submission is in flight requires waiting for the whole device.
+//! Instead resources may be moved into [`DeletionQueue`] with the
+//! [retire point](crate::queue::RetirePoint) of the submission
+//!
+//! ```ignore
+//! // Buffer is kept alive by the deletion queue until the point is reached
+//! let point = unsafe { queue.submit(&exec_info)?.into_retire_point() }.unwrap();
+//!
+//! deletion_queue.defer_drop(exec_buffer, &point);
+//! deletion_queue.defer_drop(frame_memory, &point);
+//!
+//! // Once per frame
+//! deletion_queue.collect();
+//! ```
+
+use crate::queue;
+
+use std::any::Any;
+use std::fmt;
+
+/// Resources waiting for their submissions
+///
+/// Remaining resources are destroyed on drop after waiting for their retire points
+#[derive(Default)]
+pub struct DeletionQueue {
+    i_pending: Vec<(queue::RetirePoint, Box<dyn Any>)>,
+}
+
+impl DeletionQueue {
+    pub fn new() -> DeletionQueue {
+        DeletionQueue::default()
+    }
+
+    /// Keep `resource` alive until `point` is reached
+    ///
+    /// Any owned resource is accepted, e.g. [`Memory`](crate::memory::Memory),
+    /// [`ImageMemory`](crate::memory::ImageMemory), [`Pipeline`](crate::graphics::Pipeline),
+    /// [`Framebuffer`](crate::memory::Framebuffer) or [`ExecutableBuffer`](crate::cmd::ExecutableBuffer)
+    pub fn defer_drop<T: 'static>(&mut self, resource: T, point: &queue::RetirePoint) {
+        self.i_pending.push((point.clone(), Box::new(resource)));
+    }
+
+    /// Destroy resources whose retire points are reached
+    ///
+    /// Does not block, returns number of destroyed resources
+    pub fn collect(&mut self) -> usize {
+        let count = self.i_pending.len();
+
+        self.i_pending.retain(|(point, _)| !point.is_reached());
+
+        count - self.i_pending.len()
+    }
+
+    /// Wait for every retire point and destroy all resources
+    ///
+    /// On error resources which are not retired stay in queue
+    pub fn flush(&mut self) -> Result<(), queue::QueueError> {
+        for (point, _) in &self.i_pending {
+            point.wait(u64::MAX)?;
+        }
+
+        self.i_pending.clear();
+
+        Ok(())
+    }
+
+    /// Number of resources waiting for destruction
+    pub fn len(&self) -> usize {
+        self.i_pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.i_pending.is_empty()
+    }
+}
+
+impl Drop for DeletionQueue {
+    fn drop(&mut self) {
+        for (point, _) in &self.i_pending {
+            let _ = point.wait(u64::MAX);
+        }
+    }
+}
+
+impl fmt::Debug for DeletionQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeletionQueue")
+        .field("pending", &self.i_pending.len())
+        .finish()
+    }
+}
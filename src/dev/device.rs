@@ -5,73 +5,204 @@
 use ash::vk;
 
 use crate::{libvk, hw, alloc, queue, dev};
-use crate::on_error_ret;
+use crate::{on_error, on_error_ret};
 
 use std::sync::Arc;
 use std::{ptr, fmt};
 use std::error::Error;
 use std::marker::PhantomData;
 
+/// Queues to create within single family
+///
+/// Number of queues is `priorities.len()`
+///
+/// Each priority must be within `[0.0, 1.0]`,
+/// otherwise [`Device::new`] returns [`DeviceError::QueuePriority`]
+#[derive(Debug, Clone, Copy)]
+pub struct QueueRequest<'a> {
+    pub family_index: u32,
+    pub priorities: &'a [f32],
+}
+
+impl QueueRequest<'static> {
+    /// Request single queue from family `family_index`
+    pub fn single(family_index: u32) -> QueueRequest<'static> {
+        QueueRequest {
+            family_index,
+            priorities: &[1.0],
+        }
+    }
+}
+
 /// Device configuration structure
 ///
-/// Note: on queue creation: every queue family in [`hw`](self::DeviceCfg::hw)
+/// Note: if [`queues`](self::DeviceCfg::queues) is empty then every queue family in [`hw`](self::DeviceCfg::hw)
 /// will be enabled and every queue within family will have equal priority
 pub struct DeviceCfg<'a> {
     pub lib: &'a libvk::Instance,
     pub hw: &'a hw::HWDevice,
     pub extensions: &'a [*const i8],
+    /// Queue families and queues to create
+    ///
+    /// Each family must be requested at most once,
+    /// otherwise [`Device::new`] returns [`DeviceError::DuplicateQueueFamily`]
+    pub queues: &'a [QueueRequest<'a>],
+    /// Features to enable
+    ///
+    /// Use [`hw::HWDevice::supports_features`] to check them before device creation
+    ///
+    /// `*hw.features()` enables every supported feature
+    pub features: hw::Features,
+    /// Enable `dynamicRendering` feature
+    ///
+    /// Allows to render without [`RenderPass`](crate::graphics::RenderPass) and [`Framebuffer`](crate::memory::Framebuffer)
+    /// (see [`cmd::Buffer::begin_rendering`](crate::cmd::Buffer::begin_rendering))
+    ///
+    /// Requires Vulkan 1.3 (both [instance](crate::libvk::InstanceType::version_minor) and [device](hw::HWDevice::version)),
+    /// otherwise [`Device::new`] returns [`DeviceError::DynamicRenderingUnsupported`]
+    ///
+    /// See [`hw::HWDevice::supports_dynamic_rendering`]
+    pub dynamic_rendering: bool,
+    /// Host memory allocator for the device and every object created from it
+    ///
+    /// If `None` [allocator of the instance](libvk::InstanceType::allocator) is used
     pub allocator: Option<alloc::Callback>,
+    /// Count created and destroyed Vulkan objects
+    ///
+    /// Debug option, see [`Device::live_objects`]
+    pub track_objects: bool,
 }
 
 #[derive(Debug)]
 pub enum DeviceError {
     Creating,
+    /// [`dynamic_rendering`](DeviceCfg::dynamic_rendering) was requested
+    /// but instance or device version is lower than 1.3 or feature is not supported
+    DynamicRenderingUnsupported,
+    /// Queue family with this index is requested more than once
+    DuplicateQueueFamily(u32),
+    /// Queue priority is outside of `[0.0, 1.0]`
+    QueuePriority(f32),
+    /// Failed to [wait](Device::wait_idle) for device
+    WaitIdle(vk::Result),
+    /// Device was lost, see [`Device::is_lost`]
+    DeviceLost,
 }
 
 impl fmt::Display for DeviceError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Failed to create Device (vkCreateDevice call failed)")
+        match self {
+            DeviceError::Creating => write!(f, "Failed to create Device (vkCreateDevice call failed)"),
+            DeviceError::DynamicRenderingUnsupported => write!(f, "Dynamic rendering requires Vulkan 1.3 and dynamicRendering feature"),
+            DeviceError::DuplicateQueueFamily(family) => write!(f, "Queue family {} is requested more than once", family),
+            DeviceError::QueuePriority(priority) => write!(f, "Queue priority {} is outside of [0.0, 1.0]", priority),
+            DeviceError::WaitIdle(err) => write!(f, "vkDeviceWaitIdle call failed ({})", err),
+            DeviceError::DeviceLost => write!(f, "Device was lost (VK_ERROR_DEVICE_LOST)"),
+        }
     }
 }
 
-impl Error for DeviceError {}
+impl Error for DeviceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DeviceError::WaitIdle(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Map result of the failed call to [`DeviceError`]
+///
+/// `VK_ERROR_DEVICE_LOST` marks device as [lost](Device::is_lost)
+/// and turns into [`DeviceError::DeviceLost`], other results are passed to `err`
+#[doc(hidden)]
+pub fn device_error(core: &dev::Core, result: vk::Result, err: fn(vk::Result) -> DeviceError) -> DeviceError {
+    if core.observe(result) {
+        DeviceError::DeviceLost
+    } else {
+        err(result)
+    }
+}
 
 /// Core structure of the library
 ///
 /// `Device` represents logical device and provide API to the selected GPU
+///
+/// `Device` is `Send` and `Sync` so it may be shared between threads by reference
+///
+/// Every object created from `Device` shares ownership of the logical device,
+/// so `Device` and its objects may be dropped in any order.
+/// Logical device is destroyed when the last of them is dropped
 pub struct Device {
     i_core: Arc<dev::Core>,
     i_hw: hw::HWDevice,
+    i_features: hw::Features,
+    i_queues: Vec<(u32, u32)>,
+    i_dynamic_rendering: bool,
+    i_instance: ash::Instance,
 }
 
 impl Device {
     /// Create new [`Device`] object according to [`DeviceCfg`]
     pub fn new(dev_type: &DeviceCfg) -> Result<Device, DeviceError> {
-        let mut priorities: Vec<Vec<f32>> = Vec::new();
+        if dev_type.dynamic_rendering && !dev_type.hw.supports_dynamic_rendering(dev_type.lib) {
+            return Err(DeviceError::DynamicRenderingUnsupported);
+        }
+
+        for (i, req) in dev_type.queues.iter().enumerate() {
+            if dev_type.queues[..i].iter().any(|prev| prev.family_index == req.family_index) {
+                return Err(DeviceError::DuplicateQueueFamily(req.family_index));
+            }
 
-        let dev_queue_create_info: Vec<vk::DeviceQueueCreateInfo> = dev_type
-            .hw
-            .queues()
-            .map(|info| {
-                priorities.push(vec![1.0f32; info.count() as usize]);
+            if let Some(&priority) = req.priorities.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+                return Err(DeviceError::QueuePriority(priority));
+            }
+        }
 
+        let priorities: Vec<Vec<f32>> = if dev_type.queues.is_empty() {
+            dev_type.hw.queues().map(|info| vec![1.0f32; info.count() as usize]).collect()
+        } else {
+            dev_type.queues.iter().map(|req| req.priorities.to_vec()).collect()
+        };
+
+        let families: Vec<u32> = if dev_type.queues.is_empty() {
+            dev_type.hw.queues().map(|info| info.index()).collect()
+        } else {
+            dev_type.queues.iter().map(|req| req.family_index).collect()
+        };
+
+        let dev_queue_create_info: Vec<vk::DeviceQueueCreateInfo> = families
+            .iter()
+            .zip(priorities.iter())
+            .map(|(&family, family_priorities)| {
                 vk::DeviceQueueCreateInfo {
                     s_type: vk::StructureType::DEVICE_QUEUE_CREATE_INFO,
                     p_next: ptr::null(),
                     flags: vk::DeviceQueueCreateFlags::empty(),
-                    queue_family_index: info.index(),
-                    queue_count: info.count(),
-                    p_queue_priorities: priorities.last().unwrap().as_ptr(),
+                    queue_family_index: family,
+                    queue_count: family_priorities.len() as u32,
+                    p_queue_priorities: family_priorities.as_ptr(),
                     _marker: PhantomData,
                 }
             })
             .collect();
 
+        let dynamic_rendering = vk::PhysicalDeviceDynamicRenderingFeatures {
+            s_type: vk::StructureType::PHYSICAL_DEVICE_DYNAMIC_RENDERING_FEATURES,
+            p_next: ptr::null_mut(),
+            dynamic_rendering: vk::TRUE,
+            _marker: PhantomData,
+        };
+
         // Warnng: enabled_layer_count and pp_enabled_layer_names is deprecated
         #[allow(deprecated)]
         let create_info = vk::DeviceCreateInfo {
             s_type: vk::StructureType::DEVICE_CREATE_INFO,
-            p_next: ptr::null(),
+            p_next: if dev_type.dynamic_rendering {
+                &dynamic_rendering as *const vk::PhysicalDeviceDynamicRenderingFeatures as *const std::ffi::c_void
+            } else {
+                ptr::null()
+            },
             flags: vk::DeviceCreateFlags::empty(),
             queue_create_info_count: dev_queue_create_info.len() as u32,
             p_queue_create_infos: dev_queue_create_info.as_ptr(),
@@ -79,36 +210,106 @@ impl Device {
             pp_enabled_layer_names: ptr::null(),
             enabled_extension_count: dev_type.extensions.len() as u32,
             pp_enabled_extension_names: dev_type.extensions.as_ptr(),
-            p_enabled_features: dev_type.hw.features(),
+            p_enabled_features: &dev_type.features,
             _marker: PhantomData,
         };
 
+        let callback = dev_type.allocator.or(dev_type.lib.allocator().copied());
+
         let dev: ash::Device = on_error_ret!(
-            unsafe { dev_type.lib.instance().create_device(dev_type.hw.device(), &create_info, None) },
+            unsafe { dev_type.lib.instance().create_device(dev_type.hw.device(), &create_info, callback.as_ref()) },
             DeviceError::Creating
         );
 
         // Note: to prevent lifetime bounds [HWDevice](crate::hw::HWDevice) will be cloned
         //
         // It is not optimal but maybe in the future it will be fixed
+        let queues: Vec<(u32, u32)> = families.into_iter().zip(priorities.iter().map(|p| p.len() as u32)).collect();
+
         Ok(Device {
-            i_core: Arc::new(dev::Core::new(dev, dev_type.allocator)),
-            i_hw: dev_type.hw.clone()
+            i_core: Arc::new(dev::Core::new(dev, callback, *dev_type.hw.limits(), dev_type.track_objects, &queues)),
+            i_hw: dev_type.hw.clone(),
+            i_features: dev_type.features,
+            i_queues: queues,
+            i_dynamic_rendering: dev_type.dynamic_rendering,
+            i_instance: dev_type.lib.instance().clone()
         })
     }
 
     /// Create new queue
     ///
+    /// Every call returns new handle of the same `vkQueue`,
+    /// submission and presentation through all handles are serialized
+    ///
     /// For more information see [queue crate](crate::queue)
-    pub fn get_queue(&self, cfg: &queue::QueueCfg) -> queue::Queue {
+    pub fn get_queue(&self, cfg: &queue::QueueCfg) -> Result<queue::Queue, queue::QueueError> {
         queue::Queue::new(self, cfg)
     }
 
+    /// Return how many queues of the family `family_index` were created
+    ///
+    /// Returns 0 if family was not requested
+    pub fn queue_count(&self, family_index: u32) -> u32 {
+        self.i_queues
+        .iter()
+        .find(|(family, _)| *family == family_index)
+        .map_or(0, |(_, count)| *count)
+    }
+
+    /// Features which were enabled at device creation
+    ///
+    /// Unlike [`hw().features()`](hw::HWDevice::features) returns only requested features
+    pub fn features(&self) -> &hw::Features {
+        &self.i_features
+    }
+
+    /// Was device created with [`dynamic_rendering`](DeviceCfg::dynamic_rendering)
+    pub fn is_dynamic_rendering_enabled(&self) -> bool {
+        self.i_dynamic_rendering
+    }
+
+    /// Was device lost
+    ///
+    /// Flag is set once any call of the library got `VK_ERROR_DEVICE_LOST`
+    ///
+    /// Lost device can not be recovered, all objects created from it must be destroyed
+    /// and new device must be created
+    pub fn is_lost(&self) -> bool {
+        self.i_core.is_lost()
+    }
+
+    /// Number of live objects of each type
+    ///
+    /// Requires [`DeviceCfg::track_objects`], otherwise every counter is zero
+    ///
+    /// Check the report before the device is dropped to find leaked objects
+    pub fn live_objects(&self) -> dev::ObjectReport {
+        self.i_core.live_objects().unwrap_or_default()
+    }
+
+    /// Wait until all queues of the device become idle
+    ///
+    /// Returns [`DeviceError::DeviceLost`] if device was lost
+    pub fn wait_idle(&self) -> Result<(), DeviceError> {
+        on_error!(
+            unsafe { self.i_core.device().device_wait_idle() },
+            |err| return Err(device_error(&self.i_core, err, DeviceError::WaitIdle))
+        );
+
+        Ok(())
+    }
+
     #[doc(hidden)]
     pub fn core(&self) -> &Arc<dev::Core> {
         &self.i_core
     }
 
+    /// Instance which device was created from
+    #[doc(hidden)]
+    pub fn instance(&self) -> &ash::Instance {
+        &self.i_instance
+    }
+
     #[doc(hidden)]
     pub fn device(&self) -> &ash::Device {
         self.i_core.device()
@@ -123,4 +324,14 @@ impl Device {
     pub fn hw(&self) -> &hw::HWDevice {
         &self.i_hw
     }
+
+    /// Update memory budget of the [physical device](Self::hw)
+    ///
+    /// [`Memory::allocate`](crate::memory::Memory::allocate) checks budget
+    /// known at the last refresh
+    ///
+    /// See [`HWDevice::refresh_memory_budget`](hw::HWDevice::refresh_memory_budget)
+    pub fn refresh_memory_budget(&mut self, lib: &libvk::Instance) {
+        self.i_hw.refresh_memory_budget(lib);
+    }
 }
\ No newline at end of file
@@ -3,11 +3,15 @@
 //! Contains structs which allow you work with GPU
 
 pub mod device;
+pub mod pipeline_cache;
+pub mod deletion_queue;
 
 #[doc(hidden)]
 pub mod core;
 
 pub use device::*;
+pub use pipeline_cache::*;
+pub use deletion_queue::*;
 
 #[doc(hidden)]
 pub use self::core::*;
\ No newline at end of file
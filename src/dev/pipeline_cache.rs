@@ -0,0 +1,106 @@
+//! Pipeline cache allows the result of pipeline construction to be reused
+//!
+//! Cache may be shared between [graphics](crate::graphics::Pipeline)
+//! and [compute](crate::compute::Pipeline) pipelines
+//! and saved to disk between application runs via [`PipelineCache::data`]
+
+use ash::vk;
+
+use crate::dev;
+use crate::on_error_ret;
+
+use std::sync::Arc;
+use std::{ptr, fmt};
+use std::error::Error;
+use std::marker::PhantomData;
+
+#[derive(Debug)]
+pub enum PipelineCacheError {
+    /// Failed to create pipeline cache
+    Creating,
+    /// Failed to retrieve cache data
+    Data,
+}
+
+impl fmt::Display for PipelineCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineCacheError::Creating => write!(f, "Failed to create pipeline cache (vkCreatePipelineCache call failed)"),
+            PipelineCacheError::Data => write!(f, "Failed to get pipeline cache data (vkGetPipelineCacheData call failed)"),
+        }
+    }
+}
+
+impl Error for PipelineCacheError {}
+
+/// Pipeline cache object
+pub struct PipelineCache {
+    i_core: Arc<dev::Core>,
+    i_cache: vk::PipelineCache,
+}
+
+impl PipelineCache {
+    /// Create empty cache
+    pub fn new(device: &dev::Device) -> Result<PipelineCache, PipelineCacheError> {
+        PipelineCache::from_bytes(device, &[])
+    }
+
+    /// Create cache with initial content
+    ///
+    /// `data` is expected to be retrieved via [`PipelineCache::data`]
+    ///
+    /// If `data` is not compatible with the device (e.g. driver was updated)
+    /// implementation ignores it and cache is created empty
+    pub fn from_bytes(device: &dev::Device, data: &[u8]) -> Result<PipelineCache, PipelineCacheError> {
+        let cache_info = vk::PipelineCacheCreateInfo {
+            s_type: vk::StructureType::PIPELINE_CACHE_CREATE_INFO,
+            p_next: ptr::null(),
+            flags: vk::PipelineCacheCreateFlags::empty(),
+            initial_data_size: data.len(),
+            p_initial_data: if data.is_empty() { ptr::null() } else { data.as_ptr() as *const std::ffi::c_void },
+            _marker: PhantomData,
+        };
+
+        let cache = on_error_ret!(
+            unsafe { device.device().create_pipeline_cache(&cache_info, device.allocator()) },
+            PipelineCacheError::Creating
+        );
+
+        Ok(PipelineCache {
+            i_core: device.core().clone(),
+            i_cache: cache,
+        })
+    }
+
+    /// Return cache content which may be passed to [`PipelineCache::from_bytes`]
+    pub fn data(&self) -> Result<Vec<u8>, PipelineCacheError> {
+        let data = on_error_ret!(
+            unsafe { self.i_core.device().get_pipeline_cache_data(self.i_cache) },
+            PipelineCacheError::Data
+        );
+
+        Ok(data)
+    }
+
+    #[doc(hidden)]
+    pub fn cache(&self) -> vk::PipelineCache {
+        self.i_cache
+    }
+}
+
+impl fmt::Debug for PipelineCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PipelineCache")
+        .field("i_core", &self.i_core)
+        .field("i_cache", &self.i_cache)
+        .finish()
+    }
+}
+
+impl Drop for PipelineCache {
+    fn drop(&mut self) {
+        unsafe {
+            self.i_core.device().destroy_pipeline_cache(self.i_cache, self.i_core.allocator());
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Crate level error type
+//!
+//! [`Error`] wraps error of every module so functions which use several modules
+//! may return single type and propagate errors with `?`
+//!
+//! Errors of failed Vulkan calls keep returned
+//! [`VkResult`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkResult.html)
+//! which is available through [`source`](std::error::Error::source)
+//!
+//! Example
+//! ```ignore
+//! use libvktypes::{dev, memory, Error};
+//!
+//! fn allocate(device: &dev::Device, cfg: &memory::MemoryCfg) -> Result<memory::Memory, Error> {
+//!     Ok(memory::Memory::allocate(device, cfg)?)
+//! }
+//! ```
+
+use crate::{libvk, hw, dev, queue, memory, shader, compute, cmd, surface, window, swapchain, graphics, sync};
+
+use std::fmt;
+
+macro_rules! crate_error {
+    ( $( $(#[$meta:meta])* $variant:ident($err:ty) ),* $(,)? ) => {
+        /// Error of any module
+        #[derive(Debug)]
+        pub enum Error {
+            $(
+                $(#[$meta])*
+                $variant($err),
+            )*
+        }
+
+        $(
+            impl From<$err> for Error {
+                fn from(err: $err) -> Self {
+                    Error::$variant(err)
+                }
+            }
+        )*
+
+        impl fmt::Display for Error {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $( Error::$variant(err) => write!(f, "{}", err), )*
+                }
+            }
+        }
+
+        impl std::error::Error for Error {
+            /// Source of the wrapped error (usually `VkResult`)
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                match self {
+                    $( Error::$variant(err) => err.source(), )*
+                }
+            }
+        }
+    }
+}
+
+crate_error! {
+    /// See [`libvk::InstanceError`]
+    Instance(libvk::InstanceError),
+    /// See [`hw::HWError`]
+    Hardware(hw::HWError),
+    /// See [`dev::DeviceError`]
+    Device(dev::DeviceError),
+    /// See [`dev::PipelineCacheError`]
+    PipelineCache(dev::PipelineCacheError),
+    /// See [`queue::QueueError`]
+    Queue(queue::QueueError),
+    /// See [`queue::PresentError`]
+    Present(queue::PresentError),
+    /// See [`memory::MemoryError`]
+    Memory(memory::MemoryError),
+    /// See [`memory::ImageError`]
+    Image(memory::ImageError),
+    /// See [`memory::FramebufferError`]
+    Framebuffer(memory::FramebufferError),
+    /// See [`shader::ShaderError`]
+    Shader(shader::ShaderError),
+    /// See [`compute::PipelineError`]
+    ComputePipeline(compute::PipelineError),
+    /// See [`cmd::PoolError`]
+    Pool(cmd::PoolError),
+    /// See [`cmd::BufferError`]
+    Buffer(cmd::BufferError),
+    /// See [`cmd::RecordError`]
+    Record(cmd::RecordError),
+    /// See [`surface::SurfaceError`]
+    Surface(surface::SurfaceError),
+    /// See [`surface::CapabilitiesError`]
+    Capabilities(surface::CapabilitiesError),
+    /// See [`window::WindowError`]
+    Window(window::WindowError),
+    /// See [`swapchain::SwapchainError`]
+    Swapchain(swapchain::SwapchainError),
+    /// See [`graphics::PipelineError`]
+    Pipeline(graphics::PipelineError),
+    /// See [`graphics::PipelineDescriptorError`]
+    PipelineDescriptor(graphics::PipelineDescriptorError),
+    /// See [`graphics::DynamicOffsetsError`]
+    DynamicOffsets(graphics::DynamicOffsetsError),
+    /// See [`graphics::RenderPassError`]
+    RenderPass(graphics::RenderPassError),
+    /// See [`graphics::SamplerError`]
+    Sampler(graphics::SamplerError),
+    /// See [`graphics::DescError`]
+    Desc(graphics::DescError),
+    /// See [`sync::SemaphoreError`]
+    Semaphore(sync::SemaphoreError),
+    /// See [`sync::FenceError`]
+    Fence(sync::FenceError),
+    /// See [`sync::EventError`]
+    Event(sync::EventError),
+    /// See [`sync::FrameSyncError`]
+    FrameSync(sync::FrameSyncError),
+}
@@ -2,7 +2,7 @@
 
 use raw_window_handle::HasDisplayHandle;
 
-use std::ffi::c_char;
+use std::ffi::{c_char, CString};
 
 use crate::window;
 use crate::on_error;
@@ -16,9 +16,50 @@ pub const XLIB_SURFACE_EXT_NAME: *const i8 = ash::vk::KHR_XLIB_SURFACE_NAME.as_p
 /// Device ext
 pub const SWAPCHAIN_EXT_NAME: *const i8 = ash::vk::KHR_SWAPCHAIN_NAME.as_ptr();
 
+/// Device ext
+///
+/// Enables [`MemoryDescription::budget`](crate::hw::MemoryDescription::budget) reporting
+pub const MEMORY_BUDGET_EXT_NAME: *const i8 = ash::vk::EXT_MEMORY_BUDGET_NAME.as_ptr();
+
+/// Return names of instance extensions provided by implementation
+/// or by `layer` if it is set
+///
+/// Returns empty vector if query failed
+///
+/// Entry may be obtained with [`load_entry`](crate::libvk::load_entry)
+pub fn available_extensions(entry: &ash::Entry, layer: Option<&str>) -> Vec<String> {
+    let layer = match layer.map(CString::new) {
+        Some(Ok(name)) => Some(name),
+        Some(Err(_)) => return Vec::new(),
+        None => None
+    };
+
+    let properties = unsafe {
+        entry.enumerate_instance_extension_properties(layer.as_deref())
+    }.unwrap_or_default();
+
+    properties
+        .iter()
+        .filter_map(|x| x.extension_name_as_c_str().ok())
+        .map(|x| x.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Return instance extensions required to work without window
+///
+/// Counterpart of [`required_extensions`]:
+/// no surface extensions are loaded so instance may be created on machine without display server
+///
+/// Currently it is empty, [`DEBUG_EXT_NAME`] and others may be pushed as usual
+pub fn headless_extensions() -> Vec<*const c_char> {
+    Vec::new()
+}
+
 /// Return required extensions for surface
 ///
 /// If function failed to do this returns empty vector
+///
+/// See [`headless_extensions`] for compute only or offscreen code
 pub fn required_extensions(window: &window::Window) -> Vec<*const c_char> {
     let display_handle = on_error!(window.display_handle(), { return Vec::new(); });
 
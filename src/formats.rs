@@ -1,4 +1,4 @@
-use crate::memory::ImageFormat;
+use crate::memory::{ImageFormat, ImageAspect, Extent3D};
 
 /// Return block size in bytes for the selected format
 /// according to the [specification](https://registry.khronos.org/vulkan/specs/1.3-extensions/html/vkspec.html#formats-compatibility)
@@ -13,13 +13,36 @@ pub fn block_size(format: ImageFormat) -> u64 {
         ImageFormat::R8_UINT |
         ImageFormat::R8_SINT |
         ImageFormat::R8_SRGB |
+        ImageFormat::R8_SNORM |
         ImageFormat::S8_UINT => 1,
+        ImageFormat::R4G4B4A4_UNORM_PACK16 |
+        ImageFormat::B4G4R4A4_UNORM_PACK16 |
+        ImageFormat::R5G6B5_UNORM_PACK16 |
+        ImageFormat::B5G6R5_UNORM_PACK16 |
+        ImageFormat::R5G5B5A1_UNORM_PACK16 |
+        ImageFormat::B5G5R5A1_UNORM_PACK16 |
+        ImageFormat::A1R5G5B5_UNORM_PACK16 |
+        ImageFormat::R8G8_UNORM |
+        ImageFormat::R8G8_SNORM |
+        ImageFormat::R8G8_USCALED |
+        ImageFormat::R8G8_SSCALED |
+        ImageFormat::R8G8_UINT |
+        ImageFormat::R8G8_SINT |
+        ImageFormat::R8G8_SRGB |
+        ImageFormat::R16_UNORM |
+        ImageFormat::R16_SNORM |
+        ImageFormat::R16_USCALED |
+        ImageFormat::R16_SSCALED |
+        ImageFormat::R16_UINT |
+        ImageFormat::R16_SINT |
+        ImageFormat::R16_SFLOAT |
+        ImageFormat::R10X6_UNORM_PACK16 |
+        ImageFormat::R12X4_UNORM_PACK16 |
         ImageFormat::D16_UNORM => 2,
         ImageFormat::R8G8B8_UNORM |
         ImageFormat::R8G8B8_SNORM |
         ImageFormat::R8G8B8_USCALED |
         ImageFormat::R8G8B8_SSCALED |
-        ImageFormat::R8_SNORM |
         ImageFormat::R8G8B8_UINT |
         ImageFormat::R8G8B8_SINT |
         ImageFormat::R8G8B8_SRGB |
@@ -230,4 +253,222 @@ pub fn block_size(format: ImageFormat) -> u64 {
         ImageFormat::R64G64B64A64_SFLOAT => 32,
         _ => 0
     }
-}
\ No newline at end of file
+}
+
+/// Size and layout of the texel blocks of the format
+///
+/// Uncompressed formats have blocks of single texel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatInfo {
+    /// Size of the block in bytes, see [`block_size`]
+    pub block_size_bytes: u64,
+    /// Size of the block in texels
+    pub block_extent: Extent3D,
+    /// Aspects of the image with the format, see [`aspect`]
+    pub aspect: ImageAspect,
+    /// Is format block-compressed (BC, ETC2, EAC, ASTC or PVRTC)
+    pub is_compressed: bool,
+}
+
+/// Return block width and height in texels
+///
+/// Only compressed and packed `422` formats have blocks of several texels
+fn block_extent(format: ImageFormat) -> (u32, u32) {
+    match format {
+        ImageFormat::BC1_RGB_UNORM_BLOCK |
+        ImageFormat::BC1_RGB_SRGB_BLOCK |
+        ImageFormat::BC1_RGBA_UNORM_BLOCK |
+        ImageFormat::BC1_RGBA_SRGB_BLOCK |
+        ImageFormat::BC2_UNORM_BLOCK |
+        ImageFormat::BC2_SRGB_BLOCK |
+        ImageFormat::BC3_UNORM_BLOCK |
+        ImageFormat::BC3_SRGB_BLOCK |
+        ImageFormat::BC4_UNORM_BLOCK |
+        ImageFormat::BC4_SNORM_BLOCK |
+        ImageFormat::BC5_UNORM_BLOCK |
+        ImageFormat::BC5_SNORM_BLOCK |
+        ImageFormat::BC6H_UFLOAT_BLOCK |
+        ImageFormat::BC6H_SFLOAT_BLOCK |
+        ImageFormat::BC7_UNORM_BLOCK |
+        ImageFormat::BC7_SRGB_BLOCK |
+        ImageFormat::ETC2_R8G8B8_UNORM_BLOCK |
+        ImageFormat::ETC2_R8G8B8_SRGB_BLOCK |
+        ImageFormat::ETC2_R8G8B8A1_UNORM_BLOCK |
+        ImageFormat::ETC2_R8G8B8A1_SRGB_BLOCK |
+        ImageFormat::ETC2_R8G8B8A8_UNORM_BLOCK |
+        ImageFormat::ETC2_R8G8B8A8_SRGB_BLOCK |
+        ImageFormat::EAC_R11_UNORM_BLOCK |
+        ImageFormat::EAC_R11_SNORM_BLOCK |
+        ImageFormat::EAC_R11G11_UNORM_BLOCK |
+        ImageFormat::EAC_R11G11_SNORM_BLOCK |
+        ImageFormat::ASTC_4X4_SFLOAT_BLOCK |
+        ImageFormat::ASTC_4X4_UNORM_BLOCK |
+        ImageFormat::ASTC_4X4_SRGB_BLOCK |
+        ImageFormat::PVRTC1_4BPP_UNORM_BLOCK_IMG |
+        ImageFormat::PVRTC1_4BPP_SRGB_BLOCK_IMG |
+        ImageFormat::PVRTC2_4BPP_UNORM_BLOCK_IMG |
+        ImageFormat::PVRTC2_4BPP_SRGB_BLOCK_IMG => (4, 4),
+        ImageFormat::ASTC_5X4_SFLOAT_BLOCK |
+        ImageFormat::ASTC_5X4_UNORM_BLOCK |
+        ImageFormat::ASTC_5X4_SRGB_BLOCK => (5, 4),
+        ImageFormat::ASTC_5X5_SFLOAT_BLOCK |
+        ImageFormat::ASTC_5X5_UNORM_BLOCK |
+        ImageFormat::ASTC_5X5_SRGB_BLOCK => (5, 5),
+        ImageFormat::ASTC_6X5_SFLOAT_BLOCK |
+        ImageFormat::ASTC_6X5_UNORM_BLOCK |
+        ImageFormat::ASTC_6X5_SRGB_BLOCK => (6, 5),
+        ImageFormat::ASTC_6X6_SFLOAT_BLOCK |
+        ImageFormat::ASTC_6X6_UNORM_BLOCK |
+        ImageFormat::ASTC_6X6_SRGB_BLOCK => (6, 6),
+        ImageFormat::ASTC_8X5_SFLOAT_BLOCK |
+        ImageFormat::ASTC_8X5_UNORM_BLOCK |
+        ImageFormat::ASTC_8X5_SRGB_BLOCK => (8, 5),
+        ImageFormat::ASTC_8X6_SFLOAT_BLOCK |
+        ImageFormat::ASTC_8X6_UNORM_BLOCK |
+        ImageFormat::ASTC_8X6_SRGB_BLOCK => (8, 6),
+        ImageFormat::ASTC_8X8_SFLOAT_BLOCK |
+        ImageFormat::ASTC_8X8_UNORM_BLOCK |
+        ImageFormat::ASTC_8X8_SRGB_BLOCK => (8, 8),
+        ImageFormat::ASTC_10X5_SFLOAT_BLOCK |
+        ImageFormat::ASTC_10X5_UNORM_BLOCK |
+        ImageFormat::ASTC_10X5_SRGB_BLOCK => (10, 5),
+        ImageFormat::ASTC_10X6_SFLOAT_BLOCK |
+        ImageFormat::ASTC_10X6_UNORM_BLOCK |
+        ImageFormat::ASTC_10X6_SRGB_BLOCK => (10, 6),
+        ImageFormat::ASTC_10X8_SFLOAT_BLOCK |
+        ImageFormat::ASTC_10X8_UNORM_BLOCK |
+        ImageFormat::ASTC_10X8_SRGB_BLOCK => (10, 8),
+        ImageFormat::ASTC_10X10_SFLOAT_BLOCK |
+        ImageFormat::ASTC_10X10_UNORM_BLOCK |
+        ImageFormat::ASTC_10X10_SRGB_BLOCK => (10, 10),
+        ImageFormat::ASTC_12X10_SFLOAT_BLOCK |
+        ImageFormat::ASTC_12X10_UNORM_BLOCK |
+        ImageFormat::ASTC_12X10_SRGB_BLOCK => (12, 10),
+        ImageFormat::ASTC_12X12_SFLOAT_BLOCK |
+        ImageFormat::ASTC_12X12_UNORM_BLOCK |
+        ImageFormat::ASTC_12X12_SRGB_BLOCK => (12, 12),
+        ImageFormat::PVRTC1_2BPP_UNORM_BLOCK_IMG |
+        ImageFormat::PVRTC1_2BPP_SRGB_BLOCK_IMG |
+        ImageFormat::PVRTC2_2BPP_UNORM_BLOCK_IMG |
+        ImageFormat::PVRTC2_2BPP_SRGB_BLOCK_IMG => (8, 4),
+        ImageFormat::G8B8G8R8_422_UNORM |
+        ImageFormat::B8G8R8G8_422_UNORM |
+        ImageFormat::G10X6B10X6G10X6R10X6_422_UNORM_4PACK16 |
+        ImageFormat::B10X6G10X6R10X6G10X6_422_UNORM_4PACK16 |
+        ImageFormat::G12X4B12X4G12X4R12X4_422_UNORM_4PACK16 |
+        ImageFormat::B12X4G12X4R12X4G12X4_422_UNORM_4PACK16 |
+        ImageFormat::G16B16G16R16_422_UNORM |
+        ImageFormat::B16G16R16G16_422_UNORM => (2, 1),
+        _ => (1, 1)
+    }
+}
+
+/// Return aspect of the image with the `format`
+///
+/// Every format which is not depth or stencil has `COLOR` aspect
+pub fn aspect(format: ImageFormat) -> ImageAspect {
+    match format {
+        ImageFormat::D16_UNORM |
+        ImageFormat::X8_D24_UNORM_PACK32 |
+        ImageFormat::D32_SFLOAT => ImageAspect::DEPTH,
+        ImageFormat::S8_UINT => ImageAspect::STENCIL,
+        ImageFormat::D16_UNORM_S8_UINT |
+        ImageFormat::D24_UNORM_S8_UINT |
+        ImageFormat::D32_SFLOAT_S8_UINT => ImageAspect::DEPTH | ImageAspect::STENCIL,
+        _ => ImageAspect::COLOR
+    }
+}
+
+/// Return [`FormatInfo`] for the selected format
+///
+/// If format is unknown to the library `block_size_bytes` is 0
+pub fn info(format: ImageFormat) -> FormatInfo {
+    let (width, height) = block_extent(format);
+
+    FormatInfo {
+        block_size_bytes: block_size(format),
+        block_extent: Extent3D { width, height, depth: 1 },
+        aspect: aspect(format),
+        // Packed 422 formats have blocks of two texels in a row but are not compressed
+        is_compressed: height > 1,
+    }
+}
+
+/// Return size in bytes of tightly packed texels of the format with `extent`
+///
+/// Extent is rounded up to the whole blocks,
+/// e.g. 2x2 texels of `BC1` format occupy single 8 bytes block
+///
+/// Combined depth/stencil formats are copied per aspect so result is not suitable for them
+pub fn required_staging_size(format: ImageFormat, extent: Extent3D) -> u64 {
+    let info = info(format);
+
+    (extent.width.div_ceil(info.block_extent.width) as u64)
+        *(extent.height.div_ceil(info.block_extent.height) as u64)
+        *(extent.depth.div_ceil(info.block_extent.depth) as u64)
+        *info.block_size_bytes
+}
+
+/// Pairs of UNORM formats and their sRGB counterparts
+const SRGB_PAIRS: [(ImageFormat, ImageFormat); 29] = [
+    (ImageFormat::R8_UNORM, ImageFormat::R8_SRGB),
+    (ImageFormat::R8G8_UNORM, ImageFormat::R8G8_SRGB),
+    (ImageFormat::R8G8B8_UNORM, ImageFormat::R8G8B8_SRGB),
+    (ImageFormat::B8G8R8_UNORM, ImageFormat::B8G8R8_SRGB),
+    (ImageFormat::R8G8B8A8_UNORM, ImageFormat::R8G8B8A8_SRGB),
+    (ImageFormat::B8G8R8A8_UNORM, ImageFormat::B8G8R8A8_SRGB),
+    (ImageFormat::A8B8G8R8_UNORM_PACK32, ImageFormat::A8B8G8R8_SRGB_PACK32),
+    (ImageFormat::BC1_RGB_UNORM_BLOCK, ImageFormat::BC1_RGB_SRGB_BLOCK),
+    (ImageFormat::BC1_RGBA_UNORM_BLOCK, ImageFormat::BC1_RGBA_SRGB_BLOCK),
+    (ImageFormat::BC2_UNORM_BLOCK, ImageFormat::BC2_SRGB_BLOCK),
+    (ImageFormat::BC3_UNORM_BLOCK, ImageFormat::BC3_SRGB_BLOCK),
+    (ImageFormat::BC7_UNORM_BLOCK, ImageFormat::BC7_SRGB_BLOCK),
+    (ImageFormat::ETC2_R8G8B8_UNORM_BLOCK, ImageFormat::ETC2_R8G8B8_SRGB_BLOCK),
+    (ImageFormat::ETC2_R8G8B8A1_UNORM_BLOCK, ImageFormat::ETC2_R8G8B8A1_SRGB_BLOCK),
+    (ImageFormat::ETC2_R8G8B8A8_UNORM_BLOCK, ImageFormat::ETC2_R8G8B8A8_SRGB_BLOCK),
+    (ImageFormat::ASTC_4X4_UNORM_BLOCK, ImageFormat::ASTC_4X4_SRGB_BLOCK),
+    (ImageFormat::ASTC_5X4_UNORM_BLOCK, ImageFormat::ASTC_5X4_SRGB_BLOCK),
+    (ImageFormat::ASTC_5X5_UNORM_BLOCK, ImageFormat::ASTC_5X5_SRGB_BLOCK),
+    (ImageFormat::ASTC_6X5_UNORM_BLOCK, ImageFormat::ASTC_6X5_SRGB_BLOCK),
+    (ImageFormat::ASTC_6X6_UNORM_BLOCK, ImageFormat::ASTC_6X6_SRGB_BLOCK),
+    (ImageFormat::ASTC_8X5_UNORM_BLOCK, ImageFormat::ASTC_8X5_SRGB_BLOCK),
+    (ImageFormat::ASTC_8X6_UNORM_BLOCK, ImageFormat::ASTC_8X6_SRGB_BLOCK),
+    (ImageFormat::ASTC_8X8_UNORM_BLOCK, ImageFormat::ASTC_8X8_SRGB_BLOCK),
+    (ImageFormat::ASTC_10X5_UNORM_BLOCK, ImageFormat::ASTC_10X5_SRGB_BLOCK),
+    (ImageFormat::ASTC_10X6_UNORM_BLOCK, ImageFormat::ASTC_10X6_SRGB_BLOCK),
+    (ImageFormat::ASTC_10X8_UNORM_BLOCK, ImageFormat::ASTC_10X8_SRGB_BLOCK),
+    (ImageFormat::ASTC_10X10_UNORM_BLOCK, ImageFormat::ASTC_10X10_SRGB_BLOCK),
+    (ImageFormat::ASTC_12X10_UNORM_BLOCK, ImageFormat::ASTC_12X10_SRGB_BLOCK),
+    (ImageFormat::ASTC_12X12_UNORM_BLOCK, ImageFormat::ASTC_12X12_SRGB_BLOCK),
+];
+
+/// Does format perform sRGB nonlinear encoding
+///
+/// Shader writes to such format are converted from linear values automatically
+pub fn is_srgb(format: ImageFormat) -> bool {
+    SRGB_PAIRS.iter().any(|&(_, srgb)| srgb == format)
+}
+
+/// Return sRGB counterpart of the UNORM `format`
+///
+/// sRGB format is returned as is
+///
+/// If format has no sRGB counterpart returns `None`
+pub fn to_srgb(format: ImageFormat) -> Option<ImageFormat> {
+    SRGB_PAIRS
+        .iter()
+        .find(|&&(unorm, srgb)| unorm == format || srgb == format)
+        .map(|&(_, srgb)| srgb)
+}
+
+/// Return UNORM counterpart of the sRGB `format`
+///
+/// UNORM format is returned as is
+///
+/// If format has no sRGB counterpart returns `None`
+pub fn to_unorm(format: ImageFormat) -> Option<ImageFormat> {
+    SRGB_PAIRS
+        .iter()
+        .find(|&&(unorm, srgb)| unorm == format || srgb == format)
+        .map(|&(unorm, _)| unorm)
+}
@@ -0,0 +1,664 @@
+//! Owned descriptions of pipelines, render passes and samplers
+//!
+//! Unlike [`PipelineCfg`](graphics::PipelineCfg) descriptions do not contain references
+//! so they may be stored in data files and loaded at runtime (e.g. by a material system)
+//!
+//! Vulkan enums are stored as names of ash constants, e.g. `"TRIANGLE_LIST"` or `"R8G8B8A8_UNORM"`,
+//! see [`NamedValue`]
+//!
+//! With `serde` feature descriptions implement `Serialize` and `Deserialize`,
+//! missing fields take [default](Default) values
+//!
+//! ```ignore
+//! let desc: graphics::PipelineDesc = serde_json::from_str(&material)?;
+//!
+//! let resolver = graphics::DescResolver {
+//!     shaders: &|name| shaders.get(name),
+//!     render_pass: Some(&render_pass),
+//!     descriptor: &descriptor,
+//!     cache: None,
+//! };
+//!
+//! let pipeline = graphics::Pipeline::from_desc(&device, &desc, &resolver)?;
+//! ```
+
+use crate::{dev, graphics, memory, shader};
+
+use std::error::Error;
+use std::fmt;
+
+/// Errors during conversion of descriptions
+#[derive(Debug)]
+pub enum DescError {
+    /// `name` is not known value of `kind`
+    UnknownName {
+        kind: &'static str,
+        name: String
+    },
+    /// [`DescResolver`] does not know shader with the name
+    UnknownShader(String),
+    /// Failed to create pipeline from valid description
+    Pipeline(graphics::PipelineError),
+    /// Failed to create render pass from valid description
+    RenderPass(graphics::RenderPassError),
+    /// Failed to create sampler from valid description
+    Sampler(graphics::SamplerError)
+}
+
+impl fmt::Display for DescError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DescError::UnknownName { kind, name } => write!(f, "Unknown {} {:?}", kind, name),
+            DescError::UnknownShader(name) => write!(f, "Unknown shader {:?}", name),
+            DescError::Pipeline(err) => write!(f, "{}", err),
+            DescError::RenderPass(err) => write!(f, "{}", err),
+            DescError::Sampler(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for DescError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DescError::Pipeline(err) => Some(err),
+            DescError::RenderPass(err) => Some(err),
+            DescError::Sampler(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+/// Vulkan enum which may be written as a name of its ash constant
+pub trait NamedValue: Sized + Copy + PartialEq + 'static {
+    /// Name of the type in [`DescError::UnknownName`]
+    const KIND: &'static str;
+
+    /// Every supported value with its name
+    const VALUES: &'static [(Self, &'static str)];
+
+    /// Return name of the value, `None` if value is not supported
+    fn name(self) -> Option<&'static str> {
+        Self::VALUES.iter().find(|(value, _)| *value == self).map(|(_, name)| *name)
+    }
+
+    /// Return value with the `name`, `None` if name is unknown
+    fn from_name(name: &str) -> Option<Self> {
+        Self::VALUES.iter().find(|(_, value_name)| *value_name == name).map(|(value, _)| *value)
+    }
+
+    /// Same as [`from_name`](Self::from_name) but returns [`DescError::UnknownName`]
+    fn parse(name: &str) -> Result<Self, DescError> {
+        Self::from_name(name).ok_or_else(|| DescError::UnknownName { kind: Self::KIND, name: name.to_string() })
+    }
+}
+
+macro_rules! named_values {
+    ( $ty:ty, $kind:literal, [ $( $value:ident ),* $(,)? ] ) => {
+        impl NamedValue for $ty {
+            const KIND: &'static str = $kind;
+
+            const VALUES: &'static [(Self, &'static str)] = &[
+                $( (<$ty>::$value, stringify!($value)), )*
+            ];
+        }
+    };
+}
+
+named_values!(graphics::Topology, "topology", [
+    POINT_LIST,
+    LINE_LIST,
+    LINE_STRIP,
+    TRIANGLE_LIST,
+    TRIANGLE_STRIP,
+    TRIANGLE_FAN,
+    LINE_LIST_WITH_ADJACENCY,
+    LINE_STRIP_WITH_ADJACENCY,
+    TRIANGLE_LIST_WITH_ADJACENCY,
+    TRIANGLE_STRIP_WITH_ADJACENCY,
+    PATCH_LIST,
+]);
+
+named_values!(graphics::CullMode, "cull mode", [NONE, FRONT, BACK, FRONT_AND_BACK]);
+
+named_values!(graphics::PolygonMode, "polygon mode", [FILL, LINE, POINT]);
+
+named_values!(graphics::FrontFace, "front face", [COUNTER_CLOCKWISE, CLOCKWISE]);
+
+named_values!(graphics::CompareOp, "compare op", [
+    NEVER,
+    LESS,
+    EQUAL,
+    LESS_OR_EQUAL,
+    GREATER,
+    NOT_EQUAL,
+    GREATER_OR_EQUAL,
+    ALWAYS,
+]);
+
+named_values!(graphics::BlendFactor, "blend factor", [
+    ZERO,
+    ONE,
+    SRC_COLOR,
+    ONE_MINUS_SRC_COLOR,
+    DST_COLOR,
+    ONE_MINUS_DST_COLOR,
+    SRC_ALPHA,
+    ONE_MINUS_SRC_ALPHA,
+    DST_ALPHA,
+    ONE_MINUS_DST_ALPHA,
+    CONSTANT_COLOR,
+    ONE_MINUS_CONSTANT_COLOR,
+    CONSTANT_ALPHA,
+    ONE_MINUS_CONSTANT_ALPHA,
+    SRC_ALPHA_SATURATE,
+]);
+
+named_values!(graphics::BlendOp, "blend op", [ADD, SUBTRACT, REVERSE_SUBTRACT, MIN, MAX]);
+
+named_values!(graphics::AttachmentLoadOp, "load op", [LOAD, CLEAR, DONT_CARE]);
+
+named_values!(graphics::AttachmentStoreOp, "store op", [STORE, DONT_CARE]);
+
+named_values!(graphics::SamplerFilter, "filter", [NEAREST, LINEAR]);
+
+named_values!(graphics::SamplerMipmapMode, "mipmap mode", [NEAREST, LINEAR]);
+
+named_values!(graphics::SamplerAddressMode, "address mode", [
+    REPEAT,
+    MIRRORED_REPEAT,
+    CLAMP_TO_EDGE,
+    CLAMP_TO_BORDER,
+]);
+
+named_values!(graphics::BorderColor, "border color", [
+    FLOAT_TRANSPARENT_BLACK,
+    INT_TRANSPARENT_BLACK,
+    FLOAT_OPAQUE_BLACK,
+    INT_OPAQUE_BLACK,
+    FLOAT_OPAQUE_WHITE,
+    INT_OPAQUE_WHITE,
+]);
+
+named_values!(memory::SampleCount, "sample count", [TYPE_1, TYPE_2, TYPE_4, TYPE_8, TYPE_16, TYPE_32, TYPE_64]);
+
+named_values!(memory::ImageLayout, "image layout", [
+    UNDEFINED,
+    GENERAL,
+    COLOR_ATTACHMENT_OPTIMAL,
+    DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+    DEPTH_STENCIL_READ_ONLY_OPTIMAL,
+    DEPTH_ATTACHMENT_OPTIMAL,
+    DEPTH_READ_ONLY_OPTIMAL,
+    SHADER_READ_ONLY_OPTIMAL,
+    TRANSFER_SRC_OPTIMAL,
+    TRANSFER_DST_OPTIMAL,
+    PRESENT_SRC_KHR,
+]);
+
+named_values!(memory::ImageFormat, "format", [
+    UNDEFINED,
+    R8_UNORM,
+    R8_SNORM,
+    R8_UINT,
+    R8G8_UNORM,
+    R8G8B8A8_UNORM,
+    R8G8B8A8_SNORM,
+    R8G8B8A8_UINT,
+    R8G8B8A8_SRGB,
+    B8G8R8A8_UNORM,
+    B8G8R8A8_SRGB,
+    A2B10G10R10_UNORM_PACK32,
+    R16_SFLOAT,
+    R16G16_SFLOAT,
+    R16G16B16A16_SFLOAT,
+    R32_UINT,
+    R32_SINT,
+    R32_SFLOAT,
+    R32G32_UINT,
+    R32G32_SINT,
+    R32G32_SFLOAT,
+    R32G32B32_UINT,
+    R32G32B32_SINT,
+    R32G32B32_SFLOAT,
+    R32G32B32A32_UINT,
+    R32G32B32A32_SINT,
+    R32G32B32A32_SFLOAT,
+    D16_UNORM,
+    D32_SFLOAT,
+    D24_UNORM_S8_UINT,
+    D32_SFLOAT_S8_UINT,
+]);
+
+/// Parse write mask written as letters of components, e.g. `"RGBA"` or `"RG"`
+fn parse_write_mask(mask: &str) -> Result<graphics::ColorComponent, DescError> {
+    mask.chars().try_fold(graphics::ColorComponent::empty(), |result, component| {
+        let flag = match component {
+            'R' => graphics::ColorComponent::R,
+            'G' => graphics::ColorComponent::G,
+            'B' => graphics::ColorComponent::B,
+            'A' => graphics::ColorComponent::A,
+            _ => return Err(DescError::UnknownName { kind: "color component", name: mask.to_string() })
+        };
+
+        Ok(result | flag)
+    })
+}
+
+/// Owned version of [`VertexInputCfg`](graphics::VertexInputCfg)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct VertexInputDesc {
+    pub location: u32,
+    pub binding: u32,
+    pub format: String,
+    pub offset: u32,
+}
+
+impl Default for VertexInputDesc {
+    fn default() -> VertexInputDesc {
+        VertexInputDesc {
+            location: 0,
+            binding: 0,
+            format: "R32G32_SFLOAT".to_string(),
+            offset: 0,
+        }
+    }
+}
+
+impl VertexInputDesc {
+    pub fn cfg(&self) -> Result<graphics::VertexInputCfg, DescError> {
+        Ok(graphics::VertexInputCfg {
+            location: self.location,
+            binding: self.binding,
+            format: memory::ImageFormat::parse(&self.format)?,
+            offset: self.offset,
+        })
+    }
+}
+
+/// Owned version of [`BlendCfg`](graphics::BlendCfg)
+///
+/// `write_mask` contains letters of written components, e.g. `"RGBA"`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BlendDesc {
+    pub enable: bool,
+    pub src_color: String,
+    pub dst_color: String,
+    pub color_op: String,
+    pub src_alpha: String,
+    pub dst_alpha: String,
+    pub alpha_op: String,
+    pub write_mask: String,
+}
+
+impl Default for BlendDesc {
+    /// Same as [`BlendCfg::opaque`](graphics::BlendCfg::opaque)
+    fn default() -> BlendDesc {
+        BlendDesc {
+            enable: false,
+            src_color: "ONE".to_string(),
+            dst_color: "ZERO".to_string(),
+            color_op: "ADD".to_string(),
+            src_alpha: "ONE".to_string(),
+            dst_alpha: "ZERO".to_string(),
+            alpha_op: "ADD".to_string(),
+            write_mask: "RGBA".to_string(),
+        }
+    }
+}
+
+impl BlendDesc {
+    pub fn cfg(&self) -> Result<graphics::BlendCfg, DescError> {
+        Ok(graphics::BlendCfg {
+            enable: self.enable,
+            src_color: graphics::BlendFactor::parse(&self.src_color)?,
+            dst_color: graphics::BlendFactor::parse(&self.dst_color)?,
+            color_op: graphics::BlendOp::parse(&self.color_op)?,
+            src_alpha: graphics::BlendFactor::parse(&self.src_alpha)?,
+            dst_alpha: graphics::BlendFactor::parse(&self.dst_alpha)?,
+            alpha_op: graphics::BlendOp::parse(&self.alpha_op)?,
+            write_mask: parse_write_mask(&self.write_mask)?,
+        })
+    }
+}
+
+/// Depth part of [`DepthStencilCfg`](graphics::DepthStencilCfg)
+///
+/// Stencil test is disabled
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DepthDesc {
+    pub compare_op: String,
+    pub depth_write: bool,
+    pub depth_bounds: Option<(f32, f32)>,
+}
+
+impl Default for DepthDesc {
+    /// Same as [`DepthStencilCfg::simple`](graphics::DepthStencilCfg::simple)
+    fn default() -> DepthDesc {
+        DepthDesc {
+            compare_op: "LESS_OR_EQUAL".to_string(),
+            depth_write: true,
+            depth_bounds: None,
+        }
+    }
+}
+
+impl DepthDesc {
+    pub fn cfg(&self) -> Result<graphics::DepthStencilCfg, DescError> {
+        Ok(graphics::DepthStencilCfg {
+            compare_op: graphics::CompareOp::parse(&self.compare_op)?,
+            depth_write: self.depth_write,
+            depth_bounds: self.depth_bounds,
+            ..graphics::DepthStencilCfg::simple()
+        })
+    }
+}
+
+/// Owned version of [`PipelineCfg`](graphics::PipelineCfg)
+///
+/// Shaders are referenced by names which are resolved by [`DescResolver::shaders`]
+///
+/// Render pass, descriptor and cache are taken from [`DescResolver`],
+/// dynamic rendering, viewports, scissors, specialization and per-stage push constants are not supported
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PipelineDesc {
+    pub vertex_shader: String,
+    pub frag_shader: String,
+    pub geom_shader: Option<String>,
+    pub tess_control_shader: Option<String>,
+    pub tess_eval_shader: Option<String>,
+    pub patch_control_points: u32,
+    pub vertex_size: u32,
+    pub vert_input: Vec<VertexInputDesc>,
+    pub topology: String,
+    /// Width and height
+    pub extent: [u32; 2],
+    pub push_constant_size: u32,
+    pub subpass_index: u32,
+    pub samples: String,
+    pub blend: Vec<BlendDesc>,
+    pub depth: Option<DepthDesc>,
+    /// Constant factor, clamp and slope factor of [`DepthBiasCfg`](graphics::DepthBiasCfg)
+    pub depth_bias: Option<[f32; 3]>,
+    pub enable_primitive_restart: bool,
+    pub cull_mode: String,
+    pub polygon_mode: String,
+    pub line_width: f32,
+    pub front_face: String,
+}
+
+impl Default for PipelineDesc {
+    fn default() -> PipelineDesc {
+        PipelineDesc {
+            vertex_shader: String::new(),
+            frag_shader: String::new(),
+            geom_shader: None,
+            tess_control_shader: None,
+            tess_eval_shader: None,
+            patch_control_points: 0,
+            vertex_size: 0,
+            vert_input: Vec::new(),
+            topology: "TRIANGLE_LIST".to_string(),
+            extent: [0, 0],
+            push_constant_size: 0,
+            subpass_index: 0,
+            samples: "TYPE_1".to_string(),
+            blend: Vec::new(),
+            depth: None,
+            depth_bias: None,
+            enable_primitive_restart: false,
+            cull_mode: "NONE".to_string(),
+            polygon_mode: "FILL".to_string(),
+            line_width: 1.0,
+            front_face: "COUNTER_CLOCKWISE".to_string(),
+        }
+    }
+}
+
+/// Objects which [`PipelineDesc`] refers to
+pub struct DescResolver<'a> {
+    /// Return shader by its name in description
+    pub shaders: &'a dyn Fn(&str) -> Option<&'a shader::Shader>,
+    pub render_pass: Option<&'a graphics::RenderPass>,
+    pub descriptor: &'a graphics::PipelineDescriptor,
+    pub cache: Option<&'a dev::PipelineCache>,
+}
+
+impl DescResolver<'_> {
+    fn shader(&self, name: &str) -> Result<&shader::Shader, DescError> {
+        (self.shaders)(name).ok_or_else(|| DescError::UnknownShader(name.to_string()))
+    }
+
+    fn optional_shader(&self, name: &Option<String>) -> Result<Option<&shader::Shader>, DescError> {
+        name.as_deref().map(|name| self.shader(name)).transpose()
+    }
+}
+
+impl PipelineDesc {
+    /// Resolve description and call `f` with resulting config
+    ///
+    /// Config borrows converted data so it is available only inside of `f`
+    pub fn with_cfg<R, F>(&self, resolver: &DescResolver, f: F) -> Result<R, DescError>
+    where
+        F: FnOnce(&graphics::PipelineCfg) -> R
+    {
+        let vert_input = self.vert_input.iter().map(VertexInputDesc::cfg).collect::<Result<Vec<_>, _>>()?;
+        let blend = self.blend.iter().map(BlendDesc::cfg).collect::<Result<Vec<_>, _>>()?;
+
+        let cfg = graphics::PipelineCfg {
+            vertex_size: self.vertex_size,
+            vert_input: &vert_input,
+            geom_shader: resolver.optional_shader(&self.geom_shader)?,
+            tess_control_shader: resolver.optional_shader(&self.tess_control_shader)?,
+            tess_eval_shader: resolver.optional_shader(&self.tess_eval_shader)?,
+            patch_control_points: self.patch_control_points,
+            topology: graphics::Topology::parse(&self.topology)?,
+            extent: memory::Extent2D { width: self.extent[0], height: self.extent[1] },
+            push_constant_size: self.push_constant_size,
+            render_pass: resolver.render_pass,
+            subpass_index: self.subpass_index,
+            samples: memory::SampleCount::parse(&self.samples)?,
+            blend: &blend,
+            depth_stencil: self.depth.as_ref().map(DepthDesc::cfg).transpose()?,
+            depth_bias: self.depth_bias.map(|[constant_factor, clamp, slope_factor]| {
+                graphics::DepthBiasCfg { constant_factor, clamp, slope_factor }
+            }),
+            enable_primitive_restart: self.enable_primitive_restart,
+            cull_mode: graphics::CullMode::parse(&self.cull_mode)?,
+            polygon_mode: graphics::PolygonMode::parse(&self.polygon_mode)?,
+            line_width: self.line_width,
+            front_face: graphics::FrontFace::parse(&self.front_face)?,
+            cache: resolver.cache,
+            ..graphics::PipelineCfg::new(
+                resolver.shader(&self.vertex_shader)?,
+                resolver.shader(&self.frag_shader)?,
+                resolver.descriptor
+            )
+        };
+
+        Ok(f(&cfg))
+    }
+}
+
+impl graphics::Pipeline {
+    /// Create pipeline from [`PipelineDesc`]
+    pub fn from_desc(
+        device: &dev::Device,
+        desc: &PipelineDesc,
+        resolver: &DescResolver
+    ) -> Result<graphics::Pipeline, DescError> {
+        desc.with_cfg(resolver, |cfg| graphics::Pipeline::new(device, cfg))?.map_err(DescError::Pipeline)
+    }
+}
+
+/// Owned version of [`AttachmentInfo`](graphics::AttachmentInfo)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct AttachmentDesc {
+    pub format: String,
+    pub samples: String,
+    pub load_op: String,
+    pub store_op: String,
+    pub stencil_load_op: String,
+    pub stencil_store_op: String,
+    pub initial_layout: String,
+    pub final_layout: String,
+}
+
+impl Default for AttachmentDesc {
+    /// Same as default [`AttachmentInfo`](graphics::AttachmentInfo)
+    fn default() -> AttachmentDesc {
+        AttachmentDesc {
+            format: "UNDEFINED".to_string(),
+            samples: "TYPE_1".to_string(),
+            load_op: "DONT_CARE".to_string(),
+            store_op: "DONT_CARE".to_string(),
+            stencil_load_op: "DONT_CARE".to_string(),
+            stencil_store_op: "DONT_CARE".to_string(),
+            initial_layout: "PRESENT_SRC_KHR".to_string(),
+            final_layout: "PRESENT_SRC_KHR".to_string(),
+        }
+    }
+}
+
+impl AttachmentDesc {
+    pub fn info(&self) -> Result<graphics::AttachmentInfo, DescError> {
+        Ok(graphics::AttachmentInfo {
+            format: memory::ImageFormat::parse(&self.format)?,
+            samples: memory::SampleCount::parse(&self.samples)?,
+            load_op: graphics::AttachmentLoadOp::parse(&self.load_op)?,
+            store_op: graphics::AttachmentStoreOp::parse(&self.store_op)?,
+            stencil_load_op: graphics::AttachmentLoadOp::parse(&self.stencil_load_op)?,
+            stencil_store_op: graphics::AttachmentStoreOp::parse(&self.stencil_store_op)?,
+            initial_layout: memory::ImageLayout::parse(&self.initial_layout)?,
+            final_layout: memory::ImageLayout::parse(&self.final_layout)?,
+        })
+    }
+}
+
+/// Subpass of [`RenderPassDesc`], see [`RenderPassBuilder::subpass`](graphics::RenderPassBuilder::subpass)
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SubpassDesc {
+    pub colors: Vec<usize>,
+    pub depth: Option<usize>,
+    pub inputs: Vec<usize>,
+}
+
+/// Owned description of the render pass
+///
+/// Dependencies between subpasses are generated by [`RenderPassBuilder`](graphics::RenderPassBuilder)
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RenderPassDesc {
+    pub attachments: Vec<AttachmentDesc>,
+    pub subpasses: Vec<SubpassDesc>,
+}
+
+impl RenderPassDesc {
+    /// Convert description into [`RenderPassBuilder`](graphics::RenderPassBuilder)
+    pub fn builder(&self) -> Result<graphics::RenderPassBuilder, DescError> {
+        let builder = self
+            .attachments
+            .iter()
+            .try_fold(graphics::RenderPassBuilder::new(), |builder, desc| Ok(builder.attachment(desc.info()?)))?;
+
+        Ok(
+            self
+            .subpasses
+            .iter()
+            .fold(builder, |builder, subpass| builder.subpass(&subpass.colors, subpass.depth, &subpass.inputs))
+        )
+    }
+}
+
+impl graphics::RenderPass {
+    /// Create render pass from [`RenderPassDesc`]
+    pub fn from_desc(device: &dev::Device, desc: &RenderPassDesc) -> Result<graphics::RenderPass, DescError> {
+        desc.builder()?.build(device).map_err(DescError::RenderPass)
+    }
+}
+
+/// Owned version of [`SamplerCfg`](graphics::SamplerCfg)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SamplerDesc {
+    pub mipmap_mode: String,
+    pub address_mode_u: String,
+    pub address_mode_v: String,
+    pub address_mode_w: String,
+    pub mag_filter: String,
+    pub min_filter: String,
+    pub mip_lod_bias: f32,
+    pub anisotropy_enable: bool,
+    pub max_anisotropy: f32,
+    pub compare_enable: bool,
+    pub compare_op: String,
+    pub min_lod: f32,
+    pub max_lod: f32,
+    pub border_color: String,
+    pub unnormalized_coordinates: bool,
+}
+
+impl Default for SamplerDesc {
+    /// Same as default [`SamplerCfg`](graphics::SamplerCfg)
+    fn default() -> SamplerDesc {
+        SamplerDesc {
+            mipmap_mode: "LINEAR".to_string(),
+            address_mode_u: "REPEAT".to_string(),
+            address_mode_v: "REPEAT".to_string(),
+            address_mode_w: "REPEAT".to_string(),
+            mag_filter: "LINEAR".to_string(),
+            min_filter: "LINEAR".to_string(),
+            mip_lod_bias: 0.0,
+            anisotropy_enable: false,
+            max_anisotropy: 0.0,
+            compare_enable: false,
+            compare_op: "ALWAYS".to_string(),
+            min_lod: 0.0,
+            max_lod: 0.0,
+            border_color: "INT_OPAQUE_BLACK".to_string(),
+            unnormalized_coordinates: false,
+        }
+    }
+}
+
+impl SamplerDesc {
+    pub fn cfg(&self) -> Result<graphics::SamplerCfg, DescError> {
+        Ok(graphics::SamplerCfg {
+            mipmap_mode: graphics::SamplerMipmapMode::parse(&self.mipmap_mode)?,
+            address_mode_u: graphics::SamplerAddressMode::parse(&self.address_mode_u)?,
+            address_mode_v: graphics::SamplerAddressMode::parse(&self.address_mode_v)?,
+            address_mode_w: graphics::SamplerAddressMode::parse(&self.address_mode_w)?,
+            mag_filter: graphics::SamplerFilter::parse(&self.mag_filter)?,
+            min_filter: graphics::SamplerFilter::parse(&self.min_filter)?,
+            mip_lod_bias: self.mip_lod_bias,
+            anisotropy_enable: self.anisotropy_enable,
+            max_anisotropy: self.max_anisotropy,
+            compare_enable: self.compare_enable,
+            compare_op: graphics::CompareOp::parse(&self.compare_op)?,
+            min_lod: self.min_lod,
+            max_lod: self.max_lod,
+            border_color: graphics::BorderColor::parse(&self.border_color)?,
+            unnormalized_coordinates: self.unnormalized_coordinates,
+        })
+    }
+}
+
+impl graphics::Sampler {
+    /// Create sampler from [`SamplerDesc`]
+    pub fn from_desc(device: &dev::Device, desc: &SamplerDesc) -> Result<graphics::Sampler, DescError> {
+        graphics::Sampler::new(device, &desc.cfg()?).map_err(DescError::Sampler)
+    }
+}
@@ -5,8 +5,10 @@ use ash::vk;
 pub mod render_pass;
 pub mod pipeline;
 pub mod vertex_view;
+pub mod vertex_layout;
 pub mod sampler;
 pub mod pipeline_descriptor;
+pub mod desc;
 
 #[doc(hidden)]
 pub use crate::graphics::render_pass::*;
@@ -15,9 +17,13 @@ pub use crate::graphics::pipeline::*;
 #[doc(hidden)]
 pub use vertex_view::*;
 #[doc(hidden)]
+pub use vertex_layout::*;
+#[doc(hidden)]
 pub use sampler::*;
 #[doc(hidden)]
 pub use pipeline_descriptor::*;
+#[doc(hidden)]
+pub use desc::*;
 
 /// ShaderStage specifies shader stage within single pipeline
 ///
@@ -5,9 +5,8 @@ use ash::vk;
 use crate::{
     dev,
     memory,
-    on_error,
     data_ptr,
-    on_error_ret,
+    on_error,
     shader,
     graphics
 };
@@ -58,7 +57,9 @@ use std::marker::PhantomData;
 /// ];
 ///
 /// ```
-#[derive(Debug, Clone, Copy)]
+///
+/// Offsets and locations may be computed by [`VertexLayout`](graphics::VertexLayout)
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct VertexInputCfg {
     /// Index of an attribute, the same as defined by the location layout specifier in a shader source code
     pub location: u32,
@@ -107,8 +108,303 @@ pub type Topology = vk::PrimitiveTopology;
 #[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkCullModeFlagBits.html>"]
 pub type CullMode = vk::CullModeFlags;
 
+/// Specifies how polygons are rasterized
+///
+/// Modes other than `FILL` require `fillModeNonSolid` feature
+///
+#[doc = "Possible values: <https://docs.rs/ash/latest/ash/vk/struct.PolygonMode.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPolygonMode.html>"]
+pub type PolygonMode = vk::PolygonMode;
+
+/// Specifies which triangle winding is considered front-facing
+///
+#[doc = "Possible values: <https://docs.rs/ash/latest/ash/vk/struct.FrontFace.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkFrontFace.html>"]
+pub type FrontFace = vk::FrontFace;
+
+/// Depth bias applied to fragment depth values
+///
+/// Typically used for shadow map rendering to avoid shadow acne
+///
+/// Non-zero [`clamp`](DepthBiasCfg::clamp) requires `depthBiasClamp` feature
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/html/vkspec.html#primsrast-depthbias>"]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DepthBiasCfg {
+    /// Constant depth value added to each fragment
+    pub constant_factor: f32,
+    /// Maximum (or minimum if negative) depth bias of a fragment, `0.0` disables clamping
+    pub clamp: f32,
+    /// Factor applied to fragment's slope
+    pub slope_factor: f32,
+}
+
+/// Structure specifying a viewport
+///
+#[doc = "Ash documentation: <https://docs.rs/ash/latest/ash/vk/struct.Viewport.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkViewport.html>"]
+pub type Viewport = vk::Viewport;
+
+/// Structure specifying a two-dimensional subregion
+///
+#[doc = "Ash documentation: <https://docs.rs/ash/latest/ash/vk/struct.Rect2D.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkRect2D.html>"]
+pub type Rect2D = vk::Rect2D;
+
+/// Structure specifying a two-dimensional offset
+///
+#[doc = "Ash documentation: <https://docs.rs/ash/latest/ash/vk/struct.Offset2D.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkOffset2D.html>"]
+pub type Offset2D = vk::Offset2D;
+
+/// Specifies blend factor
+///
+#[doc = "Possible values: <https://docs.rs/ash/latest/ash/vk/struct.BlendFactor.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkBlendFactor.html>"]
+pub type BlendFactor = vk::BlendFactor;
+
+/// Specifies how source and destination are combined
+///
+#[doc = "Possible values: <https://docs.rs/ash/latest/ash/vk/struct.BlendOp.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkBlendOp.html>"]
+pub type BlendOp = vk::BlendOp;
+
+/// Specifies which components are written to the attachment
+///
+#[doc = "Possible values: <https://docs.rs/ash/latest/ash/vk/struct.ColorComponentFlags.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkColorComponentFlagBits.html>"]
+pub type ColorComponent = vk::ColorComponentFlags;
+
+/// Blend state of the single color attachment
+///
+/// If `enable` is `false` fragment color is written as is
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPipelineColorBlendAttachmentState.html>"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlendCfg {
+    pub enable: bool,
+    pub src_color: BlendFactor,
+    pub dst_color: BlendFactor,
+    pub color_op: BlendOp,
+    pub src_alpha: BlendFactor,
+    pub dst_alpha: BlendFactor,
+    pub alpha_op: BlendOp,
+    pub write_mask: ColorComponent,
+}
+
+impl BlendCfg {
+    /// Blending is disabled, all components are written
+    pub fn opaque() -> BlendCfg {
+        BlendCfg {
+            enable: false,
+            src_color: BlendFactor::ONE,
+            dst_color: BlendFactor::ZERO,
+            color_op: BlendOp::ADD,
+            src_alpha: BlendFactor::ONE,
+            dst_alpha: BlendFactor::ZERO,
+            alpha_op: BlendOp::ADD,
+            write_mask: ColorComponent::RGBA,
+        }
+    }
+
+    /// Standard alpha blending
+    ///
+    /// `color = src.rgb * src.a + dst.rgb * (1 - src.a)`
+    pub fn alpha() -> BlendCfg {
+        BlendCfg {
+            enable: true,
+            src_color: BlendFactor::SRC_ALPHA,
+            dst_color: BlendFactor::ONE_MINUS_SRC_ALPHA,
+            color_op: BlendOp::ADD,
+            src_alpha: BlendFactor::ONE,
+            dst_alpha: BlendFactor::ONE_MINUS_SRC_ALPHA,
+            alpha_op: BlendOp::ADD,
+            write_mask: ColorComponent::RGBA,
+        }
+    }
+}
+
+impl Default for BlendCfg {
+    fn default() -> BlendCfg {
+        BlendCfg::opaque()
+    }
+}
+
+#[doc(hidden)]
+impl From<&BlendCfg> for vk::PipelineColorBlendAttachmentState {
+    fn from(cfg: &BlendCfg) -> Self {
+        vk::PipelineColorBlendAttachmentState {
+            blend_enable: cfg.enable as vk::Bool32,
+            src_color_blend_factor: cfg.src_color,
+            dst_color_blend_factor: cfg.dst_color,
+            color_blend_op: cfg.color_op,
+            src_alpha_blend_factor: cfg.src_alpha,
+            dst_alpha_blend_factor: cfg.dst_alpha,
+            alpha_blend_op: cfg.alpha_op,
+            color_write_mask: cfg.write_mask,
+        }
+    }
+}
+
+/// Specifies stencil action
+///
+#[doc = "Possible values: <https://docs.rs/ash/latest/ash/vk/struct.StencilOp.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkStencilOp.html>"]
+pub type StencilOp = vk::StencilOp;
+
+/// Stencil test parameters for one face orientation
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkStencilOpState.html>"]
+#[derive(Debug, Clone, Copy)]
+pub struct StencilOpState {
+    /// Action on samples that fail the stencil test
+    pub fail_op: StencilOp,
+    /// Action on samples that pass both depth and stencil tests
+    pub pass_op: StencilOp,
+    /// Action on samples that pass the stencil test and fail the depth test
+    pub depth_fail_op: StencilOp,
+    pub compare_op: graphics::CompareOp,
+    pub compare_mask: u32,
+    pub write_mask: u32,
+    pub reference: u32,
+}
+
+impl Default for StencilOpState {
+    fn default() -> StencilOpState {
+        StencilOpState {
+            fail_op: StencilOp::KEEP,
+            pass_op: StencilOp::KEEP,
+            depth_fail_op: StencilOp::KEEP,
+            compare_op: graphics::CompareOp::ALWAYS,
+            compare_mask: u32::MAX,
+            write_mask: u32::MAX,
+            reference: 0,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl From<&StencilOpState> for vk::StencilOpState {
+    fn from(cfg: &StencilOpState) -> Self {
+        vk::StencilOpState {
+            fail_op: cfg.fail_op,
+            pass_op: cfg.pass_op,
+            depth_fail_op: cfg.depth_fail_op,
+            compare_op: cfg.compare_op,
+            compare_mask: cfg.compare_mask,
+            write_mask: cfg.write_mask,
+            reference: cfg.reference,
+        }
+    }
+}
+
+/// Depth and stencil test configuration
+///
+/// If [`stencil_test`](DepthStencilCfg::stencil_test) is `false` then `front` and `back` are ignored
+///
+/// [`depth_bounds`](DepthStencilCfg::depth_bounds) requires
+/// [`depthBounds`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceFeatures.html)
+/// feature
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPipelineDepthStencilStateCreateInfo.html>"]
+#[derive(Debug, Clone, Copy)]
+pub struct DepthStencilCfg {
+    /// Comparison operator for depth test
+    pub compare_op: graphics::CompareOp,
+    /// Whether depth values of passed samples are written to the depth buffer
+    pub depth_write: bool,
+    /// Optional `(min, max)` bounds of depth bounds test
+    pub depth_bounds: Option<(f32, f32)>,
+    pub stencil_test: bool,
+    /// Stencil state for front-facing triangles
+    pub front: StencilOpState,
+    /// Stencil state for back-facing triangles (and all other primitives)
+    pub back: StencilOpState,
+}
+
+impl DepthStencilCfg {
+    /// Depth test with `LESS_OR_EQUAL` comparison, depth writes and without stencil test
+    pub fn simple() -> DepthStencilCfg {
+        DepthStencilCfg {
+            compare_op: graphics::CompareOp::LESS_OR_EQUAL,
+            depth_write: true,
+            depth_bounds: None,
+            stencil_test: false,
+            front: StencilOpState::default(),
+            back: StencilOpState::default(),
+        }
+    }
+
+    /// Same as [`simple`](DepthStencilCfg::simple) but with `GREATER_OR_EQUAL` comparison
+    ///
+    /// Use it with depth buffer cleared to `0.0`
+    pub fn reverse_z() -> DepthStencilCfg {
+        DepthStencilCfg {
+            compare_op: graphics::CompareOp::GREATER_OR_EQUAL,
+            ..DepthStencilCfg::simple()
+        }
+    }
+}
+
+#[doc(hidden)]
+impl From<&DepthStencilCfg> for vk::PipelineDepthStencilStateCreateInfo<'_> {
+    fn from(cfg: &DepthStencilCfg) -> Self {
+        let (min_depth_bounds, max_depth_bounds) = cfg.depth_bounds.unwrap_or((0.0, 1.0));
+
+        vk::PipelineDepthStencilStateCreateInfo {
+            s_type: vk::StructureType::PIPELINE_DEPTH_STENCIL_STATE_CREATE_INFO,
+            p_next: ptr::null(),
+            flags: vk::PipelineDepthStencilStateCreateFlags::empty(),
+            depth_test_enable: vk::TRUE,
+            depth_write_enable: cfg.depth_write as vk::Bool32,
+            depth_compare_op: cfg.compare_op,
+            depth_bounds_test_enable: cfg.depth_bounds.is_some() as vk::Bool32,
+            stencil_test_enable: cfg.stencil_test as vk::Bool32,
+            front: (&cfg.front).into(),
+            back: (&cfg.back).into(),
+            min_depth_bounds,
+            max_depth_bounds,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Push constant range available for the shader stages
+///
+/// `offset` and `size` are in bytes and must be multiple of 4
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPushConstantRange.html>"]
+#[derive(Debug, Clone, Copy)]
+pub struct PushConstantCfg {
+    pub stage: graphics::ShaderStage,
+    pub offset: u32,
+    pub size: u32,
+}
+
+#[doc(hidden)]
+impl From<&PushConstantCfg> for vk::PushConstantRange {
+    fn from(cfg: &PushConstantCfg) -> Self {
+        vk::PushConstantRange {
+            stage_flags: cfg.stage,
+            offset: cfg.offset,
+            size: cfg.size,
+        }
+    }
+}
+
 /// Pipeline configuration
 ///
+/// Start from [`PipelineCfg::new`] and override fields with struct update syntax
+///
 /// # Vertex stage configuration
 /// [`vertex_shader`](PipelineCfg::vertex_shader) is your vertex shader module (pretty straightforward)
 ///
@@ -121,6 +417,9 @@ pub type CullMode = vk::CullModeFlags;
 ///
 /// `vert_input` has its own documentation [here](VertexInputCfg)
 ///
+/// Every distinct [`binding`](VertexInputCfg::binding) in `vert_input` gets
+/// single binding with `vertex_size` stride
+///
 /// Vertices must be in counterclockwise order
 ///
 /// # Topology
@@ -128,20 +427,69 @@ pub type CullMode = vk::CullModeFlags;
 /// [here](https://registry.khronos.org/vulkan/specs/1.3-extensions/html/vkspec.html#drawing-primitive-topology-class)
 ///
 /// # Depth test
-/// Set [`enable_depth_test`](PipelineCfg::enable_depth_test) to perform depth test
+/// Set [`depth_stencil`](PipelineCfg::depth_stencil) to perform depth and stencil tests
+///
+/// [`DepthStencilCfg::simple`] matches common depth test
 ///
 /// However you have to allocate depth buffer and properly pass it to the render pass
 ///
+/// # Geometry shader
+/// [`geom_shader`](PipelineCfg::geom_shader) requires
+/// [`geometryShader`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceFeatures.html)
+/// feature to be [enabled](crate::dev::DeviceCfg::features) on device
+///
+/// # Tessellation
+/// Tessellation is enabled when both [`tess_control_shader`](PipelineCfg::tess_control_shader)
+/// and [`tess_eval_shader`](PipelineCfg::tess_eval_shader) are set
+///
+/// In that case [`topology`](PipelineCfg::topology) must be `PATCH_LIST`
+/// and device must support
+/// [`tessellationShader`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceFeatures.html)
+/// feature
+///
 /// # Assembly restarting
 /// Affects [indexed drawing](crate::cmd::Buffer::draw_indexed)
 ///
-/// `enable_depth_test` controls whether a special vertex index value is treated as restarting the assembly of primitives
+/// `enable_primitive_restart` controls whether a special vertex index value is treated as restarting the assembly of primitives
 ///
 /// For example the special index value is
 /// [`INDEX_REASSEMBLY_UINT32`](memory::INDEX_REASSEMBLY_UINT32) for `IndexBufferType::UINT32`
 /// and so on
 ///
 /// Read more [here](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPipelineInputAssemblyStateCreateInfo.html)
+///
+/// # Push constants
+/// [`push_constants`](PipelineCfg::push_constants) defines range for every stage
+///
+/// If it is empty [`push_constant_size`](PipelineCfg::push_constant_size) is used
+/// to define single range for all graphics stages (if size is not zero)
+///
+/// # Viewports
+/// If [`viewports`](PipelineCfg::viewports) is empty then single viewport and scissor
+/// which cover the whole [`extent`](PipelineCfg::extent) are used
+///
+/// Otherwise [`scissors`](PipelineCfg::scissors) must have the same length as `viewports`
+///
+/// More than one viewport requires
+/// [`multiViewport`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceFeatures.html)
+/// feature to be [enabled](crate::dev::DeviceCfg::features) on device.
+/// Shader selects viewport via `gl_ViewportIndex`
+///
+/// # Blending
+/// [`blend`](PipelineCfg::blend) defines blend state for every color attachment of the subpass
+///
+/// If it is empty single attachment without blending is assumed
+///
+/// Otherwise its length must match number of color attachments in the
+/// [`subpass`](PipelineCfg::subpass_index) (or number of [color formats](RenderingFormats::color))
+///
+/// # Dynamic rendering
+/// Exactly one of [`render_pass`](PipelineCfg::render_pass) and
+/// [`rendering_formats`](PipelineCfg::rendering_formats) must be set
+///
+/// Pipeline with `rendering_formats` is used within
+/// [`begin_rendering`](crate::cmd::Buffer::begin_rendering) and requires
+/// [`dynamic_rendering`](crate::dev::DeviceCfg::dynamic_rendering)
 pub struct PipelineCfg<'a> {
     pub vertex_shader: &'a shader::Shader,
     /// Size of every vertex
@@ -149,215 +497,755 @@ pub struct PipelineCfg<'a> {
     pub vert_input: &'a [VertexInputCfg],
     pub frag_shader: &'a shader::Shader,
     pub geom_shader: Option<&'a shader::Shader>,
+    pub tess_control_shader: Option<&'a shader::Shader>,
+    pub tess_eval_shader: Option<&'a shader::Shader>,
+    /// Number of control points per patch
+    ///
+    /// Ignored if tessellation shaders are not set
+    pub patch_control_points: u32,
+    /// Values of specialization constants for the selected shader stages
+    ///
+    /// Each stage may be specialized only once
+    pub specialization: &'a [(graphics::ShaderStage, &'a shader::SpecializationInfo<'a>)],
     pub topology: Topology,
     pub extent: memory::Extent2D,
+    pub viewports: &'a [Viewport],
+    pub scissors: &'a [Rect2D],
+    /// Size of push constant range for all graphics stages
+    ///
+    /// Ignored if [`push_constants`](PipelineCfg::push_constants) is not empty
     pub push_constant_size: u32,
-    pub render_pass: &'a graphics::RenderPass,
+    /// Push constant ranges per stage
+    pub push_constants: &'a [PushConstantCfg],
+    pub render_pass: Option<&'a graphics::RenderPass>,
     /// Subpass index inside [`RenderPass`](PipelineCfg::render_pass)
+    ///
+    /// Ignored for dynamic rendering
     pub subpass_index: u32,
-    pub enable_depth_test: bool,
+    /// Attachment formats for dynamic rendering
+    pub rendering_formats: Option<RenderingFormats<'a>>,
+    /// Number of rasterization samples
+    ///
+    /// Must match number of samples of the subpass attachments
+    pub samples: memory::SampleCount,
+    /// Blend state per color attachment
+    pub blend: &'a [BlendCfg],
+    /// Depth and stencil state, `None` disables both tests
+    pub depth_stencil: Option<DepthStencilCfg>,
+    /// Depth bias, `None` disables it
+    pub depth_bias: Option<DepthBiasCfg>,
     pub enable_primitive_restart: bool,
     pub cull_mode: CullMode,
-    pub descriptor: &'a graphics::PipelineDescriptor
+    /// Use `FILL` for solid geometry, `LINE` for wireframe and `POINT` for vertices only
+    pub polygon_mode: PolygonMode,
+    /// Width of rasterized lines, must be `1.0` unless `wideLines` feature is enabled
+    pub line_width: f32,
+    /// Winding of front-facing triangles, usually `COUNTER_CLOCKWISE`
+    pub front_face: FrontFace,
+    pub descriptor: &'a graphics::PipelineDescriptor,
+    /// Optional cache which is used during pipeline creation
+    pub cache: Option<&'a dev::PipelineCache>,
+    /// Index of the base pipeline in [`Pipeline::new_batch`]
+    ///
+    /// Must be less than index of this config. Must be `None` for [`Pipeline::new`]
+    pub derive_from: Option<usize>,
+}
+
+#[doc(hidden)]
+impl From<&PipelineCfg<'_>> for vk::PipelineRasterizationStateCreateInfo<'_> {
+    fn from(cfg: &PipelineCfg) -> Self {
+        let depth_bias = cfg.depth_bias.unwrap_or_default();
+
+        vk::PipelineRasterizationStateCreateInfo {
+            s_type: vk::StructureType::PIPELINE_RASTERIZATION_STATE_CREATE_INFO,
+            p_next: ptr::null(),
+            flags: vk::PipelineRasterizationStateCreateFlags::empty(),
+            depth_clamp_enable: vk::FALSE,
+            rasterizer_discard_enable: vk::FALSE,
+            polygon_mode: cfg.polygon_mode,
+            cull_mode: cfg.cull_mode,
+            front_face: cfg.front_face,
+            depth_bias_enable: cfg.depth_bias.is_some().into(),
+            depth_bias_constant_factor: depth_bias.constant_factor,
+            depth_bias_clamp: depth_bias.clamp,
+            depth_bias_slope_factor: depth_bias.slope_factor,
+            line_width: cfg.line_width,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a> PipelineCfg<'a> {
+    /// Create config with mandatory shaders and descriptor
+    ///
+    /// Other fields describe single triangle list pipeline without vertex input,
+    /// blending, depth test and culling, so override only what differs:
+    ///
+    /// ```ignore
+    /// let pipe_type = graphics::PipelineCfg {
+    ///     extent: capabilities.extent2d(),
+    ///     render_pass: Some(&render_pass),
+    ///     ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+    /// };
+    /// ```
+    ///
+    /// [`extent`](PipelineCfg::extent) is zero and has to be set unless
+    /// [`viewports`](PipelineCfg::viewports) are given. Either
+    /// [`render_pass`](PipelineCfg::render_pass) or
+    /// [`rendering_formats`](PipelineCfg::rendering_formats) has to be set as well
+    pub fn new(
+        vertex_shader: &'a shader::Shader,
+        frag_shader: &'a shader::Shader,
+        descriptor: &'a graphics::PipelineDescriptor
+    ) -> PipelineCfg<'a> {
+        PipelineCfg {
+            vertex_shader,
+            vertex_size: 0,
+            vert_input: &[],
+            frag_shader,
+            geom_shader: None,
+            tess_control_shader: None,
+            tess_eval_shader: None,
+            patch_control_points: 0,
+            specialization: &[],
+            topology: Topology::TRIANGLE_LIST,
+            extent: memory::Extent2D { width: 0, height: 0 },
+            viewports: &[],
+            scissors: &[],
+            push_constant_size: 0,
+            push_constants: &[],
+            render_pass: None,
+            subpass_index: 0,
+            rendering_formats: None,
+            samples: memory::SampleCount::TYPE_1,
+            blend: &[],
+            depth_stencil: None,
+            depth_bias: None,
+            enable_primitive_restart: false,
+            cull_mode: CullMode::NONE,
+            polygon_mode: PolygonMode::FILL,
+            line_width: 1.0,
+            front_face: FrontFace::COUNTER_CLOCKWISE,
+            descriptor,
+            cache: None,
+            derive_from: None,
+        }
+    }
+
+    /// Return vertex binding descriptions
+    ///
+    /// One description per distinct [`binding`](VertexInputCfg::binding) in ascending order
+    #[doc(hidden)]
+    pub fn vertex_bindings(&self) -> Vec<vk::VertexInputBindingDescription> {
+        let mut bindings: Vec<u32> = self.vert_input.iter().map(|x| x.binding).collect();
+
+        bindings.sort_unstable();
+        bindings.dedup();
+
+        bindings
+            .into_iter()
+            .map(|binding| vk::VertexInputBindingDescription {
+                binding,
+                stride: self.vertex_size,
+                input_rate: vk::VertexInputRate::VERTEX,
+            })
+            .collect()
+    }
+
+    /// Return stages of all shaders in config
+    pub fn stages(&self) -> graphics::ShaderStage {
+        let mut stages = graphics::ShaderStage::VERTEX | graphics::ShaderStage::FRAGMENT;
+
+        if self.geom_shader.is_some() {
+            stages |= graphics::ShaderStage::GEOMETRY;
+        }
+
+        if self.tess_control_shader.is_some() {
+            stages |= graphics::ShaderStage::TESSELLATION_CONTROL;
+        }
+
+        if self.tess_eval_shader.is_some() {
+            stages |= graphics::ShaderStage::TESSELLATION_EVALUATION;
+        }
+
+        stages
+    }
 }
 
 #[derive(Debug)]
 pub enum PipelineError {
-    DescriptorPool,
-    DescriptorSet,
-    DescriptorAllocation,
+    /// Failed to
+    /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateDescriptorPool.html) descriptor pool
+    DescriptorPool(vk::Result),
+    /// Failed to
+    /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateDescriptorSetLayout.html) descriptor set layout
+    DescriptorSet(vk::Result),
+    /// Failed to
+    /// [allocate](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkAllocateDescriptorSets.html) descriptor sets
+    DescriptorAllocation(vk::Result),
     /// Failed to create pipeline layout
-    Layout,
+    Layout(vk::Result),
+    /// Number of [`scissors`](PipelineCfg::scissors) does not match number of [`viewports`](PipelineCfg::viewports)
+    ViewportCount,
+    /// Multiple viewports are requested but `multiViewport` feature is not enabled
+    MultiViewport,
+    /// Number of [`blend`](PipelineCfg::blend) entries does not match number of subpass color attachments
+    BlendCount,
+    /// [`blend`](PipelineCfg::blend) entries differ but `independentBlend` feature is not enabled
+    IndependentBlend,
+    /// Depth bounds test is requested but `depthBounds` feature is not enabled
+    DepthBounds,
+    /// [`geom_shader`](PipelineCfg::geom_shader) is set but `geometryShader` feature is not enabled
+    GeometryShader,
+    /// Only one of [`tess_control_shader`](PipelineCfg::tess_control_shader)
+    /// and [`tess_eval_shader`](PipelineCfg::tess_eval_shader) is set
+    TessellationStages,
+    /// Tessellation is requested but `tessellationShader` feature is not enabled
+    Tessellation,
+    /// [`polygon_mode`](PipelineCfg::polygon_mode) is not `FILL` but `fillModeNonSolid` feature is not enabled
+    PolygonMode,
+    /// [`line_width`](PipelineCfg::line_width) is not `1.0` but `wideLines` feature is not enabled
+    WideLines,
+    /// [`line_width`](PipelineCfg::line_width) is out of device `lineWidthRange`
+    LineWidth,
+    /// Non-zero [`clamp`](DepthBiasCfg::clamp) is requested but `depthBiasClamp` feature is not enabled
+    DepthBiasClamp,
+    /// [`derive_from`](PipelineCfg::derive_from) does not refer to the previous config
+    DeriveIndex,
+    /// Not exactly one of [`render_pass`](PipelineCfg::render_pass)
+    /// and [`rendering_formats`](PipelineCfg::rendering_formats) is set
+    RenderTarget,
+    /// [`rendering_formats`](PipelineCfg::rendering_formats) is set but device was created without
+    /// [`dynamic_rendering`](crate::dev::DeviceCfg::dynamic_rendering)
+    DynamicRendering,
+    /// Entry of [`specialization`](PipelineCfg::specialization) is out of its data,
+    /// see [`SpecializationInfo::is_valid`](shader::SpecializationInfo::is_valid)
+    Specialization,
     /// Failed to create pipeline
-    Pipeline
+    Pipeline(vk::Result),
+    /// Error of the pipeline with given index in [`Pipeline::new_batch`]
+    Batch(usize, Box<PipelineError>)
 }
 
 impl fmt::Display for PipelineError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            PipelineError::DescriptorPool => write!(f, "Failed to create descriptor pool (vkCreateDescriptorPool call failed)"),
-            PipelineError::DescriptorSet => write!(f, "Failed to create descriptor set layout (vkCreateDescriptorSetLayout call failed)"),
-            PipelineError::DescriptorAllocation => write!(f, "Failed to allocate descriptor set (vkDescriptorSetAllocateInfo call failed)"),
-            PipelineError::Layout => write!(f, "vkCreatePipelineLayout call failed"),
-            PipelineError::Pipeline => write!(f, "vkCreateGraphicsPipelines call failed"),
+            PipelineError::DescriptorPool(_) => write!(f, "Failed to create descriptor pool (vkCreateDescriptorPool call failed)"),
+            PipelineError::DescriptorSet(_) => write!(f, "Failed to create descriptor set layout (vkCreateDescriptorSetLayout call failed)"),
+            PipelineError::DescriptorAllocation(_) => write!(f, "Failed to allocate descriptor set (vkDescriptorSetAllocateInfo call failed)"),
+            PipelineError::Layout(_) => write!(f, "vkCreatePipelineLayout call failed"),
+            PipelineError::ViewportCount => write!(f, "Number of scissors does not match number of viewports"),
+            PipelineError::MultiViewport => write!(f, "multiViewport feature is not enabled"),
+            PipelineError::BlendCount => write!(f, "Number of blend states does not match number of color attachments"),
+            PipelineError::IndependentBlend => write!(f, "independentBlend feature is not enabled"),
+            PipelineError::DepthBounds => write!(f, "depthBounds feature is not enabled"),
+            PipelineError::GeometryShader => write!(f, "geometryShader feature is not enabled"),
+            PipelineError::TessellationStages => write!(f, "Both tessellation control and evaluation shaders must be set"),
+            PipelineError::Tessellation => write!(f, "tessellationShader feature is not enabled"),
+            PipelineError::PolygonMode => write!(f, "fillModeNonSolid feature is not enabled"),
+            PipelineError::WideLines => write!(f, "wideLines feature is not enabled"),
+            PipelineError::LineWidth => write!(f, "Line width is out of device range"),
+            PipelineError::DepthBiasClamp => write!(f, "depthBiasClamp feature is not enabled"),
+            PipelineError::DeriveIndex => write!(f, "Base pipeline must precede derivative pipeline"),
+            PipelineError::RenderTarget => write!(f, "Either render pass or rendering formats must be set"),
+            PipelineError::DynamicRendering => write!(f, "dynamicRendering feature is not enabled"),
+            PipelineError::Specialization => write!(f, "Specialization constant is out of data range"),
+            PipelineError::Pipeline(_) => write!(f, "vkCreateGraphicsPipelines call failed"),
+            PipelineError::Batch(i, err) => write!(f, "Pipeline {}: {}", i, err),
         }
     }
 }
 
-impl Error for PipelineError { }
+impl Error for PipelineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PipelineError::DescriptorPool(result)
+            | PipelineError::DescriptorSet(result)
+            | PipelineError::DescriptorAllocation(result)
+            | PipelineError::Layout(result)
+            | PipelineError::Pipeline(result) => Some(result),
+            PipelineError::Batch(_, err) => Some(err.as_ref()),
+            _ => None
+        }
+    }
+}
 
-/// Graphics pipeline
-pub struct Pipeline {
+#[derive(Debug)]
+struct CorePipeline {
     i_core: Arc<dev::Core>,
     i_layout: vk::PipelineLayout,
-    i_pipeline: vk::Pipeline
+    i_pipeline: vk::Pipeline,
+    i_push_constants: Vec<vk::PushConstantRange>,
+    i_vertex_bindings: Vec<vk::VertexInputBindingDescription>,
+    i_stages: graphics::ShaderStage,
+    i_topology: Topology,
+    i_extent: memory::Extent2D,
+    i_samples: memory::SampleCount,
+    i_cull_mode: CullMode,
+    i_polygon_mode: PolygonMode,
+    i_front_face: FrontFace,
+    i_color_attachments: usize,
+}
+
+impl Drop for CorePipeline {
+    fn drop(&mut self) {
+        unsafe {
+            self.i_core.device().destroy_pipeline_layout(self.i_layout, self.i_core.allocator());
+            self.i_core.device().destroy_pipeline(self.i_pipeline, self.i_core.allocator());
+        }
+
+        self.i_core.destroyed(dev::ObjectType::Pipeline, 1);
+    }
 }
 
+/// Attachment formats of the pipeline used with dynamic rendering
+///
+/// Formats must match views passed to [`begin_rendering`](crate::cmd::Buffer::begin_rendering)
+#[derive(Debug, Clone, Copy)]
+pub struct RenderingFormats<'a> {
+    /// Format of every color attachment
+    pub color: &'a [memory::ImageFormat],
+    /// Format of the depth attachment if any
+    pub depth: Option<memory::ImageFormat>,
+}
+
+/// Graphics pipeline
+///
+/// Keeps essential parameters of [`PipelineCfg`] which are printed by `Display`
+#[derive(Debug)]
+pub struct Pipeline(Arc<CorePipeline>);
+
 impl Pipeline {
     pub fn new(device: &dev::Device, pipe_cfg: &PipelineCfg) -> Result<Pipeline, PipelineError> {
-        let mut shader_stage_create_infos = vec![
-            vk::PipelineShaderStageCreateInfo {
-                s_type: vk::StructureType::PIPELINE_SHADER_STAGE_CREATE_INFO,
-                p_next: ptr::null(),
-                flags: vk::PipelineShaderStageCreateFlags::empty(),
-                stage: vk::ShaderStageFlags::VERTEX,
-                module: pipe_cfg.vertex_shader.module(),
-                p_name: pipe_cfg.vertex_shader.entry().as_ptr(),
-                p_specialization_info: ptr::null(),
-                _marker: PhantomData,
-            },
-            vk::PipelineShaderStageCreateInfo {
-                s_type: vk::StructureType::PIPELINE_SHADER_STAGE_CREATE_INFO,
-                p_next: ptr::null(),
-                flags: vk::PipelineShaderStageCreateFlags::empty(),
-                stage: vk::ShaderStageFlags::FRAGMENT,
-                module: pipe_cfg.frag_shader.module(),
-                p_name: pipe_cfg.frag_shader.entry().as_ptr(),
-                p_specialization_info: ptr::null(),
-                _marker: PhantomData,
-            },
-        ];
-
-        if let Some(geom_shader) = pipe_cfg.geom_shader {
-            shader_stage_create_infos.push(
-                vk::PipelineShaderStageCreateInfo {
-                    s_type: vk::StructureType::PIPELINE_SHADER_STAGE_CREATE_INFO,
-                    p_next: ptr::null(),
-                    flags: vk::PipelineShaderStageCreateFlags::empty(),
-                    stage: vk::ShaderStageFlags::GEOMETRY,
-                    module: geom_shader.module(),
-                    p_name: geom_shader.entry().as_ptr(),
-                    p_specialization_info: ptr::null(),
-                    _marker: PhantomData,
+        let mut pipelines = Pipeline::create(device, std::slice::from_ref(pipe_cfg)).map_err(|(_, err)| err)?;
+
+        Ok(pipelines.remove(0))
+    }
+
+    /// Create several pipelines with single `vkCreateGraphicsPipelines` call
+    ///
+    /// Pipeline cache of the first config is used for all pipelines
+    ///
+    /// [`derive_from`](PipelineCfg::derive_from) may refer to the previous config in `cfgs`
+    ///
+    /// On failure [`PipelineError::Batch`] contains index of the config which caused error
+    pub fn new_batch(device: &dev::Device, cfgs: &[PipelineCfg]) -> Result<Vec<Pipeline>, PipelineError> {
+        if cfgs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Pipeline::create(device, cfgs).map_err(|(i, err)| PipelineError::Batch(i, Box::new(err)))
+    }
+
+    fn create(device: &dev::Device, cfgs: &[PipelineCfg]) -> Result<Vec<Pipeline>, (usize, PipelineError)> {
+        let mut data: Vec<PipelineData> = Vec::with_capacity(cfgs.len());
+
+        for (i, cfg) in cfgs.iter().enumerate() {
+            match PipelineData::new(device, cfg, i) {
+                Ok(val) => data.push(val),
+                Err(err) => {
+                    data.iter().for_each(|x| x.destroy_layout(device));
+                    return Err((i, err));
                 }
-            );
+            }
         }
 
-        let vertex_binding_descriptions: Vec<vk::VertexInputBindingDescription> =
-            (0..pipe_cfg.vert_input.len() as u32)
-            .map(|i| vk::VertexInputBindingDescription {
-                binding: i,
-                stride: pipe_cfg.vertex_size,
-                input_rate: vk::VertexInputRate::VERTEX,
+        let spec_infos: Vec<Vec<vk::SpecializationInfo>> = cfgs
+            .iter()
+            .zip(data.iter())
+            .map(|(cfg, data)| {
+                cfg.specialization
+                .iter()
+                .zip(data.i_spec_entries.iter())
+                .map(|((_, spec), entries)| spec.raw(entries))
+                .collect()
             })
             .collect();
 
-        let vertex_attribute_descriptions: Vec<vk::VertexInputAttributeDescription> =
-            pipe_cfg.vert_input.iter().map(|x| x.into()).collect();
+        let stages: Vec<Vec<vk::PipelineShaderStageCreateInfo>> = cfgs
+            .iter()
+            .zip(spec_infos.iter())
+            .map(|(cfg, spec_infos)| shader_stages(cfg, spec_infos))
+            .collect();
 
-        let vertex_input_state_create_info = vk::PipelineVertexInputStateCreateInfo {
-            s_type: vk::StructureType::PIPELINE_VERTEX_INPUT_STATE_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::PipelineVertexInputStateCreateFlags::empty(),
-            vertex_binding_description_count: vertex_binding_descriptions.len() as u32,
-            p_vertex_binding_descriptions: data_ptr!(vertex_binding_descriptions),
-            vertex_attribute_description_count: vertex_attribute_descriptions.len() as u32,
-            p_vertex_attribute_descriptions: data_ptr!(vertex_attribute_descriptions),
-            _marker: PhantomData,
-        };
+        let states: Vec<FixedState> = cfgs
+            .iter()
+            .zip(data.iter())
+            .map(|(cfg, data)| data.fixed_state(cfg))
+            .collect();
 
-        let input_assembly_state_create_info = vk::PipelineInputAssemblyStateCreateInfo {
-            s_type: vk::StructureType::PIPELINE_INPUT_ASSEMBLY_STATE_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::PipelineInputAssemblyStateCreateFlags::empty(),
-            topology: pipe_cfg.topology,
-            primitive_restart_enable: pipe_cfg.enable_primitive_restart as ash::vk::Bool32,
-            _marker: PhantomData,
-        };
+        let rendering_infos: Vec<vk::PipelineRenderingCreateInfo> = data
+            .iter()
+            .map(|data| vk::PipelineRenderingCreateInfo {
+                s_type: vk::StructureType::PIPELINE_RENDERING_CREATE_INFO,
+                p_next: ptr::null(),
+                view_mask: 0,
+                color_attachment_count: data.i_color_formats.len() as u32,
+                p_color_attachment_formats: data_ptr!(data.i_color_formats),
+                depth_attachment_format: data.i_depth_format,
+                stencil_attachment_format: vk::Format::UNDEFINED,
+                _marker: PhantomData,
+            })
+            .collect();
 
-        let viewports = [vk::Viewport {
-            x: 0_f32,
-            y: 0_f32,
-            width: pipe_cfg.extent.width as f32,
-            height: pipe_cfg.extent.height as f32,
-            min_depth: 0_f32,
-            max_depth: 1_f32,
-        }];
+        let allow_derivatives: Vec<bool> = (0..cfgs.len())
+            .map(|i| cfgs.iter().any(|x| x.derive_from == Some(i)))
+            .collect();
 
-        let scissors = [vk::Rect2D {
-            offset: vk::Offset2D { x: 0, y: 0 },
-            extent: pipe_cfg.extent,
-        }];
+        let create_infos: Vec<vk::GraphicsPipelineCreateInfo> = (0..cfgs.len())
+            .map(|i| {
+                let cfg = &cfgs[i];
+                let state = &states[i];
 
-        /*
-            Now we must specify the form of output data
-            Viewport specifies to what part of the image (or texture, or window) we want do draw
-        */
-        let viewport_state_create_info = vk::PipelineViewportStateCreateInfo {
-            s_type: vk::StructureType::PIPELINE_VIEWPORT_STATE_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::PipelineViewportStateCreateFlags::empty(),
-            viewport_count: viewports.len() as u32,
-            p_viewports: data_ptr!(viewports),
-            scissor_count: scissors.len() as u32,
-            p_scissors: data_ptr!(scissors),
-            _marker: PhantomData,
+                let mut flags = vk::PipelineCreateFlags::empty();
+
+                if allow_derivatives[i] {
+                    flags |= vk::PipelineCreateFlags::ALLOW_DERIVATIVES;
+                }
+
+                if cfg.derive_from.is_some() {
+                    flags |= vk::PipelineCreateFlags::DERIVATIVE;
+                }
+
+                vk::GraphicsPipelineCreateInfo {
+                    s_type: vk::StructureType::GRAPHICS_PIPELINE_CREATE_INFO,
+                    p_next: if cfg.rendering_formats.is_some() {
+                        &rendering_infos[i] as *const vk::PipelineRenderingCreateInfo as *const std::ffi::c_void
+                    } else {
+                        ptr::null()
+                    },
+                    flags,
+                    stage_count: stages[i].len() as u32,
+                    p_stages: stages[i].as_ptr(),
+                    p_vertex_input_state: &state.i_vertex_input,
+                    p_input_assembly_state: &state.i_input_assembly,
+                    p_tessellation_state: if data[i].i_tessellation {
+                        &state.i_tessellation
+                    } else {
+                        ptr::null()
+                    },
+                    p_viewport_state: &state.i_viewport,
+                    p_rasterization_state: &state.i_rasterization,
+                    p_multisample_state: &state.i_multisample,
+                    p_depth_stencil_state: match &data[i].i_depth {
+                        Some(cfg) => cfg,
+                        None => ptr::null()
+                    },
+                    p_color_blend_state: &state.i_color_blend,
+                    p_dynamic_state: ptr::null(),
+                    layout: data[i].i_layout,
+                    render_pass: cfg.render_pass.map_or(vk::RenderPass::null(), |x| x.render_pass()),
+                    subpass: if cfg.render_pass.is_some() { cfg.subpass_index } else { 0 },
+                    base_pipeline_handle: vk::Pipeline::null(),
+                    base_pipeline_index: cfg.derive_from.map_or(-1, |x| x as i32),
+                    _marker: PhantomData,
+                }
+            })
+            .collect();
+
+        let result = unsafe {
+            device
+            .device()
+            .create_graphics_pipelines(
+                cfgs[0].cache.map_or(vk::PipelineCache::null(), |x| x.cache()),
+                &create_infos,
+                device.allocator()
+            )
         };
 
-        /*
-            The next part of the graphics pipeline creation applies to the rasterization state
-            We must specify how polygons are going to be rasterized (changed into fragments)
-        */
-        let rasterization_state_create_info = vk::PipelineRasterizationStateCreateInfo {
-            s_type: vk::StructureType::PIPELINE_RASTERIZATION_STATE_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::PipelineRasterizationStateCreateFlags::empty(),
-            depth_clamp_enable: ash::vk::FALSE,
-            rasterizer_discard_enable: ash::vk::FALSE,
-            polygon_mode: vk::PolygonMode::FILL,
-            cull_mode: pipe_cfg.cull_mode,
-            front_face: vk::FrontFace::COUNTER_CLOCKWISE,
-            depth_bias_enable: ash::vk::FALSE,
-            depth_bias_constant_factor: 0.0,
-            depth_bias_clamp: 0.0,
-            depth_bias_slope_factor: 0.0,
-            line_width: 1.0,
-            _marker: PhantomData,
+        let pipelines = match result {
+            Ok(val) => val,
+            Err((pipelines, result)) => {
+                for &pipeline in &pipelines {
+                    if pipeline != vk::Pipeline::null() {
+                        unsafe { device.device().destroy_pipeline(pipeline, device.allocator()) };
+                    }
+                }
+
+                data.iter().for_each(|x| x.destroy_layout(device));
+
+                let failed = pipelines.iter().position(|&x| x == vk::Pipeline::null()).unwrap_or(0);
+
+                return Err((failed, PipelineError::Pipeline(result)));
+            }
         };
 
-        /*
-            In Vulkan, when we are creating a graphics pipeline, we must also specify the state relevant to multisampling
-        */
-        let multisample_state_create_info = vk::PipelineMultisampleStateCreateInfo {
-            s_type: vk::StructureType::PIPELINE_MULTISAMPLE_STATE_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::PipelineMultisampleStateCreateFlags::empty(),
-            rasterization_samples: vk::SampleCountFlags::TYPE_1,
-            sample_shading_enable: ash::vk::FALSE,
-            min_sample_shading: 1.0,
-            p_sample_mask: ptr::null(),
-            alpha_to_coverage_enable: ash::vk::FALSE,
-            alpha_to_one_enable: ash::vk::FALSE,
-            _marker: PhantomData,
+        device.core().created(dev::ObjectType::Pipeline, pipelines.len());
+
+        Ok(
+            pipelines
+            .into_iter()
+            .zip(data)
+            .zip(cfgs)
+            .map(|((pipeline, data), cfg)| Pipeline(
+                Arc::new(CorePipeline {
+                    i_core: device.core().clone(),
+                    i_layout: data.i_layout,
+                    i_pipeline: pipeline,
+                    i_push_constants: data.i_push_constants,
+                    i_vertex_bindings: data.i_vertex_bindings,
+                    i_stages: cfg.stages(),
+                    i_topology: cfg.topology,
+                    i_extent: cfg.extent,
+                    i_samples: cfg.samples,
+                    i_cull_mode: cfg.cull_mode,
+                    i_polygon_mode: cfg.polygon_mode,
+                    i_front_face: cfg.front_face,
+                    i_color_attachments: data.i_color_attachments,
+                })
+            ))
+            .collect()
+        )
+    }
+
+    #[doc(hidden)]
+    pub fn pipeline(&self) -> vk::Pipeline {
+        self.0.i_pipeline
+    }
+
+    #[doc(hidden)]
+    pub fn layout(&self) -> vk::PipelineLayout {
+        self.0.i_layout
+    }
+
+    #[doc(hidden)]
+    pub fn push_constant_ranges(&self) -> &[vk::PushConstantRange] {
+        &self.0.i_push_constants
+    }
+
+    /// Size of every vertex, see [`PipelineCfg::vertex_size`]
+    ///
+    /// Zero if pipeline has no vertex input
+    pub fn vertex_size(&self) -> u32 {
+        self.0.i_vertex_bindings.first().map_or(0, |x| x.stride)
+    }
+
+    /// Vertex bindings used by the pipeline
+    pub(crate) fn vertex_bindings(&self) -> &[vk::VertexInputBindingDescription] {
+        &self.0.i_vertex_bindings
+    }
+
+    /// Shader stages of the pipeline
+    pub fn stages(&self) -> graphics::ShaderStage {
+        self.0.i_stages
+    }
+
+    /// See [`PipelineCfg::topology`]
+    pub fn topology(&self) -> Topology {
+        self.0.i_topology
+    }
+
+    /// See [`PipelineCfg::extent`]
+    pub fn extent(&self) -> memory::Extent2D {
+        self.0.i_extent
+    }
+
+    /// See [`PipelineCfg::samples`]
+    pub fn samples(&self) -> memory::SampleCount {
+        self.0.i_samples
+    }
+
+    /// See [`PipelineCfg::cull_mode`]
+    pub fn cull_mode(&self) -> CullMode {
+        self.0.i_cull_mode
+    }
+
+    /// See [`PipelineCfg::polygon_mode`]
+    pub fn polygon_mode(&self) -> PolygonMode {
+        self.0.i_polygon_mode
+    }
+
+    /// See [`PipelineCfg::front_face`]
+    pub fn front_face(&self) -> FrontFace {
+        self.0.i_front_face
+    }
+
+    /// Number of color attachments the pipeline writes to
+    pub fn color_attachment_count(&self) -> usize {
+        self.0.i_color_attachments
+    }
+
+    pub(crate) fn keepalive(&self) -> dev::Keepalive {
+        self.0.clone()
+    }
+}
+
+impl fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Graphics pipeline\n\
+            Stages:               {:?}\n\
+            Topology:             {:?}\n\
+            Extent:               {}x{}\n\
+            Samples:              {:?}\n\
+            Cull mode:            {}\n\
+            Polygon mode:         {:?}\n\
+            Front face:           {:?}\n\
+            Color attachments:    {}\n\
+            Vertex size:          {}\n\
+            Push constant ranges: {}\n",
+            self.stages(),
+            self.topology(),
+            self.extent().width,
+            self.extent().height,
+            self.samples(),
+            if self.cull_mode().is_empty() { "NONE".to_string() } else { format!("{:?}", self.cull_mode()) },
+            self.polygon_mode(),
+            self.front_face(),
+            self.color_attachment_count(),
+            self.vertex_size(),
+            self.push_constant_ranges().len()
+        )
+    }
+}
+
+/// Data owned by the single pipeline during creation
+///
+/// Create infos point into it so it must outlive `vkCreateGraphicsPipelines` call
+struct PipelineData {
+    i_spec_entries: Vec<Vec<vk::SpecializationMapEntry>>,
+    i_vertex_bindings: Vec<vk::VertexInputBindingDescription>,
+    i_vertex_attributes: Vec<vk::VertexInputAttributeDescription>,
+    i_viewports: Vec<vk::Viewport>,
+    i_scissors: Vec<vk::Rect2D>,
+    i_blend_attachments: Vec<vk::PipelineColorBlendAttachmentState>,
+    i_depth: Option<vk::PipelineDepthStencilStateCreateInfo<'static>>,
+    i_tessellation: bool,
+    i_push_constants: Vec<vk::PushConstantRange>,
+    i_layout: vk::PipelineLayout,
+    i_color_formats: Vec<vk::Format>,
+    i_depth_format: vk::Format,
+    i_color_attachments: usize,
+}
+
+/// Fixed function state of the single pipeline
+struct FixedState<'a> {
+    i_vertex_input: vk::PipelineVertexInputStateCreateInfo<'a>,
+    i_input_assembly: vk::PipelineInputAssemblyStateCreateInfo<'a>,
+    i_tessellation: vk::PipelineTessellationStateCreateInfo<'a>,
+    i_viewport: vk::PipelineViewportStateCreateInfo<'a>,
+    i_rasterization: vk::PipelineRasterizationStateCreateInfo<'a>,
+    i_multisample: vk::PipelineMultisampleStateCreateInfo<'a>,
+    i_color_blend: vk::PipelineColorBlendStateCreateInfo<'a>,
+}
+
+impl PipelineData {
+    /// Validate `pipe_cfg` at position `index` and create pipeline layout
+    fn new(device: &dev::Device, pipe_cfg: &PipelineCfg, index: usize) -> Result<PipelineData, PipelineError> {
+        if !pipe_cfg.viewports.is_empty() && pipe_cfg.viewports.len() != pipe_cfg.scissors.len() {
+            return Err(PipelineError::ViewportCount);
+        }
+
+        if pipe_cfg.viewports.len() > 1 && device.features().multi_viewport == vk::FALSE {
+            return Err(PipelineError::MultiViewport);
+        }
+
+        if pipe_cfg.depth_stencil.is_some_and(|x| x.depth_bounds.is_some())
+            && device.features().depth_bounds == vk::FALSE
+        {
+            return Err(PipelineError::DepthBounds);
+        }
+
+        if pipe_cfg.geom_shader.is_some() && device.features().geometry_shader == vk::FALSE {
+            return Err(PipelineError::GeometryShader);
+        }
+
+        if pipe_cfg.tess_control_shader.is_some() != pipe_cfg.tess_eval_shader.is_some() {
+            return Err(PipelineError::TessellationStages);
+        }
+
+        let tessellation = pipe_cfg.tess_control_shader.is_some();
+
+        if tessellation && device.features().tessellation_shader == vk::FALSE {
+            return Err(PipelineError::Tessellation);
+        }
+
+        if pipe_cfg.polygon_mode != PolygonMode::FILL && device.features().fill_mode_non_solid == vk::FALSE {
+            return Err(PipelineError::PolygonMode);
+        }
+
+        if pipe_cfg.line_width != 1.0 {
+            if device.features().wide_lines == vk::FALSE {
+                return Err(PipelineError::WideLines);
+            }
+
+            let [min, max] = device.hw().limits().line_width_range;
+
+            if !(min..=max).contains(&pipe_cfg.line_width) {
+                return Err(PipelineError::LineWidth);
+            }
+        }
+
+        if pipe_cfg.depth_bias.is_some_and(|x| x.clamp != 0.0) && device.features().depth_bias_clamp == vk::FALSE {
+            return Err(PipelineError::DepthBiasClamp);
+        }
+
+        if pipe_cfg.render_pass.is_some() == pipe_cfg.rendering_formats.is_some() {
+            return Err(PipelineError::RenderTarget);
+        }
+
+        if pipe_cfg.rendering_formats.is_some() && !device.is_dynamic_rendering_enabled() {
+            return Err(PipelineError::DynamicRendering);
+        }
+
+        let color_count = match (pipe_cfg.render_pass, &pipe_cfg.rendering_formats) {
+            (Some(rp), _) => rp.color_attachment_count(pipe_cfg.subpass_index),
+            (None, Some(formats)) => Some(formats.color.len()),
+            (None, None) => None
         };
 
-        let color_blend_attachment_state = vk::PipelineColorBlendAttachmentState {
-            blend_enable: ash::vk::FALSE,
-            src_color_blend_factor: vk::BlendFactor::ONE,
-            dst_color_blend_factor: vk::BlendFactor::ZERO,
-            color_blend_op: vk::BlendOp::ADD,
-            src_alpha_blend_factor: vk::BlendFactor::ONE,
-            dst_alpha_blend_factor: vk::BlendFactor::ZERO,
-            alpha_blend_op: vk::BlendOp::ADD,
-            color_write_mask: vk::ColorComponentFlags::RGBA,
+        if !pipe_cfg.blend.is_empty() && color_count != Some(pipe_cfg.blend.len()) {
+            return Err(PipelineError::BlendCount);
+        }
+
+        if pipe_cfg.blend.windows(2).any(|x| x[0] != x[1]) && device.features().independent_blend == vk::FALSE {
+            return Err(PipelineError::IndependentBlend);
+        }
+
+        if pipe_cfg.derive_from.is_some_and(|x| x >= index) {
+            return Err(PipelineError::DeriveIndex);
+        }
+
+        if !pipe_cfg.specialization.iter().all(|(_, spec)| spec.is_valid()) {
+            return Err(PipelineError::Specialization);
+        }
+
+        let (viewports, scissors) = if pipe_cfg.viewports.is_empty() {
+            (
+                vec![vk::Viewport {
+                    x: 0_f32,
+                    y: 0_f32,
+                    width: pipe_cfg.extent.width as f32,
+                    height: pipe_cfg.extent.height as f32,
+                    min_depth: 0_f32,
+                    max_depth: 1_f32,
+                }],
+                vec![vk::Rect2D {
+                    offset: vk::Offset2D { x: 0, y: 0 },
+                    extent: pipe_cfg.extent,
+                }]
+            )
+        } else {
+            (pipe_cfg.viewports.to_vec(), pipe_cfg.scissors.to_vec())
         };
 
-        let color_blend_state_create_info = vk::PipelineColorBlendStateCreateInfo {
-            s_type: vk::StructureType::PIPELINE_COLOR_BLEND_STATE_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::PipelineColorBlendStateCreateFlags::empty(),
-            logic_op_enable: ash::vk::FALSE,
-            logic_op: vk::LogicOp::COPY,
-            attachment_count: 1,
-            p_attachments: &color_blend_attachment_state,
-            blend_constants: [0.0; 4],
-            _marker: PhantomData,
+        let blend_attachments: Vec<vk::PipelineColorBlendAttachmentState> = if pipe_cfg.blend.is_empty() {
+            vec![(&BlendCfg::opaque()).into()]
+        } else {
+            pipe_cfg.blend.iter().map(|x| x.into()).collect()
         };
 
-        let push_const_range = vk::PushConstantRange {
-            stage_flags: vk::ShaderStageFlags::ALL_GRAPHICS,
-            offset: 0,
-            size: pipe_cfg.push_constant_size,
+        let push_const_ranges: Vec<vk::PushConstantRange> = if !pipe_cfg.push_constants.is_empty() {
+            pipe_cfg.push_constants.iter().map(|x| x.into()).collect()
+        } else if pipe_cfg.push_constant_size != 0 {
+            vec![vk::PushConstantRange {
+                stage_flags: vk::ShaderStageFlags::ALL_GRAPHICS,
+                offset: 0,
+                size: pipe_cfg.push_constant_size,
+            }]
+        } else {
+            Vec::new()
         };
 
         /*
@@ -369,107 +1257,148 @@ impl Pipeline {
             flags: vk::PipelineLayoutCreateFlags::empty(),
             set_layout_count: pipe_cfg.descriptor.descriptor_layouts().len() as u32,
             p_set_layouts: data_ptr!(pipe_cfg.descriptor.descriptor_layouts()),
-            push_constant_range_count: if pipe_cfg.push_constant_size != 0 {
-                1
-            } else {
-                0
-            },
-            p_push_constant_ranges: if pipe_cfg.push_constant_size != 0 {
-                &push_const_range
-            } else {
-                ptr::null()
-            },
+            push_constant_range_count: push_const_ranges.len() as u32,
+            p_push_constant_ranges: data_ptr!(push_const_ranges),
             _marker: PhantomData,
         };
 
-        let pipeline_layout = unsafe { on_error_ret!(
-		    device.device().create_pipeline_layout(&layout_create_info, device.allocator()),
-            PipelineError::Layout
+        let pipeline_layout = unsafe { on_error!(
+            device.device().create_pipeline_layout(&layout_create_info, device.allocator()),
+            |err| return Err(PipelineError::Layout(err))
         )};
 
-        let depth_cfg = vk::PipelineDepthStencilStateCreateInfo {
-            s_type: vk::StructureType::PIPELINE_DEPTH_STENCIL_STATE_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::PipelineDepthStencilStateCreateFlags::empty(),
-            depth_test_enable: 1,
-            depth_write_enable: 1,
-            depth_compare_op: vk::CompareOp::LESS_OR_EQUAL,
-            depth_bounds_test_enable: 0,
-            stencil_test_enable: 0,
-            front: vk::StencilOpState::default(),
-            back: vk::StencilOpState::default(),
-            min_depth_bounds: f32::default(),
-            max_depth_bounds: f32::default(),
-            _marker: PhantomData,
-        };
-
-        let pipeline_create_info = vk::GraphicsPipelineCreateInfo {
-            s_type: vk::StructureType::GRAPHICS_PIPELINE_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::PipelineCreateFlags::empty(),
-            stage_count: shader_stage_create_infos.len() as u32,
-            p_stages: shader_stage_create_infos.as_ptr(),
-            p_vertex_input_state: &vertex_input_state_create_info,
-            p_input_assembly_state: &input_assembly_state_create_info,
-            p_tessellation_state: ptr::null(),
-            p_viewport_state: &viewport_state_create_info,
-            p_rasterization_state: &rasterization_state_create_info,
-            p_multisample_state: &multisample_state_create_info,
-            p_depth_stencil_state: if pipe_cfg.enable_depth_test {
-                &depth_cfg
-            } else {
-                ptr::null()
-            },
-            p_color_blend_state: &color_blend_state_create_info,
-            p_dynamic_state: ptr::null(),
-            layout: pipeline_layout,
-            render_pass: pipe_cfg.render_pass.render_pass(),
-            subpass: 0,
-            base_pipeline_handle: vk::Pipeline::null(),
-            base_pipeline_index: -1,
-            _marker: PhantomData,
-        };
-
-        let pipeline = unsafe { on_error!(
-            device
-            .device()
-            .create_graphics_pipelines(
-                vk::PipelineCache::null(),
-                &[pipeline_create_info],
-                device.allocator()
-            ),
-            {
-                device.device().destroy_pipeline_layout(pipeline_layout, device.allocator());
-                return Err(PipelineError::Pipeline);
-            }
-        )};
-
-
-        Ok(
-            Pipeline {
-                i_core: device.core().clone(),
-                i_layout: pipeline_layout,
-                i_pipeline: pipeline[0]
-            }
-        )
+        Ok(PipelineData {
+            i_spec_entries: pipe_cfg.specialization.iter().map(|(_, spec)| spec.map_entries()).collect(),
+            i_vertex_bindings: pipe_cfg.vertex_bindings(),
+            i_vertex_attributes: pipe_cfg.vert_input.iter().map(|x| x.into()).collect(),
+            i_viewports: viewports,
+            i_scissors: scissors,
+            i_blend_attachments: blend_attachments,
+            i_depth: pipe_cfg.depth_stencil.as_ref().map(|x| x.into()),
+            i_tessellation: tessellation,
+            i_push_constants: push_const_ranges,
+            i_layout: pipeline_layout,
+            i_color_formats: pipe_cfg.rendering_formats.as_ref().map_or(Vec::new(), |x| x.color.to_vec()),
+            i_depth_format: pipe_cfg
+                .rendering_formats
+                .as_ref()
+                .and_then(|x| x.depth)
+                .unwrap_or(vk::Format::UNDEFINED),
+            i_color_attachments: color_count.unwrap_or(0),
+        })
     }
 
-    #[doc(hidden)]
-    pub fn pipeline(&self) -> vk::Pipeline {
-        self.i_pipeline
+    fn fixed_state(&self, pipe_cfg: &PipelineCfg) -> FixedState<'_> {
+        FixedState {
+            i_vertex_input: vk::PipelineVertexInputStateCreateInfo {
+                s_type: vk::StructureType::PIPELINE_VERTEX_INPUT_STATE_CREATE_INFO,
+                p_next: ptr::null(),
+                flags: vk::PipelineVertexInputStateCreateFlags::empty(),
+                vertex_binding_description_count: self.i_vertex_bindings.len() as u32,
+                p_vertex_binding_descriptions: data_ptr!(self.i_vertex_bindings),
+                vertex_attribute_description_count: self.i_vertex_attributes.len() as u32,
+                p_vertex_attribute_descriptions: data_ptr!(self.i_vertex_attributes),
+                _marker: PhantomData,
+            },
+            i_input_assembly: vk::PipelineInputAssemblyStateCreateInfo {
+                s_type: vk::StructureType::PIPELINE_INPUT_ASSEMBLY_STATE_CREATE_INFO,
+                p_next: ptr::null(),
+                flags: vk::PipelineInputAssemblyStateCreateFlags::empty(),
+                topology: pipe_cfg.topology,
+                primitive_restart_enable: pipe_cfg.enable_primitive_restart as ash::vk::Bool32,
+                _marker: PhantomData,
+            },
+            i_tessellation: vk::PipelineTessellationStateCreateInfo {
+                s_type: vk::StructureType::PIPELINE_TESSELLATION_STATE_CREATE_INFO,
+                p_next: ptr::null(),
+                flags: vk::PipelineTessellationStateCreateFlags::empty(),
+                patch_control_points: pipe_cfg.patch_control_points,
+                _marker: PhantomData,
+            },
+            /*
+                Now we must specify the form of output data
+                Viewport specifies to what part of the image (or texture, or window) we want do draw
+            */
+            i_viewport: vk::PipelineViewportStateCreateInfo {
+                s_type: vk::StructureType::PIPELINE_VIEWPORT_STATE_CREATE_INFO,
+                p_next: ptr::null(),
+                flags: vk::PipelineViewportStateCreateFlags::empty(),
+                viewport_count: self.i_viewports.len() as u32,
+                p_viewports: data_ptr!(self.i_viewports),
+                scissor_count: self.i_scissors.len() as u32,
+                p_scissors: data_ptr!(self.i_scissors),
+                _marker: PhantomData,
+            },
+            /*
+                The next part of the graphics pipeline creation applies to the rasterization state
+                We must specify how polygons are going to be rasterized (changed into fragments)
+            */
+            i_rasterization: pipe_cfg.into(),
+            /*
+                In Vulkan, when we are creating a graphics pipeline, we must also specify the state relevant to multisampling
+            */
+            i_multisample: vk::PipelineMultisampleStateCreateInfo {
+                s_type: vk::StructureType::PIPELINE_MULTISAMPLE_STATE_CREATE_INFO,
+                p_next: ptr::null(),
+                flags: vk::PipelineMultisampleStateCreateFlags::empty(),
+                rasterization_samples: pipe_cfg.samples,
+                sample_shading_enable: ash::vk::FALSE,
+                min_sample_shading: 1.0,
+                p_sample_mask: ptr::null(),
+                alpha_to_coverage_enable: ash::vk::FALSE,
+                alpha_to_one_enable: ash::vk::FALSE,
+                _marker: PhantomData,
+            },
+            i_color_blend: vk::PipelineColorBlendStateCreateInfo {
+                s_type: vk::StructureType::PIPELINE_COLOR_BLEND_STATE_CREATE_INFO,
+                p_next: ptr::null(),
+                flags: vk::PipelineColorBlendStateCreateFlags::empty(),
+                logic_op_enable: ash::vk::FALSE,
+                logic_op: vk::LogicOp::COPY,
+                attachment_count: self.i_blend_attachments.len() as u32,
+                p_attachments: self.i_blend_attachments.as_ptr(),
+                blend_constants: [0.0; 4],
+                _marker: PhantomData,
+            },
+        }
     }
 
-    #[doc(hidden)]
-    pub fn layout(&self) -> vk::PipelineLayout {
-        self.i_layout
+    fn destroy_layout(&self, device: &dev::Device) {
+        unsafe { device.device().destroy_pipeline_layout(self.i_layout, device.allocator()) };
     }
 }
 
-impl Drop for Pipeline {
-    fn drop(&mut self) {
-        unsafe {
-            self.i_core.device().destroy_pipeline_layout(self.i_layout, self.i_core.allocator());
-            self.i_core.device().destroy_pipeline(self.i_pipeline, self.i_core.allocator());
+fn shader_stages<'a>(
+    pipe_cfg: &'a PipelineCfg,
+    spec_infos: &'a [vk::SpecializationInfo]
+) -> Vec<vk::PipelineShaderStageCreateInfo<'a>> {
+    let spec_info = |stage: graphics::ShaderStage| -> *const vk::SpecializationInfo {
+        match pipe_cfg.specialization.iter().position(|(s, _)| *s == stage) {
+            Some(i) => &spec_infos[i],
+            None => ptr::null()
         }
-    }
+    };
+
+    let stages = [
+        (vk::ShaderStageFlags::VERTEX, Some(pipe_cfg.vertex_shader)),
+        (vk::ShaderStageFlags::FRAGMENT, Some(pipe_cfg.frag_shader)),
+        (vk::ShaderStageFlags::GEOMETRY, pipe_cfg.geom_shader),
+        (vk::ShaderStageFlags::TESSELLATION_CONTROL, pipe_cfg.tess_control_shader),
+        (vk::ShaderStageFlags::TESSELLATION_EVALUATION, pipe_cfg.tess_eval_shader),
+    ];
+
+    stages
+    .into_iter()
+    .filter_map(|(stage, shader)| shader.map(|shader| (stage, shader)))
+    .map(|(stage, shader)| vk::PipelineShaderStageCreateInfo {
+        s_type: vk::StructureType::PIPELINE_SHADER_STAGE_CREATE_INFO,
+        p_next: ptr::null(),
+        flags: vk::PipelineShaderStageCreateFlags::empty(),
+        stage,
+        module: shader.module(),
+        p_name: shader.entry().as_ptr(),
+        p_specialization_info: spec_info(stage),
+        _marker: PhantomData,
+    })
+    .collect()
 }
@@ -11,7 +11,8 @@ use crate::{
     graphics,
     on_error,
     data_ptr,
-    memory
+    memory,
+    shader
 };
 
 use std::{
@@ -19,6 +20,7 @@ use std::{
     fmt
 };
 use std::error::Error;
+use std::sync::Mutex;
 use std::sync::Arc;
 use std::marker::PhantomData;
 
@@ -45,6 +47,8 @@ impl<'a> BufferBinding<'a> {
     }
 
     /// Suitable for dynamic buffers
+    ///
+    /// `offset` is relative to the `view` which may be [slice](memory::View::slice) of the buffer
     pub fn with_params(view: memory::View<'a>, offset: u64, range: u64) -> BufferBinding {
         BufferBinding {
             view,
@@ -54,10 +58,93 @@ impl<'a> BufferBinding<'a> {
     }
 }
 
+/// Image which is read by shader via `subpassLoad`
+///
+/// Unlike sampled images input attachment does not use sampler
+///
+/// Image must be an [input attachment](graphics::SubpassInfo::input_attachments) of the subpass
+/// and be created with `INPUT_ATTACHMENT` usage
+#[derive(Debug, Clone, Copy)]
+pub struct InputAttachmentBinding<'a> {
+    pub view: memory::ImageView<'a>,
+    /// Layout of the image during the subpass
+    pub layout: memory::ImageLayout,
+}
+
+impl<'a> InputAttachmentBinding<'a> {
+    /// Create binding with `SHADER_READ_ONLY_OPTIMAL` layout
+    ///
+    /// It matches layout of input attachments in [`RenderPass`](graphics::RenderPass)
+    pub fn new(view: memory::ImageView<'a>) -> InputAttachmentBinding<'a> {
+        InputAttachmentBinding {
+            view,
+            layout: memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        }
+    }
+}
+
+/// Image which is accessed by shader without sampler
+///
+/// Suitable for [`DescriptorType::SAMPLED_IMAGE`] (e.g. `texture2D`)
+/// and [`DescriptorType::STORAGE_IMAGE`] (e.g. `image2D`)
+#[derive(Debug, Clone, Copy)]
+pub struct ImageBinding<'a> {
+    pub view: memory::ImageView<'a>,
+    /// Layout of the image during shader access
+    pub layout: memory::ImageLayout,
+}
+
+impl<'a> ImageBinding<'a> {
+    /// Create binding with `SHADER_READ_ONLY_OPTIMAL` layout
+    ///
+    /// Use it for sampled images
+    pub fn new(view: memory::ImageView<'a>) -> ImageBinding<'a> {
+        ImageBinding {
+            view,
+            layout: memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        }
+    }
+
+    /// Create binding with `GENERAL` layout
+    ///
+    /// Use it for storage images
+    pub fn storage(view: memory::ImageView<'a>) -> ImageBinding<'a> {
+        ImageBinding {
+            view,
+            layout: memory::ImageLayout::GENERAL,
+        }
+    }
+}
+
+/// Buffer which is read by shader as array of formatted texels
+///
+/// Must be used with [`DescriptorType::UNIFORM_TEXEL_BUFFER`] or [`DescriptorType::STORAGE_TEXEL_BUFFER`]
+#[derive(Debug, Clone, Copy)]
+pub struct TexelBufferBinding<'a> {
+    pub view: &'a memory::BufferView,
+}
+
+impl<'a> TexelBufferBinding<'a> {
+    pub fn new(view: &'a memory::BufferView) -> TexelBufferBinding<'a> {
+        TexelBufferBinding {
+            view,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum ShaderBinding<'a, 'b> {
     Buffers(&'a [BufferBinding<'b>]),
+    /// Must be used with [`DescriptorType::COMBINED_IMAGE_SAMPLER`]
     Samplers(&'a [(&'b graphics::Sampler, memory::ImageView<'b>, memory::ImageLayout)]),
+    /// Must be used with [`DescriptorType::SAMPLED_IMAGE`] or [`DescriptorType::STORAGE_IMAGE`]
+    Images(&'a [ImageBinding<'b>]),
+    /// Must be used with [`DescriptorType::SAMPLER`]
+    SamplersOnly(&'a [&'b graphics::Sampler]),
+    /// Must be used with [`DescriptorType::INPUT_ATTACHMENT`]
+    InputAttachments(&'a [InputAttachmentBinding<'b>]),
+    /// Must be used with [`DescriptorType::UNIFORM_TEXEL_BUFFER`] or [`DescriptorType::STORAGE_TEXEL_BUFFER`]
+    TexelBuffers(&'a [TexelBufferBinding<'b>]),
 }
 
 impl<'a, 'b> ShaderBinding<'a, 'b> {
@@ -65,28 +152,116 @@ impl<'a, 'b> ShaderBinding<'a, 'b> {
         match self {
             Self::Buffers(val)  => val.len() as u32,
             Self::Samplers(val) => val.len() as u32,
+            Self::Images(val) => val.len() as u32,
+            Self::SamplersOnly(val) => val.len() as u32,
+            Self::InputAttachments(val) => val.len() as u32,
+            Self::TexelBuffers(val) => val.len() as u32,
+        }
+    }
+
+    /// Can resource be written into descriptor of type `desc_type`
+    pub fn is_compatible(&self, desc_type: DescriptorType) -> bool {
+        match self {
+            Self::Buffers(_) => matches!(
+                desc_type,
+                DescriptorType::UNIFORM_BUFFER
+                | DescriptorType::STORAGE_BUFFER
+                | DescriptorType::UNIFORM_BUFFER_DYNAMIC
+                | DescriptorType::STORAGE_BUFFER_DYNAMIC
+            ),
+            Self::Samplers(_) => desc_type == DescriptorType::COMBINED_IMAGE_SAMPLER,
+            Self::Images(_) => matches!(
+                desc_type,
+                DescriptorType::SAMPLED_IMAGE
+                | DescriptorType::STORAGE_IMAGE
+            ),
+            Self::SamplersOnly(_) => desc_type == DescriptorType::SAMPLER,
+            Self::InputAttachments(_) => desc_type == DescriptorType::INPUT_ATTACHMENT,
+            Self::TexelBuffers(_) => matches!(
+                desc_type,
+                DescriptorType::UNIFORM_TEXEL_BUFFER
+                | DescriptorType::STORAGE_TEXEL_BUFFER
+            ),
         }
     }
 }
 
 #[derive(Debug)]
 pub enum PipelineDescriptorError {
-    DescriptorPool,
-    DescriptorSet,
-    DescriptorAllocation
+    /// Failed to
+    /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateDescriptorPool.html) descriptor pool
+    DescriptorPool(vk::Result),
+    /// Failed to
+    /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateDescriptorSetLayout.html) descriptor set layout
+    DescriptorSet(vk::Result),
+    /// Failed to
+    /// [allocate](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkAllocateDescriptorSets.html) descriptor sets
+    DescriptorAllocation(vk::Result),
+    /// Set or binding does not exist in descriptor
+    InvalidBinding {
+        set: usize,
+        binding: u32
+    },
+    /// Resource does not match [`DescriptorType`] of the binding
+    TypeMismatch {
+        set: usize,
+        binding: u32
+    },
+    /// Shaders declare the same binding with different type or number of elements
+    StageMismatch {
+        set: usize,
+        binding: u32
+    },
+    /// Binding is runtime-sized array so number of descriptors is unknown
+    UnsizedBinding {
+        set: usize,
+        binding: u32
+    }
 }
 
 impl fmt::Display for PipelineDescriptorError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            PipelineDescriptorError::DescriptorPool => write!(f, "Failed to create descriptor pool (vkCreateDescriptorPool call failed)"),
-            PipelineDescriptorError::DescriptorSet => write!(f, "Failed to create descriptor set layout (vkCreateDescriptorSetLayout call failed)"),
-            PipelineDescriptorError::DescriptorAllocation => write!(f, "Failed to allocate descriptor set (vkDescriptorSetAllocateInfo call failed)"),
+            PipelineDescriptorError::DescriptorPool(_) => write!(f, "Failed to create descriptor pool (vkCreateDescriptorPool call failed)"),
+            PipelineDescriptorError::DescriptorSet(_) => write!(f, "Failed to create descriptor set layout (vkCreateDescriptorSetLayout call failed)"),
+            PipelineDescriptorError::DescriptorAllocation(_) => write!(f, "Failed to allocate descriptor set (vkDescriptorSetAllocateInfo call failed)"),
+            PipelineDescriptorError::InvalidBinding { set, binding } => write!(f, "Binding (set={}, binding={}) does not exist", set, binding),
+            PipelineDescriptorError::TypeMismatch { set, binding } => write!(f, "Resource does not match descriptor type of (set={}, binding={})", set, binding),
+            PipelineDescriptorError::StageMismatch { set, binding } => write!(f, "Shaders declare (set={}, binding={}) differently", set, binding),
+            PipelineDescriptorError::UnsizedBinding { set, binding } => write!(f, "Binding (set={}, binding={}) is runtime-sized array", set, binding),
+        }
+    }
+}
+
+impl Error for PipelineDescriptorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PipelineDescriptorError::DescriptorPool(result)
+            | PipelineDescriptorError::DescriptorSet(result)
+            | PipelineDescriptorError::DescriptorAllocation(result) => Some(result),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DynamicOffsetsError {
+    /// Number of offsets does not match number of dynamic descriptors
+    Count,
+    /// Offset with the given index is not properly aligned
+    Alignment(usize)
+}
+
+impl fmt::Display for DynamicOffsetsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynamicOffsetsError::Count => write!(f, "Number of offsets does not match number of dynamic descriptors"),
+            DynamicOffsetsError::Alignment(i) => write!(f, "Dynamic offset {} is not multiple of required alignment", i),
         }
     }
 }
 
-impl Error for PipelineDescriptorError { }
+impl Error for DynamicOffsetsError { }
 
 /// Specifies how pipeline should treat region of memory
 ///
@@ -129,12 +304,40 @@ pub struct BindingCfg {
 ///
 /// Call [`update`](PipelineDescriptor::update) to write information into `PipelineDescriptor`
 #[derive(Debug)]
-pub struct PipelineDescriptor {
+pub struct PipelineDescriptor(Arc<CoreDescriptor>);
+
+#[derive(Debug)]
+struct CoreDescriptor {
     i_core: Arc<dev::Core>,
     i_desc_types: Vec<Vec<DescriptorType>>,
+    i_desc_counts: Vec<Vec<u32>>,
     i_desc_pool: vk::DescriptorPool,
     i_desc_sets: Vec<vk::DescriptorSet>,
-    i_desc_layouts: Vec<vk::DescriptorSetLayout>
+    i_desc_layouts: Vec<vk::DescriptorSetLayout>,
+    /// Images written with `SHADER_READ_ONLY_OPTIMAL` layout
+    i_sampled: Mutex<Vec<SampledImage>>,
+    /// Memory of buffers and images which are currently written into descriptor
+    i_resources: Mutex<Vec<BoundResource>>
+}
+
+/// Descriptor slot which refers to the image in `SHADER_READ_ONLY_OPTIMAL` layout
+#[derive(Debug)]
+struct SampledImage {
+    set: usize,
+    binding: u32,
+    element: u32,
+    layout: memory::LayoutCell
+}
+
+/// Descriptor slot which refers to the buffer or image memory
+#[derive(Debug)]
+struct BoundResource {
+    set: usize,
+    binding: u32,
+    element: u32,
+    /// Only held, never read
+    #[allow(dead_code)]
+    resource: dev::Keepalive
 }
 
 impl PipelineDescriptor {
@@ -150,25 +353,31 @@ impl PipelineDescriptor {
     pub fn allocate(device: &dev::Device, cfg: &[&[BindingCfg]]) -> Result<PipelineDescriptor, PipelineDescriptorError> {
         let mut desc_size: Vec<vk::DescriptorPoolSize> = Vec::new();
         let mut desc_types: Vec<Vec<DescriptorType>> = Vec::new();
+        let mut desc_counts: Vec<Vec<u32>> = Vec::new();
 
         for &set in cfg {
             let mut set_types: Vec<DescriptorType> = Vec::new();
+            let mut set_counts: Vec<u32> = Vec::new();
 
             for binding in set {
-                desc_size.push(vk::DescriptorPoolSize {
-                    ty: binding.resource_type,
-                    descriptor_count: binding.count
-                });
+                if binding.count != 0 {
+                    desc_size.push(vk::DescriptorPoolSize {
+                        ty: binding.resource_type,
+                        descriptor_count: binding.count
+                    });
+                }
 
                 set_types.push(binding.resource_type);
+                set_counts.push(binding.count);
             }
 
             desc_types.push(set_types);
+            desc_counts.push(set_counts);
         }
 
-        let desc_pool = match create_descriptor_pool(device, &desc_size) {
+        let desc_pool = match create_descriptor_pool(device, &desc_size, cfg.len() as u32) {
             Ok(val) => if val == vk::DescriptorPool::null() { return Ok(PipelineDescriptor::empty(device)) } else { val },
-            Err(..) => return Err(PipelineDescriptorError::DescriptorPool),
+            Err(err) => return Err(PipelineDescriptorError::DescriptorPool(err)),
         };
 
         let mut sets_layout: Vec<vk::DescriptorSetLayout> = Vec::new();
@@ -176,28 +385,33 @@ impl PipelineDescriptor {
         for &res in cfg {
             match create_set_layout(device, res) {
                 Ok(set) => sets_layout.push(set),
-                Err(_) => {
+                Err(err) => {
                     clear_sets_layout(device, &sets_layout, desc_pool);
-                    return Err(PipelineDescriptorError::DescriptorSet);
+                    return Err(PipelineDescriptorError::DescriptorSet(err));
                 }
             }
         };
 
         let sets = on_error!(
             allocate_descriptor_sets(device, &sets_layout, desc_pool),
-            {
+            |err| {
                 clear_sets_layout(device, &sets_layout, desc_pool);
-                return Err(PipelineDescriptorError::DescriptorAllocation);
+                return Err(PipelineDescriptorError::DescriptorAllocation(err));
             }
         );
 
-        Ok(PipelineDescriptor {
-            i_core: device.core().clone(),
-            i_desc_types: desc_types,
-            i_desc_pool: desc_pool,
-            i_desc_sets: sets,
-            i_desc_layouts: sets_layout
-        })
+        Ok(PipelineDescriptor(
+            Arc::new(CoreDescriptor {
+                i_core: device.core().clone(),
+                i_desc_types: desc_types,
+                i_desc_counts: desc_counts,
+                i_desc_pool: desc_pool,
+                i_desc_sets: sets,
+                i_desc_layouts: sets_layout,
+                i_sampled: Mutex::new(Vec::new()),
+                i_resources: Mutex::new(Vec::new())
+            })
+        ))
     }
 
     /// Create new `PipelineResource` with the same set type but (possibly) distinct bindings repeated `count` times
@@ -244,20 +458,92 @@ impl PipelineDescriptor {
         PipelineDescriptor::with_set(device, &cfg, count)
     }
 
+    /// Create new `PipelineResource` with bindings declared in `shaders`
+    ///
+    /// [Reflected bindings](shader::Shader::bindings) of all shaders are merged,
+    /// stages of the binding which is used by several shaders are combined
+    ///
+    /// Unused bindings (and sets) between declared ones are allocated without descriptors
+    ///
+    /// Returns [`PipelineDescriptorError::StageMismatch`] if shaders declare the same binding
+    /// with different type or number of elements
+    /// and [`PipelineDescriptorError::UnsizedBinding`] for runtime-sized arrays
+    ///
+    /// Note: dynamic buffers can not be derived from shader,
+    /// use [`allocate`](PipelineDescriptor::allocate) for them
+    pub fn from_shaders(device: &dev::Device, shaders: &[&shader::Shader]) -> Result<PipelineDescriptor, PipelineDescriptorError> {
+        let mut merged: Vec<shader::ReflectedBinding> = Vec::new();
+
+        for binding in shaders.iter().flat_map(|shader| shader.bindings()) {
+            if binding.count == 0 {
+                return Err(PipelineDescriptorError::UnsizedBinding { set: binding.set, binding: binding.binding });
+            }
+
+            match merged.iter_mut().find(|x| x.set == binding.set && x.binding == binding.binding) {
+                Some(val) => {
+                    if val.resource_type != binding.resource_type || val.count != binding.count {
+                        return Err(PipelineDescriptorError::StageMismatch { set: binding.set, binding: binding.binding });
+                    }
+
+                    val.stage |= binding.stage;
+                },
+                None => merged.push(binding)
+            }
+        }
+
+        if merged.is_empty() {
+            return Ok(PipelineDescriptor::empty(device));
+        }
+
+        let unused = BindingCfg {
+            resource_type: DescriptorType::SAMPLER,
+            stage: graphics::ShaderStage::empty(),
+            count: 0,
+        };
+
+        let set_count = merged.iter().map(|x| x.set + 1).max().unwrap_or(0);
+
+        let mut sets: Vec<Vec<BindingCfg>> = vec![Vec::new(); set_count];
+
+        for binding in &merged {
+            let set = &mut sets[binding.set];
+            let index = binding.binding as usize;
+
+            if set.len() <= index {
+                set.resize(index + 1, unused);
+            }
+
+            set[index] = BindingCfg {
+                resource_type: binding.resource_type,
+                stage: binding.stage,
+                count: binding.count,
+            };
+        }
+
+        let cfg: Vec<&[BindingCfg]> = sets.iter().map(|x| x.as_slice()).collect();
+
+        PipelineDescriptor::allocate(device, &cfg)
+    }
+
     /// Create new `PipelineResource` with no bindings
     pub fn empty(device: &dev::Device) -> PipelineDescriptor {
-        PipelineDescriptor {
-            i_core: device.core().clone(),
-            i_desc_types: Vec::new(),
-            i_desc_pool: vk::DescriptorPool::null(),
-            i_desc_sets: Vec::new(),
-            i_desc_layouts: Vec::new()
-        }
+        PipelineDescriptor(
+            Arc::new(CoreDescriptor {
+                i_core: device.core().clone(),
+                i_desc_types: Vec::new(),
+                i_desc_counts: Vec::new(),
+                i_desc_pool: vk::DescriptorPool::null(),
+                i_desc_sets: Vec::new(),
+                i_desc_layouts: Vec::new(),
+                i_sampled: Mutex::new(Vec::new()),
+                i_resources: Mutex::new(Vec::new())
+            })
+        )
     }
 
     /// Does resource contain any bindings
     pub fn is_empty(&self) -> bool {
-        self.i_desc_pool == vk::DescriptorPool::null()
+        self.0.i_desc_pool == vk::DescriptorPool::null()
     }
 
     /// Update selected elements in bindings
@@ -266,46 +552,187 @@ impl PipelineDescriptor {
     /// must be within supported range
     ///
     /// About supported ranges see [`PipelineDescriptor::allocate`]
-    pub fn update(&self, update_info: &[UpdateInfo]) {
+    ///
+    /// Every [`UpdateInfo`] is checked before any write,
+    /// so on error descriptor stays untouched
+    pub fn update(&self, update_info: &[UpdateInfo]) -> Result<(), PipelineDescriptorError> {
+        for info in update_info {
+            let desc_type = self
+                .0.i_desc_types
+                .get(info.set)
+                .and_then(|set| set.get(info.binding as usize))
+                .ok_or(PipelineDescriptorError::InvalidBinding { set: info.set, binding: info.binding })?;
+
+            if !info.resources.is_compatible(*desc_type) {
+                return Err(PipelineDescriptorError::TypeMismatch { set: info.set, binding: info.binding });
+            }
+        }
+
         let mut buffer_info: Vec<Vec<vk::DescriptorBufferInfo>> = Vec::new();
         let mut image_info: Vec<Vec<vk::DescriptorImageInfo>> = Vec::new();
+        let mut texel_info: Vec<Vec<vk::BufferView>> = Vec::new();
 
         for info in update_info {
             buffer_info.push(create_buffer_info(info.resources));
             image_info.push(create_image_info(info.resources));
+            texel_info.push(create_texel_info(info.resources));
         }
 
         let write_desc: Vec<vk::WriteDescriptorSet> = update_info.iter().enumerate().map(
             |(i, info)| vk::WriteDescriptorSet {
                 s_type: vk::StructureType::WRITE_DESCRIPTOR_SET,
                 p_next: ptr::null(),
-                dst_set: self.i_desc_sets[info.set],
+                dst_set: self.0.i_desc_sets[info.set],
                 dst_binding: info.binding,
                 dst_array_element: info.starting_array_element,
                 descriptor_count: info.resources.len(),
-                descriptor_type: self.i_desc_types[info.set][info.binding as usize],
+                descriptor_type: self.0.i_desc_types[info.set][info.binding as usize],
                 p_image_info: data_ptr!(image_info[i]),
                 p_buffer_info: data_ptr!(buffer_info[i]),
-                p_texel_buffer_view: ptr::null(),
+                p_texel_buffer_view: data_ptr!(texel_info[i]),
                 _marker: PhantomData,
             }
         ).collect();
 
         unsafe {
-            self.i_core.device().update_descriptor_sets(&write_desc, &[])
+            self.0.i_core.device().update_descriptor_sets(&write_desc, &[])
         };
+
+        self.track_sampled(update_info);
+        self.track_resources(update_info);
+
+        Ok(())
+    }
+
+    /// Remember which images are expected in `SHADER_READ_ONLY_OPTIMAL` layout
+    fn track_sampled(&self, update_info: &[UpdateInfo]) {
+        let mut sampled = self.0.i_sampled.lock().expect("Descriptor lock is poisoned");
+
+        for info in update_info {
+            let first = info.starting_array_element;
+            let last = first + info.resources.len();
+
+            sampled.retain(|img| {
+                img.set != info.set || img.binding != info.binding || img.element < first || img.element >= last
+            });
+
+            let views: Vec<(memory::ImageView, memory::ImageLayout)> = match info.resources {
+                ShaderBinding::Samplers(val) => val.iter().map(|(_, view, layout)| (*view, *layout)).collect(),
+                ShaderBinding::Images(val) => val.iter().map(|binding| (binding.view, binding.layout)).collect(),
+                _ => Vec::new()
+            };
+
+            for (i, (view, layout)) in views.into_iter().enumerate() {
+                if layout == memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL {
+                    sampled.push(SampledImage {
+                        set: info.set,
+                        binding: info.binding,
+                        element: first + i as u32,
+                        layout: view.layout_cell()
+                    });
+                }
+            }
+        }
+    }
+
+    /// Keep memory written into descriptor alive until it is overwritten
+    fn track_resources(&self, update_info: &[UpdateInfo]) {
+        let mut resources = self.0.i_resources.lock().expect("Descriptor lock is poisoned");
+
+        for info in update_info {
+            let first = info.starting_array_element;
+            let last = first + info.resources.len();
+
+            resources.retain(|res| {
+                res.set != info.set || res.binding != info.binding || res.element < first || res.element >= last
+            });
+
+            let written: Vec<dev::Keepalive> = match info.resources {
+                ShaderBinding::Buffers(val) => val.iter().map(|binding| binding.view.keepalive()).collect(),
+                ShaderBinding::Samplers(val) => val.iter().map(|(_, view, _)| view.keepalive()).collect(),
+                ShaderBinding::Images(val) => val.iter().map(|binding| binding.view.keepalive()).collect(),
+                ShaderBinding::InputAttachments(val) => val.iter().map(|binding| binding.view.keepalive()).collect(),
+                ShaderBinding::SamplersOnly(_) | ShaderBinding::TexelBuffers(_) => Vec::new()
+            };
+
+            for (i, resource) in written.into_iter().enumerate() {
+                resources.push(BoundResource {
+                    set: info.set,
+                    binding: info.binding,
+                    element: first + i as u32,
+                    resource
+                });
+            }
+        }
+    }
+
+    /// Return current [tracked layout](memory::ImageView::layout) of every image
+    /// written with `SHADER_READ_ONLY_OPTIMAL` layout
+    pub(crate) fn sampled_layouts(&self) -> Vec<memory::ImageLayout> {
+        self.0.i_sampled.lock().expect("Descriptor lock is poisoned").iter().map(|img| img.layout.get()).collect()
+    }
+
+    /// Return type of every binding, `binding_types()[set][binding]`
+    pub fn binding_types(&self) -> &[Vec<DescriptorType>] {
+        &self.0.i_desc_types
+    }
+
+    /// Return number of array elements of every binding, `binding_counts()[set][binding]`
+    pub fn binding_counts(&self) -> &[Vec<u32>] {
+        &self.0.i_desc_counts
+    }
+
+    /// Return how many dynamic descriptors (including array elements) are within all sets
+    ///
+    /// It is exactly how many `offsets` [`bind_resources`](crate::cmd::Buffer::bind_resources) requires
+    pub fn dynamic_descriptor_count(&self) -> usize {
+        self.dynamic_descriptors().count()
+    }
+
+    /// Iterate over types of dynamic descriptors in order of dynamic offsets
+    ///
+    /// Order is: set, then binding, then array element
+    fn dynamic_descriptors(&self) -> impl Iterator<Item = DescriptorType> + '_ {
+        self.0.i_desc_types
+        .iter()
+        .zip(self.0.i_desc_counts.iter())
+        .flat_map(|(types, counts)| types.iter().zip(counts.iter()))
+        .filter(|(&ty, _)| is_dynamic(ty))
+        .flat_map(|(&ty, &count)| std::iter::repeat_n(ty, count as usize))
     }
 
     pub(crate) fn descriptor_sets(&self) -> &[vk::DescriptorSet] {
-        &self.i_desc_sets
+        &self.0.i_desc_sets
     }
 
     pub(crate) fn descriptor_layouts(&self) -> &[vk::DescriptorSetLayout] {
-        &self.i_desc_layouts
+        &self.0.i_desc_layouts
+    }
+
+    /// Descriptor keeps alive memory which is written into it
+    pub(crate) fn keepalive(&self) -> dev::Keepalive {
+        self.0.clone()
     }
 }
 
-impl Drop for PipelineDescriptor {
+impl fmt::Display for PipelineDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Pipeline descriptor")?;
+        writeln!(f, "Sets: {}", self.binding_types().len())?;
+
+        for (set, (types, counts)) in self.binding_types().iter().zip(self.binding_counts()).enumerate() {
+            writeln!(f, "  Set {}", set)?;
+
+            for (binding, (ty, count)) in types.iter().zip(counts).enumerate() {
+                writeln!(f, "    Binding {}: {:?} x{}", binding, ty, count)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for CoreDescriptor {
     fn drop(&mut self) {
         unsafe {
             if self.i_desc_pool != vk::DescriptorPool::null() {
@@ -313,6 +740,9 @@ impl Drop for PipelineDescriptor {
                 .i_core
                 .device()
                 .destroy_descriptor_pool(self.i_desc_pool, self.i_core.allocator());
+
+                self.i_core.destroyed(dev::ObjectType::DescriptorPool, 1);
+
                 for &set in &self.i_desc_layouts {
                     self
                     .i_core
@@ -324,23 +754,78 @@ impl Drop for PipelineDescriptor {
     }
 }
 
+/// Helper which checks dynamic offsets before they are passed
+/// into [`bind_resources`](crate::cmd::Buffer::bind_resources)
+///
+/// Each offset of `UNIFORM_BUFFER_DYNAMIC` must be multiple of [`ubo_offset`](crate::hw::HWDevice::ubo_offset)
+///
+/// Each offset of `STORAGE_BUFFER_DYNAMIC` must be multiple of [`storage_offset`](crate::hw::HWDevice::storage_offset)
+#[derive(Debug, Clone)]
+pub struct DynamicOffsets {
+    i_alignments: Vec<u64>,
+}
+
+impl DynamicOffsets {
+    /// Collect dynamic descriptors of `desc` and their alignment requirements
+    pub fn new(device: &dev::Device, desc: &PipelineDescriptor) -> DynamicOffsets {
+        let alignments = desc.dynamic_descriptors().map(|ty| {
+            if ty == DescriptorType::UNIFORM_BUFFER_DYNAMIC {
+                device.hw().ubo_offset()
+            } else {
+                device.hw().storage_offset()
+            }
+        }).collect();
+
+        DynamicOffsets {
+            i_alignments: alignments,
+        }
+    }
+
+    /// How many offsets are required
+    pub fn count(&self) -> usize {
+        self.i_alignments.len()
+    }
+
+    /// Check number of `offsets` and their alignment
+    pub fn validate(&self, offsets: &[u32]) -> Result<(), DynamicOffsetsError> {
+        if offsets.len() != self.i_alignments.len() {
+            return Err(DynamicOffsetsError::Count);
+        }
+
+        for (i, (&offset, &alignment)) in offsets.iter().zip(self.i_alignments.iter()).enumerate() {
+            if alignment != 0 && !(offset as u64).is_multiple_of(alignment) {
+                return Err(DynamicOffsetsError::Alignment(i));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_dynamic(ty: DescriptorType) -> bool {
+    ty == DescriptorType::UNIFORM_BUFFER_DYNAMIC || ty == DescriptorType::STORAGE_BUFFER_DYNAMIC
+}
+
 fn create_descriptor_pool(
     device: &dev::Device,
-    desc_size: &Vec<vk::DescriptorPoolSize>
+    desc_size: &[vk::DescriptorPoolSize],
+    max_sets: u32
 ) -> VkResult<vk::DescriptorPool> {
     let desc_info = vk::DescriptorPoolCreateInfo {
         s_type: vk::StructureType::DESCRIPTOR_POOL_CREATE_INFO,
         p_next: ptr::null(),
         flags: vk::DescriptorPoolCreateFlags::empty(),
-        max_sets: desc_size.len() as u32,
+        max_sets,
         pool_size_count: desc_size.len() as u32,
         p_pool_sizes: desc_size.as_ptr(),
         _marker: PhantomData,
     };
 
-    unsafe {
-        device.device().create_descriptor_pool(&desc_info, device.allocator())
-    }
+    let desc_pool = unsafe { device.device().create_descriptor_pool(&desc_info, device.allocator())? };
+
+    device.core().created(dev::ObjectType::DescriptorPool, 1);
+
+    Ok(desc_pool)
 }
 
 fn create_set_layout(
@@ -386,6 +871,8 @@ fn clear_sets_layout(
         .device()
         .destroy_descriptor_pool(pool, device.allocator());
 
+        device.core().destroyed(dev::ObjectType::DescriptorPool, 1);
+
         for &set in sets {
             device
             .device()
@@ -396,7 +883,7 @@ fn clear_sets_layout(
 
 fn allocate_descriptor_sets(
     device: &dev::Device,
-    sets: &Vec<vk::DescriptorSetLayout>,
+    sets: &[vk::DescriptorSetLayout],
     pool: vk::DescriptorPool
 ) -> VkResult<Vec<vk::DescriptorSet>> {
     let alloc_info = vk::DescriptorSetAllocateInfo {
@@ -415,15 +902,48 @@ fn allocate_descriptor_sets(
 
 fn create_image_info(bindings: ShaderBinding) -> Vec<vk::DescriptorImageInfo> {
     match bindings {
-        ShaderBinding::Buffers(_) => {
+        ShaderBinding::Buffers(_) | ShaderBinding::TexelBuffers(_) => {
             Vec::new()
         }
         ShaderBinding::Samplers(samplers) => {
             descriptor_image_info(&samplers)
         }
+        ShaderBinding::InputAttachments(attachments) => {
+            input_attachment_info(attachments)
+        }
+        ShaderBinding::Images(images) => {
+            image_binding_info(images)
+        }
+        ShaderBinding::SamplersOnly(samplers) => {
+            sampler_info(samplers)
+        }
     }
 }
 
+fn image_binding_info(images: &[ImageBinding]) -> Vec<vk::DescriptorImageInfo> {
+    images
+    .iter()
+    .map(|binding| {
+        vk::DescriptorImageInfo {
+            sampler: vk::Sampler::null(),
+            image_view: binding.view.image_view(),
+            image_layout: binding.layout,
+        }
+    }).collect()
+}
+
+fn sampler_info(samplers: &[&graphics::Sampler]) -> Vec<vk::DescriptorImageInfo> {
+    samplers
+    .iter()
+    .map(|sampler| {
+        vk::DescriptorImageInfo {
+            sampler: sampler.sampler(),
+            image_view: vk::ImageView::null(),
+            image_layout: vk::ImageLayout::UNDEFINED,
+        }
+    }).collect()
+}
+
 fn descriptor_image_info(samplers: &[(&graphics::Sampler, memory::ImageView, memory::ImageLayout)]) -> Vec<vk::DescriptorImageInfo> {
     samplers
     .iter()
@@ -436,12 +956,39 @@ fn descriptor_image_info(samplers: &[(&graphics::Sampler, memory::ImageView, mem
     }).collect()
 }
 
+fn input_attachment_info(attachments: &[InputAttachmentBinding]) -> Vec<vk::DescriptorImageInfo> {
+    attachments
+    .iter()
+    .map(|binding| {
+        vk::DescriptorImageInfo {
+            sampler: vk::Sampler::null(),
+            image_view: binding.view.image_view(),
+            image_layout: binding.layout,
+        }
+    }).collect()
+}
+
 fn create_buffer_info(bindings: ShaderBinding) -> Vec<vk::DescriptorBufferInfo> {
     match bindings {
         ShaderBinding::Buffers(buffers) => {
             descriptor_buffer_info(&buffers)
         }
-        ShaderBinding::Samplers(_) => {
+        ShaderBinding::Samplers(_)
+        | ShaderBinding::Images(_)
+        | ShaderBinding::SamplersOnly(_)
+        | ShaderBinding::InputAttachments(_)
+        | ShaderBinding::TexelBuffers(_) => {
+            Vec::new()
+        }
+    }
+}
+
+fn create_texel_info(bindings: ShaderBinding) -> Vec<vk::BufferView> {
+    match bindings {
+        ShaderBinding::TexelBuffers(views) => {
+            views.iter().map(|binding| binding.view.buffer_view()).collect()
+        }
+        _ => {
             Vec::new()
         }
     }
@@ -451,10 +998,12 @@ fn descriptor_buffer_info(buffers: &[BufferBinding]) -> Vec<vk::DescriptorBuffer
     buffers
     .iter()
     .map(|binding| {
+        let (offset, range) = binding.view.buffer_range(binding.offset, binding.range);
+
         vk::DescriptorBufferInfo {
             buffer: binding.view.buffer(),
-            offset: binding.offset,
-            range: binding.range,
+            offset,
+            range,
         }
     }).collect()
 }
\ No newline at end of file
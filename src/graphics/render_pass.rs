@@ -4,6 +4,8 @@
 //! 1) [subpasses](SubpassInfo)
 //! 2) [synchronization between subpasses](SubpassSync)
 //! 3) [attachments](AttachmentInfo) which defines what for *all* images are used for
+//!
+//! Instead of writing all of them by hand you may use [`RenderPassBuilder`]
 
 use ash::vk;
 
@@ -11,7 +13,7 @@ use crate::{
     dev,
     memory,
     data_ptr,
-    on_error_ret
+    on_error
 };
 
 use std::ptr;
@@ -25,7 +27,7 @@ use std::marker::PhantomData;
 pub enum RenderPassError {
     /// Error was returned as a result of `vkCreateRenderPass`
     /// [call](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateRenderPass.html)
-    Creation,
+    Creation(vk::Result),
 }
 
 impl fmt::Display for RenderPassError {
@@ -34,7 +36,13 @@ impl fmt::Display for RenderPassError {
     }
 }
 
-impl Error for RenderPassError { }
+impl Error for RenderPassError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RenderPassError::Creation(result) => Some(result),
+        }
+    }
+}
 
 /// Specify how contents of an attachment are treated at the beginning of a subpass
 ///
@@ -69,9 +77,11 @@ pub const SUBPASS_EXTERNAL: u32 = vk::SUBPASS_EXTERNAL;
 pub const NO_ATTACHMENT: u32 =  vk::ATTACHMENT_UNUSED;
 
 /// Structure specifying an attachment description
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct AttachmentInfo {
     pub format: memory::ImageFormat,
+    /// Must match number of samples of the image used as attachment
+    pub samples: memory::SampleCount,
     pub load_op: AttachmentLoadOp,
     pub store_op: AttachmentStoreOp,
     pub stencil_load_op: AttachmentLoadOp,
@@ -84,6 +94,7 @@ impl Default for AttachmentInfo {
     fn default() -> Self {
         AttachmentInfo {
             format: memory::ImageFormat::UNDEFINED,
+            samples: memory::SampleCount::TYPE_1,
             load_op: AttachmentLoadOp::DONT_CARE,
             store_op: AttachmentStoreOp::DONT_CARE,
             stencil_load_op: AttachmentLoadOp::DONT_CARE,
@@ -100,7 +111,7 @@ impl From<&AttachmentInfo> for vk::AttachmentDescription {
         vk::AttachmentDescription {
             flags: vk::AttachmentDescriptionFlags::empty(),
             format: info.format,
-            samples: vk::SampleCountFlags::TYPE_1,
+            samples: info.samples,
             load_op: info.load_op,
             store_op: info.store_op,
             stencil_load_op: info.stencil_load_op,
@@ -112,7 +123,7 @@ impl From<&AttachmentInfo> for vk::AttachmentDescription {
 }
 
 /// Essentially SubpassSync acts like a memory barrier between two (previous and next) subpasses
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SubpassSync {
     /// Index of previous subpass in [`RenderPassCfg::subpasses`] or [`SUBPASS_EXTERNAL`]
     pub src_subpass: u32,
@@ -148,7 +159,9 @@ impl From<&SubpassSync> for vk::SubpassDependency {
 /// All information about [valid usage](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkSubpassDescription.html)
 ///
 /// Note: [`SubpassInfo::resolve_attachments`] **must be** `&[]` or same length as [`SubpassInfo::color_attachments`]
-#[derive(Debug)]
+///
+/// Input attachments are used in `SHADER_READ_ONLY_OPTIMAL` layout
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SubpassInfo<'a> {
     pub input_attachments: &'a [u32],
     pub color_attachments: &'a [u32],
@@ -176,12 +189,30 @@ pub struct RenderPassCfg<'a, 'b: 'a> {
     pub subpasses: &'a [SubpassInfo<'b>],
 }
 
-/// Context for executing graphics pipeline
-pub struct RenderPass {
+#[derive(Debug)]
+struct CoreRenderPass {
     i_core: Arc<dev::Core>,
-    i_rp: vk::RenderPass
+    i_rp: vk::RenderPass,
+    i_attachment_formats: Vec<memory::ImageFormat>,
+    /// Usage which image must have to be used as attachment with the same index
+    i_attachment_usages: Vec<memory::UsageFlags>,
+    i_color_attachments: Vec<usize>,
 }
 
+impl Drop for CoreRenderPass {
+    fn drop(&mut self) {
+        unsafe {
+            self.i_core.device().destroy_render_pass(self.i_rp, self.i_core.allocator());
+        }
+    }
+}
+
+/// Context for executing graphics pipeline
+///
+/// `Display` prints attachment formats and number of color attachments of every subpass
+#[derive(Debug)]
+pub struct RenderPass(Arc<CoreRenderPass>);
+
 impl RenderPass {
     pub fn new(dev: &dev::Device, cfg: &RenderPassCfg) -> Result<RenderPass, RenderPassError> {
         let dependencies: Vec<vk::SubpassDependency> = cfg
@@ -202,7 +233,7 @@ impl RenderPass {
             .map(|x| {
                 x.input_attachments.iter().map(|&i| vk::AttachmentReference {
                     attachment: i,
-                    layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                    layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
                 })
                 .collect()
             })
@@ -273,16 +304,35 @@ impl RenderPass {
             _marker: PhantomData,
         };
 
-        let rp = on_error_ret!(
+        let mut usages = vec![memory::UsageFlags::empty(); cfg.attachments.len()];
+
+        for subpass in cfg.subpasses {
+            for &i in subpass.color_attachments.iter().chain(subpass.resolve_attachments) {
+                if let Some(usage) = usages.get_mut(i as usize) {
+                    *usage |= memory::UsageFlags::COLOR_ATTACHMENT;
+                }
+            }
+
+            if let Some(usage) = usages.get_mut(subpass.depth_stencil_attachment as usize) {
+                *usage |= memory::UsageFlags::DEPTH_STENCIL_ATTACHMENT;
+            }
+        }
+
+        let rp = on_error!(
             unsafe { dev.device().create_render_pass(&render_pass_create_info, dev.core().allocator()) },
-            RenderPassError::Creation
+            |err| return Err(RenderPassError::Creation(err))
         );
 
         Ok(
-            RenderPass {
-                i_core: dev.core().clone(),
-                i_rp: rp
-            }
+            RenderPass(
+                Arc::new(CoreRenderPass {
+                    i_core: dev.core().clone(),
+                    i_rp: rp,
+                    i_attachment_formats: cfg.attachments.iter().map(|x| x.format).collect(),
+                    i_attachment_usages: usages,
+                    i_color_attachments: cfg.subpasses.iter().map(|x| x.color_attachments.len()).collect(),
+                })
+            )
         )
     }
 
@@ -303,6 +353,7 @@ impl RenderPass {
         let attachments = [
             AttachmentInfo {
                 format: img_format,
+                samples: memory::SampleCount::TYPE_1,
                 load_op: AttachmentLoadOp::CLEAR,
                 store_op: AttachmentStoreOp::STORE,
                 stencil_load_op: AttachmentLoadOp::DONT_CARE,
@@ -337,7 +388,70 @@ impl RenderPass {
             subpasses: &subpass_info,
         };
 
-        RenderPass::new(&device, &rp_cfg)
+        RenderPass::new(device, &rp_cfg)
+    }
+
+    /// Create [`RenderPass`] with single subpass and single attachment for rendering without swapchain
+    ///
+    /// Unlike [`single_subpass`](RenderPass::single_subpass) attachment ends in
+    /// `TRANSFER_SRC_OPTIMAL` layout so result may be copied right after the render pass.
+    /// Swapchain extension is not required
+    ///
+    /// Render pass does not update [tracked layout](memory::ImageView::set_layout) of the image
+    ///
+    /// See [`ImageMemory::offscreen`](memory::ImageMemory::offscreen)
+    pub fn offscreen(device: &dev::Device, img_format: memory::ImageFormat)
+        -> Result<RenderPass, RenderPassError>
+    {
+        let subpass_info = [
+            SubpassInfo {
+                input_attachments: &[],
+                color_attachments: &[0],
+                resolve_attachments: &[],
+                depth_stencil_attachment: NO_ATTACHMENT,
+                preserve_attachments: &[],
+            }
+        ];
+
+        let attachments = [
+            AttachmentInfo {
+                format: img_format,
+                samples: memory::SampleCount::TYPE_1,
+                load_op: AttachmentLoadOp::CLEAR,
+                store_op: AttachmentStoreOp::STORE,
+                stencil_load_op: AttachmentLoadOp::DONT_CARE,
+                stencil_store_op: AttachmentStoreOp::DONT_CARE,
+                initial_layout: memory::ImageLayout::UNDEFINED,
+                final_layout: memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            }
+        ];
+
+        let subpass_sync_info = [
+            SubpassSync {
+                src_subpass: SUBPASS_EXTERNAL,
+                dst_subpass: 0,
+                src_stage: PipelineStage::TRANSFER,
+                dst_stage: PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+                src_access: AccessFlags::TRANSFER_READ,
+                dst_access: AccessFlags::COLOR_ATTACHMENT_WRITE | AccessFlags::COLOR_ATTACHMENT_READ,
+            },
+            SubpassSync {
+                src_subpass: 0,
+                dst_subpass: SUBPASS_EXTERNAL,
+                src_stage: PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+                dst_stage: PipelineStage::TRANSFER,
+                src_access: AccessFlags::COLOR_ATTACHMENT_WRITE,
+                dst_access: AccessFlags::TRANSFER_READ,
+            }
+        ];
+
+        let rp_cfg = RenderPassCfg {
+            attachments: &attachments,
+            sync_info: &subpass_sync_info,
+            subpasses: &subpass_info,
+        };
+
+        RenderPass::new(device, &rp_cfg)
     }
 
     /// Create [`RenderPass`] with single subpass and single attachment
@@ -362,6 +476,7 @@ impl RenderPass {
         let mut attachments = vec![
             AttachmentInfo {
                 format: img_format,
+                samples: memory::SampleCount::TYPE_1,
                 load_op: AttachmentLoadOp::CLEAR,
                 store_op: AttachmentStoreOp::STORE,
                 stencil_load_op: AttachmentLoadOp::DONT_CARE,
@@ -375,6 +490,7 @@ impl RenderPass {
             attachments.push(
                 AttachmentInfo {
                     format: depth_buffer_format,
+                    samples: memory::SampleCount::TYPE_1,
                     load_op: AttachmentLoadOp::CLEAR,
                     store_op: AttachmentStoreOp::DONT_CARE,
                     stencil_load_op: AttachmentLoadOp::DONT_CARE,
@@ -410,19 +526,337 @@ impl RenderPass {
             subpasses: &subpass_info,
         };
 
-        RenderPass::new(&device, &rp_cfg)
+        RenderPass::new(device, &rp_cfg)
+    }
+
+    /// Create [`RenderPass`] with single subpass for multisampled rendering
+    ///
+    /// Attachments are
+    /// - `0`: multisampled color image with `color_format`
+    /// - `1`: multisampled depth image with `depth_format`
+    /// - `2`: single sampled image with `color_format` (e.g. swapchain image) to which color is resolved
+    ///
+    /// Hence framebuffer must be created with images in the same order
+    pub fn with_msaa(
+        device: &dev::Device,
+        color_format: memory::ImageFormat,
+        depth_format: memory::ImageFormat,
+        samples: memory::SampleCount)
+        -> Result<RenderPass, RenderPassError>
+    {
+        let subpass_info = [
+            SubpassInfo {
+                input_attachments: &[],
+                color_attachments: &[0],
+                resolve_attachments: &[2],
+                depth_stencil_attachment: 1,
+                preserve_attachments: &[],
+            }
+        ];
+
+        let attachments = [
+            AttachmentInfo {
+                format: color_format,
+                samples,
+                load_op: AttachmentLoadOp::CLEAR,
+                store_op: AttachmentStoreOp::DONT_CARE,
+                stencil_load_op: AttachmentLoadOp::DONT_CARE,
+                stencil_store_op: AttachmentStoreOp::DONT_CARE,
+                initial_layout: memory::ImageLayout::UNDEFINED,
+                final_layout: memory::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            },
+            AttachmentInfo {
+                format: depth_format,
+                samples,
+                load_op: AttachmentLoadOp::CLEAR,
+                store_op: AttachmentStoreOp::DONT_CARE,
+                stencil_load_op: AttachmentLoadOp::DONT_CARE,
+                stencil_store_op: AttachmentStoreOp::DONT_CARE,
+                initial_layout: memory::ImageLayout::UNDEFINED,
+                final_layout: memory::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+            },
+            AttachmentInfo {
+                format: color_format,
+                samples: memory::SampleCount::TYPE_1,
+                load_op: AttachmentLoadOp::DONT_CARE,
+                store_op: AttachmentStoreOp::STORE,
+                stencil_load_op: AttachmentLoadOp::DONT_CARE,
+                stencil_store_op: AttachmentStoreOp::DONT_CARE,
+                initial_layout: memory::ImageLayout::UNDEFINED,
+                final_layout: memory::ImageLayout::PRESENT_SRC_KHR,
+            }
+        ];
+
+        let subpass_sync_info = [
+            SubpassSync {
+                src_subpass: SUBPASS_EXTERNAL,
+                dst_subpass: 0,
+                src_stage: PipelineStage::BOTTOM_OF_PIPE,
+                dst_stage: PipelineStage::COLOR_ATTACHMENT_OUTPUT | PipelineStage::EARLY_FRAGMENT_TESTS,
+                src_access: AccessFlags::MEMORY_READ,
+                dst_access: AccessFlags::COLOR_ATTACHMENT_WRITE
+                    | AccessFlags::COLOR_ATTACHMENT_READ
+                    | AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            },
+            SubpassSync {
+                src_subpass: 0,
+                dst_subpass: SUBPASS_EXTERNAL,
+                src_stage: PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+                dst_stage: PipelineStage::BOTTOM_OF_PIPE,
+                src_access: AccessFlags::COLOR_ATTACHMENT_WRITE | AccessFlags::COLOR_ATTACHMENT_READ,
+                dst_access: AccessFlags::MEMORY_READ,
+            }
+        ];
+
+        let rp_cfg = RenderPassCfg {
+            attachments: &attachments,
+            sync_info: &subpass_sync_info,
+            subpasses: &subpass_info,
+        };
+
+        RenderPass::new(device, &rp_cfg)
     }
 
     #[doc(hidden)]
     pub fn render_pass(&self) -> vk::RenderPass {
-        self.i_rp
+        self.0.i_rp
+    }
+
+    /// Return number of attachments
+    ///
+    /// Every framebuffer of the render pass must have exactly this number of images
+    pub fn attachment_count(&self) -> usize {
+        self.0.i_attachment_formats.len()
+    }
+
+    /// Return format of every attachment in order of [`RenderPassCfg::attachments`]
+    pub fn attachment_formats(&self) -> &[memory::ImageFormat] {
+        &self.0.i_attachment_formats
+    }
+
+    /// Return usage which image must have to be attached at `index`
+    pub(crate) fn attachment_usage(&self, index: usize) -> memory::UsageFlags {
+        self.0.i_attachment_usages[index]
+    }
+
+    /// Return number of color attachments used by subpass
+    ///
+    /// `None` if there is no such subpass
+    pub fn color_attachment_count(&self, subpass: u32) -> Option<usize> {
+        self.0.i_color_attachments.get(subpass as usize).copied()
+    }
+
+    /// Return number of subpasses
+    pub fn subpass_count(&self) -> usize {
+        self.0.i_color_attachments.len()
+    }
+
+    pub(crate) fn keepalive(&self) -> dev::Keepalive {
+        self.0.clone()
     }
 }
 
-impl Drop for RenderPass {
-    fn drop(&mut self) {
-        unsafe {
-            self.i_core.device().destroy_render_pass(self.i_rp, self.i_core.allocator());
+impl fmt::Display for RenderPass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Render pass")?;
+        writeln!(f, "Attachments: {}", self.attachment_count())?;
+
+        for (i, format) in self.attachment_formats().iter().enumerate() {
+            writeln!(f, "  {}: {:?}", i, format)?;
+        }
+
+        writeln!(f, "Subpasses:   {}", self.subpass_count())?;
+
+        for (i, count) in self.0.i_color_attachments.iter().enumerate() {
+            writeln!(f, "  {}: {} color attachment(s)", i, count)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Helper for building [`RenderPass`] step by step
+///
+/// Attachment indices are assigned in order of
+/// [`color_attachment`](RenderPassBuilder::color_attachment) and
+/// [`depth_attachment`](RenderPassBuilder::depth_attachment) calls
+///
+/// If no [`dependency`](RenderPassBuilder::dependency) was specified then
+/// [`dependencies`](RenderPassBuilder::dependencies) generates
+/// - dependency from [`SUBPASS_EXTERNAL`] to the first subpass
+/// - dependency between every two consecutive subpasses (attachments output to input attachment read)
+/// - dependency from the last subpass to [`SUBPASS_EXTERNAL`]
+///
+/// # Example
+///
+/// ```no_run
+/// use libvktypes::{graphics, memory};
+///
+/// let builder = graphics::RenderPassBuilder::new()
+///     .color_attachment(
+///         memory::ImageFormat::B8G8R8A8_UNORM,
+///         graphics::AttachmentLoadOp::CLEAR,
+///         graphics::AttachmentStoreOp::STORE,
+///         memory::ImageLayout::PRESENT_SRC_KHR
+///     )
+///     .depth_attachment(memory::ImageFormat::D32_SFLOAT)
+///     .subpass(&[0], Some(1), &[]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RenderPassBuilder {
+    i_attachments: Vec<AttachmentInfo>,
+    i_subpasses: Vec<(Vec<u32>, u32, Vec<u32>)>,
+    i_dependencies: Vec<SubpassSync>,
+}
+
+impl RenderPassBuilder {
+    pub fn new() -> RenderPassBuilder {
+        RenderPassBuilder::default()
+    }
+
+    /// Add color attachment
+    ///
+    /// Attachment is single sampled and has undefined initial layout
+    pub fn color_attachment(
+        mut self,
+        format: memory::ImageFormat,
+        load_op: AttachmentLoadOp,
+        store_op: AttachmentStoreOp,
+        final_layout: memory::ImageLayout) -> RenderPassBuilder
+    {
+        self.i_attachments.push(AttachmentInfo {
+            format,
+            samples: memory::SampleCount::TYPE_1,
+            load_op,
+            store_op,
+            stencil_load_op: AttachmentLoadOp::DONT_CARE,
+            stencil_store_op: AttachmentStoreOp::DONT_CARE,
+            initial_layout: memory::ImageLayout::UNDEFINED,
+            final_layout,
+        });
+
+        self
+    }
+
+    /// Add depth attachment which is cleared on load and discarded on store
+    pub fn depth_attachment(mut self, format: memory::ImageFormat) -> RenderPassBuilder {
+        self.i_attachments.push(AttachmentInfo {
+            format,
+            samples: memory::SampleCount::TYPE_1,
+            load_op: AttachmentLoadOp::CLEAR,
+            store_op: AttachmentStoreOp::DONT_CARE,
+            stencil_load_op: AttachmentLoadOp::DONT_CARE,
+            stencil_store_op: AttachmentStoreOp::DONT_CARE,
+            initial_layout: memory::ImageLayout::UNDEFINED,
+            final_layout: memory::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+        });
+
+        self
+    }
+
+    /// Add arbitrary attachment
+    pub fn attachment(mut self, info: AttachmentInfo) -> RenderPassBuilder {
+        self.i_attachments.push(info);
+
+        self
+    }
+
+    /// Add subpass
+    ///
+    /// `colors`, `depth` and `inputs` are attachment indices
+    pub fn subpass(mut self, colors: &[usize], depth: Option<usize>, inputs: &[usize]) -> RenderPassBuilder {
+        self.i_subpasses.push((
+            colors.iter().map(|&i| i as u32).collect(),
+            depth.map_or(NO_ATTACHMENT, |i| i as u32),
+            inputs.iter().map(|&i| i as u32).collect()
+        ));
+
+        self
+    }
+
+    /// Add dependency between subpasses
+    ///
+    /// Disables automatically generated dependencies
+    pub fn dependency(mut self, sync: SubpassSync) -> RenderPassBuilder {
+        self.i_dependencies.push(sync);
+
+        self
+    }
+
+    /// Return added attachments
+    pub fn attachments(&self) -> &[AttachmentInfo] {
+        &self.i_attachments
+    }
+
+    /// Return subpass configurations
+    pub fn subpasses(&self) -> Vec<SubpassInfo<'_>> {
+        self.i_subpasses
+            .iter()
+            .map(|(colors, depth, inputs)| SubpassInfo {
+                input_attachments: inputs,
+                color_attachments: colors,
+                resolve_attachments: &[],
+                depth_stencil_attachment: *depth,
+                preserve_attachments: &[],
+            })
+            .collect()
+    }
+
+    /// Return added dependencies or generated ones if none were added
+    pub fn dependencies(&self) -> Vec<SubpassSync> {
+        if !self.i_dependencies.is_empty() || self.i_subpasses.is_empty() {
+            return self.i_dependencies.clone();
         }
+
+        let last = self.i_subpasses.len() as u32 - 1;
+
+        let mut result = vec![
+            SubpassSync {
+                src_subpass: SUBPASS_EXTERNAL,
+                dst_subpass: 0,
+                src_stage: PipelineStage::BOTTOM_OF_PIPE,
+                dst_stage: PipelineStage::COLOR_ATTACHMENT_OUTPUT | PipelineStage::EARLY_FRAGMENT_TESTS,
+                src_access: AccessFlags::MEMORY_READ,
+                dst_access: AccessFlags::COLOR_ATTACHMENT_WRITE
+                    | AccessFlags::COLOR_ATTACHMENT_READ
+                    | AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            }
+        ];
+
+        for i in 0..last {
+            result.push(SubpassSync {
+                src_subpass: i,
+                dst_subpass: i + 1,
+                src_stage: PipelineStage::COLOR_ATTACHMENT_OUTPUT | PipelineStage::LATE_FRAGMENT_TESTS,
+                dst_stage: PipelineStage::FRAGMENT_SHADER,
+                src_access: AccessFlags::COLOR_ATTACHMENT_WRITE | AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                dst_access: AccessFlags::INPUT_ATTACHMENT_READ,
+            });
+        }
+
+        result.push(SubpassSync {
+            src_subpass: last,
+            dst_subpass: SUBPASS_EXTERNAL,
+            src_stage: PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+            dst_stage: PipelineStage::BOTTOM_OF_PIPE,
+            src_access: AccessFlags::COLOR_ATTACHMENT_WRITE | AccessFlags::COLOR_ATTACHMENT_READ,
+            dst_access: AccessFlags::MEMORY_READ,
+        });
+
+        result
     }
-}
\ No newline at end of file
+
+    /// Create [`RenderPass`]
+    pub fn build(&self, device: &dev::Device) -> Result<RenderPass, RenderPassError> {
+        let subpasses = self.subpasses();
+        let dependencies = self.dependencies();
+
+        let rp_cfg = RenderPassCfg {
+            attachments: &self.i_attachments,
+            sync_info: &dependencies,
+            subpasses: &subpasses,
+        };
+
+        RenderPass::new(device, &rp_cfg)
+    }
+}
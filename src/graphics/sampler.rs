@@ -5,7 +5,7 @@ use ash::vk;
 use crate::{
     dev,
     graphics,
-    on_error_ret
+    on_error
 };
 
 use std::{
@@ -46,21 +46,67 @@ pub type BorderColor = vk::BorderColor;
 
 #[derive(Debug)]
 pub enum SamplerError {
-    Creation
+    /// Failed to
+    /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateSampler.html) sampler
+    Creation(vk::Result),
+    /// Anisotropy is requested but `samplerAnisotropy` feature is not enabled
+    Anisotropy,
+    /// Unnormalized coordinates require the same `min_filter` and `mag_filter`
+    UnnormalizedFilter,
+    /// Unnormalized coordinates require `NEAREST` mipmap mode and zero `min_lod` and `max_lod`
+    UnnormalizedMipmap,
+    /// Unnormalized coordinates require `CLAMP_TO_EDGE` or `CLAMP_TO_BORDER` for `u` and `v` addressing
+    UnnormalizedAddressMode,
+    /// Unnormalized coordinates can not be used with anisotropy
+    UnnormalizedAnisotropy,
+    /// Unnormalized coordinates can not be used with comparison
+    UnnormalizedCompare
 }
 
 impl fmt::Display for SamplerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "vkCreateSampler call failed")
+        match self {
+            SamplerError::Creation(_) => write!(f, "vkCreateSampler call failed"),
+            SamplerError::Anisotropy => write!(f, "samplerAnisotropy feature is not enabled"),
+            SamplerError::UnnormalizedFilter => write!(f, "Unnormalized coordinates require the same min and mag filters"),
+            SamplerError::UnnormalizedMipmap => write!(f, "Unnormalized coordinates require NEAREST mipmap mode and zero lod"),
+            SamplerError::UnnormalizedAddressMode => write!(f, "Unnormalized coordinates require clamp addressing"),
+            SamplerError::UnnormalizedAnisotropy => write!(f, "Unnormalized coordinates can not be used with anisotropy"),
+            SamplerError::UnnormalizedCompare => write!(f, "Unnormalized coordinates can not be used with comparison"),
+        }
     }
 }
 
-impl Error for SamplerError {}
+impl Error for SamplerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SamplerError::Creation(result) => Some(result),
+            _ => None
+        }
+    }
+}
 
 /// Sampler creation configuration
 ///
 /// For fields description see
 /// [`VkSamplerCreateInfo`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkSamplerCreateInfo.html)
+///
+/// # Depth comparison
+/// Set `compare_enable` and `compare_op` to use sampler with `sampler2DShadow` and similar types
+///
+/// `border_color` is used with `CLAMP_TO_BORDER` addressing,
+/// for example `FLOAT_OPAQUE_WHITE` makes everything outside of shadow map lit
+///
+/// # Unnormalized coordinates
+/// Texel coordinates are used instead of `[0, 1]` range if `unnormalized_coordinates` is set
+///
+/// In that case
+/// - `min_filter` and `mag_filter` must be the same
+/// - `mipmap_mode` must be `NEAREST`, `min_lod` and `max_lod` must be zero
+/// - `address_mode_u` and `address_mode_v` must be `CLAMP_TO_EDGE` or `CLAMP_TO_BORDER`
+/// - `anisotropy_enable` and `compare_enable` must be false
+///
+/// Otherwise [`Sampler::new`] returns error
 pub struct SamplerCfg {
     pub mipmap_mode: SamplerMipmapMode,
     pub address_mode_u: SamplerAddressMode,
@@ -128,6 +174,14 @@ pub struct Sampler {
 
 impl Sampler {
     pub fn new(device: &dev::Device, cfg: &SamplerCfg) -> Result<Sampler, SamplerError> {
+        if cfg.anisotropy_enable && device.features().sampler_anisotropy == vk::FALSE {
+            return Err(SamplerError::Anisotropy);
+        }
+
+        if cfg.unnormalized_coordinates {
+            check_unnormalized(cfg)?;
+        }
+
         let info = vk::SamplerCreateInfo {
             s_type: vk::StructureType::SAMPLER_CREATE_INFO,
             p_next: ptr::null(),
@@ -151,7 +205,7 @@ impl Sampler {
         };
 
         let sampler = unsafe {
-            on_error_ret!(device.device().create_sampler(&info, device.allocator()), SamplerError::Creation)
+            on_error!(device.device().create_sampler(&info, device.allocator()), |err| return Err(SamplerError::Creation(err)))
         };
 
         Ok(
@@ -167,6 +221,34 @@ impl Sampler {
     }
 }
 
+fn check_unnormalized(cfg: &SamplerCfg) -> Result<(), SamplerError> {
+    let is_clamp = |mode: SamplerAddressMode| {
+        mode == SamplerAddressMode::CLAMP_TO_EDGE || mode == SamplerAddressMode::CLAMP_TO_BORDER
+    };
+
+    if cfg.min_filter != cfg.mag_filter {
+        return Err(SamplerError::UnnormalizedFilter);
+    }
+
+    if cfg.mipmap_mode != SamplerMipmapMode::NEAREST || cfg.min_lod != 0.0 || cfg.max_lod != 0.0 {
+        return Err(SamplerError::UnnormalizedMipmap);
+    }
+
+    if !is_clamp(cfg.address_mode_u) || !is_clamp(cfg.address_mode_v) {
+        return Err(SamplerError::UnnormalizedAddressMode);
+    }
+
+    if cfg.anisotropy_enable {
+        return Err(SamplerError::UnnormalizedAnisotropy);
+    }
+
+    if cfg.compare_enable {
+        return Err(SamplerError::UnnormalizedCompare);
+    }
+
+    Ok(())
+}
+
 impl Drop for Sampler {
     fn drop(&mut self) {
         unsafe {
@@ -0,0 +1,134 @@
+//! Vertex input configuration computed from attribute types
+
+use crate::{memory, graphics};
+
+use std::mem::size_of;
+
+/// Rust type which may be used as vertex attribute
+///
+/// `FORMAT` must describe exactly `size_of::<Self>()` bytes
+pub trait VertexAttribute {
+    const FORMAT: memory::ImageFormat;
+}
+
+macro_rules! vertex_attributes {
+    ( $( $ty:ty => $format:ident ),* $(,)? ) => {
+        $(
+            impl VertexAttribute for $ty {
+                const FORMAT: memory::ImageFormat = memory::ImageFormat::$format;
+            }
+        )*
+    };
+}
+
+vertex_attributes! {
+    f32 => R32_SFLOAT,
+    [f32; 2] => R32G32_SFLOAT,
+    [f32; 3] => R32G32B32_SFLOAT,
+    [f32; 4] => R32G32B32A32_SFLOAT,
+    u32 => R32_UINT,
+    [u32; 2] => R32G32_UINT,
+    [u32; 3] => R32G32B32_UINT,
+    [u32; 4] => R32G32B32A32_UINT,
+    i32 => R32_SINT,
+    [i32; 2] => R32G32_SINT,
+    [i32; 3] => R32G32B32_SINT,
+    [i32; 4] => R32G32B32A32_SINT,
+    [u8; 4] => R8G8B8A8_UNORM,
+}
+
+/// Attributes of the vertex and size of the vertex in every binding
+///
+/// ```
+/// use libvktypes::graphics;
+///
+/// // struct Vertex {
+/// //     pos: [f32; 4],
+/// //     uv: [f32; 2],
+/// // }
+/// let layout = graphics::VertexLayout::builder()
+///     .attr::<[f32; 4]>()
+///     .attr::<[f32; 2]>()
+///     .build();
+///
+/// assert_eq!(layout.attributes()[1].offset, 16);
+/// assert_eq!(layout.vertex_size(), 24);
+/// ```
+///
+/// Use [`attributes`](VertexLayout::attributes) as [`PipelineCfg::vert_input`](graphics::PipelineCfg::vert_input)
+/// and [`vertex_size`](VertexLayout::vertex_size) as [`PipelineCfg::vertex_size`](graphics::PipelineCfg::vertex_size)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VertexLayout {
+    i_attributes: Vec<graphics::VertexInputCfg>,
+    i_strides: Vec<(u32, u32)>,
+}
+
+impl VertexLayout {
+    pub fn builder() -> VertexLayoutBuilder {
+        VertexLayoutBuilder::default()
+    }
+
+    /// Return attributes in order they were added
+    pub fn attributes(&self) -> &[graphics::VertexInputCfg] {
+        &self.i_attributes
+    }
+
+    /// Return total size of attributes in `binding`
+    ///
+    /// Zero if binding has no attributes
+    pub fn stride(&self, binding: u32) -> u32 {
+        self.i_strides.iter().find(|(b, _)| *b == binding).map_or(0, |(_, stride)| *stride)
+    }
+
+    /// Return the largest [`stride`](VertexLayout::stride) among all bindings
+    ///
+    /// [`PipelineCfg::vertex_size`](graphics::PipelineCfg::vertex_size) is shared by every binding,
+    /// so bindings of the layout should have the same stride
+    pub fn vertex_size(&self) -> u32 {
+        self.i_strides.iter().map(|(_, stride)| *stride).max().unwrap_or(0)
+    }
+}
+
+/// Helper for building [`VertexLayout`] attribute by attribute
+///
+/// Locations are assigned in order of [`attr`](VertexLayoutBuilder::attr) and
+/// [`attr_at`](VertexLayoutBuilder::attr_at) calls,
+/// offset of every attribute is the total size of previous attributes of the same binding
+#[derive(Debug, Clone, Default)]
+pub struct VertexLayoutBuilder {
+    i_layout: VertexLayout,
+}
+
+impl VertexLayoutBuilder {
+    /// Add attribute of type `T` to binding 0
+    pub fn attr<T: VertexAttribute>(self) -> VertexLayoutBuilder {
+        self.attr_at::<T>(0)
+    }
+
+    /// Add attribute of type `T` to `binding`
+    pub fn attr_at<T: VertexAttribute>(mut self, binding: u32) -> VertexLayoutBuilder {
+        let layout = &mut self.i_layout;
+
+        let offset = layout.stride(binding);
+
+        layout.i_attributes.push(graphics::VertexInputCfg {
+            location: layout.i_attributes.len() as u32,
+            binding,
+            format: T::FORMAT,
+            offset,
+        });
+
+        let stride = offset + size_of::<T>() as u32;
+
+        match layout.i_strides.iter_mut().find(|(b, _)| *b == binding) {
+            Some((_, val)) => *val = stride,
+            None => layout.i_strides.push((binding, stride))
+        }
+
+        self
+    }
+
+    pub fn build(self) -> VertexLayout {
+        self.i_layout
+    }
+}
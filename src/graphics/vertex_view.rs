@@ -14,25 +14,26 @@ use crate::{
 /// Note: you may bind single vertex buffer to the different layouts (with different offsets)
 ///
 /// See docs for [`VertexInputCfg`](graphics::VertexInputCfg)
+///
+/// Use [`Memory::vertex_view`](memory::Memory::vertex_view) to check offset against buffer size
 #[derive(Debug, Clone)]
 pub struct VertexView<'a> {
     i_view: memory::View<'a>,
-    i_offset: u32
+    i_offset: u32,
+    i_size: u64
 }
 
 impl<'a> VertexView<'a> {
     pub fn from_cfg(view: memory::View<'a>, cfg: graphics::VertexInputCfg) -> VertexView<'a> {
-        VertexView {
-            i_view: view,
-            i_offset: cfg.offset
-        }
+        VertexView::with_offset(view, cfg.offset)
     }
 
     /// About `offset` read docs for [`VertexInputCfg`](graphics::VertexInputCfg)
     pub fn with_offset(view: memory::View<'a>, offset: u32) -> VertexView<'a> {
         VertexView {
             i_view: view,
-            i_offset: offset
+            i_offset: offset,
+            i_size: view.size().saturating_sub(offset as u64)
         }
     }
 
@@ -40,6 +41,16 @@ impl<'a> VertexView<'a> {
         self.i_offset
     }
 
+    /// Bytes of the buffer available after [`offset`](Self::offset)
+    pub fn size(&self) -> u64 {
+        self.i_size
+    }
+
+    /// Return view to the vertex buffer
+    pub fn view(&self) -> memory::View<'a> {
+        self.i_view
+    }
+
     pub(crate) fn buffer(&self) -> vk::Buffer {
         self.i_view.buffer()
     }
@@ -1,13 +1,39 @@
 //! Provide information about available hardware
 //!
 //! Instead of [dev module](crate::dev) `hw` represents hardware level
+//!
+//! # Compute only
+//! Surface is required only for presentation.
+//! Pass [`None`] to [`Description::poll`] and do not filter queues by surface support
+//!
+//! ```no_run
+//! use libvktypes::{libvk, hw, extensions};
+//!
+//! let lib_type = libvk::InstanceType {
+//!     extensions: &extensions::headless_extensions(),
+//!     ..libvk::InstanceType::default()
+//! };
+//!
+//! let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+//!
+//! let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+//!
+//! let (hw_dev, queue, _) = hw_list
+//!     .find_first(
+//!         hw::any,
+//!         hw::QueueFamilyDescription::is_compute,
+//!         hw::any
+//!     )
+//!     .expect("Failed to find suitable hardware device");
+//! ```
 
 use ash::vk;
 
-use crate::on_error_ret;
-use crate::{libvk, surface, offset};
+use crate::{on_error, on_error_ret};
+use crate::{libvk, surface, memory, offset};
 
 use std::ffi::CStr;
+use std::error::Error;
 use std::fmt;
 
 #[derive(Debug)]
@@ -16,6 +42,23 @@ pub enum HWError {
     SurfaceSupport,
 }
 
+impl fmt::Display for HWError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let err_msg = match self {
+            HWError::Enumerate => {
+                "Failed to enumerate physical devices (vkEnumeratePhysicalDevices call failed)"
+            },
+            HWError::SurfaceSupport => {
+                "Failed to query surface support (vkGetPhysicalDeviceSurfaceSupportKHR call failed)"
+            },
+        };
+
+        write!(f, "{:?}", err_msg)
+    }
+}
+
+impl Error for HWError {}
+
 /// Represents GPU type
 ///
 #[doc = "Ash documentation about possible values <https://docs.rs/ash/latest/ash/vk/struct.BorderColor.html>"]
@@ -32,6 +75,7 @@ pub struct QueueFamilyDescription {
     i_count: u32,
     i_property: vk::QueueFlags,
     i_surface_support: bool,
+    i_granularity: memory::Extent3D,
 }
 
 impl QueueFamilyDescription {
@@ -59,6 +103,7 @@ impl QueueFamilyDescription {
             i_count: property.queue_count,
             i_property: property.queue_flags,
             i_surface_support: surface_support,
+            i_granularity: property.min_image_transfer_granularity,
         }
     }
 
@@ -89,9 +134,26 @@ impl QueueFamilyDescription {
 
     /// Is VK_QUEUE_SPARSE_BINDING_BIT set for queue family
     pub fn is_sparce_binding(&self) -> bool {
+        self.is_sparse_binding()
+    }
+
+    /// Is VK_QUEUE_SPARSE_BINDING_BIT set for queue family
+    pub fn is_sparse_binding(&self) -> bool {
         self.i_property.contains(vk::QueueFlags::SPARSE_BINDING)
     }
 
+    /// Return minimum granularity supported for image transfer operations on the queue family
+    ///
+    /// `(1, 1, 1)` means there are no restrictions on copy regions
+    ///
+    /// `(0, 0, 0)` means only whole mip levels can be transferred
+    ///
+    /// See [`BufferImageRegion::fits_granularity`](crate::cmd::BufferImageRegion::fits_granularity)
+    #[doc = "See more <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkQueueFamilyProperties.html>"]
+    pub fn min_image_transfer_granularity(&self) -> memory::Extent3D {
+        self.i_granularity
+    }
+
     /// If [`surface`](crate::surface::Surface) was provided in [`poll`](crate::hw::Description::poll)
     /// returns does selected queue family support `surface`
     ///
@@ -131,6 +193,18 @@ impl QueueFamilyDescription {
     ) -> bool {
         matches!(self.explicit_support_surface(hw, surface), Ok(true))
     }
+
+    /// Does selected queue family within hw device support every surface in `surfaces`
+    ///
+    /// Same as [`support_surface`](crate::hw::QueueFamilyDescription::support_surface) for several surfaces
+    /// (e.g. one per window)
+    pub fn support_surfaces(
+        &self,
+        hw: &HWDevice,
+        surfaces: &[&surface::Surface]
+    ) -> bool {
+        surfaces.iter().all(|surface| self.support_surface(hw, surface))
+    }
 }
 
 impl fmt::Display for QueueFamilyDescription {
@@ -141,13 +215,13 @@ impl fmt::Display for QueueFamilyDescription {
             Support graphics:       {}\n\
             Support compute:        {}\n\
             Support transfer:       {}\n\
-            Support sparce binding: {}\n\
+            Support sparse binding: {}\n\
             Support surface:        {}\n",
             self.count(),
             if self.is_graphics() { "yes" } else { "no" },
             if self.is_compute()  { "yes" } else { "no" },
             if self.is_transfer() { "yes" } else { "no" },
-            if self.is_sparce_binding() {
+            if self.is_sparse_binding() {
                 "yes"
             } else {
                 "no"
@@ -183,6 +257,8 @@ pub struct MemoryDescription {
     /// Corresponding heap index
     i_heap_index: u32,
     i_property: vk::MemoryPropertyFlags,
+    i_budget: u64,
+    i_usage: u64,
 }
 
 impl MemoryDescription {
@@ -196,6 +272,8 @@ impl MemoryDescription {
             i_heap_size: heap_size,
             i_heap_index: mem_type.heap_index,
             i_property: mem_type.property_flags,
+            i_budget: heap_size,
+            i_usage: 0,
         }
     }
 
@@ -214,6 +292,27 @@ impl MemoryDescription {
         self.i_heap_index
     }
 
+    /// Return how many bytes of the heap process may use
+    ///
+    /// Without `VK_EXT_memory_budget` (see [`refresh_memory_budget`](HWDevice::refresh_memory_budget))
+    /// returns [`heap_size`](Self::heap_size)
+    #[doc = "See more <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceMemoryBudgetPropertiesEXT.html>"]
+    pub fn budget(&self) -> u64 {
+        self.i_budget
+    }
+
+    /// Return how many bytes of the heap process currently uses
+    ///
+    /// Without `VK_EXT_memory_budget` returns 0
+    pub fn usage(&self) -> u64 {
+        self.i_usage
+    }
+
+    /// Return remaining budget of the heap in bytes
+    pub fn available(&self) -> u64 {
+        self.i_budget.saturating_sub(self.i_usage)
+    }
+
     /// Each memory has its own property as bitmask
     ///
     /// Method checks that selected memory satisfies requirements defined by ```flags```
@@ -295,6 +394,93 @@ impl fmt::Display for MemoryDescription {
 
 pub type Features = vk::PhysicalDeviceFeatures;
 
+/// Operations which are supported by images or buffers of some format
+///
+#[doc = "Possible values: <https://docs.rs/ash/latest/ash/vk/struct.FormatFeatureFlags.html>"]
+///
+#[doc = "See more <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkFormatFeatureFlagBits.html>"]
+pub type FormatFeature = vk::FormatFeatureFlags;
+
+/// Support of the format by device for every tiling
+///
+/// Returned by [`HWDevice::format_properties`]
+///
+#[doc = "See more <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkFormatProperties.html>"]
+#[derive(Debug, Clone, Copy)]
+pub struct FormatSupport {
+    i_properties: vk::FormatProperties,
+}
+
+impl FormatSupport {
+    /// Features of images with selected `tiling`
+    ///
+    /// Tilings other than `LINEAR` and `OPTIMAL` have no features
+    pub fn features(&self, tiling: memory::Tiling) -> FormatFeature {
+        match tiling {
+            memory::Tiling::LINEAR => self.i_properties.linear_tiling_features,
+            memory::Tiling::OPTIMAL => self.i_properties.optimal_tiling_features,
+            _ => FormatFeature::empty()
+        }
+    }
+
+    /// Features of buffers with the format
+    pub fn buffer_features(&self) -> FormatFeature {
+        self.i_properties.buffer_features
+    }
+
+    /// Are all `features` supported for images with `tiling`
+    pub fn supports(&self, tiling: memory::Tiling, features: FormatFeature) -> bool {
+        self.features(tiling).contains(features)
+    }
+
+    /// May image be sampled in shaders
+    pub fn sampled(&self, tiling: memory::Tiling) -> bool {
+        self.supports(tiling, FormatFeature::SAMPLED_IMAGE)
+    }
+
+    /// May image be used as storage image
+    pub fn storage(&self, tiling: memory::Tiling) -> bool {
+        self.supports(tiling, FormatFeature::STORAGE_IMAGE)
+    }
+
+    /// May image be used as color attachment
+    pub fn color_attachment(&self, tiling: memory::Tiling) -> bool {
+        self.supports(tiling, FormatFeature::COLOR_ATTACHMENT)
+    }
+
+    /// May image be used as depth/stencil attachment
+    pub fn depth_attachment(&self, tiling: memory::Tiling) -> bool {
+        self.supports(tiling, FormatFeature::DEPTH_STENCIL_ATTACHMENT)
+    }
+
+    /// May image be source of transfer commands
+    pub fn transfer_src(&self, tiling: memory::Tiling) -> bool {
+        self.supports(tiling, FormatFeature::TRANSFER_SRC)
+    }
+
+    /// May image be destination of transfer commands
+    pub fn transfer_dst(&self, tiling: memory::Tiling) -> bool {
+        self.supports(tiling, FormatFeature::TRANSFER_DST)
+    }
+}
+
+fn features_as_slice(features: &Features) -> &[vk::Bool32] {
+    // PhysicalDeviceFeatures consists only of Bool32 fields
+    unsafe {
+        std::slice::from_raw_parts(
+            features as *const Features as *const vk::Bool32,
+            std::mem::size_of::<Features>()/std::mem::size_of::<vk::Bool32>()
+        )
+    }
+}
+
+fn max_sample_count(counts: vk::SampleCountFlags) -> memory::SampleCount {
+    match counts.as_raw() {
+        0 => memory::SampleCount::TYPE_1,
+        raw => memory::SampleCount::from_raw(1 << (u32::BITS - 1 - raw.leading_zeros()))
+    }
+}
+
 #[derive(Clone)]
 pub struct HWDevice {
     i_device: vk::PhysicalDevice,
@@ -332,28 +518,135 @@ impl HWDevice {
             .enumerate()
             .map(|(i, prop)| QueueFamilyDescription::new(prop, i as u32, hw, surface))
             .filter(|q| {
-                q.is_compute() || q.is_graphics() || q.is_transfer() || q.is_sparce_binding()
+                q.is_compute() || q.is_graphics() || q.is_transfer() || q.is_sparse_binding()
             })
             .collect();
 
-        HWDevice {
+        let mut hw_dev = HWDevice {
             i_device: hw,
             i_features: unsafe { lib.instance().get_physical_device_features(hw) },
             i_properties: properties,
             i_queues: queue_desc,
             i_heap_info: memory_desc,
+        };
+
+        hw_dev.refresh_memory_budget(lib);
+
+        hw_dev
+    }
+
+    /// Is device extension `name` supported
+    pub fn is_extension_supported(&self, lib: &libvk::Instance, name: &CStr) -> bool {
+        let extensions = on_error!(
+            unsafe { lib.instance().enumerate_device_extension_properties(self.i_device) },
+            { return false; }
+        );
+
+        extensions.iter().any(|ext| ext.extension_name_as_c_str() == Ok(name))
+    }
+
+    /// Update [`budget`](MemoryDescription::budget) and [`usage`](MemoryDescription::usage)
+    /// of every memory type
+    ///
+    /// Requires Vulkan 1.1 instance and device and `VK_EXT_memory_budget` support.
+    /// Otherwise values stay as they are
+    ///
+    /// Budget is queried on [`poll`](Description::poll) and may be refreshed any time after
+    pub fn refresh_memory_budget(&mut self, lib: &libvk::Instance) {
+        let version = std::cmp::min(lib.version(), self.version());
+
+        if version < vk::API_VERSION_1_1 || !self.is_extension_supported(lib, vk::EXT_MEMORY_BUDGET_NAME) {
+            return;
+        }
+
+        let mut budget = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+
+        let mut props = vk::PhysicalDeviceMemoryProperties2::default().push_next(&mut budget);
+
+        unsafe {
+            lib.instance().get_physical_device_memory_properties2(self.i_device, &mut props)
+        };
+
+        for mem in &mut self.i_heap_info {
+            mem.i_budget = budget.heap_budget[mem.i_heap_index as usize];
+            mem.i_usage = budget.heap_usage[mem.i_heap_index as usize];
+        }
+    }
+
+    /// Is `dynamicRendering` feature supported
+    ///
+    /// Requires Vulkan 1.3 instance and device, otherwise returns false
+    ///
+    /// See [`DeviceCfg::dynamic_rendering`](crate::dev::DeviceCfg::dynamic_rendering)
+    pub fn supports_dynamic_rendering(&self, lib: &libvk::Instance) -> bool {
+        let version = std::cmp::min(lib.version(), self.version());
+
+        if version < vk::API_VERSION_1_3 {
+            return false;
         }
+
+        let mut dynamic_rendering = vk::PhysicalDeviceDynamicRenderingFeatures::default();
+
+        let mut features = vk::PhysicalDeviceFeatures2::default().push_next(&mut dynamic_rendering);
+
+        unsafe {
+            lib.instance().get_physical_device_features2(self.i_device, &mut features)
+        };
+
+        dynamic_rendering.dynamic_rendering != vk::FALSE
     }
 
     pub(crate) fn device(&self) -> vk::PhysicalDevice {
         self.i_device
     }
 
+    /// Query which operations are supported for `format`
+    ///
+    /// Useful to check usage of the image before [allocation](memory::ImageMemory::allocate)
+    pub fn format_properties(&self, lib: &libvk::Instance, format: memory::ImageFormat) -> FormatSupport {
+        FormatSupport {
+            i_properties: unsafe { lib.instance().get_physical_device_format_properties(self.i_device, format) },
+        }
+    }
+
+    /// Return first format from `candidates` which supports all `features` with `tiling`
+    ///
+    /// For example, to select depth buffer format
+    ///
+    /// ```ignore
+    /// let format = hw_dev.find_supported_format(
+    ///     &lib,
+    ///     &[memory::ImageFormat::D32_SFLOAT, memory::ImageFormat::D24_UNORM_S8_UINT],
+    ///     memory::Tiling::OPTIMAL,
+    ///     hw::FormatFeature::DEPTH_STENCIL_ATTACHMENT
+    /// );
+    /// ```
+    pub fn find_supported_format(
+        &self,
+        lib: &libvk::Instance,
+        candidates: &[memory::ImageFormat],
+        tiling: memory::Tiling,
+        features: FormatFeature
+    ) -> Option<memory::ImageFormat> {
+        candidates
+            .iter()
+            .copied()
+            .find(|&format| self.format_properties(lib, format).supports(tiling, features))
+    }
+
     /// Features information
     pub fn features(&self) -> &Features {
         &self.i_features
     }
 
+    /// Check if every feature from `requested` is supported by device
+    pub fn supports_features(&self, requested: &Features) -> bool {
+        features_as_slice(requested)
+        .iter()
+        .zip(features_as_slice(&self.i_features))
+        .all(|(&req, &supported)| req == vk::FALSE || supported != vk::FALSE)
+    }
+
     /// Device name
     pub fn name(&self) -> String {
         unsafe {
@@ -458,11 +751,48 @@ impl HWDevice {
         self.i_properties.limits.non_coherent_atom_size
     }
 
+    /// Granularity in bytes at which buffers and optimal images may be placed
+    /// in the same memory without aliasing
+    #[doc = "See more <https://registry.khronos.org/vulkan/specs/1.3-extensions/html/vkspec.html#resources-bufferimagegranularity>"]
+    pub fn buffer_image_granularity(&self) -> u64 {
+        self.i_properties.limits.buffer_image_granularity
+    }
+
+    /// Max number of samples supported for color attachments
+    pub fn max_color_samples(&self) -> memory::SampleCount {
+        max_sample_count(self.i_properties.limits.framebuffer_color_sample_counts)
+    }
+
+    /// Max number of samples supported for depth attachments
+    pub fn max_depth_samples(&self) -> memory::SampleCount {
+        max_sample_count(self.i_properties.limits.framebuffer_depth_sample_counts)
+    }
+
     /// Max sampler anisotropy
     pub fn max_anisotropy(&self) -> f32 {
         self.i_properties.limits.max_sampler_anisotropy
     }
 
+    /// Max number of work groups which may be dispatched in each dimension
+    pub fn max_work_group_count(&self) -> [u32; 3] {
+        self.i_properties.limits.max_compute_work_group_count
+    }
+
+    /// Max local size of work group in each dimension
+    pub fn max_work_group_size(&self) -> [u32; 3] {
+        self.i_properties.limits.max_compute_work_group_size
+    }
+
+    /// Max total number of invocations in a single work group
+    pub fn max_work_group_invocations(&self) -> u32 {
+        self.i_properties.limits.max_compute_work_group_invocations
+    }
+
+    #[doc(hidden)]
+    pub fn limits(&self) -> &vk::PhysicalDeviceLimits {
+        &self.i_properties.limits
+    }
+
     /// Return iterator over available queues
     pub fn queues(&self) -> impl Iterator<Item = &QueueFamilyDescription> {
         self.i_queues.iter()
@@ -637,6 +967,11 @@ impl Description {
         self.list().filter(move |x| selector(x))
     }
 
+    /// Return first hw device which satisfies `dev` and has queue family and memory
+    /// which satisfy `queue` and `mem`
+    ///
+    /// Do not use [`is_surface_supported`](QueueFamilyDescription::is_surface_supported) in `queue`
+    /// if hardware was [polled](Description::poll) without surface: it is always `false` in that case
     // TODO mb rewrite it with find_map?
     pub fn find_first<T, U, S>(
         &self,
@@ -657,6 +992,33 @@ impl Description {
 
         None
     }
+
+    /// Same as [`find_first`](Description::find_first) but queue family must also
+    /// [support](QueueFamilyDescription::support_surfaces) presentation to every surface in `surfaces`
+    ///
+    /// Surface support is queried explicitly so hardware may be [polled](Description::poll) with any surface
+    pub fn find_first_with_surfaces<T, U, S>(
+        &self,
+        dev: T,
+        queue: U,
+        mem: S,
+        surfaces: &[&surface::Surface]
+    ) -> Option<(&HWDevice, &QueueFamilyDescription, &MemoryDescription)>
+    where
+        T: Fn(&HWDevice) -> bool,
+        U: Fn(&QueueFamilyDescription) -> bool,
+        S: Fn(&MemoryDescription) -> bool,
+    {
+        for hw in self.filter_hw(dev) {
+            let q = hw.find_first_queue(|x| queue(x) && x.support_surfaces(hw, surfaces));
+
+            if let (Some(q), Some(m)) = (q, hw.find_first_memory(&mem)) {
+                return Some((hw, q, m));
+            }
+        }
+
+        None
+    }
 }
 
 /// Helper function which provides nicer placeholder for filters
@@ -9,48 +9,179 @@ use std::fmt::{
     Formatter,
     Debug
 };
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::marker::PhantomData;
 
 use ash::vk;
 
 use crate::debug;
 
+/// Return names of installed instance layers
+///
+/// Returns empty vector if query failed
+///
+/// Entry may be obtained with [`load_entry`](crate::libvk::load_entry)
+pub fn available_layers(entry: &ash::Entry) -> Vec<String> {
+    let properties = unsafe { entry.enumerate_instance_layer_properties() }.unwrap_or_default();
+
+    properties
+        .iter()
+        .filter_map(|x| x.layer_name_as_c_str().ok())
+        .map(|x| x.to_string_lossy().into_owned())
+        .collect()
+}
+
 pub trait Layer {
     fn info(&self) -> *const c_void;
     fn name() -> CString;
 }
 
-pub struct DebugLayer<'a>(vk::DebugUtilsMessengerCreateInfoEXT<'a>);
+/// Severity of debug message
+///
+#[doc = "Values: <https://docs.rs/ash/latest/ash/vk/struct.DebugUtilsMessageSeverityFlagsEXT.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkDebugUtilsMessageSeverityFlagBitsEXT.html>"]
+pub type Severity = vk::DebugUtilsMessageSeverityFlagsEXT;
+
+/// Type of debug message
+///
+#[doc = "Values: <https://docs.rs/ash/latest/ash/vk/struct.DebugUtilsMessageTypeFlagsEXT.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkDebugUtilsMessageTypeFlagBitsEXT.html>"]
+pub type MessageType = vk::DebugUtilsMessageTypeFlagsEXT;
+
+/// User handler of debug messages
+pub type DebugCallback = dyn Fn(Severity, MessageType, &str) + Send + Sync;
+
+/// State which is shared between [`DebugLayer`], [`Instance`](crate::libvk::Instance) and debug callback
+pub(crate) struct DebugState {
+    pub(crate) callback: Option<Box<DebugCallback>>,
+    pub(crate) errors: AtomicUsize,
+    pub(crate) warnings: AtomicUsize,
+}
+
+impl DebugState {
+    pub(crate) fn error_count(&self) -> usize {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn warning_count(&self) -> usize {
+        self.warnings.load(Ordering::Relaxed)
+    }
+}
+
+/// Validation layer with
+/// [debug messenger](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkDebugUtilsMessengerCreateInfoEXT.html)
+///
+/// By default messages are printed into stdout
+///
+/// Use [`with_callback`](DebugLayer::with_callback) to handle them manually
+pub struct DebugLayer<'a> {
+    i_info: vk::DebugUtilsMessengerCreateInfoEXT<'a>,
+    i_state: Arc<DebugState>,
+}
 
 impl<'a> DebugLayer<'a> {
+    /// Report messages of all severities and types
     pub fn full() -> DebugLayer<'a> {
-        DebugLayer(
-            vk::DebugUtilsMessengerCreateInfoEXT {
+        DebugLayer::new(Severity::VERBOSE)
+    }
+
+    /// Report messages with severity not less than `min_severity`
+    pub fn new(min_severity: Severity) -> DebugLayer<'a> {
+        let state = Arc::new(DebugState {
+            callback: None,
+            errors: AtomicUsize::new(0),
+            warnings: AtomicUsize::new(0),
+        });
+
+        DebugLayer {
+            i_info: vk::DebugUtilsMessengerCreateInfoEXT {
                 s_type: vk::StructureType::DEBUG_UTILS_MESSENGER_CREATE_INFO_EXT,
                 p_next: ptr::null(),
                 flags: vk::DebugUtilsMessengerCreateFlagsEXT::empty(),
-                message_severity: vk::DebugUtilsMessageSeverityFlagsEXT::WARNING |
-                    vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE |
-                    vk::DebugUtilsMessageSeverityFlagsEXT::INFO |
-                    vk::DebugUtilsMessageSeverityFlagsEXT::ERROR,
-                message_type: vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
-                    | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
-                    | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION,
+                message_severity: severity_mask(min_severity),
+                message_type: MessageType::GENERAL
+                    | MessageType::PERFORMANCE
+                    | MessageType::VALIDATION,
                 pfn_user_callback: Some(debug::vulkan_debug_utils_callback),
-                p_user_data: ptr::null_mut(),
+                p_user_data: Arc::as_ptr(&state) as *mut c_void,
                 _marker: PhantomData,
-            }
-        )
+            },
+            i_state: state,
+        }
+    }
+
+    /// Return [default](DebugLayer::default) layer if validation layer is installed and `None` otherwise
+    ///
+    /// Useful for builds which are run on machines without Vulkan SDK
+    pub fn if_available(entry: &ash::Entry) -> Option<DebugLayer<'a>> {
+        let name = DebugLayer::name().to_string_lossy().into_owned();
+
+        if available_layers(entry).contains(&name) {
+            Some(DebugLayer::default())
+        } else {
+            None
+        }
+    }
+
+    /// Report only messages with severity not less than `min_severity`
+    pub fn with_severity(mut self, min_severity: Severity) -> DebugLayer<'a> {
+        self.i_info.message_severity = severity_mask(min_severity);
+        self
+    }
+
+    /// Report only messages of `types`
+    pub fn with_types(mut self, types: MessageType) -> DebugLayer<'a> {
+        self.i_info.message_type = types;
+        self
+    }
+
+    /// Pass messages into `callback` instead of printing them
+    ///
+    /// Note: callback must be set before instance creation
+    pub fn with_callback<F>(self, callback: F) -> DebugLayer<'a>
+    where
+        F: Fn(Severity, MessageType, &str) + Send + Sync + 'static
+    {
+        let state = Arc::new(DebugState {
+            callback: Some(Box::new(callback)),
+            errors: AtomicUsize::new(0),
+            warnings: AtomicUsize::new(0),
+        });
+
+        DebugLayer {
+            i_info: vk::DebugUtilsMessengerCreateInfoEXT {
+                p_user_data: Arc::as_ptr(&state) as *mut c_void,
+                ..self.i_info
+            },
+            i_state: state,
+        }
+    }
+
+    /// Return how many error messages were reported so far
+    pub fn error_count(&self) -> usize {
+        self.i_state.error_count()
+    }
+
+    /// Return how many warning messages were reported so far
+    pub fn warning_count(&self) -> usize {
+        self.i_state.warning_count()
     }
 
     pub fn as_raw(&self) -> &vk::DebugUtilsMessengerCreateInfoEXT {
-        &self.0
+        &self.i_info
+    }
+
+    pub(crate) fn state(&self) -> &Arc<DebugState> {
+        &self.i_state
     }
 }
 
 impl<'a> Layer for DebugLayer<'a> {
     fn info(&self) -> *const c_void {
-        &self.0 as *const vk::DebugUtilsMessengerCreateInfoEXT as *const c_void
+        &self.i_info as *const vk::DebugUtilsMessengerCreateInfoEXT as *const c_void
     }
 
     fn name() -> CString {
@@ -59,24 +190,9 @@ impl<'a> Layer for DebugLayer<'a> {
 }
 
 impl<'a> Default for DebugLayer<'a> {
+    /// Report warnings and errors
     fn default() -> DebugLayer<'a> {
-        DebugLayer(
-            vk::DebugUtilsMessengerCreateInfoEXT {
-                s_type: vk::StructureType::DEBUG_UTILS_MESSENGER_CREATE_INFO_EXT,
-                p_next: ptr::null(),
-                flags: vk::DebugUtilsMessengerCreateFlagsEXT::empty(),
-                message_severity: vk::DebugUtilsMessageSeverityFlagsEXT::WARNING |
-                    // vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE |
-                    // vk::DebugUtilsMessageSeverityFlagsEXT::INFO |
-                    vk::DebugUtilsMessageSeverityFlagsEXT::ERROR,
-                message_type: vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
-                    | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
-                    | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION,
-                pfn_user_callback: Some(debug::vulkan_debug_utils_callback),
-                p_user_data: ptr::null_mut(),
-                _marker: PhantomData,
-            }
-        )
+        DebugLayer::new(Severity::WARNING)
     }
 }
 
@@ -84,4 +200,11 @@ impl<'a> Debug for DebugLayer<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "VK_LAYER_KHRONOS_validation")
     }
-}
\ No newline at end of file
+}
+
+fn severity_mask(min_severity: Severity) -> Severity {
+    [Severity::VERBOSE, Severity::INFO, Severity::WARNING, Severity::ERROR]
+    .into_iter()
+    .filter(|s| s.as_raw() >= min_severity.as_raw())
+    .fold(Severity::empty(), |acc, s| acc | s)
+}
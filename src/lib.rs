@@ -1,4 +1,29 @@
 //! Library aims to make interaction with GPU via Vulkan API less verbose and safer
+//!
+//! # Headless rendering
+//! Window, [surface] and [swapchain] are required only for presentation
+//!
+//! To render without them
+//! 1. Poll hardware with [`hw::Description::poll`] without surface
+//! 2. Create device without swapchain extension
+//! 3. Allocate render target with [`memory::ImageMemory::offscreen`]
+//! 4. Create render pass with [`graphics::RenderPass::offscreen`] and framebuffer from the image
+//! 5. Execute commands with [`queue::Queue::exec`] and copy image into host visible memory
+//!
+//! See `offscreen` example
+//!
+//! # Threading
+//! [`dev::Device`], [`memory::Memory`] and pipelines are `Send` and `Sync`
+//!
+//! Vulkan requires external synchronization of command pools and queues,
+//! so [`cmd::Pool`] and [`cmd::Buffer`] are `Send` but not `Sync`
+//! and submissions to the same [`queue::Queue`] are serialized by locks.
+//! Buffers of the pool may be dropped on another thread, so freeing of them is serialized by the pool lock
+//!
+//! To record commands in parallel
+//! 1. Create pool for every thread with [`cmd::PoolSet::per_thread`]
+//! 2. Move each pool into its thread, record and [`commit`](cmd::Buffer::commit) buffers there
+//! 3. Send [`cmd::ExecutableBuffer`]s back and submit them from the thread which owns the queue
 
 pub mod macros;
 pub mod alloc;
@@ -19,7 +44,10 @@ pub mod swapchain;
 pub mod graphics;
 pub mod sync;
 pub mod formats;
+pub mod error;
 
 pub(crate) mod offset;
+pub(crate) mod spirv;
 
-pub use winit;
\ No newline at end of file
+pub use winit;
+pub use error::Error;
\ No newline at end of file
@@ -6,10 +6,14 @@ use ash;
 use ash::vk;
 use ash::ext::debug_utils;
 
-use crate::on_error_ret;
-use crate::layers::{DebugLayer, Layer};
+use crate::{alloc, on_error_ret};
+use crate::layers::{DebugLayer, DebugState, Layer};
 
 use std::ptr;
+use std::fmt;
+use std::error::Error;
+use std::ffi::CStr;
+use std::sync::Arc;
 use std::marker::PhantomData;
 
 #[derive(Debug)]
@@ -18,8 +22,17 @@ pub struct InstanceType<'a> {
     pub version_minor: u32,
     pub version_patch: u32,
     pub dynamic_load: bool,
+    /// Validation layer, see [`DebugLayer::if_available`] to skip it when layer is not installed
     pub debug_layer: Option<DebugLayer<'a>>,
     pub extensions: &'a [*const i8],
+    /// Additional layers to enable
+    ///
+    /// Every layer must be [available](crate::layers::available_layers)
+    pub layers: &'a [*const i8],
+    /// Host memory allocator for the instance and instance level objects
+    ///
+    /// Also used by [`Device`](crate::dev::Device) if [`DeviceCfg::allocator`](crate::dev::DeviceCfg::allocator) is `None`
+    pub allocator: Option<alloc::Callback>,
 }
 
 impl<'a> Default for InstanceType<'a> {
@@ -31,6 +44,8 @@ impl<'a> Default for InstanceType<'a> {
             dynamic_load: false,
             debug_layer: None,
             extensions: &[],
+            layers: &[],
+            allocator: None,
         }
     }
 }
@@ -40,23 +55,96 @@ pub struct Instance {
     i_instance: ash::Instance,
     i_debug_loader: debug_utils::Instance,
     i_debug_messenger: vk::DebugUtilsMessengerEXT,
+    i_debug_state: Option<Arc<DebugState>>,
+    i_version: u32,
+    i_callback: Option<alloc::Callback>,
 }
 
 #[derive(Debug)]
 pub enum InstanceError {
     LibraryLoad,
+    /// Requested layer is not installed
+    MissingLayer(String),
+    /// Requested extension is provided neither by implementation nor by requested layers
+    MissingExtension(String),
     Instance,
     DebugUtilsCreating,
     Unknown,
 }
 
+impl fmt::Display for InstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let err_msg = match self {
+            InstanceError::LibraryLoad => {
+                "Failed to load Vulkan library"
+            },
+            InstanceError::MissingLayer(layer) => {
+                return write!(f, "{:?}", format!("Layer {} is not available", layer));
+            },
+            InstanceError::MissingExtension(ext) => {
+                return write!(f, "{:?}", format!("Extension {} is not available", ext));
+            },
+            InstanceError::Instance => {
+                "Failed to create instance (vkCreateInstance call failed)"
+            },
+            InstanceError::DebugUtilsCreating => {
+                "Failed to create debug messenger (vkCreateDebugUtilsMessengerEXT call failed)"
+            },
+            InstanceError::Unknown => {
+                "Unknown error"
+            },
+        };
+
+        write!(f, "{:?}", err_msg)
+    }
+}
+
+impl Error for InstanceError {}
+
+/// Load Vulkan entry points without creating instance
+///
+/// Entry may be used to query [layers](crate::layers::available_layers)
+/// and [extensions](crate::extensions::available_extensions) before [`Instance::new`]
+///
+/// `dynamic_load` has the same meaning as [`InstanceType::dynamic_load`]
+pub fn load_entry(dynamic_load: bool) -> Result<ash::Entry, InstanceError> {
+    if dynamic_load {
+        Ok(on_error_ret!(unsafe { ash::Entry::load() }, InstanceError::LibraryLoad))
+    } else {
+        Ok(ash::Entry::linked())
+    }
+}
+
+fn check_availability(entry: &ash::Entry, layers: &[*const i8], extensions: &[*const i8]) -> Result<(), InstanceError> {
+    let to_string = |name: &*const i8| unsafe { CStr::from_ptr(*name) }.to_string_lossy().into_owned();
+
+    let layers: Vec<String> = layers.iter().map(to_string).collect();
+
+    let available_layers = crate::layers::available_layers(entry);
+
+    if let Some(missing) = layers.iter().find(|x| !available_layers.contains(x)) {
+        return Err(InstanceError::MissingLayer(missing.clone()));
+    }
+
+    let mut available_extensions = crate::extensions::available_extensions(entry, None);
+
+    for layer in &layers {
+        available_extensions.extend(crate::extensions::available_extensions(entry, Some(layer)));
+    }
+
+    match extensions.iter().map(to_string).find(|x| !available_extensions.contains(x)) {
+        Some(missing) => Err(InstanceError::MissingExtension(missing)),
+        None => Ok(())
+    }
+}
+
 impl Instance {
+    /// Create instance
+    ///
+    /// Requested layers and extensions are checked before creation
+    /// so missing ones are reported as [`InstanceError::MissingLayer`] and [`InstanceError::MissingExtension`]
     pub fn new(desc: &InstanceType) -> Result<Instance, InstanceError> {
-        let entry: ash::Entry = if desc.dynamic_load {
-            on_error_ret!(unsafe { ash::Entry::load() }, InstanceError::LibraryLoad)
-        } else {
-            ash::Entry::linked()
-        };
+        let entry: ash::Entry = load_entry(desc.dynamic_load)?;
 
         let app_info = vk::ApplicationInfo {
             s_type: vk::StructureType::APPLICATION_INFO,
@@ -74,8 +162,16 @@ impl Instance {
             _marker: PhantomData,
         };
 
-        let layer_names = [DebugLayer::name()];
-        let layers: Vec<*const i8> = layer_names.iter().map(|raw_name| raw_name.as_ptr()).collect();
+        let debug_layer_name = DebugLayer::name();
+
+        let layers: Vec<*const i8> = desc
+            .debug_layer
+            .iter()
+            .map(|_| debug_layer_name.as_ptr())
+            .chain(desc.layers.iter().copied())
+            .collect();
+
+        check_availability(&entry, &layers, desc.extensions)?;
 
         let create_info = vk::InstanceCreateInfo {
             s_type: vk::StructureType::INSTANCE_CREATE_INFO,
@@ -86,12 +182,12 @@ impl Instance {
             },
             flags: vk::InstanceCreateFlags::empty(),
             p_application_info: &app_info,
-            pp_enabled_layer_names: if desc.debug_layer.is_some() {
-                layers.as_ptr()
-            } else {
+            pp_enabled_layer_names: if layers.is_empty() {
                 ptr::null()
+            } else {
+                layers.as_ptr()
             },
-            enabled_layer_count: if desc.debug_layer.is_some() { 1 } else { 0 },
+            enabled_layer_count: layers.len() as u32,
             pp_enabled_extension_names: if desc.extensions.is_empty() {
                 ptr::null()
             } else {
@@ -106,14 +202,17 @@ impl Instance {
         };
 
         let instance: ash::Instance = on_error_ret!(
-            unsafe { entry.create_instance(&create_info, None) },
+            unsafe { entry.create_instance(&create_info, desc.allocator.as_ref()) },
             InstanceError::Instance
         );
 
         let dbg_loader = debug_utils::Instance::new(&entry, &instance);
 
         let dbg_messenger: vk::DebugUtilsMessengerEXT = if let Some(layer) = &desc.debug_layer {
-            on_error_ret!(unsafe { dbg_loader.create_debug_utils_messenger(layer.as_raw(), None) }, InstanceError::DebugUtilsCreating)
+            on_error_ret!(
+                unsafe { dbg_loader.create_debug_utils_messenger(layer.as_raw(), desc.allocator.as_ref()) },
+                InstanceError::DebugUtilsCreating
+            )
         }
         else {
             vk::DebugUtilsMessengerEXT::null()
@@ -124,9 +223,33 @@ impl Instance {
 			i_instance: instance,
 			i_debug_loader: dbg_loader,
 			i_debug_messenger: dbg_messenger,
+			i_debug_state: desc.debug_layer.as_ref().map(|layer| layer.state().clone()),
+			i_version: app_info.api_version,
+			i_callback: desc.allocator,
 		})
     }
 
+    /// Return Vulkan API version requested at instance creation
+    ///
+    /// Use [`vk::api_version_major`](ash::vk::api_version_major) and related functions to decode it
+    pub fn version(&self) -> u32 {
+        self.i_version
+    }
+
+    /// Return how many errors debug layer has reported so far
+    ///
+    /// Always 0 if instance was created without [`debug_layer`](InstanceType::debug_layer)
+    pub fn error_count(&self) -> usize {
+        self.i_debug_state.as_ref().map_or(0, |state| state.error_count())
+    }
+
+    /// Return how many warnings debug layer has reported so far
+    ///
+    /// Always 0 if instance was created without [`debug_layer`](InstanceType::debug_layer)
+    pub fn warning_count(&self) -> usize {
+        self.i_debug_state.as_ref().map_or(0, |state| state.warning_count())
+    }
+
     #[doc(hidden)]
     pub fn instance(&self) -> &ash::Instance {
         &self.i_instance
@@ -136,14 +259,19 @@ impl Instance {
     pub fn entry(&self) -> &ash::Entry {
         &self.i_entry
     }
+
+    #[doc(hidden)]
+    pub fn allocator(&self) -> Option<&alloc::Callback> {
+        self.i_callback.as_ref()
+    }
 }
 
 impl Drop for Instance {
     fn drop(&mut self) {
 		if self.i_debug_messenger != vk::DebugUtilsMessengerEXT::null() {
-			unsafe { self.i_debug_loader.destroy_debug_utils_messenger(self.i_debug_messenger, None); }
+			unsafe { self.i_debug_loader.destroy_debug_utils_messenger(self.i_debug_messenger, self.i_callback.as_ref()); }
 		}
 
-		unsafe { self.i_instance.destroy_instance(None); }
+		unsafe { self.i_instance.destroy_instance(self.i_callback.as_ref()); }
     }
 }
@@ -43,8 +43,27 @@ macro_rules! on_option_ret {
 ///     Ok(result)
 /// }
 /// ```
+///
+/// Error value may be bound with `|name|` before the action
+/// ```
+/// use libvktypes::on_error;
+///
+/// fn foo() -> Result<u32, String> {
+///     let x: Result<u32, &'static str> = Err("Foo error");
+///
+///     let result = on_error!(x, |err| return Err(format!("Failed with {}", err)));
+///
+///     Ok(result)
+/// }
+/// ```
 #[macro_export]
 macro_rules! on_error {
+    ( $e:expr, |$err:ident| $err_exp:expr ) => {
+        match $e {
+            Ok(x) => x,
+            Err($err) => { $err_exp },
+        }
+    };
     ( $e:expr, $err_exp:expr ) => {
         match $e {
             Ok(x) => x,
@@ -0,0 +1,267 @@
+//! Sub-allocation of device memory
+//!
+//! Drivers limit total number of
+//! [allocations](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceLimits.html)
+//! (`maxMemoryAllocationCount`, often 4096)
+//!
+//! [`Allocator`] requests large blocks of memory and places many
+//! [`Memory`](crate::memory::Memory) and [`ImageMemory`](crate::memory::ImageMemory) objects inside them
+use ash::vk;
+
+use core::ffi::c_void;
+use std::sync::{Mutex, MutexGuard};
+use std::sync::Arc;
+use std::{fmt, ptr};
+use std::marker::PhantomData;
+
+use crate::{dev, hw, memory, offset};
+use crate::on_error;
+
+/// Default size of the single block
+pub const DEFAULT_BLOCK_SIZE: u64 = 64*1024*1024;
+
+struct Block {
+    i_memory: vk::DeviceMemory,
+    i_type: u32,
+    i_mapped: *mut c_void,
+    /// Sorted by offset, neighbour ranges are always merged
+    i_free: Vec<(u64, u64)>
+}
+
+// Mapped pointer is owned by the block and unmapped only on allocator drop
+unsafe impl Send for Block {}
+
+impl Block {
+    /// Find the first free range which fits `size` bytes aligned to `alignment`
+    fn take(&mut self, size: u64, alignment: u64) -> Option<u64> {
+        let (i, begin) = self.i_free.iter().enumerate().find_map(|(i, &(offset, len))| {
+            let begin = offset + offset::padding_bytes(offset, alignment);
+
+            if begin + size <= offset + len {
+                Some((i, begin))
+            } else {
+                None
+            }
+        })?;
+
+        let (offset, len) = self.i_free.remove(i);
+
+        // Tail first so head is inserted before it
+        if begin + size < offset + len {
+            self.i_free.insert(i, (begin + size, offset + len - begin - size));
+        }
+
+        if begin > offset {
+            self.i_free.insert(i, (offset, begin - offset));
+        }
+
+        Some(begin)
+    }
+
+    fn give(&mut self, offset: u64, size: u64) {
+        let i = self.i_free.partition_point(|&(begin, _)| begin < offset);
+
+        self.i_free.insert(i, (offset, size));
+
+        if i + 1 < self.i_free.len() && offset + size == self.i_free[i + 1].0 {
+            self.i_free[i].1 += self.i_free[i + 1].1;
+            self.i_free.remove(i + 1);
+        }
+
+        if i > 0 && self.i_free[i - 1].0 + self.i_free[i - 1].1 == offset {
+            self.i_free[i - 1].1 += self.i_free[i].1;
+            self.i_free.remove(i);
+        }
+    }
+}
+
+pub(crate) struct AllocatorState {
+    i_core: Arc<dev::Core>,
+    i_block_size: u64,
+    i_blocks: Mutex<Vec<Block>>
+}
+
+impl AllocatorState {
+    pub(crate) fn free(&self, block: usize, offset: u64, size: u64) {
+        self.blocks()[block].give(offset, size);
+    }
+
+    fn blocks(&self) -> MutexGuard<'_, Vec<Block>> {
+        self.i_blocks.lock().expect("Allocator lock is poisoned")
+    }
+}
+
+impl Drop for AllocatorState {
+    fn drop(&mut self) {
+        for block in self.blocks().iter() {
+            unsafe {
+                if !block.i_mapped.is_null() {
+                    self.i_core.device().unmap_memory(block.i_memory);
+                }
+
+                self.i_core.device().free_memory(block.i_memory, self.i_core.allocator());
+            }
+        }
+    }
+}
+
+/// Part of the block which is owned by [`Region`](memory::Region)
+pub(crate) struct BlockRange {
+    pub allocator: Arc<AllocatorState>,
+    pub block: usize,
+    pub size: u64,
+    pub mapped: *mut c_void
+}
+
+// Pointer refers to the persistently mapped block which outlives the range
+unsafe impl Send for BlockRange {}
+
+unsafe impl Sync for BlockRange {}
+
+/// Memory sub-allocator
+///
+/// Memory for each memory type is requested by blocks of [`block_size`](Allocator::block_size)
+///
+/// Requests which are larger than half of the block get dedicated allocation
+///
+/// Memory is returned to the allocator when [`Memory`](crate::memory::Memory)
+/// or [`ImageMemory`](crate::memory::ImageMemory) is dropped and may be reused.
+/// Blocks are freed only with the allocator (and all memory allocated from it)
+///
+/// Every range is aligned to `bufferImageGranularity` so buffers and images
+/// may be placed in the same block
+///
+/// HOST_VISIBLE blocks are mapped for the whole lifetime so
+/// [`map_memory`](crate::memory::Memory::map_memory) does not call `vkMapMemory`
+///
+/// See [`Memory::allocate_from`](crate::memory::Memory::allocate_from)
+/// and [`ImageMemory::allocate_from`](crate::memory::ImageMemory::allocate_from)
+#[derive(Clone)]
+pub struct Allocator(Arc<AllocatorState>);
+
+impl Allocator {
+    /// Create allocator which requests memory by `block_size` bytes
+    ///
+    /// See [`DEFAULT_BLOCK_SIZE`]
+    pub fn new(device: &dev::Device, block_size: u64) -> Allocator {
+        Allocator(
+            Arc::new(AllocatorState {
+                i_core: device.core().clone(),
+                i_block_size: block_size,
+                i_blocks: Mutex::new(Vec::new())
+            })
+        )
+    }
+
+    /// Return size of the single block in bytes
+    pub fn block_size(&self) -> u64 {
+        self.0.i_block_size
+    }
+
+    /// Return how many blocks were allocated
+    ///
+    /// Dedicated allocations are not counted
+    pub fn block_count(&self) -> usize {
+        self.0.blocks().len()
+    }
+
+    pub(crate) fn allocate(
+        &self,
+        device: &dev::Device,
+        size: u64,
+        alignment: u64,
+        desc: &hw::MemoryDescription
+    ) -> Result<memory::Region, memory::MemoryError> {
+        if size > self.0.i_block_size/2 {
+            return memory::Region::allocate(device, size, desc);
+        }
+
+        // Buffers and images share blocks so every range starts and ends
+        // on its own bufferImageGranularity page
+        let alignment = alignment
+            .max(device.hw().memory_alignment())
+            .max(device.hw().buffer_image_granularity());
+
+        // Keep the end of the range aligned so flushes never cross into the neighbour
+        let size = offset::full_size(size, alignment);
+
+        let mut blocks = self.0.blocks();
+
+        let found = blocks
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, block)| block.i_type == desc.index())
+            .find_map(|(i, block)| block.take(size, alignment).map(|offset| (i, offset)));
+
+        let (index, offset) = match found {
+            Some(val) => val,
+            None => {
+                let mut block = self.create_block(device, desc)?;
+
+                let offset = block.take(size, alignment).ok_or(memory::MemoryError::NoSuitableMemory)?;
+
+                blocks.push(block);
+
+                (blocks.len() - 1, offset)
+            }
+        };
+
+        Ok(memory::Region::suballocated(
+            device,
+            desc,
+            blocks[index].i_memory,
+            offset,
+            BlockRange {
+                allocator: self.0.clone(),
+                block: index,
+                size,
+                mapped: blocks[index].i_mapped
+            }
+        ))
+    }
+
+    fn create_block(&self, device: &dev::Device, desc: &hw::MemoryDescription) -> Result<Block, memory::MemoryError> {
+        let memory_info = vk::MemoryAllocateInfo {
+            s_type: vk::StructureType::MEMORY_ALLOCATE_INFO,
+            p_next: ptr::null(),
+            allocation_size: self.0.i_block_size,
+            memory_type_index: desc.index(),
+            _marker: PhantomData,
+        };
+
+        let block_memory = on_error!(
+            unsafe { device.device().allocate_memory(&memory_info, device.allocator()) },
+            |err| return Err(memory::memory_error(device.core(), err, memory::MemoryError::DeviceMemory))
+        );
+
+        let mapped = if desc.is_host_visible() {
+            match unsafe {
+                device.device().map_memory(block_memory, 0, vk::WHOLE_SIZE, vk::MemoryMapFlags::empty())
+            } {
+                Ok(val) => val,
+                Err(err) => {
+                    unsafe { device.device().free_memory(block_memory, device.allocator()) };
+                    return Err(memory::memory_error(device.core(), err, memory::MemoryError::MapAccess));
+                }
+            }
+        } else {
+            ptr::null_mut()
+        };
+
+        Ok(Block {
+            i_memory: block_memory,
+            i_type: desc.index(),
+            i_mapped: mapped,
+            i_free: vec![(0, self.0.i_block_size)]
+        })
+    }
+}
+
+impl fmt::Debug for Allocator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Allocator")
+        .field("i_block_size", &self.0.i_block_size)
+        .field("block_count", &self.block_count())
+        .finish()
+    }
+}
@@ -0,0 +1,98 @@
+//! Typed view over the buffer which is used by texel buffer descriptors
+//!
+//! Unlike [`View`](memory::View) [`BufferView`] is a Vulkan object
+//! which specifies how shader should interpret buffer content
+use ash::vk;
+
+use crate::on_error;
+use crate::{dev, memory};
+
+use std::sync::Arc;
+use std::ptr;
+use std::marker::PhantomData;
+
+/// Configuration of [`BufferView`]
+///
+/// Buffer must be created with
+/// [`UNIFORM_TEXEL_BUFFER`](memory::UNIFORM_TEXEL_BUFFER) or [`STORAGE_TEXEL_BUFFER`](memory::STORAGE_TEXEL_BUFFER) usage
+pub struct BufferViewCfg<'a> {
+    pub view: memory::View<'a>,
+    /// Format of the single texel
+    pub format: memory::ImageFormat,
+    /// Offset in bytes from the start of the view
+    pub offset: u64,
+    /// Size in bytes, `vk::WHOLE_SIZE` means till the end of the view
+    pub range: u64,
+}
+
+impl<'a> BufferViewCfg<'a> {
+    /// Cover the whole buffer with `format`
+    pub fn new(view: memory::View<'a>, format: memory::ImageFormat) -> BufferViewCfg<'a> {
+        BufferViewCfg {
+            view,
+            format,
+            offset: 0,
+            range: vk::WHOLE_SIZE,
+        }
+    }
+}
+
+/// Represents `vk::BufferView`
+///
+/// Underlying [`Memory`](memory::Memory) is kept alive until `BufferView` is dropped
+#[derive(Debug)]
+pub struct BufferView {
+    i_core: Arc<dev::Core>,
+    i_buffer_view: vk::BufferView,
+    i_format: memory::ImageFormat,
+    /// Buffer must outlive view
+    #[allow(dead_code)]
+    i_memory: dev::Keepalive,
+}
+
+impl BufferView {
+    pub fn new(device: &dev::Device, cfg: &BufferViewCfg) -> Result<BufferView, memory::MemoryError> {
+        let (offset, range) = cfg.view.buffer_range(cfg.offset, cfg.range);
+
+        let create_info = vk::BufferViewCreateInfo {
+            s_type: vk::StructureType::BUFFER_VIEW_CREATE_INFO,
+            p_next: ptr::null(),
+            flags: vk::BufferViewCreateFlags::empty(),
+            buffer: cfg.view.buffer(),
+            format: cfg.format,
+            offset,
+            range,
+            _marker: PhantomData,
+        };
+
+        let buffer_view = on_error!(
+            unsafe { device.device().create_buffer_view(&create_info, device.allocator()) },
+            |err| return Err(memory::MemoryError::BufferView(err))
+        );
+
+        Ok(BufferView {
+            i_core: device.core().clone(),
+            i_buffer_view: buffer_view,
+            i_format: cfg.format,
+            i_memory: cfg.view.keepalive(),
+        })
+    }
+
+    /// Return format of the texels
+    pub fn format(&self) -> memory::ImageFormat {
+        self.i_format
+    }
+
+    #[doc(hidden)]
+    pub fn buffer_view(&self) -> vk::BufferView {
+        self.i_buffer_view
+    }
+}
+
+impl Drop for BufferView {
+    fn drop(&mut self) {
+        unsafe {
+            self.i_core.device().destroy_buffer_view(self.i_buffer_view, self.i_core.allocator());
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Image data in host memory
+use crate::{memory, formats};
+
+/// Tightly packed texels of the 2D image
+///
+/// Rows follow each other without padding, texel size is [`formats::block_size`] of `format`
+///
+/// See [`Swapchain::screenshot`](crate::swapchain::Swapchain::screenshot)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuImage {
+    pub width: u32,
+    pub height: u32,
+    pub format: memory::ImageFormat,
+    pub data: Vec<u8>,
+}
+
+impl CpuImage {
+    /// Return bytes of the texel at (`x`, `y`)
+    ///
+    /// Returns `None` if texel is outside of the image
+    pub fn pixel(&self, x: u32, y: u32) -> Option<&[u8]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let texel_size = formats::block_size(self.format) as usize;
+        let offset = ((y as usize)*(self.width as usize) + (x as usize))*texel_size;
+
+        self.data.get(offset..offset + texel_size)
+    }
+
+    /// Reorder `B8G8R8A8` texels into `R8G8B8A8`
+    ///
+    /// Images of other formats are not changed
+    pub fn bgra_to_rgba(&mut self) {
+        let format = match self.format {
+            memory::ImageFormat::B8G8R8A8_UNORM => memory::ImageFormat::R8G8B8A8_UNORM,
+            memory::ImageFormat::B8G8R8A8_SRGB => memory::ImageFormat::R8G8B8A8_SRGB,
+            _ => return
+        };
+
+        for texel in self.data.chunks_exact_mut(4) {
+            texel.swap(0, 2);
+        }
+
+        self.format = format;
+    }
+}
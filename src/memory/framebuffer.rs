@@ -5,7 +5,7 @@
 //! Detailed info you can found [here](https://stackoverflow.com/questions/39557141/what-is-the-difference-between-framebuffer-and-image-in-vulkan)
 use ash::vk;
 
-use crate::on_error_ret;
+use crate::on_error;
 use crate::{dev, graphics, memory};
 
 use std::error::Error;
@@ -16,16 +16,56 @@ use std::marker::PhantomData;
 
 #[derive(Debug)]
 pub enum FramebufferError {
-    Framebuffer,
+    /// Failed to
+    /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateFramebuffer.html) framebuffer
+    Framebuffer(vk::Result),
+    /// Number of images does not match [`attachment_count`](graphics::RenderPass::attachment_count)
+    AttachmentCount {
+        expected: usize,
+        actual: usize
+    },
+    /// Extent of the attachment with the given index does not match extent of the framebuffer
+    Extent(usize),
+    /// Format of the attachment does not match [`attachment_formats`](graphics::RenderPass::attachment_formats)
+    Incompatible {
+        index: usize,
+        expected: memory::ImageFormat,
+        got: memory::ImageFormat
+    },
+    /// Image was created without usage required by render pass
+    /// (`COLOR_ATTACHMENT` or `DEPTH_STENCIL_ATTACHMENT`)
+    Usage {
+        index: usize,
+        required: memory::UsageFlags
+    },
 }
 
 impl fmt::Display for FramebufferError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "vkCreateFramebuffer call failed")
+        match self {
+            FramebufferError::Framebuffer(_) => write!(f, "vkCreateFramebuffer call failed"),
+            FramebufferError::AttachmentCount { expected, actual } => {
+                write!(f, "Render pass expects {} attachments but {} were provided", expected, actual)
+            },
+            FramebufferError::Extent(i) => write!(f, "Extent of attachment {} does not match framebuffer extent", i),
+            FramebufferError::Incompatible { index, expected, got } => {
+                write!(f, "Attachment {} has format {:?} but render pass expects {:?}", index, got, expected)
+            },
+            FramebufferError::Usage { index, required } => {
+                write!(f, "Attachment {} must be created with {:?} usage", index, required)
+            },
+        }
     }
 }
 
-impl Error for FramebufferError {}
+impl Error for FramebufferError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FramebufferError::Framebuffer(result) => Some(result),
+            _ => None
+        }
+    }
+}
 
 pub struct FramebufferCfg<'a, 'b : 'a> {
     pub images: &'a [memory::ImageView<'b>],
@@ -33,15 +73,37 @@ pub struct FramebufferCfg<'a, 'b : 'a> {
     pub render_pass: &'a graphics::RenderPass
 }
 
-pub struct Framebuffer {
+struct CoreFramebuffer {
     i_core: Arc<dev::Core>,
     i_frame: vk::Framebuffer,
-    i_extent: vk::Extent2D
+    i_extent: vk::Extent2D,
+    /// Attachments must outlive framebuffer
+    #[allow(dead_code)]
+    i_images: Vec<dev::Keepalive>
+}
+
+impl Drop for CoreFramebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.i_core.device().destroy_framebuffer(self.i_frame, self.i_core.allocator());
+        }
+
+        self.i_core.destroyed(dev::ObjectType::Framebuffer, 1);
+    }
 }
 
+pub struct Framebuffer(Arc<CoreFramebuffer>);
+
 impl Framebuffer {
     /// Create new framebuffer from existing [image](crate::memory::ImageMemory)
+    ///
+    /// Images are checked against the render pass:
+    /// their number must be equal to [`attachment_count`](graphics::RenderPass::attachment_count),
+    /// formats must match [`attachment_formats`](graphics::RenderPass::attachment_formats)
+    /// and color (depth) attachments must have `COLOR_ATTACHMENT` (`DEPTH_STENCIL_ATTACHMENT`) usage
     pub fn new(device: &dev::Device, cfg: &FramebufferCfg) -> Result<Framebuffer, FramebufferError> {
+        check_attachments(cfg)?;
+
         let img_views: Vec<vk::ImageView> = cfg.images.iter().map(|img| img.image_view()).collect();
 
         let create_info = vk::FramebufferCreateInfo {
@@ -57,33 +119,101 @@ impl Framebuffer {
             _marker: PhantomData,
         };
 
-        let framebuffer = on_error_ret!(
+        let framebuffer = on_error!(
             unsafe { device.device().create_framebuffer(&create_info, device.allocator()) },
-            FramebufferError::Framebuffer
+            |err| return Err(FramebufferError::Framebuffer(err))
         );
 
-        Ok(Framebuffer {
-            i_core: device.core().clone(),
-            i_frame: framebuffer,
-            i_extent: cfg.extent,
+        device.core().created(dev::ObjectType::Framebuffer, 1);
+
+        Ok(Framebuffer(
+            Arc::new(CoreFramebuffer {
+                i_core: device.core().clone(),
+                i_frame: framebuffer,
+                i_extent: cfg.extent,
+                i_images: cfg.images.iter().map(|img| img.keepalive()).collect(),
+            })
+        ))
+    }
+
+    /// Create framebuffer for every swapchain image
+    ///
+    /// Each framebuffer has the swapchain image as attachment `0`
+    /// followed by `extra_attachments` (e.g. shared depth buffer)
+    ///
+    /// Attachments are checked the same way as in [`new`](Framebuffer::new),
+    /// additionally [`FramebufferError::Extent`] is returned if any image has extent other than `extent`
+    pub fn for_swapchain(
+        device: &dev::Device,
+        render_pass: &graphics::RenderPass,
+        swapchain_images: &[memory::ImageMemory],
+        extra_attachments: &[memory::ImageView],
+        extent: memory::Extent2D
+    ) -> Result<Vec<Framebuffer>, FramebufferError> {
+        let fits = |view: &memory::ImageView| {
+            let img_extent = view.extent();
+
+            img_extent.width == extent.width && img_extent.height == extent.height
+        };
+
+        if let Some(i) = extra_attachments.iter().position(|view| !fits(view)) {
+            return Err(FramebufferError::Extent(i + 1));
+        }
+
+        if !swapchain_images.iter().all(|img| fits(&img.view(0))) {
+            return Err(FramebufferError::Extent(0));
+        }
+
+        swapchain_images
+        .iter()
+        .map(|img| {
+            let mut images = vec![img.view(0)];
+            images.extend_from_slice(extra_attachments);
+
+            let cfg = FramebufferCfg {
+                images: &images,
+                extent,
+                render_pass
+            };
+
+            Framebuffer::new(device, &cfg)
         })
+        .collect()
     }
 
     #[doc(hidden)]
     pub fn framebuffer(&self) -> vk::Framebuffer {
-        self.i_frame
+        self.0.i_frame
     }
 
     #[doc(hidden)]
     pub fn extent(&self) -> vk::Extent2D {
-        self.i_extent
+        self.0.i_extent
+    }
+
+    pub(crate) fn keepalive(&self) -> dev::Keepalive {
+        self.0.clone()
     }
 }
 
-impl Drop for Framebuffer {
-    fn drop(&mut self) {
-        unsafe {
-            self.i_core.device().destroy_framebuffer(self.i_frame, self.i_core.allocator());
+fn check_attachments(cfg: &FramebufferCfg) -> Result<(), FramebufferError> {
+    let expected = cfg.render_pass.attachment_count();
+
+    if cfg.images.len() != expected {
+        return Err(FramebufferError::AttachmentCount { expected, actual: cfg.images.len() });
+    }
+
+    for (index, (view, &format)) in cfg.images.iter().zip(cfg.render_pass.attachment_formats()).enumerate() {
+        if view.format() != format {
+            return Err(FramebufferError::Incompatible { index, expected: format, got: view.format() });
+        }
+
+        let required = cfg.render_pass.attachment_usage(index);
+
+        if !view.usage().contains(required) {
+            return Err(FramebufferError::Usage { index, required });
         }
     }
-}
\ No newline at end of file
+
+    Ok(())
+}
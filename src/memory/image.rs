@@ -2,12 +2,13 @@
 
 use ash::vk;
 
-use crate::{on_error, on_error_ret};
+use crate::on_error;
 use crate::{dev, hw, memory};
 
 use std::error::Error;
 use std::{fmt, ptr};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicI32, Ordering};
 use std::marker::PhantomData;
 
 /// Represents image usage flags
@@ -70,6 +71,13 @@ pub type Extent2D = vk::Extent2D;
 /// ```
 pub type Extent3D = vk::Extent3D;
 
+/// Structure specifying a three-dimensional offset
+///
+#[doc = "Ash documentation: <https://docs.rs/ash/latest/ash/vk/struct.Offset3D.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkOffset3D.html>"]
+pub type Offset3D = vk::Offset3D;
+
 /// Image usage flags
 ///
 #[doc = "Values: <https://docs.rs/ash/latest/ash/vk/struct.ImageUsageFlags.html>"]
@@ -98,6 +106,15 @@ pub type CompositeAlphaFlags = vk::CompositeAlphaFlagsKHR;
 #[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkImageTiling.html>"]
 pub type Tiling = vk::ImageTiling;
 
+/// Number of samples per texel
+///
+/// Use `TYPE_1` for regular (not multisampled) images
+///
+#[doc = "Values: <https://docs.rs/ash/latest/ash/vk/struct.SampleCountFlags.html>"]
+///
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkSampleCountFlagBits.html>"]
+pub type SampleCount = vk::SampleCountFlags;
+
 /// Errors during [`ImageMemory`] initialization and access
 #[derive(Debug)]
 pub enum ImageError {
@@ -130,6 +147,36 @@ impl fmt::Display for ImageError {
 
 impl Error for ImageError {}
 
+/// Type of the image and how it will be viewed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageKind {
+    /// Single two-dimensional image
+    D2,
+    /// Array of two-dimensional images
+    D2Array,
+    /// Cubemap
+    ///
+    /// Image has 6 layers (faces) in order `+X, -X, +Y, -Y, +Z, -Z`
+    Cube
+}
+
+impl ImageKind {
+    fn view_type(&self) -> vk::ImageViewType {
+        match self {
+            ImageKind::D2 => vk::ImageViewType::TYPE_2D,
+            ImageKind::D2Array => vk::ImageViewType::TYPE_2D_ARRAY,
+            ImageKind::Cube => vk::ImageViewType::CUBE,
+        }
+    }
+
+    fn flags(&self) -> vk::ImageCreateFlags {
+        match self {
+            ImageKind::Cube => vk::ImageCreateFlags::CUBE_COMPATIBLE,
+            _ => vk::ImageCreateFlags::empty(),
+        }
+    }
+}
+
 pub struct ImageCfg<'a> {
     /// What queue families will have access to the image
     pub queue_families: &'a [u32],
@@ -141,6 +188,24 @@ pub struct ImageCfg<'a> {
     pub layout: memory::ImageLayout,
     pub aspect: ImageAspect,
     pub tiling: Tiling,
+    /// Number of samples per texel
+    ///
+    /// Multisampled images must have single mip level
+    pub samples: SampleCount,
+    /// Number of mipmap levels
+    ///
+    /// Must be at least 1
+    ///
+    /// See [`max_mip_levels`] for full mip chain
+    pub mip_levels: u32,
+    /// Type of the image and its view
+    pub kind: ImageKind,
+    /// Number of layers in the image
+    ///
+    /// For [`ImageKind::D2`] must be 1
+    ///
+    /// For [`ImageKind::Cube`] must be 6
+    pub array_layers: u32,
     /// How many of the image buffers we want to allocate one by one
     ///
     /// For example
@@ -150,17 +215,49 @@ pub struct ImageCfg<'a> {
     pub count: usize
 }
 
+/// Return number of mip levels required for the full mip chain of the image with `extent`
+///
+/// # Example
+///
+/// ```
+/// use libvktypes::memory;
+///
+/// let extent = memory::Extent3D { width: 1024, height: 512, depth: 1 };
+///
+/// assert_eq!(memory::max_mip_levels(extent), 11);
+/// ```
+pub fn max_mip_levels(extent: Extent3D) -> u32 {
+    let max_side = std::cmp::max(std::cmp::max(extent.width, extent.height), extent.depth);
+
+    u32::BITS - max_side.max(1).leading_zeros()
+}
+
 pub struct ImagesAllocationInfo<'a, 'b : 'a> {
     pub properties: hw::MemoryProperty,
+    /// Memory types for which `filter` returns `false` are not used
     pub filter: &'a dyn Fn(&hw::MemoryDescription) -> bool,
     pub image_cfgs: &'a [ImageCfg<'b>]
 }
 
+/// Tracked layout of the single image
+///
+/// Shared between [`ImageMemory`] and descriptors which refer to the image
+#[derive(Debug, Clone)]
+pub(crate) struct LayoutCell(Arc<AtomicI32>);
+
+impl LayoutCell {
+    pub(crate) fn get(&self) -> memory::ImageLayout {
+        memory::ImageLayout::from_raw(self.0.load(Ordering::Relaxed))
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ImageInfo {
     pub extent: Extent3D,
     pub subresource: vk::ImageSubresourceRange,
     pub format: ImageFormat,
+    pub view_type: vk::ImageViewType,
+    pub usage: UsageFlags,
 }
 
 impl fmt::Display for ImageInfo {
@@ -172,14 +269,18 @@ impl fmt::Display for ImageInfo {
             level count: {:?}\n\
             base array layer: {:?}\n\
             layer count: {:?}\n\
-            format: {:?}\n",
+            format: {:?}\n\
+            view type: {:?}\n\
+            usage: {:?}\n",
             self.extent,
             self.subresource.aspect_mask,
             self.subresource.base_mip_level,
             self.subresource.level_count,
             self.subresource.base_array_layer,
             self.subresource.layer_count,
-            self.format
+            self.format,
+            self.view_type,
+            self.usage
         ).expect("Failed to print ImageInfo");
 
         Ok(())
@@ -210,21 +311,90 @@ impl fmt::Display for ImageInfo {
 ///
 /// It is important for [`map_memory`](memory::ImageView::map_memory) function as you have to take into account
 /// returned buffer may be larger that you are expecting
-pub struct ImageMemory {
+pub struct ImageMemory(Arc<CoreImageMemory>);
+
+struct CoreImageMemory {
     i_core: Arc<dev::Core>,
     i_images: Vec<vk::Image>,
     i_image_views: Vec<vk::ImageView>,
     i_subregions: Vec<memory::Subregion>,
     i_info: Vec<ImageInfo>,
-    i_memory: memory::Region
+    i_layouts: Vec<Arc<AtomicI32>>,
+    i_memory: memory::Region,
+    /// Owner of preallocated images (e.g. swapchain), released after views are destroyed
+    #[allow(dead_code)]
+    i_owner: Option<dev::Keepalive>
 }
 
 impl ImageMemory {
+    /// Allocate images described by `cfg`
+    ///
+    /// Support of every format, usage and extent is checked before creation
+    /// (see [`UnsupportedImage`](memory::MemoryError::UnsupportedImage)
+    /// and [`ExtentTooLarge`](memory::MemoryError::ExtentTooLarge)),
+    /// if any image can not be created nothing is leaked
     pub fn allocate(device: &dev::Device, cfg: &ImagesAllocationInfo) -> Result<ImageMemory, memory::MemoryError> {
+        ImageMemory::allocate_impl(device, cfg, None)
+    }
+
+    /// Same as [`allocate`](Self::allocate) but memory is taken from the `allocator` block
+    ///
+    /// Memory is returned to the `allocator` on drop
+    pub fn allocate_from(
+        device: &dev::Device,
+        cfg: &ImagesAllocationInfo,
+        allocator: &memory::Allocator
+    ) -> Result<ImageMemory, memory::MemoryError> {
+        ImageMemory::allocate_impl(device, cfg, Some(allocator))
+    }
+
+    /// Allocate single color image which may be used as render target without swapchain
+    ///
+    /// Image has `COLOR_ATTACHMENT` and `TRANSFER_SRC` usage so it may be used in
+    /// [`Framebuffer`](memory::Framebuffer) and then [copied](crate::cmd::Buffer::copy_image_to_buffer)
+    /// into host visible memory
+    ///
+    /// See [`RenderPass::offscreen`](crate::graphics::RenderPass::offscreen)
+    pub fn offscreen(
+        device: &dev::Device,
+        format: memory::ImageFormat,
+        extent: memory::Extent2D
+    ) -> Result<ImageMemory, memory::MemoryError> {
+        let image_cfg = ImageCfg {
+            queue_families: &[],
+            simultaneous_access: false,
+            format,
+            extent: memory::Extent3D { width: extent.width, height: extent.height, depth: 1 },
+            usage: memory::ImageUsageFlags::COLOR_ATTACHMENT | memory::ImageUsageFlags::TRANSFER_SRC,
+            layout: memory::ImageLayout::UNDEFINED,
+            aspect: memory::ImageAspect::COLOR,
+            tiling: memory::Tiling::OPTIMAL,
+            samples: memory::SampleCount::TYPE_1,
+            mip_levels: 1,
+            kind: ImageKind::D2,
+            array_layers: 1,
+            count: 1
+        };
+
+        let alloc_info = ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &[image_cfg]
+        };
+
+        ImageMemory::allocate(device, &alloc_info)
+    }
+
+    fn allocate_impl(
+        device: &dev::Device,
+        cfg: &ImagesAllocationInfo,
+        allocator: Option<&memory::Allocator>
+    ) -> Result<ImageMemory, memory::MemoryError> {
         let mut images: Vec<vk::Image> = Vec::new();
         let mut memory_requirements: Vec<vk::MemoryRequirements> = Vec::new();
 
         let mut info: Vec<ImageInfo> = Vec::new();
+        let mut layouts: Vec<Arc<AtomicI32>> = Vec::new();
 
         for cfg in cfg.image_cfgs {
             let sharing_mode = if cfg.simultaneous_access {
@@ -236,13 +406,13 @@ impl ImageMemory {
             let image_info = vk::ImageCreateInfo {
                 s_type: vk::StructureType::IMAGE_CREATE_INFO,
                 p_next: ptr::null(),
-                flags: vk::ImageCreateFlags::empty(),
+                flags: cfg.kind.flags(),
                 image_type: vk::ImageType::TYPE_2D,
                 format: cfg.format,
                 extent: cfg.extent,
-                mip_levels: 1,
-                array_layers: 1,
-                samples: vk::SampleCountFlags::TYPE_1,
+                mip_levels: cfg.mip_levels,
+                array_layers: cfg.array_layers,
+                samples: cfg.samples,
                 tiling: cfg.tiling,
                 usage: cfg.usage,
                 sharing_mode: sharing_mode,
@@ -252,31 +422,42 @@ impl ImageMemory {
                 _marker: PhantomData,
             };
 
+            // Unsupported format or usage must not reach vkCreateImage
+            if let Err(err) = check_format_support(device, &image_info) {
+                free_images(device.core(), &images);
+                return Err(err);
+            }
+
             for _ in 0..cfg.count {
                 let subres = vk::ImageSubresourceRange {
                     aspect_mask: cfg.aspect,
                     base_mip_level: 0,
-                    level_count: 1,
+                    level_count: cfg.mip_levels,
                     base_array_layer: 0,
-                    layer_count: 1,
+                    layer_count: cfg.array_layers,
                 };
 
                 let img_info = ImageInfo {
                     extent: cfg.extent,
                     subresource: subres,
-                    format: cfg.format
+                    format: cfg.format,
+                    view_type: cfg.kind.view_type(),
+                    usage: cfg.usage
                 };
 
                 info.push(img_info);
+                layouts.push(Arc::new(AtomicI32::new(cfg.layout.as_raw())));
 
                 let img = on_error!(
                     unsafe { device.device().create_image(&image_info, device.allocator()) },
-                    {
+                    |err| {
                         free_images(device.core(), &images);
-                        return Err(memory::MemoryError::Image)
+                        return Err(memory::MemoryError::Image(err))
                     }
                 );
 
+                device.core().created(dev::ObjectType::Image, 1);
+
                 images.push(img);
 
                 let requirements = unsafe {
@@ -291,15 +472,27 @@ impl ImageMemory {
 
         let regions_info = memory::Region::calculate_subregions(device, &memory_requirements);
 
-        let mem_desc = match memory::Region::find_memory(device.hw(), regions_info.memory_bits, cfg.properties) {
-            Some(val) => val,
-            None => {
+        let mem_desc = match memory::Region::select_memory(
+            device.hw(),
+            &memory_requirements,
+            regions_info.memory_bits,
+            cfg.properties,
+            cfg.filter,
+            regions_info.total_size
+        ) {
+            Ok(val) => val,
+            Err(err) => {
                 free_images(device.core(), &images);
-                return Err(memory::MemoryError::NoSuitableMemory)
+                return Err(err)
             },
         };
 
-        let img_memory = match memory::Region::allocate(device, regions_info.total_size, mem_desc) {
+        let allocated = match allocator {
+            Some(allocator) => allocator.allocate(device, regions_info.total_size, regions_info.alignment, mem_desc),
+            None => memory::Region::allocate(device, regions_info.total_size, mem_desc)
+        };
+
+        let img_memory = match allocated {
             Ok(val) => val,
             Err(err) => {
                 free_images(device.core(), &images);
@@ -312,10 +505,14 @@ impl ImageMemory {
                 unsafe {
                     device
                     .device()
-                    .bind_image_memory(images[i], img_memory.memory(), regions_info.subregions[i].offset)
-                }, {
+                    .bind_image_memory(
+                        images[i],
+                        img_memory.memory(),
+                        img_memory.offset() + regions_info.subregions[i].offset
+                    )
+                }, |err| {
                     free_images(device.core(), &images);
-                    return Err(memory::MemoryError::ImageBind)
+                    return Err(memory::MemoryError::ImageBind(err))
                 }
             );
         }
@@ -329,35 +526,53 @@ impl ImageMemory {
         };
 
         Ok(
-            ImageMemory {
-                i_core: device.core().clone(),
-                i_images: images,
-                i_image_views: views,
-                i_subregions: regions_info.subregions,
-                i_info: info,
-                i_memory: img_memory
-            }
+            ImageMemory(
+                Arc::new(CoreImageMemory {
+                    i_core: device.core().clone(),
+                    i_images: images,
+                    i_image_views: views,
+                    i_subregions: regions_info.subregions,
+                    i_info: info,
+                    i_layouts: layouts,
+                    i_memory: img_memory,
+                    i_owner: None
+                })
+            )
         )
     }
 
     /// Create views for all images within allocation
     pub fn views(&self) -> Vec<memory::ImageView> {
-        self.i_images.iter().enumerate().map(|(i, _)| memory::ImageView::new(self, i)).collect()
+        self.0.i_images.iter().enumerate().map(|(i, _)| memory::ImageView::new(self, i)).collect()
     }
 
     /// Create and return view to the selected image buffer
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`element_count`](Self::element_count),
+    /// see [`try_view`](Self::try_view)
     pub fn view(&self, index: usize) -> memory::ImageView {
-        memory::ImageView::new(self, index)
+        self.try_view(index).unwrap_or_else(|| panic!("Image index {} is out of bounds (count is {})", index, self.element_count()))
+    }
+
+    /// Same as [`view`](Self::view) but returns `None` if image does not exist
+    pub fn try_view(&self, index: usize) -> Option<memory::ImageView<'_>> {
+        if index < self.element_count() {
+            Some(memory::ImageView::new(self, index))
+        } else {
+            None
+        }
     }
 
     /// Create and return view to the whole image buffer
     pub fn size(&self) -> u64 {
-        self.i_memory.size()
+        self.0.i_memory.size()
     }
 
     /// Map the whole memory into buffer
     pub fn map_memory<T>(&self) -> Result<&mut [T], memory::MemoryError> {
-        self.i_memory.map_memory(0, self.i_memory.size(), self.i_memory.size())
+        self.0.i_memory.map_memory(0, self.0.i_memory.size(), self.0.i_memory.size())
     }
 
     /// Unmap the **whole** memory
@@ -367,14 +582,14 @@ impl ImageMemory {
     ///
     /// You **must not** use such pointer
     pub fn unmap_memory(&self) {
-        self.i_memory.unmap_memory();
+        self.0.i_memory.unmap_memory();
     }
 
     /// Make host memory changes visible to the device
     ///
     /// Memory **must be** HOST_VISIBLE and **must not be** HOST_COHERENT
     pub fn flush(&self) -> Result<(), memory::MemoryError> {
-        self.i_memory.flush(0, self.i_memory.size())
+        self.0.i_memory.flush(0, self.0.i_memory.size())
     }
 
     /// Make device memory changes visible to the host
@@ -382,42 +597,85 @@ impl ImageMemory {
     /// Potential use cases are discussed
     /// [here](https://stackoverflow.com/questions/75324067/difference-between-vkinvalidatemappedmemoryranges-and-vkcmdpipelinebarrier-in-vu)
     pub fn sync(&self) -> Result<(), memory::MemoryError> {
-        self.i_memory.sync(0, self.i_memory.size())
+        self.0.i_memory.sync(0, self.0.i_memory.size())
     }
 
     pub(crate) fn access<T, F>(&self, f: &mut F, index: usize) -> Result<(), memory::MemoryError>
     where
         F: FnMut(&mut [T])
     {
-        self.i_memory.access(
+        self.0.i_memory.access(
             f,
-            self.i_subregions[index].offset,
-            self.i_subregions[index].allocated_size,
-            self.i_subregions[index].allocated_size
+            self.0.i_subregions[index].offset,
+            self.0.i_subregions[index].allocated_size,
+            self.0.i_subregions[index].allocated_size
         )
     }
 
+    /// Return number of images
+    pub fn element_count(&self) -> usize {
+        self.0.i_images.len()
+    }
+
+    /// Return offset of the selected image from the beginning of the allocation
+    pub fn offset(&self, index: usize) -> u64 {
+        self.0.i_subregions[index].offset
+    }
+
+    /// Return offset of the allocation inside its device memory object
+    ///
+    /// Non zero only for memory taken from [`Allocator`](memory::Allocator) block
+    pub fn memory_offset(&self) -> u64 {
+        self.0.i_memory.offset()
+    }
+
+    /// Return bytes reserved for the selected image
+    pub fn allocated_size(&self, index: usize) -> u64 {
+        self.0.i_subregions[index].allocated_size
+    }
+
+    /// Return size of the selected image from its memory requirements
+    pub fn requested_size(&self, index: usize) -> u64 {
+        self.0.i_subregions[index].allocated_size
+    }
+
+    /// Iterate over placement of all images
+    pub fn layout(&self) -> impl Iterator<Item = memory::ElementInfo> + '_ {
+        (0..self.element_count()).map(|i| memory::ElementInfo {
+            index: i,
+            offset: self.offset(i),
+            allocated_size: self.allocated_size(i),
+            requested_size: self.requested_size(i),
+            kind: memory::ElementKind::Image(self.0.i_info[i].usage)
+        })
+    }
+
     pub(crate) fn subregions(&self) -> &Vec<memory::Subregion> {
-        &self.i_subregions
+        &self.0.i_subregions
     }
 
     pub(crate) fn image_views(&self) -> &Vec<vk::ImageView> {
-        &self.i_image_views
+        &self.0.i_image_views
     }
 
     pub(crate) fn info(&self) -> &Vec<ImageInfo> {
-        &self.i_info
+        &self.0.i_info
     }
 
     pub(crate) fn images(&self) -> &Vec<vk::Image> {
-        &self.i_images
+        &self.0.i_images
     }
 
+    /// Wrap `image` owned by `owner`
+    ///
+    /// `owner` is kept alive until view of the image is destroyed
     pub(crate) fn preallocated(
         core: &Arc<dev::Core>,
+        owner: dev::Keepalive,
         image: vk::Image,
         img_format: vk::Format,
-        extent: memory::Extent2D
+        extent: memory::Extent2D,
+        usage: UsageFlags
     ) -> Result<ImageMemory, memory::MemoryError> {
         let iw_info = vk::ImageViewCreateInfo {
             s_type: vk::StructureType::IMAGE_VIEW_CREATE_INFO,
@@ -442,9 +700,11 @@ impl ImageMemory {
             _marker: PhantomData,
         };
 
-        let img_view = on_error_ret!(
+        let img_view = on_error!(
             unsafe { core.device().create_image_view(&iw_info, core.allocator()) },
-            memory::MemoryError::ImageView);
+            |err| return Err(memory::MemoryError::ImageView(err)));
+
+        core.created(dev::ObjectType::ImageView, 1);
 
         let requirements = unsafe {
             core
@@ -470,25 +730,51 @@ impl ImageMemory {
                 base_array_layer: 0,
                 layer_count: 1,
             },
-            format: img_format
+            format: img_format,
+            view_type: vk::ImageViewType::TYPE_2D,
+            usage
         };
 
-        Ok(ImageMemory {
-            i_core: core.clone(),
-            i_images: vec![image],
-            i_image_views: vec![img_view],
-            i_subregions: vec![img_region],
-            i_info: vec![img_info],
-            i_memory: memory::Region::empty(core, requirements.size)
-        })
+        Ok(ImageMemory(
+            Arc::new(CoreImageMemory {
+                i_core: core.clone(),
+                i_images: vec![image],
+                i_image_views: vec![img_view],
+                i_subregions: vec![img_region],
+                i_info: vec![img_info],
+                i_layouts: vec![Arc::new(AtomicI32::new(memory::ImageLayout::UNDEFINED.as_raw()))],
+                i_memory: memory::Region::empty(core, requirements.size),
+                i_owner: Some(owner)
+            })
+        ))
     }
 
     pub(crate) fn region(&self) -> &memory::Region {
-        &self.i_memory
+        &self.0.i_memory
+    }
+
+    pub(crate) fn image_layout(&self, index: usize) -> memory::ImageLayout {
+        memory::ImageLayout::from_raw(self.0.i_layouts[index].load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn set_layout(&self, index: usize, layout: memory::ImageLayout) {
+        self.0.i_layouts[index].store(layout.as_raw(), Ordering::Relaxed);
+    }
+
+    /// Shared storage of the tracked layout
+    ///
+    /// Lets descriptors check layout of the image at bind time
+    pub(crate) fn layout_cell(&self, index: usize) -> LayoutCell {
+        LayoutCell(self.0.i_layouts[index].clone())
+    }
+
+    /// Images with their memory are freed when the last handle is dropped
+    pub(crate) fn keepalive(&self) -> dev::Keepalive {
+        self.0.clone()
     }
 }
 
-impl Drop for ImageMemory {
+impl Drop for CoreImageMemory {
     fn drop(&mut self) {
         free_image_views(&self.i_core, &self.i_image_views);
 
@@ -501,9 +787,9 @@ impl Drop for ImageMemory {
 impl fmt::Debug for ImageMemory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Memory")
-        .field("i_core", &self.i_core)
-        .field("i_memory", &self.i_memory)
-        .field("i_info", &self.i_info)
+        .field("i_core", &self.0.i_core)
+        .field("i_memory", &self.0.i_memory)
+        .field("i_info", &self.0.i_info)
         .finish()
     }
 }
@@ -513,17 +799,19 @@ impl fmt::Display for ImageMemory {
         write!(f,
             "core: {:?}\n\
             memory: {:?}\n",
-            self.i_core,
-            self.i_memory
+            self.0.i_core,
+            self.0.i_memory
         ).expect("Failed to print Memory");
 
-        for i in 0..self.i_info.len() {
+        for i in 0..self.0.i_info.len() {
             write!(f,
                 "---------------\n\
                 id: {:?}\n\
+                subregion: {:?}\n\
                 {:?}",
                 i,
-                self.i_info[i]
+                self.0.i_subregions[i],
+                self.0.i_info[i]
             ).expect("Failed to print Memory");
         }
 
@@ -531,6 +819,47 @@ impl fmt::Display for ImageMemory {
     }
 }
 
+fn check_format_support(device: &dev::Device, image_info: &vk::ImageCreateInfo) -> Result<(), memory::MemoryError> {
+    let unsupported = memory::MemoryError::UnsupportedImage {
+        format: image_info.format,
+        usage: image_info.usage,
+        tiling: image_info.tiling
+    };
+
+    let result = unsafe {
+        device.instance().get_physical_device_image_format_properties(
+            device.hw().device(),
+            image_info.format,
+            image_info.image_type,
+            image_info.tiling,
+            image_info.usage,
+            image_info.flags
+        )
+    };
+
+    let properties = match result {
+        Ok(properties) => properties,
+        Err(vk::Result::ERROR_FORMAT_NOT_SUPPORTED) => return Err(unsupported),
+        Err(err) => return Err(memory::MemoryError::Image(err))
+    };
+
+    let requested = image_info.extent;
+    let max = properties.max_extent;
+
+    if requested.width > max.width || requested.height > max.height || requested.depth > max.depth {
+        return Err(memory::MemoryError::ExtentTooLarge { requested, max });
+    }
+
+    if !properties.sample_counts.contains(image_info.samples)
+        || image_info.mip_levels > properties.max_mip_levels
+        || image_info.array_layers > properties.max_array_layers
+    {
+        return Err(unsupported);
+    }
+
+    Ok(())
+}
+
 fn free_images(core: &Arc<dev::Core>, images: &Vec<vk::Image>) {
     for &image in images {
         unsafe {
@@ -539,6 +868,8 @@ fn free_images(core: &Arc<dev::Core>, images: &Vec<vk::Image>) {
             .destroy_image(image, core.allocator());
         }
     }
+
+    core.destroyed(dev::ObjectType::Image, images.len());
 }
 
 fn free_image_views(core: &Arc<dev::Core>, images: &Vec<vk::ImageView>) {
@@ -549,6 +880,8 @@ fn free_image_views(core: &Arc<dev::Core>, images: &Vec<vk::ImageView>) {
             .destroy_image_view(image, core.allocator());
         }
     }
+
+    core.destroyed(dev::ObjectType::ImageView, images.len());
 }
 
 fn create_image_views(core: &Arc<dev::Core>, images: &Vec<vk::Image>, cfgs: &[ImageInfo])
@@ -561,7 +894,7 @@ fn create_image_views(core: &Arc<dev::Core>, images: &Vec<vk::Image>, cfgs: &[Im
             s_type: vk::StructureType::IMAGE_VIEW_CREATE_INFO,
             p_next: ptr::null(),
             flags: vk::ImageViewCreateFlags::empty(),
-            view_type: vk::ImageViewType::TYPE_2D,
+            view_type: cfg.view_type,
             format: cfg.format,
             components: vk::ComponentMapping {
                 r: vk::ComponentSwizzle::R,
@@ -576,12 +909,14 @@ fn create_image_views(core: &Arc<dev::Core>, images: &Vec<vk::Image>, cfgs: &[Im
 
         let img_view = on_error!(
             unsafe { core.device().create_image_view(&iw_info, core.allocator()) },
-            {
+            |err| {
                 free_image_views(core, &views);
-                return Err(memory::MemoryError::ImageView)
+                return Err(memory::MemoryError::ImageView(err))
             }
         );
 
+        core.created(dev::ObjectType::ImageView, 1);
+
         views.push(img_view);
     }
 
@@ -0,0 +1,48 @@
+//! Read-only description of how elements are placed inside allocation
+//!
+//! See [`Memory::layout`](crate::memory::Memory::layout)
+//! and [`ImageMemory::layout`](crate::memory::ImageMemory::layout)
+
+use crate::memory;
+
+use std::fmt;
+
+/// Type of the element and its usage flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementKind {
+    Buffer(memory::BufferUsageFlags),
+    Image(memory::UsageFlags)
+}
+
+/// Placement of the single buffer or image within allocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElementInfo {
+    /// Index of the element, same as for [`view`](crate::memory::Memory::view)
+    pub index: usize,
+    /// Offset from the beginning of the allocation in bytes
+    pub offset: u64,
+    /// Bytes reserved for the element
+    pub allocated_size: u64,
+    /// Size which was requested
+    ///
+    /// For images it is size from memory requirements
+    pub requested_size: u64,
+    pub kind: ElementKind
+}
+
+impl fmt::Display for ElementInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,
+            "index: {:?}\n\
+            offset: {:?} ({:#x})\n\
+            allocated size: {:?} ({:#x})\n\
+            requested size: {:?}\n\
+            kind: {:?}\n",
+            self.index,
+            self.offset, self.offset,
+            self.allocated_size, self.allocated_size,
+            self.requested_size,
+            self.kind
+        )
+    }
+}
@@ -14,6 +14,8 @@ use std::marker::PhantomData;
 #[doc = "Ash documentation about possible values <https://docs.rs/ash/latest/ash/vk/struct.BufferUsageFlags.html>"]
 ///
 #[doc = "Vulkan documentation <https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VkBufferUsageFlagBits.html>"]
+///
+/// Constants below may be combined, e.g. `memory::STORAGE | memory::INDIRECT`
 pub type BufferUsageFlags = vk::BufferUsageFlags;
 
 // Workaround
@@ -33,10 +35,30 @@ pub const VERTEX: BufferUsageFlags = BufferUsageFlags::from_raw(
     FULL_TRANSFER.as_raw() | (BufferUsageFlags::VERTEX_BUFFER).as_raw()
 );
 
+/// Buffer which is written by compute shader and then read as vertex buffer
+///
+/// Put [buffer barrier](crate::cmd::Buffer::set_barrier) from `SHADER_WRITE` in `COMPUTE_SHADER` stage
+/// to `VERTEX_ATTRIBUTE_READ` in `VERTEX_INPUT` stage between dispatch and draw
+pub const STORAGE_VERTEX: BufferUsageFlags = BufferUsageFlags::from_raw(
+    STORAGE.as_raw() | (BufferUsageFlags::VERTEX_BUFFER).as_raw()
+);
+
 pub const INDEX: BufferUsageFlags = BufferUsageFlags::from_raw(
     FULL_TRANSFER.as_raw() | (BufferUsageFlags::INDEX_BUFFER).as_raw()
 );
 
+pub const INDIRECT: BufferUsageFlags = BufferUsageFlags::from_raw(
+    FULL_TRANSFER.as_raw() | (BufferUsageFlags::INDIRECT_BUFFER).as_raw()
+);
+
+pub const UNIFORM_TEXEL_BUFFER: BufferUsageFlags = BufferUsageFlags::from_raw(
+    FULL_TRANSFER.as_raw() | (BufferUsageFlags::UNIFORM_TEXEL_BUFFER).as_raw()
+);
+
+pub const STORAGE_TEXEL_BUFFER: BufferUsageFlags = BufferUsageFlags::from_raw(
+    FULL_TRANSFER.as_raw() | (BufferUsageFlags::STORAGE_TEXEL_BUFFER).as_raw()
+);
+
 /// Size of the indices
 ///
 #[doc = "Ash documentation about possible values <https://docs.rs/ash/latest/ash/vk/struct.IndexType.html>"]
@@ -73,6 +95,7 @@ pub struct BufferCfg<'a> {
 #[derive(Clone)]
 pub struct MemoryCfg<'a, 'b : 'a> {
     pub properties: hw::MemoryProperty,
+    /// Memory types for which `filter` returns `false` are not used
     pub filter: &'a dyn Fn(&hw::MemoryDescription) -> bool,
     pub buffers: &'a [&'a BufferCfg<'b>]
 }
@@ -87,6 +110,10 @@ pub struct MemoryCfg<'a, 'b : 'a> {
 /// Size of allocated memory is greater or equal to the requested size
 /// (sum of all [`BufferCfg::size`] in [`MemoryCfg::buffers`]) due to alignment requirements
 ///
+/// All buffers must share at least one memory type.
+/// Otherwise allocation fails with [`MemoryError::IncompatibleMemoryType`](crate::memory::MemoryError::IncompatibleMemoryType)
+/// which contains index of the first conflicting buffer
+///
 /// # Alignment
 /// Each buffer from [`MemoryCfg::buffers`] will be separately aligned at least
 /// for [`hw::memory_alignment`](crate::hw::HWDevice::memory_alignment)
@@ -100,11 +127,21 @@ pub struct MemoryCfg<'a, 'b : 'a> {
 /// Whole memory chunk is split into regions (buffers) which are defined by [`MemoryCfg::buffers`]
 ///
 /// To help with managing regions [`Memory View`](crate::memory::View) struct was provided
-pub struct Memory {
+///
+/// # Threading
+///
+/// `Memory` is `Send` and `Sync` so the same buffers may be used by buffers recorded on several threads
+///
+/// [`access`](Self::access) and [`access_range`](Self::access_range) of the same memory are serialized,
+/// [`map_memory`](Self::map_memory) is not and must be synchronized by user
+pub struct Memory(Arc<CoreMemory>);
+
+struct CoreMemory {
     i_core: Arc<dev::Core>,
     i_buffers: Vec<vk::Buffer>,
     i_subregions: Vec<memory::Subregion>,
     i_sizes: Vec<u64>,
+    i_usages: Vec<BufferUsageFlags>,
     i_memory: memory::Region
 }
 
@@ -112,10 +149,30 @@ impl Memory {
     pub fn allocate(
         device: &dev::Device,
         cfg: &MemoryCfg
+    ) -> Result<Memory, memory::MemoryError> {
+        Memory::allocate_impl(device, cfg, None)
+    }
+
+    /// Same as [`allocate`](Self::allocate) but memory is taken from the `allocator` block
+    ///
+    /// Memory is returned to the `allocator` on drop
+    pub fn allocate_from(
+        device: &dev::Device,
+        cfg: &MemoryCfg,
+        allocator: &memory::Allocator
+    ) -> Result<Memory, memory::MemoryError> {
+        Memory::allocate_impl(device, cfg, Some(allocator))
+    }
+
+    fn allocate_impl(
+        device: &dev::Device,
+        cfg: &MemoryCfg,
+        allocator: Option<&memory::Allocator>
     ) -> Result<Memory, memory::MemoryError> {
         let mut buffers: Vec<vk::Buffer> = Vec::new();
         let mut memory_requirements: Vec<vk::MemoryRequirements> = Vec::new();
         let mut sizes: Vec<u64> = Vec::new();
+        let mut usages: Vec<BufferUsageFlags> = Vec::new();
 
         for cfg in cfg.buffers {
             let sharing_mode = if cfg.simultaneous_access {
@@ -138,14 +195,17 @@ impl Memory {
 
             for _ in 0..cfg.count {
                 sizes.push(cfg.size);
+                usages.push(cfg.usage);
 
                 let buffer = on_error!(unsafe {
                     device.device().create_buffer(&buffer_info, device.allocator())
-                }, {
+                }, |err| {
                     free_buffers(device.core(), &buffers);
-                    return Err(memory::MemoryError::Buffer);
+                    return Err(memory::MemoryError::Buffer(err));
                 });
 
+                device.core().created(dev::ObjectType::Buffer, 1);
+
                 buffers.push(buffer);
 
                 let requirements: vk::MemoryRequirements = unsafe {
@@ -160,15 +220,27 @@ impl Memory {
 
         let regions_info = memory::Region::calculate_subregions(device, &memory_requirements);
 
-        let mem_desc = match memory::Region::find_memory(device.hw(), regions_info.memory_bits, cfg.properties) {
-            Some(val) => val,
-            None => {
+        let mem_desc = match memory::Region::select_memory(
+            device.hw(),
+            &memory_requirements,
+            regions_info.memory_bits,
+            cfg.properties,
+            cfg.filter,
+            regions_info.total_size
+        ) {
+            Ok(val) => val,
+            Err(err) => {
                 free_buffers(device.core(), &buffers);
-                return Err(memory::MemoryError::NoSuitableMemory)
+                return Err(err)
             },
         };
 
-        let dev_memory = match memory::Region::allocate(device, regions_info.total_size, mem_desc) {
+        let allocated = match allocator {
+            Some(allocator) => allocator.allocate(device, regions_info.total_size, regions_info.alignment, mem_desc),
+            None => memory::Region::allocate(device, regions_info.total_size, mem_desc)
+        };
+
+        let dev_memory = match allocated {
             Ok(val) => val,
             Err(err) => {
                 free_buffers(device.core(), &buffers);
@@ -177,7 +249,9 @@ impl Memory {
         };
 
         // Without coherency we have to manually synchronize memory between host and device
-        if !cfg
+        // Sub-allocated memory is shared with other allocations so it is left intact
+        if !dev_memory.is_suballocated()
+            && !cfg
             .properties
             .contains(vk::MemoryPropertyFlags::HOST_COHERENT)
             && cfg
@@ -201,9 +275,9 @@ impl Memory {
                         dev_memory.size(),
                         vk::MemoryMapFlags::empty()
                     ),
-                    {
+                    |err| {
                         free_buffers(device.core(), &buffers);
-                        return Err(memory::MemoryError::MapAccess);
+                        return Err(memory::MemoryError::MapAccess(err));
                     }
                 );
 
@@ -211,9 +285,9 @@ impl Memory {
                     device
                         .device()
                         .flush_mapped_memory_ranges(&[mem_range]),
-                    {
+                    |err| {
                         free_buffers(device.core(), &buffers);
-                        return Err(memory::MemoryError::Flush);
+                        return Err(memory::MemoryError::Flush(err));
                     }
                 );
 
@@ -226,22 +300,29 @@ impl Memory {
                 unsafe {
                     device
                     .device()
-                    .bind_buffer_memory(buffers[i], dev_memory.memory(), regions_info.subregions[i].offset)
+                    .bind_buffer_memory(
+                        buffers[i],
+                        dev_memory.memory(),
+                        dev_memory.offset() + regions_info.subregions[i].offset
+                    )
                 },
-                {
+                |err| {
                     free_buffers(device.core(), &buffers);
-                    return Err(memory::MemoryError::Bind);
+                    return Err(memory::MemoryError::Bind(err));
                 }
             )
         }
 
-        Ok(Memory {
-            i_core: device.core().clone(),
-            i_memory: dev_memory,
-            i_buffers: buffers,
-            i_sizes: sizes,
-            i_subregions: regions_info.subregions
-        })
+        Ok(Memory(
+            Arc::new(CoreMemory {
+                i_core: device.core().clone(),
+                i_memory: dev_memory,
+                i_buffers: buffers,
+                i_sizes: sizes,
+                i_usages: usages,
+                i_subregions: regions_info.subregions
+            })
+        ))
     }
 
     /// Perfrom operation `f` over selected buffer
@@ -249,27 +330,173 @@ impl Memory {
     /// It is relatively expensive operation as memory will be mapped and unmapped
     ///
     /// It is better to use [`map_memory`](Self::map_memory) for frequent changes
+    ///
+    /// Returns [`MemoryError::InvalidIndex`](memory::MemoryError::InvalidIndex) if buffer does not exist
     pub fn access<T, F>(&self, f: &mut F, index: usize) -> Result<(), memory::MemoryError>
     where
         F: FnMut(&mut [T]),
     {
-        self.i_memory.access(
+        self.check_index(index)?;
+
+        self.0.i_memory.access(
             f,
-            self.i_subregions[index].offset,
-            self.i_sizes[index],
-            self.i_subregions[index].allocated_size
+            self.0.i_subregions[index].offset,
+            self.0.i_sizes[index],
+            self.0.i_subregions[index].allocated_size
+        )
+    }
+
+    /// Perfrom operation `f` over `byte_len` bytes of the selected buffer starting from `byte_offset`
+    ///
+    /// Unlike [`access`](Self::access) maps (and flushes for non-coherent memory)
+    /// only requested range, expanded to the
+    /// [`memory_alignment`](crate::hw::HWDevice::memory_alignment) boundaries
+    ///
+    /// Returns [`MemoryError::MapAccess`](memory::MemoryError::MapAccess)
+    /// with `ERROR_MEMORY_MAP_FAILED` if range exceeds size of the buffer
+    /// and [`MemoryError::InvalidIndex`](memory::MemoryError::InvalidIndex) if buffer does not exist
+    pub fn access_range<T, F>(
+        &self,
+        f: &mut F,
+        index: usize,
+        byte_offset: u64,
+        byte_len: u64
+    ) -> Result<(), memory::MemoryError>
+    where
+        F: FnMut(&mut [T]),
+    {
+        self.check_index(index)?;
+
+        if byte_offset.checked_add(byte_len).is_none_or(|end| end > self.0.i_sizes[index]) {
+            return Err(memory::MemoryError::MapAccess(vk::Result::ERROR_MEMORY_MAP_FAILED));
+        }
+
+        self.0.i_memory.access_range(f, self.0.i_subregions[index].offset + byte_offset, byte_len)
+    }
+
+    /// Copy content of the selected buffer into host memory
+    ///
+    /// Memory **must be** HOST_VISIBLE
+    ///
+    /// Data is copied while memory is mapped (and invalidated for non-coherent memory)
+    /// so returned vector does not depend on the mapping
+    ///
+    /// Returns [`MemoryError::InvalidIndex`](memory::MemoryError::InvalidIndex) if buffer does not exist
+    pub fn read(&self, index: usize) -> Result<Vec<u8>, memory::MemoryError> {
+        self.check_index(index)?;
+
+        self.0.i_memory.read(self.0.i_subregions[index].offset, self.0.i_sizes[index])
+    }
+
+    /// Same as [`read`](Self::read) but content is returned as vector of `T`
+    ///
+    /// `T` must be plain data type: any bit pattern must be valid value of `T`
+    ///
+    /// Returns [`MemoryError::SizeMismatch`](memory::MemoryError::SizeMismatch)
+    /// if buffer size is not multiple of `size_of::<T>()`
+    pub fn read_as<T: Copy>(&self, index: usize) -> Result<Vec<T>, memory::MemoryError> {
+        self.check_index(index)?;
+
+        let size = self.0.i_sizes[index];
+        let type_size = std::mem::size_of::<T>();
+
+        if type_size == 0 || !size.is_multiple_of(type_size as u64) {
+            return Err(memory::MemoryError::SizeMismatch { size, type_size });
+        }
+
+        let bytes = self.read(index)?;
+
+        // Vector of bytes is not aligned for `T` so elements are read one by one
+        Ok(
+            bytes
+            .chunks_exact(type_size)
+            .map(|chunk| unsafe { ptr::read_unaligned(chunk.as_ptr() as *const T) })
+            .collect()
+        )
+    }
+
+    /// Map the whole memory until returned [`MappedMemory`] is dropped
+    ///
+    /// Unlike [`access`](Self::access) memory is mapped only once
+    /// so it is suitable for data which is updated every frame
+    ///
+    /// Memory is borrowed mutably by [`MappedMemory`],
+    /// so other accessors (e.g. [`access`](Self::access)) can not alias slices returned by it
+    ///
+    /// ```compile_fail
+    /// use libvktypes::memory;
+    ///
+    /// fn alias(memory: &mut memory::Memory) {
+    ///     let mut mapped = memory.map_persistent().unwrap();
+    ///     let values = mapped.slice_of::<u32>(0).unwrap();
+    ///
+    ///     memory.access(&mut |_: &mut [u32]| {}, 0).unwrap();
+    ///
+    ///     values[0] = 1;
+    /// }
+    /// ```
+    ///
+    /// Memory **must be** HOST_VISIBLE
+    pub fn map_persistent(&mut self) -> Result<MappedMemory<'_>, memory::MemoryError> {
+        let data = self.0.i_memory.map_persistent()?;
+
+        Ok(
+            MappedMemory {
+                i_memory: self,
+                i_data: data
+            }
         )
     }
 
     /// Return whole size of the memory in bytes
     pub fn size(&self) -> u64 {
-        self.i_memory.size()
+        self.0.i_memory.size()
+    }
+
+    /// Return number of buffers
+    pub fn element_count(&self) -> usize {
+        self.0.i_buffers.len()
+    }
+
+    /// Return offset of the selected buffer from the beginning of the allocation
+    pub fn offset(&self, index: usize) -> u64 {
+        self.0.i_subregions[index].offset
+    }
+
+    /// Return offset of the allocation inside its device memory object
+    ///
+    /// Non zero only for memory taken from [`Allocator`](memory::Allocator) block
+    pub fn memory_offset(&self) -> u64 {
+        self.0.i_memory.offset()
+    }
+
+    /// Return bytes reserved for the selected buffer
+    pub fn allocated_size(&self, index: usize) -> u64 {
+        self.0.i_subregions[index].allocated_size
+    }
+
+    /// Return size of the selected buffer from [`BufferCfg::size`]
+    pub fn requested_size(&self, index: usize) -> u64 {
+        self.0.i_sizes[index]
+    }
+
+    /// Iterate over placement of all buffers
+    ///
+    /// Useful to check offsets of dynamic uniform buffers
+    pub fn layout(&self) -> impl Iterator<Item = memory::ElementInfo> + '_ {
+        (0..self.element_count()).map(|i| memory::ElementInfo {
+            index: i,
+            offset: self.offset(i),
+            allocated_size: self.allocated_size(i),
+            requested_size: self.requested_size(i),
+            kind: memory::ElementKind::Buffer(self.0.i_usages[i])
+        })
     }
 
     /// Create and return views to the buffers
     pub fn views(&self) -> Vec<memory::View> {
         self
-        .i_buffers
+        .0.i_buffers
         .iter()
         .enumerate()
         .map(|(i, _)| memory::View::new(self, i))
@@ -281,18 +508,114 @@ impl Memory {
     /// About `offset` read docs for [`VertexInputCfg`](graphics::VertexInputCfg)
     ///
     /// Buffer must contain `VERTEX_BUFFER` flag
-    pub fn vertex_view(&self, index: usize, offset: u32) -> graphics::VertexView {
-        graphics::VertexView::with_offset(self.view(index), offset)
+    ///
+    /// Returns [`MemoryError::InvalidIndex`](memory::MemoryError::InvalidIndex) if buffer does not exist
+    /// and [`MemoryError::InvalidOffset`](memory::MemoryError::InvalidOffset) if `offset` is not less than
+    /// [size](Self::requested_size) of the buffer
+    pub fn vertex_view(&self, index: usize, offset: u32) -> Result<graphics::VertexView<'_>, memory::MemoryError> {
+        self.check_index(index)?;
+
+        let size = self.0.i_sizes[index];
+
+        if offset as u64 >= size {
+            return Err(memory::MemoryError::InvalidOffset { offset: offset as u64, size });
+        }
+
+        Ok(graphics::VertexView::with_offset(self.view(index), offset))
+    }
+
+    /// Same as [`vertex_view`](Self::vertex_view) but returns `None` on error
+    pub fn try_vertex_view(&self, index: usize, offset: u32) -> Option<graphics::VertexView<'_>> {
+        self.vertex_view(index, offset).ok()
+    }
+
+    /// Create [`IndexBuffer`](memory::IndexBuffer) with indices of `index_type` for the buffer
+    ///
+    /// Buffer must contain `INDEX_BUFFER` flag
+    ///
+    /// Returns [`MemoryError::InvalidIndex`](memory::MemoryError::InvalidIndex) if buffer does not exist
+    /// and [`MemoryError::SizeMismatch`](memory::MemoryError::SizeMismatch)
+    /// if buffer size is not multiple of index size
+    pub fn index_view(&self, index: usize, index_type: IndexBufferType) -> Result<memory::IndexBuffer<'_>, memory::MemoryError> {
+        self.check_index(index)?;
+
+        let size = self.0.i_sizes[index];
+        let type_size = memory::index_size(index_type);
+
+        if !size.is_multiple_of(type_size) {
+            return Err(memory::MemoryError::SizeMismatch { size, type_size: type_size as usize });
+        }
+
+        Ok(memory::IndexBuffer::new(self.view(index), index_type))
+    }
+
+    /// Write `indices` into the beginning of the selected buffer
+    ///
+    /// Bind buffer with [`IndexBufferType::UINT16`](IndexBufferType::UINT16)
+    /// (e.g. via [`index_view`](Self::index_view))
+    ///
+    /// Memory **must be** HOST_VISIBLE
+    ///
+    /// Returns [`MemoryError::SizeMismatch`](memory::MemoryError::SizeMismatch)
+    /// if buffer size is not multiple of `size_of::<u16>()`
+    /// and [`MemoryError::TransferSize`](memory::MemoryError::TransferSize) if `indices` do not fit into the buffer
+    pub fn write_indices_u16(&self, index: usize, indices: &[u16]) -> Result<(), memory::MemoryError> {
+        self.write_indices(index, indices)
+    }
+
+    /// Same as [`write_indices_u16`](Self::write_indices_u16)
+    /// for [`IndexBufferType::UINT32`](IndexBufferType::UINT32)
+    pub fn write_indices_u32(&self, index: usize, indices: &[u32]) -> Result<(), memory::MemoryError> {
+        self.write_indices(index, indices)
+    }
+
+    fn write_indices<T: Copy>(&self, index: usize, indices: &[T]) -> Result<(), memory::MemoryError> {
+        self.check_index(index)?;
+
+        let size = self.0.i_sizes[index];
+        let type_size = std::mem::size_of::<T>();
+
+        if !size.is_multiple_of(type_size as u64) {
+            return Err(memory::MemoryError::SizeMismatch { size, type_size });
+        }
+
+        if std::mem::size_of_val(indices) as u64 > size {
+            return Err(memory::MemoryError::TransferSize);
+        }
+
+        self.access(&mut |dst: &mut [T]| dst[..indices.len()].copy_from_slice(indices), index)
     }
 
     /// Create and return view to the selected buffer
+    ///
+    /// # Panics
+    ///
+    /// If `index` is not less than [`element_count`](Self::element_count),
+    /// see [`try_view`](Self::try_view)
     pub fn view(&self, index: usize) -> memory::View {
-        memory::View::new(self, index)
+        self.try_view(index).unwrap_or_else(|| panic!("Buffer index {} is out of bounds (count is {})", index, self.element_count()))
+    }
+
+    /// Same as [`view`](Self::view) but returns `None` if buffer does not exist
+    pub fn try_view(&self, index: usize) -> Option<memory::View<'_>> {
+        if index < self.element_count() {
+            Some(memory::View::new(self, index))
+        } else {
+            None
+        }
+    }
+
+    fn check_index(&self, index: usize) -> Result<(), memory::MemoryError> {
+        if index < self.element_count() {
+            Ok(())
+        } else {
+            Err(memory::MemoryError::InvalidIndex { index, count: self.element_count() })
+        }
     }
 
     /// Map the whole memory into buffer
     pub fn map_memory<T>(&self) -> Result<&mut [T], memory::MemoryError> {
-        self.i_memory.map_memory(0, self.i_memory.size(), self.i_memory.size())
+        self.0.i_memory.map_memory(0, self.0.i_memory.size(), self.0.i_memory.size())
     }
 
     /// Unmap the **whole** memory
@@ -303,14 +626,14 @@ impl Memory {
     ///
     /// You **must not** use such pointer
     pub fn unmap_memory(&self) {
-        self.i_memory.unmap_memory();
+        self.0.i_memory.unmap_memory();
     }
 
     /// Make host memory changes visible to the device
     ///
     /// Memory **must be** HOST_VISIBLE and **must not be** HOST_COHERENT
     pub fn flush(&self) -> Result<(), memory::MemoryError> {
-        self.i_memory.flush(0, self.i_memory.size())
+        self.0.i_memory.flush(0, self.0.i_memory.size())
     }
 
     /// Make device memory changes visible to the host
@@ -318,27 +641,120 @@ impl Memory {
     /// Potential use cases are discussed
     /// [here](https://stackoverflow.com/questions/75324067/difference-between-vkinvalidatemappedmemoryranges-and-vkcmdpipelinebarrier-in-vu)
     pub fn sync(&self) -> Result<(), memory::MemoryError> {
-        self.i_memory.sync(0, self.i_memory.size())
+        self.0.i_memory.sync(0, self.0.i_memory.size())
     }
 
     pub(crate) fn buffer(&self, index: usize) -> vk::Buffer {
-        self.i_buffers[index]
+        self.0.i_buffers[index]
     }
 
     pub(crate) fn subregions(&self) -> &Vec<memory::Subregion> {
-        &self.i_subregions
+        &self.0.i_subregions
+    }
+
+    pub(crate) fn usage(&self, index: usize) -> BufferUsageFlags {
+        self.0.i_usages[index]
     }
 
     pub(crate) fn sizes(&self) -> &Vec<u64> {
-        &self.i_sizes
+        &self.0.i_sizes
     }
 
     pub(crate) fn region(&self) -> &memory::Region {
-        &self.i_memory
+        &self.0.i_memory
     }
+
+    /// Memory with all its buffers is freed when the last handle is dropped
+    pub(crate) fn keepalive(&self) -> dev::Keepalive {
+        self.0.clone()
+    }
+}
+
+/// Memory which stays mapped during lifetime of the struct
+///
+/// Created by [`Memory::map_persistent`]
+///
+/// For HOST_COHERENT memory [`flush`](Self::flush) and [`sync`](Self::sync) do nothing
+///
+/// Memory is unmapped on drop
+pub struct MappedMemory<'a> {
+    i_memory: &'a mut Memory,
+    i_data: *mut u8
 }
 
-impl Drop for Memory {
+impl<'a> MappedMemory<'a> {
+    /// Return mapped content of the selected buffer
+    ///
+    /// Returns [`MemoryError::InvalidIndex`](memory::MemoryError::InvalidIndex) if buffer does not exist
+    /// and [`MemoryError::SizeMismatch`](memory::MemoryError::SizeMismatch)
+    /// if buffer size is not multiple of `size_of::<T>()`
+    pub fn slice_of<T>(&mut self, index: usize) -> Result<&mut [T], memory::MemoryError> {
+        self.i_memory.check_index(index)?;
+
+        let size = self.i_memory.0.i_sizes[index];
+        let type_size = std::mem::size_of::<T>();
+
+        if type_size == 0 || !size.is_multiple_of(type_size as u64) {
+            return Err(memory::MemoryError::SizeMismatch { size, type_size });
+        }
+
+        Ok(
+            unsafe {
+                std::slice::from_raw_parts_mut(
+                    self.i_data.add(self.i_memory.0.i_subregions[index].offset as usize) as *mut T,
+                    (size as usize)/type_size
+                )
+            }
+        )
+    }
+
+    /// Make host changes of the selected buffer visible to the device
+    pub fn flush(&self, index: usize) -> Result<(), memory::MemoryError> {
+        self.i_memory.check_index(index)?;
+
+        let region = &self.i_memory.0.i_memory;
+
+        if region.is_coherent() {
+            return Ok(());
+        }
+
+        let (offset, size) = region.atom_range(self.i_memory.0.i_subregions[index].offset, self.i_memory.0.i_sizes[index]);
+
+        region.flush(offset, size)
+    }
+
+    /// Make device changes of the selected buffer visible to the host
+    pub fn sync(&self, index: usize) -> Result<(), memory::MemoryError> {
+        self.i_memory.check_index(index)?;
+
+        let region = &self.i_memory.0.i_memory;
+
+        if region.is_coherent() {
+            return Ok(());
+        }
+
+        let (offset, size) = region.atom_range(self.i_memory.0.i_subregions[index].offset, self.i_memory.0.i_sizes[index]);
+
+        region.sync(offset, size)
+    }
+}
+
+impl<'a> Drop for MappedMemory<'a> {
+    fn drop(&mut self) {
+        self.i_memory.0.i_memory.unmap_persistent();
+    }
+}
+
+impl<'a> fmt::Debug for MappedMemory<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedMemory")
+        .field("i_memory", &self.i_memory)
+        .field("i_data", &self.i_data)
+        .finish()
+    }
+}
+
+impl Drop for CoreMemory {
     fn drop(&mut self) {
         free_buffers(&self.i_core, &self.i_buffers);
     }
@@ -350,15 +766,17 @@ fn free_buffers(device: &dev::Core, buffers: &Vec<vk::Buffer>) {
             device.device().destroy_buffer(buffer, device.allocator());
         }
     }
+
+    device.destroyed(dev::ObjectType::Buffer, buffers.len());
 }
 
 impl fmt::Debug for Memory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Memory")
-        .field("i_core", &self.i_core)
-        .field("i_device_memory", &self.i_memory)
-        .field("i_buffers", &self.i_buffers)
-        .field("i_pos", &self.i_subregions)
+        .field("i_core", &self.0.i_core)
+        .field("i_device_memory", &self.0.i_memory)
+        .field("i_buffers", &self.0.i_buffers)
+        .field("i_pos", &self.0.i_subregions)
         .finish()
     }
 }
@@ -368,11 +786,11 @@ impl fmt::Display for Memory {
         write!(f,
             "core: {:?}\n\
             memory: {:?}\n",
-            self.i_core,
-            self.i_memory,
+            self.0.i_core,
+            self.0.i_memory,
         ).expect("Failed to print Memory");
 
-        for i in 0..self.i_subregions.len() {
+        for i in 0..self.0.i_subregions.len() {
             write!(f,
                 "---------------\n\
                 index: {:?}\n\
@@ -380,9 +798,9 @@ impl fmt::Display for Memory {
                 subregion: {:?}\n\
                 size: {:?}\n",
                 i,
-                self.i_buffers[i],
-                self.i_subregions[i],
-                self.i_sizes[i]
+                self.0.i_buffers[i],
+                self.0.i_subregions[i],
+                self.0.i_sizes[i]
             ).expect("Failed to print Memory");
         }
 
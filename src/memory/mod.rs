@@ -8,6 +8,11 @@ pub mod memory;
 pub mod image;
 pub mod framebuffer;
 pub mod view;
+pub mod buffer_view;
+pub mod staging;
+pub mod cpu_image;
+pub mod allocator;
+pub mod layout;
 pub(crate) mod region;
 
 #[doc(hidden)]
@@ -18,6 +23,16 @@ pub use image::*;
 pub use framebuffer::*;
 #[doc(hidden)]
 pub use view::*;
+#[doc(hidden)]
+pub use buffer_view::*;
+#[doc(hidden)]
+pub use staging::*;
+#[doc(hidden)]
+pub use cpu_image::*;
+#[doc(hidden)]
+pub use allocator::*;
+#[doc(hidden)]
+pub use layout::*;
 pub(crate) use region::*;
 
 use std::error::Error;
@@ -34,66 +49,178 @@ pub type ImageLayout = ash::vk::ImageLayout;
 #[derive(Debug)]
 pub enum MemoryError {
     /// Failed to [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateBuffer.html) buffer
-    Buffer,
+    Buffer(ash::vk::Result),
     /// Failed to find suitable memory
     NoSuitableMemory,
+    /// Element with `index` has no memory type in common with previous elements
+    ///
+    /// Such element should be allocated separately
+    IncompatibleMemoryType {
+        index: usize
+    },
     /// Failed to [allocate](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkAllocateMemory.html) memory
-    DeviceMemory,
+    DeviceMemory(ash::vk::Result),
     /// Failed to
     /// [map](https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/vkMapMemory.html) memory
-    MapAccess,
+    MapAccess(ash::vk::Result),
     /// Failed to
     /// [flush](https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/vkFlushMappedMemoryRanges.html) memory
-    Flush,
+    Flush(ash::vk::Result),
     /// Failed to
     /// [bind](https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/vkBindBufferMemory.html) memory
-    Bind,
+    Bind(ash::vk::Result),
     /// Failed to
     /// [invalidate mapped memory range](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkInvalidateMappedMemoryRanges.html)
-    Sync,
+    Sync(ash::vk::Result),
     /// Failed to
     /// [allocate](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateImage.html) image
-    Image,
+    ///
+    /// Requested format properties are checked before creation
+    /// so errors are reported as [`UnsupportedImage`](MemoryError::UnsupportedImage)
+    /// and [`ExtentTooLarge`](MemoryError::ExtentTooLarge)
+    Image(ash::vk::Result),
+    /// Device does not support images of `format` with `usage` and `tiling`
+    ///
+    /// Also returned if samples, mip levels or array layers exceed
+    /// [limits](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkImageFormatProperties.html)
+    /// of the format
+    UnsupportedImage {
+        format: ImageFormat,
+        usage: ImageUsageFlags,
+        tiling: Tiling
+    },
+    /// `requested` extent of the image exceeds `max` extent supported for its format, usage and tiling
+    ExtentTooLarge {
+        requested: Extent3D,
+        max: Extent3D
+    },
     /// Failed to
     /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateImageView.html) image view
-    ImageView,
+    ImageView(ash::vk::Result),
     /// Failed to
     /// [bind](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkBindImageMemory.html) image memory
-    ImageBind
+    ImageBind(ash::vk::Result),
+    /// Failed to
+    /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateBufferView.html) buffer view
+    BufferView(ash::vk::Result),
+    /// Failed to record or execute transfer commands
+    Transfer(Box<crate::Error>),
+    /// Data does not fit into transfer destination
+    TransferSize,
+    /// Device was lost during the call, see [`Device::is_lost`](crate::dev::Device::is_lost)
+    DeviceLost,
+    /// Buffer of `size` bytes can not be read as slice of elements of `type_size` bytes
+    ///
+    /// See [`Memory::read_as`]
+    SizeMismatch {
+        size: u64,
+        type_size: usize
+    },
+    /// `offset` is outside of the buffer with `size` bytes
+    InvalidOffset {
+        offset: u64,
+        size: u64
+    },
+    /// `offset` is not multiple of required `alignment`
+    ///
+    /// See [`View::slice`]
+    MisalignedOffset {
+        offset: u64,
+        alignment: u64
+    },
+    /// Element with `index` does not exist, allocation has only `count` elements
+    InvalidIndex {
+        index: usize,
+        count: usize
+    },
+    /// Requested size exceeds remaining budget of every compatible heap
+    ///
+    /// `heap` and `available` describe the first compatible heap
+    ///
+    /// See [`MemoryDescription::available`](crate::hw::MemoryDescription::available)
+    ExceedsBudget {
+        heap: u32,
+        requested: u64,
+        available: u64
+    }
 }
 
 impl fmt::Display for MemoryError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let err_msg = match self {
-            MemoryError::Buffer => {
+            MemoryError::Buffer(_) => {
                 "Failed to create buffer (vkCreateBuffer call failed)"
             },
             MemoryError::NoSuitableMemory => {
                 "Failed to find suitable memory"
             },
-            MemoryError::DeviceMemory => {
+            MemoryError::IncompatibleMemoryType { index } => {
+                return write!(f, "{:?}", format!("Element {} has no memory type in common with previous elements", index));
+            },
+            MemoryError::DeviceMemory(_) => {
                 "Failed to allocate memory for buffer (vkAllocateMemory call failed)"
             },
-            MemoryError::MapAccess => {
+            MemoryError::MapAccess(_) => {
                 "Failed to map memory (vkMapMemory call failed)"
             },
-            MemoryError::Flush => {
+            MemoryError::Flush(_) => {
                 "Failed to flush memory (vkFlushMappedMemoryRanges call failed)"
             },
-            MemoryError::Bind => {
+            MemoryError::Bind(_) => {
                 "Failed to bind memory (vkBindBufferMemory call failed)"
             },
-            MemoryError::Sync => {
+            MemoryError::Sync(_) => {
                 "Failed to invalidate mapped memory range (vkInvalidateMappedMemoryRanges call failed)"
             },
-            MemoryError::Image => {
+            MemoryError::Image(_) => {
                 "Failed to create image (vkCreateImage call failed)"
             },
-            MemoryError::ImageView => {
+            MemoryError::UnsupportedImage { format, usage, tiling } => {
+                return write!(f, "{:?}", format!("Image of format {:?} with usage {:?} and tiling {:?} is not supported", format, usage, tiling));
+            },
+            MemoryError::ExtentTooLarge { requested, max } => {
+                return write!(
+                    f,
+                    "{:?}",
+                    format!(
+                        "Image extent {}x{}x{} exceeds maximum {}x{}x{}",
+                        requested.width, requested.height, requested.depth,
+                        max.width, max.height, max.depth
+                    )
+                );
+            },
+            MemoryError::ImageView(_) => {
                 "Failed to create image view (vkCreateImageView call failed)"
             },
-            MemoryError::ImageBind => {
+            MemoryError::ImageBind(_) => {
                 "Failed to bind image memory (vkBindImageMemory call failed)"
+            },
+            MemoryError::BufferView(_) => {
+                "Failed to create buffer view (vkCreateBufferView call failed)"
+            },
+            MemoryError::Transfer(_) => {
+                "Failed to record or execute transfer commands"
+            },
+            MemoryError::TransferSize => {
+                "Data does not fit into transfer destination"
+            },
+            MemoryError::DeviceLost => {
+                "Device was lost (VK_ERROR_DEVICE_LOST)"
+            },
+            MemoryError::SizeMismatch { size, type_size } => {
+                return write!(f, "{:?}", format!("Buffer size {} is not multiple of type size {}", size, type_size));
+            },
+            MemoryError::InvalidOffset { offset, size } => {
+                return write!(f, "{:?}", format!("Offset {} is out of bounds (buffer size is {})", offset, size));
+            },
+            MemoryError::MisalignedOffset { offset, alignment } => {
+                return write!(f, "{:?}", format!("Offset {} is not multiple of {}", offset, alignment));
+            },
+            MemoryError::InvalidIndex { index, count } => {
+                return write!(f, "{:?}", format!("Element index {} is out of bounds (count is {})", index, count));
+            },
+            MemoryError::ExceedsBudget { heap, requested, available } => {
+                return write!(f, "{:?}", format!("Requested {} bytes but heap {} has only {} bytes available", requested, heap, available));
             }
         };
 
@@ -101,4 +228,34 @@ impl fmt::Display for MemoryError {
     }
 }
 
-impl Error for MemoryError {}
\ No newline at end of file
+impl Error for MemoryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MemoryError::Buffer(result)
+            | MemoryError::DeviceMemory(result)
+            | MemoryError::MapAccess(result)
+            | MemoryError::Flush(result)
+            | MemoryError::Bind(result)
+            | MemoryError::Sync(result)
+            | MemoryError::Image(result)
+            | MemoryError::ImageView(result)
+            | MemoryError::ImageBind(result)
+            | MemoryError::BufferView(result) => Some(result),
+            MemoryError::Transfer(err) => Some(err.as_ref()),
+            _ => None
+        }
+    }
+}
+
+/// Map result of the failed call to [`MemoryError`]
+///
+/// `VK_ERROR_DEVICE_LOST` marks device as [lost](crate::dev::Device::is_lost)
+/// and turns into [`MemoryError::DeviceLost`], other results are passed to `err`
+#[doc(hidden)]
+pub fn memory_error(core: &crate::dev::Core, result: ash::vk::Result, err: fn(ash::vk::Result) -> MemoryError) -> MemoryError {
+    if core.observe(result) {
+        MemoryError::DeviceLost
+    } else {
+        err(result)
+    }
+}
\ No newline at end of file
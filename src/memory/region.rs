@@ -1,11 +1,12 @@
 use ash::vk;
 
 use core::ffi::c_void;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::{AtomicPtr, Ordering};
 use std::fmt;
 use std::marker::PhantomData;
 
-use crate::{on_error, on_error_ret};
+use crate::on_error;
 use crate::{dev, hw, memory, offset};
 
 use std::ptr;
@@ -41,14 +42,22 @@ impl Subregion {
 pub(crate) struct RegionInfo {
     pub subregions: Vec<Subregion>,
     pub total_size: u64,
-    pub memory_bits: u32
+    pub memory_bits: u32,
+    pub alignment: u64
 }
 
 pub(crate) struct Region {
     i_core: Arc<dev::Core>,
     i_memory: vk::DeviceMemory,
     i_size: u64,
-    i_flags: hw::MemoryProperty
+    i_flags: hw::MemoryProperty,
+    i_atom_size: u64,
+    i_offset: u64,
+    i_block: Option<memory::BlockRange>,
+    /// Host access of the same memory from several threads is serialized
+    i_map_lock: Mutex<()>,
+    /// Whole region mapped by [`map_persistent`](Region::map_persistent) or null
+    i_persistent: AtomicPtr<c_void>
 }
 
 impl Region {
@@ -59,6 +68,7 @@ impl Region {
         let mut memory_type_bits = 0xffffffffu32;
         let mut last = 0u64;
         let mut total_size = 0u64;
+        let mut max_alignment = 1u64;
         let mut pos: Vec<Subregion> = Vec::new();
 
         for requirement in requirements {
@@ -91,12 +101,15 @@ impl Region {
             last += aligned_size;
 
             total_size += requirement.size + alignment;
+
+            max_alignment = std::cmp::max(max_alignment, alignment);
         }
 
         RegionInfo {
             subregions: pos,
             total_size: total_size,
-            memory_bits: memory_type_bits
+            memory_bits: memory_type_bits,
+            alignment: max_alignment
         }
     }
 
@@ -110,9 +123,9 @@ impl Region {
         };
 
         let dev_memory: vk::DeviceMemory = unsafe {
-            on_error_ret!(
+            on_error!(
                 device.device().allocate_memory(&memory_info, device.allocator()),
-                memory::MemoryError::DeviceMemory
+                |err| return Err(memory::memory_error(device.core(), err, memory::MemoryError::DeviceMemory))
             )
         };
 
@@ -137,9 +150,9 @@ impl Region {
                         size,
                         vk::MemoryMapFlags::empty()
                     ),
-                    {
+                    |err| {
                         device.device().free_memory(dev_memory, device.allocator());
-                        return Err(memory::MemoryError::MapAccess);
+                        return Err(memory::memory_error(device.core(), err, memory::MemoryError::MapAccess));
                     }
                 );
 
@@ -147,9 +160,9 @@ impl Region {
                     device
                         .device()
                         .flush_mapped_memory_ranges(&[mem_range]),
-                    {
+                    |err| {
                         device.device().free_memory(dev_memory, device.allocator());
-                        return Err(memory::MemoryError::Flush);
+                        return Err(memory::memory_error(device.core(), err, memory::MemoryError::Flush));
                     }
                 );
 
@@ -161,44 +174,203 @@ impl Region {
             i_core: device.core().clone(),
             i_memory: dev_memory,
             i_size: size,
-            i_flags: desc.flags()
+            i_flags: desc.flags(),
+            i_atom_size: device.hw().memory_alignment(),
+            i_offset: 0,
+            i_block: None,
+            i_map_lock: Mutex::new(()),
+            i_persistent: AtomicPtr::new(ptr::null_mut())
         })
     }
 
-    pub(crate) fn find_memory<'a, 'b : 'a>(hw: &'b hw::HWDevice, memory_bits: u32, properties: hw::MemoryProperty) -> Option<&'a hw::MemoryDescription> {
-        let memory_filter = |m: &'b hw::MemoryDescription| -> Option<&'a hw::MemoryDescription> {
-            if ((memory_bits >> m.index()) & 1) == 1
-                && m.is_compatible(properties)
-            {
-                Some(m)
-            } else {
-                None
+    /// Region which occupies `range` of the [`Allocator`](memory::Allocator) block
+    pub(crate) fn suballocated(
+        device: &dev::Device,
+        desc: &hw::MemoryDescription,
+        block_memory: vk::DeviceMemory,
+        offset: u64,
+        range: memory::BlockRange) -> Region
+    {
+        Region {
+            i_core: device.core().clone(),
+            i_memory: block_memory,
+            i_size: range.size,
+            i_flags: desc.flags(),
+            i_atom_size: device.hw().memory_alignment(),
+            i_offset: offset,
+            i_block: Some(range),
+            i_map_lock: Mutex::new(()),
+            i_persistent: AtomicPtr::new(ptr::null_mut())
+        }
+    }
+
+    pub(crate) fn find_memory<'a, 'b : 'a>(
+        hw: &'b hw::HWDevice,
+        memory_bits: u32,
+        properties: hw::MemoryProperty,
+        filter: &dyn Fn(&hw::MemoryDescription) -> bool) -> Option<&'a hw::MemoryDescription>
+    {
+        hw.memory().find(|m| Region::is_suitable(m, memory_bits, properties, filter))
+    }
+
+    fn is_suitable(
+        desc: &hw::MemoryDescription,
+        memory_bits: u32,
+        properties: hw::MemoryProperty,
+        filter: &dyn Fn(&hw::MemoryDescription) -> bool) -> bool
+    {
+        ((memory_bits >> desc.index()) & 1) == 1
+            && desc.is_compatible(properties)
+            && filter(desc)
+    }
+
+    /// Find memory suitable for all `requirements`
+    ///
+    /// Memory which heap has less than `size` bytes of
+    /// [available budget](hw::MemoryDescription::available) is skipped
+    ///
+    /// If there is no such memory tries to find the first element which
+    /// has no common memory type with previous ones
+    pub(crate) fn select_memory<'a, 'b : 'a>(
+        hw: &'b hw::HWDevice,
+        requirements: &[vk::MemoryRequirements],
+        memory_bits: u32,
+        properties: hw::MemoryProperty,
+        filter: &dyn Fn(&hw::MemoryDescription) -> bool,
+        size: u64) -> Result<&'a hw::MemoryDescription, memory::MemoryError>
+    {
+        if let Some(first) = Region::find_memory(hw, memory_bits, properties, filter) {
+            let with_budget = hw.memory().find(|m| {
+                Region::is_suitable(m, memory_bits, properties, filter) && m.available() >= size
+            });
+
+            return match with_budget {
+                Some(desc) => Ok(desc),
+                None => Err(memory::MemoryError::ExceedsBudget {
+                    heap: first.heap_index(),
+                    requested: size,
+                    available: first.available()
+                })
+            };
+        }
+
+        let mut acc_bits = 0xffffffffu32;
+
+        for (i, requirement) in requirements.iter().enumerate() {
+            if Region::find_memory(hw, requirement.memory_type_bits, properties, filter).is_none() {
+                break;
+            }
+
+            if Region::find_memory(hw, acc_bits & requirement.memory_type_bits, properties, filter).is_none() {
+                return Err(memory::MemoryError::IncompatibleMemoryType { index: i });
             }
-        };
 
-        hw.memory().find_map(memory_filter)
+            acc_bits &= requirement.memory_type_bits;
+        }
+
+        Err(memory::MemoryError::NoSuitableMemory)
     }
 
     pub(crate) fn memory(&self) -> vk::DeviceMemory {
         self.i_memory
     }
 
+    /// Offset of the region inside `vkDeviceMemory`
+    ///
+    /// Non zero only for memory from [`Allocator`](memory::Allocator)
+    pub(crate) fn offset(&self) -> u64 {
+        self.i_offset
+    }
+
+    /// Map `size` bytes from `offset` (relative to the region)
+    ///
+    /// Persistently mapped blocks and regions are not mapped again
+    fn map_raw(&self, offset: u64, size: u64) -> Result<*mut c_void, memory::MemoryError> {
+        if let Some(range) = &self.i_block {
+            if !range.mapped.is_null() {
+                return Ok(unsafe { (range.mapped as *mut u8).add((self.i_offset + offset) as usize) as *mut c_void });
+            }
+        }
+
+        let persistent = self.i_persistent.load(Ordering::Acquire);
+
+        if !persistent.is_null() {
+            return Ok(unsafe { (persistent as *mut u8).add(offset as usize) as *mut c_void });
+        }
+
+        Ok(on_error!(
+            unsafe {
+                self.i_core.device().map_memory(
+                    self.i_memory,
+                    self.i_offset + offset,
+                    size,
+                    vk::MemoryMapFlags::empty(),
+                )
+            },
+            |err| return Err(memory::memory_error(&self.i_core, err, memory::MemoryError::MapAccess))
+        ))
+    }
+
+    pub(crate) fn is_suballocated(&self) -> bool {
+        self.i_block.is_some()
+    }
+
+    fn lock_access(&self) -> MutexGuard<'_, ()> {
+        self.i_map_lock.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn is_persistently_mapped(&self) -> bool {
+        self.i_block.as_ref().is_some_and(|range| !range.mapped.is_null())
+        || !self.i_persistent.load(Ordering::Acquire).is_null()
+    }
+
+    /// Map the whole region until [`unmap_persistent`](Region::unmap_persistent) is called
+    ///
+    /// While region is mapped other accessors reuse the mapping and do not unmap it
+    ///
+    /// Region **must not** be mapped yet
+    pub(crate) fn map_persistent(&self) -> Result<*mut u8, memory::MemoryError> {
+        let _lock = self.lock_access();
+
+        debug_assert!(self.i_persistent.load(Ordering::Acquire).is_null(), "Region is already mapped");
+
+        let data = self.map_raw(0, self.i_size)?;
+
+        self.i_persistent.store(data, Ordering::Release);
+
+        Ok(data as *mut u8)
+    }
+
+    pub(crate) fn unmap_persistent(&self) {
+        let _lock = self.lock_access();
+
+        self.i_persistent.store(ptr::null_mut(), Ordering::Release);
+
+        self.unmap_memory();
+    }
+
     pub(crate) fn access<T, F>(&self, f: &mut F, offset: u64, size: u64, allocated_size: u64) -> Result<(), memory::MemoryError>
     where
         F: FnMut(&mut [T]),
     {
+        let _lock = self.lock_access();
+
         let data = self.map_memory(offset, size, allocated_size)?;
 
+        let (sync_begin, sync_size) = self.atom_range(offset, size);
+
+        // Device writes must be visible before `f` reads them
+        if !self.is_coherent() {
+            if let Err(err) = self.sync(sync_begin, sync_size) {
+                self.unmap_memory();
+                return Err(err);
+            }
+        }
+
         f(data);
 
-        let result = if !self
-            .i_flags
-            .contains(vk::MemoryPropertyFlags::HOST_COHERENT)
-            && self
-            .i_flags
-            .contains(vk::MemoryPropertyFlags::HOST_VISIBLE)
-        {
-            self.flush(offset, size)
+        let result = if !self.is_coherent() {
+            self.flush(sync_begin, sync_size)
         }
         else {
             Ok(())
@@ -209,18 +381,103 @@ impl Region {
         result
     }
 
+    /// Map only `[offset, offset + size)` range (absolute offsets)
+    ///
+    /// Mapped range is expanded to the `nonCoherentAtomSize` boundaries
+    /// so flush covers only required atoms
+    pub(crate) fn access_range<T, F>(&self, f: &mut F, offset: u64, size: u64) -> Result<(), memory::MemoryError>
+    where
+        F: FnMut(&mut [T]),
+    {
+        let _lock = self.lock_access();
+
+        let (map_begin, map_size) = self.atom_range(offset, size);
+
+        let data: *mut c_void = self.map_raw(map_begin, map_size)?;
+
+        if !self.is_coherent() {
+            if let Err(err) = self.sync(map_begin, map_size) {
+                self.unmap_memory();
+                return Err(err);
+            }
+        }
+
+        let slice = unsafe {
+            std::slice::from_raw_parts_mut(
+                (data as *mut u8).add((offset - map_begin) as usize) as *mut T,
+                (size as usize)/std::mem::size_of::<T>()
+            )
+        };
+
+        f(slice);
+
+        let result = if !self.is_coherent() {
+            self.flush(map_begin, map_size)
+        }
+        else {
+            Ok(())
+        };
+
+        self.unmap_memory();
+
+        result
+    }
+
+    /// Copy `[offset, offset + size)` range (absolute offsets) into host memory
+    ///
+    /// Non-coherent memory is invalidated before copy
+    /// and memory is unmapped only after data is copied
+    pub(crate) fn read(&self, offset: u64, size: u64) -> Result<Vec<u8>, memory::MemoryError> {
+        let _lock = self.lock_access();
+
+        let (map_begin, map_size) = self.atom_range(offset, size);
+
+        let data: *mut c_void = self.map_raw(map_begin, map_size)?;
+
+        if !self.is_coherent() {
+            if let Err(err) = self.sync(map_begin, map_size) {
+                self.unmap_memory();
+                return Err(err);
+            }
+        }
+
+        let mut result = vec![0u8; size as usize];
+
+        unsafe {
+            ptr::copy_nonoverlapping(
+                (data as *const u8).add((offset - map_begin) as usize),
+                result.as_mut_ptr(),
+                size as usize
+            );
+        }
+
+        self.unmap_memory();
+
+        Ok(result)
+    }
+
+    /// Expand `[offset, offset + size)` to the `nonCoherentAtomSize` boundaries
+    ///
+    /// Returns offset and size of the expanded range
+    pub(crate) fn atom_range(&self, offset: u64, size: u64) -> (u64, u64) {
+        let begin = offset - offset % self.i_atom_size;
+        let end = std::cmp::min(offset::full_size(offset + size, self.i_atom_size), self.i_size);
+
+        (begin, end - begin)
+    }
+
+    pub(crate) fn core(&self) -> &Arc<dev::Core> {
+        &self.i_core
+    }
+
+    /// Is host access synchronized without explicit flush or invalidate
+    pub(crate) fn is_coherent(&self) -> bool {
+        self.i_flags.contains(vk::MemoryPropertyFlags::HOST_COHERENT)
+        || !self.i_flags.contains(vk::MemoryPropertyFlags::HOST_VISIBLE)
+    }
+
     pub(crate) fn map_memory<T>(&self, offset: u64, size: u64, allocated_size: u64) -> Result<&mut [T], memory::MemoryError> {
-        let data: *mut c_void = on_error_ret!(
-            unsafe {
-                self.i_core.device().map_memory(
-                    self.i_memory,
-                    offset,
-                    allocated_size,
-                    vk::MemoryMapFlags::empty(),
-                )
-            },
-            memory::MemoryError::MapAccess
-        );
+        let data: *mut c_void = self.map_raw(offset, allocated_size)?;
 
         Ok(unsafe { std::slice::from_raw_parts_mut(data as *mut T, (size as usize)/std::mem::size_of::<T>()) })
     }
@@ -230,18 +487,18 @@ impl Region {
             s_type: vk::StructureType::MAPPED_MEMORY_RANGE,
             p_next: ptr::null(),
             memory: self.i_memory,
-            offset,
+            offset: self.i_offset + offset,
             size,
             _marker: PhantomData,
         };
 
-        on_error_ret!(
+        on_error!(
             unsafe {
                 self.i_core
                 .device()
                 .flush_mapped_memory_ranges(&[mem_range])
             },
-            memory::MemoryError::Flush
+            |err| return Err(memory::memory_error(&self.i_core, err, memory::MemoryError::Flush))
         );
 
         Ok(())
@@ -252,24 +509,28 @@ impl Region {
             s_type: vk::StructureType::MAPPED_MEMORY_RANGE,
             p_next: ptr::null(),
             memory: self.i_memory,
-            offset,
+            offset: self.i_offset + offset,
             size,
             _marker: PhantomData,
         };
 
-        on_error_ret!(
+        on_error!(
             unsafe {
                 self.i_core
                 .device()
                 .invalidate_mapped_memory_ranges(&[mem_range])
             },
-            memory::MemoryError::Sync
+            |err| return Err(memory::memory_error(&self.i_core, err, memory::MemoryError::Sync))
         );
 
         Ok(())
     }
 
     pub(crate) fn unmap_memory(&self) {
+        if self.is_persistently_mapped() {
+            return;
+        }
+
         unsafe { self.i_core.device().unmap_memory(self.i_memory) };
     }
 
@@ -278,7 +539,12 @@ impl Region {
             i_core: core.clone(),
             i_memory: vk::DeviceMemory::null(),
             i_size: size,
-            i_flags: vk::MemoryPropertyFlags::empty()
+            i_flags: vk::MemoryPropertyFlags::empty(),
+            i_atom_size: 1,
+            i_offset: 0,
+            i_block: None,
+            i_map_lock: Mutex::new(()),
+            i_persistent: AtomicPtr::new(ptr::null_mut())
         }
     }
 
@@ -293,6 +559,11 @@ impl Region {
 
 impl Drop for Region {
     fn drop(&mut self) {
+        if let Some(range) = &self.i_block {
+            range.allocator.free(range.block, self.i_offset, range.size);
+            return;
+        }
+
         if !self.is_empty() {
             unsafe {
                 self.i_core
@@ -310,6 +581,7 @@ impl fmt::Debug for Region {
         .field("i_memory", &self.i_memory)
         .field("i_size", &self.i_size)
         .field("i_flags", &self.i_flags)
+        .field("i_offset", &self.i_offset)
         .finish()
     }
 }
@@ -0,0 +1,272 @@
+//! One-shot transfers between host and device local memory
+use crate::{dev, hw, memory, cmd, queue, formats};
+use crate::on_error;
+
+use std::cell::RefCell;
+
+/// Helper which copies data into and out of device local memory
+///
+/// Every call records command buffer from [`pool`](StagingBuffer::new),
+/// executes it on the selected queue and waits for completion
+///
+/// Staging memory is allocated on the first call
+/// and reused by the next ones while data fits into it
+///
+/// Staging memory is HOST_COHERENT so no explicit flush or invalidation is required
+///
+/// Destination (or source) of the transfer **must be** created
+/// with proper `TRANSFER_DST` (`TRANSFER_SRC`) usage and
+/// be accessible from the queue family of the `queue`
+pub struct StagingBuffer<'a> {
+    i_device: &'a dev::Device,
+    i_queue: &'a queue::Queue,
+    i_pool: &'a cmd::Pool,
+    i_memory: RefCell<Option<memory::Memory>>
+}
+
+impl<'a> StagingBuffer<'a> {
+    /// `pool` **must be** created for the family of the `queue`
+    pub fn new(device: &'a dev::Device, queue: &'a queue::Queue, pool: &'a cmd::Pool) -> StagingBuffer<'a> {
+        StagingBuffer {
+            i_device: device,
+            i_queue: queue,
+            i_pool: pool,
+            i_memory: RefCell::new(None)
+        }
+    }
+
+    /// Copy `data` into the beginning of the `dst` buffer
+    ///
+    /// Returns [`MemoryError::TransferSize`](memory::MemoryError::TransferSize)
+    /// if `data` does not fit into `dst`
+    pub fn upload(&self, dst: memory::View, data: &[u8]) -> Result<(), memory::MemoryError> {
+        let size = data.len() as u64;
+
+        if size > dst.size() {
+            return Err(memory::MemoryError::TransferSize);
+        }
+
+        self.with_staging(size, &mut |staging: &memory::Memory| {
+            staging.access(&mut |bytes: &mut [u8]| {
+                bytes[..data.len()].copy_from_slice(data);
+            }, 0)?;
+
+            self.exec(&mut |cmd_buffer: &cmd::Buffer| {
+                cmd_buffer.copy_memory_range(&staging.view(0), 0, &dst, 0, size);
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Copy beginning of the `src` buffer into `data`
+    ///
+    /// Returns [`MemoryError::TransferSize`](memory::MemoryError::TransferSize)
+    /// if `src` is smaller than `data`
+    pub fn download(&self, src: memory::View, data: &mut [u8]) -> Result<(), memory::MemoryError> {
+        let size = data.len() as u64;
+
+        if size > src.size() {
+            return Err(memory::MemoryError::TransferSize);
+        }
+
+        self.with_staging(size, &mut |staging: &memory::Memory| {
+            self.exec(&mut |cmd_buffer: &cmd::Buffer| {
+                cmd_buffer.copy_memory_range(&src, 0, &staging.view(0), 0, size);
+
+                Ok(())
+            })?;
+
+            staging.access(&mut |bytes: &mut [u8]| {
+                data.copy_from_slice(&bytes[..data.len()]);
+            }, 0)
+        })
+    }
+
+    /// Copy tightly packed texels from `data` into base mip level of all layers of the `dst` image
+    ///
+    /// Image is transitioned from its current [layout](memory::ImageView::layout)
+    /// into `TRANSFER_DST_OPTIMAL` before copy and into `final_layout` after it
+    ///
+    /// Returns [`MemoryError::TransferSize`](memory::MemoryError::TransferSize)
+    /// if `data` is smaller than the base mip level
+    /// and [`MemoryError::Transfer`](memory::MemoryError::Transfer)
+    /// if queue family can not transfer the whole image
+    pub fn upload_image(
+        &self,
+        dst: memory::ImageView,
+        data: &[u8],
+        final_layout: memory::ImageLayout
+    ) -> Result<(), memory::MemoryError> {
+        let size = image_size(dst);
+
+        if (data.len() as u64) < size {
+            return Err(memory::MemoryError::TransferSize);
+        }
+
+        self.with_staging(size, &mut |staging: &memory::Memory| {
+            staging.access(&mut |bytes: &mut [u8]| {
+                bytes[..size as usize].copy_from_slice(&data[..size as usize]);
+            }, 0)?;
+
+            self.exec(&mut |cmd_buffer: &cmd::Buffer| {
+                cmd_buffer.set_image_barrier(
+                    dst,
+                    cmd::AccessType::NONE,
+                    cmd::AccessType::TRANSFER_WRITE,
+                    dst.layout(),
+                    memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    cmd::PipelineStage::TOP_OF_PIPE,
+                    cmd::PipelineStage::TRANSFER,
+                    cmd::QUEUE_FAMILY_IGNORED,
+                    cmd::QUEUE_FAMILY_IGNORED
+                );
+
+                cmd_buffer.try_copy_buffer_to_image(staging.view(0), dst)?;
+
+                if final_layout != memory::ImageLayout::TRANSFER_DST_OPTIMAL {
+                    cmd_buffer.set_image_barrier(
+                        dst,
+                        cmd::AccessType::TRANSFER_WRITE,
+                        cmd::AccessType::MEMORY_READ,
+                        memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+                        final_layout,
+                        cmd::PipelineStage::TRANSFER,
+                        cmd::PipelineStage::ALL_COMMANDS,
+                        cmd::QUEUE_FAMILY_IGNORED,
+                        cmd::QUEUE_FAMILY_IGNORED
+                    );
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Copy base mip level of all layers of the `src` image into `data` as tightly packed texels
+    ///
+    /// Image is transitioned from its current [layout](memory::ImageView::layout)
+    /// into `TRANSFER_SRC_OPTIMAL` before copy and back after it.
+    /// If current layout is `UNDEFINED` image stays in `TRANSFER_SRC_OPTIMAL`
+    ///
+    /// Returns [`MemoryError::TransferSize`](memory::MemoryError::TransferSize)
+    /// if `data` is smaller than the base mip level
+    pub fn download_image(&self, src: memory::ImageView, data: &mut [u8]) -> Result<(), memory::MemoryError> {
+        let size = image_size(src);
+
+        if (data.len() as u64) < size {
+            return Err(memory::MemoryError::TransferSize);
+        }
+
+        let old_layout = src.layout();
+
+        self.with_staging(size, &mut |staging: &memory::Memory| {
+            self.exec(&mut |cmd_buffer: &cmd::Buffer| {
+                cmd_buffer.set_image_barrier(
+                    src,
+                    cmd::AccessType::MEMORY_WRITE,
+                    cmd::AccessType::TRANSFER_READ,
+                    old_layout,
+                    memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    cmd::PipelineStage::ALL_COMMANDS,
+                    cmd::PipelineStage::TRANSFER,
+                    cmd::QUEUE_FAMILY_IGNORED,
+                    cmd::QUEUE_FAMILY_IGNORED
+                );
+
+                cmd_buffer.copy_image_to_buffer(src, staging.view(0));
+
+                if old_layout != memory::ImageLayout::UNDEFINED
+                    && old_layout != memory::ImageLayout::TRANSFER_SRC_OPTIMAL
+                {
+                    cmd_buffer.set_image_barrier(
+                        src,
+                        cmd::AccessType::TRANSFER_READ,
+                        cmd::AccessType::MEMORY_READ,
+                        memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                        old_layout,
+                        cmd::PipelineStage::TRANSFER,
+                        cmd::PipelineStage::ALL_COMMANDS,
+                        cmd::QUEUE_FAMILY_IGNORED,
+                        cmd::QUEUE_FAMILY_IGNORED
+                    );
+                }
+
+                Ok(())
+            })?;
+
+            staging.access(&mut |bytes: &mut [u8]| {
+                data[..size as usize].copy_from_slice(&bytes[..size as usize]);
+            }, 0)
+        })
+    }
+
+    /// Return size of the currently allocated staging memory in bytes
+    ///
+    /// Returns 0 if nothing was transferred yet
+    pub fn capacity(&self) -> u64 {
+        self.i_memory.borrow().as_ref().map_or(0, |staging| staging.view(0).size())
+    }
+
+    fn with_staging<F>(&self, size: u64, f: &mut F) -> Result<(), memory::MemoryError>
+    where
+        F: FnMut(&memory::Memory) -> Result<(), memory::MemoryError>
+    {
+        let mut staging = self.i_memory.borrow_mut();
+
+        if staging.as_ref().is_none_or(|mem| mem.view(0).size() < size) {
+            // Drop old memory before allocating the new one
+            *staging = None;
+
+            let buffer_cfg = memory::BufferCfg {
+                size: size.next_power_of_two(),
+                usage: memory::FULL_TRANSFER,
+                queue_families: &[self.i_queue.family()],
+                simultaneous_access: false,
+                count: 1
+            };
+
+            let mem_cfg = memory::MemoryCfg {
+                properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+                filter: &hw::any,
+                buffers: &[&buffer_cfg]
+            };
+
+            *staging = Some(memory::Memory::allocate(self.i_device, &mem_cfg)?);
+        }
+
+        match staging.as_ref() {
+            Some(mem) => f(mem),
+            None => Err(memory::MemoryError::NoSuitableMemory)
+        }
+    }
+
+    fn exec<F>(&self, record: &mut F) -> Result<(), memory::MemoryError>
+    where
+        F: FnMut(&cmd::Buffer) -> Result<(), cmd::BufferError>
+    {
+        let cmd_buffer = on_error!(self.i_pool.allocate(), |err| return Err(memory::MemoryError::Transfer(Box::new(err.into()))));
+
+        on_error!(record(&cmd_buffer), |err| return Err(memory::MemoryError::Transfer(Box::new(err.into()))));
+
+        let exec_buffer = on_error!(cmd_buffer.commit(), |err| return Err(memory::MemoryError::Transfer(Box::new(err.into()))));
+
+        let exec_info = queue::ExecInfo {
+            buffers: &[&exec_buffer],
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        on_error!(self.i_queue.exec(&exec_info), |err| return Err(memory::MemoryError::Transfer(Box::new(err.into()))));
+
+        Ok(())
+    }
+}
+
+/// Size in bytes of tightly packed base mip level of all layers
+pub(crate) fn image_size(view: memory::ImageView) -> u64 {
+    formats::required_staging_size(view.format(), view.extent())*(view.array_layers() as u64)
+}
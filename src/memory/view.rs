@@ -1,37 +1,91 @@
 //! Provide handler to the part of the [`Memory`](crate::memory::Memory)
 
-use crate::memory;
+use crate::{memory, dev, queue, cmd};
 
 use ash::vk;
 
 /// "Pointer-like" struct for the buffer
+///
+/// View covers the whole buffer or its part (see [`slice`](Self::slice))
 #[derive(Debug, Clone, Copy)]
 pub struct View<'a> {
     i_memory: &'a memory::Memory,
-    i_index: usize
+    i_index: usize,
+    i_offset: u64,
+    i_size: u64
 }
 
 impl<'a> View<'a> {
     pub(crate) fn new(storage: &memory::Memory, index: usize) -> View {
         View {
             i_memory: storage,
-            i_index: index
+            i_index: index,
+            i_offset: 0,
+            i_size: storage.sizes()[index]
         }
     }
 
-    /// Return offset of the buffer
+    /// Return view of `len` bytes starting from `offset` bytes of the current view
+    ///
+    /// Commands which take view
+    /// (e.g. [`copy_memory`](cmd::Buffer::copy_memory), [`set_barrier`](cmd::Buffer::set_barrier)
+    /// or [`BufferBinding`](crate::graphics::BufferBinding)) access only sliced range
+    ///
+    /// Returns [`MemoryError::InvalidOffset`](memory::MemoryError::InvalidOffset)
+    /// if range is empty or does not fit into the view
+    ///
+    /// For buffers with `UNIFORM_BUFFER` usage start of the range must be multiple of
+    /// [`hw::ubo_offset`](crate::hw::HWDevice::ubo_offset),
+    /// otherwise [`MemoryError::MisalignedOffset`](memory::MemoryError::MisalignedOffset) is returned
+    pub fn slice(&self, offset: u64, len: u64) -> Result<View<'a>, memory::MemoryError> {
+        if len == 0 || offset.checked_add(len).is_none_or(|end| end > self.i_size) {
+            return Err(memory::MemoryError::InvalidOffset { offset: offset.saturating_add(len), size: self.i_size });
+        }
+
+        let buffer_offset = self.i_offset + offset;
+
+        if self.usage().contains(memory::BufferUsageFlags::UNIFORM_BUFFER) {
+            let alignment = self.i_memory.region().core().limits().min_uniform_buffer_offset_alignment;
+
+            if !buffer_offset.is_multiple_of(alignment) {
+                return Err(memory::MemoryError::MisalignedOffset { offset: buffer_offset, alignment });
+            }
+        }
+
+        Ok(View {
+            i_memory: self.i_memory,
+            i_index: self.i_index,
+            i_offset: buffer_offset,
+            i_size: len
+        })
+    }
+
+    /// Return offset of the view in memory
+    ///
+    /// For [sliced](Self::slice) views it includes offset of the slice
     pub fn offset(&self) -> u64 {
-        self.i_memory.subregions()[self.i_index].offset
+        self.i_memory.subregions()[self.i_index].offset + self.i_offset
     }
 
-    /// Return requested size of the buffer
+    /// Return requested size of the buffer or size of the [slice](Self::slice)
     pub fn size(&self) -> u64 {
-        self.i_memory.sizes()[self.i_index]
+        self.i_size
     }
 
     /// Return size of the buffer with respect to the alignment
+    ///
+    /// For [sliced](Self::slice) views it is equal to [`size`](Self::size)
     pub fn allocated_size(&self) -> u64 {
-        self.i_memory.subregions()[self.i_index].allocated_size
+        if self.is_whole() {
+            self.i_memory.subregions()[self.i_index].allocated_size
+        } else {
+            self.i_size
+        }
+    }
+
+    /// Return usage flags the buffer was created with
+    pub fn usage(&self) -> memory::BufferUsageFlags {
+        self.i_memory.usage(self.i_index)
     }
 
     /// Map selected region of memory
@@ -66,7 +120,31 @@ impl<'a> View<'a> {
     where
         F: FnMut(&mut [T]),
     {
-        self.i_memory.access(f, self.i_index)
+        if self.is_whole() {
+            self.i_memory.access(f, self.i_index)
+        } else {
+            self.i_memory.access_range(f, self.i_index, self.i_offset, self.i_size)
+        }
+    }
+
+    /// Copy the whole buffer into host memory and return its content
+    ///
+    /// Unlike [`access`](Self::access) works for memory which is not HOST_VISIBLE
+    ///
+    /// Buffer **must be** created with `TRANSFER_SRC` usage
+    ///
+    /// See [`StagingBuffer::download`](memory::StagingBuffer::download)
+    pub fn download(
+        &self,
+        device: &dev::Device,
+        queue: &queue::Queue,
+        pool: &cmd::Pool
+    ) -> Result<Vec<u8>, memory::MemoryError> {
+        let mut data = vec![0u8; self.size() as usize];
+
+        memory::StagingBuffer::new(device, queue, pool).download(*self, &mut data)?;
+
+        Ok(data)
     }
 
     /// Unmap memory by view
@@ -79,6 +157,87 @@ impl<'a> View<'a> {
     pub(crate) fn buffer(&self) -> vk::Buffer {
         self.i_memory.buffer(self.i_index)
     }
+
+    /// Offset of the view from the start of [`buffer`](Self::buffer)
+    pub(crate) fn buffer_offset(&self) -> u64 {
+        self.i_offset
+    }
+
+    /// Turn `offset` and `range` relative to the view into range of the [`buffer`](Self::buffer)
+    ///
+    /// `vk::WHOLE_SIZE` is kept only if the view ends with the buffer
+    pub(crate) fn buffer_range(&self, offset: u64, range: u64) -> (u64, u64) {
+        let ends_with_buffer = self.i_offset + self.i_size == self.i_memory.sizes()[self.i_index];
+
+        if range == vk::WHOLE_SIZE && !ends_with_buffer {
+            (self.i_offset + offset, self.i_size.saturating_sub(offset))
+        } else {
+            (self.i_offset + offset, range)
+        }
+    }
+
+    pub(crate) fn keepalive(&self) -> dev::Keepalive {
+        self.i_memory.keepalive()
+    }
+
+    fn is_whole(&self) -> bool {
+        self.i_offset == 0 && self.i_size == self.i_memory.sizes()[self.i_index]
+    }
+}
+
+/// Views are equal if they point to the same range of the same buffer of the same [`Memory`](memory::Memory)
+impl PartialEq for View<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.i_memory, other.i_memory)
+        && self.i_index == other.i_index
+        && self.i_offset == other.i_offset
+        && self.i_size == other.i_size
+    }
+}
+
+impl Eq for View<'_> {}
+
+/// [`View`] of the index buffer with known type of indices
+///
+/// Use [`Memory::index_view`](memory::Memory::index_view) to create it
+/// and [`Buffer::bind_indices`](cmd::Buffer::bind_indices) to bind it
+#[derive(Debug, Clone, Copy)]
+pub struct IndexBuffer<'a> {
+    i_view: View<'a>,
+    i_type: memory::IndexBufferType
+}
+
+impl<'a> IndexBuffer<'a> {
+    /// Size of `view` should be multiple of index size
+    pub fn new(view: View<'a>, index_type: memory::IndexBufferType) -> IndexBuffer<'a> {
+        IndexBuffer {
+            i_view: view,
+            i_type: index_type
+        }
+    }
+
+    /// Return view to the index buffer
+    pub fn view(&self) -> View<'a> {
+        self.i_view
+    }
+
+    pub fn index_type(&self) -> memory::IndexBufferType {
+        self.i_type
+    }
+
+    /// Number of indices which fit into the buffer
+    pub fn count(&self) -> u64 {
+        self.i_view.size()/index_size(self.i_type)
+    }
+}
+
+/// Size of single index of the type in bytes
+pub(crate) fn index_size(it: memory::IndexBufferType) -> u64 {
+    match it {
+        vk::IndexType::UINT8_EXT => 1,
+        vk::IndexType::UINT16 => 2,
+        _ => 4
+    }
 }
 
 /// "Pointer-like" struct for the buffer
@@ -142,6 +301,28 @@ impl<'a> ImageView<'a> {
         self.i_memory.access(f, self.i_index)
     }
 
+    /// Copy base mip level of the image into host memory and return tightly packed texels
+    ///
+    /// Unlike [`access`](Self::access) works for images with `OPTIMAL` tiling
+    /// and memory which is not HOST_VISIBLE
+    ///
+    /// Image **must be** created with `TRANSFER_SRC` usage
+    /// and its [tracked layout](Self::layout) must match the actual one
+    ///
+    /// See [`StagingBuffer::download_image`](memory::StagingBuffer::download_image)
+    pub fn download(
+        &self,
+        device: &dev::Device,
+        queue: &queue::Queue,
+        pool: &cmd::Pool
+    ) -> Result<Vec<u8>, memory::MemoryError> {
+        let mut data = vec![0u8; memory::staging::image_size(*self) as usize];
+
+        memory::StagingBuffer::new(device, queue, pool).download_image(*self, &mut data)?;
+
+        Ok(data)
+    }
+
     /// Return image aspect
     ///
     /// For swapchain images returns `ImageAspect::COLOR`
@@ -149,6 +330,47 @@ impl<'a> ImageView<'a> {
         self.i_memory.info()[self.i_index].subresource.aspect_mask
     }
 
+    /// Return number of mip levels
+    pub fn mip_levels(&self) -> u32 {
+        self.i_memory.info()[self.i_index].subresource.level_count
+    }
+
+    /// Return number of array layers
+    pub fn array_layers(&self) -> u32 {
+        self.i_memory.info()[self.i_index].subresource.layer_count
+    }
+
+    /// Return image format
+    pub fn format(&self) -> memory::ImageFormat {
+        self.i_memory.info()[self.i_index].format
+    }
+
+    /// Return usage flags the image was created with
+    ///
+    /// For swapchain images returns [`SwapchainCfg::flags`](crate::swapchain::SwapchainCfg::flags)
+    pub fn usage(&self) -> memory::UsageFlags {
+        self.i_memory.info()[self.i_index].usage
+    }
+
+    /// Return layout which image will have after execution of already recorded commands
+    ///
+    /// Initially it is [`ImageCfg::layout`](memory::ImageCfg::layout)
+    /// (`UNDEFINED` for swapchain images)
+    ///
+    /// Layout is updated by [`set_image_barrier`](crate::cmd::Buffer::set_image_barrier)
+    /// and other commands which perform layout transition
+    pub fn layout(&self) -> memory::ImageLayout {
+        self.i_memory.image_layout(self.i_index)
+    }
+
+    /// Override tracked layout
+    ///
+    /// Use it when layout is changed by something library does not track
+    /// (e.g. by render pass [`final layout`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkAttachmentDescription.html))
+    pub fn set_layout(&self, layout: memory::ImageLayout) {
+        self.i_memory.set_layout(self.i_index, layout);
+    }
+
     /// Unmap memory by view
     ///
     /// Use for [`map_memory`](Self::map_memory)
@@ -171,6 +393,10 @@ impl<'a> ImageView<'a> {
         }
     }
 
+    pub(crate) fn layout_cell(&self) -> memory::LayoutCell {
+        self.i_memory.layout_cell(self.i_index)
+    }
+
     pub(crate) fn image_view(&self) -> vk::ImageView {
         self.i_memory.image_views()[self.i_index]
     }
@@ -178,4 +404,16 @@ impl<'a> ImageView<'a> {
     pub(crate) fn image(&self) -> vk::Image {
         self.i_memory.images()[self.i_index]
     }
-}
\ No newline at end of file
+
+    pub(crate) fn keepalive(&self) -> dev::Keepalive {
+        self.i_memory.keepalive()
+    }
+}
+/// Views are equal if they point to the same image of the same [`ImageMemory`](memory::ImageMemory)
+impl PartialEq for ImageView<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.i_memory, other.i_memory) && self.i_index == other.i_index
+    }
+}
+
+impl Eq for ImageView<'_> {}
@@ -7,55 +7,236 @@ use std::sync::Arc;
 use std::error::Error;
 use std::marker::PhantomData;
 
-use crate::{on_error_ret, data_ptr};
+use crate::{on_error, data_ptr};
 use crate::{dev, cmd, sync, swapchain};
 
+/// Information about single submission
+///
+/// All [`buffers`](ExecInfo::buffers) are submitted in the given order
+/// and start execution in that order
+///
+/// However order of *completion* is not guaranteed:
+/// if one buffer depends on results of the previous one
+/// synchronization (e.g. [`Buffer::set_barrier`](crate::cmd::Buffer::set_barrier)) is required
+///
+/// If [`fence`](ExecInfo::fence) is `None` fence is created for every submission
+///
+/// Otherwise provided fence is signaled when execution is finished.
+/// It **must be** in unsignaled state and it is not reset after wait
 pub struct ExecInfo<'a> {
-    pub buffer: &'a cmd::ExecutableBuffer,
+    pub buffers: &'a [&'a cmd::ExecutableBuffer],
     pub wait_stage: cmd::PipelineStage,
     pub timeout: u64,
     pub wait: &'a [&'a sync::Semaphore],
     pub signal: &'a [&'a sync::Semaphore],
+    pub fence: Option<&'a sync::Fence>,
 }
 
+/// Present single image
+///
+/// Shorthand for [`MultiPresentInfo`] with single swapchain
 pub struct PresentInfo<'a, 'b : 'a> {
     pub swapchain: &'a swapchain::Swapchain,
     pub image_index: u32,
     pub wait: &'a [&'b sync::Semaphore]
 }
 
+/// Present images of several swapchains with single `vkQueuePresentKHR` call
+///
+/// Each pair is swapchain and index of its image
+///
+/// Every swapchain must be created from the same device
+pub struct MultiPresentInfo<'a, 'b : 'a> {
+    pub images: &'a [(&'b swapchain::Swapchain, u32)],
+    pub wait: &'a [&'b sync::Semaphore]
+}
+
 #[derive(Debug)]
 pub enum QueueError {
     /// Failed to
     /// [submit](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkQueueSubmit.html)
     /// queue
-    Execution,
+    Execution(vk::Result),
     /// Failed to
     /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateFence.html)
     /// fence
-    Fence,
+    Fence(vk::Result),
     /// Execution time exceed max time
     Timeout,
     /// Failed to
+    /// [wait](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkWaitForFences.html)
+    /// for submission
+    Wait(vk::Result),
+    /// [`exec`](Queue::exec) time exceeded [`ExecInfo::timeout`] but submission is still executed
+    ///
+    /// Buffers of the submission and resources used by them are kept alive by the retire point,
+    /// so they may be dropped by the caller
+    Unfinished(RetirePoint),
+    /// Failed to
     /// [present](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkQueuePresentKHR.html)
     /// image
-    Present
+    Present(vk::Result),
+    /// Failed to
+    /// [wait](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkQueueWaitIdle.html)
+    /// queue to become idle
+    WaitIdle(vk::Result),
+    /// Device was lost during execution, see [`Device::is_lost`](crate::dev::Device::is_lost)
+    DeviceLost,
+    /// Queue with such family and index was not created with [`Device`](crate::dev::Device)
+    InvalidQueue {
+        family_index: u32,
+        queue_index: u32
+    }
 }
 
 impl fmt::Display for QueueError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let err_msg = match self {
-            QueueError::Execution => {
+            QueueError::Execution(_) => {
                 "Failed to submit queue (vkQueueSubmit call failed)"
             },
-            QueueError::Fence => {
+            QueueError::Fence(_) => {
                 "Failed to create fence (vkCreateFence call failed)"
             },
             QueueError::Timeout => {
                 "Execution time exceed max time"
             },
-            QueueError::Present => {
+            QueueError::Wait(_) => {
+                "Failed to wait for submission (vkWaitForFences call failed)"
+            },
+            QueueError::Unfinished(_) => {
+                "Execution time exceed max time, submission is still executed"
+            },
+            QueueError::Present(_) => {
                 "Failed to present image"
+            },
+            QueueError::WaitIdle(_) => {
+                "Failed to wait queue (vkQueueWaitIdle call failed)"
+            },
+            QueueError::DeviceLost => {
+                "Device was lost (VK_ERROR_DEVICE_LOST)"
+            },
+            QueueError::InvalidQueue { family_index, queue_index } => {
+                return write!(f, "{:?}", format!("Queue {} of family {} was not created", queue_index, family_index));
+            }
+        };
+
+        write!(f, "{:?}", err_msg)
+    }
+}
+
+impl Error for QueueError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            QueueError::Execution(result)
+            | QueueError::Fence(result)
+            | QueueError::Wait(result)
+            | QueueError::Present(result)
+            | QueueError::WaitIdle(result) => Some(result),
+            _ => None
+        }
+    }
+}
+
+/// Map result of the failed call to [`QueueError`]
+///
+/// `VK_ERROR_DEVICE_LOST` marks device as [lost](crate::dev::Device::is_lost)
+/// and turns into [`QueueError::DeviceLost`], other results are passed to `err`
+#[doc(hidden)]
+pub fn queue_error(core: &dev::Core, result: vk::Result, err: fn(vk::Result) -> QueueError) -> QueueError {
+    if core.observe(result) {
+        QueueError::DeviceLost
+    } else {
+        err(result)
+    }
+}
+
+/// Map result of the failed `vkWaitForFences` call to [`QueueError`]
+///
+/// Only `VK_TIMEOUT` turns into [`QueueError::Timeout`]
+fn wait_error(core: &dev::Core, result: vk::Result) -> QueueError {
+    match result {
+        vk::Result::TIMEOUT => QueueError::Timeout,
+        _ => queue_error(core, result, QueueError::Wait)
+    }
+}
+
+/// Successful result of [`present`](crate::queue::Queue::present)
+/// or [`next_image`](crate::swapchain::Swapchain::next_image)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresentSuccess {
+    /// Swapchain matches the surface properties exactly
+    Optimal,
+    /// Swapchain no longer matches the surface properties exactly
+    /// but still can be used
+    ///
+    /// It is recommended to recreate swapchain
+    Suboptimal
+}
+
+/// Result of presentation for single swapchain
+pub type PresentResult = Result<PresentSuccess, PresentError>;
+
+/// Convert `VkResult` of single swapchain into [`PresentResult`]
+#[doc(hidden)]
+pub fn present_result(result: vk::Result) -> PresentResult {
+    match result {
+        vk::Result::SUCCESS => Ok(PresentSuccess::Optimal),
+        vk::Result::SUBOPTIMAL_KHR => Ok(PresentSuccess::Suboptimal),
+        err => Err(PresentError::from(err))
+    }
+}
+
+impl From<bool> for PresentSuccess {
+    /// Convert `suboptimal` flag returned by ash into [`PresentSuccess`]
+    fn from(suboptimal: bool) -> Self {
+        if suboptimal {
+            PresentSuccess::Suboptimal
+        } else {
+            PresentSuccess::Optimal
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresentError {
+    /// Surface has changed and swapchain is no longer compatible with it
+    ///
+    /// Swapchain **must be** recreated
+    ///
+    /// See [`VK_ERROR_OUT_OF_DATE_KHR`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkResult.html)
+    OutOfDate,
+    /// Surface is no longer available
+    ///
+    /// See [`VK_ERROR_SURFACE_LOST_KHR`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkResult.html)
+    SurfaceLost,
+    /// Failed to
+    /// [present](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkQueuePresentKHR.html)
+    /// image
+    Present(vk::Result)
+}
+
+impl From<vk::Result> for PresentError {
+    fn from(result: vk::Result) -> Self {
+        match result {
+            vk::Result::ERROR_OUT_OF_DATE_KHR => PresentError::OutOfDate,
+            vk::Result::ERROR_SURFACE_LOST_KHR => PresentError::SurfaceLost,
+            _ => PresentError::Present(result)
+        }
+    }
+}
+
+impl fmt::Display for PresentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let err_msg = match self {
+            PresentError::OutOfDate => {
+                "Swapchain is out of date and must be recreated"
+            },
+            PresentError::SurfaceLost => {
+                "Surface is no longer available"
+            },
+            PresentError::Present(_) => {
+                "Failed to present image (vkQueuePresentKHR call failed)"
             }
         };
 
@@ -63,7 +244,14 @@ impl fmt::Display for QueueError {
     }
 }
 
-impl Error for QueueError {}
+impl Error for PresentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PresentError::Present(result) => Some(result),
+            _ => None
+        }
+    }
+}
 
 /// Information about what queue to allocate
 ///
@@ -72,6 +260,8 @@ impl Error for QueueError {}
 ///
 /// [`queue_index`](crate::queue::QueueCfg::queue_index)
 /// **must be** less than related queue count
+///
+/// Otherwise [`Queue::new`] returns [`QueueError::InvalidQueue`]
 #[doc = "See more: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkGetDeviceQueue.html>"]
 #[derive(Debug)]
 pub struct QueueCfg {
@@ -79,6 +269,14 @@ pub struct QueueCfg {
     pub queue_index: u32,
 }
 
+/// Handle to the device queue
+///
+/// # Threading
+///
+/// Submission and presentation require external synchronization of the queue,
+/// so they are serialized between all handles of the same queue (see [`Device::get_queue`](dev::Device::get_queue))
+///
+/// To submit buffers recorded on several threads send them to the thread which owns the queue
 pub struct Queue {
     i_core: Arc<dev::Core>,
     i_queue: vk::Queue,
@@ -87,19 +285,39 @@ pub struct Queue {
 }
 
 impl Queue {
-    pub fn new(dev: &dev::Device, cfg: &QueueCfg) -> Queue {
-        Queue {
+    pub fn new(dev: &dev::Device, cfg: &QueueCfg) -> Result<Queue, QueueError> {
+        if cfg.queue_index >= dev.queue_count(cfg.family_index) {
+            return Err(QueueError::InvalidQueue {
+                family_index: cfg.family_index,
+                queue_index: cfg.queue_index
+            });
+        }
+
+        Ok(Queue {
             i_core: dev.core().clone(),
             i_queue: unsafe {
                 dev.device().get_device_queue(cfg.family_index, cfg.queue_index)
             },
             i_family: cfg.family_index,
-            i_index: cfg.queue_index
-        }
+            i_index: cfg.queue_index,
+        })
     }
 
-    /// Execute selected buffer
-    pub fn exec(&self, info: &ExecInfo) -> Result<(), QueueError> {
+    /// Submit selected buffer without waiting for its completion
+    ///
+    /// Returned [`SubmitHandle`] owns fence related to submission
+    ///
+    /// Buffer must stay alive until handle is waited or dropped
+    /// or until its [retire point](SubmitHandle::into_retire_point) is reached
+    ///
+    /// If [`ExecInfo::fence`] is set handle waits for it but does not destroy it
+    pub fn submit<'a>(&self, info: &ExecInfo<'a>) -> Result<SubmitHandle<'a>, QueueError> {
+        if let Some(fence) = info.fence {
+            self.submit_raw(info, fence.fence())?;
+
+            return Ok(self.borrowed_handle(fence.fence()));
+        }
+
         let dev = self.i_core.device();
 
         let fence_info = vk::FenceCreateInfo {
@@ -109,42 +327,221 @@ impl Queue {
             _marker: PhantomData,
         };
 
-        let fence = on_error_ret!(
+        let fence = on_error!(
             unsafe { dev.create_fence(&fence_info, self.i_core.allocator()) },
-            QueueError::Fence
+            |err| return Err(QueueError::Fence(err))
         );
 
-        let wait_sems: Vec<vk::Semaphore> = info.wait.iter().map(|s| s.semaphore()).collect();
-        let sign_sems: Vec<vk::Semaphore> = info.signal.iter().map(|s| s.semaphore()).collect();
+        self.i_core.created(dev::ObjectType::Fence, 1);
+
+        if let Err(err) = self.submit_raw(info, fence) {
+            unsafe { dev.destroy_fence(fence, self.i_core.allocator()) };
+            self.i_core.destroyed(dev::ObjectType::Fence, 1);
+            return Err(err);
+        }
+
+        Ok(self.owned_handle(fence))
+    }
+
+    /// Submit selected buffer and signal `fence` when execution is finished
+    ///
+    /// Does not wait for completion
+    ///
+    /// `fence` **must be** in unsignaled state
+    pub fn submit_with_fence(&self, info: &ExecInfo, fence: &sync::Fence) -> Result<(), QueueError> {
+        self.submit_raw(info, fence.fence())
+    }
+
+    /// Handle which waits for the caller's `fence`
+    fn borrowed_handle<'a>(&self, fence: vk::Fence) -> SubmitHandle<'a> {
+        SubmitHandle {
+            i_core: self.i_core.clone(),
+            i_fence: fence,
+            i_detached: false,
+            i_point: None,
+            _marker: PhantomData
+        }
+    }
+
+    /// Handle which destroys `fence` with the last copy of its retire point
+    fn owned_handle<'a>(&self, fence: vk::Fence) -> SubmitHandle<'a> {
+        SubmitHandle {
+            i_core: self.i_core.clone(),
+            i_fence: fence,
+            i_detached: false,
+            i_point: Some(RetirePoint(Arc::new(RetireFence {
+                i_core: self.i_core.clone(),
+                i_fence: fence,
+                i_resources: Vec::new()
+            }))),
+            _marker: PhantomData
+        }
+    }
+
+    fn submit_raw(&self, info: &ExecInfo, fence: vk::Fence) -> Result<(), QueueError> {
+        self.submit_batch_raw(std::slice::from_ref(info), fence)
+    }
+
+    fn submit_batch_raw(&self, infos: &[ExecInfo], fence: vk::Fence) -> Result<(), QueueError> {
+        let wait_sems: Vec<Vec<vk::Semaphore>> = infos
+            .iter()
+            .map(|info| info.wait.iter().map(|s| s.semaphore()).collect())
+            .collect();
+
+        // Stage mask is defined per wait semaphore
+        let wait_stages: Vec<Vec<cmd::PipelineStage>> = infos
+            .iter()
+            .map(|info| vec![info.wait_stage; info.wait.len()])
+            .collect();
+
+        let sign_sems: Vec<Vec<vk::Semaphore>> = infos
+            .iter()
+            .map(|info| info.signal.iter().map(|s| s.semaphore()).collect())
+            .collect();
+
+        let cmd_buffers: Vec<Vec<vk::CommandBuffer>> = infos
+            .iter()
+            .map(|info| info.buffers.iter().map(|b| *b.buffer()).collect())
+            .collect();
+
+        let submit_infos: Vec<vk::SubmitInfo> = (0..infos.len())
+            .map(|i| vk::SubmitInfo {
+                s_type: vk::StructureType::SUBMIT_INFO,
+                p_next: ptr::null(),
+                wait_semaphore_count: wait_sems[i].len() as u32,
+                p_wait_semaphores: data_ptr!(wait_sems[i]),
+                p_wait_dst_stage_mask: data_ptr!(wait_stages[i]),
+                command_buffer_count: cmd_buffers[i].len() as u32,
+                p_command_buffers: data_ptr!(cmd_buffers[i]),
+                signal_semaphore_count: sign_sems[i].len() as u32,
+                p_signal_semaphores: data_ptr!(sign_sems[i]),
+                _marker: PhantomData,
+            })
+            .collect();
+
+        let _lock = self.i_core.lock_queue(self.i_family, self.i_index);
+
+        on_error!(
+            unsafe { self.i_core.device().queue_submit(self.i_queue, &submit_infos, fence) },
+            |err| return Err(queue_error(&self.i_core, err, QueueError::Execution))
+        );
+
+        Ok(())
+    }
+
+    /// Execute selected buffer
+    ///
+    /// Blocks until buffer execution is finished or [`ExecInfo::timeout`] is exceeded
+    ///
+    /// On timeout returns [`QueueError::Unfinished`] with retire point of the submission
+    /// which keeps [`ExecInfo::buffers`] alive.
+    ///
+    /// Semaphores and [`ExecInfo::fence`] can not be kept alive by the point,
+    /// so if any of them is set call still waits for the submission and returns [`QueueError::Timeout`]
+    ///
+    /// Nothing is presented so surface and swapchain are not required
+    pub fn exec(&self, info: &ExecInfo) -> Result<(), QueueError> {
+        Queue::finish(self.submit(info)?, std::slice::from_ref(info), info.timeout)
+    }
+
+    /// Wait for `handle` and detach it on timeout if nothing but buffers of `infos` must stay alive
+    ///
+    /// Otherwise `handle` waits for the submission on drop
+    fn finish(handle: SubmitHandle, infos: &[ExecInfo], timeout: u64) -> Result<(), QueueError> {
+        let detachable = handle.i_point.is_some()
+            && infos.iter().all(|info| info.wait.is_empty() && info.signal.is_empty());
+
+        match handle.wait(timeout) {
+            Err(QueueError::Timeout) if detachable => {
+                // Every buffer is kept alive by the point
+                let mut point = unsafe { handle.into_retire_point() }.expect("Fence must be owned by the handle");
+
+                for buffer in infos.iter().flat_map(|info| info.buffers) {
+                    point.keep(buffer.keepalive());
+                }
 
-        let submit_info = vk::SubmitInfo {
-            s_type: vk::StructureType::SUBMIT_INFO,
+                Err(QueueError::Unfinished(point))
+            },
+            result => result
+        }
+    }
+
+    /// Execute `buffer` which may be submitted only once
+    ///
+    /// `buffer` is executed after [`ExecInfo::buffers`] within the same submission
+    ///
+    /// Blocks until execution is finished or [`ExecInfo::timeout`] is exceeded.
+    /// Buffer is freed after that
+    ///
+    /// On [`QueueError::Unfinished`] `buffer` is kept alive by the returned retire point
+    pub fn exec_once(&self, buffer: cmd::OneTimeBuffer, info: &ExecInfo) -> Result<(), QueueError> {
+        let buffers: Vec<&cmd::ExecutableBuffer> = info
+            .buffers
+            .iter()
+            .copied()
+            .chain(std::iter::once(buffer.executable()))
+            .collect();
+
+        self.exec(&ExecInfo {
+            buffers: &buffers,
+            ..*info
+        })
+    }
+
+    /// Execute several submissions with single
+    /// [`vkQueueSubmit`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkQueueSubmit.html) call
+    ///
+    /// Submissions start execution in the order of `infos`
+    ///
+    /// Blocks until all submissions are finished or the largest [`ExecInfo::timeout`] is exceeded
+    ///
+    /// Only [`fence`](ExecInfo::fence) of the last submission is used
+    ///
+    /// Timeout is handled the same way as in [`exec`](Queue::exec)
+    pub fn exec_batch(&self, infos: &[ExecInfo]) -> Result<(), QueueError> {
+        let timeout = infos.iter().map(|info| info.timeout).max().unwrap_or(0);
+
+        if let Some(fence) = infos.last().and_then(|info| info.fence) {
+            self.submit_batch_raw(infos, fence.fence())?;
+
+            return Queue::finish(self.borrowed_handle(fence.fence()), infos, timeout);
+        }
+
+        let dev = self.i_core.device();
+
+        let fence_info = vk::FenceCreateInfo {
+            s_type: vk::StructureType::FENCE_CREATE_INFO,
             p_next: ptr::null(),
-            wait_semaphore_count: wait_sems.len() as u32,
-            p_wait_semaphores: data_ptr!(wait_sems),
-            p_wait_dst_stage_mask: &info.wait_stage,
-            command_buffer_count: 1,
-            p_command_buffers: info.buffer.buffer(),
-            signal_semaphore_count: sign_sems.len() as u32,
-            p_signal_semaphores: data_ptr!(sign_sems),
+            flags:  vk::FenceCreateFlags::empty(),
             _marker: PhantomData,
         };
 
-        unsafe {
-            if dev.queue_submit(self.i_queue, &[submit_info], fence).is_err() {
-               dev.destroy_fence(fence, self.i_core.allocator());
-               return Err(QueueError::Execution);
-            }
-        }
+        let fence = on_error!(
+            unsafe { dev.create_fence(&fence_info, self.i_core.allocator()) },
+            |err| return Err(QueueError::Fence(err))
+        );
 
-        unsafe {
-            if dev.wait_for_fences(&[fence], true, info.timeout).is_err() {
-               dev.destroy_fence(fence, self.i_core.allocator());
-               return Err(QueueError::Timeout);
-            }
+        self.i_core.created(dev::ObjectType::Fence, 1);
+
+        if let Err(err) = self.submit_batch_raw(infos, fence) {
+            unsafe { dev.destroy_fence(fence, self.i_core.allocator()) };
+            self.i_core.destroyed(dev::ObjectType::Fence, 1);
+            return Err(err);
         }
 
-        unsafe { dev.destroy_fence(fence, self.i_core.allocator()) };
+        Queue::finish(self.owned_handle(fence), infos, timeout)
+    }
+
+    /// Wait until all submitted work on the queue is finished
+    ///
+    #[doc = "See more: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkQueueWaitIdle.html>"]
+    pub fn wait_idle(&self) -> Result<(), QueueError> {
+        let _lock = self.i_core.lock_queue(self.i_family, self.i_index);
+
+        on_error!(
+            unsafe { self.i_core.device().queue_wait_idle(self.i_queue) },
+            |err| return Err(queue_error(&self.i_core, err, QueueError::WaitIdle))
+        );
 
         Ok(())
     }
@@ -160,24 +557,61 @@ impl Queue {
     }
 
     /// Present selected image from swapchain
-    pub fn present(&self, info: &PresentInfo) -> Result<(), QueueError> {
+    ///
+    /// Returns [`PresentSuccess::Suboptimal`] if swapchain should be recreated
+    /// and [`PresentError::OutOfDate`] if swapchain must be recreated
+    ///
+    /// Same as [`present_multiple`](Queue::present_multiple) with single swapchain
+    pub fn present(&self, info: &PresentInfo) -> PresentResult {
+        let multi_info = MultiPresentInfo {
+            images: &[(info.swapchain, info.image_index)],
+            wait: info.wait
+        };
+
+        self.present_multiple(&multi_info).remove(0)
+    }
+
+    /// Present several images at once
+    ///
+    /// Returns result for every swapchain in the same order as [`MultiPresentInfo::images`]
+    ///
+    /// So one swapchain may be out of date while others are presented
+    pub fn present_multiple(&self, info: &MultiPresentInfo) -> Vec<PresentResult> {
+        let loader = match info.images.first() {
+            Some((swapchain, _)) => swapchain.loader(),
+            None => return Vec::new()
+        };
+
         let semaphores: Vec<vk::Semaphore> = info.wait.iter().map(|s| s.semaphore()).collect();
+        let swapchains: Vec<vk::SwapchainKHR> = info.images.iter().map(|(s, _)| s.swapchain()).collect();
+        let indices: Vec<u32> = info.images.iter().map(|(_, i)| *i).collect();
+        // Not filled results are replaced with the result of the whole call
+        let mut results: Vec<vk::Result> = vec![vk::Result::ERROR_UNKNOWN; swapchains.len()];
 
         let present_info:vk::PresentInfoKHR = vk::PresentInfoKHR {
             s_type: vk::StructureType::PRESENT_INFO_KHR,
             p_next: ptr::null(),
             wait_semaphore_count: semaphores.len() as u32,
             p_wait_semaphores: data_ptr!(semaphores),
-            swapchain_count: 1,
-            p_swapchains: &info.swapchain.swapchain(),
-            p_image_indices: &info.image_index,
-            p_results: ptr::null_mut(),
+            swapchain_count: swapchains.len() as u32,
+            p_swapchains: swapchains.as_ptr(),
+            p_image_indices: indices.as_ptr(),
+            p_results: results.as_mut_ptr(),
             _marker: PhantomData,
         };
 
-        on_error_ret!(unsafe { info.swapchain.loader().queue_present(self.i_queue, &present_info) }, QueueError::Present);
+        let _lock = self.i_core.lock_queue(self.i_family, self.i_index);
 
-        Ok(())
+        let call_result = match unsafe { loader.queue_present(self.i_queue, &present_info) } {
+            Ok(_) => vk::Result::SUCCESS,
+            Err(result) => result
+        };
+
+        results
+            .into_iter()
+            .map(|result| if result == vk::Result::ERROR_UNKNOWN { call_result } else { result })
+            .map(present_result)
+            .collect()
     }
 }
 
@@ -188,4 +622,141 @@ impl fmt::Debug for Queue {
         .field("i_queue", &(&self.i_queue as *const vk::Queue))
         .finish()
     }
-}
\ No newline at end of file
+}
+
+/// Pending submission returned by [`Queue::submit`]
+///
+/// On drop handle waits until submission is finished
+/// unless it was converted [into retire point](SubmitHandle::into_retire_point)
+pub struct SubmitHandle<'a> {
+    i_core: Arc<dev::Core>,
+    i_fence: vk::Fence,
+    /// Submission is not waited on drop
+    i_detached: bool,
+    /// Owner of the fence created for submission, `None` if fence is owned by the caller
+    i_point: Option<RetirePoint>,
+    _marker: PhantomData<&'a cmd::ExecutableBuffer>
+}
+
+impl<'a> SubmitHandle<'a> {
+    /// Stop tracking submission without waiting for it
+    ///
+    /// Returned [`RetirePoint`] is reached when submission is finished
+    ///
+    /// If [`ExecInfo::fence`] was set returns `None` since fence is owned by the caller
+    /// and nothing is waited
+    ///
+    /// # Safety
+    ///
+    /// Buffers of the submission and resources used by them **must** stay alive
+    /// until the point is reached (or until the caller's fence is signaled),
+    /// e.g. moved into [`DeletionQueue`](crate::dev::DeletionQueue)
+    pub unsafe fn into_retire_point(mut self) -> Option<RetirePoint> {
+        self.i_detached = true;
+        self.i_point.take()
+    }
+
+    /// Wait until submission is finished or `timeout` (in nanoseconds) is exceeded
+    pub fn wait(&self, timeout: u64) -> Result<(), QueueError> {
+        on_error!(
+            unsafe { self.i_core.device().wait_for_fences(&[self.i_fence], true, timeout) },
+            |err| return Err(wait_error(&self.i_core, err))
+        );
+
+        Ok(())
+    }
+
+    /// Is submission finished
+    pub fn is_done(&self) -> bool {
+        unsafe { self.i_core.device().get_fence_status(self.i_fence).unwrap_or(false) }
+    }
+}
+
+impl<'a> Drop for SubmitHandle<'a> {
+    fn drop(&mut self) {
+        if self.i_detached {
+            return;
+        }
+
+        unsafe {
+            let _ = self.i_core.device().wait_for_fences(&[self.i_fence], true, u64::MAX);
+        }
+    }
+}
+
+struct RetireFence {
+    i_core: Arc<dev::Core>,
+    i_fence: vk::Fence,
+    /// Released after the fence is destroyed
+    i_resources: Vec<dev::Keepalive>,
+}
+
+impl Drop for RetireFence {
+    fn drop(&mut self) {
+        // Fence must not be destroyed while submission is pending
+        unsafe {
+            let _ = self.i_core.device().wait_for_fences(&[self.i_fence], true, u64::MAX);
+
+            self.i_core.device().destroy_fence(self.i_fence, self.i_core.allocator());
+        }
+
+        self.i_core.destroyed(dev::ObjectType::Fence, 1);
+    }
+}
+
+/// Point on the queue timeline which is reached when submission is finished
+///
+/// Obtained from [`SubmitHandle::into_retire_point`]
+///
+/// Point may be cloned, submission fence is destroyed with the last copy.
+/// If the last copy is dropped before the point is reached drop waits for the submission
+#[derive(Clone)]
+pub struct RetirePoint(Arc<RetireFence>);
+
+impl RetirePoint {
+    /// Keep `resource` alive until the point is dropped
+    ///
+    /// Point must not be cloned yet
+    fn keep(&mut self, resource: dev::Keepalive) {
+        Arc::get_mut(&mut self.0)
+        .expect("Retire point must not be shared")
+        .i_resources
+        .push(resource);
+    }
+
+    /// Is submission finished
+    ///
+    /// Lost device is treated as finished since nothing is executed anymore
+    pub fn is_reached(&self) -> bool {
+        match unsafe { self.0.i_core.device().get_fence_status(self.0.i_fence) } {
+            Ok(status) => status,
+            Err(err) => self.0.i_core.observe(err)
+        }
+    }
+
+    /// Wait until submission is finished or `timeout` (in nanoseconds) is exceeded
+    pub fn wait(&self, timeout: u64) -> Result<(), QueueError> {
+        on_error!(
+            unsafe { self.0.i_core.device().wait_for_fences(&[self.0.i_fence], true, timeout) },
+            |err| return Err(wait_error(&self.0.i_core, err))
+        );
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for RetirePoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetirePoint")
+        .field("i_fence", &self.0.i_fence)
+        .finish()
+    }
+}
+
+impl<'a> fmt::Debug for SubmitHandle<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubmitHandle")
+        .field("i_fence", &self.i_fence)
+        .finish()
+    }
+}
@@ -3,14 +3,14 @@
 use ash::vk;
 use ash::util::read_spv;
 
-use crate::dev;
-use crate::{on_error_ret, on_option_ret};
+use crate::{dev, graphics, spirv};
+use crate::{on_error, on_error_ret, on_option_ret, data_ptr};
 
 use std::{ptr, mem, fmt};
 use std::error::Error;
 use std::sync::Arc;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::ffi::CString;
 use std::marker::PhantomData;
 
@@ -32,22 +32,142 @@ use shaderc;
 /// let compute_shader_type = shader::Kind::Compute;
 ///
 /// let geometry_shader_type = shader::Kind::Geometry;
+///
+/// let tess_control_shader_type = shader::Kind::TessControl;
+///
+/// let tess_eval_shader_type = shader::Kind::TessEvaluation;
 /// ```
 pub type Kind = shaderc::ShaderKind;
 
+/// Single specialization constant
+///
+/// `constant_id` is the same as defined by `constant_id` layout specifier in a shader source code
+///
+/// `offset` and `size` define bytes of the constant within [`SpecializationInfo::data`]
+#[derive(Debug, Clone, Copy)]
+pub struct SpecializationEntry {
+    pub constant_id: u32,
+    pub offset: u32,
+    pub size: usize,
+}
+
+/// Values of specialization constants for single shader stage
+///
+/// Example
+///
+/// ```ignore
+///     // part of compute shader code
+///     layout(local_size_x_id = 0) in;
+///     layout(constant_id = 1) const uint VALUE = 0;
+/// ```
+/// And corresponding configuration
+/// ```
+/// use libvktypes::shader;
+///
+/// let data: [u32; 2] = [64, 42];
+///
+/// let entries = [
+///     shader::SpecializationEntry {
+///         constant_id: 0,
+///         offset: 0,
+///         size: std::mem::size_of::<u32>(),
+///     },
+///     shader::SpecializationEntry {
+///         constant_id: 1,
+///         offset: std::mem::size_of::<u32>() as u32,
+///         size: std::mem::size_of::<u32>(),
+///     }
+/// ];
+///
+/// let bytes: Vec<u8> = data.iter().flat_map(|x| x.to_ne_bytes()).collect();
+///
+/// let info = shader::SpecializationInfo {
+///     entries: &entries,
+///     data: &bytes,
+/// };
+/// ```
+#[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkSpecializationInfo.html>"]
+#[derive(Debug, Clone, Copy)]
+pub struct SpecializationInfo<'a> {
+    pub entries: &'a [SpecializationEntry],
+    pub data: &'a [u8],
+}
+
+impl<'a> SpecializationInfo<'a> {
+    /// Does every entry lie within [`data`](Self::data)
+    ///
+    /// Pipelines with invalid specialization are not created
+    pub fn is_valid(&self) -> bool {
+        self.entries.iter().all(|entry| {
+            (entry.offset as usize)
+                .checked_add(entry.size)
+                .is_some_and(|end| end <= self.data.len())
+        })
+    }
+
+    pub(crate) fn map_entries(&self) -> Vec<vk::SpecializationMapEntry> {
+        self.entries.iter().map(|entry| {
+            vk::SpecializationMapEntry {
+                constant_id: entry.constant_id,
+                offset: entry.offset,
+                size: entry.size,
+            }
+        })
+        .collect()
+    }
+
+    pub(crate) fn raw<'b>(&'b self, entries: &'b [vk::SpecializationMapEntry]) -> vk::SpecializationInfo<'b> {
+        vk::SpecializationInfo {
+            map_entry_count: entries.len() as u32,
+            p_map_entries: data_ptr!(entries),
+            data_size: self.data.len(),
+            p_data: data_ptr!(self.data) as *const std::ffi::c_void,
+            _marker: PhantomData,
+        }
+    }
+}
+
 pub struct ShaderCfg<'a> {
     pub path: &'a str,
     pub entry: &'a str,
+    /// Directories where `#include` files are searched
+    ///
+    /// `#include "file"` is searched relative to the including file first,
+    /// `#include <file>` only in these directories
+    ///
+    /// Used only for `glsl` source code
+    pub include_dirs: &'a [&'a str],
 }
 
 #[derive(Debug)]
 pub enum ShaderError {
 	InvalidFile,
 	BytecodeRead,
-	ShaderCreation,
+	ShaderCreation(vk::Result),
     Shaderc,
-    Compiling,
-    NullTerminate
+    /// `log` is the full compiler output
+    ///
+    /// `file` and `line` point to the first error
+    Compile {
+        log: String,
+        file: String,
+        line: Option<u32>
+    },
+    NullTerminate,
+    Reflection,
+    /// Bytecode is empty or its size is not a multiple of 4 bytes
+    BytecodeSize,
+    /// Bytecode does not start with SPIR-V magic number
+    BytecodeMagic,
+    /// Module does not declare entry point with name from [`ShaderCfg::entry`]
+    ///
+    /// `available` contains all declared entry points
+    NoSuchEntryPoint {
+        entry: String,
+        available: Vec<String>
+    },
+    /// Shader was not built from `glsl` so its kind is unknown
+    UnknownKind
 }
 
 impl fmt::Display for ShaderError {
@@ -59,17 +179,32 @@ impl fmt::Display for ShaderError {
             ShaderError::BytecodeRead => {
                 "Failed to read from file"
             },
-            ShaderError::ShaderCreation => {
+            ShaderError::ShaderCreation(_) => {
                 "Failed to create shader (vkCreateShaderModule call failed)"
             },
             ShaderError::Shaderc => {
                 "Failed to create compiler (internal shaderc library error)"
             },
-            ShaderError::Compiling => {
-                "Failed to compile shader source code"
+            ShaderError::Compile { log, .. } => {
+                return write!(f, "Failed to compile shader source code:\n{}", log);
             },
             ShaderError::NullTerminate => {
                 "Failed to null terminate shader entry name"
+            },
+            ShaderError::Reflection => {
+                "Failed to parse SPIR-V bytecode"
+            },
+            ShaderError::BytecodeSize => {
+                "Invalid SPIR-V bytecode size (must be a multiple of 4 bytes)"
+            },
+            ShaderError::BytecodeMagic => {
+                "Invalid SPIR-V magic number"
+            },
+            ShaderError::NoSuchEntryPoint { entry, available } => {
+                return write!(f, "Shader has no entry point {:?} (available: {:?})", entry, available);
+            },
+            ShaderError::UnknownKind => {
+                "Failed to reload shader (shader kind is unknown)"
             }
         };
 
@@ -77,77 +212,134 @@ impl fmt::Display for ShaderError {
     }
 }
 
-impl Error for ShaderError {}
+impl Error for ShaderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ShaderError::ShaderCreation(result) => Some(result),
+            _ => None
+        }
+    }
+}
+
+/// Resource declared in shader with `layout(set = X, binding = Y)`
+///
+/// Returned by [`Shader::bindings`]
+///
+/// Reflection can not distinguish dynamic buffers so
+/// `UNIFORM_BUFFER` or `STORAGE_BUFFER` is reported for them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReflectedBinding {
+    pub set: usize,
+    pub binding: u32,
+    pub resource_type: graphics::DescriptorType,
+    /// Number of array elements
+    ///
+    /// `0` for runtime-sized arrays
+    ///
+    /// Arrays sized by specialization constant have default size of the constant
+    pub count: u32,
+    /// Stage of the shader entry point
+    ///
+    /// For modules older than SPIR-V 1.4 with several entry points
+    /// resource may be used only by another entry point
+    pub stage: graphics::ShaderStage,
+}
 
 /// Shader type represents loaded shader bytecode wrapper
 ///
 /// You may think of it as file handler
+///
+/// # Hot reload
+/// [`reload_from_file`](Self::reload_from_file) and [`reload_from_glsl`](Self::reload_from_glsl)
+/// replace shader module in place
+///
+/// Pipelines are not affected by reload and have to be recreated
+/// with [`Pipeline::new`](graphics::Pipeline::new).
+/// Old pipeline stays valid until dropped so it may be kept if creation fails
+///
+/// Previous modules are kept until [`release_retired`](Self::release_retired) is called
+/// or shader is dropped
 pub struct Shader {
 	i_core: Arc<dev::Core>,
 	i_module: vk::ShaderModule,
 	i_entry: CString,
+	i_bindings: Vec<ReflectedBinding>,
+	i_path: String,
+	i_kind: Option<Kind>,
+	i_include_dirs: Vec<String>,
+	i_defines: Vec<(String, String)>,
+	i_retired: Vec<vk::ShaderModule>,
 }
 
 impl Shader {
     /// Build shader module from provided SPIR-V bytecode
     pub fn from_bytecode(device: &dev::Device, shader_type: &ShaderCfg, bytecode: &[u32]) -> Result<Shader, ShaderError> {
-        let shader_info = vk::ShaderModuleCreateInfo {
-            s_type: vk::StructureType::SHADER_MODULE_CREATE_INFO,
-            p_next: ptr::null(),
-            flags: vk::ShaderModuleCreateFlags::empty(),
-            code_size: bytecode.len()*mem::size_of::<u32>(),
-            p_code: bytecode.as_ptr(),
-            _marker: PhantomData,
-        };
-
-        let shader_module: vk::ShaderModule = on_error_ret!(
-            unsafe { device.device().create_shader_module(&shader_info, device.allocator()) },
-            ShaderError::ShaderCreation
-        );
-
         let entry = on_error_ret!(CString::new(shader_type.entry), ShaderError::NullTerminate);
 
+        let (shader_module, bindings) = create_module(device.core(), bytecode, shader_type.entry)?;
+
         Ok(Shader {
             i_core: device.core().clone(),
             i_module: shader_module,
-            i_entry: entry
+            i_entry: entry,
+            i_bindings: bindings,
+            i_path: shader_type.path.to_string(),
+            i_kind: None,
+            i_include_dirs: shader_type.include_dirs.iter().map(|x| x.to_string()).collect(),
+            i_defines: Vec::new(),
+            i_retired: Vec::new()
         })
     }
 
+    /// Build shader module from SPIR-V bytes (e.g. generated at build time and included with `include_bytes!`)
+    ///
+    /// Length of `bytes` must be a multiple of 4
+    ///
+    /// Both little and big endian modules are accepted
+    pub fn from_bytes(device: &dev::Device, shader_type: &ShaderCfg, bytes: &[u8]) -> Result<Shader, ShaderError> {
+        let bytecode = bytes_to_words(bytes)?;
+
+        Shader::from_bytecode(device, shader_type, &bytecode)
+    }
+
     /// Build shader module from SPIR-V bytecode file
     ///
     /// Note: compare this method with [`from_glsl_file`](Self::from_glsl_file)
     pub fn from_file(device: &dev::Device, shader_type: &ShaderCfg) -> Result<Shader, ShaderError> {
-        let mut spv_file: fs::File = on_error_ret!(
-            fs::File::open(Path::new(shader_type.path)),
-            ShaderError::InvalidFile
-        );
-
-        let spv_bytecode: Vec<u32> = on_error_ret!(
-            read_spv(&mut spv_file),
-            ShaderError::BytecodeRead
-        );
+        let spv_bytecode = read_bytecode(shader_type.path)?;
 
         Shader::from_bytecode(device, shader_type, &spv_bytecode)
     }
 
     /// Build shader module from `glsl` source code directly
     pub fn from_glsl(device: &dev::Device, cfg: &ShaderCfg, src: &str, kind: Kind) -> Result<Shader, ShaderError> {
-        let compiler = on_option_ret!(shaderc::Compiler::new(), ShaderError::Shaderc);
+        Self::from_glsl_with_defines(device, cfg, src, kind, &[])
+    }
 
-        let binary_result = match compiler.compile_into_spirv(src, kind, cfg.path, cfg.entry, None) {
-            Ok(val) => val,
-            Err(err) => {
-                print!("{}", err);
-                return Err(ShaderError::Compiling);
-            }
-        };
+    /// Build shader module from `glsl` source code with preprocessor `defines`
+    ///
+    /// Each pair is the same as `#define NAME VALUE` at the beginning of the source code.
+    /// Empty value means `#define NAME`
+    ///
+    /// Allows to build different variants of the shader from the same source code
+    pub fn from_glsl_with_defines(
+        device: &dev::Device,
+        cfg: &ShaderCfg,
+        src: &str,
+        kind: Kind,
+        defines: &[(&str, &str)]
+    ) -> Result<Shader, ShaderError> {
+        let include_dirs: Vec<String> = cfg.include_dirs.iter().map(|x| x.to_string()).collect();
+        let defines: Vec<(String, String)> = defines.iter().map(|(x, y)| (x.to_string(), y.to_string())).collect();
 
-        if binary_result.is_empty() {
-            return Err(ShaderError::Compiling);
-        }
+        let bytecode = compile(cfg.path, cfg.entry, src, kind, &include_dirs, &defines)?;
+
+        let mut shader = Self::from_bytecode(device, cfg, &bytecode)?;
+
+        shader.i_kind = Some(kind);
+        shader.i_defines = defines;
 
-        Self::from_bytecode(device, cfg, binary_result.as_binary())
+        Ok(shader)
     }
 
     /// Build shader module from file with `glsl` source code directly
@@ -159,15 +351,88 @@ impl Shader {
         Self::from_glsl(device, cfg, &src, kind)
     }
 
+    /// Build shader module again from [`path`](ShaderCfg::path)
+    ///
+    /// Shaders built from `glsl` are compiled again with the same kind, include directories and defines.
+    /// Otherwise file is read as SPIR-V bytecode
+    ///
+    /// On failure shader stays unchanged
+    pub fn reload_from_file(&mut self) -> Result<(), ShaderError> {
+        let bytecode = match self.i_kind {
+            Some(kind) => {
+                let src = on_error_ret!(fs::read_to_string(&self.i_path), ShaderError::InvalidFile);
+
+                self.compile(&src, kind)?
+            },
+            None => read_bytecode(&self.i_path)?
+        };
+
+        self.replace_module(&bytecode)
+    }
+
+    /// Build shader module again from `glsl` source code
+    ///
+    /// Shader must be built from `glsl` code before, otherwise [`ShaderError::UnknownKind`] is returned
+    ///
+    /// On failure shader stays unchanged
+    pub fn reload_from_glsl(&mut self, src: &str) -> Result<(), ShaderError> {
+        let kind = on_option_ret!(self.i_kind, ShaderError::UnknownKind);
+
+        let bytecode = self.compile(src, kind)?;
+
+        self.replace_module(&bytecode)
+    }
+
+    /// Destroy modules replaced by reload
+    ///
+    /// Vulkan does not require module to outlive pipelines which were created from it
+    /// so it is always safe to call this method after new pipelines are created
+    pub fn release_retired(&mut self) {
+        for module in self.i_retired.drain(..) {
+            unsafe {
+                self.i_core.device().destroy_shader_module(module, self.i_core.allocator());
+            }
+        }
+    }
+
+    /// Return number of modules replaced by reload and not yet released
+    pub fn retired_count(&self) -> usize {
+        self.i_retired.len()
+    }
+
     /// Return reference to name of entry function (point) in shader
     pub fn entry(&self) -> &CString {
         &self.i_entry
     }
 
+    /// Return descriptor bindings declared in shader sorted by set and binding
+    ///
+    /// See [`PipelineDescriptor::from_shaders`](graphics::PipelineDescriptor::from_shaders)
+    pub fn bindings(&self) -> Vec<ReflectedBinding> {
+        self.i_bindings.clone()
+    }
+
     #[doc(hidden)]
     pub fn module(&self) -> vk::ShaderModule {
         self.i_module
     }
+
+    fn compile(&self, src: &str, kind: Kind) -> Result<Vec<u32>, ShaderError> {
+        let entry = on_error_ret!(self.i_entry.to_str(), ShaderError::NullTerminate);
+
+        compile(&self.i_path, entry, src, kind, &self.i_include_dirs, &self.i_defines)
+    }
+
+    fn replace_module(&mut self, bytecode: &[u32]) -> Result<(), ShaderError> {
+        let entry = on_error_ret!(self.i_entry.to_str(), ShaderError::NullTerminate);
+
+        let (shader_module, bindings) = create_module(&self.i_core, bytecode, entry)?;
+
+        self.i_retired.push(std::mem::replace(&mut self.i_module, shader_module));
+        self.i_bindings = bindings;
+
+        Ok(())
+    }
 }
 
 impl Drop for Shader {
@@ -175,5 +440,153 @@ impl Drop for Shader {
         unsafe {
             self.i_core.device().destroy_shader_module(self.i_module, self.i_core.allocator());
         }
+
+        self.release_retired();
+    }
+}
+
+fn create_module(
+    core: &dev::Core,
+    bytecode: &[u32],
+    entry: &str
+) -> Result<(vk::ShaderModule, Vec<ReflectedBinding>), ShaderError> {
+    let shader_info = vk::ShaderModuleCreateInfo {
+        s_type: vk::StructureType::SHADER_MODULE_CREATE_INFO,
+        p_next: ptr::null(),
+        flags: vk::ShaderModuleCreateFlags::empty(),
+        code_size: mem::size_of_val(bytecode),
+        p_code: bytecode.as_ptr(),
+        _marker: PhantomData,
+    };
+
+    if bytecode.first() != Some(&spirv::MAGIC) {
+        return Err(ShaderError::BytecodeMagic);
+    }
+
+    let entries = on_option_ret!(spirv::entry_points(bytecode), ShaderError::Reflection);
+
+    if !entries.iter().any(|name| name == entry) {
+        return Err(ShaderError::NoSuchEntryPoint { entry: entry.to_string(), available: entries });
+    }
+
+    let bindings = on_option_ret!(spirv::reflect(bytecode, entry), ShaderError::Reflection);
+
+    let shader_module: vk::ShaderModule = on_error!(
+        unsafe { core.device().create_shader_module(&shader_info, core.allocator()) },
+        |err| return Err(ShaderError::ShaderCreation(err))
+    );
+
+    Ok((shader_module, bindings))
+}
+
+fn bytes_to_words(bytes: &[u8]) -> Result<Vec<u32>, ShaderError> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(4) {
+        return Err(ShaderError::BytecodeSize);
+    }
+
+    let words: Vec<u32> = bytes
+        .chunks_exact(4)
+        .map(|x| u32::from_le_bytes([x[0], x[1], x[2], x[3]]))
+        .collect();
+
+    if words[0] == spirv::MAGIC {
+        Ok(words)
+    }
+    else if words[0].swap_bytes() == spirv::MAGIC {
+        Ok(words.into_iter().map(u32::swap_bytes).collect())
+    }
+    else {
+        Err(ShaderError::BytecodeMagic)
+    }
+}
+
+fn read_bytecode(path: &str) -> Result<Vec<u32>, ShaderError> {
+    let mut spv_file: fs::File = on_error_ret!(
+        fs::File::open(Path::new(path)),
+        ShaderError::InvalidFile
+    );
+
+    Ok(on_error_ret!(read_spv(&mut spv_file), ShaderError::BytecodeRead))
+}
+
+fn compile(
+    path: &str,
+    entry: &str,
+    src: &str,
+    kind: Kind,
+    include_dirs: &[String],
+    defines: &[(String, String)]
+) -> Result<Vec<u32>, ShaderError> {
+    let compiler = on_option_ret!(shaderc::Compiler::new(), ShaderError::Shaderc);
+
+    let mut options = on_option_ret!(shaderc::CompileOptions::new(), ShaderError::Shaderc);
+
+    for (name, value) in defines {
+        options.add_macro_definition(name, if value.is_empty() { None } else { Some(value) });
+    }
+
+    let include_dirs: Vec<PathBuf> = include_dirs.iter().map(PathBuf::from).collect();
+
+    options.set_include_callback(move |name, include_type, parent, _| {
+        resolve_include(&include_dirs, name, include_type, parent)
+    });
+
+    let binary_result = match compiler.compile_into_spirv(src, kind, path, entry, Some(&options)) {
+        Ok(val) => val,
+        Err(shaderc::Error::CompilationError(_, log)) => {
+            return Err(compile_error(path, log));
+        },
+        Err(err) => {
+            return Err(compile_error(path, err.to_string()));
+        }
+    };
+
+    if binary_result.is_empty() {
+        return Err(compile_error(path, String::new()));
     }
-}
\ No newline at end of file
+
+    Ok(binary_result.as_binary().to_vec())
+}
+
+fn resolve_include(
+    include_dirs: &[PathBuf],
+    name: &str,
+    include_type: shaderc::IncludeType,
+    parent: &str
+) -> shaderc::IncludeCallbackResult {
+    let parent_dir = Path::new(parent).parent().map(Path::to_path_buf);
+
+    let candidates = match include_type {
+        shaderc::IncludeType::Relative => parent_dir.iter().chain(include_dirs.iter()),
+        shaderc::IncludeType::Standard => None.iter().chain(include_dirs.iter()),
+    };
+
+    for dir in candidates {
+        let path = dir.join(name);
+
+        if let Ok(content) = fs::read_to_string(&path) {
+            return Ok(shaderc::ResolvedInclude {
+                resolved_name: path.to_string_lossy().into_owned(),
+                content
+            });
+        }
+    }
+
+    Err(format!("Failed to find include file {}", name))
+}
+
+/// Find location of the first error in the compiler `log`
+///
+/// shaderc reports errors as `file:line: error: message`
+fn compile_error(path: &str, log: String) -> ShaderError {
+    let location = log
+        .lines()
+        .find_map(|line| line.find(": error:").map(|pos| &line[..pos]))
+        .and_then(|location| location.rsplit_once(':'))
+        .and_then(|(file, line)| line.trim().parse::<u32>().ok().map(|line| (file.to_string(), line)));
+
+    match location {
+        Some((file, line)) => ShaderError::Compile { log, file, line: Some(line) },
+        None => ShaderError::Compile { log, file: path.to_string(), line: None }
+    }
+}
@@ -0,0 +1,284 @@
+//! Minimal SPIR-V reader for shader reflection
+//!
+//! Only instructions required to find descriptor bindings are decoded
+//!
+//! See [specification](https://registry.khronos.org/SPIR-V/specs/unified1/SPIRV.html)
+use ash::vk;
+
+use crate::shader;
+
+use std::collections::{HashMap, HashSet};
+
+pub(crate) const MAGIC: u32 = 0x0723_0203;
+const HEADER_SIZE: usize = 5;
+
+/// Since SPIR-V 1.4 interface of the entry point lists every global variable it uses
+const VERSION_1_4: u32 = 0x0001_0400;
+
+const OP_ENTRY_POINT: u32 = 15;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_CONSTANT: u32 = 43;
+const OP_SPEC_CONSTANT: u32 = 50;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+
+const STORAGE_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_UNIFORM: u32 = 2;
+const STORAGE_STORAGE_BUFFER: u32 = 12;
+
+const DIM_BUFFER: u32 = 5;
+const DIM_SUBPASS_DATA: u32 = 6;
+
+/// Image is accessed only with sampler
+const IMAGE_SAMPLED: u32 = 1;
+
+enum Type {
+    Image {
+        dim: u32,
+        sampled: u32
+    },
+    Sampler,
+    SampledImage,
+    Array {
+        element: u32,
+        length: u32
+    },
+    RuntimeArray {
+        element: u32
+    },
+    Pointer {
+        pointee: u32
+    },
+}
+
+struct Variable {
+    id: u32,
+    pointer: u32,
+    storage: u32
+}
+
+/// Find all resources decorated with `set` and `binding` in `code`
+///
+/// Stage is taken from the entry point with `entry` name
+///
+/// Since SPIR-V 1.4 only resources from the interface of the entry point are returned.
+/// Older modules do not list resources in the interface so every resource of the module
+/// is returned, even if it is used only by another entry point
+///
+/// Arrays sized by specialization constant have default size of the constant
+/// since specialization is applied only on pipeline creation
+///
+/// Returns `None` if `code` is not valid SPIR-V or array size is not a constant
+/// (e.g. it is computed by `OpSpecConstantOp`)
+pub(crate) fn reflect(code: &[u32], entry: &str) -> Option<Vec<shader::ReflectedBinding>> {
+    if code.len() < HEADER_SIZE || code[0] != MAGIC {
+        return None;
+    }
+
+    let mut stage = vk::ShaderStageFlags::empty();
+    let mut interface: Option<HashSet<u32>> = None;
+    let mut types: HashMap<u32, Type> = HashMap::new();
+    let mut constants: HashMap<u32, u32> = HashMap::new();
+    let mut sets: HashMap<u32, u32> = HashMap::new();
+    let mut bindings: HashMap<u32, u32> = HashMap::new();
+    let mut buffer_blocks: HashSet<u32> = HashSet::new();
+    let mut variables: Vec<Variable> = Vec::new();
+
+    let mut i = HEADER_SIZE;
+
+    while i < code.len() {
+        let word_count = (code[i] >> 16) as usize;
+        let opcode = code[i] & 0xffff;
+
+        if word_count == 0 || i + word_count > code.len() {
+            return None;
+        }
+
+        let args = &code[i + 1..i + word_count];
+
+        match opcode {
+            OP_ENTRY_POINT if args.len() >= 3 && literal_string(&args[2..]) == entry => {
+                stage = execution_stage(args[0]);
+
+                if code[1] >= VERSION_1_4 {
+                    interface = Some(args[2 + literal_word_count(&args[2..])..].iter().copied().collect());
+                }
+            },
+            OP_TYPE_IMAGE if args.len() >= 7 => {
+                types.insert(args[0], Type::Image { dim: args[2], sampled: args[6] });
+            },
+            OP_TYPE_SAMPLER if !args.is_empty() => {
+                types.insert(args[0], Type::Sampler);
+            },
+            OP_TYPE_SAMPLED_IMAGE if !args.is_empty() => {
+                types.insert(args[0], Type::SampledImage);
+            },
+            OP_TYPE_ARRAY if args.len() >= 3 => {
+                types.insert(args[0], Type::Array { element: args[1], length: args[2] });
+            },
+            OP_TYPE_RUNTIME_ARRAY if args.len() >= 2 => {
+                types.insert(args[0], Type::RuntimeArray { element: args[1] });
+            },
+            OP_TYPE_POINTER if args.len() >= 3 => {
+                types.insert(args[0], Type::Pointer { pointee: args[2] });
+            },
+            OP_CONSTANT | OP_SPEC_CONSTANT if args.len() >= 3 => {
+                constants.insert(args[1], args[2]);
+            },
+            OP_VARIABLE if args.len() >= 3 => {
+                variables.push(Variable { id: args[1], pointer: args[0], storage: args[2] });
+            },
+            OP_DECORATE if args.len() >= 2 => {
+                match args[1] {
+                    DECORATION_BUFFER_BLOCK => {
+                        buffer_blocks.insert(args[0]);
+                    },
+                    DECORATION_BINDING if args.len() >= 3 => {
+                        bindings.insert(args[0], args[2]);
+                    },
+                    DECORATION_DESCRIPTOR_SET if args.len() >= 3 => {
+                        sets.insert(args[0], args[2]);
+                    },
+                    _ => ()
+                }
+            },
+            _ => ()
+        }
+
+        i += word_count;
+    }
+
+    let mut result: Vec<shader::ReflectedBinding> = Vec::new();
+
+    let used = variables.iter().filter(|var| match &interface {
+        Some(ids) => ids.contains(&var.id),
+        None => true
+    });
+
+    for var in used {
+        let (Some(&set), Some(&binding)) = (sets.get(&var.id), bindings.get(&var.id)) else {
+            continue;
+        };
+
+        let mut ty = match types.get(&var.pointer) {
+            Some(Type::Pointer { pointee }) => *pointee,
+            _ => continue
+        };
+
+        let mut count = 1;
+
+        loop {
+            match types.get(&ty) {
+                Some(Type::Array { element, length }) => {
+                    // Binding without known size can not be skipped, layout would miss it
+                    count *= constants.get(length)?;
+                    ty = *element;
+                },
+                Some(Type::RuntimeArray { element }) => {
+                    count = 0;
+                    ty = *element;
+                },
+                _ => break
+            }
+        }
+
+        let resource_type = match var.storage {
+            STORAGE_UNIFORM if buffer_blocks.contains(&ty) => vk::DescriptorType::STORAGE_BUFFER,
+            STORAGE_UNIFORM => vk::DescriptorType::UNIFORM_BUFFER,
+            STORAGE_STORAGE_BUFFER => vk::DescriptorType::STORAGE_BUFFER,
+            STORAGE_UNIFORM_CONSTANT => match types.get(&ty) {
+                Some(Type::Sampler) => vk::DescriptorType::SAMPLER,
+                Some(Type::SampledImage) => vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+                Some(Type::Image { dim: DIM_SUBPASS_DATA, .. }) => vk::DescriptorType::INPUT_ATTACHMENT,
+                Some(Type::Image { dim: DIM_BUFFER, sampled: IMAGE_SAMPLED }) => vk::DescriptorType::UNIFORM_TEXEL_BUFFER,
+                Some(Type::Image { dim: DIM_BUFFER, .. }) => vk::DescriptorType::STORAGE_TEXEL_BUFFER,
+                Some(Type::Image { sampled: IMAGE_SAMPLED, .. }) => vk::DescriptorType::SAMPLED_IMAGE,
+                Some(Type::Image { .. }) => vk::DescriptorType::STORAGE_IMAGE,
+                _ => continue
+            },
+            _ => continue
+        };
+
+        result.push(shader::ReflectedBinding {
+            set: set as usize,
+            binding,
+            resource_type,
+            count,
+            stage
+        });
+    }
+
+    result.sort_by_key(|x| (x.set, x.binding));
+
+    Some(result)
+}
+
+/// Return names of all entry points declared in `code`
+///
+/// Returns `None` if `code` is not valid SPIR-V
+pub(crate) fn entry_points(code: &[u32]) -> Option<Vec<String>> {
+    if code.len() < HEADER_SIZE || code[0] != MAGIC {
+        return None;
+    }
+
+    let mut result = Vec::new();
+
+    let mut i = HEADER_SIZE;
+
+    while i < code.len() {
+        let word_count = (code[i] >> 16) as usize;
+        let opcode = code[i] & 0xffff;
+
+        if word_count == 0 || i + word_count > code.len() {
+            return None;
+        }
+
+        if opcode == OP_ENTRY_POINT && word_count >= 4 {
+            result.push(literal_string(&code[i + 3..i + word_count]));
+        }
+
+        i += word_count;
+    }
+
+    Some(result)
+}
+
+/// Decode nul-terminated UTF-8 string packed into words
+fn literal_string(words: &[u32]) -> String {
+    let bytes: Vec<u8> = words
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .take_while(|&byte| byte != 0)
+        .collect();
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Return number of words occupied by nul-terminated string
+fn literal_word_count(words: &[u32]) -> usize {
+    words
+        .iter()
+        .position(|word| word.to_le_bytes().contains(&0))
+        .map_or(words.len(), |i| i + 1)
+}
+
+fn execution_stage(model: u32) -> vk::ShaderStageFlags {
+    match model {
+        0 => vk::ShaderStageFlags::VERTEX,
+        1 => vk::ShaderStageFlags::TESSELLATION_CONTROL,
+        2 => vk::ShaderStageFlags::TESSELLATION_EVALUATION,
+        3 => vk::ShaderStageFlags::GEOMETRY,
+        4 => vk::ShaderStageFlags::FRAGMENT,
+        5 => vk::ShaderStageFlags::COMPUTE,
+        _ => vk::ShaderStageFlags::empty()
+    }
+}
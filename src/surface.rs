@@ -4,7 +4,7 @@ use ash::vk;
 use ash::khr::surface;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 
-use crate::{libvk, window, hw, memory, swapchain};
+use crate::{libvk, alloc, window, hw, memory, swapchain};
 use crate::on_error_ret;
 
 use std::error::Error;
@@ -33,10 +33,11 @@ impl fmt::Display for SurfaceError {
 
 impl Error for SurfaceError {}
 
-/// Note: custom allocator is not supported
+/// Surface uses [allocator of the instance](libvk::InstanceType::allocator)
 pub struct Surface {
     i_loader: surface::Instance,
     i_surface: vk::SurfaceKHR,
+    i_callback: Option<alloc::Callback>,
 }
 
 impl Surface {
@@ -49,7 +50,7 @@ impl Surface {
             lib.instance(),
             display_handle.as_raw(),
             window_handle.as_raw(),
-            None,
+            lib.allocator(),
         ).unwrap() };
 
         let surface_loader = surface::Instance::new(lib.entry(), lib.instance());
@@ -58,6 +59,7 @@ impl Surface {
             Surface {
                 i_loader: surface_loader,
                 i_surface: surface,
+                i_callback: lib.allocator().copied(),
             }
         )
     }
@@ -75,7 +77,7 @@ impl Surface {
 
 impl Drop for Surface {
     fn drop(&mut self) {
-        unsafe { self.i_loader.destroy_surface(self.i_surface, None) };
+        unsafe { self.i_loader.destroy_surface(self.i_surface, self.i_callback.as_ref()) };
     }
 }
 
@@ -136,6 +138,10 @@ impl fmt::Display for CapabilitiesError {
 
 impl Error for CapabilitiesError {}
 
+/// Surface properties of the hw device
+///
+/// Required only for swapchain creation, compute and [offscreen](crate::memory::ImageMemory::offscreen)
+/// rendering do not need it
 pub struct Capabilities {
     i_modes: Vec<vk::PresentModeKHR>,
     i_capabilities: vk::SurfaceCapabilitiesKHR,
@@ -175,6 +181,30 @@ impl Capabilities {
         )
     }
 
+    /// Query capabilities again
+    ///
+    /// Current extent and image count limits may change (e.g. after window resize)
+    ///
+    /// On failure capabilities stay unchanged
+    pub fn refresh(&mut self, hw: &hw::HWDevice, surface: &Surface) -> Result<(), CapabilitiesError> {
+        *self = Capabilities::get(hw, surface)?;
+
+        Ok(())
+    }
+
+    #[doc(hidden)]
+    pub fn from_raw(
+        capabilities: vk::SurfaceCapabilitiesKHR,
+        formats: Vec<SurfaceFormat>,
+        modes: Vec<swapchain::PresentMode>
+    ) -> Capabilities {
+        Capabilities {
+            i_modes: modes,
+            i_capabilities: capabilities,
+            i_formats: formats
+        }
+    }
+
     /// Return number of minimal number of images required for the swapchain
     pub fn min_img_count(&self) -> u32 {
         self.i_capabilities.min_image_count
@@ -192,6 +222,11 @@ impl Capabilities {
         }
     }
 
+    /// Clamp `count` to range [min_img_count; max_img_count]
+    pub fn clamp_img_count(&self, count: u32) -> u32 {
+        count.clamp(self.min_img_count(), self.max_img_count())
+    }
+
     /// Return true if `count` is in range [min_img_count; max_img_count]
     pub fn is_img_count_supported(&self, count: u32) -> bool {
         (self.min_img_count()..=self.max_img_count()).contains(&count)
@@ -217,16 +252,103 @@ impl Capabilities {
         self.i_modes.contains(&mode)
     }
 
+    /// Return first supported presentation mode from `preferences`
+    ///
+    /// Falls back to `FIFO` which is always available
+    ///
+    /// ```no_run
+    /// use libvktypes::{surface, swapchain};
+    ///
+    /// # fn select(capabilities: &surface::Capabilities) {
+    /// let mode = capabilities.choose_present_mode(&[swapchain::PresentMode::MAILBOX]);
+    /// # }
+    /// ```
+    pub fn choose_present_mode(&self, preferences: &[swapchain::PresentMode]) -> swapchain::PresentMode {
+        preferences
+            .iter()
+            .find(|&&mode| self.is_mode_supported(mode))
+            .copied()
+            .unwrap_or(swapchain::PresentMode::FIFO)
+    }
+
+    /// Return first supported combination of format and color space from `preferences`
+    ///
+    /// Falls back to the first format reported by surface
+    ///
+    /// # Panics
+    ///
+    /// If surface reports no formats (Vulkan guarantees at least one)
+    pub fn choose_format(&self, preferences: &[(memory::ImageFormat, memory::ColorSpace)]) -> SurfaceFormat {
+        preferences
+            .iter()
+            .map(|&(format, color_space)| SurfaceFormat { format, color_space })
+            .find(|&format| self.is_format_supported(format))
+            .unwrap_or_else(|| *self.i_formats.first().expect("Surface reports no formats"))
+    }
+
     /// Check if selected flags is supported
     pub fn is_flags_supported(&self, flags: memory::UsageFlags) -> bool {
         self.i_capabilities.supported_usage_flags.contains(flags)
     }
 
+    /// Return all usage flags which swapchain images may have
+    ///
+    /// For example check `STORAGE` before using swapchain images in compute shader
+    pub fn supported_usage_flags(&self) -> memory::UsageFlags {
+        self.i_capabilities.supported_usage_flags
+    }
+
     /// Return 2d extent supported by surface
+    ///
+    /// Width and height are `0xFFFFFFFF` if extent is defined by swapchain,
+    /// use [`clamp_extent`](Capabilities::clamp_extent) in that case
     pub fn extent2d(&self) -> memory::Extent2D {
         self.i_capabilities.current_extent
     }
 
+    /// Is width or height of the [current extent](Capabilities::extent2d) zero
+    ///
+    /// It happens when window is minimized,
+    /// swapchain can not be created until window is restored (see [`SwapchainError::ZeroExtent`](swapchain::SwapchainError::ZeroExtent))
+    pub fn is_zero_extent(&self) -> bool {
+        let current = self.i_capabilities.current_extent;
+
+        current.width == 0 || current.height == 0
+    }
+
+    /// Return the smallest extent of the swapchain images
+    pub fn min_extent(&self) -> memory::Extent2D {
+        self.i_capabilities.min_image_extent
+    }
+
+    /// Return the largest extent of the swapchain images
+    pub fn max_extent(&self) -> memory::Extent2D {
+        self.i_capabilities.max_image_extent
+    }
+
+    /// Return extent for the swapchain
+    ///
+    /// If surface defines [current extent](Capabilities::extent2d) it is returned as is
+    ///
+    /// Otherwise (width and height are `0xFFFFFFFF`) `desired` extent
+    /// clamped to [`min_extent`](Capabilities::min_extent) and [`max_extent`](Capabilities::max_extent)
+    /// is returned
+    pub fn clamp_extent(&self, desired: memory::Extent2D) -> memory::Extent2D {
+        let current = self.i_capabilities.current_extent;
+
+        if current.width != u32::MAX {
+            return current;
+        }
+
+        let min = self.min_extent();
+        let max = self.max_extent();
+
+        memory::Extent2D {
+            width: desired.width.clamp(min.width, max.width),
+            height: desired.height.clamp(min.height, max.height),
+        }
+    }
+
     /// Return 3d extent from supported 2d extent and selected depth
     pub fn extent3d(&self, ext_depth: u32) -> memory::Extent3D {
         memory::Extent3D {
@@ -1,37 +1,118 @@
 //! Array of presentable images
 //!
+//! Swapchain is needed only for presentation so compute only and offscreen code
+//! does not require it (and [`SWAPCHAIN_EXT_NAME`](crate::extensions::SWAPCHAIN_EXT_NAME) as well)
+//!
 //! See [more](https://registry.khronos.org/vulkan/specs/1.2-extensions/html/chap34.html#_wsi_swapchain)
 
 use ash::khr::swapchain;
 use ash::vk;
 
-use crate::on_error_ret;
-use crate::{dev, libvk, surface, sync, memory};
+use crate::{on_error, on_error_ret};
+use crate::{dev, libvk, surface, sync, memory, formats, queue, cmd};
 
 use std::ptr;
 use std::fmt;
 use std::sync::Arc;
 use std::error::Error;
 use std::marker::PhantomData;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum SwapchainError {
-    Creating,
-    NextImage,
-    Images
+    /// Failed to
+    /// [create](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkCreateSwapchainKHR.html) swapchain
+    Creating(vk::Result),
+    /// Failed to
+    /// [acquire](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkAcquireNextImageKHR.html) next image
+    NextImage(vk::Result),
+    /// Surface has changed and swapchain is no longer compatible with it
+    ///
+    /// Swapchain **must be** recreated
+    OutOfDate,
+    /// No image became available within timeout
+    Timeout,
+    /// Timeout is zero and no image is available right now
+    NotReady,
+    /// Neither semaphore nor fence was provided for image acquisition
+    ///
+    /// See [`AcquireSync::None`]
+    NoSync,
+    /// Failed to
+    /// [get](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkGetSwapchainImagesKHR.html) swapchain images
+    Images(vk::Result),
+    /// Failed to create view of swapchain image
+    ImageMemory(memory::MemoryError),
+    /// Failed to query surface capabilities for image count negotiation
+    Capabilities,
+    /// Surface does not support any sRGB format with `SRGB_NONLINEAR` color space
+    NoSrgbFormat,
+    /// Requested or current surface extent has zero width or height (e.g. window is minimized)
+    ///
+    /// Frames should be skipped until [`Capabilities::is_zero_extent`](surface::Capabilities::is_zero_extent)
+    /// returns `false`
+    ZeroExtent,
+    /// Swapchain was created without `TRANSFER_SRC` in [`SwapchainCfg::flags`]
+    /// so its images can not be copied
+    NoTransferSrc,
+    /// Failed to copy swapchain image into host memory
+    Screenshot(memory::MemoryError)
+}
+
+impl From<vk::Result> for SwapchainError {
+    /// Map result of
+    /// [`vkAcquireNextImageKHR`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkAcquireNextImageKHR.html)
+    fn from(result: vk::Result) -> Self {
+        match result {
+            vk::Result::ERROR_OUT_OF_DATE_KHR => SwapchainError::OutOfDate,
+            vk::Result::TIMEOUT => SwapchainError::Timeout,
+            vk::Result::NOT_READY => SwapchainError::NotReady,
+            _ => SwapchainError::NextImage(result)
+        }
+    }
 }
 
 impl fmt::Display for SwapchainError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let err_msg = match self {
-            SwapchainError::Creating => {
+            SwapchainError::Creating(_) => {
                 "Failed to create swapchain (vkCreateSwapchainKHR call failed)"
             },
-            SwapchainError::NextImage => {
-                "Failed to create image view (vkAcquireNextImageKHR call failed)"
+            SwapchainError::NextImage(_) => {
+                "Failed to acquire next image (vkAcquireNextImageKHR call failed)"
+            },
+            SwapchainError::OutOfDate => {
+                "Swapchain is out of date and must be recreated"
+            },
+            SwapchainError::Timeout => {
+                "No image became available within timeout"
+            },
+            SwapchainError::NotReady => {
+                "No image is available right now"
+            },
+            SwapchainError::NoSync => {
+                "Semaphore or fence is required to acquire image"
+            },
+            SwapchainError::Images(_) => {
+                "Failed to get images from swapchain (vkGetSwapchainImagesKHR call failed)"
+            },
+            SwapchainError::ImageMemory(_) => {
+                "Failed to create view of swapchain image"
+            },
+            SwapchainError::Capabilities => {
+                "Failed to get surface capabilities"
+            },
+            SwapchainError::NoSrgbFormat => {
+                "Surface does not support sRGB formats"
+            },
+            SwapchainError::ZeroExtent => {
+                "Swapchain extent is zero (window is minimized)"
+            },
+            SwapchainError::NoTransferSrc => {
+                "Swapchain images can not be copied, add TRANSFER_SRC to SwapchainCfg::flags"
             },
-            SwapchainError::Images => {
-                "Failed to get images from swapchain"
+            SwapchainError::Screenshot(_) => {
+                "Failed to copy swapchain image into host memory"
             }
         };
 
@@ -39,7 +120,18 @@ impl fmt::Display for SwapchainError {
     }
 }
 
-impl Error for SwapchainError {}
+impl Error for SwapchainError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SwapchainError::Creating(result)
+            | SwapchainError::NextImage(result)
+            | SwapchainError::Images(result) => Some(result),
+            SwapchainError::ImageMemory(err)
+            | SwapchainError::Screenshot(err) => Some(err),
+            _ => None
+        }
+    }
+}
 
 /// Present modes
 ///
@@ -48,6 +140,30 @@ impl Error for SwapchainError {}
 #[doc = "Vulkan documentation: <https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkPresentModeKHR.html>"]
 pub type PresentMode = vk::PresentModeKHR;
 
+/// Synchronization primitives signaled when acquired image may be used
+pub enum AcquireSync<'a> {
+    Semaphore(&'a sync::Semaphore),
+    Fence(&'a sync::Fence),
+    Both(&'a sync::Semaphore, &'a sync::Fence),
+    /// No synchronization at all
+    ///
+    /// Image must be synchronized externally which is impossible
+    /// without semaphore or fence so Vulkan forbids it
+    /// and [`Swapchain::next_image`] returns [`SwapchainError::NoSync`]
+    None
+}
+
+/// Image returned by [`Swapchain::next_image`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcquiredImage {
+    /// Index of the image in [`Swapchain::images`]
+    pub index: u32,
+    /// Swapchain no longer matches surface exactly but still may be used
+    ///
+    /// Swapchain should be recreated
+    pub suboptimal: bool
+}
+
 /// Swapchain configuration struct
 ///
 /// Note:
@@ -58,9 +174,11 @@ pub type PresentMode = vk::PresentModeKHR;
 ///
 /// See [Capabilities::is_format_supported](crate::surface::Capabilities::is_format_supported)
 ///
-/// Swapchain creation process **does not** check if `num_of_images` is valid
+/// `num_of_images` is clamped to the range supported by surface
 ///
-/// See [Capabilities::is_img_count_supported](crate::surface::Capabilities::is_img_count_supported)
+/// See [Capabilities::clamp_img_count](crate::surface::Capabilities::clamp_img_count)
+///
+/// Driver may create more images than requested, use [Swapchain::image_count] to get actual number
 ///
 /// Swapchain creation process **does not** check if `present_mode` is supported
 ///
@@ -83,6 +201,8 @@ pub type PresentMode = vk::PresentModeKHR;
 /// [Capabilities::pre_transformation](crate::surface::Capabilities::pre_transformation) for `transform`
 ///
 /// [Capabilities::alpha_composition](crate::surface::Capabilities::alpha_composition) for `alpha`
+///
+/// Or start from [SwapchainCfg::srgb]
 pub struct SwapchainCfg {
     pub num_of_images: u32,
     pub format: memory::ImageFormat,
@@ -94,15 +214,80 @@ pub struct SwapchainCfg {
     pub alpha: memory::CompositeAlphaFlags,
 }
 
-pub struct Swapchain {
+impl SwapchainCfg {
+    /// Create configuration with sRGB format and `SRGB_NONLINEAR` color space
+    ///
+    /// Writes to sRGB images are gamma encoded by hardware
+    /// so shaders may output colors in linear space
+    ///
+    /// Other fields are filled from `capabilities`:
+    /// minimal number of images, `FIFO` present mode, `COLOR_ATTACHMENT` usage,
+    /// [clamped](crate::surface::Capabilities::clamp_extent) extent,
+    /// current pre transformation and first supported alpha composition
+    ///
+    /// Returns [`SwapchainError::NoSrgbFormat`] if surface has no suitable format
+    ///
+    /// ```no_run
+    /// use libvktypes::{surface, swapchain};
+    ///
+    /// # fn create(capabilities: &surface::Capabilities) {
+    /// let swp_type = swapchain::SwapchainCfg {
+    ///     present_mode: capabilities.choose_present_mode(&[swapchain::PresentMode::MAILBOX]),
+    ///     ..swapchain::SwapchainCfg::srgb(capabilities).expect("No sRGB formats")
+    /// };
+    /// # }
+    /// ```
+    pub fn srgb(capabilities: &surface::Capabilities) -> Result<SwapchainCfg, SwapchainError> {
+        let surf_format = capabilities
+            .formats()
+            .find(|f| formats::is_srgb(f.format) && f.color_space == memory::ColorSpace::SRGB_NONLINEAR)
+            .ok_or(SwapchainError::NoSrgbFormat)?;
+
+        Ok(
+            SwapchainCfg {
+                num_of_images: capabilities.min_img_count(),
+                format: surf_format.format,
+                color: surf_format.color_space,
+                present_mode: PresentMode::FIFO,
+                flags: memory::UsageFlags::COLOR_ATTACHMENT,
+                extent: capabilities.clamp_extent(capabilities.max_extent()),
+                transform: capabilities.pre_transformation(),
+                alpha: capabilities.first_alpha_composition().unwrap_or(memory::CompositeAlphaFlags::OPAQUE)
+            }
+        )
+    }
+}
+
+/// Swapchain handle, destroyed with the last reference
+///
+/// Shared with [images](Swapchain::images) so swapchain outlives views of its images
+struct CoreSwapchain {
     i_core: Arc<dev::Core>,
     i_loader: swapchain::Device,
     i_swapchain: vk::SwapchainKHR,
+}
+
+impl Drop for CoreSwapchain {
+    fn drop(&mut self) {
+        unsafe { self.i_loader.destroy_swapchain(self.i_swapchain, self.i_core.allocator()) };
+    }
+}
+
+pub struct Swapchain {
+    i_images: Vec<memory::ImageMemory>,
+    i_swapchain: Arc<CoreSwapchain>,
+    i_num_of_images: u32,
     i_format: vk::Format,
-    i_extent: memory::Extent2D
+    i_extent: memory::Extent2D,
+    i_usage: memory::UsageFlags,
+    i_color: memory::ColorSpace,
+    i_present_mode: PresentMode,
+    i_transform: surface::PreTransformation,
+    i_alpha: memory::CompositeAlphaFlags
 }
 
 impl Swapchain {
+    /// Returns [`SwapchainError::ZeroExtent`] while window is minimized
     pub fn new(lib: &libvk::Instance,
                dev: &dev::Device,
                surface: &surface::Surface,
@@ -110,12 +295,49 @@ impl Swapchain {
     ) -> Result<Swapchain, SwapchainError> {
         let loader = swapchain::Device::new(lib.instance(), dev.device());
 
+        let swapchain = Swapchain::create(dev, loader, surface, swp_type, vk::SwapchainKHR::null())?;
+
+        let images = Swapchain::query_images(&swapchain, swp_type.format, swp_type.extent, swp_type.flags)?;
+
+        Ok(
+            Swapchain {
+                i_images: images,
+                i_swapchain: swapchain,
+                i_num_of_images: swp_type.num_of_images,
+                i_format: swp_type.format,
+                i_extent: swp_type.extent,
+                i_usage: swp_type.flags,
+                i_color: swp_type.color,
+                i_present_mode: swp_type.present_mode,
+                i_transform: swp_type.transform,
+                i_alpha: swp_type.alpha
+            }
+        )
+    }
+
+    /// Create swapchain which replaces `old_swapchain`
+    fn create(
+        dev: &dev::Device,
+        loader: swapchain::Device,
+        surface: &surface::Surface,
+        swp_type: &SwapchainCfg,
+        old_swapchain: vk::SwapchainKHR
+    ) -> Result<Arc<CoreSwapchain>, SwapchainError> {
+        let capabilities = on_error_ret!(
+            surface::Capabilities::get(dev.hw(), surface),
+            SwapchainError::Capabilities
+        );
+
+        if swp_type.extent.width == 0 || swp_type.extent.height == 0 || capabilities.is_zero_extent() {
+            return Err(SwapchainError::ZeroExtent);
+        }
+
         let create_info = vk::SwapchainCreateInfoKHR {
             s_type: vk::StructureType::SWAPCHAIN_CREATE_INFO_KHR,
             p_next: ptr::null(),
             flags: vk::SwapchainCreateFlagsKHR::empty(),
             surface: surface.surface(),
-            min_image_count: swp_type.num_of_images,
+            min_image_count: capabilities.clamp_img_count(swp_type.num_of_images),
             image_format: swp_type.format,
             image_color_space: swp_type.color,
             image_extent: swp_type.extent,
@@ -128,65 +350,39 @@ impl Swapchain {
             composite_alpha: swp_type.alpha,
             present_mode: swp_type.present_mode,
             clipped: ash::vk::TRUE,
-            old_swapchain: vk::SwapchainKHR::null(),
+            old_swapchain,
             _marker: PhantomData,
         };
 
         let swapchain =
-            on_error_ret!(unsafe {loader.create_swapchain(&create_info, None)}, SwapchainError::Creating);
+            on_error!(unsafe {loader.create_swapchain(&create_info, dev.allocator())}, |err| return Err(SwapchainError::Creating(err)));
 
         Ok(
-            Swapchain {
+            Arc::new(CoreSwapchain {
                 i_core: dev.core().clone(),
                 i_loader: loader,
-                i_swapchain: swapchain,
-                i_format: swp_type.format,
-                i_extent: swp_type.extent
-            }
+                i_swapchain: swapchain
+            })
         )
     }
 
-    pub fn next_image(&self, timeout: u64, sem: Option<&sync::Semaphore>, fence: Option<&sync::Fence>)
-        -> Result<u32, SwapchainError>
-    {
-        let (image_index, _) = on_error_ret!(
-            unsafe {
-                self.i_loader.acquire_next_image(
-                    self.i_swapchain,
-                    timeout,
-                    if let Some(s) = sem {
-                        s.semaphore()
-                    } else {
-                        vk::Semaphore::null()
-                    },
-                    if let Some(f) = fence {
-                        f.fence()
-                    } else {
-                        vk::Fence::null()
-                    }
-                )
-            },
-            SwapchainError::NextImage
+    fn query_images(
+        swapchain: &Arc<CoreSwapchain>,
+        format: memory::ImageFormat,
+        extent: memory::Extent2D,
+        usage: memory::UsageFlags
+    ) -> Result<Vec<memory::ImageMemory>, SwapchainError> {
+        let swapchain_images = on_error!(
+            unsafe { swapchain.i_loader.get_swapchain_images(swapchain.i_swapchain) },
+            |err| return Err(SwapchainError::Images(err))
         );
 
-        Ok(image_index)
-    }
-
-    pub fn images(&self) -> Result<Vec<memory::ImageMemory>, SwapchainError> {
-        let mut result: Vec<memory::ImageMemory> = Vec::new();
-
-        let swapchain_images = on_error_ret!(
-            unsafe {
-                self.i_loader
-                    .get_swapchain_images(self.i_swapchain)
-            },
-            SwapchainError::Images
-        );
+        let mut result: Vec<memory::ImageMemory> = Vec::with_capacity(swapchain_images.len());
 
         for image in swapchain_images {
-            let memory = on_error_ret!(
-                memory::ImageMemory::preallocated(&self.i_core, image, self.i_format, self.i_extent),
-                SwapchainError::Images);
+            let memory = on_error!(
+                memory::ImageMemory::preallocated(&swapchain.i_core, swapchain.clone(), image, format, extent, usage),
+                |err| return Err(SwapchainError::ImageMemory(err)));
 
             result.push(memory);
         }
@@ -194,24 +390,217 @@ impl Swapchain {
         Ok(result)
     }
 
+    /// Acquire index of the next available image
+    ///
+    /// Zero `timeout` does not block and returns [`SwapchainError::NotReady`] if no image is available
+    ///
+    /// Timeout greater than `u64::MAX` nanoseconds (e.g. [`Duration::MAX`]) means wait forever
+    pub fn next_image(&self, timeout: Duration, sync: AcquireSync) -> Result<AcquiredImage, SwapchainError> {
+        let (sem, fence) = match sync {
+            AcquireSync::Semaphore(s) => (s.semaphore(), vk::Fence::null()),
+            AcquireSync::Fence(f) => (vk::Semaphore::null(), f.fence()),
+            AcquireSync::Both(s, f) => (s.semaphore(), f.fence()),
+            AcquireSync::None => return Err(SwapchainError::NoSync)
+        };
+
+        let timeout = u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX);
+
+        let result = unsafe {
+            self.i_swapchain.i_loader.acquire_next_image(self.i_swapchain.i_swapchain, timeout, sem, fence)
+        };
+
+        match result {
+            Ok((index, suboptimal)) => Ok(AcquiredImage { index, suboptimal }),
+            Err(err) => Err(SwapchainError::from(err))
+        }
+    }
+
+    /// Return presentable images
+    ///
+    /// Images have [usage](memory::ImageView::usage) from [`SwapchainCfg::flags`]
+    /// so with `STORAGE` flag they may be used as
+    /// [`STORAGE_IMAGE`](crate::graphics::DescriptorType::STORAGE_IMAGE) descriptors
+    ///
+    /// Images and their views are created once on swapchain creation (or [recreation](Swapchain::recreate))
+    /// so repeated calls return the same images
+    ///
+    /// Images keep the swapchain alive, so their views (e.g. used by framebuffers)
+    /// stay valid even if swapchain is dropped or recreated
+    pub fn images(&self) -> &[memory::ImageMemory] {
+        &self.i_images
+    }
+
+    /// Replace swapchain with the new one of the given `extent`
+    ///
+    /// Current swapchain is passed as
+    /// [`oldSwapchain`](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkSwapchainCreateInfoKHR.html)
+    /// and other parameters are taken from the original [`SwapchainCfg`]
+    ///
+    /// Images are replaced with images of the new swapchain.
+    /// Previous images are valid until their last reference is dropped,
+    /// however they **must not** be acquired or presented anymore
+    ///
+    /// Current swapchain is retired even on error, so it must be recreated again
+    /// (e.g. after [`SwapchainError::ZeroExtent`] when window is restored)
+    pub fn recreate(&mut self, dev: &dev::Device, surface: &surface::Surface, extent: memory::Extent2D) -> Result<(), SwapchainError> {
+        let swp_type = SwapchainCfg {
+            num_of_images: self.i_num_of_images,
+            format: self.i_format,
+            color: self.i_color,
+            present_mode: self.i_present_mode,
+            flags: self.i_usage,
+            extent,
+            transform: self.i_transform,
+            alpha: self.i_alpha
+        };
+
+        let swapchain = Swapchain::create(
+            dev,
+            self.i_swapchain.i_loader.clone(),
+            surface,
+            &swp_type,
+            self.i_swapchain.i_swapchain
+        )?;
+
+        self.i_images = Swapchain::query_images(&swapchain, swp_type.format, extent, swp_type.flags)?;
+        self.i_swapchain = swapchain;
+        self.i_extent = extent;
+
+        Ok(())
+    }
+
+    /// Return actual number of images in swapchain
+    ///
+    /// May be greater than [`SwapchainCfg::num_of_images`]
+    pub fn image_count(&self) -> u32 {
+        self.i_images.len() as u32
+    }
+
+    /// Return extent of the swapchain images
+    pub fn extent(&self) -> memory::Extent2D {
+        self.i_extent
+    }
+
     #[doc(hidden)]
     pub fn loader(&self) -> &swapchain::Device {
-        &self.i_loader
+        &self.i_swapchain.i_loader
     }
 
     #[doc(hidden)]
     pub fn swapchain(&self) -> vk::SwapchainKHR {
-        self.i_swapchain
+        self.i_swapchain.i_swapchain
     }
 
-    #[doc(hidden)]
-    pub fn format(&self) -> vk::Format {
+    /// Return format of the swapchain images
+    pub fn format(&self) -> memory::ImageFormat {
         self.i_format
     }
+
+    /// Return usage flags of the swapchain images
+    pub fn usage(&self) -> memory::UsageFlags {
+        self.i_usage
+    }
+
+    /// Return color space of the swapchain images
+    pub fn color_space(&self) -> memory::ColorSpace {
+        self.i_color
+    }
+
+    /// Return present mode the swapchain was created with
+    pub fn present_mode(&self) -> PresentMode {
+        self.i_present_mode
+    }
+
+    /// Copy image with `image_index` into host memory
+    ///
+    /// Swapchain **must be** created with `TRANSFER_SRC` in [`SwapchainCfg::flags`],
+    /// otherwise [`SwapchainError::NoTransferSrc`] is returned
+    ///
+    /// Image is expected in its [tracked layout](memory::ImageView::layout)
+    /// or in `PRESENT_SRC_KHR` if layout is not tracked yet
+    /// (e.g. it was changed only by render pass final layout).
+    /// Image is transitioned into `TRANSFER_SRC_OPTIMAL` for the copy and back after it
+    ///
+    /// Call blocks until copy is finished, `pool` **must be** created for the family of the `queue`
+    ///
+    /// `B8G8R8A8` images are converted into `R8G8B8A8`, see [`CpuImage::bgra_to_rgba`](memory::CpuImage::bgra_to_rgba)
+    pub fn screenshot(
+        &self,
+        device: &dev::Device,
+        queue: &queue::Queue,
+        pool: &cmd::Pool,
+        image_index: u32
+    ) -> Result<memory::CpuImage, SwapchainError> {
+        if !self.i_usage.contains(memory::UsageFlags::TRANSFER_SRC) {
+            return Err(SwapchainError::NoTransferSrc);
+        }
+
+        let image = self.i_images.get(image_index as usize).ok_or(
+            SwapchainError::Screenshot(memory::MemoryError::InvalidIndex {
+                index: image_index as usize,
+                count: self.i_images.len()
+            })
+        )?;
+
+        let view = image.view(0);
+
+        if view.layout() == memory::ImageLayout::UNDEFINED {
+            view.set_layout(memory::ImageLayout::PRESENT_SRC_KHR);
+        }
+
+        let extent = memory::Extent3D { width: self.i_extent.width, height: self.i_extent.height, depth: 1 };
+
+        let mut data = vec![0u8; formats::required_staging_size(self.i_format, extent) as usize];
+
+        let staging = memory::StagingBuffer::new(device, queue, pool);
+
+        on_error!(staging.download_image(view, &mut data), |err| return Err(SwapchainError::Screenshot(err)));
+
+        let mut screenshot = memory::CpuImage {
+            width: self.i_extent.width,
+            height: self.i_extent.height,
+            format: self.i_format,
+            data
+        };
+
+        screenshot.bgra_to_rgba();
+
+        Ok(screenshot)
+    }
 }
 
-impl Drop for Swapchain {
-    fn drop(&mut self) {
-        unsafe { self.i_loader.destroy_swapchain(self.i_swapchain, None) };
+impl fmt::Display for Swapchain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Swapchain\n\
+            Format:       {:?}\n\
+            Color space:  {:?}\n\
+            Extent:       {}x{}\n\
+            Image count:  {}\n\
+            Present mode: {:?}\n\
+            Usage:        {:?}\n",
+            self.format(),
+            self.color_space(),
+            self.extent().width,
+            self.extent().height,
+            self.image_count(),
+            self.present_mode(),
+            self.usage()
+        )
     }
-}
\ No newline at end of file
+}
+
+impl fmt::Debug for Swapchain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Swapchain")
+        .field("swapchain", &self.i_swapchain.i_swapchain)
+        .field("format", &self.i_format)
+        .field("color_space", &self.i_color)
+        .field("extent", &self.i_extent)
+        .field("usage", &self.i_usage)
+        .field("present_mode", &self.i_present_mode)
+        .field("images", &self.i_images)
+        .finish()
+    }
+}
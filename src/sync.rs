@@ -2,13 +2,15 @@
 
 use ash::vk;
 
-use crate::dev;
+use crate::{dev, swapchain};
 use crate::on_error_ret;
 
 use std::sync::Arc;
+use std::cell::RefCell;
 use std::{error, fmt, ptr};
 
 use std::marker::PhantomData;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum SemaphoreError {
@@ -43,6 +45,8 @@ impl Semaphore {
             SemaphoreError::Create
         );
 
+        device.core().created(dev::ObjectType::Semaphore, 1);
+
         Ok(Semaphore {
             i_core: device.core().clone(),
             i_semaphore: semaphore,
@@ -62,17 +66,46 @@ impl Drop for Semaphore {
                 .device()
                 .destroy_semaphore(self.i_semaphore, self.i_core.allocator());
         }
+
+        self.i_core.destroyed(dev::ObjectType::Semaphore, 1);
     }
 }
 
 #[derive(Debug)]
 pub enum FenceError {
     Create,
+    /// Failed to
+    /// [wait](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkWaitForFences.html)
+    /// fence or timeout is exceeded
+    Wait,
+    /// Failed to
+    /// [reset](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkResetFences.html)
+    /// fence
+    Reset,
+    /// Failed to
+    /// [get](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkGetFenceStatus.html)
+    /// fence status
+    Status,
 }
 
 impl fmt::Display for FenceError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Failed to create fence (vkCreateFence call failed)")
+        let err_msg = match self {
+            FenceError::Create => {
+                "Failed to create fence (vkCreateFence call failed)"
+            },
+            FenceError::Wait => {
+                "Failed to wait fence (vkWaitForFences call failed)"
+            },
+            FenceError::Reset => {
+                "Failed to reset fence (vkResetFences call failed)"
+            },
+            FenceError::Status => {
+                "Failed to get fence status (vkGetFenceStatus call failed)"
+            },
+        };
+
+        write!(f, "{:?}", err_msg)
     }
 }
 
@@ -101,12 +134,44 @@ impl Fence {
             FenceError::Create
         );
 
+        device.core().created(dev::ObjectType::Fence, 1);
+
         Ok(Fence {
             i_core: device.core().clone(),
             i_fence: fence,
         })
     }
 
+    /// Wait until fence is signaled or `timeout` (in nanoseconds) is exceeded
+    pub fn wait(&self, timeout: u64) -> Result<(), FenceError> {
+        on_error_ret!(
+            unsafe { self.i_core.device().wait_for_fences(&[self.i_fence], true, timeout) },
+            FenceError::Wait
+        );
+
+        Ok(())
+    }
+
+    /// Set fence into unsignaled state
+    pub fn reset(&self) -> Result<(), FenceError> {
+        on_error_ret!(
+            unsafe { self.i_core.device().reset_fences(&[self.i_fence]) },
+            FenceError::Reset
+        );
+
+        Ok(())
+    }
+
+    /// Return `true` if fence is signaled
+    pub fn status(&self) -> Result<bool, FenceError> {
+        let status = on_error_ret!(
+            unsafe { self.i_core.device().get_fence_status(self.i_fence) },
+            FenceError::Status
+        );
+
+        Ok(status)
+    }
+
     #[doc(hidden)]
     pub fn fence(&self) -> vk::Fence {
         self.i_fence
@@ -120,5 +185,328 @@ impl Drop for Fence {
                 .device()
                 .destroy_fence(self.i_fence, self.i_core.allocator());
         }
+
+        self.i_core.destroyed(dev::ObjectType::Fence, 1);
+    }
+}
+
+#[derive(Debug)]
+pub enum EventError {
+    Create,
+    /// Failed to
+    /// [set](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkSetEvent.html)
+    /// event
+    Set,
+    /// Failed to
+    /// [reset](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkResetEvent.html)
+    /// event
+    Reset,
+    /// Failed to
+    /// [get](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/vkGetEventStatus.html)
+    /// event status
+    Status,
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let err_msg = match self {
+            EventError::Create => {
+                "Failed to create event (vkCreateEvent call failed)"
+            },
+            EventError::Set => {
+                "Failed to set event (vkSetEvent call failed)"
+            },
+            EventError::Reset => {
+                "Failed to reset event (vkResetEvent call failed)"
+            },
+            EventError::Status => {
+                "Failed to get event status (vkGetEventStatus call failed)"
+            },
+        };
+
+        write!(f, "{:?}", err_msg)
+    }
+}
+
+impl error::Error for EventError {}
+
+/// Fine-grained synchronization within a single queue
+/// ([see more](https://registry.khronos.org/vulkan/specs/1.3-extensions/html/vkspec.html#synchronization-events))
+///
+/// Event is signaled by [`cmd::Buffer::set_event`](crate::cmd::Buffer::set_event) or [`set`](Self::set)
+/// and waited by [`cmd::Buffer::wait_events`](crate::cmd::Buffer::wait_events)
+///
+/// Host can not wait on event, only [poll](Self::status) it
+pub struct Event {
+    i_core: Arc<dev::Core>,
+    i_event: vk::Event,
+}
+
+impl Event {
+    /// Create event in unsignaled state
+    pub fn new(device: &dev::Device) -> Result<Event, EventError> {
+        let event_create_info = vk::EventCreateInfo {
+            s_type: vk::StructureType::EVENT_CREATE_INFO,
+            p_next: ptr::null(),
+            flags: vk::EventCreateFlags::empty(),
+            _marker: PhantomData,
+        };
+
+        let event = on_error_ret!(
+            unsafe { device.device().create_event(&event_create_info, device.allocator()) },
+            EventError::Create
+        );
+
+        Ok(Event {
+            i_core: device.core().clone(),
+            i_event: event,
+        })
+    }
+
+    /// Set event into signaled state from host
+    pub fn set(&self) -> Result<(), EventError> {
+        on_error_ret!(
+            unsafe { self.i_core.device().set_event(self.i_event) },
+            EventError::Set
+        );
+
+        Ok(())
+    }
+
+    /// Set event into unsignaled state from host
+    pub fn reset(&self) -> Result<(), EventError> {
+        on_error_ret!(
+            unsafe { self.i_core.device().reset_event(self.i_event) },
+            EventError::Reset
+        );
+
+        Ok(())
+    }
+
+    /// Return `true` if event is signaled
+    pub fn status(&self) -> Result<bool, EventError> {
+        let status = on_error_ret!(
+            unsafe { self.i_core.device().get_event_status(self.i_event) },
+            EventError::Status
+        );
+
+        Ok(status)
+    }
+
+    #[doc(hidden)]
+    pub fn event(&self) -> vk::Event {
+        self.i_event
+    }
+}
+
+impl Drop for Event {
+    fn drop(&mut self) {
+        unsafe {
+            self.i_core
+                .device()
+                .destroy_event(self.i_event, self.i_core.allocator());
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FrameSyncError {
+    /// Frame count must be at least 1
+    ZeroFrames,
+    /// Failed to create semaphore
+    CreateSemaphore(SemaphoreError),
+    /// Failed to create fence
+    CreateFence(FenceError),
+    /// Failed to wait frame's fence
+    Wait(FenceError),
+    /// Failed to reset frame's fence
+    Reset(FenceError),
+    /// Failed to acquire next image
+    Acquire(swapchain::SwapchainError),
+    /// Swapchain is out of date and must be recreated
+    OutOfDate,
+}
+
+impl fmt::Display for FrameSyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let err_msg = match self {
+            FrameSyncError::ZeroFrames => {
+                "Frame count must be at least 1"
+            },
+            FrameSyncError::CreateSemaphore(_) => {
+                "Failed to create frame semaphore"
+            },
+            FrameSyncError::CreateFence(_) => {
+                "Failed to create frame fence"
+            },
+            FrameSyncError::Wait(_) => {
+                "Failed to wait frame fence"
+            },
+            FrameSyncError::Reset(_) => {
+                "Failed to reset frame fence"
+            },
+            FrameSyncError::Acquire(_) => {
+                "Failed to acquire next image"
+            },
+            FrameSyncError::OutOfDate => {
+                "Swapchain is out of date and must be recreated"
+            },
+        };
+
+        write!(f, "{:?}", err_msg)
+    }
+}
+
+impl error::Error for FrameSyncError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            FrameSyncError::CreateSemaphore(err) => Some(err),
+            FrameSyncError::CreateFence(err)
+            | FrameSyncError::Wait(err)
+            | FrameSyncError::Reset(err) => Some(err),
+            FrameSyncError::Acquire(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+/// Synchronization primitives related to single frame
+///
+/// `image_available` is signaled when swapchain image is acquired
+///
+/// `render_finished` should be signaled by rendering submission and waited by presentation
+///
+/// `in_flight` should be signaled by rendering submission
+pub struct FrameResources {
+    i_image_available: Semaphore,
+    i_render_finished: Semaphore,
+    i_in_flight: Fence,
+}
+
+impl FrameResources {
+    fn new(device: &dev::Device) -> Result<FrameResources, FrameSyncError> {
+        Ok(FrameResources {
+            i_image_available: Semaphore::new(device).map_err(FrameSyncError::CreateSemaphore)?,
+            i_render_finished: Semaphore::new(device).map_err(FrameSyncError::CreateSemaphore)?,
+            i_in_flight: Fence::new(device, true).map_err(FrameSyncError::CreateFence)?,
+        })
+    }
+
+    pub fn image_available(&self) -> &Semaphore {
+        &self.i_image_available
+    }
+
+    pub fn render_finished(&self) -> &Semaphore {
+        &self.i_render_finished
+    }
+
+    pub fn in_flight(&self) -> &Fence {
+        &self.i_in_flight
+    }
+}
+
+/// Helper for "N frames in flight" pattern
+///
+/// Owns N sets of [`FrameResources`] and switches them in round-robin order
+///
+/// # Example
+///
+/// ```ignore
+/// let (image, frame) = frame_sync.acquire(&swapchain)?;
+///
+/// // Submit rendering that waits `frame.image_available()`,
+/// // signals `frame.render_finished()` and `frame.in_flight()`
+/// // then present image that waits `frame.render_finished()`
+///
+/// frame_sync.advance();
+/// ```
+pub struct FrameSync {
+    i_frames: Vec<FrameResources>,
+    i_current: usize,
+    i_images_in_flight: RefCell<Vec<Option<usize>>>,
+}
+
+impl FrameSync {
+    /// Create `count` sets of synchronization primitives
+    ///
+    /// All fences are created in signaled state
+    ///
+    /// Returns [`FrameSyncError::ZeroFrames`] if `count` is 0
+    pub fn new(device: &dev::Device, count: usize) -> Result<FrameSync, FrameSyncError> {
+        if count == 0 {
+            return Err(FrameSyncError::ZeroFrames);
+        }
+
+        let mut frames = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            frames.push(FrameResources::new(device)?);
+        }
+
+        Ok(FrameSync {
+            i_frames: frames,
+            i_current: 0,
+            i_images_in_flight: RefCell::new(Vec::new()),
+        })
     }
-}
\ No newline at end of file
+
+    /// Wait until current frame is available and acquire next image from swapchain
+    ///
+    /// If acquired image is still used by another frame then waits that frame too
+    ///
+    /// Fence of current frame is reset so it **must be** signaled by next submission
+    ///
+    /// Returned [`AcquiredImage::suboptimal`](swapchain::AcquiredImage::suboptimal) is set
+    /// if swapchain should be recreated
+    pub fn acquire(&self, swapchain: &swapchain::Swapchain) -> Result<(swapchain::AcquiredImage, &FrameResources), FrameSyncError> {
+        let frame = &self.i_frames[self.i_current];
+
+        frame.in_flight().wait(u64::MAX).map_err(FrameSyncError::Wait)?;
+
+        let image = match swapchain.next_image(Duration::MAX, swapchain::AcquireSync::Semaphore(frame.image_available())) {
+            Ok(val) => val,
+            Err(swapchain::SwapchainError::OutOfDate) => return Err(FrameSyncError::OutOfDate),
+            Err(err) => return Err(FrameSyncError::Acquire(err)),
+        };
+
+        let img_index = image.index;
+
+        let mut images = self.i_images_in_flight.borrow_mut();
+
+        if images.len() <= img_index as usize {
+            images.resize(img_index as usize + 1, None);
+        }
+
+        if let Some(other) = images[img_index as usize] {
+            if other != self.i_current {
+                self.i_frames[other].in_flight().wait(u64::MAX).map_err(FrameSyncError::Wait)?;
+            }
+        }
+
+        images[img_index as usize] = Some(self.i_current);
+
+        frame.in_flight().reset().map_err(FrameSyncError::Reset)?;
+
+        Ok((image, frame))
+    }
+
+    /// Switch to the next frame
+    pub fn advance(&mut self) {
+        self.i_current = (self.i_current + 1) % self.i_frames.len();
+    }
+
+    /// Return index of current frame
+    pub fn current(&self) -> usize {
+        self.i_current
+    }
+
+    /// Return resources of current frame
+    pub fn frame(&self) -> &FrameResources {
+        &self.i_frames[self.i_current]
+    }
+
+    /// Return number of frames
+    pub fn count(&self) -> usize {
+        self.i_frames.len()
+    }
+}
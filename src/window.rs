@@ -2,6 +2,8 @@
 
 use winit::window::WindowBuilder;
 use winit::event_loop::EventLoopBuilder;
+use winit::event::{Event, WindowEvent, DeviceEvent, KeyEvent, ElementState};
+use winit::keyboard::PhysicalKey;
 
 #[cfg(target_os = "linux")]
 use winit::platform::x11::EventLoopBuilderExtX11;
@@ -11,15 +13,31 @@ use winit::platform::wayland::EventLoopBuilderExtWayland;
 #[cfg(target_os = "windows")]
 use winit::platform::windows::EventLoopBuilderExtWindows;
 
+use crate::memory;
+
 use std::fmt;
+use std::error::Error;
+use std::collections::HashSet;
 
 pub type EventLoop = winit::event_loop::EventLoop<()>;
 pub type Window = winit::window::Window;
 
+/// Physical key code
+///
+#[doc = "Winit documentation about possible values <https://docs.rs/winit/0.29.15/winit/keyboard/enum.KeyCode.html>"]
+pub type KeyCode = winit::keyboard::KeyCode;
+
+/// State of the key or mouse button
+///
+#[doc = "Winit documentation about possible values <https://docs.rs/winit/0.29.15/winit/event/enum.ElementState.html>"]
+pub type KeyState = ElementState;
+
 #[derive(Debug)]
 pub enum WindowError {
     EventLoop,
-    Window
+    Window,
+    /// Event loop exited with error
+    Run
 }
 
 impl fmt::Display for WindowError {
@@ -31,12 +49,17 @@ impl fmt::Display for WindowError {
             WindowError::Window => {
                 "Failed to create window (winit error)"
             },
+            WindowError::Run => {
+                "Failed to run eventloop (winit error)"
+            },
         };
 
         write!(f, "{:?}", err_msg)
     }
 }
 
+impl Error for WindowError {}
+
 #[cfg(target_os = "linux")]
 /// Create new eventloop
 ///
@@ -72,4 +95,125 @@ pub fn create_window(eventloop: &EventLoop) -> Result<Window, WindowError> {
         Ok(result) => Ok(result),
         Err(_) => Err(WindowError::Window)
     }
-}
\ No newline at end of file
+}
+
+/// Keyboard, mouse and window size state collected from `winit` events
+///
+/// Mouse motion and resize are accumulated until [`end_frame`](InputState::end_frame)
+///
+/// [`run_render_loop`] updates state automatically
+#[derive(Debug, Default)]
+pub struct InputState {
+    i_keys: HashSet<KeyCode>,
+    i_mouse_delta: (f64, f64),
+    i_resized: Option<memory::Extent2D>,
+}
+
+impl InputState {
+    pub fn new() -> InputState {
+        InputState::default()
+    }
+
+    /// Update state from `event`
+    ///
+    /// Events which are not related to input are ignored
+    pub fn update(&mut self, event: &Event<()>) {
+        match event {
+            Event::WindowEvent { event: WindowEvent::KeyboardInput {
+                event: KeyEvent { physical_key: PhysicalKey::Code(key), state, .. },
+                ..
+            }, .. } => {
+                self.update_key(*key, *state);
+            },
+            Event::WindowEvent { event: WindowEvent::Focused(false), .. } => {
+                // Release events are not delivered to the window without focus
+                self.i_keys.clear();
+            },
+            Event::WindowEvent { event: WindowEvent::Resized(size), .. } => {
+                self.i_resized = Some(memory::Extent2D { width: size.width, height: size.height });
+            },
+            Event::DeviceEvent { event: DeviceEvent::MouseMotion { delta: (x, y) }, .. } => {
+                self.i_mouse_delta.0 += x;
+                self.i_mouse_delta.1 += y;
+            },
+            _ => ()
+        }
+    }
+
+    /// Set state of the `key` directly
+    pub fn update_key(&mut self, key: KeyCode, state: KeyState) {
+        match state {
+            KeyState::Pressed => self.i_keys.insert(key),
+            KeyState::Released => self.i_keys.remove(&key),
+        };
+    }
+
+    /// Is `key` pressed at the moment
+    pub fn is_key_down(&self, key: KeyCode) -> bool {
+        self.i_keys.contains(&key)
+    }
+
+    /// Return raw mouse motion since the last [`end_frame`](InputState::end_frame)
+    pub fn mouse_delta(&self) -> (f64, f64) {
+        self.i_mouse_delta
+    }
+
+    /// Return new window size if window was resized since the last [`end_frame`](InputState::end_frame)
+    pub fn was_resized(&self) -> Option<memory::Extent2D> {
+        self.i_resized
+    }
+
+    /// Reset mouse motion and resize, keys stay pressed
+    pub fn end_frame(&mut self) {
+        self.i_mouse_delta = (0.0, 0.0);
+        self.i_resized = None;
+    }
+}
+
+/// What [`run_render_loop`] should do after the frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlFlow {
+    Continue,
+    Exit
+}
+
+/// Single iteration of [`run_render_loop`]
+pub struct Frame<'a> {
+    pub window: &'a Window,
+    pub input: &'a InputState,
+}
+
+/// Run `eventloop` and call `render` for every redraw of `window`
+///
+/// Loop exits when window is closed or `render` returns [`ControlFlow::Exit`]
+///
+/// Redraw is requested every time the loop becomes idle
+pub fn run_render_loop<F>(eventloop: EventLoop, window: &Window, mut render: F) -> Result<(), WindowError>
+where
+    F: FnMut(Frame) -> ControlFlow
+{
+    let mut input = InputState::new();
+
+    let result = eventloop.run(move |event, target| {
+        input.update(&event);
+
+        match event {
+            Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
+                target.exit();
+            },
+            Event::AboutToWait => {
+                window.request_redraw();
+            },
+            Event::WindowEvent { event: WindowEvent::RedrawRequested, .. } => {
+                if render(Frame { window, input: &input }) == ControlFlow::Exit {
+                    target.exit();
+                }
+
+                input.end_frame();
+            },
+            _ => ()
+        }
+    });
+
+    result.map_err(|_| WindowError::Run)
+}
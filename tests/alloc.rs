@@ -0,0 +1,248 @@
+#[cfg(test)]
+mod alloc {
+    use libvktypes::{alloc, libvk, layers, extensions, hw, dev, memory, graphics, shader, cmd, queue};
+
+    use ash::vk;
+
+    use std::ffi::c_void;
+    use std::marker::PhantomData;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::{ptr, cmp};
+
+    static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+    static FREES: AtomicUsize = AtomicUsize::new(0);
+
+    // Size and alignment of the allocation are stored right before returned pointer
+    const HEADER: usize = 2*std::mem::size_of::<usize>();
+
+    unsafe fn layout(size: usize, alignment: usize) -> (std::alloc::Layout, usize) {
+        let offset = cmp::max(alignment, HEADER);
+
+        (std::alloc::Layout::from_size_align_unchecked(size + offset, cmp::max(alignment, HEADER)), offset)
+    }
+
+    unsafe fn allocate(size: usize, alignment: usize) -> *mut c_void {
+        let (layout, offset) = layout(size, alignment);
+
+        let base = std::alloc::alloc(layout);
+
+        if base.is_null() {
+            return ptr::null_mut();
+        }
+
+        let memory = base.add(offset);
+
+        *(memory.sub(HEADER) as *mut [usize; 2]) = [size, alignment];
+
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+
+        memory as *mut c_void
+    }
+
+    unsafe fn free(memory: *mut c_void) {
+        if memory.is_null() {
+            return;
+        }
+
+        let memory = memory as *mut u8;
+
+        let [size, alignment] = *(memory.sub(HEADER) as *const [usize; 2]);
+
+        let (layout, offset) = layout(size, alignment);
+
+        std::alloc::dealloc(memory.sub(offset), layout);
+
+        FREES.fetch_add(1, Ordering::SeqCst);
+    }
+
+    unsafe extern "system" fn on_allocation(
+        _: *mut c_void,
+        size: usize,
+        alignment: usize,
+        _: vk::SystemAllocationScope
+    ) -> *mut c_void {
+        allocate(size, alignment)
+    }
+
+    unsafe extern "system" fn on_reallocation(
+        _: *mut c_void,
+        original: *mut c_void,
+        size: usize,
+        alignment: usize,
+        _: vk::SystemAllocationScope
+    ) -> *mut c_void {
+        if original.is_null() {
+            return allocate(size, alignment);
+        }
+
+        if size == 0 {
+            free(original);
+            return ptr::null_mut();
+        }
+
+        let memory = allocate(size, alignment);
+
+        if !memory.is_null() {
+            let old_size = (*((original as *mut u8).sub(HEADER) as *const [usize; 2]))[0];
+
+            ptr::copy_nonoverlapping(original as *const u8, memory as *mut u8, cmp::min(size, old_size));
+
+            free(original);
+        }
+
+        memory
+    }
+
+    unsafe extern "system" fn on_free(_: *mut c_void, memory: *mut c_void) {
+        free(memory)
+    }
+
+    const VERT_SHADER: &str = "
+#version 460
+
+vec2 positions[3] = vec2[](
+    vec2( 0.5,  0.5),
+    vec2( 0.0, -0.5),
+    vec2(-0.5,  0.5)
+);
+
+void main() {
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+}
+";
+
+    const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(0.0, 1.0, 0.0, 1.0);
+}
+";
+
+    #[test]
+    fn balanced_allocations() {
+        let callback = alloc::Callback {
+            p_user_data: ptr::null_mut(),
+            pfn_allocation: Some(on_allocation),
+            pfn_reallocation: Some(on_reallocation),
+            pfn_free: Some(on_free),
+            pfn_internal_allocation: None,
+            pfn_internal_free: None,
+            _marker: PhantomData,
+        };
+
+        {
+            let lib_type = libvk::InstanceType {
+                debug_layer: Some(layers::DebugLayer::default()),
+                extensions: &[extensions::DEBUG_EXT_NAME],
+                allocator: Some(callback),
+                ..libvk::InstanceType::default()
+            };
+
+            let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+            let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+            let (hw_dev, qf, _) = hw_list
+                .find_first(
+                    hw::HWDevice::is_dedicated_gpu,
+                    hw::QueueFamilyDescription::is_graphics,
+                    |_| true
+                )
+                .expect("Failed to find suitable hardware device");
+
+            // Device inherits allocator of the instance
+            let dev_type = dev::DeviceCfg {
+                lib: &lib,
+                hw: hw_dev,
+                extensions: &[],
+                queues: &[],
+                features: hw::Features::default(),
+                dynamic_rendering: false,
+                allocator: None,
+                track_objects: false,
+            };
+
+            let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+            assert!(device.allocator().is_some());
+
+            let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+            let extent = memory::Extent2D { width: 64, height: 64 };
+
+            let image = memory::ImageMemory::offscreen(&device, format, extent).expect("Failed to allocate image");
+
+            let render_pass = graphics::RenderPass::offscreen(&device, format).expect("Failed to create render pass");
+
+            let framebuffer_cfg = memory::FramebufferCfg {
+                render_pass: &render_pass,
+                images: &[image.view(0)],
+                extent,
+            };
+
+            let framebuffer = memory::Framebuffer::new(&device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+            let shader_type = shader::ShaderCfg {
+                path: "ALLOC",
+                entry: "main",
+                include_dirs: &[],
+            };
+
+            let vert_shader = shader::Shader::from_glsl(&device, &shader_type, VERT_SHADER, shader::Kind::Vertex)
+                .expect("Failed to create vertex shader module");
+
+            let frag_shader = shader::Shader::from_glsl(&device, &shader_type, FRAG_SHADER, shader::Kind::Fragment)
+                .expect("Failed to create fragment shader module");
+
+            let descriptor = graphics::PipelineDescriptor::empty(&device);
+
+            let pipe_type = graphics::PipelineCfg {
+                extent,
+                render_pass: Some(&render_pass),
+                ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+            };
+
+            let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+            let cmd_pool_type = cmd::PoolCfg {
+                queue_index: qf.index(),
+            };
+
+            let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+            let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate cmd buffer");
+
+            cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+            cmd_buffer.bind_graphics_pipeline(&pipeline);
+            cmd_buffer.draw(3, 1, 0, 0);
+            cmd_buffer.end_render_pass();
+
+            let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+            let queue_type = queue::QueueCfg {
+                family_index: qf.index(),
+                queue_index: 0,
+            };
+
+            let cmd_queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+            let exec_info = queue::ExecInfo {
+                wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+                buffers: &[&exec_buffer],
+                timeout: u64::MAX,
+                wait: &[],
+                signal: &[],
+                fence: None,
+            };
+
+            cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+            assert_eq!(lib.error_count(), 0);
+        }
+
+        assert!(ALLOCATIONS.load(Ordering::SeqCst) > 0);
+        assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), FREES.load(Ordering::SeqCst));
+    }
+}
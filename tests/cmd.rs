@@ -41,7 +41,11 @@ mod cmd {
             lib: &lib,
             hw: hw_dev,
             extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
             allocator: None,
+            track_objects: false,
         };
 
         let device = dev::Device::new(&dev_type).expect("Failed to create device");
@@ -76,7 +80,11 @@ mod cmd {
             lib: &lib,
             hw: hw_dev,
             extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
             allocator: None,
+            track_objects: false,
         };
 
         let device = dev::Device::new(&dev_type).expect("Failed to create device");
@@ -100,14 +108,18 @@ mod cmd {
         let shader_type = shader::ShaderCfg {
             path: "tests/compiled_shaders/fill_memory.spv",
             entry: "main",
+            include_dirs: &[],
         };
 
         let shader = shader::Shader::from_file(&device, &shader_type).expect("Failed to create shader module");
 
         let pipe_type = compute::PipelineCfg {
             buffers: &[buff.view(0)],
+            descriptor: None,
             shader: &shader,
+            specialization: None,
             push_constant_size: 0,
+            cache: None,
         };
 
         let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
@@ -131,14 +143,15 @@ mod cmd {
             queue_index: 0,
         };
 
-        let queue = queue::Queue::new(&device, &queue_type);
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
 
         let exec_info = queue::ExecInfo {
             wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
-            buffer: &exec_buffer,
+            buffers: &[&exec_buffer],
             timeout: u64::MAX,
             wait: &[],
             signal: &[],
+            fence: None,
         };
 
         assert!(queue.exec(&exec_info).is_ok())
@@ -204,6 +217,10 @@ mod cmd {
                 layout: memory::ImageLayout::UNDEFINED,
                 aspect: memory::ImageAspect::COLOR,
                 tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
                 count: 1
             }
         ];
@@ -251,16 +268,2275 @@ mod cmd {
             queue_index: 0,
         };
 
-        let queue = queue::Queue::new(&device, &queue_type);
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
 
         let exec_info = queue::ExecInfo {
             wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
-            buffer: &exec_buffer,
+            buffers: &[&exec_buffer],
             timeout: u64::MAX,
             wait: &[],
             signal: &[],
+            fence: None,
         };
 
         assert!(queue.exec(&exec_info).is_ok())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn generate_mip_chain() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_SRGB;
+
+        let extent = memory::Extent3D {height: 256, width: 128, depth: 1 };
+
+        let staging_cfg = memory::BufferCfg {
+            size: (extent.width*extent.height) as u64*formats::block_size(format),
+            usage: memory::BufferUsageFlags::TRANSFER_SRC,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&staging_cfg]
+        };
+
+        let staging_buffer = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        staging_buffer.view(0).access(&mut |bytes: &mut [u8]| {
+            bytes.fill(0x42);
+        }).expect("Failed to write to the staging buffer");
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: format,
+                extent: extent,
+                usage: memory::ImageUsageFlags::SAMPLED
+                    | memory::ImageUsageFlags::TRANSFER_SRC
+                    | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: memory::max_mip_levels(extent),
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        let image = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image memory");
+
+        assert_eq!(image.view(0).mip_levels(), 9);
+
+        let pool = test_context::get_cmd_pool();
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::empty(),
+            cmd::AccessType::TRANSFER_WRITE,
+            memory::ImageLayout::UNDEFINED,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            graphics::PipelineStage::TOP_OF_PIPE,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_buffer_to_image(staging_buffer.view(0), image.view(0));
+
+        cmd_buffer.blit_mip_chain(image.view(0));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        assert!(queue.exec(&exec_info).is_ok())
+    }
+
+    #[test]
+    fn copy_to_cubemap() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent3D {height: 64, width: 64, depth: 1 };
+
+        let staging_cfg = memory::BufferCfg {
+            size: 6*(extent.width*extent.height) as u64*formats::block_size(format),
+            usage: memory::BufferUsageFlags::TRANSFER_SRC,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&staging_cfg]
+        };
+
+        let staging_buffer = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        staging_buffer.view(0).access(&mut |bytes: &mut [u8]| {
+            bytes.fill(0x42);
+        }).expect("Failed to write to the staging buffer");
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: format,
+                extent: extent,
+                usage: memory::ImageUsageFlags::SAMPLED | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::Cube,
+                array_layers: 6,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        let image = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image memory");
+
+        assert_eq!(image.view(0).array_layers(), 6);
+
+        let pool = test_context::get_cmd_pool();
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::empty(),
+            cmd::AccessType::TRANSFER_WRITE,
+            memory::ImageLayout::UNDEFINED,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            graphics::PipelineStage::TOP_OF_PIPE,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        // All faces at once
+        cmd_buffer.copy_buffer_to_image(staging_buffer.view(0), image.view(0));
+
+        // Face by face
+        for face in 0..6 {
+            cmd_buffer.copy_buffer_to_image_layers(staging_buffer.view(0), image.view(0), face, 1);
+        }
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        assert!(queue.exec(&exec_info).is_ok())
+    }
+
+    #[test]
+    fn copy_to_image_regions() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent3D {height: 64, width: 64, depth: 1 };
+
+        let quad_size = (32*32) as u64*formats::block_size(format);
+
+        let staging_cfg = memory::BufferCfg {
+            size: 4*quad_size,
+            usage: memory::BufferUsageFlags::TRANSFER_SRC,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&staging_cfg]
+        };
+
+        let staging_buffer = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        staging_buffer.view(0).access(&mut |bytes: &mut [u8]| {
+            bytes.fill(0x42);
+        }).expect("Failed to write to the staging buffer");
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: format,
+                extent: extent,
+                usage: memory::ImageUsageFlags::SAMPLED | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        let image = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image memory");
+
+        let regions: Vec<cmd::BufferImageRegion> = (0..4)
+            .map(|i| cmd::BufferImageRegion {
+                buffer_offset: i*quad_size,
+                image_offset: memory::Offset3D { x: 32*(i as i32 % 2), y: 32*(i as i32 / 2), z: 0 },
+                image_extent: memory::Extent3D { width: 32, height: 32, depth: 1 },
+                ..cmd::BufferImageRegion::full(image.view(0))
+            })
+            .collect();
+
+        let pool = test_context::get_cmd_pool();
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::empty(),
+            cmd::AccessType::TRANSFER_WRITE,
+            memory::ImageLayout::UNDEFINED,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            graphics::PipelineStage::TOP_OF_PIPE,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_buffer_to_image_region(staging_buffer.view(0), image.view(0), &regions);
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        assert!(queue.exec(&exec_info).is_ok())
+    }
+
+    #[test]
+    fn render_to_buffer() {
+        const VERT_SHADER: &str = "
+#version 460
+
+void main() {
+    vec2 positions[3] = vec2[](vec2(-1.0, -1.0), vec2(3.0, -1.0), vec2(-1.0, 3.0));
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+}
+";
+
+        const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(1.0, 0.0, 0.0, 1.0);
+}
+";
+
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent3D {height: 64, width: 64, depth: 1 };
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: format,
+                extent: extent,
+                usage: memory::ImageUsageFlags::COLOR_ATTACHMENT | memory::ImageUsageFlags::TRANSFER_SRC,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        let image = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image memory");
+
+        let readback_cfg = memory::BufferCfg {
+            size: (extent.width*extent.height) as u64*formats::block_size(format),
+            usage: memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&readback_cfg]
+        };
+
+        let readback = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        let render_pass = graphics::RenderPass::single_subpass(device, format).expect("Failed to create render pass");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[image.view(0)],
+            extent: memory::Extent2D { width: extent.width, height: extent.height },
+        };
+
+        let framebuffer = memory::Framebuffer::new(device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let vert_shader_type = shader::ShaderCfg {
+            path: "VERT_DATA",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert_shader = shader::Shader::from_glsl(device, &vert_shader_type, VERT_SHADER, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader module");
+
+        let frag_shader_type = shader::ShaderCfg {
+            path: "FRAG_DATA",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let frag_shader = shader::Shader::from_glsl(device, &frag_shader_type, FRAG_SHADER, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader module");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let pipe_type = graphics::PipelineCfg {
+            extent: framebuffer_cfg.extent,
+            render_pass: Some(&render_pass),
+            ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        let pool = test_context::get_cmd_pool();
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.draw(3, 1, 0, 0);
+        cmd_buffer.end_render_pass();
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::COLOR_ATTACHMENT_WRITE,
+            cmd::AccessType::TRANSFER_READ,
+            memory::ImageLayout::PRESENT_SRC_KHR,
+            memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            graphics::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_image_to_buffer(image.view(0), readback.view(0));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        readback.access(&mut |pixels: &mut [[u8; 4]]| {
+            assert_eq!(pixels[(32*extent.width + 32) as usize], [0xff, 0x00, 0x00, 0xff]);
+        }, 0).expect("Failed to read rendered image");
+    }
+
+    #[test]
+    fn indirect_draw() {
+        const VERT_SHADER: &str = "
+#version 460
+
+void main() {
+    vec2 positions[3] = vec2[](vec2(-1.0, -1.0), vec2(3.0, -1.0), vec2(-1.0, 3.0));
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+}
+";
+
+        const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(1.0, 0.0, 0.0, 1.0);
+}
+";
+
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent3D {height: 64, width: 64, depth: 1 };
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: format,
+                extent: extent,
+                usage: memory::ImageUsageFlags::COLOR_ATTACHMENT | memory::ImageUsageFlags::TRANSFER_SRC,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        let image = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image memory");
+
+        let readback_cfg = memory::BufferCfg {
+            size: (extent.width*extent.height) as u64*formats::block_size(format),
+            usage: memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&readback_cfg]
+        };
+
+        let readback = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        let indirect_cfg = memory::BufferCfg {
+            size: std::mem::size_of::<cmd::DrawIndirectCommand>() as u64,
+            usage: memory::INDIRECT,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let indirect_mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&indirect_cfg]
+        };
+
+        let indirect = memory::Memory::allocate(&device, &indirect_mem_cfg).expect("Failed to allocate memory");
+
+        indirect.access(&mut |commands: &mut [cmd::DrawIndirectCommand]| {
+            commands[0] = cmd::DrawIndirectCommand {
+                vertex_count: 3,
+                instance_count: 1,
+                first_vertex: 0,
+                first_instance: 0,
+            };
+        }, 0).expect("Failed to write indirect command");
+
+        let render_pass = graphics::RenderPass::single_subpass(device, format).expect("Failed to create render pass");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[image.view(0)],
+            extent: memory::Extent2D { width: extent.width, height: extent.height },
+        };
+
+        let framebuffer = memory::Framebuffer::new(device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let vert_shader_type = shader::ShaderCfg {
+            path: "VERT_DATA",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert_shader = shader::Shader::from_glsl(device, &vert_shader_type, VERT_SHADER, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader module");
+
+        let frag_shader_type = shader::ShaderCfg {
+            path: "FRAG_DATA",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let frag_shader = shader::Shader::from_glsl(device, &frag_shader_type, FRAG_SHADER, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader module");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let pipe_type = graphics::PipelineCfg {
+            extent: framebuffer_cfg.extent,
+            render_pass: Some(&render_pass),
+            ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        let pool = test_context::get_cmd_pool();
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.draw_indirect(indirect.view(0), 0, 1, std::mem::size_of::<cmd::DrawIndirectCommand>() as u32);
+        cmd_buffer.end_render_pass();
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::COLOR_ATTACHMENT_WRITE,
+            cmd::AccessType::TRANSFER_READ,
+            memory::ImageLayout::PRESENT_SRC_KHR,
+            memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            graphics::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_image_to_buffer(image.view(0), readback.view(0));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        readback.access(&mut |pixels: &mut [[u8; 4]]| {
+            assert_eq!(pixels[(32*extent.width + 32) as usize], [0xff, 0x00, 0x00, 0xff]);
+        }, 0).expect("Failed to read rendered image");
+    }
+
+    #[test]
+    fn blit_downsample() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let src_extent = memory::Extent3D { width: 4, height: 4, depth: 1 };
+
+        let dst_extent = memory::Extent3D { width: 2, height: 2, depth: 1 };
+
+        let texel_size = formats::block_size(format);
+
+        let staging_cfg = memory::BufferCfg {
+            size: (src_extent.width*src_extent.height) as u64*texel_size,
+            usage: memory::BufferUsageFlags::TRANSFER_SRC,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let readback_cfg = memory::BufferCfg {
+            size: (dst_extent.width*dst_extent.height) as u64*texel_size,
+            usage: memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&staging_cfg, &readback_cfg]
+        };
+
+        let buffers = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        // Every 2x2 block contains 0, 64, 128 and 192 so average is 96
+        buffers.access(&mut |pixels: &mut [[u8; 4]]| {
+            for (i, pixel) in pixels.iter_mut().enumerate() {
+                let value = 64*(((i/4) % 2)*2 + (i % 4) % 2) as u8;
+                *pixel = [value, value, value, 0xff];
+            }
+        }, 0).expect("Failed to fill staging buffer");
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: format,
+                extent: src_extent,
+                usage: memory::ImageUsageFlags::TRANSFER_SRC | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            },
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: format,
+                extent: dst_extent,
+                usage: memory::ImageUsageFlags::TRANSFER_SRC | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        let images = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image memory");
+
+        let src = images.view(0);
+
+        let dst = images.view(1);
+
+        let pool = test_context::get_cmd_pool();
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.set_image_barrier(
+            src,
+            cmd::AccessType::empty(),
+            cmd::AccessType::TRANSFER_WRITE,
+            memory::ImageLayout::UNDEFINED,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            graphics::PipelineStage::TOP_OF_PIPE,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_buffer_to_image(buffers.view(0), src);
+
+        cmd_buffer.set_image_barrier(
+            src,
+            cmd::AccessType::TRANSFER_WRITE,
+            cmd::AccessType::TRANSFER_READ,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            graphics::PipelineStage::TRANSFER,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.set_image_barrier(
+            dst,
+            cmd::AccessType::empty(),
+            cmd::AccessType::TRANSFER_WRITE,
+            memory::ImageLayout::UNDEFINED,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            graphics::PipelineStage::TOP_OF_PIPE,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.blit_image(src, dst, cmd::Filter::LINEAR);
+
+        cmd_buffer.set_image_barrier(
+            dst,
+            cmd::AccessType::TRANSFER_WRITE,
+            cmd::AccessType::TRANSFER_READ,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            graphics::PipelineStage::TRANSFER,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        assert_eq!(dst.layout(), memory::ImageLayout::TRANSFER_SRC_OPTIMAL);
+
+        cmd_buffer.copy_image_to_buffer(dst, buffers.view(1));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        buffers.access(&mut |pixels: &mut [[u8; 4]]| {
+            for pixel in pixels.iter() {
+                assert!(pixel[0].abs_diff(96) <= 1, "Unexpected pixel {:?}", pixel);
+                assert_eq!(pixel[3], 0xff);
+            }
+        }, 1).expect("Failed to read downsampled image");
+    }
+
+    #[test]
+    fn clear_offscreen_target() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent3D {height: 16, width: 16, depth: 1 };
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: format,
+                extent: extent,
+                usage: memory::ImageUsageFlags::COLOR_ATTACHMENT
+                    | memory::ImageUsageFlags::TRANSFER_SRC
+                    | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        let image = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image memory");
+
+        let readback_cfg = memory::BufferCfg {
+            size: (extent.width*extent.height) as u64*formats::block_size(format),
+            usage: memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 2
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&readback_cfg]
+        };
+
+        let readback = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        let render_pass = graphics::RenderPass::single_subpass(device, format).expect("Failed to create render pass");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[image.view(0)],
+            extent: memory::Extent2D { width: extent.width, height: extent.height },
+        };
+
+        let framebuffer = memory::Framebuffer::new(device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let pool = test_context::get_cmd_pool();
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        // Sky color
+        cmd_buffer.begin_render_pass_with_clear(&render_pass, &framebuffer, &[cmd::ClearValue::Color([0.0, 0.6, 1.0, 1.0])]);
+        cmd_buffer.end_render_pass();
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::COLOR_ATTACHMENT_WRITE,
+            cmd::AccessType::TRANSFER_READ,
+            memory::ImageLayout::PRESENT_SRC_KHR,
+            memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            graphics::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_image_to_buffer(image.view(0), readback.view(0));
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::TRANSFER_READ,
+            cmd::AccessType::TRANSFER_WRITE,
+            memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            graphics::PipelineStage::TRANSFER,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.clear_color_image(image.view(0), [1.0, 0.0, 1.0, 1.0]);
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::TRANSFER_WRITE,
+            cmd::AccessType::TRANSFER_READ,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            graphics::PipelineStage::TRANSFER,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_image_to_buffer(image.view(0), readback.view(1));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        readback.access(&mut |pixels: &mut [[u8; 4]]| {
+            assert!(pixels.iter().all(|&x| x[0] == 0x00 && x[1].abs_diff(153) <= 1 && x[2] == 0xff && x[3] == 0xff));
+        }, 0).expect("Failed to read cleared attachment");
+
+        readback.access(&mut |pixels: &mut [[u8; 4]]| {
+            assert!(pixels.iter().all(|&x| x == [0xff, 0x00, 0xff, 0xff]));
+        }, 1).expect("Failed to read cleared image");
+    }
+
+    #[test]
+    fn transfer_granularity() {
+        let image_extent = memory::Extent3D { width: 100, height: 60, depth: 1 };
+
+        let region = |x: i32, y: i32, width: u32, height: u32| cmd::BufferImageRegion {
+            buffer_offset: 0,
+            buffer_row_length: 0,
+            image_offset: memory::Offset3D { x, y, z: 0 },
+            image_extent: memory::Extent3D { width, height, depth: 1 },
+            base_layer: 0,
+            layer_count: 1,
+        };
+
+        let any = memory::Extent3D { width: 1, height: 1, depth: 1 };
+
+        assert!(region(3, 7, 11, 13).fits_granularity(any, image_extent));
+
+        let whole_only = memory::Extent3D { width: 0, height: 0, depth: 0 };
+
+        assert!(region(0, 0, 100, 60).fits_granularity(whole_only, image_extent));
+        assert!(!region(0, 0, 50, 60).fits_granularity(whole_only, image_extent));
+        assert!(!region(10, 0, 90, 60).fits_granularity(whole_only, image_extent));
+
+        let blocks = memory::Extent3D { width: 16, height: 16, depth: 1 };
+
+        assert!(region(16, 32, 32, 16).fits_granularity(blocks, image_extent));
+        // Extent is not aligned but reaches image border
+        assert!(region(96, 48, 4, 12).fits_granularity(blocks, image_extent));
+        assert!(!region(8, 0, 16, 16).fits_granularity(blocks, image_extent));
+        assert!(!region(0, 0, 20, 16).fits_granularity(blocks, image_extent));
+    }
+
+    #[test]
+    fn download_rendered_image() {
+        const VERT_SHADER: &str = "
+#version 460
+
+void main() {
+    vec2 positions[3] = vec2[](vec2(-1.0, -1.0), vec2(3.0, -1.0), vec2(-1.0, 3.0));
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+}
+";
+
+        const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(1.0, 0.0, 0.0, 1.0);
+}
+";
+
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent3D {height: 64, width: 64, depth: 1 };
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: format,
+                extent: extent,
+                usage: memory::ImageUsageFlags::COLOR_ATTACHMENT | memory::ImageUsageFlags::TRANSFER_SRC,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        let image = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image memory");
+
+        let render_pass = graphics::RenderPass::single_subpass(device, format).expect("Failed to create render pass");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[image.view(0)],
+            extent: memory::Extent2D { width: extent.width, height: extent.height },
+        };
+
+        let framebuffer = memory::Framebuffer::new(device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let vert_shader_type = shader::ShaderCfg {
+            path: "VERT_DATA",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert_shader = shader::Shader::from_glsl(device, &vert_shader_type, VERT_SHADER, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader module");
+
+        let frag_shader_type = shader::ShaderCfg {
+            path: "FRAG_DATA",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let frag_shader = shader::Shader::from_glsl(device, &frag_shader_type, FRAG_SHADER, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader module");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let pipe_type = graphics::PipelineCfg {
+            extent: framebuffer_cfg.extent,
+            render_pass: Some(&render_pass),
+            ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        let pool = test_context::get_cmd_pool();
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.draw(3, 1, 0, 0);
+        cmd_buffer.end_render_pass();
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        // Render pass leaves image in its final layout
+        image.view(0).set_layout(memory::ImageLayout::PRESENT_SRC_KHR);
+
+        let pixels = image.view(0).download(device, &queue, pool).expect("Failed to download image");
+
+        assert_eq!(pixels.len() as u64, (extent.width*extent.height) as u64*formats::block_size(format));
+
+        let pixel = 4*(32*extent.width + 32) as usize;
+
+        assert_eq!(pixels[pixel..pixel + 4], [0xff, 0x00, 0x00, 0xff]);
+
+        // Layout is restored after download
+        assert_eq!(image.view(0).layout(), memory::ImageLayout::PRESENT_SRC_KHR);
+    }
+
+    #[test]
+    fn view_equality_and_usage() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 64,
+            usage: memory::VERTEX,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 2
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let first = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+        let second = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let view = first.view(0);
+        let copy = view;
+
+        assert_eq!(view, copy);
+        assert_eq!(view, first.view(0));
+        assert_ne!(view, first.view(1));
+        assert_ne!(view, second.view(0));
+
+        assert_eq!(view.usage(), memory::VERTEX);
+
+        let images = test_context::get_image_list();
+
+        let image = images[0].view(0);
+
+        assert_eq!(image, images[0].view(0));
+        assert_ne!(image, images[1].view(0));
+        assert_eq!(image.format(), images[0].view(0).format());
+        assert!(image.usage().contains(memory::ImageUsageFlags::COLOR_ATTACHMENT));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "INDEX_BUFFER")]
+    fn index_buffer_usage_check() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 64,
+            usage: memory::VERTEX,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let memory = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let cmd_buffer = test_context::get_cmd_pool().allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.bind_index_buffer(memory.view(0), 0, memory::IndexBufferType::UINT32);
+    }
+
+    #[test]
+    fn layout_validation() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let pool = test_context::get_cmd_pool();
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: memory::ImageFormat::R8G8B8A8_UNORM,
+                extent: memory::Extent3D { width: 4, height: 4, depth: 1 },
+                usage: memory::ImageUsageFlags::TRANSFER_SRC | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let image_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        let image = memory::ImageMemory::allocate(device, &image_info).expect("Failed to allocate image");
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 64,
+            usage: memory::FULL_TRANSFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let staging = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        assert!(matches!(
+            cmd_buffer.try_copy_buffer_to_image(staging.view(0), image.view(0)),
+            Err(cmd::BufferError::ImageLayout { layout: memory::ImageLayout::UNDEFINED })
+        ));
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::NONE,
+            cmd::AccessType::TRANSFER_WRITE,
+            memory::ImageLayout::UNDEFINED,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            cmd::PipelineStage::TOP_OF_PIPE,
+            cmd::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_buffer_to_image(staging.view(0), image.view(0));
+
+        cmd_buffer.clear_color_image(image.view(0), [0.0, 0.0, 0.0, 1.0]);
+
+        // Image is still TRANSFER_DST_OPTIMAL
+        cmd_buffer.copy_image_to_buffer(image.view(0), staging.view(0));
+
+        let result = cmd_buffer.commit();
+
+        if cfg!(debug_assertions) {
+            match result {
+                Err(cmd::BufferError::Record(err)) => {
+                    assert_eq!(err.violations, vec![cmd::LayoutViolation {
+                        command: 3,
+                        name: "copy_image_to_buffer",
+                        layout: memory::ImageLayout::TRANSFER_DST_OPTIMAL
+                    }]);
+                },
+                _ => panic!("Layout violation is not reported")
+            }
+        } else {
+            assert!(result.is_ok());
+        }
+    }
+
+    #[test]
+    fn buffer_reset() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 4,
+            usage: memory::FULL_TRANSFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 2
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let pool = cmd::Pool::new(device, &cmd::PoolCfg { queue_index: queue.index() })
+            .expect("Failed to allocate command pool");
+
+        let cmd_queue = queue::Queue::new(device, &queue::QueueCfg { family_index: queue.index(), queue_index: 0 })
+            .expect("Failed to get queue");
+
+        let mut cmd_buffer = pool.allocate().expect("Failed to allocate command buffer");
+
+        for i in 0..100u32 {
+            data.view(0).access(&mut |bytes: &mut [u32]| bytes[0] = i).expect("Failed to write memory");
+
+            cmd_buffer.copy_memory(&data.view(0), &data.view(1));
+
+            let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+            let exec_info = queue::ExecInfo {
+                wait_stage: cmd::PipelineStage::TRANSFER,
+                buffers: &[&exec_buffer],
+                timeout: u64::MAX,
+                wait: &[],
+                signal: &[],
+                fence: None,
+            };
+
+            cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+            data.view(1).access(&mut |bytes: &mut [u32]| assert_eq!(bytes[0], i)).expect("Failed to read memory");
+
+            cmd_buffer = exec_buffer.reset().expect("Failed to reset command buffer");
+        }
+
+        assert!(matches!(pool.reset(), Err(cmd::PoolError::OutstandingBuffers)));
+
+        drop(cmd_buffer);
+
+        assert!(pool.reset().is_ok());
+    }
+
+    #[test]
+    fn one_time_submit() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 4,
+            usage: memory::FULL_TRANSFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 2
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        data.view(0).access(&mut |bytes: &mut [u32]| bytes[0] = 42).expect("Failed to write memory");
+
+        let pool = test_context::get_cmd_pool();
+
+        let cmd_queue = queue::Queue::new(device, &queue::QueueCfg { family_index: queue.index(), queue_index: 0 })
+            .expect("Failed to get queue");
+
+        // ONE_TIME_SUBMIT buffer can not become reusable ExecutableBuffer
+        let cmd_buffer = pool.allocate_with(cmd::BufferUsage::ONE_TIME_SUBMIT).expect("Failed to allocate command buffer");
+
+        assert!(matches!(cmd_buffer.commit(), Err(cmd::BufferError::OneTimeSubmit)));
+
+        let cmd_buffer = pool.allocate_with(cmd::BufferUsage::ONE_TIME_SUBMIT).expect("Failed to allocate command buffer");
+
+        cmd_buffer.copy_memory(&data.view(0), &data.view(1));
+
+        let one_time: cmd::OneTimeBuffer = cmd_buffer.commit_once().expect("Failed to commit command buffer");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        // Buffer is moved here, second exec_once does not compile
+        cmd_queue.exec_once(one_time, &exec_info).expect("Failed to execute queue");
+
+        data.view(1).access(&mut |bytes: &mut [u32]| assert_eq!(bytes[0], 42)).expect("Failed to read memory");
+
+        // Reusable buffers keep working as before
+        let cmd_buffer = pool.allocate_with(cmd::BufferUsage::SIMULTANEOUS_USE).expect("Failed to allocate command buffer");
+
+        cmd_buffer.copy_memory(&data.view(1), &data.view(0));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let exec_info = queue::ExecInfo {
+            buffers: &[&exec_buffer],
+            ..exec_info
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+    }
+
+    #[test]
+    fn keepalive_after_commit() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 4,
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        data.view(0).access(&mut |bytes: &mut [u32]| bytes[0] = 42).expect("Failed to write memory");
+
+        let shader_cfg = shader::ShaderCfg {
+            path: "tests/compiled_shaders/fill_memory.spv",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_file(device, &shader_cfg).expect("Failed to create shader module");
+
+        let pipe_cfg = compute::PipelineCfg {
+            buffers: &[data.view(0)],
+            descriptor: None,
+            shader: &shader,
+            specialization: None,
+            push_constant_size: 0,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(device, &pipe_cfg).expect("Failed to create pipeline");
+
+        let pool = cmd::Pool::new(device, &cmd::PoolCfg { queue_index: queue.index() })
+            .expect("Failed to allocate command pool");
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        cmd_buffer.dispatch(1, 1, 1);
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        // Pipeline is still referenced by the buffer
+        drop(pipeline);
+        drop(shader);
+
+        let cmd_queue = queue::Queue::new(device, &queue::QueueCfg { family_index: queue.index(), queue_index: 0 })
+            .expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+        data.view(0).access(&mut |bytes: &mut [u32]| assert_eq!(bytes[0], 0)).expect("Failed to read memory");
+    }
+
+    #[test]
+    fn group_count() {
+        assert_eq!(cmd::group_count([1920, 1080, 1], [16, 16, 1]), [120, 68, 1]);
+        assert_eq!(cmd::group_count([1, 1, 1], [64, 1, 1]), [1, 1, 1]);
+        assert_eq!(cmd::group_count([0, 0, 0], [8, 8, 8]), [0, 0, 0]);
+        assert_eq!(cmd::group_count([65, 7, 3], [64, 0, 2]), [2, 7, 2]);
+    }
+
+    #[test]
+    fn dispatch_limits() {
+        let hw = test_context::get_compute_hw();
+
+        let device = test_context::get_compute_device();
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: test_context::get_compute_queue().index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        let max_count = hw.max_work_group_count();
+
+        if max_count[1] < u32::MAX {
+            let err = cmd_buffer.dispatch_checked(1, max_count[1] + 1, 1).expect_err("Limit must be checked");
+
+            assert!(err.violations.is_empty());
+            assert_eq!(err.limits, vec![cmd::LimitViolation {
+                command: 0,
+                name: "dispatch_checked",
+                limit: "maxComputeWorkGroupCount",
+                dimension: 1,
+                value: max_count[1] + 1,
+                max: max_count[1]
+            }]);
+        }
+
+        let max_size = hw.max_work_group_size();
+
+        let err = cmd_buffer.dispatch_for([1, 1, 1], [1, 1, max_size[2] + 1]).expect_err("Limit must be checked");
+
+        assert_eq!(err.limits[0].limit, "maxComputeWorkGroupSize");
+        assert_eq!(err.limits[0].dimension, 2);
+
+        let local = [max_size[0], max_size[1], 1];
+
+        if (local[0] as u64)*(local[1] as u64) > hw.max_work_group_invocations() as u64 {
+            let err = cmd_buffer.dispatch_for([1, 1, 1], local).expect_err("Limit must be checked");
+
+            assert_eq!(err.limits[0].limit, "maxComputeWorkGroupInvocations");
+        }
+
+        // Nothing was recorded
+        assert!(cmd_buffer.commit().is_ok());
+    }
+
+    #[test]
+    fn batched_barriers() {
+        let device = test_context::get_compute_device();
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: test_context::get_compute_queue().index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let images: Vec<memory::ImageMemory> = (0..10)
+            .map(|_| {
+                memory::ImageMemory::offscreen(
+                    device,
+                    memory::ImageFormat::R8G8B8A8_UNORM,
+                    memory::Extent2D { width: 16, height: 16 }
+                )
+                .expect("Failed to allocate image")
+            })
+            .collect();
+
+        let barriers: Vec<cmd::ImageBarrier> = images
+            .iter()
+            .map(|img| cmd::ImageBarrier {
+                view: img.view(0),
+                src_type: cmd::AccessType::NONE,
+                dst_type: cmd::AccessType::TRANSFER_WRITE,
+                src_layout: memory::ImageLayout::UNDEFINED,
+                dst_layout: memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+                src_queue_family: cmd::QUEUE_FAMILY_IGNORED,
+                dst_queue_family: cmd::QUEUE_FAMILY_IGNORED,
+            })
+            .collect();
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.set_barriers(
+            cmd::PipelineStage::TOP_OF_PIPE,
+            cmd::PipelineStage::TRANSFER,
+            &[],
+            &[],
+            &barriers
+        );
+
+        assert_eq!(cmd_buffer.command_count(), 1);
+        assert!(images.iter().all(|img| img.view(0).layout() == memory::ImageLayout::TRANSFER_DST_OPTIMAL));
+
+        cmd_buffer.set_global_barrier(
+            cmd::AccessType::TRANSFER_WRITE,
+            cmd::AccessType::SHADER_READ,
+            cmd::PipelineStage::TRANSFER,
+            cmd::PipelineStage::COMPUTE_SHADER
+        );
+
+        assert_eq!(cmd_buffer.command_count(), 2);
+
+        assert!(cmd_buffer.commit().is_ok());
+    }
+
+    #[test]
+    fn per_thread_recording() {
+        const THREADS: usize = 4;
+
+        let device = test_context::get_graphics_device();
+
+        let queue_family = test_context::get_graphics_queue();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 256,
+            usage: memory::BufferUsageFlags::TRANSFER_SRC | memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue_family.index()],
+            simultaneous_access: false,
+            count: 2*THREADS
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let memory = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        for i in 0..2*THREADS {
+            memory.access(&mut |bytes: &mut [u32]| bytes.fill(i as u32), i).expect("Failed to write memory");
+        }
+
+        fn is_send<T: Send>() {}
+
+        is_send::<cmd::Pool>();
+        is_send::<cmd::Buffer>();
+        is_send::<cmd::ExecutableBuffer>();
+        is_send::<queue::Queue>();
+
+        fn is_sync<T: Sync>() {}
+
+        is_sync::<queue::Queue>();
+
+        // Validation layer of the test device checks thread safety by default
+        let pools = cmd::PoolSet::per_thread(device, queue_family.index(), THREADS).expect("Failed to create pools");
+
+        assert_eq!(pools.len(), THREADS);
+
+        // Every thread copies its own source buffer into its own destination
+        let exec_buffers: Vec<cmd::ExecutableBuffer> = std::thread::scope(|s| {
+            let workers: Vec<_> = pools
+                .into_iter()
+                .enumerate()
+                .map(|(i, pool)| {
+                    let memory = &memory;
+
+                    s.spawn(move || {
+                        let cmd_buffer = pool.allocate().expect("Failed to allocate command buffer");
+
+                        cmd_buffer.copy_memory(&memory.view(i), &memory.view(THREADS + i));
+
+                        cmd_buffer.commit().expect("Failed to commit buffer")
+                    })
+                })
+                .collect();
+
+            workers.into_iter().map(|w| w.join().expect("Worker panicked")).collect()
+        });
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue_family.index(),
+            queue_index: 0
+        };
+
+        let queue = queue::Queue::new(device, &queue_type).expect("Failed to get queue");
+
+        let buffers: Vec<&cmd::ExecutableBuffer> = exec_buffers.iter().collect();
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &buffers,
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        for i in 0..THREADS {
+            memory.access(&mut |bytes: &mut [u32]| assert!(bytes.iter().all(|&x| x == i as u32)), THREADS + i)
+                .expect("Failed to read memory");
+        }
+    }
+
+    #[test]
+    fn vertex_range_validation() {
+        const VERT_SHADER: &str = "
+#version 460
+
+layout(location = 0) in vec4 position;
+
+void main() {
+    gl_Position = position;
+}
+";
+
+        const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(1.0, 0.0, 0.0, 1.0);
+}
+";
+
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent2D { width: 16, height: 16 };
+
+        let image = memory::ImageMemory::offscreen(device, format, extent).expect("Failed to allocate image");
+
+        let render_pass = graphics::RenderPass::offscreen(device, format).expect("Failed to create render pass");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[image.view(0)],
+            extent,
+        };
+
+        let framebuffer = memory::Framebuffer::new(device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let vertex_cfg = memory::BufferCfg {
+            size: 3*16,
+            usage: memory::BufferUsageFlags::VERTEX_BUFFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let index_cfg = memory::BufferCfg {
+            size: 3*4,
+            usage: memory::BufferUsageFlags::INDEX_BUFFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&vertex_cfg, &index_cfg]
+        };
+
+        let memory = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let shader_type = shader::ShaderCfg {
+            path: "VERTEX_RANGE",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert_shader = shader::Shader::from_glsl(device, &shader_type, VERT_SHADER, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader module");
+
+        let frag_shader = shader::Shader::from_glsl(device, &shader_type, FRAG_SHADER, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader module");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let pipe_type = graphics::PipelineCfg {
+            vertex_size: 16,
+            vert_input: &[graphics::VertexInputCfg {
+                location: 0,
+                binding: 0,
+                format: memory::ImageFormat::R32G32B32A32_SFLOAT,
+                offset: 0,
+            }],
+            extent,
+            render_pass: Some(&render_pass),
+            ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        assert_eq!(pipeline.vertex_size(), 16);
+
+        let pool = test_context::get_cmd_pool();
+
+        // Every vertex and index fits into the bound buffers
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.bind_vertex_buffers(&[memory.vertex_view(0, 0).expect("Failed to create vertex view")]);
+        cmd_buffer.bind_index_buffer(memory.view(1), 0, memory::IndexBufferType::UINT32);
+        cmd_buffer.draw(3, 1, 0, 0);
+        cmd_buffer.draw_indexed(3, 1, 0, 0, 0);
+        cmd_buffer.end_render_pass();
+
+        cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.bind_vertex_buffers(&[memory.vertex_view(0, 16).expect("Failed to create vertex view")]);
+        cmd_buffer.bind_index_buffer(memory.view(1), 4, memory::IndexBufferType::UINT32);
+        cmd_buffer.draw(3, 1, 0, 0);
+        cmd_buffer.draw_indexed(2, 1, 1, 0, 0);
+        cmd_buffer.end_render_pass();
+
+        let result = cmd_buffer.commit();
+
+        if cfg!(debug_assertions) {
+            match result {
+                Err(cmd::BufferError::Record(err)) => {
+                    assert!(err.violations.is_empty());
+                    assert_eq!(err.ranges, vec![
+                        cmd::RangeViolation {
+                            command: 4,
+                            name: "draw",
+                            binding: Some(0),
+                            required: 48,
+                            available: 32
+                        },
+                        cmd::RangeViolation {
+                            command: 5,
+                            name: "draw_indexed",
+                            binding: None,
+                            required: 12,
+                            available: 8
+                        }
+                    ]);
+                },
+                _ => panic!("Range violation is not reported")
+            }
+        } else {
+            assert!(result.is_ok());
+        }
+    }
+
+    #[test]
+    fn compressed_upload() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::BC1_RGBA_UNORM_BLOCK;
+
+        let extent = memory::Extent3D { width: 8, height: 8, depth: 1 };
+
+        // Four 4x4 blocks of 8 bytes
+        assert_eq!(formats::required_staging_size(format, extent), 32);
+
+        let staging_cfg = memory::BufferCfg {
+            size: formats::required_staging_size(format, extent),
+            usage: memory::BufferUsageFlags::TRANSFER_SRC,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let small_cfg = memory::BufferCfg {
+            size: 16,
+            ..staging_cfg
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&staging_cfg, &small_cfg]
+        };
+
+        let staging = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        // Every block is opaque red
+        staging.access(&mut |blocks: &mut [[u8; 8]]| {
+            blocks.fill([0x00, 0xf8, 0x00, 0xf8, 0x00, 0x00, 0x00, 0x00]);
+        }, 0).expect("Failed to fill staging buffer");
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format,
+                extent,
+                usage: memory::ImageUsageFlags::SAMPLED | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        // BC formats are optional so only math is checked on such devices
+        let image = match memory::ImageMemory::allocate(device, &alloc_info) {
+            Ok(image) => image,
+            Err(memory::MemoryError::UnsupportedImage { .. }) => return,
+            Err(err) => panic!("Failed to allocate image memory: {}", err)
+        };
+
+        let cmd_buffer = test_context::get_cmd_pool().allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::empty(),
+            cmd::AccessType::TRANSFER_WRITE,
+            memory::ImageLayout::UNDEFINED,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            graphics::PipelineStage::TOP_OF_PIPE,
+            graphics::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        assert!(matches!(
+            cmd_buffer.try_copy_buffer_to_image(staging.view(1), image.view(0)),
+            Err(cmd::BufferError::StagingSize { required: 32, size: 16 })
+        ));
+
+        cmd_buffer.copy_buffer_to_image(staging.view(0), image.view(0));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+    }
+
+    #[test]
+    fn copy_between_slices() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 64,
+            usage: memory::FULL_TRANSFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 2
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        // Second element has non-zero offset in memory
+        let view = data.view(1);
+
+        view.access(&mut |values: &mut [u32]| {
+            for (i, value) in values.iter_mut().enumerate() {
+                *value = i as u32;
+            }
+        }).expect("Failed to write memory");
+
+        let src = view.slice(0, 16).expect("Failed to slice view");
+        let dst = view.slice(32, 32).expect("Failed to slice view");
+
+        assert_eq!(src.offset(), view.offset());
+        assert_eq!(dst.offset(), view.offset() + 32);
+        assert_eq!(dst.size(), 32);
+
+        // Slices compose
+        let nested = dst.slice(8, 8).expect("Failed to slice view");
+
+        assert_eq!(nested.offset(), view.offset() + 40);
+        assert!(nested == view.slice(40, 8).expect("Failed to slice view"));
+        assert!(nested != dst);
+
+        assert!(matches!(
+            view.slice(60, 8),
+            Err(memory::MemoryError::InvalidOffset { offset: 68, size: 64 })
+        ));
+        assert!(matches!(dst.slice(0, 0), Err(memory::MemoryError::InvalidOffset { .. })));
+
+        let cmd_queue = queue::Queue::new(device, &queue::QueueCfg { family_index: queue.index(), queue_index: 0 })
+            .expect("Failed to get queue");
+
+        let mut cmd_buffer = test_context::get_cmd_pool().allocate().expect("Failed to allocate command buffer");
+
+        // Only first 16 bytes of dst are written
+        cmd_buffer.copy_memory(&src, &dst);
+
+        cmd_buffer.set_barrier(
+            &dst,
+            cmd::AccessType::TRANSFER_WRITE,
+            cmd::AccessType::HOST_READ,
+            cmd::PipelineStage::TRANSFER,
+            cmd::PipelineStage::HOST,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED
+        );
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+        let values: Vec<u32> = data.read_as(1).expect("Failed to read memory");
+
+        assert_eq!(values, vec![0, 1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 12, 13, 14, 15]);
+
+        nested.access(&mut |values: &mut [u32]| assert_eq!(values, &[2, 3])).expect("Failed to read slice");
+    }
+
+    #[test]
+    fn uniform_slice_alignment() {
+        let hw = test_context::get_graphics_hw();
+
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let alignment = hw.ubo_offset();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 2*alignment,
+            usage: memory::UNIFORM,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let slice = data.view(0).slice(alignment, alignment).expect("Failed to slice view");
+
+        assert_eq!(slice.size(), alignment);
+
+        if alignment > 1 {
+            assert!(matches!(
+                data.view(0).slice(1, 4),
+                Err(memory::MemoryError::MisalignedOffset { offset: 1, alignment: a }) if a == alignment
+            ));
+        }
+    }
+
+    #[test]
+    fn u16_indices_match_u32() {
+        const VERT_SHADER: &str = "
+#version 460
+
+layout(location = 0) out vec2 uv;
+
+void main() {
+    vec2 positions[4] = vec2[](vec2(-0.5, -0.5), vec2(0.5, -0.5), vec2(-0.5, 0.5), vec2(0.5, 0.5));
+    uv = positions[gl_VertexIndex] + 0.5;
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+}
+";
+
+        const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) in vec2 uv;
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(uv, 0.0, 1.0);
+}
+";
+
+        const INDICES: [u32; 6] = [0, 1, 2, 2, 1, 3];
+
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent2D { width: 64, height: 64 };
+
+        let image = memory::ImageMemory::offscreen(device, format, extent).expect("Failed to allocate image");
+
+        let queue_families = [queue.index()];
+
+        let index_cfg = |size: u64| memory::BufferCfg {
+            size,
+            usage: memory::INDEX,
+            queue_families: &queue_families,
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let readback_cfg = memory::BufferCfg {
+            size: (extent.width*extent.height) as u64*formats::block_size(format),
+            usage: memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&index_cfg(12), &index_cfg(24), &index_cfg(6), &readback_cfg]
+        };
+
+        let data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let indices_u16: Vec<u16> = INDICES.iter().map(|&i| i as u16).collect();
+
+        data.write_indices_u16(0, &indices_u16).expect("Failed to write u16 indices");
+        data.write_indices_u32(1, &INDICES).expect("Failed to write u32 indices");
+
+        assert!(matches!(data.write_indices_u32(0, &INDICES), Err(memory::MemoryError::TransferSize)));
+        assert!(matches!(
+            data.index_view(2, memory::IndexBufferType::UINT32),
+            Err(memory::MemoryError::SizeMismatch { size: 6, type_size: 4 })
+        ));
+
+        let u16_view = data.index_view(0, memory::IndexBufferType::UINT16).expect("Failed to create index view");
+        let u32_view = data.index_view(1, memory::IndexBufferType::UINT32).expect("Failed to create index view");
+
+        assert_eq!(u16_view.count(), 6);
+        assert_eq!(u32_view.count(), 6);
+
+        let render_pass = graphics::RenderPass::single_subpass(device, format).expect("Failed to create render pass");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[image.view(0)],
+            extent,
+        };
+
+        let framebuffer = memory::Framebuffer::new(device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let shader_type = shader::ShaderCfg {
+            path: "INDEXED_QUAD",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert_shader = shader::Shader::from_glsl(device, &shader_type, VERT_SHADER, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader module");
+
+        let frag_shader = shader::Shader::from_glsl(device, &shader_type, FRAG_SHADER, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader module");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let pipe_type = graphics::PipelineCfg {
+            extent,
+            render_pass: Some(&render_pass),
+            ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_queue = queue::Queue::new(device, &queue::QueueCfg { family_index: queue.index(), queue_index: 0 })
+            .expect("Failed to get queue");
+
+        let render = |indices: memory::IndexBuffer, readback: usize| {
+            let cmd_buffer = test_context::get_cmd_pool().allocate().expect("Failed to allocate cmd buffer");
+
+            cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+            cmd_buffer.bind_graphics_pipeline(&pipeline);
+            cmd_buffer.bind_indices(indices);
+            cmd_buffer.draw_indexed(indices.count() as u32, 1, 0, 0, 0);
+            cmd_buffer.end_render_pass();
+
+            cmd_buffer.set_image_barrier(
+                image.view(0),
+                cmd::AccessType::COLOR_ATTACHMENT_WRITE,
+                cmd::AccessType::TRANSFER_READ,
+                memory::ImageLayout::PRESENT_SRC_KHR,
+                memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                graphics::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+                graphics::PipelineStage::TRANSFER,
+                cmd::QUEUE_FAMILY_IGNORED,
+                cmd::QUEUE_FAMILY_IGNORED);
+
+            cmd_buffer.copy_image_to_buffer(image.view(0), data.view(readback));
+
+            let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+            let exec_info = queue::ExecInfo {
+                wait_stage: cmd::PipelineStage::TRANSFER,
+                buffers: &[&exec_buffer],
+                timeout: u64::MAX,
+                wait: &[],
+                signal: &[],
+                fence: None,
+            };
+
+            cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+            data.read(readback).expect("Failed to read rendered image")
+        };
+
+        let pixels_u16 = render(u16_view, 3);
+
+        let center = 4*(32*extent.width + 32) as usize;
+
+        assert_eq!(pixels_u16[center + 3], 0xff);
+        assert!(pixels_u16[center] > 0);
+
+        assert!(pixels_u16 == render(u32_view, 3));
+    }
+}
@@ -0,0 +1,293 @@
+mod test_context;
+
+#[cfg(test)]
+mod compute {
+    use libvktypes::{
+        memory,
+        hw,
+        shader,
+        compute,
+        cmd,
+        queue,
+        sync,
+    };
+
+    use super::test_context;
+
+    const BUFFER_ELEMENTS: usize = 32;
+
+    #[test]
+    fn compute_only_context() {
+        let lib = test_context::get_compute_instance();
+
+        let hw_list = hw::Description::poll(lib, None).expect("Failed to list hardware");
+
+        // Surface support is unknown without surface
+        assert!(hw_list.list().flat_map(|x| x.queues()).all(|x| !x.is_surface_supported()));
+
+        assert!(test_context::get_compute_queue().is_compute());
+
+        assert!(test_context::get_compute_device().features().geometry_shader == 0);
+
+        assert_eq!(lib.error_count(), 0);
+    }
+
+    #[test]
+    fn fill_memory() {
+        let device = test_context::get_compute_device();
+
+        let queue = test_context::get_compute_queue();
+
+        let compute_memory = memory::BufferCfg {
+            size: (BUFFER_ELEMENTS*std::mem::size_of::<u32>()) as u64,
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&compute_memory]
+        };
+
+        let data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let shader_type = shader::ShaderCfg {
+            path: "tests/compiled_shaders/fill_memory.spv",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_file(device, &shader_type).expect("Failed to create shader module");
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[data.view(0)],
+            descriptor: None,
+            shader: &shader,
+            specialization: None,
+            push_constant_size: 0,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        cmd_buffer.dispatch(BUFFER_ELEMENTS as u32, 1, 1);
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let cmd_queue = queue::Queue::new(device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+        data.access(&mut |values: &mut [u32]| {
+            for (i, value) in values.iter().enumerate() {
+                assert_eq!(*value, i as u32);
+            }
+        }, 0).expect("Failed to read memory");
+    }
+
+    #[test]
+    fn dispatch_then_copy_with_event() {
+        let device = test_context::get_compute_device();
+
+        let queue = test_context::get_compute_queue();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: (BUFFER_ELEMENTS*std::mem::size_of::<u32>()) as u64,
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 2
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let shader_type = shader::ShaderCfg {
+            path: "tests/compiled_shaders/fill_memory.spv",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_file(device, &shader_type).expect("Failed to create shader module");
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[data.view(0)],
+            descriptor: None,
+            shader: &shader,
+            specialization: None,
+            push_constant_size: 0,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        let event = sync::Event::new(device).expect("Failed to create event");
+
+        assert_eq!(event.status().ok(), Some(false));
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        cmd_buffer.dispatch(BUFFER_ELEMENTS as u32, 1, 1);
+
+        cmd_buffer.set_event(&event, cmd::PipelineStage::COMPUTE_SHADER);
+
+        cmd_buffer.wait_events(
+            &[&event],
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::PipelineStage::TRANSFER,
+            &[cmd::BufferBarrier {
+                view: data.view(0),
+                src_type: cmd::AccessType::SHADER_WRITE,
+                dst_type: cmd::AccessType::TRANSFER_READ,
+                src_queue_family: cmd::QUEUE_FAMILY_IGNORED,
+                dst_queue_family: cmd::QUEUE_FAMILY_IGNORED,
+            }],
+            &[]
+        );
+
+        cmd_buffer.copy_memory(&data.view(0), &data.view(1));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let cmd_queue = queue::Queue::new(device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+        assert_eq!(event.status().ok(), Some(true));
+
+        data.access(&mut |values: &mut [u32]| {
+            for (i, value) in values.iter().enumerate() {
+                assert_eq!(*value, i as u32);
+            }
+        }, 1).expect("Failed to read memory");
+
+        event.reset().expect("Failed to reset event");
+
+        assert_eq!(event.status().ok(), Some(false));
+
+        event.set().expect("Failed to set event");
+
+        assert_eq!(event.status().ok(), Some(true));
+    }
+
+    #[test]
+    fn specialization_out_of_range() {
+        let device = test_context::get_compute_device();
+
+        let queue = test_context::get_compute_queue();
+
+        let compute_memory = memory::BufferCfg {
+            size: 64,
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&compute_memory]
+        };
+
+        let data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let shader_type = shader::ShaderCfg {
+            path: "tests/compiled_shaders/fill_memory.spv",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_file(device, &shader_type).expect("Failed to create shader module");
+
+        let spec_data = 16u32.to_ne_bytes();
+
+        // Second constant starts right after the data
+        let spec_entries = [
+            shader::SpecializationEntry {
+                constant_id: 0,
+                offset: 0,
+                size: std::mem::size_of::<u32>(),
+            },
+            shader::SpecializationEntry {
+                constant_id: 1,
+                offset: std::mem::size_of::<u32>() as u32,
+                size: std::mem::size_of::<u32>(),
+            }
+        ];
+
+        let spec_info = shader::SpecializationInfo {
+            entries: &spec_entries,
+            data: &spec_data,
+        };
+
+        assert!(!spec_info.is_valid());
+
+        assert!(shader::SpecializationInfo { entries: &spec_entries[..1], data: &spec_data }.is_valid());
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[data.view(0)],
+            descriptor: None,
+            shader: &shader,
+            specialization: Some(&spec_info),
+            push_constant_size: 0,
+            cache: None,
+        };
+
+        assert!(matches!(compute::Pipeline::new(device, &pipe_type), Err(compute::PipelineError::Specialization)));
+    }
+}
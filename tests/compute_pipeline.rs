@@ -9,8 +9,55 @@ mod compute_pipeline {
         memory,
         shader,
         compute,
+        cmd,
+        queue,
+        graphics,
+        formats,
     };
 
+    const SPEC_SHADER: &str = "
+        #version 460
+
+        layout(local_size_x_id = 0) in;
+
+        layout(constant_id = 1) const uint VALUE = 0;
+
+        layout(std430, binding = 0) buffer Data {
+            uint data[];
+        };
+
+        void main() {
+            data[gl_GlobalInvocationID.x] = VALUE;
+        }
+    ";
+
+    const PUSH_SHADER: &str = "
+        #version 460
+
+        layout(local_size_x = 1) in;
+
+        layout(push_constant) uniform Constants {
+            uint value;
+            float scale;
+        } constants;
+
+        layout(std430, binding = 0) buffer Data {
+            uint data[];
+        };
+
+        void main() {
+            data[0] = constants.value;
+            data[1] = floatBitsToUint(constants.scale);
+        }
+    ";
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Constants {
+        value: u32,
+        scale: f32,
+    }
+
     #[test]
     fn create_pipeline() {
         let lib_type = libvk::InstanceType {
@@ -34,7 +81,11 @@ mod compute_pipeline {
             lib: &lib,
             hw: hw_dev,
             extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
             allocator: None,
+            track_objects: false,
         };
 
         let device = dev::Device::new(&dev_type).expect("Failed to create device");
@@ -58,16 +109,1190 @@ mod compute_pipeline {
         let shader_type = shader::ShaderCfg {
             path: "tests/compiled_shaders/fill_memory.spv",
             entry: "main",
+            include_dirs: &[],
         };
 
         let shader = shader::Shader::from_file(&device, &shader_type).expect("Failed to create shader module");
 
         let pipe_type = compute::PipelineCfg {
             buffers: &[data.view(0)],
+            descriptor: None,
             shader: &shader,
+            specialization: None,
             push_constant_size: 0,
+            cache: None,
         };
 
         assert!(compute::Pipeline::new(&device, &pipe_type).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn specialization_constants() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        const LOCAL_SIZE: u32 = 16;
+
+        let compute_memory = memory::BufferCfg {
+            size: (LOCAL_SIZE as u64)*(std::mem::size_of::<u32>() as u64),
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&compute_memory]
+        };
+
+        let data = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        let shader_type = shader::ShaderCfg {
+            path: "spec_shader.comp",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_glsl(&device, &shader_type, SPEC_SHADER, shader::Kind::Compute)
+            .expect("Failed to create shader module");
+
+        let spec_data: Vec<u8> = [LOCAL_SIZE, 42u32].iter().flat_map(|x| x.to_ne_bytes()).collect();
+
+        let spec_entries = [
+            shader::SpecializationEntry {
+                constant_id: 0,
+                offset: 0,
+                size: std::mem::size_of::<u32>(),
+            },
+            shader::SpecializationEntry {
+                constant_id: 1,
+                offset: std::mem::size_of::<u32>() as u32,
+                size: std::mem::size_of::<u32>(),
+            }
+        ];
+
+        let spec_info = shader::SpecializationInfo {
+            entries: &spec_entries,
+            data: &spec_data,
+        };
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[data.view(0)],
+            descriptor: None,
+            shader: &shader,
+            specialization: Some(&spec_info),
+            push_constant_size: 0,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        cmd_buffer.dispatch(1, 1, 1);
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        data.access(&mut |values: &mut [u32]| {
+            assert!(values.iter().all(|&x| x == 42));
+        }, 0).expect("Failed to read memory");
+    }
+
+    #[test]
+    fn push_constants_round_trip() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let compute_memory = memory::BufferCfg {
+            size: 2*(std::mem::size_of::<u32>() as u64),
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&compute_memory]
+        };
+
+        let data = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        let shader_type = shader::ShaderCfg {
+            path: "push_shader.comp",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_glsl(&device, &shader_type, PUSH_SHADER, shader::Kind::Compute)
+            .expect("Failed to create shader module");
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[data.view(0)],
+            descriptor: None,
+            shader: &shader,
+            specialization: None,
+            push_constant_size: std::mem::size_of::<Constants>() as u32,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        let constants = Constants {
+            value: 42,
+            scale: 0.5,
+        };
+
+        cmd_buffer.push(&pipeline, graphics::ShaderStage::COMPUTE, 0, &constants)
+            .expect("Failed to push constants");
+
+        cmd_buffer.dispatch(1, 1, 1);
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        data.access(&mut |values: &mut [u32]| {
+            assert_eq!(values[0], 42);
+            assert_eq!(f32::from_bits(values[1]), 0.5);
+        }, 0).expect("Failed to read memory");
+    }
+
+    #[test]
+    fn push_constants_out_of_range() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let compute_memory = memory::BufferCfg {
+            size: 2*(std::mem::size_of::<u32>() as u64),
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&compute_memory]
+        };
+
+        let data = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        let shader_type = shader::ShaderCfg {
+            path: "push_shader.comp",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_glsl(&device, &shader_type, PUSH_SHADER, shader::Kind::Compute)
+            .expect("Failed to create shader module");
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[data.view(0)],
+            descriptor: None,
+            shader: &shader,
+            specialization: None,
+            push_constant_size: std::mem::size_of::<Constants>() as u32,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        assert!(matches!(
+            cmd_buffer.push(&pipeline, graphics::ShaderStage::COMPUTE, 0, &[0u32; 4]),
+            Err(cmd::BufferError::PushConstantRange)
+        ));
+
+        assert!(matches!(
+            cmd_buffer.push(&pipeline, graphics::ShaderStage::COMPUTE, 4, &0u64),
+            Err(cmd::BufferError::PushConstantRange)
+        ));
+
+        assert!(matches!(
+            cmd_buffer.push(&pipeline, graphics::ShaderStage::VERTEX, 0, &0u32),
+            Err(cmd::BufferError::PushConstantRange)
+        ));
+
+        assert!(cmd_buffer.push(&pipeline, graphics::ShaderStage::COMPUTE, 4, &[1.0f32][..]).is_ok());
+    }
+
+    const MULTI_SET_SHADER: &str = "
+        #version 460
+
+        layout(local_size_x = 1) in;
+
+        layout(std430, set = 0, binding = 0) buffer Output {
+            uint outputs[];
+        };
+
+        layout(std430, set = 1, binding = 0) buffer Input {
+            uint inputs[];
+        };
+
+        void main() {
+            outputs[gl_GlobalInvocationID.x] = inputs[gl_GlobalInvocationID.x] * 2;
+        }
+    ";
+
+    #[test]
+    fn multiple_descriptor_sets() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        const COUNT: usize = 4;
+
+        let compute_memory = memory::BufferCfg {
+            size: (COUNT*std::mem::size_of::<u32>()) as u64,
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&compute_memory, &compute_memory]
+        };
+
+        let data = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        data.access(&mut |values: &mut [u32]| {
+            for (i, v) in values.iter_mut().enumerate() {
+                *v = i as u32 + 1;
+            }
+        }, 1).expect("Failed to write memory");
+
+        let storage_binding = graphics::BindingCfg {
+            resource_type: graphics::DescriptorType::STORAGE_BUFFER,
+            stage: graphics::ShaderStage::COMPUTE,
+            count: 1,
+        };
+
+        let descs = graphics::PipelineDescriptor::allocate(&device, &[&[storage_binding], &[storage_binding]])
+            .expect("Failed to allocate resources");
+
+        let output_binding = [graphics::BufferBinding::new(data.view(0))];
+        let input_binding = [graphics::BufferBinding::new(data.view(1))];
+
+        descs.update(&[
+            graphics::UpdateInfo {
+                set: 0,
+                binding: 0,
+                starting_array_element: 0,
+                resources: graphics::ShaderBinding::Buffers(&output_binding),
+            },
+            graphics::UpdateInfo {
+                set: 1,
+                binding: 0,
+                starting_array_element: 0,
+                resources: graphics::ShaderBinding::Buffers(&input_binding),
+            }
+        ]).expect("Failed to update descriptor");
+
+        let shader_type = shader::ShaderCfg {
+            path: "multi_set_shader.comp",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_glsl(&device, &shader_type, MULTI_SET_SHADER, shader::Kind::Compute)
+            .expect("Failed to create shader module");
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[],
+            descriptor: Some(&descs),
+            shader: &shader,
+            specialization: None,
+            push_constant_size: 0,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        cmd_buffer.bind_compute_resources(&pipeline, &descs, &[]);
+
+        cmd_buffer.dispatch(COUNT as u32, 1, 1);
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        data.access(&mut |values: &mut [u32]| {
+            assert_eq!(values, &[2, 4, 6, 8]);
+        }, 0).expect("Failed to read memory");
+    }
+
+    const TEXEL_SHADER: &str = "
+        #version 460
+
+        layout(local_size_x = 1) in;
+
+        layout(std430, set = 0, binding = 0) buffer Output {
+            float sum;
+        };
+
+        layout(set = 0, binding = 1) uniform samplerBuffer texels;
+
+        void main() {
+            float result = 0.0;
+
+            for (int i = 0; i < textureSize(texels); i++) {
+                result += texelFetch(texels, i).r;
+            }
+
+            sum = result;
+        }
+    ";
+
+    #[test]
+    fn texel_buffer_sum() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let output_memory = memory::BufferCfg {
+            size: std::mem::size_of::<f32>() as u64,
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let texel_memory = memory::BufferCfg {
+            size: 4*(std::mem::size_of::<f32>() as u64),
+            usage: memory::UNIFORM_TEXEL_BUFFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&output_memory, &texel_memory]
+        };
+
+        let data = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        data.access(&mut |values: &mut [f32]| {
+            values.copy_from_slice(&[1.0, 2.0, 3.0, 4.5]);
+        }, 1).expect("Failed to write memory");
+
+        let buffer_view = memory::BufferView::new(
+            &device,
+            &memory::BufferViewCfg::new(data.view(1), memory::ImageFormat::R32_SFLOAT)
+        ).expect("Failed to create buffer view");
+
+        let descs = graphics::PipelineDescriptor::allocate(&device, &[&[
+            graphics::BindingCfg {
+                resource_type: graphics::DescriptorType::STORAGE_BUFFER,
+                stage: graphics::ShaderStage::COMPUTE,
+                count: 1,
+            },
+            graphics::BindingCfg {
+                resource_type: graphics::DescriptorType::UNIFORM_TEXEL_BUFFER,
+                stage: graphics::ShaderStage::COMPUTE,
+                count: 1,
+            }
+        ]]).expect("Failed to allocate resources");
+
+        let output_binding = [graphics::BufferBinding::new(data.view(0))];
+        let texel_binding = [graphics::TexelBufferBinding::new(&buffer_view)];
+
+        descs.update(&[
+            graphics::UpdateInfo {
+                set: 0,
+                binding: 0,
+                starting_array_element: 0,
+                resources: graphics::ShaderBinding::Buffers(&output_binding),
+            },
+            graphics::UpdateInfo {
+                set: 0,
+                binding: 1,
+                starting_array_element: 0,
+                resources: graphics::ShaderBinding::TexelBuffers(&texel_binding),
+            }
+        ]).expect("Failed to update descriptor");
+
+        let shader_type = shader::ShaderCfg {
+            path: "texel_shader.comp",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_glsl(&device, &shader_type, TEXEL_SHADER, shader::Kind::Compute)
+            .expect("Failed to create shader module");
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[],
+            descriptor: Some(&descs),
+            shader: &shader,
+            specialization: None,
+            push_constant_size: 0,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        cmd_buffer.bind_compute_resources(&pipeline, &descs, &[]);
+
+        cmd_buffer.dispatch(1, 1, 1);
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        data.access(&mut |values: &mut [f32]| {
+            assert_eq!(values[0], 10.5);
+        }, 0).expect("Failed to read memory");
+    }
+
+    const GRADIENT_SHADER: &str = "
+        #version 460
+
+        layout(local_size_x = 1, local_size_y = 1) in;
+
+        layout(set = 0, binding = 0, r32f) uniform writeonly image2D gradient;
+
+        void main() {
+            ivec2 pos = ivec2(gl_GlobalInvocationID.xy);
+
+            imageStore(gradient, pos, vec4(float(pos.x + 8*pos.y), 0.0, 0.0, 0.0));
+        }
+    ";
+
+    #[test]
+    fn storage_image_gradient() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        const SIZE: u32 = 8;
+
+        let image_cfg = memory::ImageCfg {
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            format: memory::ImageFormat::R32_SFLOAT,
+            extent: memory::Extent3D { width: SIZE, height: SIZE, depth: 1 },
+            usage: memory::ImageUsageFlags::STORAGE | memory::ImageUsageFlags::TRANSFER_SRC,
+            layout: memory::ImageLayout::UNDEFINED,
+            aspect: memory::ImageAspect::COLOR,
+            tiling: memory::Tiling::OPTIMAL,
+            samples: memory::SampleCount::TYPE_1,
+            mip_levels: 1,
+            kind: memory::ImageKind::D2,
+            array_layers: 1,
+            count: 1
+        };
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &[image_cfg]
+        };
+
+        let image = memory::ImageMemory::allocate(&device, &alloc_info).expect("Failed to allocate image");
+
+        let readback_memory = memory::BufferCfg {
+            size: ((SIZE*SIZE) as u64)*(std::mem::size_of::<f32>() as u64),
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&readback_memory]
+        };
+
+        let readback = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        let descs = graphics::PipelineDescriptor::allocate(&device, &[&[
+            graphics::BindingCfg {
+                resource_type: graphics::DescriptorType::STORAGE_IMAGE,
+                stage: graphics::ShaderStage::COMPUTE,
+                count: 1,
+            }
+        ]]).expect("Failed to allocate resources");
+
+        let image_binding = [graphics::ImageBinding::storage(image.view(0))];
+
+        descs.update(&[graphics::UpdateInfo {
+            set: 0,
+            binding: 0,
+            starting_array_element: 0,
+            resources: graphics::ShaderBinding::Images(&image_binding),
+        }]).expect("Failed to update descriptor");
+
+        let shader_type = shader::ShaderCfg {
+            path: "gradient_shader.comp",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_glsl(&device, &shader_type, GRADIENT_SHADER, shader::Kind::Compute)
+            .expect("Failed to create shader module");
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[],
+            descriptor: Some(&descs),
+            shader: &shader,
+            specialization: None,
+            push_constant_size: 0,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::empty(),
+            cmd::AccessType::SHADER_WRITE,
+            memory::ImageLayout::UNDEFINED,
+            memory::ImageLayout::GENERAL,
+            cmd::PipelineStage::TOP_OF_PIPE,
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        cmd_buffer.bind_compute_resources(&pipeline, &descs, &[]);
+
+        cmd_buffer.dispatch(SIZE, SIZE, 1);
+
+        cmd_buffer.set_image_barrier(
+            image.view(0),
+            cmd::AccessType::SHADER_WRITE,
+            cmd::AccessType::TRANSFER_READ,
+            memory::ImageLayout::GENERAL,
+            memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_image_to_buffer(image.view(0), readback.view(0));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute queue");
+
+        readback.access(&mut |values: &mut [f32]| {
+            for (i, &v) in values.iter().enumerate() {
+                assert_eq!(v, i as f32);
+            }
+        }, 0).expect("Failed to read memory");
+    }
+
+    const BLUR_SHADER: &str = "
+        #version 460
+
+        layout(local_size_x = 8, local_size_y = 8) in;
+
+        layout(set = 0, binding = 0, r32f) uniform image2D source;
+        layout(set = 0, binding = 1, r32f) uniform image2D target;
+
+        layout(push_constant) uniform Blur {
+            int radius;
+            int vertical;
+        } blur;
+
+        float load(ivec2 pos) {
+            return blur.vertical == 0 ? imageLoad(source, pos).r : imageLoad(target, pos).r;
+        }
+
+        void main() {
+            ivec2 pos = ivec2(gl_GlobalInvocationID.xy);
+            ivec2 size = imageSize(source);
+
+            if (pos.x >= size.x || pos.y >= size.y) {
+                return;
+            }
+
+            ivec2 dir = blur.vertical == 0 ? ivec2(1, 0) : ivec2(0, 1);
+
+            float sum = 0.0;
+
+            for (int i = -blur.radius; i <= blur.radius; ++i) {
+                sum += load(clamp(pos + i*dir, ivec2(0), size - 1));
+            }
+
+            vec4 value = vec4(sum/float(2*blur.radius + 1), 0.0, 0.0, 0.0);
+
+            if (blur.vertical == 0) {
+                imageStore(target, pos, value);
+            } else {
+                imageStore(source, pos, value);
+            }
+        }
+    ";
+
+    #[test]
+    fn storage_image_blur() {
+        const SIZE: u32 = 8;
+
+        const RADIUS: i32 = 1;
+
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        // Nothing to check without compute capable device
+        let Some((hw_dev, queue, _)) = hw_list.find_first(hw::any, hw::QueueFamilyDescription::is_compute, hw::any) else {
+            return;
+        };
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let format = memory::ImageFormat::R32_SFLOAT;
+
+        let extent = memory::Extent3D { width: SIZE, height: SIZE, depth: 1 };
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format,
+                extent,
+                usage: memory::ImageUsageFlags::STORAGE
+                    | memory::ImageUsageFlags::TRANSFER_SRC
+                    | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 2
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        let images = memory::ImageMemory::allocate(&device, &alloc_info).expect("Failed to allocate images");
+
+        let source = images.view(0);
+        let target = images.view(1);
+
+        let staging_cfg = memory::BufferCfg {
+            size: formats::required_staging_size(format, extent),
+            usage: memory::BufferUsageFlags::TRANSFER_SRC | memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 2
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&staging_cfg]
+        };
+
+        let buffers = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        // Single bright texel in the center
+        buffers.access(&mut |texels: &mut [f32]| {
+            texels.fill(0.0);
+            texels[(SIZE*SIZE/2 + SIZE/2) as usize] = 9.0;
+        }, 0).expect("Failed to fill staging buffer");
+
+        let descs = graphics::PipelineDescriptor::allocate(&device, &[&[
+            graphics::BindingCfg {
+                resource_type: graphics::DescriptorType::STORAGE_IMAGE,
+                stage: graphics::ShaderStage::COMPUTE,
+                count: 1,
+            },
+            graphics::BindingCfg {
+                resource_type: graphics::DescriptorType::STORAGE_IMAGE,
+                stage: graphics::ShaderStage::COMPUTE,
+                count: 1,
+            }
+        ]]).expect("Failed to allocate resources");
+
+        descs.update(&[
+            graphics::UpdateInfo {
+                set: 0,
+                binding: 0,
+                starting_array_element: 0,
+                resources: graphics::ShaderBinding::Images(&[graphics::ImageBinding::storage(source)]),
+            },
+            graphics::UpdateInfo {
+                set: 0,
+                binding: 1,
+                starting_array_element: 0,
+                resources: graphics::ShaderBinding::Images(&[graphics::ImageBinding::storage(target)]),
+            }
+        ]).expect("Failed to update descriptor");
+
+        let shader_type = shader::ShaderCfg {
+            path: "blur_shader.comp",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_glsl(&device, &shader_type, BLUR_SHADER, shader::Kind::Compute)
+            .expect("Failed to create compute shader module");
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[],
+            descriptor: Some(&descs),
+            shader: &shader,
+            specialization: None,
+            push_constant_size: 2*std::mem::size_of::<i32>() as u32,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.set_image_barrier(
+            source,
+            cmd::AccessType::empty(),
+            cmd::AccessType::TRANSFER_WRITE,
+            memory::ImageLayout::UNDEFINED,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            cmd::PipelineStage::TOP_OF_PIPE,
+            cmd::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_buffer_to_image(buffers.view(0), source);
+
+        cmd_buffer.set_image_barrier(
+            source,
+            cmd::AccessType::TRANSFER_WRITE,
+            cmd::AccessType::SHADER_READ,
+            memory::ImageLayout::TRANSFER_DST_OPTIMAL,
+            memory::ImageLayout::GENERAL,
+            cmd::PipelineStage::TRANSFER,
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.set_image_barrier(
+            target,
+            cmd::AccessType::empty(),
+            cmd::AccessType::SHADER_WRITE,
+            memory::ImageLayout::UNDEFINED,
+            memory::ImageLayout::GENERAL,
+            cmd::PipelineStage::TOP_OF_PIPE,
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        cmd_buffer.bind_compute_resources(&pipeline, &descs, &[]);
+
+        // Horizontal pass
+        cmd_buffer.push(&pipeline, graphics::ShaderStage::COMPUTE, 0, &[RADIUS, 0]).expect("Failed to push constants");
+
+        cmd_buffer.dispatch_for([SIZE, SIZE, 1], [8, 8, 1]).expect("Failed to dispatch");
+
+        // Vertical pass reads result of the horizontal one and overwrites source
+        cmd_buffer.set_image_barrier(
+            target,
+            cmd::AccessType::SHADER_WRITE,
+            cmd::AccessType::SHADER_READ,
+            memory::ImageLayout::GENERAL,
+            memory::ImageLayout::GENERAL,
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.set_image_barrier(
+            source,
+            cmd::AccessType::SHADER_READ,
+            cmd::AccessType::SHADER_WRITE,
+            memory::ImageLayout::GENERAL,
+            memory::ImageLayout::GENERAL,
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.push(&pipeline, graphics::ShaderStage::COMPUTE, 0, &[RADIUS, 1]).expect("Failed to push constants");
+
+        cmd_buffer.dispatch_for([SIZE, SIZE, 1], [8, 8, 1]).expect("Failed to dispatch");
+
+        cmd_buffer.set_image_barrier(
+            source,
+            cmd::AccessType::SHADER_WRITE,
+            cmd::AccessType::TRANSFER_READ,
+            memory::ImageLayout::GENERAL,
+            memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::PipelineStage::TRANSFER,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED);
+
+        cmd_buffer.copy_image_to_buffer(source, buffers.view(1));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_cfg = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0
+        };
+
+        let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            buffers: &[&exec_buffer],
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+        // Center texel is spread over 3x3 square and the rest stays black
+        buffers.access(&mut |texels: &mut [f32]| {
+            for (i, &texel) in texels.iter().enumerate() {
+                let (x, y) = (i as u32 % SIZE, i as u32 / SIZE);
+
+                let expected = if (3..=5).contains(&x) && (3..=5).contains(&y) { 1.0 } else { 0.0 };
+
+                assert_eq!(texel, expected, "Unexpected value at ({}, {})", x, y);
+            }
+        }, 1).expect("Failed to read memory");
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod dev {
-    use libvktypes::{dev, extensions, hw, layers, libvk};
+    use libvktypes::{dev, extensions, graphics, hw, layers, libvk, memory, queue, shader, cmd, sync};
+
+    use ash::vk;
 
     #[test]
     fn device_creation() {
@@ -25,7 +27,11 @@ mod dev {
             lib: &lib,
             hw: hw_dev,
             extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
             allocator: None,
+            track_objects: false,
         };
 
         assert!(dev::Device::new(&dev_type).is_ok());
@@ -54,9 +60,519 @@ mod dev {
             lib: &lib,
             hw: hw_dev,
             extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
             allocator: None,
+            track_objects: false,
         };
 
         assert!(dev::Device::new(&dev_type).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn anisotropic_sampler() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let features = hw::Features {
+            sampler_anisotropy: 1,
+            ..hw::Features::default()
+        };
+
+        let (hw_dev, _, _) = hw_list
+            .find_first(
+                |hw| hw.is_dedicated_gpu() && hw.supports_features(&features),
+                hw::QueueFamilyDescription::is_graphics,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        assert!(hw_dev.supports_features(&hw::Features::default()));
+        assert!(hw_dev.supports_features(hw_dev.features()));
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features,
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        assert_eq!(device.features().sampler_anisotropy, 1);
+        assert_eq!(device.features().geometry_shader, 0);
+
+        let sampler_cfg = graphics::SamplerCfg {
+            anisotropy_enable: true,
+            max_anisotropy: 4.0,
+            ..graphics::SamplerCfg::default()
+        };
+
+        assert!(graphics::Sampler::new(&device, &sampler_cfg).is_ok());
+        assert_eq!(lib.error_count(), 0);
+    }
+
+    #[test]
+    fn device_lost() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, _, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        // Separate device so the shared test device is never marked as lost
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        assert!(!device.is_lost());
+        assert!(device.wait_idle().is_ok());
+
+        assert!(matches!(
+            memory::memory_error(device.core(), vk::Result::ERROR_OUT_OF_DEVICE_MEMORY, memory::MemoryError::DeviceMemory),
+            memory::MemoryError::DeviceMemory(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY)
+        ));
+        assert!(matches!(
+            queue::queue_error(device.core(), vk::Result::TIMEOUT, |_| queue::QueueError::Timeout),
+            queue::QueueError::Timeout
+        ));
+        assert!(!device.is_lost());
+
+        assert!(matches!(
+            queue::queue_error(device.core(), vk::Result::ERROR_DEVICE_LOST, queue::QueueError::Execution),
+            queue::QueueError::DeviceLost
+        ));
+        assert!(device.is_lost());
+
+        assert!(matches!(
+            memory::memory_error(device.core(), vk::Result::ERROR_DEVICE_LOST, memory::MemoryError::DeviceMemory),
+            memory::MemoryError::DeviceLost
+        ));
+        assert!(matches!(
+            dev::device_error(device.core(), vk::Result::ERROR_DEVICE_LOST, dev::DeviceError::WaitIdle),
+            dev::DeviceError::DeviceLost
+        ));
+        assert!(device.is_lost());
+    }
+
+    const VERT_SHADER: &str = "
+#version 460
+
+vec2 positions[3] = vec2[](
+    vec2( 0.5,  0.5),
+    vec2( 0.0, -0.5),
+    vec2(-0.5,  0.5)
+);
+
+void main() {
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+}
+";
+
+    const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(0.0, 1.0, 0.0, 1.0);
+}
+";
+
+    #[test]
+    fn object_tracking() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, qf, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_graphics,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: true,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        assert!(device.live_objects().is_empty());
+
+        {
+            let buffer_cfg = memory::BufferCfg {
+                size: 64,
+                usage: memory::BufferUsageFlags::STORAGE_BUFFER,
+                queue_families: &[qf.index()],
+                simultaneous_access: false,
+                count: 2
+            };
+
+            let mem_cfg = memory::MemoryCfg {
+                properties: hw::MemoryProperty::HOST_VISIBLE,
+                filter: &hw::any,
+                buffers: &[&buffer_cfg]
+            };
+
+            let _buffers = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+            let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+            let extent = memory::Extent2D { width: 64, height: 64 };
+
+            let image = memory::ImageMemory::offscreen(&device, format, extent).expect("Failed to allocate image");
+
+            let render_pass = graphics::RenderPass::offscreen(&device, format).expect("Failed to create render pass");
+
+            let framebuffer_cfg = memory::FramebufferCfg {
+                render_pass: &render_pass,
+                images: &[image.view(0)],
+                extent,
+            };
+
+            let framebuffer = memory::Framebuffer::new(&device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+            let shader_type = shader::ShaderCfg {
+                path: "TRACKING",
+                entry: "main",
+                include_dirs: &[],
+            };
+
+            let vert_shader = shader::Shader::from_glsl(&device, &shader_type, VERT_SHADER, shader::Kind::Vertex)
+                .expect("Failed to create vertex shader module");
+
+            let frag_shader = shader::Shader::from_glsl(&device, &shader_type, FRAG_SHADER, shader::Kind::Fragment)
+                .expect("Failed to create fragment shader module");
+
+            let descriptor = graphics::PipelineDescriptor::empty(&device);
+
+            let pipe_type = graphics::PipelineCfg {
+                extent,
+                render_pass: Some(&render_pass),
+                ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+            };
+
+            let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+            let cmd_pool = cmd::Pool::new(&device, &cmd::PoolCfg { queue_index: qf.index() })
+                .expect("Failed to allocate command pool");
+
+            let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate cmd buffer");
+
+            cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+            cmd_buffer.bind_graphics_pipeline(&pipeline);
+            cmd_buffer.draw(3, 1, 0, 0);
+            cmd_buffer.end_render_pass();
+
+            let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+            let _semaphore = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+            let fence = sync::Fence::new(&device, false).expect("Failed to create fence");
+
+            let cmd_queue = queue::Queue::new(&device, &queue::QueueCfg { family_index: qf.index(), queue_index: 0 })
+                .expect("Failed to get queue");
+
+            let exec_info = queue::ExecInfo {
+                wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+                buffers: &[&exec_buffer],
+                timeout: u64::MAX,
+                wait: &[],
+                signal: &[],
+                fence: Some(&fence),
+            };
+
+            cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+            let report = device.live_objects();
+
+            assert_eq!(report.buffers, 2);
+            assert_eq!(report.images, 1);
+            assert_eq!(report.image_views, 1);
+            assert_eq!(report.pipelines, 1);
+            assert_eq!(report.framebuffers, 1);
+            assert_eq!(report.command_pools, 1);
+            assert_eq!(report.semaphores, 1);
+            assert_eq!(report.fences, 1);
+            assert_eq!(report.get(dev::ObjectType::DescriptorPool), 0);
+        }
+
+        assert_eq!(device.live_objects(), dev::ObjectReport::default());
+        assert_eq!(lib.error_count(), 0);
+    }
+
+    #[test]
+    fn drop_device_first() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, qf, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_graphics,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent2D { width: 16, height: 16 };
+
+        let image = memory::ImageMemory::offscreen(&device, format, extent).expect("Failed to allocate image");
+
+        let render_pass = graphics::RenderPass::offscreen(&device, format).expect("Failed to create render pass");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[image.view(0)],
+            extent,
+        };
+
+        let framebuffer = memory::Framebuffer::new(&device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 64,
+            usage: memory::BufferUsageFlags::UNIFORM_TEXEL_BUFFER,
+            queue_families: &[qf.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let buffers = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        let buffer_view = memory::BufferView::new(
+            &device,
+            &memory::BufferViewCfg::new(buffers.view(0), memory::ImageFormat::R32_SFLOAT)
+        ).expect("Failed to create buffer view");
+
+        let sampler = graphics::Sampler::new(&device, &graphics::SamplerCfg::default()).expect("Failed to create sampler");
+
+        let vert_cfg = shader::ShaderCfg {
+            path: "tests/compiled_shaders/single_dot.spv",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let frag_cfg = shader::ShaderCfg {
+            path: "tests/compiled_shaders/single_color.spv",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert_shader = shader::Shader::from_file(&device, &vert_cfg).expect("Failed to create vertex shader module");
+        let frag_shader = shader::Shader::from_file(&device, &frag_cfg).expect("Failed to create fragment shader module");
+
+        let descriptor = graphics::PipelineDescriptor::empty(&device);
+
+        let pipe_type = graphics::PipelineCfg {
+            topology: graphics::Topology::POINT_LIST,
+            extent,
+            render_pass: Some(&render_pass),
+            ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+        };
+
+        let pipeline = graphics::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd::PoolCfg { queue_index: qf.index() }).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.draw(1, 1, 0, 0);
+        cmd_buffer.end_render_pass();
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_cfg = queue::QueueCfg {
+            family_index: qf.index(),
+            queue_index: 0,
+        };
+
+        let cmd_queue = queue::Queue::new(&device, &queue_cfg).expect("Failed to get queue");
+
+        let fence = sync::Fence::new(&device, false).expect("Failed to create fence");
+
+        // Every object shares ownership of the logical device
+        drop(device);
+
+        // Objects are dropped before resources they refer to
+        drop(render_pass);
+        drop(pipeline);
+        drop(vert_shader);
+        drop(frag_shader);
+        drop(descriptor);
+        drop(framebuffer);
+        drop(image);
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: Some(&fence),
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+        fence.wait(u64::MAX).expect("Failed to wait fence");
+
+        buffers.access(&mut |data: &mut [f32]| data.fill(1.0), 0).expect("Failed to write memory");
+
+        assert_eq!(buffers.read_as::<f32>(0).expect("Failed to read memory"), vec![1.0; 16]);
+
+        drop(buffers);
+        drop(fence);
+        drop(exec_buffer);
+        drop(cmd_pool);
+        drop(cmd_queue);
+        drop(sampler);
+        drop(buffer_view);
+
+        assert_eq!(lib.error_count(), 0);
+    }
+
+    #[test]
+    fn dynamic_rendering_requires_vulkan_1_3() {
+        // Default instance version is 1.0
+        let lib_type = libvk::InstanceType::default();
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, _, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        assert!(!hw_dev.supports_dynamic_rendering(&lib));
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: true,
+            allocator: None,
+            track_objects: false,
+        };
+
+        assert!(matches!(dev::Device::new(&dev_type), Err(dev::DeviceError::DynamicRenderingUnsupported)));
+    }
+
+    #[test]
+    fn invalid_queue_requests() {
+        let lib_type = libvk::InstanceType::default();
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let family = queue.index();
+
+        let out_of_range = [dev::QueueRequest { family_index: family, priorities: &[0.5, 1.5] }];
+        let nan = [dev::QueueRequest { family_index: family, priorities: &[f32::NAN] }];
+
+        let mut dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[dev::QueueRequest::single(family), dev::QueueRequest::single(family)],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        assert!(matches!(dev::Device::new(&dev_type), Err(dev::DeviceError::DuplicateQueueFamily(i)) if i == family));
+
+        dev_type.queues = &out_of_range;
+
+        assert!(matches!(dev::Device::new(&dev_type), Err(dev::DeviceError::QueuePriority(p)) if p == 1.5));
+
+        dev_type.queues = &nan;
+
+        assert!(matches!(dev::Device::new(&dev_type), Err(dev::DeviceError::QueuePriority(_))));
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod formats {
+    use libvktypes::{formats, memory};
+
+    #[test]
+    fn srgb_mapping() {
+        let pairs = [
+            (memory::ImageFormat::B8G8R8A8_UNORM, memory::ImageFormat::B8G8R8A8_SRGB),
+            (memory::ImageFormat::R8G8B8A8_UNORM, memory::ImageFormat::R8G8B8A8_SRGB),
+        ];
+
+        for (unorm, srgb) in pairs {
+            assert!(formats::is_srgb(srgb));
+            assert!(!formats::is_srgb(unorm));
+
+            assert_eq!(formats::to_srgb(unorm), Some(srgb));
+            assert_eq!(formats::to_srgb(srgb), Some(srgb));
+
+            assert_eq!(formats::to_unorm(srgb), Some(unorm));
+            assert_eq!(formats::to_unorm(unorm), Some(unorm));
+        }
+
+        assert!(!formats::is_srgb(memory::ImageFormat::R32G32B32A32_SFLOAT));
+        assert_eq!(formats::to_srgb(memory::ImageFormat::R32G32B32A32_SFLOAT), None);
+        assert_eq!(formats::to_unorm(memory::ImageFormat::D32_SFLOAT), None);
+    }
+
+    #[test]
+    fn format_info() {
+        let sizes = [
+            (memory::ImageFormat::R8_UNORM, 1),
+            (memory::ImageFormat::R8G8_UNORM, 2),
+            (memory::ImageFormat::R16_SFLOAT, 2),
+            (memory::ImageFormat::R8G8B8A8_SRGB, 4),
+            (memory::ImageFormat::R32G32B32A32_SFLOAT, 16),
+        ];
+
+        for (format, size) in sizes {
+            let info = formats::info(format);
+
+            assert_eq!(info.block_size_bytes, size);
+            assert_eq!(info.block_extent, memory::Extent3D { width: 1, height: 1, depth: 1 });
+            assert_eq!(info.aspect, memory::ImageAspect::COLOR);
+            assert!(!info.is_compressed);
+        }
+
+        let bc1 = formats::info(memory::ImageFormat::BC1_RGB_UNORM_BLOCK);
+
+        assert_eq!(bc1.block_size_bytes, 8);
+        assert_eq!(bc1.block_extent, memory::Extent3D { width: 4, height: 4, depth: 1 });
+        assert!(bc1.is_compressed);
+
+        assert_eq!(formats::info(memory::ImageFormat::BC7_SRGB_BLOCK).block_size_bytes, 16);
+        assert_eq!(
+            formats::info(memory::ImageFormat::ASTC_10X8_UNORM_BLOCK).block_extent,
+            memory::Extent3D { width: 10, height: 8, depth: 1 }
+        );
+
+        assert_eq!(formats::aspect(memory::ImageFormat::D32_SFLOAT), memory::ImageAspect::DEPTH);
+        assert_eq!(
+            formats::aspect(memory::ImageFormat::D24_UNORM_S8_UINT),
+            memory::ImageAspect::DEPTH | memory::ImageAspect::STENCIL
+        );
+
+        let extent = |width, height| memory::Extent3D { width, height, depth: 1 };
+
+        assert_eq!(formats::required_staging_size(memory::ImageFormat::R16_SFLOAT, extent(3, 5)), 30);
+        assert_eq!(formats::required_staging_size(memory::ImageFormat::BC1_RGBA_UNORM_BLOCK, extent(8, 8)), 32);
+        // Partial blocks are rounded up
+        assert_eq!(formats::required_staging_size(memory::ImageFormat::BC1_RGBA_UNORM_BLOCK, extent(5, 2)), 16);
+        assert_eq!(formats::required_staging_size(memory::ImageFormat::BC3_UNORM_BLOCK, extent(1, 1)), 16);
+    }
+}
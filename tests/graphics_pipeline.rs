@@ -2,7 +2,7 @@ mod test_context;
 
 #[cfg(test)]
 mod graphics_pipeline {
-    use libvktypes::{graphics, memory, hw};
+    use libvktypes::{graphics, memory, hw, shader, cmd, queue, dev};
 
     use super::test_context;
 
@@ -12,21 +12,19 @@ mod graphics_pipeline {
 
         let capabilities = test_context::get_surface_capabilities();
 
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
         let pipe_type = graphics::PipelineCfg {
-            vertex_shader: test_context::get_vert_shader(),
             vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
-            vert_input: &[],
-            frag_shader: test_context::get_frag_shader(),
-            geom_shader: None,
             topology: graphics::Topology::TRIANGLE_STRIP,
             extent: capabilities.extent2d(),
-            push_constant_size: 0,
-            render_pass: test_context::get_render_pass(),
-            subpass_index: 0,
-            enable_depth_test: false,
-            enable_primitive_restart: false,
+            render_pass: Some(test_context::get_render_pass()),
             cull_mode: graphics::CullMode::BACK,
-            descriptor: &graphics::PipelineDescriptor::empty(dev)
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
         };
 
         assert!(graphics::Pipeline::new(dev, &pipe_type).is_ok());
@@ -47,20 +45,16 @@ mod graphics_pipeline {
         ]]).expect("Failed to allocate resources");
 
         let pipe_type = graphics::PipelineCfg {
-            vertex_shader: test_context::get_vert_shader(),
             vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
-            vert_input: &[],
-            frag_shader: test_context::get_frag_shader(),
-            geom_shader: None,
             topology: graphics::Topology::TRIANGLE_STRIP,
             extent: capabilities.extent2d(),
-            push_constant_size: 0,
-            render_pass: test_context::get_render_pass(),
-            subpass_index: 0,
-            enable_depth_test: false,
-            enable_primitive_restart: false,
+            render_pass: Some(test_context::get_render_pass()),
             cull_mode: graphics::CullMode::BACK,
-            descriptor: &descs
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descs
+            )
         };
 
         assert!(graphics::Pipeline::new(device, &pipe_type).is_ok());
@@ -101,7 +95,95 @@ mod graphics_pipeline {
             binding: 0,
             starting_array_element: 0,
             resources: graphics::ShaderBinding::Buffers(&[graphics::BufferBinding::new(uniform_data.view(0))]),
-        }])
+        }]).expect("Failed to update descriptor");
+    }
+
+    #[test]
+    fn animate_with_push_constants() {
+        const VERT_SHADER: &str = "
+#version 460
+
+layout(push_constant) uniform Offset {
+    vec4 offset;
+} push;
+
+void main() {
+    gl_Position = vec4(0.0, 0.0, 0.0, 1.0) + push.offset;
+    gl_PointSize = 1.0;
+}
+";
+
+        let device = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let queue = test_context::get_graphics_queue();
+
+        let shader_type = shader::ShaderCfg {
+            path: "VERT_DATA",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert_shader = shader::Shader::from_glsl(device, &shader_type, VERT_SHADER, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader module");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            topology: graphics::Topology::POINT_LIST,
+            extent: capabilities.extent2d(),
+            push_constants: &[
+                graphics::PushConstantCfg {
+                    stage: graphics::ShaderStage::VERTEX,
+                    offset: 0,
+                    size: std::mem::size_of::<[f32; 4]>() as u32,
+                }
+            ],
+            render_pass: Some(test_context::get_render_pass()),
+            ..graphics::PipelineCfg::new(&vert_shader, test_context::get_frag_shader(), &descriptor)
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        let queue_cfg = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0
+        };
+
+        let cmd_queue = queue::Queue::new(device, &queue_cfg).expect("Failed to get queue");
+
+        let pool = test_context::get_cmd_pool();
+
+        let framebuffers = test_context::get_framebuffers();
+
+        for frame in 0..4 {
+            let offset: [f32; 4] = [0.1*(frame as f32), 0.0, 0.0, 0.0];
+
+            let data: Vec<u8> = offset.iter().flat_map(|x| x.to_ne_bytes()).collect();
+
+            let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+            cmd_buffer.begin_render_pass(test_context::get_render_pass(), &framebuffers[0]);
+            cmd_buffer.bind_graphics_pipeline(&pipeline);
+            cmd_buffer.update_graphics_push_constants(&pipeline, graphics::ShaderStage::VERTEX, 0, &data);
+            cmd_buffer.draw(1, 1, 0, 0);
+            cmd_buffer.end_render_pass();
+
+            let exec_buffer = cmd_buffer.commit().expect("Failed to commit buffer");
+
+            let exec_info = queue::ExecInfo {
+                wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+                buffers: &[&exec_buffer],
+                timeout: u64::MAX,
+                wait: &[],
+                signal: &[],
+                fence: None,
+            };
+
+            assert!(cmd_queue.exec(&exec_info).is_ok());
+        }
     }
 
     #[test]
@@ -112,4 +194,1273 @@ mod graphics_pipeline {
 
         assert!(graphics::Sampler::new(device, &cfg).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn blend_count_mismatch() {
+        let dev = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
+        let two_blends = [graphics::BlendCfg::alpha(), graphics::BlendCfg::opaque()];
+
+        let one_blend = [graphics::BlendCfg::alpha()];
+
+        let mut pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            topology: graphics::Topology::TRIANGLE_STRIP,
+            extent: capabilities.extent2d(),
+            render_pass: Some(test_context::get_render_pass()),
+            blend: &two_blends,
+            cull_mode: graphics::CullMode::BACK,
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        assert!(matches!(graphics::Pipeline::new(dev, &pipe_type), Err(graphics::PipelineError::BlendCount)));
+
+        pipe_type.blend = &one_blend;
+
+        assert!(graphics::Pipeline::new(dev, &pipe_type).is_ok());
+    }
+
+    #[test]
+    fn depth_stencil_translation() {
+        use ash::vk;
+
+        let simple: vk::PipelineDepthStencilStateCreateInfo = (&graphics::DepthStencilCfg::simple()).into();
+
+        assert_eq!(simple.depth_test_enable, vk::TRUE);
+        assert_eq!(simple.depth_write_enable, vk::TRUE);
+        assert_eq!(simple.depth_compare_op, vk::CompareOp::LESS_OR_EQUAL);
+        assert_eq!(simple.depth_bounds_test_enable, vk::FALSE);
+        assert_eq!(simple.stencil_test_enable, vk::FALSE);
+
+        let outline = graphics::DepthStencilCfg {
+            compare_op: graphics::CompareOp::GREATER,
+            depth_write: false,
+            depth_bounds: Some((0.25, 0.75)),
+            stencil_test: true,
+            front: graphics::StencilOpState {
+                pass_op: graphics::StencilOp::REPLACE,
+                reference: 1,
+                ..Default::default()
+            },
+            back: graphics::StencilOpState {
+                compare_op: graphics::CompareOp::NOT_EQUAL,
+                reference: 1,
+                ..Default::default()
+            },
+        };
+
+        let raw: vk::PipelineDepthStencilStateCreateInfo = (&outline).into();
+
+        assert_eq!(raw.depth_write_enable, vk::FALSE);
+        assert_eq!(raw.depth_compare_op, vk::CompareOp::GREATER);
+        assert_eq!(raw.depth_bounds_test_enable, vk::TRUE);
+        assert_eq!((raw.min_depth_bounds, raw.max_depth_bounds), (0.25, 0.75));
+        assert_eq!(raw.stencil_test_enable, vk::TRUE);
+        assert_eq!(raw.front.pass_op, vk::StencilOp::REPLACE);
+        assert_eq!(raw.front.reference, 1);
+        assert_eq!(raw.back.compare_op, vk::CompareOp::NOT_EQUAL);
+        assert_eq!(raw.back.fail_op, vk::StencilOp::KEEP);
+    }
+
+    #[test]
+    fn write_input_attachment() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let image_cfg = memory::ImageCfg {
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            format: memory::ImageFormat::R8G8B8A8_UNORM,
+            extent: capabilities.extent3d(1),
+            usage: memory::ImageUsageFlags::COLOR_ATTACHMENT | memory::ImageUsageFlags::INPUT_ATTACHMENT,
+            layout: memory::ImageLayout::UNDEFINED,
+            aspect: memory::ImageAspect::COLOR,
+            tiling: memory::Tiling::OPTIMAL,
+            samples: memory::SampleCount::TYPE_1,
+            mip_levels: 1,
+            kind: memory::ImageKind::D2,
+            array_layers: 1,
+            count: 1
+        };
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &[image_cfg]
+        };
+
+        let image = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image");
+
+        let descs = graphics::PipelineDescriptor::allocate(&device, &[&[
+            graphics::BindingCfg {
+                resource_type: graphics::DescriptorType::INPUT_ATTACHMENT,
+                stage: graphics::ShaderStage::FRAGMENT,
+                count: 1,
+            }
+        ]]).expect("Failed to allocate resources");
+
+        assert!(!descs.is_empty());
+
+        descs.update(&[graphics::UpdateInfo {
+            set: 0,
+            binding: 0,
+            starting_array_element: 0,
+            resources: graphics::ShaderBinding::InputAttachments(&[
+                graphics::InputAttachmentBinding::new(image.view(0))
+            ]),
+        }]).expect("Failed to update descriptor");
+    }
+
+    #[test]
+    fn tessellation_requires_both_stages() {
+        let dev = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
+        let pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            tess_control_shader: Some(test_context::get_vert_shader()),
+            patch_control_points: 3,
+            topology: graphics::Topology::PATCH_LIST,
+            extent: capabilities.extent2d(),
+            render_pass: Some(test_context::get_render_pass()),
+            cull_mode: graphics::CullMode::BACK,
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        assert!(matches!(graphics::Pipeline::new(dev, &pipe_type), Err(graphics::PipelineError::TessellationStages)));
+    }
+
+    #[test]
+    fn pipeline_cache_round_trip() {
+        let device = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let cache = dev::PipelineCache::new(device).expect("Failed to create pipeline cache");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let mut pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            topology: graphics::Topology::TRIANGLE_STRIP,
+            extent: capabilities.extent2d(),
+            render_pass: Some(test_context::get_render_pass()),
+            cull_mode: graphics::CullMode::BACK,
+            cache: Some(&cache),
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        assert!(graphics::Pipeline::new(device, &pipe_type).is_ok());
+
+        pipe_type.topology = graphics::Topology::TRIANGLE_LIST;
+
+        assert!(graphics::Pipeline::new(device, &pipe_type).is_ok());
+
+        let data = cache.data().expect("Failed to get cache data");
+
+        let restored = dev::PipelineCache::from_bytes(device, &data).expect("Failed to restore pipeline cache");
+
+        pipe_type.cache = Some(&restored);
+
+        assert!(graphics::Pipeline::new(device, &pipe_type).is_ok());
+
+        assert!(restored.data().is_ok());
+    }
+
+    #[test]
+    fn batch_creation() {
+        let dev = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
+        let base = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            topology: graphics::Topology::TRIANGLE_STRIP,
+            extent: capabilities.extent2d(),
+            render_pass: Some(test_context::get_render_pass()),
+            cull_mode: graphics::CullMode::BACK,
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        let cfgs = [
+            graphics::PipelineCfg { ..base },
+            graphics::PipelineCfg { cull_mode: graphics::CullMode::NONE, derive_from: Some(0), ..base },
+            graphics::PipelineCfg { cull_mode: graphics::CullMode::FRONT, derive_from: Some(0), ..base },
+        ];
+
+        let pipelines = graphics::Pipeline::new_batch(dev, &cfgs).expect("Failed to create pipelines");
+
+        assert_eq!(pipelines.len(), cfgs.len());
+
+        let invalid = [
+            graphics::PipelineCfg { ..base },
+            graphics::PipelineCfg { derive_from: Some(1), ..base },
+        ];
+
+        match graphics::Pipeline::new_batch(dev, &invalid) {
+            Err(graphics::PipelineError::Batch(1, err)) => {
+                assert!(matches!(*err, graphics::PipelineError::DeriveIndex))
+            },
+            _ => panic!("Expected error for the second pipeline"),
+        }
+    }
+
+    #[test]
+    fn dynamic_uniform_offsets() {
+        const VERT_SHADER: &str = "
+#version 460
+
+layout(set = 0, binding = 0) uniform Offset {
+    vec4 offset;
+} ubo;
+
+void main() {
+    gl_Position = vec4(0.0, 0.0, 0.0, 1.0) + ubo.offset;
+    gl_PointSize = 1.0;
+}
+";
+
+        let device = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let queue = test_context::get_graphics_queue();
+
+        let element_size = device.hw().ubo_size(std::mem::size_of::<[f32; 4]>() as u64);
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[
+                &memory::BufferCfg {
+                    size: 2*element_size,
+                    usage: memory::UNIFORM,
+                    queue_families: &[queue.index()],
+                    simultaneous_access: false,
+                    count: 1
+                }
+            ]
+        };
+
+        let uniform_data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let descs = graphics::PipelineDescriptor::allocate(device, &[&[
+            graphics::BindingCfg {
+                resource_type: graphics::DescriptorType::UNIFORM_BUFFER_DYNAMIC,
+                stage: graphics::ShaderStage::VERTEX,
+                count: 1,
+            }
+        ]]).expect("Failed to allocate resources");
+
+        assert_eq!(descs.dynamic_descriptor_count(), 1);
+
+        let binding = [
+            graphics::BufferBinding::with_params(
+                uniform_data.view(0),
+                0,
+                std::mem::size_of::<[f32; 4]>() as u64
+            )
+        ];
+
+        descs.update(&[graphics::UpdateInfo {
+            set: 0,
+            binding: 0,
+            starting_array_element: 0,
+            resources: graphics::ShaderBinding::Buffers(&binding),
+        }]).expect("Failed to update descriptor");
+
+        let offsets = graphics::DynamicOffsets::new(device, &descs);
+
+        assert_eq!(offsets.count(), 1);
+        assert!(matches!(offsets.validate(&[]), Err(graphics::DynamicOffsetsError::Count)));
+        assert!(matches!(offsets.validate(&[0, 0]), Err(graphics::DynamicOffsetsError::Count)));
+
+        if device.hw().ubo_offset() > 1 {
+            assert!(matches!(offsets.validate(&[1]), Err(graphics::DynamicOffsetsError::Alignment(0))));
+        }
+
+        let shader_type = shader::ShaderCfg {
+            path: "VERT_DATA",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert_shader = shader::Shader::from_glsl(device, &shader_type, VERT_SHADER, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader module");
+
+        let pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            topology: graphics::Topology::POINT_LIST,
+            extent: capabilities.extent2d(),
+            render_pass: Some(test_context::get_render_pass()),
+            ..graphics::PipelineCfg::new(&vert_shader, test_context::get_frag_shader(), &descs)
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        uniform_data.access(&mut |values: &mut [f32]| {
+            let stride = element_size as usize/std::mem::size_of::<f32>();
+
+            values[0] = -0.5;
+            values[stride] = 0.5;
+        }, 0).expect("Failed to write memory");
+
+        let queue_cfg = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0
+        };
+
+        let cmd_queue = queue::Queue::new(device, &queue_cfg).expect("Failed to get queue");
+
+        let pool = test_context::get_cmd_pool();
+
+        let framebuffers = test_context::get_framebuffers();
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.begin_render_pass(test_context::get_render_pass(), &framebuffers[0]);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+
+        for i in 0..2 {
+            let dynamic_offsets = [(i*element_size) as u32];
+
+            offsets.validate(&dynamic_offsets).expect("Invalid dynamic offsets");
+
+            cmd_buffer.bind_resources(&pipeline, &descs, &dynamic_offsets);
+            cmd_buffer.draw(1, 1, 0, 0);
+        }
+
+        cmd_buffer.end_render_pass();
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit buffer");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        assert!(cmd_queue.exec(&exec_info).is_ok());
+    }
+
+    #[test]
+    fn update_validation() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[
+                &memory::BufferCfg {
+                    size: 16,
+                    usage: memory::UNIFORM,
+                    queue_families: &[queue.index()],
+                    simultaneous_access: false,
+                    count: 1
+                }
+            ]
+        };
+
+        let uniform_data = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let image_cfg = memory::ImageCfg {
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            format: memory::ImageFormat::R8G8B8A8_UNORM,
+            extent: memory::Extent3D { width: 4, height: 4, depth: 1 },
+            usage: memory::ImageUsageFlags::SAMPLED,
+            layout: memory::ImageLayout::UNDEFINED,
+            aspect: memory::ImageAspect::COLOR,
+            tiling: memory::Tiling::OPTIMAL,
+            samples: memory::SampleCount::TYPE_1,
+            mip_levels: 1,
+            kind: memory::ImageKind::D2,
+            array_layers: 1,
+            count: 1
+        };
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &[image_cfg]
+        };
+
+        let image = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image");
+
+        let sampler = graphics::Sampler::new(device, &graphics::SamplerCfg::default()).expect("Failed to create sampler");
+
+        let descs = graphics::PipelineDescriptor::allocate(device, &[&[
+            graphics::BindingCfg {
+                resource_type: graphics::DescriptorType::UNIFORM_BUFFER,
+                stage: graphics::ShaderStage::VERTEX,
+                count: 1,
+            }
+        ]]).expect("Failed to allocate resources");
+
+        let buffers = [graphics::BufferBinding::new(uniform_data.view(0))];
+
+        let samplers = [(&sampler, image.view(0), memory::ImageLayout::SHADER_READ_ONLY_OPTIMAL)];
+
+        assert!(matches!(
+            descs.update(&[graphics::UpdateInfo {
+                set: 1,
+                binding: 0,
+                starting_array_element: 0,
+                resources: graphics::ShaderBinding::Buffers(&buffers),
+            }]),
+            Err(graphics::PipelineDescriptorError::InvalidBinding { set: 1, binding: 0 })
+        ));
+
+        assert!(matches!(
+            descs.update(&[graphics::UpdateInfo {
+                set: 0,
+                binding: 3,
+                starting_array_element: 0,
+                resources: graphics::ShaderBinding::Buffers(&buffers),
+            }]),
+            Err(graphics::PipelineDescriptorError::InvalidBinding { set: 0, binding: 3 })
+        ));
+
+        assert!(matches!(
+            descs.update(&[graphics::UpdateInfo {
+                set: 0,
+                binding: 0,
+                starting_array_element: 0,
+                resources: graphics::ShaderBinding::Samplers(&samplers),
+            }]),
+            Err(graphics::PipelineDescriptorError::TypeMismatch { set: 0, binding: 0 })
+        ));
+
+        assert!(descs.update(&[graphics::UpdateInfo {
+            set: 0,
+            binding: 0,
+            starting_array_element: 0,
+            resources: graphics::ShaderBinding::Buffers(&buffers),
+        }]).is_ok());
+    }
+
+    #[test]
+    fn shadow_sampler() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let depth_cfg = memory::ImageCfg {
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            format: memory::ImageFormat::D32_SFLOAT,
+            extent: memory::Extent3D { width: 64, height: 64, depth: 1 },
+            usage: memory::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT | memory::ImageUsageFlags::SAMPLED,
+            layout: memory::ImageLayout::UNDEFINED,
+            aspect: memory::ImageAspect::DEPTH,
+            tiling: memory::Tiling::OPTIMAL,
+            samples: memory::SampleCount::TYPE_1,
+            mip_levels: 1,
+            kind: memory::ImageKind::D2,
+            array_layers: 1,
+            count: 1
+        };
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &[depth_cfg]
+        };
+
+        let shadow_map = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate shadow map");
+
+        let sampler_cfg = graphics::SamplerCfg {
+            address_mode_u: graphics::SamplerAddressMode::CLAMP_TO_BORDER,
+            address_mode_v: graphics::SamplerAddressMode::CLAMP_TO_BORDER,
+            address_mode_w: graphics::SamplerAddressMode::CLAMP_TO_BORDER,
+            compare_enable: true,
+            compare_op: graphics::CompareOp::LESS_OR_EQUAL,
+            border_color: graphics::BorderColor::FLOAT_OPAQUE_WHITE,
+            ..graphics::SamplerCfg::default()
+        };
+
+        let sampler = graphics::Sampler::new(device, &sampler_cfg).expect("Failed to create sampler");
+
+        let shader_cfg = shader::ShaderCfg {
+            path: "SHADOW",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let frag_shader = shader::Shader::from_glsl(
+            device,
+            &shader_cfg,
+            "#version 460
+
+            layout(location = 0) in vec3 light_pos;
+            layout(location = 0) out vec4 color;
+
+            layout(set = 0, binding = 0) uniform sampler2DShadow shadow_map;
+
+            void main() {
+                color = vec4(texture(shadow_map, light_pos));
+            }",
+            shader::Kind::Fragment
+        ).expect("Failed to compile shader");
+
+        let descs = graphics::PipelineDescriptor::from_shaders(device, &[&frag_shader])
+            .expect("Failed to allocate resources");
+
+        let samplers = [(&sampler, shadow_map.view(0), memory::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL)];
+
+        assert!(descs.update(&[graphics::UpdateInfo {
+            set: 0,
+            binding: 0,
+            starting_array_element: 0,
+            resources: graphics::ShaderBinding::Samplers(&samplers),
+        }]).is_ok());
+    }
+
+    #[test]
+    fn unnormalized_sampler() {
+        let device = test_context::get_graphics_device();
+
+        let cfg = graphics::SamplerCfg {
+            mipmap_mode: graphics::SamplerMipmapMode::NEAREST,
+            address_mode_u: graphics::SamplerAddressMode::CLAMP_TO_EDGE,
+            address_mode_v: graphics::SamplerAddressMode::CLAMP_TO_EDGE,
+            mag_filter: graphics::SamplerFilter::NEAREST,
+            min_filter: graphics::SamplerFilter::NEAREST,
+            unnormalized_coordinates: true,
+            ..graphics::SamplerCfg::default()
+        };
+
+        assert!(graphics::Sampler::new(device, &cfg).is_ok());
+
+        assert!(matches!(
+            graphics::Sampler::new(device, &graphics::SamplerCfg { min_filter: graphics::SamplerFilter::LINEAR, ..cfg }),
+            Err(graphics::SamplerError::UnnormalizedFilter)
+        ));
+
+        assert!(matches!(
+            graphics::Sampler::new(device, &graphics::SamplerCfg { max_lod: 1.0, ..cfg }),
+            Err(graphics::SamplerError::UnnormalizedMipmap)
+        ));
+
+        assert!(matches!(
+            graphics::Sampler::new(device, &graphics::SamplerCfg { address_mode_v: graphics::SamplerAddressMode::REPEAT, ..cfg }),
+            Err(graphics::SamplerError::UnnormalizedAddressMode)
+        ));
+
+        assert!(matches!(
+            graphics::Sampler::new(device, &graphics::SamplerCfg { compare_enable: true, ..cfg }),
+            Err(graphics::SamplerError::UnnormalizedCompare)
+        ));
+    }
+
+    #[test]
+    fn render_target_validation() {
+        let dev = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
+        let formats = graphics::RenderingFormats {
+            color: &[capabilities.formats().next().expect("No formats").format],
+            depth: None,
+        };
+
+        let mut pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            topology: graphics::Topology::TRIANGLE_STRIP,
+            extent: capabilities.extent2d(),
+            render_pass: Some(test_context::get_render_pass()),
+            rendering_formats: Some(formats),
+            cull_mode: graphics::CullMode::BACK,
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        assert!(matches!(graphics::Pipeline::new(dev, &pipe_type), Err(graphics::PipelineError::RenderTarget)));
+
+        pipe_type.render_pass = None;
+        pipe_type.rendering_formats = None;
+
+        assert!(matches!(graphics::Pipeline::new(dev, &pipe_type), Err(graphics::PipelineError::RenderTarget)));
+
+        pipe_type.rendering_formats = Some(formats);
+
+        assert!(!dev.is_dynamic_rendering_enabled());
+        assert!(matches!(graphics::Pipeline::new(dev, &pipe_type), Err(graphics::PipelineError::DynamicRendering)));
+    }
+
+    #[test]
+    fn shared_vertex_binding() {
+        let dev = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
+        // Position and uv from the same buffer as in the texture example
+        let vert_input = [
+            graphics::VertexInputCfg {
+                location: 0,
+                binding: 0,
+                format: memory::ImageFormat::R32G32B32A32_SFLOAT,
+                offset: 0,
+            },
+            graphics::VertexInputCfg {
+                location: 1,
+                binding: 0,
+                format: memory::ImageFormat::R32G32_SFLOAT,
+                offset: std::mem::size_of::<[f32; 4]>() as u32,
+            }
+        ];
+
+        let mut pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 6]>() as u32,
+            vert_input: &vert_input,
+            extent: capabilities.extent2d(),
+            render_pass: Some(test_context::get_render_pass()),
+            cull_mode: graphics::CullMode::BACK,
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        let bindings = pipe_type.vertex_bindings();
+
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0].binding, 0);
+        assert_eq!(bindings[0].stride, std::mem::size_of::<[f32; 6]>() as u32);
+
+        let split_input = [
+            graphics::VertexInputCfg { binding: 2, ..vert_input[0] },
+            graphics::VertexInputCfg { binding: 0, ..vert_input[1] },
+        ];
+
+        pipe_type.vert_input = &split_input;
+
+        let bindings: Vec<u32> = pipe_type.vertex_bindings().iter().map(|x| x.binding).collect();
+
+        assert_eq!(bindings, vec![0, 2]);
+    }
+
+    #[test]
+    fn polygon_mode_and_line_width() {
+        let dev = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
+        let mut pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            topology: graphics::Topology::TRIANGLE_STRIP,
+            extent: capabilities.extent2d(),
+            render_pass: Some(test_context::get_render_pass()),
+            polygon_mode: graphics::PolygonMode::LINE,
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        let result = graphics::Pipeline::new(dev, &pipe_type);
+
+        if dev.features().fill_mode_non_solid == 0 {
+            assert!(matches!(result, Err(graphics::PipelineError::PolygonMode)));
+        } else {
+            assert!(result.is_ok());
+        }
+
+        pipe_type.polygon_mode = graphics::PolygonMode::FILL;
+        pipe_type.line_width = -1.0;
+
+        let result = graphics::Pipeline::new(dev, &pipe_type);
+
+        if dev.features().wide_lines == 0 {
+            assert!(matches!(result, Err(graphics::PipelineError::WideLines)));
+        } else {
+            assert!(matches!(result, Err(graphics::PipelineError::LineWidth)));
+        }
+    }
+
+    #[test]
+    fn rasterization_translation() {
+        use ash::vk;
+
+        let dev = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
+        let mut pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            topology: graphics::Topology::TRIANGLE_STRIP,
+            extent: capabilities.extent2d(),
+            render_pass: Some(test_context::get_render_pass()),
+            cull_mode: graphics::CullMode::BACK,
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        let raw: vk::PipelineRasterizationStateCreateInfo = (&pipe_type).into();
+
+        assert_eq!(raw.front_face, vk::FrontFace::COUNTER_CLOCKWISE);
+        assert_eq!(raw.depth_bias_enable, vk::FALSE);
+        assert_eq!(raw.depth_bias_constant_factor, 0.0);
+
+        pipe_type.front_face = graphics::FrontFace::CLOCKWISE;
+        pipe_type.depth_bias = Some(graphics::DepthBiasCfg {
+            constant_factor: 1.25,
+            clamp: 0.0,
+            slope_factor: 1.75,
+        });
+
+        let raw: vk::PipelineRasterizationStateCreateInfo = (&pipe_type).into();
+
+        assert_eq!(raw.front_face, vk::FrontFace::CLOCKWISE);
+        assert_eq!(raw.depth_bias_enable, vk::TRUE);
+        assert_eq!(raw.depth_bias_constant_factor, 1.25);
+        assert_eq!(raw.depth_bias_clamp, 0.0);
+        assert_eq!(raw.depth_bias_slope_factor, 1.75);
+
+        assert!(graphics::Pipeline::new(dev, &pipe_type).is_ok());
+
+        pipe_type.depth_bias = Some(graphics::DepthBiasCfg { clamp: 0.5, ..Default::default() });
+
+        let result = graphics::Pipeline::new(dev, &pipe_type);
+
+        if dev.features().depth_bias_clamp == vk::FALSE {
+            assert!(matches!(result, Err(graphics::PipelineError::DepthBiasClamp)));
+        } else {
+            assert!(result.is_ok());
+        }
+    }
+
+    #[test]
+    fn pipeline_error_source() {
+        use ash::vk;
+        use std::error::Error;
+
+        let err = graphics::PipelineError::Batch(1, Box::new(graphics::PipelineError::Pipeline(vk::Result::ERROR_OUT_OF_HOST_MEMORY)));
+
+        let inner = err.source().expect("Batch must have source");
+
+        assert!(matches!(
+            inner.downcast_ref::<graphics::PipelineError>(),
+            Some(graphics::PipelineError::Pipeline(vk::Result::ERROR_OUT_OF_HOST_MEMORY))
+        ));
+
+        assert_eq!(
+            inner.source().and_then(|x| x.downcast_ref::<vk::Result>()),
+            Some(&vk::Result::ERROR_OUT_OF_HOST_MEMORY)
+        );
+
+        let err: libvktypes::Error = graphics::PipelineError::Layout(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY).into();
+
+        assert_eq!(
+            err.source().and_then(|x| x.downcast_ref::<vk::Result>()),
+            Some(&vk::Result::ERROR_OUT_OF_DEVICE_MEMORY)
+        );
+
+        // Validation errors are not caused by Vulkan calls
+        assert!(graphics::PipelineError::RenderTarget.source().is_none());
+    }
+
+    #[test]
+    fn desc_names() {
+        use graphics::NamedValue;
+
+        for (value, name) in graphics::Topology::VALUES {
+            assert_eq!(value.name(), Some(*name));
+            assert_eq!(graphics::Topology::from_name(name), Some(*value));
+        }
+
+        for (value, name) in memory::ImageFormat::VALUES {
+            assert_eq!(memory::ImageFormat::from_name(name), Some(*value));
+        }
+
+        assert_eq!(graphics::CullMode::from_name("BACK"), Some(graphics::CullMode::BACK));
+        assert_eq!(memory::SampleCount::TYPE_4.name(), Some("TYPE_4"));
+
+        assert!(graphics::Topology::from_name("triangle_list").is_none());
+        assert!(matches!(
+            graphics::BlendOp::parse("MULTIPLY"),
+            Err(graphics::DescError::UnknownName { kind: "blend op", .. })
+        ));
+    }
+
+    #[test]
+    fn pipeline_desc() {
+        use ash::vk;
+
+        let dev = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
+        let extent = capabilities.extent2d();
+
+        let desc = graphics::PipelineDesc {
+            vertex_shader: "vert".to_string(),
+            frag_shader: "frag".to_string(),
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            vert_input: vec![graphics::VertexInputDesc::default()],
+            topology: "TRIANGLE_STRIP".to_string(),
+            extent: [extent.width, extent.height],
+            blend: vec![graphics::BlendDesc::default()],
+            depth_bias: Some([1.25, 0.0, 1.75]),
+            cull_mode: "BACK".to_string(),
+            front_face: "CLOCKWISE".to_string(),
+            ..graphics::PipelineDesc::default()
+        };
+
+        let shaders = |name: &str| match name {
+            "vert" => Some(test_context::get_vert_shader()),
+            "frag" => Some(test_context::get_frag_shader()),
+            _ => None
+        };
+
+        let resolver = graphics::DescResolver {
+            shaders: &shaders,
+            render_pass: Some(test_context::get_render_pass()),
+            descriptor: &descriptor,
+            cache: None,
+        };
+
+        let vert_input = [
+            graphics::VertexInputCfg {
+                location: 0,
+                binding: 0,
+                format: memory::ImageFormat::R32G32_SFLOAT,
+                offset: 0,
+            }
+        ];
+
+        let pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            vert_input: &vert_input,
+            topology: graphics::Topology::TRIANGLE_STRIP,
+            extent,
+            render_pass: Some(test_context::get_render_pass()),
+            blend: &[graphics::BlendCfg::opaque()],
+            depth_bias: Some(graphics::DepthBiasCfg {
+                constant_factor: 1.25,
+                clamp: 0.0,
+                slope_factor: 1.75,
+            }),
+            cull_mode: graphics::CullMode::BACK,
+            front_face: graphics::FrontFace::CLOCKWISE,
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        let expected: vk::PipelineRasterizationStateCreateInfo = (&pipe_type).into();
+
+        desc.with_cfg(&resolver, |cfg| {
+            let raw: vk::PipelineRasterizationStateCreateInfo = cfg.into();
+
+            assert_eq!(raw.cull_mode, expected.cull_mode);
+            assert_eq!(raw.front_face, expected.front_face);
+            assert_eq!(raw.polygon_mode, expected.polygon_mode);
+            assert_eq!(raw.depth_bias_enable, expected.depth_bias_enable);
+            assert_eq!(raw.depth_bias_constant_factor, expected.depth_bias_constant_factor);
+            assert_eq!(raw.depth_bias_slope_factor, expected.depth_bias_slope_factor);
+
+            assert_eq!(cfg.topology, pipe_type.topology);
+            assert_eq!(cfg.extent, pipe_type.extent);
+            assert_eq!(cfg.vert_input[0].format, vert_input[0].format);
+            assert_eq!(cfg.blend[0].write_mask, pipe_type.blend[0].write_mask);
+            assert_eq!(cfg.blend[0].enable, pipe_type.blend[0].enable);
+        }).expect("Failed to resolve description");
+
+        assert!(graphics::Pipeline::from_desc(dev, &desc, &resolver).is_ok());
+
+        let unknown_shader = graphics::PipelineDesc { frag_shader: "missing".to_string(), ..desc.clone() };
+
+        assert!(matches!(
+            graphics::Pipeline::from_desc(dev, &unknown_shader, &resolver),
+            Err(graphics::DescError::UnknownShader(name)) if name == "missing"
+        ));
+
+        let unknown_mask = graphics::PipelineDesc {
+            blend: vec![graphics::BlendDesc { write_mask: "RGBX".to_string(), ..graphics::BlendDesc::default() }],
+            ..desc
+        };
+
+        assert!(matches!(
+            graphics::Pipeline::from_desc(dev, &unknown_mask, &resolver),
+            Err(graphics::DescError::UnknownName { .. })
+        ));
+    }
+
+    #[test]
+    fn sampler_desc() {
+        let device = test_context::get_graphics_device();
+
+        let desc = graphics::SamplerDesc {
+            mag_filter: "NEAREST".to_string(),
+            address_mode_u: "CLAMP_TO_BORDER".to_string(),
+            border_color: "FLOAT_OPAQUE_WHITE".to_string(),
+            compare_enable: true,
+            compare_op: "LESS".to_string(),
+            ..graphics::SamplerDesc::default()
+        };
+
+        let cfg = desc.cfg().expect("Failed to convert description");
+        let default = graphics::SamplerCfg::default();
+
+        assert_eq!(cfg.mag_filter, graphics::SamplerFilter::NEAREST);
+        assert_eq!(cfg.min_filter, default.min_filter);
+        assert_eq!(cfg.address_mode_u, graphics::SamplerAddressMode::CLAMP_TO_BORDER);
+        assert_eq!(cfg.address_mode_v, default.address_mode_v);
+        assert_eq!(cfg.border_color, graphics::BorderColor::FLOAT_OPAQUE_WHITE);
+        assert!(cfg.compare_enable);
+        assert_eq!(cfg.compare_op, graphics::CompareOp::LESS);
+
+        assert!(graphics::Sampler::from_desc(device, &desc).is_ok());
+
+        let unknown = graphics::SamplerDesc { mipmap_mode: "CUBIC".to_string(), ..desc };
+
+        assert!(matches!(graphics::Sampler::from_desc(device, &unknown), Err(graphics::DescError::UnknownName { .. })));
+    }
+
+    #[test]
+    fn pipeline_display() {
+        let device = test_context::get_graphics_device();
+
+        let extent = memory::Extent2D { width: 64, height: 64 };
+
+        let render_pass = graphics::RenderPass::offscreen(device, memory::ImageFormat::R8G8B8A8_UNORM)
+            .expect("Failed to create render pass");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let pipe_type = graphics::PipelineCfg {
+            extent,
+            render_pass: Some(&render_pass),
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        assert_eq!(pipeline.topology(), graphics::Topology::TRIANGLE_LIST);
+        assert_eq!(pipeline.extent(), extent);
+        assert_eq!(pipeline.stages(), graphics::ShaderStage::VERTEX | graphics::ShaderStage::FRAGMENT);
+        assert_eq!(pipeline.color_attachment_count(), 1);
+
+        assert_eq!(
+            pipeline.to_string(),
+            "Graphics pipeline\n\
+            Stages:               VERTEX | FRAGMENT\n\
+            Topology:             TRIANGLE_LIST\n\
+            Extent:               64x64\n\
+            Samples:              TYPE_1\n\
+            Cull mode:            NONE\n\
+            Polygon mode:         FILL\n\
+            Front face:           COUNTER_CLOCKWISE\n\
+            Color attachments:    1\n\
+            Vertex size:          0\n\
+            Push constant ranges: 0\n"
+        );
+    }
+
+    #[test]
+    fn descriptor_display() {
+        let device = test_context::get_graphics_device();
+
+        let descriptor = graphics::PipelineDescriptor::allocate(device, &[&[
+            graphics::BindingCfg {
+                resource_type: graphics::DescriptorType::UNIFORM_BUFFER,
+                stage: graphics::ShaderStage::VERTEX,
+                count: 1,
+            },
+            graphics::BindingCfg {
+                resource_type: graphics::DescriptorType::COMBINED_IMAGE_SAMPLER,
+                stage: graphics::ShaderStage::FRAGMENT,
+                count: 2,
+            }
+        ]]).expect("Failed to allocate descriptor");
+
+        assert_eq!(
+            descriptor.binding_types(),
+            &[vec![graphics::DescriptorType::UNIFORM_BUFFER, graphics::DescriptorType::COMBINED_IMAGE_SAMPLER]]
+        );
+        assert_eq!(descriptor.binding_counts(), &[vec![1, 2]]);
+
+        assert_eq!(
+            descriptor.to_string(),
+            "Pipeline descriptor\n\
+            Sets: 1\n  \
+              Set 0\n    \
+                Binding 0: UNIFORM_BUFFER x1\n    \
+                Binding 1: COMBINED_IMAGE_SAMPLER x2\n"
+        );
+    }
+
+    #[test]
+    fn vertex_layout() {
+        // Vertex of the texture example
+        let texture_input = [
+            graphics::VertexInputCfg {
+                location: 0,
+                binding: 0,
+                format: memory::ImageFormat::R32G32B32A32_SFLOAT,
+                offset: 0,
+            },
+            graphics::VertexInputCfg {
+                location: 1,
+                binding: 0,
+                format: memory::ImageFormat::R32G32_SFLOAT,
+                offset: std::mem::size_of::<[f32; 4]>() as u32,
+            }
+        ];
+
+        let layout = graphics::VertexLayout::builder()
+            .attr::<[f32; 4]>()
+            .attr::<[f32; 2]>()
+            .build();
+
+        assert_eq!(layout.attributes(), &texture_input);
+        assert_eq!(layout.vertex_size(), std::mem::size_of::<[f32; 6]>() as u32);
+
+        // Vertex of the cube example
+        let cube_input = [
+            graphics::VertexInputCfg {
+                location: 0,
+                binding: 0,
+                format: memory::ImageFormat::R32G32B32A32_SFLOAT,
+                offset: 0,
+            }
+        ];
+
+        let layout = graphics::VertexLayout::builder().attr::<[f32; 4]>().build();
+
+        assert_eq!(layout.attributes(), &cube_input);
+        assert_eq!(layout.vertex_size(), std::mem::size_of::<[f32; 4]>() as u32);
+
+        // Offsets are counted separately for every binding
+        let layout = graphics::VertexLayout::builder()
+            .attr::<[f32; 2]>()
+            .attr_at::<[f32; 2]>(1)
+            .attr::<[u8; 4]>()
+            .attr_at::<u32>(1)
+            .build();
+
+        let offsets: Vec<(u32, u32, u32)> = layout.attributes().iter().map(|x| (x.location, x.binding, x.offset)).collect();
+
+        assert_eq!(offsets, [(0, 0, 0), (1, 1, 0), (2, 0, 8), (3, 1, 8)]);
+        assert_eq!(layout.attributes()[2].format, memory::ImageFormat::R8G8B8A8_UNORM);
+        assert_eq!((layout.stride(0), layout.stride(1), layout.stride(2)), (12, 12, 0));
+        assert_eq!(layout.vertex_size(), 12);
+
+        assert!(graphics::VertexLayout::builder().build().attributes().is_empty());
+    }
+
+    #[test]
+    fn geometry_shader_feature() {
+        const GEOM_SHADER: &str = "
+#version 460
+
+layout(points) in;
+layout(points, max_vertices = 1) out;
+
+void main() {
+    gl_Position = gl_in[0].gl_Position;
+    gl_PointSize = 1.0;
+    EmitVertex();
+    EndPrimitive();
+}
+";
+
+        let dev = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
+        let shader_type = shader::ShaderCfg {
+            path: "GEOM_DATA",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let geom_shader = shader::Shader::from_glsl(dev, &shader_type, GEOM_SHADER, shader::Kind::Geometry)
+            .expect("Failed to create geometry shader module");
+
+        let pipe_type = graphics::PipelineCfg {
+            geom_shader: Some(&geom_shader),
+            topology: graphics::Topology::POINT_LIST,
+            extent: capabilities.extent2d(),
+            render_pass: Some(test_context::get_render_pass()),
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        let result = graphics::Pipeline::new(dev, &pipe_type);
+
+        if dev.features().geometry_shader == 0 {
+            assert!(matches!(result, Err(graphics::PipelineError::GeometryShader)));
+        } else {
+            let pipeline = result.expect("Failed to create pipeline");
+
+            assert!(pipeline.stages().contains(graphics::ShaderStage::GEOMETRY));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn pipeline_desc_serde_round_trip() {
+        let desc = graphics::PipelineDesc {
+            vertex_shader: "vert".to_string(),
+            frag_shader: "frag".to_string(),
+            geom_shader: Some("geom".to_string()),
+            vertex_size: std::mem::size_of::<[f32; 5]>() as u32,
+            vert_input: vec![
+                graphics::VertexInputDesc {
+                    location: 0,
+                    binding: 0,
+                    format: "R32G32_SFLOAT".to_string(),
+                    offset: 0,
+                },
+                graphics::VertexInputDesc {
+                    location: 1,
+                    binding: 0,
+                    format: "R32G32B32_SFLOAT".to_string(),
+                    offset: std::mem::size_of::<[f32; 2]>() as u32,
+                },
+            ],
+            topology: "TRIANGLE_STRIP".to_string(),
+            extent: [800, 600],
+            push_constant_size: 16,
+            samples: "TYPE_4".to_string(),
+            blend: vec![graphics::BlendDesc {
+                enable: true,
+                src_color: "SRC_ALPHA".to_string(),
+                dst_color: "ONE_MINUS_SRC_ALPHA".to_string(),
+                write_mask: "RGB".to_string(),
+                ..graphics::BlendDesc::default()
+            }],
+            depth: Some(graphics::DepthDesc {
+                compare_op: "LESS_OR_EQUAL".to_string(),
+                depth_write: false,
+                depth_bounds: Some((0.25, 0.75)),
+            }),
+            depth_bias: Some([1.0, 0.0, 1.5]),
+            cull_mode: "BACK".to_string(),
+            line_width: 2.0,
+            front_face: "CLOCKWISE".to_string(),
+            ..graphics::PipelineDesc::default()
+        };
+
+        let json = serde_json::to_string(&desc).expect("Failed to serialize to JSON");
+
+        assert_eq!(serde_json::from_str::<graphics::PipelineDesc>(&json).expect("Failed to deserialize JSON"), desc);
+
+        let ron = ron::to_string(&desc).expect("Failed to serialize to RON");
+
+        assert_eq!(ron::from_str::<graphics::PipelineDesc>(&ron).expect("Failed to deserialize RON"), desc);
+
+        // Missing fields take default values
+        let partial: graphics::PipelineDesc = serde_json::from_str(r#"{ "vertex_shader": "vert", "cull_mode": "BACK" }"#)
+            .expect("Failed to deserialize JSON");
+
+        assert_eq!(partial, graphics::PipelineDesc {
+            vertex_shader: "vert".to_string(),
+            cull_mode: "BACK".to_string(),
+            ..graphics::PipelineDesc::default()
+        });
+    }
+
+    #[test]
+    fn independent_blend() {
+        let dev = test_context::get_graphics_device();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let render_pass = graphics::RenderPassBuilder::new()
+            .color_attachment(test_context::get_color_format(), graphics::AttachmentLoadOp::CLEAR,
+                graphics::AttachmentStoreOp::STORE, memory::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .color_attachment(test_context::get_color_format(), graphics::AttachmentLoadOp::CLEAR,
+                graphics::AttachmentStoreOp::STORE, memory::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .subpass(&[0, 1], None, &[])
+            .build(dev)
+            .expect("Failed to create render pass");
+
+        let descriptor = graphics::PipelineDescriptor::empty(dev);
+
+        let pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
+            topology: graphics::Topology::TRIANGLE_STRIP,
+            extent: capabilities.extent2d(),
+            render_pass: Some(&render_pass),
+            blend: &[graphics::BlendCfg::alpha(), graphics::BlendCfg::opaque()],
+            ..graphics::PipelineCfg::new(
+                test_context::get_vert_shader(),
+                test_context::get_frag_shader(),
+                &descriptor
+            )
+        };
+
+        let result = graphics::Pipeline::new(dev, &pipe_type);
+
+        if dev.features().independent_blend == 0 {
+            assert!(matches!(result, Err(graphics::PipelineError::IndependentBlend)));
+        } else {
+            assert!(result.is_ok());
+        }
+    }
+}
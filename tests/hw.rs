@@ -6,7 +6,8 @@ mod hw {
         libvk,
         hw,
         layers,
-        extensions
+        extensions,
+        memory
     };
 
     use super::test_context;
@@ -38,4 +39,40 @@ mod hw {
         assert!(hw_dev.ubo_size(hw_dev.ubo_offset()) == hw_dev.ubo_offset());
         assert!(hw_dev.ubo_size(12345) % hw_dev.ubo_offset() == 0);
     }
+
+    #[test]
+    fn format_support() {
+        let lib = test_context::get_graphics_instance();
+
+        let hw_dev = test_context::get_graphics_hw();
+
+        // Vulkan requires support of at least one of them as depth attachment
+        let depth_format = hw_dev.find_supported_format(
+            lib,
+            &[memory::ImageFormat::D32_SFLOAT, memory::ImageFormat::X8_D24_UNORM_PACK32],
+            memory::Tiling::OPTIMAL,
+            hw::FormatFeature::DEPTH_STENCIL_ATTACHMENT
+        );
+
+        assert!(depth_format.is_some());
+
+        // Mandatory format support
+        let rgba = hw_dev.format_properties(lib, memory::ImageFormat::R8G8B8A8_UNORM);
+
+        assert!(rgba.sampled(memory::Tiling::OPTIMAL));
+        assert!(rgba.color_attachment(memory::Tiling::OPTIMAL));
+        assert!(rgba.transfer_src(memory::Tiling::OPTIMAL));
+        assert!(rgba.transfer_dst(memory::Tiling::OPTIMAL));
+        assert!(!rgba.depth_attachment(memory::Tiling::OPTIMAL));
+
+        let depth = hw_dev.format_properties(lib, depth_format.unwrap());
+
+        assert!(depth.depth_attachment(memory::Tiling::OPTIMAL));
+        assert!(!depth.color_attachment(memory::Tiling::OPTIMAL));
+
+        assert_eq!(
+            hw_dev.find_supported_format(lib, &[], memory::Tiling::OPTIMAL, hw::FormatFeature::SAMPLED_IMAGE),
+            None
+        );
+    }
 }
\ No newline at end of file
@@ -10,6 +10,8 @@ mod libvk {
 
     use super::test_context;
 
+    use std::sync::{Arc, Mutex};
+
     #[test]
     fn default_instance() {
         let lib = libvk::Instance::new(&libvk::InstanceType::default());
@@ -58,4 +60,80 @@ mod libvk {
 
         assert!(lib.is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn debug_filter_and_callback() {
+        let layer = layers::DebugLayer::new(layers::Severity::WARNING)
+            .with_types(layers::MessageType::VALIDATION);
+
+        assert_eq!(layer.as_raw().message_severity, layers::Severity::WARNING | layers::Severity::ERROR);
+        assert_eq!(layer.as_raw().message_type, layers::MessageType::VALIDATION);
+
+        let messages = Arc::new(Mutex::new(Vec::<String>::new()));
+        let sink = messages.clone();
+
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layer.with_callback(move |_, _, msg| sink.lock().unwrap().push(msg.to_string()))),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to create instance");
+
+        assert_eq!(lib.error_count(), 0);
+        assert_eq!(lib.error_count(), lib_type.debug_layer.as_ref().unwrap().error_count());
+        assert!(messages.lock().unwrap().len() >= lib.error_count() + lib.warning_count());
+    }
+
+    #[test]
+    fn missing_layer_and_extension() {
+        let bogus_layer = c"VK_LAYER_LIBVKTYPES_bogus";
+
+        let lib_type = libvk::InstanceType {
+            layers: &[bogus_layer.as_ptr()],
+            ..libvk::InstanceType::default()
+        };
+
+        match libvk::Instance::new(&lib_type) {
+            Err(libvk::InstanceError::MissingLayer(name)) => assert_eq!(name, "VK_LAYER_LIBVKTYPES_bogus"),
+            other => panic!("Expected MissingLayer, got {:?}", other.err())
+        }
+
+        let bogus_ext = c"VK_LIBVKTYPES_bogus";
+
+        let lib_type = libvk::InstanceType {
+            extensions: &[bogus_ext.as_ptr()],
+            ..libvk::InstanceType::default()
+        };
+
+        match libvk::Instance::new(&lib_type) {
+            Err(libvk::InstanceError::MissingExtension(name)) => assert_eq!(name, "VK_LIBVKTYPES_bogus"),
+            other => panic!("Expected MissingExtension, got {:?}", other.err())
+        }
+    }
+
+    #[test]
+    fn available_layers_and_extensions() {
+        let entry = libvk::load_entry(false).expect("Failed to load entry");
+
+        let layers = layers::available_layers(&entry);
+
+        assert!(!layers.contains(&"VK_LAYER_LIBVKTYPES_bogus".to_string()));
+
+        // Debug layer is enabled only if it is installed
+        let debug_layer = layers::DebugLayer::if_available(&entry);
+
+        assert_eq!(debug_layer.is_some(), layers.contains(&"VK_LAYER_KHRONOS_validation".to_string()));
+
+        let lib_type = libvk::InstanceType {
+            debug_layer,
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        assert!(libvk::Instance::new(&lib_type).is_ok());
+
+        assert!(extensions::available_extensions(&entry, None).contains(&"VK_EXT_debug_utils".to_string()));
+        assert!(extensions::available_extensions(&entry, Some("VK_LAYER_LIBVKTYPES_bogus")).is_empty());
+    }
+}
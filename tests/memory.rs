@@ -8,9 +8,14 @@ mod memory {
         hw,
         layers,
         libvk,
-        memory
+        memory,
+        cmd,
+        queue,
+        graphics
     };
 
+    use ash::vk;
+
     use super::test_context;
 
     #[test]
@@ -36,7 +41,11 @@ mod memory {
             lib: &lib,
             hw: hw_dev,
             extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
             allocator: None,
+            track_objects: false,
         };
 
         let device = dev::Device::new(&dev_type).expect("Failed to create device");
@@ -81,7 +90,11 @@ mod memory {
             lib: &lib,
             hw: hw_dev,
             extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
             allocator: None,
+            track_objects: false,
         };
 
         let device = dev::Device::new(&dev_type).expect("Failed to create device");
@@ -115,7 +128,10 @@ mod memory {
     fn image_allocation() {
         let swp = test_context::get_swapchain();
 
-        assert!(swp.images().is_ok());
+        assert!(!swp.images().is_empty());
+        assert_eq!(swp.images().len() as u32, swp.image_count());
+        assert_eq!(swp.images()[0].view(0).format(), swp.format());
+        assert_eq!(swp.extent(), test_context::get_surface_capabilities().extent2d());
     }
 
     #[test]
@@ -134,6 +150,10 @@ mod memory {
                 layout: memory::ImageLayout::UNDEFINED,
                 aspect: memory::ImageAspect::DEPTH,
                 tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
                 count: 1
             }
         ];
@@ -166,6 +186,112 @@ mod memory {
         assert!(memory::Framebuffer::new(dev, &framebuffer_cfg).is_ok());
     }
 
+    #[test]
+    fn framebuffers_for_swapchain() {
+        let dev = test_context::get_graphics_device();
+
+        let rp = test_context::get_render_pass();
+
+        let images = test_context::get_image_list();
+
+        let extent = test_context::get_surface_capabilities().extent2d();
+
+        let frames = memory::Framebuffer::for_swapchain(dev, rp, images, &[], extent)
+            .expect("Failed to create framebuffers");
+
+        assert_eq!(frames.len(), images.len());
+
+        assert!(matches!(
+            memory::Framebuffer::for_swapchain(dev, rp, images, &[images[0].view(0)], extent),
+            Err(memory::FramebufferError::AttachmentCount { expected: 1, actual: 2 })
+        ));
+
+        let smaller = memory::Extent2D {
+            width: extent.width/2,
+            height: extent.height,
+        };
+
+        assert!(matches!(
+            memory::Framebuffer::for_swapchain(dev, rp, images, &[], smaller),
+            Err(memory::FramebufferError::Extent(0))
+        ));
+    }
+
+    #[test]
+    fn framebuffer_compatibility() {
+        let dev = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let caps = test_context::get_surface_capabilities();
+
+        let images = test_context::get_image_list();
+
+        let depth_rp = graphics::RenderPass::with_depth_buffers(
+            dev,
+            images[0].view(0).format(),
+            memory::ImageFormat::D32_SFLOAT,
+            1
+        ).expect("Failed to create render pass");
+
+        let missing_depth = memory::FramebufferCfg {
+            render_pass: &depth_rp,
+            images: &[images[0].view(0)],
+            extent: caps.extent2d()
+        };
+
+        assert!(matches!(
+            memory::Framebuffer::new(dev, &missing_depth),
+            Err(memory::FramebufferError::AttachmentCount { expected: 2, actual: 1 })
+        ));
+
+        let color_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: memory::ImageFormat::R8G8B8A8_SRGB,
+                extent: caps.extent3d(1),
+                usage: memory::ImageUsageFlags::COLOR_ATTACHMENT,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &color_cfg
+        };
+
+        let color = memory::ImageMemory::allocate(dev, &alloc_info).expect("Failed to allocate image");
+
+        let unorm_rp = graphics::RenderPass::single_subpass(dev, memory::ImageFormat::R8G8B8A8_UNORM)
+            .expect("Failed to create render pass");
+
+        assert_eq!(unorm_rp.attachment_formats(), &[memory::ImageFormat::R8G8B8A8_UNORM]);
+
+        let wrong_format = memory::FramebufferCfg {
+            render_pass: &unorm_rp,
+            images: &[color.view(0)],
+            extent: caps.extent2d()
+        };
+
+        assert!(matches!(
+            memory::Framebuffer::new(dev, &wrong_format),
+            Err(memory::FramebufferError::Incompatible {
+                index: 0,
+                expected: memory::ImageFormat::R8G8B8A8_UNORM,
+                got: memory::ImageFormat::R8G8B8A8_SRGB
+            })
+        ));
+    }
+
     #[test]
     fn access_buffers() {
         let lib_type = libvk::InstanceType {
@@ -189,7 +315,11 @@ mod memory {
             lib: &lib,
             hw: hw_dev,
             extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
             allocator: None,
+            track_objects: false,
         };
 
         let device = dev::Device::new(&dev_type).expect("Failed to create device");
@@ -231,6 +361,175 @@ mod memory {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn access_buffer_ranges() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let vertex_data = memory::BufferCfg {
+            size: 1024,
+            usage: memory::VERTEX,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&vertex_data]
+        };
+
+        let memory = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        memory.access(&mut |bytes: &mut [u8]| {
+            bytes.fill(0x11);
+        }, 0).expect("Failed to fill buffer");
+
+        memory.access_range(&mut |bytes: &mut [u8]| {
+            assert_eq!(bytes.len(), 100);
+            bytes.fill(0x42);
+        }, 0, 10, 100).expect("Failed to write first range");
+
+        memory.access_range(&mut |bytes: &mut [u8]| {
+            assert_eq!(bytes.len(), 24);
+            bytes.fill(0xff);
+        }, 0, 1000, 24).expect("Failed to write second range");
+
+        memory.access(&mut |bytes: &mut [u8]| {
+            assert!(bytes[..10].iter().all(|&x| x == 0x11));
+            assert!(bytes[10..110].iter().all(|&x| x == 0x42));
+            assert!(bytes[110..1000].iter().all(|&x| x == 0x11));
+            assert!(bytes[1000..].iter().all(|&x| x == 0xff));
+        }, 0).expect("Failed to read buffer");
+
+        assert!(matches!(
+            memory.access_range(&mut |_: &mut [u8]| {}, 0, 1000, 25),
+            Err(memory::MemoryError::MapAccess(vk::Result::ERROR_MEMORY_MAP_FAILED))
+        ));
+    }
+
+    #[test]
+    fn persistent_mapping() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let vertex_data = memory::BufferCfg {
+            size: 64,
+            usage: memory::VERTEX,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let ubo = memory::BufferCfg {
+            size: 128,
+            usage: memory::UNIFORM,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&vertex_data, &ubo]
+        };
+
+        let mut memory = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        {
+            let mut mapped = memory.map_persistent().expect("Failed to map memory");
+
+            for frame in 0..4u32 {
+                mapped.slice_of::<u32>(0).expect("Failed to get vertex data").fill(frame);
+                mapped.slice_of::<f32>(1).expect("Failed to get uniform data").fill(frame as f32);
+
+                assert!(mapped.flush(0).is_ok());
+                assert!(mapped.flush(1).is_ok());
+            }
+
+            assert_eq!(mapped.slice_of::<u32>(0).expect("Failed to get vertex data").len(), 16);
+            assert_eq!(mapped.slice_of::<u8>(1).expect("Failed to get uniform data").len(), 128);
+
+            assert!(matches!(
+                mapped.slice_of::<u32>(2),
+                Err(memory::MemoryError::InvalidIndex { index: 2, count: 2 })
+            ));
+
+            assert!(matches!(
+                mapped.slice_of::<[u8; 3]>(0),
+                Err(memory::MemoryError::SizeMismatch { size: 64, type_size: 3 })
+            ));
+
+            assert!(mapped.slice_of::<u32>(0).expect("Failed to get vertex data").iter().all(|&x| x == 3));
+        }
+
+        drop(memory.map_persistent().expect("Failed to map memory again"));
+
+        memory.access(&mut |values: &mut [u32]| {
+            assert!(values.iter().all(|&x| x == 3));
+        }, 0).expect("Failed to read vertex data");
+
+        memory.access(&mut |values: &mut [f32]| {
+            assert!(values.iter().all(|&x| x == 3.0));
+        }, 1).expect("Failed to read uniform data");
+    }
+
     #[test]
     fn multiple_images() {
         let queue = test_context::get_graphics_queue();
@@ -245,6 +544,10 @@ mod memory {
                 layout: memory::ImageLayout::UNDEFINED,
                 aspect: memory::ImageAspect::DEPTH,
                 tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
                 count: 1
             },
             memory::ImageCfg {
@@ -256,6 +559,10 @@ mod memory {
                 layout: memory::ImageLayout::UNDEFINED,
                 aspect: memory::ImageAspect::COLOR,
                 tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
                 count: 2
             }
         ];
@@ -285,6 +592,10 @@ mod memory {
                 layout: memory::ImageLayout::UNDEFINED,
                 aspect: memory::ImageAspect::COLOR,
                 tiling: memory::Tiling::LINEAR,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
                 count: 1
             }
         ];
@@ -303,4 +614,771 @@ mod memory {
 
         assert!(result.is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn mixed_memory_types() {
+        let queue = test_context::get_graphics_queue();
+
+        let images_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: memory::ImageFormat::R8G8B8A8_UNORM,
+                extent: memory::Extent3D {height: 64, width: 64, depth: 1 },
+                usage: memory::ImageUsageFlags::TRANSFER_SRC | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::LINEAR,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            },
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: memory::ImageFormat::D32_SFLOAT,
+                extent: memory::Extent3D {height: 64, width: 64, depth: 1 },
+                usage: memory::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::DEPTH,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &images_cfg
+        };
+
+        let device = test_context::get_graphics_device();
+
+        let linear_bits = image_memory_bits(device, &images_cfg[0]);
+        let optimal_bits = image_memory_bits(device, &images_cfg[1]);
+
+        let is_local = |bits: u32| device
+            .hw()
+            .memory()
+            .any(|m| (bits >> m.index()) & 1 == 1 && m.is_compatible(hw::MemoryProperty::DEVICE_LOCAL));
+
+        // Linear image may have no DEVICE_LOCAL memory at all, nothing to check then
+        if !is_local(linear_bits) || !is_local(optimal_bits) {
+            return;
+        }
+
+        let result = memory::ImageMemory::allocate(device, &alloc_info);
+
+        if is_local(linear_bits & optimal_bits) {
+            assert!(result.is_ok(), "Images with common memory type must be allocated together");
+        } else {
+            assert!(matches!(result, Err(memory::MemoryError::IncompatibleMemoryType { index: 1 })));
+        }
+    }
+
+    /// Return `memoryTypeBits` of the 2D image created from `cfg`
+    fn image_memory_bits(device: &dev::Device, cfg: &memory::ImageCfg) -> u32 {
+        let image_info = vk::ImageCreateInfo::default()
+            .image_type(vk::ImageType::TYPE_2D)
+            .format(cfg.format)
+            .extent(cfg.extent)
+            .mip_levels(cfg.mip_levels)
+            .array_layers(cfg.array_layers)
+            .samples(cfg.samples)
+            .tiling(cfg.tiling)
+            .usage(cfg.usage)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(cfg.layout);
+
+        unsafe {
+            let image = device
+                .device()
+                .create_image(&image_info, device.allocator())
+                .expect("Failed to create image");
+
+            let requirements = device.device().get_image_memory_requirements(image);
+
+            device.device().destroy_image(image, device.allocator());
+
+            requirements.memory_type_bits
+        }
+    }
+
+    #[test]
+    fn staging_round_trip() {
+        let device = test_context::get_graphics_device();
+
+        let queue_family = test_context::get_graphics_queue();
+
+        let local_cfg = memory::BufferCfg {
+            size: 256,
+            usage: memory::STORAGE,
+            queue_families: &[queue_family.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            buffers: &[&local_cfg]
+        };
+
+        let local = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let queue = device.get_queue(&queue::QueueCfg {
+            family_index: queue_family.index(),
+            queue_index: 0
+        }).expect("Failed to get queue");
+
+        let pool = cmd::Pool::new(device, &cmd::PoolCfg { queue_index: queue_family.index() })
+            .expect("Failed to allocate command pool");
+
+        let staging = memory::StagingBuffer::new(device, &queue, &pool);
+
+        let data: Vec<u8> = (0..200).map(|i| i as u8).collect();
+
+        staging.upload(local.view(0), &data).expect("Failed to upload data");
+
+        let capacity = staging.capacity();
+
+        assert!(capacity >= data.len() as u64);
+
+        let mut result = vec![0u8; data.len()];
+
+        staging.download(local.view(0), &mut result).expect("Failed to download data");
+
+        assert_eq!(data, result);
+
+        let whole = local.view(0).download(device, &queue, &pool).expect("Failed to download buffer");
+
+        assert_eq!(whole.len(), 256);
+        assert_eq!(whole[..data.len()], data[..]);
+
+        // Staging memory is reused
+        assert_eq!(staging.capacity(), capacity);
+
+        assert!(matches!(
+            staging.upload(local.view(0), &[0u8; 512]),
+            Err(memory::MemoryError::TransferSize)
+        ));
+    }
+
+    #[test]
+    fn exceeds_budget() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let mem_type = device
+            .hw()
+            .find_first_memory(|m| m.is_compatible(hw::MemoryProperty::HOST_VISIBLE))
+            .expect("No host visible memory");
+
+        // Without VK_EXT_memory_budget the whole heap is available
+        assert!(mem_type.usage() <= mem_type.budget());
+        assert!(mem_type.available() <= mem_type.heap_size());
+
+        let heap = mem_type.heap_index();
+
+        // Split request so every buffer stays below maxBufferSize
+        let chunk: u64 = 1 << 28;
+
+        let buffer_cfg = memory::BufferCfg {
+            size: chunk,
+            usage: memory::BufferUsageFlags::TRANSFER_SRC,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: (mem_type.available()/chunk + 1) as usize
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &|m: &hw::MemoryDescription| m.heap_index() == heap,
+            buffers: &[&buffer_cfg]
+        };
+
+        assert!(matches!(
+            memory::Memory::allocate(device, &mem_cfg),
+            Err(memory::MemoryError::ExceedsBudget { heap: err_heap, .. }) if err_heap == heap
+        ));
+    }
+
+    #[test]
+    fn suballocation_stress() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let allocator = memory::Allocator::new(device, memory::DEFAULT_BLOCK_SIZE);
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 256,
+            usage: memory::BufferUsageFlags::UNIFORM_BUFFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        // Far more than maxMemoryAllocationCount of most drivers
+        let mut alive = Vec::new();
+
+        for i in 0..10_000u32 {
+            let memory = memory::Memory::allocate_from(device, &mem_cfg, &allocator)
+                .expect("Failed to allocate memory");
+
+            memory.view(0).access(&mut |bytes: &mut [u32]| bytes[0] = i)
+                .expect("Failed to write memory");
+
+            // Keep some allocations alive so freed ranges are interleaved
+            if i % 8 == 0 {
+                alive.push((i, memory));
+            }
+        }
+
+        for (i, memory) in &alive {
+            memory.view(0).access(&mut |bytes: &mut [u32]| assert_eq!(bytes[0], *i))
+                .expect("Failed to read memory");
+        }
+
+        assert!(allocator.block_count() <= 2);
+
+        drop(alive);
+
+        let _memory = memory::Memory::allocate_from(device, &mem_cfg, &allocator)
+            .expect("Failed to allocate memory");
+
+        assert!(allocator.block_count() <= 2);
+    }
+
+    #[test]
+    fn layout_introspection() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let vertex_cfg = memory::BufferCfg {
+            size: 3,
+            usage: memory::BufferUsageFlags::VERTEX_BUFFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let ubo_cfg = memory::BufferCfg {
+            size: 64,
+            usage: memory::UNIFORM,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 2
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&vertex_cfg, &ubo_cfg]
+        };
+
+        let memory = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        assert_eq!(memory.element_count(), 3);
+        assert_eq!(memory.layout().count(), 3);
+
+        let printed = format!("{}", memory);
+
+        for info in memory.layout() {
+            assert_eq!(info.offset, memory.view(info.index).offset());
+            assert_eq!(info.allocated_size, memory.allocated_size(info.index));
+            assert_eq!(info.requested_size, memory.view(info.index).size());
+
+            assert!(printed.contains(&format!(
+                "subregion: Subregion {{ offset: {}, allocated_size: {} }}\nsize: {}\n",
+                info.offset,
+                info.allocated_size,
+                info.requested_size
+            )));
+        }
+
+        let infos: Vec<memory::ElementInfo> = memory.layout().collect();
+
+        assert_eq!(infos[0].kind, memory::ElementKind::Buffer(memory::BufferUsageFlags::VERTEX_BUFFER));
+        assert_eq!(infos[2].kind, memory::ElementKind::Buffer(memory::UNIFORM));
+        assert!(infos[1].offset >= infos[0].offset + infos[0].requested_size);
+
+        let images_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: memory::ImageFormat::D32_SFLOAT,
+                extent: memory::Extent3D {height: 64, width: 64, depth: 1 },
+                usage: memory::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::DEPTH,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 2
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &images_cfg
+        };
+
+        let images = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate images");
+
+        let printed = format!("{}", images);
+
+        assert_eq!(images.element_count(), 2);
+
+        for info in images.layout() {
+            assert_eq!(info.offset, images.view(info.index).offset());
+            assert_eq!(info.kind, memory::ElementKind::Image(memory::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT));
+
+            assert!(printed.contains(&format!(
+                "id: {}\nsubregion: Subregion {{ offset: {}, allocated_size: {} }}\n",
+                info.index,
+                info.offset,
+                info.allocated_size
+            )));
+        }
+    }
+
+    #[test]
+    fn allocation_error_source() {
+        use std::error::Error;
+
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        // No heap is that large so vkAllocateMemory fails for the first block
+        let allocator = memory::Allocator::new(device, u64::MAX/2);
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 256,
+            usage: memory::BufferUsageFlags::UNIFORM_BUFFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let result = match memory::Memory::allocate_from(device, &mem_cfg, &allocator) {
+            Err(memory::MemoryError::DeviceMemory(result)) => result,
+            _ => panic!("Allocation must fail with DeviceMemory error"),
+        };
+
+        assert!(result == vk::Result::ERROR_OUT_OF_DEVICE_MEMORY || result == vk::Result::ERROR_OUT_OF_HOST_MEMORY);
+
+        let err = memory::MemoryError::DeviceMemory(result);
+
+        assert_eq!(err.source().and_then(|x| x.downcast_ref::<vk::Result>()), Some(&result));
+
+        let err: libvktypes::Error = err.into();
+
+        assert!(matches!(err, libvktypes::Error::Memory(memory::MemoryError::DeviceMemory(_))));
+        assert_eq!(err.source().and_then(|x| x.downcast_ref::<vk::Result>()), Some(&result));
+    }
+
+    #[test]
+    fn invalid_element_index() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 64,
+            usage: memory::BufferUsageFlags::VERTEX_BUFFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 2
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let memory = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        assert!(memory.try_view(1).is_some());
+        assert!(memory.try_view(2).is_none());
+        assert!(memory.try_vertex_view(2, 0).is_none());
+
+        assert!(matches!(
+            memory.vertex_view(0, 64),
+            Err(memory::MemoryError::InvalidOffset { offset: 64, size: 64 })
+        ));
+
+        assert_eq!(memory.vertex_view(1, 63).expect("Failed to create vertex view").size(), 1);
+
+        assert!(matches!(
+            memory.access(&mut |_: &mut [u8]| {}, 2),
+            Err(memory::MemoryError::InvalidIndex { index: 2, count: 2 })
+        ));
+
+        assert!(matches!(
+            memory.access_range(&mut |_: &mut [u8]| {}, 5, 0, 4),
+            Err(memory::MemoryError::InvalidIndex { index: 5, count: 2 })
+        ));
+
+        let image_cfg = memory::ImageCfg {
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            format: memory::ImageFormat::R8G8B8A8_UNORM,
+            extent: memory::Extent3D { height: 16, width: 16, depth: 1 },
+            usage: memory::ImageUsageFlags::COLOR_ATTACHMENT,
+            layout: memory::ImageLayout::UNDEFINED,
+            aspect: memory::ImageAspect::COLOR,
+            tiling: memory::Tiling::OPTIMAL,
+            samples: memory::SampleCount::TYPE_1,
+            mip_levels: 1,
+            kind: memory::ImageKind::D2,
+            array_layers: 1,
+            count: 1
+        };
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &[image_cfg]
+        };
+
+        let images = memory::ImageMemory::allocate(device, &alloc_info).expect("Failed to allocate image");
+
+        assert!(images.try_view(0).is_some());
+
+        // Index of the second buffer does not exist among images
+        assert!(images.try_view(1).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn view_out_of_bounds() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 64,
+            usage: memory::BufferUsageFlags::UNIFORM_BUFFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let memory = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        memory.view(1);
+    }
+
+    #[test]
+    fn unsupported_image_format() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, qf, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_graphics,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: true,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let valid_cfg = memory::ImageCfg {
+            queue_families: &[qf.index()],
+            simultaneous_access: false,
+            format: memory::ImageFormat::R8G8B8A8_UNORM,
+            extent: memory::Extent3D { width: 16, height: 16, depth: 1 },
+            usage: memory::ImageUsageFlags::COLOR_ATTACHMENT,
+            layout: memory::ImageLayout::UNDEFINED,
+            aspect: memory::ImageAspect::COLOR,
+            tiling: memory::Tiling::OPTIMAL,
+            samples: memory::SampleCount::TYPE_1,
+            mip_levels: 1,
+            kind: memory::ImageKind::D2,
+            array_layers: 1,
+            count: 2
+        };
+
+        // Compressed formats can not be used as attachments
+        let rejected_cfg = memory::ImageCfg {
+            format: memory::ImageFormat::BC1_RGBA_UNORM_BLOCK,
+            count: 1,
+            ..valid_cfg
+        };
+
+        let image_cfgs = [valid_cfg, rejected_cfg];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfgs
+        };
+
+        assert!(matches!(
+            memory::ImageMemory::allocate(&device, &alloc_info),
+            Err(memory::MemoryError::UnsupportedImage { format: memory::ImageFormat::BC1_RGBA_UNORM_BLOCK, .. })
+        ));
+
+        // Images of the first element are destroyed and no invalid call reached the driver
+        assert!(device.live_objects().is_empty());
+        assert_eq!(lib.error_count(), 0);
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfgs[..1]
+        };
+
+        let images = memory::ImageMemory::allocate(&device, &alloc_info).expect("Failed to allocate images");
+
+        assert_eq!(device.live_objects().images, 2);
+        assert_eq!(device.live_objects().image_views, 2);
+
+        drop(images);
+
+        assert!(device.live_objects().is_empty());
+    }
+
+    #[test]
+    fn cached_memory_read() {
+        let device = test_context::get_graphics_device();
+        let queue = test_context::get_graphics_queue();
+
+        let src_cfg = memory::BufferCfg {
+            size: 64,
+            usage: memory::BufferUsageFlags::TRANSFER_SRC,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let src = memory::Memory::allocate(device, &memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&src_cfg]
+        }).expect("Failed to allocate memory");
+
+        let dst_cfg = memory::BufferCfg {
+            size: 64,
+            usage: memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        // Prefer non-coherent memory as it requires invalidation before read
+        let dst = memory::Memory::allocate(device, &memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_CACHED,
+            filter: &|desc| !desc.is_host_coherent(),
+            buffers: &[&dst_cfg]
+        }).or_else(|_| memory::Memory::allocate(device, &memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_CACHED,
+            filter: &hw::any,
+            buffers: &[&dst_cfg]
+        })).expect("Failed to allocate memory");
+
+        src.access(&mut |data: &mut [u32]| {
+            for (i, value) in data.iter_mut().enumerate() {
+                *value = i as u32 + 1;
+            }
+        }, 0).expect("Failed to write memory");
+
+        // Host cache now holds the old content of the buffer
+        assert_eq!(dst.read(0).expect("Failed to read memory").len(), 64);
+
+        let cmd_buffer = test_context::get_cmd_pool().allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.copy_memory(&src.view(0), &dst.view(0));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let cmd_queue = queue::Queue::new(device, &queue::QueueCfg { family_index: queue.index(), queue_index: 0 })
+            .expect("Failed to get queue");
+
+        cmd_queue.exec(&queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        }).expect("Failed to execute queue");
+
+        let expected: Vec<u32> = (1..=16).collect();
+
+        assert_eq!(dst.read_as::<u32>(0).expect("Failed to read memory"), expected);
+
+        let bytes = dst.read(0).expect("Failed to read memory");
+
+        assert_eq!(&bytes[..8], &[1, 0, 0, 0, 2, 0, 0, 0]);
+
+        assert!(matches!(
+            dst.read_as::<[u8; 3]>(0),
+            Err(memory::MemoryError::SizeMismatch { size: 64, type_size: 3 })
+        ));
+
+        assert!(matches!(
+            dst.read(1),
+            Err(memory::MemoryError::InvalidIndex { index: 1, count: 1 })
+        ));
+    }
+
+    #[test]
+    fn image_extent_too_large() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let extent = memory::Extent3D { width: 1 << 20, height: 1 << 20, depth: 1 };
+
+        let image_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: memory::ImageFormat::R8G8B8A8_UNORM,
+                extent,
+                usage: memory::ImageUsageFlags::SAMPLED | memory::ImageUsageFlags::TRANSFER_DST,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &image_cfg
+        };
+
+        match memory::ImageMemory::allocate(device, &alloc_info) {
+            Err(memory::MemoryError::ExtentTooLarge { requested, max }) => {
+                assert_eq!(requested, extent);
+                assert!(max.width < extent.width || max.height < extent.height);
+            },
+            _ => panic!("Extent is not rejected")
+        }
+    }
+
+    #[test]
+    fn suballocation_buffer_image_granularity() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let allocator = memory::Allocator::new(device, memory::DEFAULT_BLOCK_SIZE);
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 100,
+            usage: memory::BufferUsageFlags::STORAGE_BUFFER,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        };
+
+        let buffer = memory::Memory::allocate_from(device, &mem_cfg, &allocator)
+            .expect("Failed to allocate memory");
+
+        let images_cfg = [
+            memory::ImageCfg {
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                format: memory::ImageFormat::R8G8B8A8_UNORM,
+                extent: memory::Extent3D { width: 16, height: 16, depth: 1 },
+                usage: memory::ImageUsageFlags::SAMPLED,
+                layout: memory::ImageLayout::UNDEFINED,
+                aspect: memory::ImageAspect::COLOR,
+                tiling: memory::Tiling::OPTIMAL,
+                samples: memory::SampleCount::TYPE_1,
+                mip_levels: 1,
+                kind: memory::ImageKind::D2,
+                array_layers: 1,
+                count: 1
+            }
+        ];
+
+        let alloc_info = memory::ImagesAllocationInfo {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            image_cfgs: &images_cfg
+        };
+
+        let image = memory::ImageMemory::allocate_from(device, &alloc_info, &allocator)
+            .expect("Failed to allocate image");
+
+        // Buffer and image got different memory types so they never share a block
+        if allocator.block_count() != 1 {
+            return;
+        }
+
+        let granularity = device.hw().buffer_image_granularity();
+
+        let buffer_pages = (buffer.memory_offset()/granularity, (buffer.memory_offset() + buffer.size() - 1)/granularity);
+        let image_pages = (image.memory_offset()/granularity, (image.memory_offset() + image.size() - 1)/granularity);
+
+        assert!(buffer_pages.1 < image_pages.0 || image_pages.1 < buffer_pages.0);
+
+        assert_eq!(buffer.memory_offset() % granularity, 0);
+        assert_eq!(image.memory_offset() % granularity, 0);
+    }
+}
@@ -0,0 +1,450 @@
+mod test_context;
+
+#[cfg(test)]
+mod offscreen {
+    use libvktypes::{graphics, memory, hw, shader, cmd, queue, formats, compute};
+
+    use super::test_context;
+
+    const VERT_SHADER: &str = "
+#version 460
+
+vec2 positions[3] = vec2[](
+    vec2( 0.5,  0.5),
+    vec2( 0.0, -0.5),
+    vec2(-0.5,  0.5)
+);
+
+void main() {
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+}
+";
+
+    const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(0.0, 1.0, 0.0, 1.0);
+}
+";
+
+    #[test]
+    fn render_triangle() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent2D { width: 64, height: 64 };
+
+        let image = memory::ImageMemory::offscreen(device, format, extent).expect("Failed to allocate image");
+
+        let readback_cfg = memory::BufferCfg {
+            size: (extent.width*extent.height) as u64*formats::block_size(format),
+            usage: memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&readback_cfg]
+        };
+
+        let readback = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let render_pass = graphics::RenderPass::offscreen(device, format).expect("Failed to create render pass");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[image.view(0)],
+            extent,
+        };
+
+        let framebuffer = memory::Framebuffer::new(device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let shader_type = shader::ShaderCfg {
+            path: "OFFSCREEN",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert_shader = shader::Shader::from_glsl(device, &shader_type, VERT_SHADER, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader module");
+
+        let frag_shader = shader::Shader::from_glsl(device, &shader_type, FRAG_SHADER, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader module");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let pipe_type = graphics::PipelineCfg {
+            extent,
+            render_pass: Some(&render_pass),
+            ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_buffer = test_context::get_cmd_pool().allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.draw(3, 1, 0, 0);
+        cmd_buffer.end_render_pass();
+
+        // Render pass leaves image in TRANSFER_SRC_OPTIMAL
+        image.view(0).set_layout(memory::ImageLayout::TRANSFER_SRC_OPTIMAL);
+
+        cmd_buffer.copy_image_to_buffer(image.view(0), readback.view(0));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let cmd_queue = queue::Queue::new(device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+        readback.access(&mut |pixels: &mut [[u8; 4]]| {
+            // Center is covered by triangle
+            assert_eq!(pixels[(32*extent.width + 32) as usize], [0x00, 0xff, 0x00, 0xff]);
+            // Corners are not
+            assert_eq!(pixels[0], [0x00, 0x00, 0x00, 0x00]);
+            assert_eq!(pixels[(extent.width*extent.height - 1) as usize], [0x00, 0x00, 0x00, 0x00]);
+        }, 0).expect("Failed to read rendered image");
+    }
+
+    #[test]
+    fn partial_render_area() {
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent2D { width: 64, height: 64 };
+
+        let image = memory::ImageMemory::offscreen(device, format, extent).expect("Failed to allocate image");
+
+        let readback_cfg = memory::BufferCfg {
+            size: (extent.width*extent.height) as u64*formats::block_size(format),
+            usage: memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&readback_cfg]
+        };
+
+        let readback = memory::Memory::allocate(device, &mem_cfg).expect("Failed to allocate memory");
+
+        let first_pass = graphics::RenderPass::offscreen(device, format).expect("Failed to create render pass");
+
+        // Same as offscreen but keeps result of the first pass outside of render area
+        let second_pass = graphics::RenderPassBuilder::new()
+            .attachment(graphics::AttachmentInfo {
+                format,
+                samples: memory::SampleCount::TYPE_1,
+                load_op: graphics::AttachmentLoadOp::CLEAR,
+                store_op: graphics::AttachmentStoreOp::STORE,
+                stencil_load_op: graphics::AttachmentLoadOp::DONT_CARE,
+                stencil_store_op: graphics::AttachmentStoreOp::DONT_CARE,
+                initial_layout: memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                final_layout: memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            })
+            .subpass(&[0], None, &[])
+            .dependency(graphics::SubpassSync {
+                src_subpass: graphics::SUBPASS_EXTERNAL,
+                dst_subpass: 0,
+                src_stage: graphics::PipelineStage::TRANSFER,
+                dst_stage: graphics::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+                src_access: graphics::AccessFlags::TRANSFER_READ,
+                dst_access: graphics::AccessFlags::COLOR_ATTACHMENT_WRITE,
+            })
+            .dependency(graphics::SubpassSync {
+                src_subpass: 0,
+                dst_subpass: graphics::SUBPASS_EXTERNAL,
+                src_stage: graphics::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+                dst_stage: graphics::PipelineStage::TRANSFER,
+                src_access: graphics::AccessFlags::COLOR_ATTACHMENT_WRITE,
+                dst_access: graphics::AccessFlags::TRANSFER_READ,
+            })
+            .build(device)
+            .expect("Failed to create render pass");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &first_pass,
+            images: &[image.view(0)],
+            extent,
+        };
+
+        let framebuffer = memory::Framebuffer::new(device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let red = cmd::ClearValue::Color([1.0, 0.0, 0.0, 1.0]);
+        let green = cmd::ClearValue::Color([0.0, 1.0, 0.0, 1.0]);
+
+        let left_half = graphics::Rect2D {
+            offset: graphics::Offset2D { x: 0, y: 0 },
+            extent: memory::Extent2D { width: extent.width/2, height: extent.height },
+        };
+
+        let cmd_buffer = test_context::get_cmd_pool().allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.begin_render_pass_with_clear(&first_pass, &framebuffer, &[red]);
+        cmd_buffer.end_render_pass();
+
+        let too_wide = graphics::Rect2D {
+            offset: graphics::Offset2D { x: extent.width as i32/2 + 1, y: 0 },
+            ..left_half
+        };
+
+        let err = cmd_buffer
+            .begin_render_pass_area(&second_pass, &framebuffer, too_wide, &[green])
+            .expect_err("Render area must be validated");
+
+        assert_eq!(err.limits.len(), 1);
+        assert_eq!(err.limits[0].dimension, 0);
+        assert_eq!(err.limits[0].value, extent.width + 1);
+        assert_eq!(err.limits[0].max, extent.width);
+
+        let negative = graphics::Rect2D {
+            offset: graphics::Offset2D { x: 0, y: -1 },
+            ..left_half
+        };
+
+        assert!(cmd_buffer.begin_render_pass_area(&second_pass, &framebuffer, negative, &[green]).is_err());
+
+        cmd_buffer.begin_render_pass_area(&second_pass, &framebuffer, left_half, &[green])
+            .expect("Failed to begin render pass");
+        cmd_buffer.end_render_pass();
+
+        image.view(0).set_layout(memory::ImageLayout::TRANSFER_SRC_OPTIMAL);
+
+        cmd_buffer.copy_image_to_buffer(image.view(0), readback.view(0));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let cmd_queue = queue::Queue::new(device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+        readback.access(&mut |pixels: &mut [[u8; 4]]| {
+            for y in [0, extent.height/2, extent.height - 1] {
+                let row = (y*extent.width) as usize;
+
+                assert_eq!(pixels[row], [0x00, 0xff, 0x00, 0xff]);
+                assert_eq!(pixels[row + (extent.width/2 - 1) as usize], [0x00, 0xff, 0x00, 0xff]);
+                assert_eq!(pixels[row + (extent.width/2) as usize], [0xff, 0x00, 0x00, 0xff]);
+                assert_eq!(pixels[row + (extent.width - 1) as usize], [0xff, 0x00, 0x00, 0xff]);
+            }
+        }, 0).expect("Failed to read rendered image");
+    }
+
+    #[test]
+    fn compute_generated_vertices() {
+        const COMP_SHADER: &str = "
+#version 460
+
+layout(local_size_x = 3) in;
+
+layout(set = 0, binding = 0) buffer Vertices {
+    vec4 positions[];
+};
+
+vec2 triangle[3] = vec2[](
+    vec2( 0.5,  0.5),
+    vec2( 0.0, -0.5),
+    vec2(-0.5,  0.5)
+);
+
+void main() {
+    positions[gl_GlobalInvocationID.x] = vec4(triangle[gl_GlobalInvocationID.x], 0.0, 1.0);
+}
+";
+
+        const VERT_INPUT_SHADER: &str = "
+#version 460
+
+layout(location = 0) in vec4 position;
+
+void main() {
+    gl_Position = position;
+}
+";
+
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let format = memory::ImageFormat::R8G8B8A8_UNORM;
+
+        let extent = memory::Extent2D { width: 64, height: 64 };
+
+        let image = memory::ImageMemory::offscreen(device, format, extent).expect("Failed to allocate image");
+
+        let vertex_cfg = memory::BufferCfg {
+            size: 3*std::mem::size_of::<[f32; 4]>() as u64,
+            usage: memory::STORAGE_VERTEX,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let vertices = memory::Memory::allocate(device, &memory::MemoryCfg {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            buffers: &[&vertex_cfg]
+        }).expect("Failed to allocate vertex memory");
+
+        let readback_cfg = memory::BufferCfg {
+            size: (extent.width*extent.height) as u64*formats::block_size(format),
+            usage: memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let readback = memory::Memory::allocate(device, &memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&readback_cfg]
+        }).expect("Failed to allocate memory");
+
+        let shader_type = shader::ShaderCfg {
+            path: "OFFSCREEN",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let comp_shader = shader::Shader::from_glsl(device, &shader_type, COMP_SHADER, shader::Kind::Compute)
+            .expect("Failed to create compute shader module");
+
+        let vert_shader = shader::Shader::from_glsl(device, &shader_type, VERT_INPUT_SHADER, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader module");
+
+        let frag_shader = shader::Shader::from_glsl(device, &shader_type, FRAG_SHADER, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader module");
+
+        let compute_pipeline = compute::Pipeline::new(device, &compute::PipelineCfg {
+            buffers: &[vertices.view(0)],
+            descriptor: None,
+            shader: &comp_shader,
+            specialization: None,
+            push_constant_size: 0,
+            cache: None,
+        }).expect("Failed to create compute pipeline");
+
+        let render_pass = graphics::RenderPass::offscreen(device, format).expect("Failed to create render pass");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[image.view(0)],
+            extent,
+        };
+
+        let framebuffer = memory::Framebuffer::new(device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let pipe_type = graphics::PipelineCfg {
+            vertex_size: std::mem::size_of::<[f32; 4]>() as u32,
+            vert_input: &[graphics::VertexInputCfg {
+                location: 0,
+                binding: 0,
+                format: memory::ImageFormat::R32G32B32A32_SFLOAT,
+                offset: 0,
+            }],
+            extent,
+            render_pass: Some(&render_pass),
+            ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        let mut cmd_buffer = test_context::get_cmd_pool().allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.bind_compute_pipeline(&compute_pipeline);
+        cmd_buffer.dispatch(1, 1, 1);
+
+        cmd_buffer.set_barrier(
+            &vertices.view(0),
+            cmd::AccessType::SHADER_WRITE,
+            cmd::AccessType::VERTEX_ATTRIBUTE_READ,
+            cmd::PipelineStage::COMPUTE_SHADER,
+            cmd::PipelineStage::VERTEX_INPUT,
+            cmd::QUEUE_FAMILY_IGNORED,
+            cmd::QUEUE_FAMILY_IGNORED
+        );
+
+        cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.bind_vertex_buffers(&[vertices.vertex_view(0, 0).expect("Failed to create vertex view")]);
+        cmd_buffer.draw(3, 1, 0, 0);
+        cmd_buffer.end_render_pass();
+
+        image.view(0).set_layout(memory::ImageLayout::TRANSFER_SRC_OPTIMAL);
+
+        cmd_buffer.copy_image_to_buffer(image.view(0), readback.view(0));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let cmd_queue = queue::Queue::new(device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+        readback.access(&mut |pixels: &mut [[u8; 4]]| {
+            assert!(pixels.iter().any(|&x| x != [0x00, 0x00, 0x00, 0x00]));
+            assert_eq!(pixels[(32*extent.width + 32) as usize], [0x00, 0xff, 0x00, 0xff]);
+            assert_eq!(pixels[0], [0x00, 0x00, 0x00, 0x00]);
+        }, 0).expect("Failed to read rendered image");
+    }
+}
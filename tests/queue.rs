@@ -2,7 +2,21 @@ mod test_context;
 
 #[cfg(test)]
 mod queue {
-    use libvktypes::queue;
+    use libvktypes::{
+        dev,
+        extensions,
+        hw,
+        layers,
+        libvk,
+        memory,
+        shader,
+        compute,
+        cmd,
+        queue,
+        sync
+    };
+
+    use ash::vk;
 
     use super::test_context;
 
@@ -17,6 +31,857 @@ mod queue {
             queue_index: 0,
         };
 
-        let _ = device.get_queue(&cfg);
+        let _ = device.get_queue(&cfg).expect("Failed to get queue");
+    }
+
+    #[test]
+    fn present_result_mapping() {
+        assert_eq!(queue::PresentSuccess::from(false), queue::PresentSuccess::Optimal);
+        assert_eq!(queue::PresentSuccess::from(true), queue::PresentSuccess::Suboptimal);
+
+        assert_eq!(queue::PresentError::from(vk::Result::ERROR_OUT_OF_DATE_KHR), queue::PresentError::OutOfDate);
+        assert_eq!(queue::PresentError::from(vk::Result::ERROR_SURFACE_LOST_KHR), queue::PresentError::SurfaceLost);
+        assert_eq!(queue::PresentError::from(vk::Result::ERROR_DEVICE_LOST), queue::PresentError::Present(vk::Result::ERROR_DEVICE_LOST));
+
+        assert_eq!(queue::present_result(vk::Result::SUCCESS), Ok(queue::PresentSuccess::Optimal));
+        assert_eq!(queue::present_result(vk::Result::SUBOPTIMAL_KHR), Ok(queue::PresentSuccess::Suboptimal));
+        assert_eq!(queue::present_result(vk::Result::ERROR_OUT_OF_DATE_KHR), Err(queue::PresentError::OutOfDate));
+        assert_eq!(queue::present_result(vk::Result::ERROR_SURFACE_LOST_KHR), Err(queue::PresentError::SurfaceLost));
+    }
+
+    #[test]
+    fn async_submit() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let compute_memory = memory::BufferCfg {
+            size: 4,
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&compute_memory]
+        };
+
+        let buff = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        let shader_type = shader::ShaderCfg {
+            path: "tests/compiled_shaders/fill_memory.spv",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_file(&device, &shader_type).expect("Failed to create shader module");
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[buff.view(0)],
+            descriptor: None,
+            shader: &shader,
+            specialization: None,
+            push_constant_size: 0,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        cmd_buffer.bind_compute_pipeline(&pipeline);
+
+        cmd_buffer.dispatch(1, 1, 1);
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        let handle = queue.submit(&exec_info).expect("Failed to submit buffer");
+
+        assert!(handle.wait(u64::MAX).is_ok());
+
+        assert!(handle.is_done());
+    }
+
+    #[test]
+    fn copy_chain_single_submit() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let transfer_memory = memory::BufferCfg {
+            size: 64,
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 4
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&transfer_memory]
+        };
+
+        let data = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        data.access(&mut |bytes: &mut [u8]| {
+            bytes.fill(0x42);
+        }, 0).expect("Failed to fill the buffer");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        // Every copy reads result of the previous one so barrier is required
+        // Barrier covers all commands which are earlier in submission order
+        let copies: Vec<cmd::ExecutableBuffer> = (0..3)
+            .map(|i| {
+                let mut cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+                cmd_buffer.set_barrier(
+                    &data.view(i),
+                    cmd::AccessType::TRANSFER_WRITE,
+                    cmd::AccessType::TRANSFER_READ,
+                    cmd::PipelineStage::TRANSFER,
+                    cmd::PipelineStage::TRANSFER,
+                    cmd::QUEUE_FAMILY_IGNORED,
+                    cmd::QUEUE_FAMILY_IGNORED
+                );
+
+                cmd_buffer.copy_memory(&data.view(i), &data.view(i + 1));
+
+                cmd_buffer.commit().expect("Failed to commit command buffer")
+            })
+            .collect();
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&copies[0], &copies[1]],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        let last_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&copies[2]],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        queue.exec_batch(&[exec_info, last_info]).expect("Failed to execute batch");
+
+        data.access(&mut |bytes: &mut [u8]| {
+            assert!(bytes.iter().all(|&x| x == 0x42));
+        }, 3).expect("Failed to read the buffer");
+    }
+
+    #[test]
+    fn explicit_queue_request() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[dev::QueueRequest::single(queue.index())],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        assert_eq!(device.queue_count(queue.index()), 1);
+
+        assert!(device.get_queue(&queue::QueueCfg { family_index: queue.index(), queue_index: 0 }).is_ok());
+
+        assert!(matches!(
+            device.get_queue(&queue::QueueCfg { family_index: queue.index(), queue_index: 1 }),
+            Err(queue::QueueError::InvalidQueue { queue_index: 1, .. })
+        ));
+
+        let other_family = hw_dev.queues().find(|q| q.index() != queue.index());
+
+        if let Some(other) = other_family {
+            assert_eq!(device.queue_count(other.index()), 0);
+
+            assert!(matches!(
+                device.get_queue(&queue::QueueCfg { family_index: other.index(), queue_index: 0 }),
+                Err(queue::QueueError::InvalidQueue { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn ownership_transfer_upload() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, compute_family, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let transfer_family = hw_dev.queues().find(|q| q.is_transfer() && q.index() != compute_family.index());
+
+        // Ownership transfer requires two different families
+        let Some(transfer_family) = transfer_family else {
+            return;
+        };
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[
+                dev::QueueRequest::single(compute_family.index()),
+                dev::QueueRequest::single(transfer_family.index())
+            ],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let host_cfg = memory::BufferCfg {
+            size: 1024,
+            usage: memory::BufferUsageFlags::TRANSFER_SRC | memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[transfer_family.index(), compute_family.index()],
+            simultaneous_access: true,
+            count: 2
+        };
+
+        let host = memory::Memory::allocate(&device, &memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&host_cfg]
+        }).expect("Failed to allocate host memory");
+
+        // Exclusive to transfer family until ownership is released
+        let local_cfg = memory::BufferCfg {
+            size: 1024,
+            usage: memory::BufferUsageFlags::TRANSFER_SRC | memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[transfer_family.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let local = memory::Memory::allocate(&device, &memory::MemoryCfg {
+            properties: hw::MemoryProperty::DEVICE_LOCAL,
+            filter: &hw::any,
+            buffers: &[&local_cfg]
+        }).expect("Failed to allocate device memory");
+
+        host.view(0).access(&mut |bytes: &mut [u8]| {
+            bytes.fill(0x42);
+        }).expect("Failed to write to the host buffer");
+
+        let transfer_pool = cmd::Pool::new(&device, &cmd::PoolCfg { queue_index: transfer_family.index() })
+            .expect("Failed to allocate transfer command pool");
+
+        let upload = transfer_pool.allocate().expect("Failed to allocate command buffer");
+
+        upload.copy_memory(&host.view(0), &local.view(0));
+
+        upload.release_ownership(
+            local.view(0),
+            transfer_family.index(),
+            compute_family.index(),
+            cmd::PipelineStage::TRANSFER,
+            cmd::AccessType::TRANSFER_WRITE
+        );
+
+        let upload = upload.commit().expect("Failed to commit command buffer");
+
+        let compute_pool = cmd::Pool::new(&device, &cmd::PoolCfg { queue_index: compute_family.index() })
+            .expect("Failed to allocate compute command pool");
+
+        let readback = compute_pool.allocate().expect("Failed to allocate command buffer");
+
+        readback.acquire_ownership(
+            local.view(0),
+            transfer_family.index(),
+            compute_family.index(),
+            cmd::PipelineStage::TRANSFER,
+            cmd::AccessType::TRANSFER_READ
+        );
+
+        readback.copy_memory(&local.view(0), &host.view(1));
+
+        let readback = readback.commit().expect("Failed to commit command buffer");
+
+        let uploaded = sync::Semaphore::new(&device).expect("Failed to create semaphore");
+
+        let transfer_queue = device.get_queue(&queue::QueueCfg {
+            family_index: transfer_family.index(),
+            queue_index: 0
+        }).expect("Failed to get queue");
+
+        let compute_queue = device.get_queue(&queue::QueueCfg {
+            family_index: compute_family.index(),
+            queue_index: 0
+        }).expect("Failed to get queue");
+
+        transfer_queue.exec(&queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&upload],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[&uploaded],
+            fence: None,
+        }).expect("Failed to execute upload");
+
+        compute_queue.exec(&queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&readback],
+            timeout: u64::MAX,
+            wait: &[&uploaded],
+            signal: &[],
+            fence: None,
+        }).expect("Failed to execute readback");
+
+        host.access(&mut |bytes: &mut [u8]| {
+            assert!(bytes.iter().all(|&x| x == 0x42));
+        }, 1).expect("Failed to read the buffer");
+
+        assert_eq!(lib.error_count(), 0);
+    }
+
+    #[test]
+    fn fence_ring() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[dev::QueueRequest::single(queue.index())],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let compute_memory = memory::BufferCfg {
+            size: 4,
+            usage: memory::STORAGE,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 1
+        };
+
+        let mem_cfg = memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+            filter: &hw::any,
+            buffers: &[&compute_memory]
+        };
+
+        let buff = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+        let shader_type = shader::ShaderCfg {
+            path: "tests/compiled_shaders/fill_memory.spv",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let shader = shader::Shader::from_file(&device, &shader_type).expect("Failed to create shader module");
+
+        let pipe_type = compute::PipelineCfg {
+            buffers: &[buff.view(0)],
+            descriptor: None,
+            shader: &shader,
+            specialization: None,
+            push_constant_size: 0,
+            cache: None,
+        };
+
+        let pipeline = compute::Pipeline::new(&device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        // Buffer can not be pending twice so every slot has its own
+        let exec_buffers: Vec<cmd::ExecutableBuffer> = (0..2)
+            .map(|_| {
+                let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+                cmd_buffer.bind_compute_pipeline(&pipeline);
+
+                cmd_buffer.dispatch(1, 1, 1);
+
+                cmd_buffer.commit().expect("Failed to commit command buffer")
+            })
+            .collect();
+
+        let fences = [
+            sync::Fence::new(&device, true).expect("Failed to create fence"),
+            sync::Fence::new(&device, true).expect("Failed to create fence")
+        ];
+
+        let queue = device.get_queue(&queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        }).expect("Failed to get queue");
+
+        for i in 0..1000 {
+            let slot = i % fences.len();
+
+            fences[slot].wait(u64::MAX).expect("Failed to wait fence");
+            fences[slot].reset().expect("Failed to reset fence");
+
+            let exec_info = queue::ExecInfo {
+                wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+                buffers: &[&exec_buffers[slot]],
+                timeout: u64::MAX,
+                wait: &[],
+                signal: &[],
+                fence: None,
+            };
+
+            queue.submit_with_fence(&exec_info, &fences[slot]).expect("Failed to submit");
+        }
+
+        queue.wait_idle().expect("Failed to wait queue");
+
+        assert!(fences.iter().all(|f| f.status().expect("Failed to get fence status")));
+
+        fences[0].reset().expect("Failed to reset fence");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COMPUTE_SHADER,
+            buffers: &[&exec_buffers[0]],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: Some(&fences[0]),
+        };
+
+        queue.exec(&exec_info).expect("Failed to execute");
+
+        // External fence is signaled but not destroyed
+        assert!(fences[0].status().expect("Failed to get fence status"));
+    }
+
+    #[test]
+    fn deferred_destruction() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: true,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let cmd_queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let mut deletion_queue = dev::DeletionQueue::new();
+
+        let mut collected = 0;
+
+        // Per frame resources are dropped without waiting for the device
+        for frame in 0..100u32 {
+            let buffer_cfg = memory::BufferCfg {
+                size: 256,
+                usage: memory::FULL_TRANSFER,
+                queue_families: &[queue.index()],
+                simultaneous_access: false,
+                count: 2
+            };
+
+            let mem_cfg = memory::MemoryCfg {
+                properties: hw::MemoryProperty::HOST_VISIBLE | hw::MemoryProperty::HOST_COHERENT,
+                filter: &hw::any,
+                buffers: &[&buffer_cfg]
+            };
+
+            let frame_memory = memory::Memory::allocate(&device, &mem_cfg).expect("Failed to allocate memory");
+
+            frame_memory.access(&mut |values: &mut [u32]| {
+                values.fill(frame);
+            }, 0).expect("Failed to fill memory");
+
+            let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+            cmd_buffer.copy_memory(&frame_memory.view(0), &frame_memory.view(1));
+
+            let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+            let exec_info = queue::ExecInfo {
+                wait_stage: cmd::PipelineStage::TRANSFER,
+                buffers: &[&exec_buffer],
+                timeout: u64::MAX,
+                wait: &[],
+                signal: &[],
+                fence: None,
+            };
+
+            let handle = cmd_queue.submit(&exec_info).expect("Failed to submit buffer");
+
+            // Buffer and memory are moved into the deletion queue with the point
+            let point = unsafe { handle.into_retire_point() }
+                .expect("Submission without fence must have retire point");
+
+            deletion_queue.defer_drop(exec_buffer, &point);
+            deletion_queue.defer_drop(frame_memory, &point);
+
+            collected += deletion_queue.collect();
+        }
+
+        assert_eq!(collected + deletion_queue.len(), 200);
+
+        deletion_queue.flush().expect("Failed to wait for submissions");
+
+        assert!(deletion_queue.is_empty());
+
+        let report = device.live_objects();
+
+        assert_eq!(report.buffers, 0);
+        assert_eq!(report.fences, 0);
+
+        assert_eq!(lib.error_count(), 0);
+    }
+
+    #[test]
+    fn exec_timeout_returns_retire_point() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: false,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let cmd_pool_type = cmd::PoolCfg {
+            queue_index: queue.index(),
+        };
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd_pool_type).expect("Failed to allocate command pool");
+
+        let queue_type = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        };
+
+        let cmd_queue = queue::Queue::new(&device, &queue_type).expect("Failed to get queue");
+
+        let event = sync::Event::new(&device).expect("Failed to create event");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        // Submission can not finish until event is set from host
+        cmd_buffer.wait_events(&[&event], cmd::PipelineStage::HOST, cmd::PipelineStage::TRANSFER, &[], &[]);
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: 1_000_000,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        let point = match cmd_queue.exec(&exec_info) {
+            Err(queue::QueueError::Unfinished(point)) => point,
+            result => panic!("Expected unfinished submission, got {:?}", result)
+        };
+
+        assert!(!point.is_reached());
+
+        event.set().expect("Failed to set event");
+
+        point.wait(u64::MAX).expect("Failed to wait for submission");
+
+        assert!(point.is_reached());
+
+        assert_eq!(lib.error_count(), 0);
+    }
+
+    #[test]
+    fn unfinished_submission_keeps_buffers_alive() {
+        let lib_type = libvk::InstanceType {
+            debug_layer: Some(layers::DebugLayer::default()),
+            extensions: &[extensions::DEBUG_EXT_NAME],
+            ..libvk::InstanceType::default()
+        };
+
+        let lib = libvk::Instance::new(&lib_type).expect("Failed to load library");
+        let hw_list = hw::Description::poll(&lib, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first(
+                hw::HWDevice::is_dedicated_gpu,
+                hw::QueueFamilyDescription::is_compute,
+                |_| true
+            )
+            .expect("Failed to find suitable hardware device");
+
+        let dev_type = dev::DeviceCfg {
+            lib: &lib,
+            hw: hw_dev,
+            extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
+            allocator: None,
+            track_objects: true,
+        };
+
+        let device = dev::Device::new(&dev_type).expect("Failed to create device");
+
+        let buffer_cfg = memory::BufferCfg {
+            size: 1024,
+            usage: memory::BufferUsageFlags::TRANSFER_SRC | memory::BufferUsageFlags::TRANSFER_DST,
+            queue_families: &[queue.index()],
+            simultaneous_access: false,
+            count: 2
+        };
+
+        let data = memory::Memory::allocate(&device, &memory::MemoryCfg {
+            properties: hw::MemoryProperty::HOST_VISIBLE,
+            filter: &hw::any,
+            buffers: &[&buffer_cfg]
+        }).expect("Failed to allocate memory");
+
+        let allocated = device.live_objects().buffers;
+
+        let cmd_pool = cmd::Pool::new(&device, &cmd::PoolCfg { queue_index: queue.index() })
+            .expect("Failed to allocate command pool");
+
+        let cmd_queue = queue::Queue::new(&device, &queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0,
+        }).expect("Failed to get queue");
+
+        let event = sync::Event::new(&device).expect("Failed to create event");
+
+        let cmd_buffer = cmd_pool.allocate().expect("Failed to allocate command buffer");
+
+        // Submission can not finish until event is set from host
+        cmd_buffer.wait_events(&[&event], cmd::PipelineStage::HOST, cmd::PipelineStage::TRANSFER, &[], &[]);
+        cmd_buffer.copy_memory(&data.view(0), &data.view(1));
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::TRANSFER,
+            buffers: &[&exec_buffer],
+            timeout: 1_000_000,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        let point = match cmd_queue.exec(&exec_info) {
+            Err(queue::QueueError::Unfinished(point)) => point,
+            result => panic!("Expected unfinished submission, got {:?}", result)
+        };
+
+        drop(exec_buffer);
+        drop(data);
+
+        // Command buffer and memory used by it are released only with the point
+        assert_eq!(device.live_objects().buffers, allocated);
+
+        event.set().expect("Failed to set event");
+
+        drop(point);
+
+        assert_eq!(device.live_objects().buffers, 0);
+
+        assert_eq!(lib.error_count(), 0);
     }
-}
\ No newline at end of file
+}
@@ -34,6 +34,7 @@ mod render_pass {
         let attachment = [
             graphics::AttachmentInfo {
                 format: cfg.formats().next().expect("No available formats").format,
+                samples: memory::SampleCount::TYPE_1,
                 load_op: graphics::AttachmentLoadOp::CLEAR,
                 store_op: graphics::AttachmentStoreOp::STORE,
                 stencil_load_op: graphics::AttachmentLoadOp::DONT_CARE,
@@ -58,4 +59,235 @@ mod render_pass {
 
         assert!(graphics::RenderPass::new(dev, &rp_cfg).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn msaa_render_pass() {
+        let dev = test_context::get_graphics_device();
+
+        let cfg = test_context::get_surface_capabilities();
+
+        let samples = test_context::get_graphics_hw().max_color_samples();
+
+        assert!(samples >= memory::SampleCount::TYPE_1);
+
+        let rp = graphics::RenderPass::with_msaa(
+            dev,
+            cfg.formats().next().expect("No available formats").format,
+            memory::ImageFormat::D32_SFLOAT,
+            samples.min(test_context::get_graphics_hw().max_depth_samples())
+        );
+
+        assert_eq!(rp.expect("Failed to create render pass").color_attachment_count(0), Some(1));
+    }
+
+    #[test]
+    fn deferred_builder() {
+        let dev = test_context::get_graphics_device();
+
+        let cfg = test_context::get_surface_capabilities();
+
+        let surf_format = cfg.formats().next().expect("No available formats").format;
+
+        let builder = graphics::RenderPassBuilder::new()
+            .color_attachment(surf_format, graphics::AttachmentLoadOp::CLEAR,
+                graphics::AttachmentStoreOp::STORE, memory::ImageLayout::PRESENT_SRC_KHR)
+            .color_attachment(memory::ImageFormat::R8G8B8A8_UNORM, graphics::AttachmentLoadOp::CLEAR,
+                graphics::AttachmentStoreOp::DONT_CARE, memory::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .color_attachment(memory::ImageFormat::R16G16B16A16_SFLOAT, graphics::AttachmentLoadOp::CLEAR,
+                graphics::AttachmentStoreOp::DONT_CARE, memory::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .depth_attachment(memory::ImageFormat::D32_SFLOAT)
+            .subpass(&[1, 2], Some(3), &[])
+            .subpass(&[0], None, &[1, 2]);
+
+        let gbuffer = |format| graphics::AttachmentInfo {
+            format: format,
+            samples: memory::SampleCount::TYPE_1,
+            load_op: graphics::AttachmentLoadOp::CLEAR,
+            store_op: graphics::AttachmentStoreOp::DONT_CARE,
+            stencil_load_op: graphics::AttachmentLoadOp::DONT_CARE,
+            stencil_store_op: graphics::AttachmentStoreOp::DONT_CARE,
+            initial_layout: memory::ImageLayout::UNDEFINED,
+            final_layout: memory::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        };
+
+        let attachments = [
+            graphics::AttachmentInfo {
+                format: surf_format,
+                store_op: graphics::AttachmentStoreOp::STORE,
+                final_layout: memory::ImageLayout::PRESENT_SRC_KHR,
+                ..gbuffer(surf_format)
+            },
+            gbuffer(memory::ImageFormat::R8G8B8A8_UNORM),
+            gbuffer(memory::ImageFormat::R16G16B16A16_SFLOAT),
+            graphics::AttachmentInfo {
+                format: memory::ImageFormat::D32_SFLOAT,
+                final_layout: memory::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                ..gbuffer(memory::ImageFormat::D32_SFLOAT)
+            },
+        ];
+
+        let subpasses = [
+            graphics::SubpassInfo {
+                color_attachments: &[1, 2],
+                depth_stencil_attachment: 3,
+                ..Default::default()
+            },
+            graphics::SubpassInfo {
+                input_attachments: &[1, 2],
+                color_attachments: &[0],
+                ..Default::default()
+            }
+        ];
+
+        assert_eq!(builder.attachments(), &attachments);
+        assert_eq!(builder.subpasses(), &subpasses);
+
+        let deps = builder.dependencies();
+
+        assert_eq!(deps.len(), 3);
+        assert_eq!((deps[0].src_subpass, deps[0].dst_subpass), (graphics::SUBPASS_EXTERNAL, 0));
+        assert_eq!((deps[1].src_subpass, deps[1].dst_subpass), (0, 1));
+        assert_eq!((deps[2].src_subpass, deps[2].dst_subpass), (1, graphics::SUBPASS_EXTERNAL));
+
+        let rp_cfg = graphics::RenderPassCfg {
+            attachments: &attachments,
+            sync_info: &deps,
+            subpasses: &subpasses,
+        };
+
+        assert!(graphics::RenderPass::new(dev, &rp_cfg).is_ok());
+
+        let rp = builder.build(dev).expect("Failed to create render pass");
+
+        assert_eq!(rp.color_attachment_count(0), Some(2));
+        assert_eq!(rp.color_attachment_count(1), Some(1));
+    }
+
+    #[test]
+    fn render_pass_desc() {
+        let dev = test_context::get_graphics_device();
+
+        let color = graphics::AttachmentDesc {
+            format: "R8G8B8A8_UNORM".to_string(),
+            load_op: "CLEAR".to_string(),
+            store_op: "STORE".to_string(),
+            initial_layout: "UNDEFINED".to_string(),
+            final_layout: "TRANSFER_SRC_OPTIMAL".to_string(),
+            ..graphics::AttachmentDesc::default()
+        };
+
+        let depth = graphics::AttachmentDesc {
+            format: "D32_SFLOAT".to_string(),
+            load_op: "CLEAR".to_string(),
+            initial_layout: "UNDEFINED".to_string(),
+            final_layout: "DEPTH_STENCIL_ATTACHMENT_OPTIMAL".to_string(),
+            ..graphics::AttachmentDesc::default()
+        };
+
+        let desc = graphics::RenderPassDesc {
+            attachments: vec![color, depth],
+            subpasses: vec![graphics::SubpassDesc { colors: vec![0], depth: Some(1), inputs: vec![] }],
+        };
+
+        let expected = graphics::RenderPassBuilder::new()
+            .attachment(graphics::AttachmentInfo {
+                format: memory::ImageFormat::R8G8B8A8_UNORM,
+                load_op: graphics::AttachmentLoadOp::CLEAR,
+                store_op: graphics::AttachmentStoreOp::STORE,
+                initial_layout: memory::ImageLayout::UNDEFINED,
+                final_layout: memory::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                ..Default::default()
+            })
+            .attachment(graphics::AttachmentInfo {
+                format: memory::ImageFormat::D32_SFLOAT,
+                load_op: graphics::AttachmentLoadOp::CLEAR,
+                initial_layout: memory::ImageLayout::UNDEFINED,
+                final_layout: memory::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                ..Default::default()
+            })
+            .subpass(&[0], Some(1), &[]);
+
+        let builder = desc.builder().expect("Failed to convert description");
+
+        assert_eq!(builder.attachments(), expected.attachments());
+        assert_eq!(builder.subpasses(), expected.subpasses());
+
+        let rp = graphics::RenderPass::from_desc(dev, &desc).expect("Failed to create render pass");
+
+        assert_eq!(rp.color_attachment_count(0), Some(1));
+
+        let unknown = graphics::RenderPassDesc {
+            attachments: vec![graphics::AttachmentDesc { format: "RGBA8".to_string(), ..Default::default() }],
+            subpasses: vec![],
+        };
+
+        assert!(matches!(
+            graphics::RenderPass::from_desc(dev, &unknown),
+            Err(graphics::DescError::UnknownName { kind: "format", .. })
+        ));
+    }
+
+    #[test]
+    fn render_pass_display() {
+        let dev = test_context::get_graphics_device();
+
+        let rp = graphics::RenderPass::offscreen(dev, memory::ImageFormat::R8G8B8A8_UNORM)
+            .expect("Failed to create render pass");
+
+        assert_eq!(rp.subpass_count(), 1);
+
+        assert_eq!(
+            rp.to_string(),
+            "Render pass\n\
+            Attachments: 1\n  \
+              0: R8G8B8A8_UNORM\n\
+            Subpasses:   1\n  \
+              0: 1 color attachment(s)\n"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn render_pass_desc_serde_round_trip() {
+        let desc = graphics::RenderPassDesc {
+            attachments: vec![
+                graphics::AttachmentDesc {
+                    format: "R8G8B8A8_UNORM".to_string(),
+                    load_op: "CLEAR".to_string(),
+                    store_op: "STORE".to_string(),
+                    initial_layout: "UNDEFINED".to_string(),
+                    final_layout: "TRANSFER_SRC_OPTIMAL".to_string(),
+                    ..graphics::AttachmentDesc::default()
+                },
+                graphics::AttachmentDesc {
+                    format: "D32_SFLOAT".to_string(),
+                    load_op: "CLEAR".to_string(),
+                    initial_layout: "UNDEFINED".to_string(),
+                    final_layout: "DEPTH_STENCIL_ATTACHMENT_OPTIMAL".to_string(),
+                    ..graphics::AttachmentDesc::default()
+                },
+            ],
+            subpasses: vec![graphics::SubpassDesc { colors: vec![0], depth: Some(1), inputs: vec![] }],
+        };
+
+        let json = serde_json::to_string(&desc).expect("Failed to serialize to JSON");
+
+        assert_eq!(serde_json::from_str::<graphics::RenderPassDesc>(&json).expect("Failed to deserialize JSON"), desc);
+
+        let ron = ron::to_string(&desc).expect("Failed to serialize to RON");
+
+        assert_eq!(ron::from_str::<graphics::RenderPassDesc>(&ron).expect("Failed to deserialize RON"), desc);
+
+        let sampler = graphics::SamplerDesc {
+            address_mode_u: "CLAMP_TO_EDGE".to_string(),
+            anisotropy_enable: true,
+            max_anisotropy: 4.0,
+            max_lod: 8.0,
+            ..graphics::SamplerDesc::default()
+        };
+
+        let ron = ron::to_string(&sampler).expect("Failed to serialize to RON");
+
+        assert_eq!(ron::from_str::<graphics::SamplerDesc>(&ron).expect("Failed to deserialize RON"), sampler);
+    }
+}
@@ -9,6 +9,7 @@ mod shader {
         layers,
         libvk,
         shader,
+        graphics,
     };
 
     use super::test_context;
@@ -36,7 +37,11 @@ mod shader {
             lib: &lib,
             hw: hw_dev,
             extensions: &[],
+            queues: &[],
+            features: hw::Features::default(),
+            dynamic_rendering: false,
             allocator: None,
+            track_objects: false,
         };
 
         let device = dev::Device::new(&dev_type).expect("Failed to create device");
@@ -44,6 +49,7 @@ mod shader {
         let shader_type = shader::ShaderCfg {
             path: "tests/compiled_shaders/fill_memory.spv",
             entry: "main",
+            include_dirs: &[],
         };
 
         assert!(shader::Shader::from_file(&device, &shader_type).is_ok());
@@ -56,8 +62,332 @@ mod shader {
         let shader_type = shader::ShaderCfg {
             path: "tests/shaders/single_dot.vert",
             entry: "main",
+            include_dirs: &[],
         };
 
         assert!(shader::Shader::from_glsl_file(&device, &shader_type, shader::Kind::Vertex).is_ok());
     }
-}
\ No newline at end of file
+
+    const REFLECT_VERT: &str = "
+#version 460
+
+layout(location = 0) in vec4 position;
+
+layout(set = 0, binding = 0) uniform Transform {
+    mat4 mvp;
+};
+
+layout(set = 1, binding = 2) buffer Offsets {
+    vec4 offsets[];
+};
+
+void main() {
+    gl_Position = mvp*(position + offsets[gl_InstanceIndex]);
+}
+";
+
+    const REFLECT_FRAG: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+layout(set = 0, binding = 0) uniform Transform {
+    mat4 mvp;
+};
+
+layout(set = 0, binding = 1) uniform sampler2D textures[4];
+
+void main() {
+    color = mvp*texture(textures[0], vec2(0.0));
+}
+";
+
+    const MISMATCH_FRAG: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+layout(set = 0, binding = 0) uniform sampler2D tex;
+
+void main() {
+    color = texture(tex, vec2(0.0));
+}
+";
+
+    #[test]
+    fn reflect_bindings() {
+        let device = test_context::get_graphics_device();
+
+        let cfg = shader::ShaderCfg {
+            path: "REFLECT",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert = shader::Shader::from_glsl(device, &cfg, REFLECT_VERT, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader");
+
+        let frag = shader::Shader::from_glsl(device, &cfg, REFLECT_FRAG, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader");
+
+        assert_eq!(vert.bindings(), vec![
+            shader::ReflectedBinding {
+                set: 0,
+                binding: 0,
+                resource_type: graphics::DescriptorType::UNIFORM_BUFFER,
+                count: 1,
+                stage: graphics::ShaderStage::VERTEX
+            },
+            shader::ReflectedBinding {
+                set: 1,
+                binding: 2,
+                resource_type: graphics::DescriptorType::STORAGE_BUFFER,
+                count: 1,
+                stage: graphics::ShaderStage::VERTEX
+            }
+        ]);
+
+        assert_eq!(frag.bindings()[1], shader::ReflectedBinding {
+            set: 0,
+            binding: 1,
+            resource_type: graphics::DescriptorType::COMBINED_IMAGE_SAMPLER,
+            count: 4,
+            stage: graphics::ShaderStage::FRAGMENT
+        });
+
+        let desc = graphics::PipelineDescriptor::from_shaders(device, &[&vert, &frag])
+            .expect("Failed to allocate descriptor");
+
+        assert!(!desc.is_empty());
+
+        let mismatch = shader::Shader::from_glsl(device, &cfg, MISMATCH_FRAG, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader");
+
+        assert!(matches!(
+            graphics::PipelineDescriptor::from_shaders(device, &[&vert, &mismatch]),
+            Err(graphics::PipelineDescriptorError::StageMismatch { set: 0, binding: 0 })
+        ));
+    }
+
+    const BROKEN_FRAG: &str = "#version 460
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = undeclared_value;
+}
+";
+
+    const VARIANT_FRAG: &str = "#version 460
+#extension GL_GOOGLE_include_directive : require
+
+#include <color.glsl>
+
+layout(location = 0) out vec4 color;
+
+void main() {
+#ifdef INVERT
+    color = vec4(1.0) - base_color();
+#else
+    color = base_color();
+#endif
+}
+";
+
+    #[test]
+    fn compile_diagnostics() {
+        let device = test_context::get_graphics_device();
+
+        let cfg = shader::ShaderCfg {
+            path: "broken.frag",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        match shader::Shader::from_glsl(device, &cfg, BROKEN_FRAG, shader::Kind::Fragment) {
+            Err(shader::ShaderError::Compile { log, file, line }) => {
+                assert!(log.contains("undeclared_value"));
+                assert_eq!(file, "broken.frag");
+                assert_eq!(line, Some(6));
+            },
+            _ => panic!("Shader must fail to compile")
+        }
+
+        let cfg = shader::ShaderCfg {
+            path: "variant.frag",
+            entry: "main",
+            include_dirs: &["tests/shaders/include"],
+        };
+
+        assert!(
+            shader::Shader::from_glsl_with_defines(
+                device,
+                &cfg,
+                VARIANT_FRAG,
+                shader::Kind::Fragment,
+                &[("COLOR_R", "0.5")]
+            ).is_ok()
+        );
+
+        assert!(
+            shader::Shader::from_glsl_with_defines(
+                device,
+                &cfg,
+                VARIANT_FRAG,
+                shader::Kind::Fragment,
+                &[("COLOR_R", "0.5"), ("INVERT", "")]
+            ).is_ok()
+        );
+
+        // COLOR_R is not defined
+        assert!(matches!(
+            shader::Shader::from_glsl(device, &cfg, VARIANT_FRAG, shader::Kind::Fragment),
+            Err(shader::ShaderError::Compile { .. })
+        ));
+    }
+
+    #[test]
+    fn reload_shader() {
+        let device = test_context::get_graphics_device();
+
+        let cfg = shader::ShaderCfg {
+            path: "RELOAD",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let mut shader = shader::Shader::from_glsl(device, &cfg, MISMATCH_FRAG, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader");
+
+        let module = shader.module();
+
+        assert!(shader.reload_from_glsl(BROKEN_FRAG).is_err());
+        assert_eq!(shader.module(), module);
+        assert_eq!(shader.retired_count(), 0);
+
+        shader.reload_from_glsl(REFLECT_FRAG).expect("Failed to reload shader");
+
+        assert_ne!(shader.module(), module);
+        assert_eq!(shader.retired_count(), 1);
+        assert_eq!(shader.bindings().len(), 2);
+
+        shader.release_retired();
+
+        assert_eq!(shader.retired_count(), 0);
+
+        let spv_cfg = shader::ShaderCfg {
+            path: "tests/compiled_shaders/fill_memory.spv",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let mut spv_shader = shader::Shader::from_file(device, &spv_cfg).expect("Failed to create shader");
+
+        assert!(matches!(spv_shader.reload_from_glsl(REFLECT_FRAG), Err(shader::ShaderError::UnknownKind)));
+
+        spv_shader.reload_from_file().expect("Failed to reload shader");
+
+        assert_eq!(spv_shader.retired_count(), 1);
+    }
+
+    #[test]
+    fn from_bytes() {
+        let device = test_context::get_graphics_device();
+
+        let cfg = shader::ShaderCfg {
+            path: "tests/compiled_shaders/fill_memory.spv",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let bytes = std::fs::read(cfg.path).expect("Failed to read shader file");
+
+        assert!(shader::Shader::from_bytes(device, &cfg, &bytes).is_ok());
+
+        assert!(matches!(
+            shader::Shader::from_bytes(device, &cfg, &bytes[..bytes.len() - 1]),
+            Err(shader::ShaderError::BytecodeSize)
+        ));
+
+        assert!(matches!(
+            shader::Shader::from_bytes(device, &cfg, &[]),
+            Err(shader::ShaderError::BytecodeSize)
+        ));
+
+        // Header and the first word of OpCapability instruction
+        assert!(matches!(
+            shader::Shader::from_bytes(device, &cfg, &bytes[..24]),
+            Err(shader::ShaderError::Reflection)
+        ));
+
+        // Big endian module is the same module
+        let swapped: Vec<u8> = bytes
+            .chunks_exact(4)
+            .flat_map(|x| [x[3], x[2], x[1], x[0]])
+            .collect();
+
+        let swapped_shader = shader::Shader::from_bytes(device, &cfg, &swapped).expect("Failed to load swapped bytecode");
+
+        assert_eq!(swapped_shader.entry().to_str(), Ok("main"));
+
+        let mut corrupted = bytes.clone();
+        corrupted[1] ^= 0xff;
+
+        assert!(matches!(
+            shader::Shader::from_bytes(device, &cfg, &corrupted),
+            Err(shader::ShaderError::BytecodeMagic)
+        ));
+
+        let wrong_entry = shader::ShaderCfg {
+            entry: "fill",
+            ..cfg
+        };
+
+        match shader::Shader::from_bytes(device, &wrong_entry, &bytes) {
+            Err(shader::ShaderError::NoSuchEntryPoint { entry, available }) => {
+                assert_eq!(entry, "fill");
+                assert_eq!(available, vec![String::from("main")]);
+            },
+            _ => panic!("Entry point must be validated")
+        }
+    }
+
+    #[test]
+    fn reflect_spec_constant_array() {
+        const FRAG: &str = "
+#version 460
+
+layout(constant_id = 0) const uint TEXTURE_COUNT = 3;
+
+layout(set = 0, binding = 0) uniform sampler2D textures[TEXTURE_COUNT];
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = texture(textures[0], vec2(0.0));
+}
+";
+
+        let device = test_context::get_graphics_device();
+
+        let cfg = shader::ShaderCfg {
+            path: "SPEC_ARRAY",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let frag = shader::Shader::from_glsl(device, &cfg, FRAG, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader");
+
+        // Default value of the constant is used
+        assert_eq!(frag.bindings(), vec![
+            shader::ReflectedBinding {
+                set: 0,
+                binding: 0,
+                resource_type: graphics::DescriptorType::COMBINED_IMAGE_SAMPLER,
+                count: 3,
+                stage: graphics::ShaderStage::FRAGMENT
+            }
+        ]);
+    }
+}
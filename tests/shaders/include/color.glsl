@@ -0,0 +1,3 @@
+vec4 base_color() {
+    return vec4(COLOR_R, 0.0, 0.0, 1.0);
+}
@@ -2,7 +2,9 @@ mod test_context;
 
 #[cfg(test)]
 mod surface {
-    use libvktypes::{hw, surface};
+    use libvktypes::{hw, memory, surface, swapchain};
+
+    use ash::vk;
 
     use super::test_context;
 
@@ -35,4 +37,117 @@ mod surface {
 
         assert!(surface::Capabilities::get(&hw_dev, &surface).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn supported_usage_flags() {
+        let capabilities = test_context::get_surface_capabilities();
+
+        let flags = capabilities.supported_usage_flags();
+
+        assert!(flags.contains(memory::UsageFlags::COLOR_ATTACHMENT));
+        assert!(capabilities.is_flags_supported(flags));
+    }
+
+    #[test]
+    fn clamp_extent() {
+        let raw = vk::SurfaceCapabilitiesKHR {
+            current_extent: memory::Extent2D { width: u32::MAX, height: u32::MAX },
+            min_image_extent: memory::Extent2D { width: 16, height: 32 },
+            max_image_extent: memory::Extent2D { width: 1024, height: 768 },
+            ..Default::default()
+        };
+
+        let capabilities = surface::Capabilities::from_raw(raw, Vec::new(), Vec::new());
+
+        assert_eq!(
+            capabilities.clamp_extent(memory::Extent2D { width: 800, height: 600 }),
+            memory::Extent2D { width: 800, height: 600 }
+        );
+
+        assert_eq!(
+            capabilities.clamp_extent(memory::Extent2D { width: 0, height: 4096 }),
+            memory::Extent2D { width: 16, height: 768 }
+        );
+
+        let raw = vk::SurfaceCapabilitiesKHR {
+            current_extent: memory::Extent2D { width: 640, height: 480 },
+            ..raw
+        };
+
+        let capabilities = surface::Capabilities::from_raw(raw, Vec::new(), Vec::new());
+
+        assert_eq!(
+            capabilities.clamp_extent(memory::Extent2D { width: 800, height: 600 }),
+            memory::Extent2D { width: 640, height: 480 }
+        );
+    }
+
+    #[test]
+    fn refresh_capabilities() {
+        let hw = test_context::get_graphics_hw();
+
+        let surface_ref = test_context::get_surface();
+
+        let mut capabilities = surface::Capabilities::get(hw, surface_ref).expect("Failed to get capabilities");
+
+        assert!(capabilities.refresh(hw, surface_ref).is_ok());
+
+        let extent = capabilities.clamp_extent(capabilities.max_extent());
+
+        assert!(extent.width >= capabilities.min_extent().width);
+        assert!(extent.height >= capabilities.min_extent().height);
+    }
+
+    #[test]
+    fn choose_present_mode() {
+        let capabilities = surface::Capabilities::from_raw(
+            vk::SurfaceCapabilitiesKHR::default(),
+            Vec::new(),
+            vec![swapchain::PresentMode::FIFO, swapchain::PresentMode::IMMEDIATE]
+        );
+
+        assert_eq!(
+            capabilities.choose_present_mode(&[swapchain::PresentMode::MAILBOX, swapchain::PresentMode::IMMEDIATE]),
+            swapchain::PresentMode::IMMEDIATE
+        );
+
+        assert_eq!(
+            capabilities.choose_present_mode(&[swapchain::PresentMode::MAILBOX]),
+            swapchain::PresentMode::FIFO
+        );
+
+        assert_eq!(capabilities.choose_present_mode(&[]), swapchain::PresentMode::FIFO);
+    }
+
+    #[test]
+    fn choose_format() {
+        let unorm = surface::SurfaceFormat {
+            format: memory::ImageFormat::B8G8R8A8_UNORM,
+            color_space: memory::ColorSpace::SRGB_NONLINEAR,
+        };
+
+        let srgb = surface::SurfaceFormat {
+            format: memory::ImageFormat::B8G8R8A8_SRGB,
+            color_space: memory::ColorSpace::SRGB_NONLINEAR,
+        };
+
+        let capabilities = surface::Capabilities::from_raw(
+            vk::SurfaceCapabilitiesKHR::default(),
+            vec![unorm, srgb],
+            Vec::new()
+        );
+
+        assert_eq!(
+            capabilities.choose_format(&[
+                (memory::ImageFormat::R8G8B8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR),
+                (memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::SRGB_NONLINEAR)
+            ]),
+            srgb
+        );
+
+        assert_eq!(
+            capabilities.choose_format(&[(memory::ImageFormat::B8G8R8A8_SRGB, memory::ColorSpace::DISPLAY_P3_NONLINEAR_EXT)]),
+            unorm
+        );
+    }
+}
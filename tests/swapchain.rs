@@ -2,7 +2,11 @@ mod test_context;
 
 #[cfg(test)]
 mod swapchain {
-    use libvktypes::{surface, swapchain, memory};
+    use libvktypes::{surface, swapchain, memory, hw, queue, sync, graphics, shader, cmd};
+
+    use ash::vk;
+
+    use std::time::Duration;
 
     use super::test_context;
 
@@ -50,4 +54,473 @@ mod swapchain {
 
         assert!(swapchain::Swapchain::new(lib_ref, device, surface_ref, &swp_type).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn recreate() {
+        let lib_ref = test_context::get_graphics_instance();
+
+        let surface_ref = test_context::get_surface();
+
+        let device = test_context::get_graphics_device();
+
+        let _ = test_context::get_present_queue();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let swp_type = swapchain::SwapchainCfg {
+            num_of_images: 3,
+            format: memory::ImageFormat::B8G8R8A8_UNORM,
+            color: memory::ColorSpace::SRGB_NONLINEAR,
+            present_mode: swapchain::PresentMode::FIFO,
+            flags: memory::UsageFlags::COLOR_ATTACHMENT,
+            extent: capabilities.extent2d(),
+            transform: capabilities.pre_transformation(),
+            alpha: capabilities.alpha_composition(),
+        };
+
+        let mut swapchain = swapchain::Swapchain::new(lib_ref, device, surface_ref, &swp_type)
+            .expect("Failed to create swapchain");
+
+        let image_count = swapchain.image_count();
+
+        // Repeated calls return the same images instead of new wrappers
+        let first = swapchain.images();
+        let second = swapchain.images();
+
+        assert_eq!(first.len(), image_count as usize);
+        assert_eq!(first.len(), second.len());
+
+        for (lhs, rhs) in first.iter().zip(second) {
+            assert!(lhs.view(0) == rhs.view(0));
+        }
+
+        let old_handle = swapchain.swapchain();
+
+        // Framebuffer keeps view of the previous image alive
+        let render_pass = graphics::RenderPass::single_subpass(device, swapchain.format())
+            .expect("Failed to create render pass");
+
+        let old_framebuffer = memory::Framebuffer::new(device, &memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[swapchain.images()[0].view(0)],
+            extent: swapchain.extent()
+        }).expect("Failed to create framebuffer");
+
+        swapchain.recreate(device, surface_ref, capabilities.extent2d()).expect("Failed to recreate swapchain");
+
+        assert!(swapchain.swapchain() != old_handle);
+        assert_eq!(swapchain.images().len(), swapchain.image_count() as usize);
+
+        // Previous swapchain is destroyed after its last image
+        drop(swapchain);
+        drop(old_framebuffer);
+
+        assert_eq!(lib_ref.error_count(), 0);
+    }
+
+    #[test]
+    fn next_image_result_mapping() {
+        assert!(matches!(
+            swapchain::SwapchainError::from(vk::Result::ERROR_OUT_OF_DATE_KHR),
+            swapchain::SwapchainError::OutOfDate
+        ));
+
+        assert!(matches!(
+            swapchain::SwapchainError::from(vk::Result::ERROR_DEVICE_LOST),
+            swapchain::SwapchainError::NextImage(vk::Result::ERROR_DEVICE_LOST)
+        ));
+
+        assert!(matches!(
+            swapchain::SwapchainError::from(vk::Result::TIMEOUT),
+            swapchain::SwapchainError::Timeout
+        ));
+
+        assert!(matches!(
+            swapchain::SwapchainError::from(vk::Result::NOT_READY),
+            swapchain::SwapchainError::NotReady
+        ));
+    }
+
+    #[test]
+    fn next_image_zero_timeout() {
+        let device = test_context::get_graphics_device();
+
+        let swapchain = test_context::get_swapchain();
+
+        assert!(matches!(
+            swapchain.next_image(Duration::ZERO, swapchain::AcquireSync::None),
+            Err(swapchain::SwapchainError::NoSync)
+        ));
+
+        // Images are never presented back so eventually none of them is available
+        let mut fences = Vec::new();
+
+        let result = loop {
+            let fence = sync::Fence::new(device, false).expect("Failed to create fence");
+
+            let result = swapchain.next_image(Duration::ZERO, swapchain::AcquireSync::Fence(&fence));
+
+            fences.push(fence);
+
+            match result {
+                Ok(image) => assert!(image.index < swapchain.image_count()),
+                Err(err) => break err
+            }
+
+            assert!(fences.len() <= swapchain.image_count() as usize);
+        };
+
+        assert!(matches!(
+            result,
+            swapchain::SwapchainError::NotReady | swapchain::SwapchainError::Timeout
+        ));
+
+        for fence in &fences[..fences.len() - 1] {
+            fence.wait(u64::MAX).expect("Failed to wait fence");
+        }
+    }
+
+    #[test]
+    fn select_queue_for_surfaces() {
+        let lib_ref = test_context::get_graphics_instance();
+
+        let surface_ref = test_context::get_surface();
+
+        // Support is queried explicitly so surface is not needed for polling
+        let hw_list = hw::Description::poll(lib_ref, None).expect("Failed to list hardware");
+
+        let (hw_dev, queue, _) = hw_list
+            .find_first_with_surfaces(hw::any, hw::QueueFamilyDescription::is_graphics, hw::any, &[surface_ref])
+            .expect("Failed to find queue with presentation support");
+
+        assert!(queue.support_surface(hw_dev, surface_ref));
+        assert!(queue.support_surfaces(hw_dev, &[surface_ref, surface_ref]));
+        assert!(queue.support_surfaces(hw_dev, &[]));
+
+        let device = test_context::get_graphics_device();
+
+        let queue_cfg = queue::QueueCfg {
+            family_index: test_context::get_graphics_queue().index(),
+            queue_index: 0
+        };
+
+        let cmd_queue = queue::Queue::new(device, &queue_cfg).expect("Failed to get queue");
+
+        let present_info = queue::MultiPresentInfo {
+            images: &[],
+            wait: &[]
+        };
+
+        assert!(cmd_queue.present_multiple(&present_info).is_empty());
+    }
+
+    #[test]
+    fn srgb_cfg() {
+        let raw = vk::SurfaceCapabilitiesKHR {
+            min_image_count: 2,
+            current_extent: memory::Extent2D { width: 640, height: 480 },
+            supported_composite_alpha: memory::CompositeAlphaFlags::OPAQUE,
+            ..Default::default()
+        };
+
+        let unorm = surface::SurfaceFormat {
+            format: memory::ImageFormat::B8G8R8A8_UNORM,
+            color_space: memory::ColorSpace::SRGB_NONLINEAR,
+        };
+
+        let srgb = surface::SurfaceFormat {
+            format: memory::ImageFormat::B8G8R8A8_SRGB,
+            color_space: memory::ColorSpace::SRGB_NONLINEAR,
+        };
+
+        let capabilities = surface::Capabilities::from_raw(raw, vec![unorm, srgb], vec![swapchain::PresentMode::FIFO]);
+
+        let cfg = swapchain::SwapchainCfg::srgb(&capabilities).expect("Failed to select sRGB format");
+
+        assert_eq!(cfg.format, memory::ImageFormat::B8G8R8A8_SRGB);
+        assert_eq!(cfg.color, memory::ColorSpace::SRGB_NONLINEAR);
+        assert_eq!(cfg.num_of_images, 2);
+        assert_eq!(cfg.extent, memory::Extent2D { width: 640, height: 480 });
+
+        let capabilities = surface::Capabilities::from_raw(raw, vec![unorm], vec![swapchain::PresentMode::FIFO]);
+
+        assert!(matches!(
+            swapchain::SwapchainCfg::srgb(&capabilities),
+            Err(swapchain::SwapchainError::NoSrgbFormat)
+        ));
+    }
+
+    #[test]
+    fn clamp_img_count() {
+        let raw = vk::SurfaceCapabilitiesKHR {
+            min_image_count: 2,
+            max_image_count: 4,
+            ..Default::default()
+        };
+
+        let capabilities = surface::Capabilities::from_raw(raw, Vec::new(), Vec::new());
+
+        assert_eq!(capabilities.clamp_img_count(0), 2);
+        assert_eq!(capabilities.clamp_img_count(3), 3);
+        assert_eq!(capabilities.clamp_img_count(16), 4);
+
+        // Zero means there is no limit
+        let raw = vk::SurfaceCapabilitiesKHR {
+            max_image_count: 0,
+            ..raw
+        };
+
+        let capabilities = surface::Capabilities::from_raw(raw, Vec::new(), Vec::new());
+
+        assert_eq!(capabilities.clamp_img_count(16), 16);
+    }
+
+    #[test]
+    fn zero_extent() {
+        let raw = vk::SurfaceCapabilitiesKHR {
+            current_extent: memory::Extent2D { width: 0, height: 0 },
+            ..Default::default()
+        };
+
+        assert!(surface::Capabilities::from_raw(raw, Vec::new(), Vec::new()).is_zero_extent());
+
+        let raw = vk::SurfaceCapabilitiesKHR {
+            current_extent: memory::Extent2D { width: 640, height: 0 },
+            ..raw
+        };
+
+        assert!(surface::Capabilities::from_raw(raw, Vec::new(), Vec::new()).is_zero_extent());
+
+        let raw = vk::SurfaceCapabilitiesKHR {
+            current_extent: memory::Extent2D { width: 640, height: 480 },
+            ..raw
+        };
+
+        assert!(!surface::Capabilities::from_raw(raw, Vec::new(), Vec::new()).is_zero_extent());
+
+        // Extent is defined by swapchain
+        let raw = vk::SurfaceCapabilitiesKHR {
+            current_extent: memory::Extent2D { width: u32::MAX, height: u32::MAX },
+            ..raw
+        };
+
+        assert!(!surface::Capabilities::from_raw(raw, Vec::new(), Vec::new()).is_zero_extent());
+    }
+
+    #[test]
+    fn zero_extent_swapchain() {
+        let lib_ref = test_context::get_graphics_instance();
+
+        let surface_ref = test_context::get_surface();
+
+        let device = test_context::get_graphics_device();
+
+        let _ = test_context::get_present_queue();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let swp_type = swapchain::SwapchainCfg {
+            num_of_images: 3,
+            format: memory::ImageFormat::B8G8R8A8_UNORM,
+            color: memory::ColorSpace::SRGB_NONLINEAR,
+            present_mode: swapchain::PresentMode::FIFO,
+            flags: memory::UsageFlags::COLOR_ATTACHMENT,
+            extent: memory::Extent2D { width: 0, height: 0 },
+            transform: capabilities.pre_transformation(),
+            alpha: capabilities.alpha_composition(),
+        };
+
+        assert!(matches!(
+            swapchain::Swapchain::new(lib_ref, device, surface_ref, &swp_type),
+            Err(swapchain::SwapchainError::ZeroExtent)
+        ));
+    }
+
+    #[test]
+    fn screenshot() {
+        const VERT_SHADER: &str = "
+#version 460
+
+vec2 positions[3] = vec2[](
+    vec2( 0.5,  0.5),
+    vec2( 0.0, -0.5),
+    vec2(-0.5,  0.5)
+);
+
+void main() {
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+}
+";
+
+        const FRAG_SHADER: &str = "
+#version 460
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(0.0, 1.0, 0.0, 1.0);
+}
+";
+
+        let lib_ref = test_context::get_graphics_instance();
+
+        let surface_ref = test_context::get_surface();
+
+        let device = test_context::get_graphics_device();
+
+        let queue = test_context::get_graphics_queue();
+
+        let _ = test_context::get_present_queue();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        assert!(capabilities.is_flags_supported(memory::UsageFlags::TRANSFER_SRC));
+
+        let pool = test_context::get_cmd_pool();
+
+        let queue_cfg = queue::QueueCfg {
+            family_index: queue.index(),
+            queue_index: 0
+        };
+
+        let cmd_queue = queue::Queue::new(device, &queue_cfg).expect("Failed to get queue");
+
+        // Swapchain of the test context has no TRANSFER_SRC usage
+        assert!(matches!(
+            test_context::get_swapchain().screenshot(device, &cmd_queue, pool, 0),
+            Err(swapchain::SwapchainError::NoTransferSrc)
+        ));
+
+        let swp_type = swapchain::SwapchainCfg {
+            num_of_images: 2,
+            format: memory::ImageFormat::B8G8R8A8_UNORM,
+            color: memory::ColorSpace::SRGB_NONLINEAR,
+            present_mode: swapchain::PresentMode::FIFO,
+            flags: memory::UsageFlags::COLOR_ATTACHMENT | memory::UsageFlags::TRANSFER_SRC,
+            extent: capabilities.extent2d(),
+            transform: capabilities.pre_transformation(),
+            alpha: capabilities.alpha_composition(),
+        };
+
+        let swapchain = swapchain::Swapchain::new(lib_ref, device, surface_ref, &swp_type).expect("Failed to create swapchain");
+
+        let render_pass = graphics::RenderPass::single_subpass(device, swp_type.format).expect("Failed to create render pass");
+
+        let fence = sync::Fence::new(device, false).expect("Failed to create fence");
+
+        let image = swapchain.next_image(Duration::from_secs(1), swapchain::AcquireSync::Fence(&fence)).expect("Failed to acquire image");
+
+        fence.wait(u64::MAX).expect("Failed to wait fence");
+
+        let framebuffer_cfg = memory::FramebufferCfg {
+            render_pass: &render_pass,
+            images: &[swapchain.images()[image.index as usize].view(0)],
+            extent: swapchain.extent(),
+        };
+
+        let framebuffer = memory::Framebuffer::new(device, &framebuffer_cfg).expect("Failed to create framebuffer");
+
+        let shader_type = shader::ShaderCfg {
+            path: "SCREENSHOT",
+            entry: "main",
+            include_dirs: &[],
+        };
+
+        let vert_shader = shader::Shader::from_glsl(device, &shader_type, VERT_SHADER, shader::Kind::Vertex)
+            .expect("Failed to create vertex shader module");
+
+        let frag_shader = shader::Shader::from_glsl(device, &shader_type, FRAG_SHADER, shader::Kind::Fragment)
+            .expect("Failed to create fragment shader module");
+
+        let descriptor = graphics::PipelineDescriptor::empty(device);
+
+        let pipe_type = graphics::PipelineCfg {
+            extent: swapchain.extent(),
+            render_pass: Some(&render_pass),
+            ..graphics::PipelineCfg::new(&vert_shader, &frag_shader, &descriptor)
+        };
+
+        let pipeline = graphics::Pipeline::new(device, &pipe_type).expect("Failed to create pipeline");
+
+        let cmd_buffer = pool.allocate().expect("Failed to allocate cmd buffer");
+
+        cmd_buffer.begin_render_pass(&render_pass, &framebuffer);
+        cmd_buffer.bind_graphics_pipeline(&pipeline);
+        cmd_buffer.draw(3, 1, 0, 0);
+        cmd_buffer.end_render_pass();
+
+        let exec_buffer = cmd_buffer.commit().expect("Failed to commit command buffer");
+
+        let exec_info = queue::ExecInfo {
+            wait_stage: cmd::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+            buffers: &[&exec_buffer],
+            timeout: u64::MAX,
+            wait: &[],
+            signal: &[],
+            fence: None,
+        };
+
+        cmd_queue.exec(&exec_info).expect("Failed to execute queue");
+
+        let screenshot = swapchain.screenshot(device, &cmd_queue, pool, image.index).expect("Failed to take screenshot");
+
+        assert_eq!(screenshot.format, memory::ImageFormat::R8G8B8A8_UNORM);
+        assert_eq!((screenshot.width, screenshot.height), (swapchain.extent().width, swapchain.extent().height));
+        assert_eq!(screenshot.data.len(), (screenshot.width*screenshot.height*4) as usize);
+
+        assert_eq!(screenshot.pixel(screenshot.width/2, screenshot.height/2), Some(&[0, 255, 0, 255][..]));
+        assert_eq!(screenshot.pixel(screenshot.width, 0), None);
+
+        assert!(matches!(
+            swapchain.screenshot(device, &cmd_queue, pool, swapchain.image_count()),
+            Err(swapchain::SwapchainError::Screenshot(memory::MemoryError::InvalidIndex { .. }))
+        ));
+
+        assert_eq!(lib_ref.error_count(), 0);
+    }
+
+    #[test]
+    fn swapchain_display() {
+        let lib_ref = test_context::get_graphics_instance();
+
+        let surface_ref = test_context::get_surface();
+
+        let device = test_context::get_graphics_device();
+
+        let _ = test_context::get_present_queue();
+
+        let capabilities = test_context::get_surface_capabilities();
+
+        let swp_type = swapchain::SwapchainCfg {
+            num_of_images: 2,
+            format: memory::ImageFormat::B8G8R8A8_UNORM,
+            color: memory::ColorSpace::SRGB_NONLINEAR,
+            present_mode: swapchain::PresentMode::FIFO,
+            flags: memory::UsageFlags::COLOR_ATTACHMENT,
+            extent: capabilities.extent2d(),
+            transform: capabilities.pre_transformation(),
+            alpha: capabilities.alpha_composition(),
+        };
+
+        let swapchain = swapchain::Swapchain::new(lib_ref, device, surface_ref, &swp_type).expect("Failed to create swapchain");
+
+        assert_eq!(swapchain.present_mode(), swapchain::PresentMode::FIFO);
+        assert_eq!(swapchain.color_space(), memory::ColorSpace::SRGB_NONLINEAR);
+
+        assert_eq!(
+            swapchain.to_string(),
+            format!(
+                "Swapchain\n\
+                Format:       B8G8R8A8_UNORM\n\
+                Color space:  SRGB_NONLINEAR\n\
+                Extent:       {}x{}\n\
+                Image count:  {}\n\
+                Present mode: FIFO\n\
+                Usage:        COLOR_ATTACHMENT\n",
+                swapchain.extent().width,
+                swapchain.extent().height,
+                swapchain.image_count()
+            )
+        );
+    }
+}
@@ -21,4 +21,55 @@ mod sync {
 
         assert!(sync::Fence::new(dev, true).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn fence_reset() {
+        let dev = test_context::get_graphics_device();
+
+        let fence = sync::Fence::new(dev, true).expect("Failed to create fence");
+
+        assert!(fence.status().expect("Failed to get fence status"));
+
+        assert!(fence.wait(u64::MAX).is_ok());
+
+        assert!(fence.reset().is_ok());
+
+        assert!(!fence.status().expect("Failed to get fence status"));
+    }
+
+    #[test]
+    fn frame_sync_round_robin() {
+        let dev = test_context::get_graphics_device();
+
+        let mut frame_sync = sync::FrameSync::new(dev, 2).expect("Failed to create frame sync");
+
+        assert_eq!(frame_sync.count(), 2);
+        assert_eq!(frame_sync.current(), 0);
+
+        assert!(frame_sync.frame().in_flight().status().expect("Failed to get fence status"));
+
+        frame_sync.advance();
+        assert_eq!(frame_sync.current(), 1);
+
+        frame_sync.advance();
+        assert_eq!(frame_sync.current(), 0);
+    }
+
+    #[test]
+    fn frame_sync_zero_frames() {
+        let dev = test_context::get_graphics_device();
+
+        assert!(matches!(sync::FrameSync::new(dev, 0), Err(sync::FrameSyncError::ZeroFrames)));
+    }
+
+    #[test]
+    fn frame_sync_error_source() {
+        use std::error::Error;
+
+        let err = sync::FrameSyncError::Wait(sync::FenceError::Wait);
+
+        assert!(matches!(err.source(), Some(source) if source.is::<sync::FenceError>()));
+
+        assert!(sync::FrameSyncError::OutOfDate.source().is_none());
+    }
+}
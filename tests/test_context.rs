@@ -17,8 +17,28 @@ use libvktypes::{
 use std::sync::Once;
 use std::mem::MaybeUninit;
 
+/// Fixed size of render targets in headless mode
+pub const HEADLESS_EXTENT: memory::Extent2D = memory::Extent2D { width: 256, height: 256 };
+
+/// Fixed format of render targets in headless mode
+pub const HEADLESS_FORMAT: memory::ImageFormat = memory::ImageFormat::R8G8B8A8_UNORM;
+
 static INIT_WINDOW: Once = Once::new();
 
+static INIT_COMPUTE_INSTANCE: Once = Once::new();
+
+static mut COMPUTE_INSTANCE: MaybeUninit<libvk::Instance> = MaybeUninit::<libvk::Instance>::uninit();
+
+static INIT_COMPUTE_HW: Once = Once::new();
+
+static mut COMPUTE_HW: MaybeUninit<hw::HWDevice> = MaybeUninit::<hw::HWDevice>::uninit();
+
+static mut COMPUTE_QUEUE: MaybeUninit<hw::QueueFamilyDescription> = MaybeUninit::<hw::QueueFamilyDescription>::uninit();
+
+static INIT_COMPUTE_DEV: Once = Once::new();
+
+static mut COMPUTE_DEV: MaybeUninit<dev::Device> = MaybeUninit::<dev::Device>::uninit();
+
 static mut WINDOW: MaybeUninit<window::Window> = MaybeUninit::<window::Window>::uninit();
 
 static INIT_GRAPHICS_INSTANCE: Once = Once::new();
@@ -79,6 +99,82 @@ static INIT_FRAMEBUFFER: Once = Once::new();
 
 static mut FRAMEBUFFER: MaybeUninit<Vec<memory::Framebuffer>> = MaybeUninit::<Vec<memory::Framebuffer>>::uninit();
 
+/// Run tests without window, surface and swapchain
+///
+/// Enabled with `LIBVKTYPES_HEADLESS=1`
+pub fn is_headless() -> bool {
+    std::env::var("LIBVKTYPES_HEADLESS").is_ok_and(|x| x == "1")
+}
+
+/// Instance without any surface extensions
+pub fn get_compute_instance() -> &'static libvk::Instance {
+    unsafe {
+        INIT_COMPUTE_INSTANCE.call_once(|| {
+            let mut extensions = extensions::headless_extensions();
+            extensions.push(extensions::DEBUG_EXT_NAME);
+
+            let lib_type = libvk::InstanceType {
+                debug_layer: Some(layers::DebugLayer::default()),
+                extensions: &extensions,
+                ..libvk::InstanceType::default()
+            };
+
+            COMPUTE_INSTANCE.write(libvk::Instance::new(&lib_type).expect("Failed to init compute instance"));
+        });
+
+        COMPUTE_INSTANCE.assume_init_ref()
+    }
+}
+
+pub fn get_compute_hw() -> &'static hw::HWDevice {
+    unsafe {
+        INIT_COMPUTE_HW.call_once(|| {
+            let hw_list = hw::Description::poll(get_compute_instance(), None).expect("Failed to list hardware");
+
+            let (hw_dev, qf, _) = hw_list
+                .find_first(
+                    hw::any,
+                    hw::QueueFamilyDescription::is_compute,
+                    hw::any
+                )
+                .expect("Failed to find suitable hardware device");
+
+            COMPUTE_HW.write(hw_dev.clone());
+            COMPUTE_QUEUE.write(*qf);
+        });
+
+        COMPUTE_HW.assume_init_ref()
+    }
+}
+
+pub fn get_compute_queue() -> &'static hw::QueueFamilyDescription {
+    get_compute_hw();
+
+    unsafe { COMPUTE_QUEUE.assume_init_ref() }
+}
+
+/// Device without swapchain extension
+pub fn get_compute_device() -> &'static dev::Device {
+    unsafe {
+        INIT_COMPUTE_DEV.call_once(|| {
+            let dev_type = dev::DeviceCfg {
+                lib: get_compute_instance(),
+                hw: get_compute_hw(),
+                extensions: &[],
+                queues: &[],
+                features: hw::Features::default(),
+                dynamic_rendering: false,
+                allocator: None,
+                track_objects: false,
+            };
+
+            COMPUTE_DEV.write(dev::Device::new(&dev_type).expect("Failed to create device"));
+        });
+
+        COMPUTE_DEV.assume_init_ref()
+    }
+}
+
 pub fn get_window() -> &'static window::Window {
     unsafe {
         INIT_WINDOW.call_once(|| {
@@ -94,9 +190,17 @@ pub fn get_window() -> &'static window::Window {
 pub fn get_graphics_instance() -> &'static libvk::Instance {
     unsafe {
         INIT_GRAPHICS_INSTANCE.call_once(|| {
-            let mut extensions = extensions::required_extensions(get_window());
+            let mut extensions = if is_headless() {
+                extensions::headless_extensions()
+            } else {
+                extensions::required_extensions(get_window())
+            };
+
             extensions.push(extensions::DEBUG_EXT_NAME);
-            extensions.push(extensions::SURFACE_EXT_NAME);
+
+            if !is_headless() {
+                extensions.push(extensions::SURFACE_EXT_NAME);
+            }
 
             let lib_type = libvk::InstanceType {
                 debug_layer: Some(layers::DebugLayer::default()),
@@ -124,13 +228,16 @@ pub fn get_surface() -> &'static surface::Surface {
 pub fn get_graphics_hw() -> &'static hw::HWDevice {
     unsafe {
         INIT_GRAPHICS_HW.call_once(|| {
-            let surface = get_surface();
-            let hw_list = hw::Description::poll(get_graphics_instance(), Some(surface)).expect("Failed to list hardware");
+            let headless = is_headless();
+
+            let surface = if headless { None } else { Some(get_surface()) };
+
+            let hw_list = hw::Description::poll(get_graphics_instance(), surface).expect("Failed to list hardware");
 
             let (hw_dev, qf, _) = hw_list
                 .find_first(
                     hw::HWDevice::is_dedicated_gpu,
-                    |q| q.is_graphics() && q.is_surface_supported(),
+                    |q| q.is_graphics() && (headless || q.is_surface_supported()),
                     hw::any
                 )
                 .expect("Failed to find suitable hardware device");
@@ -174,14 +281,36 @@ pub fn get_surface_capabilities() -> &'static surface::Capabilities {
     }
 }
 
+/// Format of render targets: swapchain format or [`HEADLESS_FORMAT`]
+pub fn get_color_format() -> memory::ImageFormat {
+    if is_headless() {
+        HEADLESS_FORMAT
+    } else {
+        get_surface_capabilities().formats().next().expect("No available formats").format
+    }
+}
+
+/// Size of render targets: surface extent or [`HEADLESS_EXTENT`]
+pub fn get_extent() -> memory::Extent2D {
+    if is_headless() {
+        HEADLESS_EXTENT
+    } else {
+        get_surface_capabilities().extent2d()
+    }
+}
+
 pub fn get_graphics_device() -> &'static dev::Device {
     unsafe {
         INIT_GRAPHICS_DEV.call_once(|| {
             let dev_type = dev::DeviceCfg {
                 lib: get_graphics_instance(),
                 hw: get_graphics_hw(),
-                extensions: &[extensions::SWAPCHAIN_EXT_NAME],
+                extensions: if is_headless() { &[] } else { &[extensions::SWAPCHAIN_EXT_NAME] },
+                queues: &[],
+                features: *get_graphics_hw().features(),
+                dynamic_rendering: false,
                 allocator: None,
+                track_objects: false,
             };
 
             GRAPHICS_DEV.write(dev::Device::new(&dev_type).expect("Failed to create device"));
@@ -228,6 +357,7 @@ pub fn get_vert_shader() -> &'static shader::Shader {
             let shader_type = shader::ShaderCfg {
                 path: "tests/compiled_shaders/single_dot.spv",
                 entry: "main",
+                include_dirs: &[],
             };
 
             VERT_SHADER.write(shader::Shader::from_file(dev, &shader_type).expect("Failed to create shader module"));
@@ -245,6 +375,7 @@ pub fn get_frag_shader() -> &'static shader::Shader {
             let shader_type = shader::ShaderCfg {
                 path: "tests/compiled_shaders/single_color.spv",
                 entry: "main",
+                include_dirs: &[],
             };
 
             FRAG_SHADER.write(shader::Shader::from_file(dev, &shader_type).expect("Failed to create shader module"));
@@ -257,27 +388,34 @@ pub fn get_frag_shader() -> &'static shader::Shader {
 pub fn get_render_pass() -> &'static graphics::RenderPass {
     unsafe {
         INIT_RENDER_PASS.call_once(|| {
-            let capabilities = get_surface_capabilities();
-
             let dev = get_graphics_device();
 
-            RENDER_PASS.write(
-                graphics::RenderPass::single_subpass(
-                    dev,
-                    capabilities.formats().next().expect("No available formats").format)
-                    .expect("Failed to create render pass"));
+            let render_pass = if is_headless() {
+                graphics::RenderPass::offscreen(dev, get_color_format())
+            } else {
+                graphics::RenderPass::single_subpass(dev, get_color_format())
+            };
+
+            RENDER_PASS.write(render_pass.expect("Failed to create render pass"));
         });
 
         RENDER_PASS.assume_init_ref()
     }
 }
 
-pub fn get_image_list() -> &'static Vec<memory::ImageMemory> {
+pub fn get_image_list() -> &'static [memory::ImageMemory] {
+    if !is_headless() {
+        return get_swapchain().images();
+    }
+
     unsafe {
         INIT_IMAGE_LIST.call_once(|| {
-            let swp = get_swapchain();
+            let images = (0..2)
+                .map(|_| memory::ImageMemory::offscreen(get_graphics_device(), get_color_format(), get_extent()))
+                .collect::<Result<Vec<_>, _>>()
+                .expect("Failed to allocate offscreen images");
 
-            IMAGE_LIST.write(swp.images().expect("Failed to get image list"));
+            IMAGE_LIST.write(images);
         });
 
         IMAGE_LIST.assume_init_ref()
@@ -305,7 +443,6 @@ pub fn get_graphics_pipeline() -> &'static graphics::Pipeline {
     unsafe {
         INIT_GRAPHICS_PIPELINE.call_once(|| {
             let dev = get_graphics_device();
-            let capabilities = get_surface_capabilities();
 
             let vertex_cfg = graphics::VertexInputCfg {
                 location: 0,
@@ -314,21 +451,16 @@ pub fn get_graphics_pipeline() -> &'static graphics::Pipeline {
                 offset: 0,
             };
 
+            let descriptor = graphics::PipelineDescriptor::empty(dev);
+
             let pipe_type = graphics::PipelineCfg {
-                vertex_shader: get_vert_shader(),
                 vertex_size: std::mem::size_of::<[f32; 2]>() as u32,
                 vert_input: &[vertex_cfg],
-                frag_shader: get_frag_shader(),
-                geom_shader: None,
                 topology: graphics::Topology::TRIANGLE_STRIP,
-                extent: capabilities.extent2d(),
-                push_constant_size: 0,
-                render_pass: get_render_pass(),
-                subpass_index: 0,
-                enable_depth_test: false,
-                enable_primitive_restart: false,
+                extent: get_extent(),
+                render_pass: Some(get_render_pass()),
                 cull_mode: graphics::CullMode::BACK,
-                descriptor: &graphics::PipelineDescriptor::empty(dev)
+                ..graphics::PipelineCfg::new(get_vert_shader(), get_frag_shader(), &descriptor)
             };
 
             GRAPHICS_PIPELINE.write(graphics::Pipeline::new(dev, &pipe_type).expect("Failed to create pipeline"));
@@ -347,14 +479,12 @@ pub fn get_framebuffers() -> &'static Vec<memory::Framebuffer> {
 
             let imgs = get_image_list();
 
-            let capabilities = get_surface_capabilities();
-
             let framebuffers: Vec<memory::Framebuffer> =
                 imgs.iter().map(|img| {
                     let framebuffer_cfg = memory::FramebufferCfg {
                         render_pass: rp,
                         images: &[img.view(0)],
-                        extent: capabilities.extent2d(),
+                        extent: get_extent(),
                     };
 
                     memory::Framebuffer::new(dev, &framebuffer_cfg).expect("Failed to create framebuffer")
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod window {
+    use libvktypes::{window, memory};
+    use libvktypes::winit::event::{Event, WindowEvent, DeviceEvent};
+    use libvktypes::winit::event::DeviceId;
+    use libvktypes::winit::window::WindowId;
+
+    fn window_event(event: WindowEvent) -> Event<()> {
+        Event::WindowEvent {
+            window_id: unsafe { WindowId::dummy() },
+            event
+        }
+    }
+
+    fn mouse_motion(x: f64, y: f64) -> Event<()> {
+        Event::DeviceEvent {
+            device_id: unsafe { DeviceId::dummy() },
+            event: DeviceEvent::MouseMotion { delta: (x, y) }
+        }
+    }
+
+    #[test]
+    fn input_keys() {
+        let mut input = window::InputState::new();
+
+        assert!(!input.is_key_down(window::KeyCode::KeyW));
+
+        input.update_key(window::KeyCode::KeyW, window::KeyState::Pressed);
+        input.update_key(window::KeyCode::KeyA, window::KeyState::Pressed);
+        input.update_key(window::KeyCode::KeyA, window::KeyState::Released);
+
+        assert!(input.is_key_down(window::KeyCode::KeyW));
+        assert!(!input.is_key_down(window::KeyCode::KeyA));
+
+        input.end_frame();
+
+        assert!(input.is_key_down(window::KeyCode::KeyW));
+
+        input.update(&window_event(WindowEvent::Focused(false)));
+
+        assert!(!input.is_key_down(window::KeyCode::KeyW));
+    }
+
+    #[test]
+    fn input_mouse_and_resize() {
+        let mut input = window::InputState::new();
+
+        assert_eq!(input.mouse_delta(), (0.0, 0.0));
+        assert_eq!(input.was_resized(), None);
+
+        input.update(&mouse_motion(1.0, -2.0));
+        input.update(&mouse_motion(0.5, 4.0));
+        input.update(&window_event(WindowEvent::Resized((640, 480).into())));
+        input.update(&window_event(WindowEvent::Resized((800, 600).into())));
+
+        assert_eq!(input.mouse_delta(), (1.5, 2.0));
+        assert_eq!(input.was_resized(), Some(memory::Extent2D { width: 800, height: 600 }));
+
+        input.end_frame();
+
+        assert_eq!(input.mouse_delta(), (0.0, 0.0));
+        assert_eq!(input.was_resized(), None);
+    }
+}